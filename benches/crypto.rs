@@ -0,0 +1,62 @@
+//! Key derivation cost and AES-GCM throughput.
+//!
+//! Run with `cargo bench --bench crypto`.
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rpm::crypto::{key_derivation, CryptoManager};
+use std::hint::black_box;
+
+const PASSWORD: &str = "correct horse battery staple";
+const SALT: &[u8] = b"rpm-bench-salt-0123456789abcdef";
+
+/// (label, memory KiB, iterations, lanes). The first is what `derive_key` uses.
+const ARGON2_PARAMS: &[(&str, u32, u32, u32)] = &[
+    ("default-19MiB-t2-p1", Params::DEFAULT_M_COST, Params::DEFAULT_T_COST, Params::DEFAULT_P_COST),
+    ("owasp-46MiB-t1-p1", 46 * 1024, 1, 1),
+    ("owasp-12MiB-t3-p1", 12 * 1024, 3, 1),
+    ("strong-64MiB-t3-p4", 64 * 1024, 3, 4),
+];
+
+const PAYLOAD_SIZES: &[usize] = &[64, 4 * 1024, 1024 * 1024];
+
+fn argon2(c: &mut Criterion) {
+    let mut group = c.benchmark_group("argon2id");
+    group.sample_size(10);
+    group.bench_function("derive_key", |b| {
+        b.iter(|| black_box(key_derivation::derive_key(PASSWORD, Some(SALT)).unwrap()))
+    });
+    for &(label, m_cost, t_cost, p_cost) in ARGON2_PARAMS {
+        let params = Params::new(m_cost, t_cost, p_cost, Some(32)).expect("argon2 params");
+        let argon2 = Argon2::new(Algorithm::Argon2id, Version::V0x13, params);
+        group.bench_function(label, |b| {
+            b.iter(|| {
+                let mut key = [0u8; 32];
+                argon2.hash_password_into(PASSWORD.as_bytes(), SALT, &mut key).unwrap();
+                black_box(key)
+            })
+        });
+    }
+    group.finish();
+}
+
+fn aes_gcm(c: &mut Criterion) {
+    let crypto = CryptoManager::new().expect("crypto");
+    let key = [7u8; 32];
+    let mut group = c.benchmark_group("aes_gcm");
+    for &size in PAYLOAD_SIZES {
+        let data = vec![0x5au8; size];
+        let (ciphertext, nonce) = crypto.encrypt_data(&data, &key).unwrap();
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(BenchmarkId::new("encrypt", size), &data, |b, data| {
+            b.iter(|| black_box(crypto.encrypt_data(data, &key).unwrap()))
+        });
+        group.bench_with_input(BenchmarkId::new("decrypt", size), &ciphertext, |b, ciphertext| {
+            b.iter(|| black_box(crypto.decrypt_data(ciphertext, &nonce, &key).unwrap()))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, argon2, aes_gcm);
+criterion_main!(benches);
@@ -0,0 +1,111 @@
+//! Unlock, def file load, search and save on seeded vaults.
+//!
+//! Run with `cargo bench --features dev-tools --bench vault`.
+
+use base64::engine::general_purpose::STANDARD_NO_PAD as BASE64_STANDARD_NO_PAD;
+use base64::Engine;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use rpm::config::{Config, DirectoryConfig};
+use rpm::crypto::{key_derivation, CryptoManager, SecureKey};
+use rpm::dev;
+use rpm::storage::PasswordStorage;
+use rpm::tui::fuzzy_filter;
+use std::hint::black_box;
+use std::path::PathBuf;
+
+const SIZES: &[usize] = &[1_000, 10_000];
+
+struct SeededVault {
+    dir: PathBuf,
+    storage: PasswordStorage,
+    key: SecureKey,
+}
+
+impl SeededVault {
+    fn new(entries: usize) -> Self {
+        let dir = std::env::temp_dir().join(format!("rpm-bench-{}-{}", std::process::id(), entries));
+        let _ = std::fs::remove_dir_all(&dir);
+        let crypto = CryptoManager::new().expect("crypto");
+        let key = dev::seed_vault(&dir, entries, dev::DEFAULT_SEED, &crypto).expect("seed vault");
+        let config = Config {
+            passwords_directory: Some(dir.clone()),
+            ..Config::default()
+        };
+        let storage = PasswordStorage::new(&config, crypto);
+        Self { dir, storage, key }
+    }
+}
+
+impl Drop for SeededVault {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// Master password check, key derivation and decryption of all names, as in the TUI
+fn unlock(c: &mut Criterion) {
+    let crypto = CryptoManager::new().expect("crypto");
+    let mut group = c.benchmark_group("unlock");
+    group.sample_size(10);
+    for &size in SIZES {
+        let vault = SeededVault::new(size);
+        let dir_config = DirectoryConfig::load(&vault.dir).expect("directory config");
+        let hash = dir_config.master_password_hash.expect("hash");
+        let salt = BASE64_STANDARD_NO_PAD
+            .decode(dir_config.encryption_key_salt.expect("salt"))
+            .expect("salt");
+        group.bench_function(BenchmarkId::from_parameter(size), |b| {
+            b.iter(|| {
+                assert!(crypto.verify_password(dev::SEED_MASTER_PASSWORD, &hash).unwrap());
+                let key = key_derivation::derive_key(dev::SEED_MASTER_PASSWORD, Some(&salt)).unwrap();
+                black_box(vault.storage.list_decrypted_names(&key).unwrap())
+            })
+        });
+    }
+    group.finish();
+}
+
+fn def_file_load(c: &mut Criterion) {
+    let mut group = c.benchmark_group("def_file_load");
+    for &size in SIZES {
+        let vault = SeededVault::new(size);
+        group.bench_function(BenchmarkId::from_parameter(size), |b| {
+            b.iter(|| black_box(vault.storage.load_def_file(vault.key.as_slice()).unwrap()))
+        });
+    }
+    group.finish();
+}
+
+/// Filter latency per keystroke: the list is re-filtered after every typed character
+fn search(c: &mut Criterion) {
+    let mut group = c.benchmark_group("search");
+    for &size in SIZES {
+        let names: Vec<String> = dev::generate_entries(size, dev::DEFAULT_SEED)
+            .into_iter()
+            .map(|(name, _, _)| name)
+            .collect();
+        for query in ["g", "git", "bank/alice 0"] {
+            group.bench_with_input(BenchmarkId::new(query, size), &names, |b, names| {
+                b.iter(|| black_box(fuzzy_filter(names, query)))
+            });
+        }
+    }
+    group.finish();
+}
+
+/// Changing a password rewrites its entry file and the def file
+fn save(c: &mut Criterion) {
+    let mut group = c.benchmark_group("save");
+    for &size in SIZES {
+        let vault = SeededVault::new(size);
+        let key = vault.key.as_slice();
+        let (filename, _) = vault.storage.list_decrypted_names(key).unwrap().swap_remove(size / 2);
+        group.bench_function(BenchmarkId::from_parameter(size), |b| {
+            b.iter(|| vault.storage.update_password_file(&filename, "bench-password", key).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, unlock, def_file_load, search, save);
+criterion_main!(benches);
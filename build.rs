@@ -0,0 +1,76 @@
+//! Build information for the diagnostics in the About screen. Only inputs that
+//! are the same for the same sources go in, so builds stay reproducible: no
+//! timestamps or host names.
+
+use std::path::Path;
+use std::process::Command;
+
+/// Crates the vault's security rests on; their resolved versions are reported
+const CRYPTO_CRATES: &[&str] = &[
+    "aes-gcm",
+    "chacha20poly1305",
+    "argon2",
+    "sha2",
+    "hmac",
+    "x25519-dalek",
+    "age",
+    "rand",
+    "getrandom",
+    "rustls",
+    "ring",
+    "zeroize",
+];
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=Cargo.lock");
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs/heads");
+    println!("cargo:rerun-if-env-changed=RPM_BUILD_HASH");
+
+    // Packagers building from a tarball can pass the commit themselves
+    let hash = std::env::var("RPM_BUILD_HASH")
+        .ok()
+        .filter(|hash| !hash.is_empty())
+        .or_else(git_hash)
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=RPM_BUILD_HASH={}", hash);
+
+    for (name, variable) in [("PROFILE", "RPM_BUILD_PROFILE"), ("OPT_LEVEL", "RPM_OPT_LEVEL"), ("TARGET", "RPM_TARGET")] {
+        println!("cargo:rustc-env={}={}", variable, std::env::var(name).unwrap_or_default());
+    }
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+    let lock = std::fs::read_to_string(Path::new(&manifest_dir).join("Cargo.lock")).unwrap_or_default();
+    println!("cargo:rustc-env=RPM_CRYPTO_CRATES={}", crate_versions(&lock).join(","));
+}
+
+fn git_hash() -> Option<String> {
+    let output = Command::new("git").args(["rev-parse", "--short=12", "HEAD"]).output().ok()?;
+    let hash = String::from_utf8(output.stdout).ok()?.trim().to_string();
+    (output.status.success() && !hash.is_empty()).then_some(hash)
+}
+
+/// `name version` of every locked package in `CRYPTO_CRATES`, in that order
+fn crate_versions(lock: &str) -> Vec<String> {
+    let mut packages = Vec::new();
+    let mut name = None;
+    for line in lock.lines() {
+        if line == "[[package]]" {
+            name = None;
+        } else if let Some(value) = line.strip_prefix("name = ") {
+            name = Some(value.trim_matches('"'));
+        } else if let (Some(package), Some(version)) = (name, line.strip_prefix("version = ")) {
+            packages.push((package, version.trim_matches('"')));
+        }
+    }
+    CRYPTO_CRATES
+        .iter()
+        .flat_map(|wanted| {
+            packages
+                .iter()
+                .filter(move |(package, _)| package == wanted)
+                .map(|(package, version)| format!("{} {}", package, version))
+        })
+        .collect()
+}
@@ -0,0 +1,323 @@
+use crate::config::Config;
+use crate::crypto::{key_derivation, CryptoManager};
+use crate::errors::{RpmError, RpmResult};
+use crate::storage::atomic::{sync_parent, write_atomic, write_synced};
+use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+use base64::Engine;
+use chrono::{DateTime, Duration, Utc};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tokio::sync::watch;
+use tracing::{error, info};
+use zeroize::Zeroize;
+
+/// First line of every backup archive
+const MAGIC: &[u8] = b"RPMBACKUP1\n";
+
+/// File extension of backup archives
+pub const BACKUP_EXTENSION: &str = "rpmbak";
+
+/// Vault files packed into a backup (and mirrored by sync): the def file, the trash,
+/// the audit log, sync tombstones, entry files and the directory config
+pub fn is_vault_file(name: &str) -> bool {
+    name == "def"
+        || name == "trash"
+        || name == "audit"
+        || name == "tombstones"
+        || name == ".rpm_config"
+        || (name.ends_with(".pwd") && !name.contains(['/', '\\']))
+}
+
+/// File name prefix of scheduled snapshots; only these are pruned
+const AUTO_PREFIX: &str = "rpm-auto-";
+
+/// How often the scheduler checks whether a snapshot is due
+const SCHEDULER_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(600);
+
+/// Plaintext header of an archive, readable without the passphrase
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupInfo {
+    pub created_at: DateTime<Utc>,
+    /// Number of `.pwd` files (entries) in the archive
+    pub entry_count: usize,
+    /// Base64 Argon2id salt of the passphrase key; `None` for scheduled snapshots,
+    /// whose payload is not encrypted again (the vault files already are)
+    #[serde(default)]
+    salt: Option<String>,
+    #[serde(skip)]
+    pub path: PathBuf,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ArchivedFile {
+    name: String,
+    /// Base64 file contents
+    data: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Payload {
+    files: Vec<ArchivedFile>,
+}
+
+impl BackupInfo {
+    /// Whether restoring needs a passphrase
+    pub fn is_encrypted(&self) -> bool {
+        self.salt.is_some()
+    }
+}
+
+/// Pack the vault files of `passwords_dir` into `target`, encrypted with AES-256-GCM
+/// under a key derived from `passphrase` (independent of the master password)
+pub fn create_backup(passwords_dir: &Path, target: &Path, passphrase: &str, crypto: &CryptoManager) -> RpmResult<BackupInfo> {
+    if passphrase.is_empty() {
+        return Err(RpmError::InvalidInput("Backup passphrase must not be empty".to_string()));
+    }
+    write_archive(passwords_dir, target, Some((passphrase, crypto)))
+}
+
+/// Pack the vault files into `target` without a passphrase. The files stay encrypted
+/// with the vault key, so restoring such a snapshot still needs the master password.
+pub fn create_snapshot(passwords_dir: &Path, target: &Path) -> RpmResult<BackupInfo> {
+    write_archive(passwords_dir, target, None)
+}
+
+fn write_archive(passwords_dir: &Path, target: &Path, passphrase: Option<(&str, &CryptoManager)>) -> RpmResult<BackupInfo> {
+    let mut files = Vec::new();
+    for item in std::fs::read_dir(passwords_dir)? {
+        let item = item?;
+        let name = item.file_name().to_string_lossy().into_owned();
+        if item.file_type()?.is_file() && is_vault_file(&name) {
+            files.push(ArchivedFile {
+                name,
+                data: BASE64_STANDARD.encode(std::fs::read(item.path())?),
+            });
+        }
+    }
+    if !files.iter().any(|f| f.name == "def") {
+        return Err(RpmError::InvalidInput(format!(
+            "No vault found in {}",
+            passwords_dir.display()
+        )));
+    }
+    files.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut salt = [0u8; 16];
+    rand::thread_rng().fill_bytes(&mut salt);
+    let info = BackupInfo {
+        created_at: Utc::now(),
+        entry_count: files.iter().filter(|f| f.name.ends_with(".pwd")).count(),
+        salt: passphrase.map(|_| BASE64_STANDARD.encode(salt)),
+        path: target.to_path_buf(),
+    };
+
+    let payload = serde_json::to_vec(&Payload { files })?;
+    let mut archive = MAGIC.to_vec();
+    archive.extend_from_slice(&serde_json::to_vec(&info)?);
+    archive.push(b'\n');
+    match passphrase {
+        Some((passphrase, crypto)) => {
+            let mut key = key_derivation::derive_key(passphrase, Some(&salt))?;
+            let encrypted = crypto.encrypt_data(&payload, &key);
+            key.zeroize();
+            let (ciphertext, nonce) = encrypted?;
+            archive.extend_from_slice(&nonce);
+            archive.extend_from_slice(&ciphertext);
+        }
+        None => archive.extend_from_slice(&payload),
+    }
+
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    write_atomic(target, &archive)?;
+    Ok(info)
+}
+
+/// Split an archive into its header and the encrypted part (nonce + ciphertext)
+fn split_archive(data: &[u8]) -> RpmResult<(BackupInfo, &[u8])> {
+    let rest = data
+        .strip_prefix(MAGIC)
+        .ok_or_else(|| RpmError::InvalidInput("Not an RPM backup archive".to_string()))?;
+    let newline = rest
+        .iter()
+        .position(|b| *b == b'\n')
+        .ok_or_else(|| RpmError::InvalidInput("Backup archive header is truncated".to_string()))?;
+    let info: BackupInfo = serde_json::from_slice(&rest[..newline])?;
+    Ok((info, &rest[newline + 1..]))
+}
+
+/// Read the plaintext header of an archive
+pub fn read_info(path: &Path) -> RpmResult<BackupInfo> {
+    let data = std::fs::read(path)?;
+    let (mut info, _) = split_archive(&data)?;
+    info.path = path.to_path_buf();
+    Ok(info)
+}
+
+/// Archives in `directory`, newest first; unreadable files are skipped
+pub fn list_backups(directory: &Path) -> RpmResult<Vec<BackupInfo>> {
+    if !directory.exists() {
+        return Ok(Vec::new());
+    }
+    let mut backups: Vec<BackupInfo> = std::fs::read_dir(directory)?
+        .filter_map(|item| item.ok().map(|item| item.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == BACKUP_EXTENSION))
+        .filter_map(|path| read_info(&path).ok())
+        .collect();
+    backups.sort_by_key(|info| std::cmp::Reverse(info.created_at));
+    Ok(backups)
+}
+
+/// Replace the vault files in `passwords_dir` with those from the archive
+/// (`passphrase` is ignored for snapshots without one).
+/// Files are written next to their targets and flushed to disk first, then renamed
+/// into place (the def file last); vault files missing from the archive are removed
+/// only after that, once no def file refers to them.
+/// Returns the number of restored entries.
+pub fn restore_backup(archive: &Path, passwords_dir: &Path, passphrase: &str, crypto: &CryptoManager) -> RpmResult<usize> {
+    let data = std::fs::read(archive)?;
+    let (info, body) = split_archive(&data)?;
+    let mut payload: Payload = match info.salt {
+        Some(ref salt) => {
+            if body.len() < 12 {
+                return Err(RpmError::InvalidInput("Backup archive is truncated".to_string()));
+            }
+            let salt = BASE64_STANDARD
+                .decode(salt)
+                .map_err(|e| RpmError::Crypto(format!("Invalid backup salt: {}", e)))?;
+
+            let mut key = key_derivation::derive_key(passphrase, Some(&salt))?;
+            let decrypted = crypto.decrypt_data(&body[12..], &body[..12], &key);
+            key.zeroize();
+            // A wrong passphrase fails authentication of the ciphertext
+            let mut plaintext = decrypted.map_err(|_| RpmError::AuthenticationFailed)?;
+            let payload = serde_json::from_slice(&plaintext);
+            plaintext.zeroize();
+            payload?
+        }
+        None => serde_json::from_slice(body)?,
+    };
+
+    if let Some(file) = payload.files.iter().find(|f| !is_vault_file(&f.name)) {
+        return Err(RpmError::InvalidInput(format!("Unexpected file in backup: {}", file.name)));
+    }
+    // The def file goes last so an interrupted restore never references missing entries
+    payload.files.sort_by_key(|f| f.name == "def");
+
+    std::fs::create_dir_all(passwords_dir)?;
+    let mut staged = Vec::new();
+    for file in &payload.files {
+        let contents = BASE64_STANDARD
+            .decode(&file.data)
+            .map_err(|e| RpmError::InvalidInput(format!("Corrupted file {} in backup: {}", file.name, e)));
+        let staged_path = passwords_dir.join(format!("{}.restore", file.name));
+        let written = contents.and_then(|contents| write_synced(&staged_path, &contents).map_err(RpmError::Io));
+        if let Err(e) = written {
+            for (path, _) in &staged {
+                let _ = std::fs::remove_file(path);
+            }
+            return Err(e);
+        }
+        staged.push((staged_path, passwords_dir.join(&file.name)));
+    }
+
+    for (staged_path, target) in staged {
+        std::fs::rename(&staged_path, &target)?;
+    }
+    sync_parent(&passwords_dir.join("def"))?;
+
+    // Entries that exist now but not in the backup; the restored def file no longer lists them
+    for item in std::fs::read_dir(passwords_dir)? {
+        let item = item?;
+        let name = item.file_name().to_string_lossy().into_owned();
+        if is_vault_file(&name) && !payload.files.iter().any(|f| f.name == name) {
+            std::fs::remove_file(item.path())?;
+        }
+    }
+    sync_parent(&passwords_dir.join("def"))?;
+    Ok(info.entry_count)
+}
+
+/// Delete the oldest scheduled snapshots so that at most `keep` remain.
+/// Manual backups are never removed. Returns the number of deleted files.
+pub fn prune_snapshots(directory: &Path, keep: usize) -> RpmResult<usize> {
+    let snapshots: Vec<BackupInfo> = list_backups(directory)?
+        .into_iter()
+        .filter(|info| {
+            info.path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with(AUTO_PREFIX))
+        })
+        .collect();
+
+    let mut removed = 0;
+    for info in snapshots.iter().skip(keep) {
+        std::fs::remove_file(&info.path)?;
+        removed += 1;
+    }
+    Ok(removed)
+}
+
+/// Take a scheduled snapshot if the newest one is older than `backup_interval_hours`,
+/// then prune. Returns the path of the new snapshot, if one was taken.
+pub fn scheduled_backup(config: &Config) -> RpmResult<Option<PathBuf>> {
+    let directory = config.backup_directory_path();
+    let interval = Duration::hours(i64::try_from(config.backup_interval_hours).unwrap_or(i64::MAX / 3600));
+    let now = Utc::now();
+    let last = list_backups(&directory)?
+        .into_iter()
+        .find(|info| {
+            info.path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with(AUTO_PREFIX))
+        })
+        .map(|info| info.created_at);
+    if last.is_some_and(|last| now - last < interval) {
+        return Ok(None);
+    }
+
+    let passwords_dir = config.passwords_directory_path();
+    if !passwords_dir.join("def").exists() {
+        // Nothing to back up yet
+        return Ok(None);
+    }
+    let target = directory.join(format!("{}{}.{}", AUTO_PREFIX, now.format("%Y%m%d-%H%M%S"), BACKUP_EXTENSION));
+    let info = create_snapshot(&passwords_dir, &target)?;
+    let pruned = prune_snapshots(&directory, config.backup_keep_count.max(1))?;
+    info!(
+        path = %target.display(),
+        entries = info.entry_count,
+        pruned,
+        "Scheduled backup written"
+    );
+    Ok(Some(target))
+}
+
+/// Background task: re-reads the config every check and takes snapshots every
+/// `backup_interval_hours` (0 disables them) until shutdown
+pub async fn run_scheduler(mut shutdown_rx: watch::Receiver<()>) {
+    loop {
+        let result = tokio::task::spawn_blocking(|| {
+            let config = Config::load().map_err(|e| RpmError::Config(e.to_string()))?;
+            if config.backup_interval_hours == 0 {
+                return Ok(None);
+            }
+            scheduled_backup(&config)
+        })
+        .await;
+        match result {
+            Ok(Err(e)) => error!("Scheduled backup failed: {}", e),
+            Err(e) => error!("Scheduled backup task failed: {}", e),
+            Ok(Ok(_)) => {}
+        }
+
+        tokio::select! {
+            _ = tokio::time::sleep(SCHEDULER_CHECK_INTERVAL) => {}
+            _ = shutdown_rx.changed() => break,
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use crate::config::Config;
+use crate::errors::{RpmError, RpmResult};
+use sha1::{Digest, Sha1};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Length of a hex encoded SHA-1 hash
+const SHA1_HEX_LEN: usize = 40;
+
+/// Hash prefix length sent to the range API; the rest of the hash never leaves the machine
+const RANGE_PREFIX_LEN: usize = 5;
+
+/// HIBP "Pwned Passwords" range API
+const HIBP_RANGE_URL: &str = "https://api.pwnedpasswords.com/range/";
+
+/// Compute the uppercase hex SHA-1 of a password, as used by Have I Been Pwned
+pub fn sha1_hex(password: &str) -> String {
+    hex::encode_upper(Sha1::digest(password.as_bytes()))
+}
+
+/// Locally downloaded HIBP "Pwned Passwords" dataset (SHA-1, ordered by hash).
+///
+/// Each line has the form `HASH:COUNT`. Lookups binary search the file on disk,
+/// so the multi-gigabyte dataset never has to be loaded into memory and no
+/// network requests are made.
+pub struct OfflineHibpDataset {
+    path: PathBuf,
+    len: u64,
+}
+
+impl OfflineHibpDataset {
+    /// Open a dataset file
+    pub fn open(path: &Path) -> RpmResult<Self> {
+        let metadata = std::fs::metadata(path)?;
+        if !metadata.is_file() {
+            return Err(RpmError::InvalidInput(format!(
+                "HIBP dataset is not a file: {}",
+                path.display()
+            )));
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+            len: metadata.len(),
+        })
+    }
+
+    /// How many times the password appears in the dataset (`None` if not breached)
+    pub fn check_password(&self, password: &str) -> RpmResult<Option<u64>> {
+        self.lookup_hash(&sha1_hex(password))
+    }
+
+    /// Binary search the dataset for an uppercase hex SHA-1 hash
+    pub fn lookup_hash(&self, hash: &str) -> RpmResult<Option<u64>> {
+        let target = hash.to_ascii_uppercase();
+        let mut reader = BufReader::new(File::open(&self.path)?);
+        let mut line = Vec::new();
+
+        // Invariant: if the target line exists, it starts within [lo, hi)
+        let (mut lo, mut hi) = (0u64, self.len);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            let start = line_start_at_or_after(&mut reader, mid)?;
+            if start >= hi {
+                hi = mid;
+                continue;
+            }
+
+            line.clear();
+            let read = reader.read_until(b'\n', &mut line)? as u64;
+            if read == 0 {
+                hi = mid;
+                continue;
+            }
+
+            let (line_hash, count) = parse_line(&line)?;
+            match line_hash.as_str().cmp(target.as_str()) {
+                std::cmp::Ordering::Equal => return Ok(Some(count)),
+                std::cmp::Ordering::Less => lo = start + read,
+                std::cmp::Ordering::Greater => hi = mid,
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+/// Online HIBP lookups with k-anonymity: only the first five hex characters of
+/// the SHA-1 are sent, the API answers with every suffix in that range and the
+/// match happens locally. Responses are padded so their size does not leak
+/// how many suffixes a range really has.
+pub struct OnlineHibpClient {
+    agent: ureq::Agent,
+}
+
+impl Default for OnlineHibpClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OnlineHibpClient {
+    pub fn new() -> Self {
+        Self {
+            agent: ureq::AgentBuilder::new()
+                .timeout(Duration::from_secs(15))
+                .https_only(true)
+                .build(),
+        }
+    }
+
+    /// How many times the password appears in known breaches (`None` if not breached)
+    pub fn check_password(&self, password: &str) -> RpmResult<Option<u64>> {
+        let hash = sha1_hex(password);
+        let (prefix, suffix) = hash.split_at(RANGE_PREFIX_LEN);
+        let body = self
+            .agent
+            .get(&format!("{}{}", HIBP_RANGE_URL, prefix))
+            .set("Add-Padding", "true")
+            .call()
+            .map_err(|e| RpmError::Io(std::io::Error::other(format!("HIBP range request failed: {}", e))))?
+            .into_string()?;
+        Ok(find_suffix(&body, suffix))
+    }
+}
+
+/// Look up a hash suffix in a range API response (`SUFFIX:COUNT` lines).
+/// Padding lines have a count of 0 and never count as a match.
+pub fn find_suffix(body: &str, suffix: &str) -> Option<u64> {
+    body.lines()
+        .filter_map(|line| line.trim().split_once(':'))
+        .find(|(line_suffix, _)| line_suffix.eq_ignore_ascii_case(suffix))
+        .and_then(|(_, count)| count.trim().parse().ok())
+        .filter(|&count| count > 0)
+}
+
+/// Breach lookup configured for this installation: the offline dataset when
+/// `hibp_dataset_path` is set, otherwise the online range API when `hibp_online` is on
+pub enum BreachChecker {
+    Offline(OfflineHibpDataset),
+    Online(OnlineHibpClient),
+}
+
+impl BreachChecker {
+    /// `None` while neither source is configured
+    pub fn from_config(config: &Config) -> RpmResult<Option<Self>> {
+        if let Some(ref path) = config.hibp_dataset_path {
+            return OfflineHibpDataset::open(path).map(|dataset| Some(Self::Offline(dataset)));
+        }
+        Ok(config.hibp_online.then(|| Self::Online(OnlineHibpClient::new())))
+    }
+
+    /// How many times the password appears in known breaches (`None` if not breached)
+    pub fn check_password(&self, password: &str) -> RpmResult<Option<u64>> {
+        match self {
+            Self::Offline(dataset) => dataset.check_password(password),
+            Self::Online(client) => client.check_password(password),
+        }
+    }
+}
+
+/// Position the reader at the first line starting at or after `pos` and return that offset
+fn line_start_at_or_after(reader: &mut BufReader<File>, pos: u64) -> RpmResult<u64> {
+    if pos == 0 {
+        reader.seek(SeekFrom::Start(0))?;
+        return Ok(0);
+    }
+
+    // Start one byte earlier so that a line beginning exactly at `pos` is not skipped
+    reader.seek(SeekFrom::Start(pos - 1))?;
+    let mut skipped = Vec::new();
+    let consumed = reader.read_until(b'\n', &mut skipped)? as u64;
+    Ok(pos - 1 + consumed)
+}
+
+/// Parse a `HASH:COUNT` line
+fn parse_line(line: &[u8]) -> RpmResult<(String, u64)> {
+    let text = std::str::from_utf8(line)
+        .map_err(|_| RpmError::InvalidInput("HIBP dataset contains invalid UTF-8".to_string()))?
+        .trim_end();
+    let (hash, count) = text.split_once(':').unwrap_or((text, "0"));
+    if hash.len() != SHA1_HEX_LEN {
+        return Err(RpmError::InvalidInput(format!(
+            "Unexpected HIBP dataset line (is it the SHA-1 ordered-by-hash file?): {}",
+            text
+        )));
+    }
+    Ok((hash.to_ascii_uppercase(), count.trim().parse().unwrap_or(0)))
+}
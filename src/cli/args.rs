@@ -0,0 +1,237 @@
+//! Command line arguments. Without a command rpm starts the TUI
+
+use super::PasswordSource;
+use clap::{Args, Parser, Subcommand};
+use std::path::PathBuf;
+
+#[derive(Debug, Default, Parser)]
+#[command(
+    name = "rpm",
+    version,
+    about = "Secure password manager with TUI, system tray, and browser extensions",
+    after_help = "Set RPM_ASKPASS to a program that prints the password to ask for it outside the terminal"
+)]
+pub struct Cli {
+    /// Log filter: a level such as `debug`, or a directive such as `rpm::storage=trace`
+    #[arg(long, global = true, value_name = "FILTER")]
+    pub log_level: Option<String>,
+    /// Copy a password in the running rpm (or after unlocking)
+    #[arg(long, value_name = "ENTRY")]
+    pub copy: Option<String>,
+    /// Run as the browser extension's native host
+    #[arg(long)]
+    pub native_messaging: bool,
+    #[command(flatten)]
+    pub global: GlobalArgs,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// Options every command accepts, before or after its name
+#[derive(Debug, Default, Args)]
+pub struct GlobalArgs {
+    /// Answer yes to confirmations
+    #[arg(short = 'y', long, global = true)]
+    pub yes: bool,
+    /// Read the master password's first line from stdin
+    #[arg(long, global = true, conflicts_with = "password_fd")]
+    pub password_stdin: bool,
+    /// Read the master password's first line from a file descriptor
+    #[arg(long, global = true, value_name = "FD")]
+    pub password_fd: Option<i32>,
+}
+
+impl GlobalArgs {
+    pub fn password_source(&self) -> Option<PasswordSource> {
+        match self.password_fd {
+            Some(fd) => Some(PasswordSource::Fd(fd)),
+            None => self.password_stdin.then_some(PasswordSource::Stdin),
+        }
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Print an entry's password, or copy it to the clipboard
+    Get {
+        /// Entry name; a unique part of it is enough
+        entry: String,
+        /// Copy to the clipboard instead of printing
+        #[arg(short, long)]
+        clip: bool,
+        #[command(flatten)]
+        json: JsonArgs,
+    },
+    /// Add an entry, asking for its password
+    Add {
+        entry: String,
+        username: Option<String>,
+    },
+    /// List entry names
+    List {
+        #[command(flatten)]
+        json: JsonArgs,
+    },
+    /// Move an entry to the trash
+    Rm {
+        /// Entry name; a unique part of it is enough
+        entry: String,
+    },
+    /// Print generated passwords; the vault is not needed
+    Generate(GenerateArgs),
+    /// Import entries from another password manager or a browser
+    #[command(subcommand)]
+    Import(ImportCommand),
+    /// Write an encrypted export now, as the scheduled export would
+    Export,
+    /// Pack the vault into an archive protected by its own passphrase
+    Backup { file: PathBuf },
+    /// Replace the vault with the contents of a backup archive
+    Restore { file: PathBuf },
+    /// Re-encrypt the vault under a new key; the master password stays the same
+    RotateKey,
+    /// Decrypt every entry and compare the index with the files on disk
+    Verify,
+    /// Pull remote changes with git, then push local ones
+    Sync {
+        #[command(subcommand)]
+        command: Option<SyncCommand>,
+    },
+    /// Browser extension support
+    #[command(subcommand)]
+    NativeMessaging(NativeMessagingCommand),
+    /// Developer tools
+    #[cfg(feature = "dev-tools")]
+    #[command(subcommand)]
+    Dev(DevCommand),
+}
+
+/// `--json` output of `get` and `list`
+#[derive(Debug, Default, Args)]
+pub struct JsonArgs {
+    /// Print JSON
+    #[arg(long)]
+    pub json: bool,
+    /// Include passwords in the JSON
+    #[arg(long, requires = "json")]
+    pub show_password: bool,
+}
+
+/// Generator settings. Start from the generator defaults with symbols on, or
+/// from a saved preset that the other options adjust
+#[derive(Debug, Default, Args)]
+pub struct GenerateArgs {
+    /// Characters, or words with --passphrase
+    #[arg(short, long, value_name = "N")]
+    pub length: Option<usize>,
+    /// How many passwords to print
+    #[arg(short = 'n', long, value_name = "N", default_value_t = 1)]
+    pub count: usize,
+    /// Generator preset saved in the TUI
+    #[arg(long, value_name = "NAME")]
+    pub preset: Option<String>,
+    /// Characters never to use
+    #[arg(long, value_name = "CHARS")]
+    pub exclude: Option<String>,
+    /// Extra characters to use
+    #[arg(long, value_name = "CHARS")]
+    pub chars: Option<String>,
+    /// Use symbols
+    #[arg(long, conflicts_with = "no_symbols")]
+    pub symbols: bool,
+    #[arg(long)]
+    pub no_symbols: bool,
+    #[arg(long)]
+    pub no_uppercase: bool,
+    #[arg(long)]
+    pub no_lowercase: bool,
+    #[arg(long)]
+    pub no_digits: bool,
+    /// Add Cyrillic letters
+    #[arg(long)]
+    pub cyrillic: bool,
+    /// Leave out look-alike characters such as 0 and O
+    #[arg(long)]
+    pub no_ambiguous: bool,
+    /// Words in the interface language instead of characters
+    #[arg(long)]
+    pub passphrase: bool,
+    /// Print a JSON array
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ImportCommand {
+    /// CSV export, with the column mapping saved by the TUI or guessed from the headers
+    Csv { file: PathBuf },
+    /// KeePass database (KDBX 3.1 or 4)
+    Kdbx { file: PathBuf },
+    /// password-store, decrypting each entry with gpg
+    Pass {
+        /// Store directory [default: $PASSWORD_STORE_DIR or ~/.password-store]
+        dir: Option<PathBuf>,
+    },
+    /// macOS Keychain
+    Keychain,
+    /// Windows Credential Manager
+    Credman,
+    /// Firefox profile (read-only)
+    Firefox {
+        /// Profile directory [default: the default profile]
+        profile: Option<PathBuf>,
+    },
+    /// Google Chrome profile (read-only)
+    Chrome {
+        /// Profile directory [default: the default profile]
+        profile: Option<PathBuf>,
+    },
+    /// Chromium profile (read-only)
+    Chromium {
+        /// Profile directory [default: the default profile]
+        profile: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SyncCommand {
+    /// Make the passwords directory a git repository and enable auto-commits
+    Init { remote: Option<String> },
+    /// Settle a conflict by overwriting the remote vault with the local one
+    KeepLocal,
+    /// Settle a conflict by replacing the local vault with the remote one
+    TakeRemote,
+    /// Mirror the vault to the WebDAV server now
+    Webdav {
+        #[command(subcommand)]
+        resolve: Option<WebDavCommand>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum WebDavCommand {
+    /// Overwrite the conflicting files on the server with the local ones
+    KeepLocal,
+    /// Replace the conflicting local files with the server's
+    TakeRemote,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum NativeMessagingCommand {
+    /// Register the native host for a browser extension
+    Install { extension_id: String },
+}
+
+#[cfg(feature = "dev-tools")]
+#[derive(Debug, Subcommand)]
+pub enum DevCommand {
+    /// Create a reproducible synthetic vault
+    Seed {
+        #[arg(long, default_value_t = 1000)]
+        entries: usize,
+        #[arg(long, default_value_t = crate::dev::DEFAULT_SEED)]
+        seed: u64,
+        #[arg(long, default_value = "rpm-dev-vault")]
+        dir: PathBuf,
+    },
+}
@@ -0,0 +1,222 @@
+//! `rpm get`, `rpm add`, `rpm list` and `rpm rm`: work with entries without the TUI
+
+use super::{unlock, Term, Tone};
+use crate::config::{Config, DirectoryConfig};
+use crate::crypto::CryptoManager;
+use crate::errors::{RpmError, RpmResult};
+use crate::models::{EntryDetails, EntryMeta};
+use crate::storage::PasswordStorage;
+use crate::tui::clipboard::{self, CopiedText};
+use arboard::Clipboard;
+use serde::Serialize;
+use zeroize::Zeroize;
+
+/// Entry `query` names: an exact match, then one ignoring case, then the only
+/// name containing it. Never a guess between several entries
+pub fn find_entry<'a>(names: &'a [(String, String)], query: &str) -> RpmResult<&'a (String, String)> {
+    let lowercase = query.to_lowercase();
+    if let Some(found) = names
+        .iter()
+        .find(|(_, name)| name == query)
+        .or_else(|| names.iter().find(|(_, name)| name.to_lowercase() == lowercase))
+    {
+        return Ok(found);
+    }
+    let matches: Vec<&(String, String)> = names.iter().filter(|(_, name)| name.to_lowercase().contains(&lowercase)).collect();
+    match matches.as_slice() {
+        [found] => Ok(found),
+        [] => Err(RpmError::InvalidInput(format!("No entry matches '{}'", query))),
+        _ => {
+            let mut candidates: Vec<&str> = matches.iter().map(|(_, name)| name.as_str()).collect();
+            candidates.sort_unstable();
+            Err(RpmError::InvalidInput(format!(
+                "'{}' matches several entries: {}",
+                query,
+                candidates.join(", ")
+            )))
+        }
+    }
+}
+
+/// An entry as `--json` prints it. The password and notes are only there
+/// with `--show-password`
+#[derive(Debug, Serialize)]
+pub struct EntryJson {
+    #[serde(flatten)]
+    pub meta: EntryMeta,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+}
+
+impl EntryJson {
+    /// `meta` with the secrets of its entry if `show_password`
+    fn load(storage: &PasswordStorage, meta: EntryMeta, show_password: bool, key: &[u8]) -> RpmResult<Self> {
+        if !show_password {
+            return Ok(Self { meta, password: None, notes: None });
+        }
+        let password = storage.load_password_file(&meta.id, key)?;
+        let notes = storage.load_entry_details(&meta.id, key)?.notes;
+        Ok(Self { meta, password: Some(password), notes })
+    }
+}
+
+impl Drop for EntryJson {
+    fn drop(&mut self) {
+        self.password.zeroize();
+        self.notes.zeroize();
+    }
+}
+
+/// `rpm get [--clip] [--json [--show-password]] <name>`: print the password, or
+/// copy it and clear the clipboard after the configured timeout. With `--json`
+/// the entry's metadata is printed instead
+pub fn get(term: &Term, query: &str, clip: bool, show_password: bool, config: &Config, crypto: CryptoManager) -> RpmResult<()> {
+    let key = unlock(term, config, &crypto)?;
+    let storage = PasswordStorage::new(config, crypto);
+    let mut names = storage.list_decrypted_names(key.as_slice())?;
+    let result = find_entry(&names, query).map(|(filename, name)| (filename.clone(), name.clone()));
+    for (_, name) in names.iter_mut() {
+        name.zeroize();
+    }
+    let (filename, name) = result?;
+
+    if term.json() {
+        let meta = storage
+            .entry_meta(&filename, key.as_slice())?
+            .ok_or_else(|| RpmError::InvalidInput(format!("No entry matches '{}'", query)))?;
+        term.print_json(&EntryJson::load(&storage, meta, show_password, key.as_slice())?)?;
+    }
+    let mut password = storage.load_password_file(&filename, key.as_slice())?;
+    let result = if clip {
+        copy_password(term, &password, &name, config)
+    } else {
+        if !term.json() {
+            println!("{}", password);
+        }
+        Ok(())
+    };
+    password.zeroize();
+    result?;
+    storage.mark_used(&filename, key.as_slice())
+}
+
+/// Copy and stay running until the clipboard is cleared: on X11 and Wayland
+/// the copied text is only available while its owner runs
+fn copy_password(term: &Term, password: &str, name: &str, config: &Config) -> RpmResult<()> {
+    let policy = DirectoryConfig::load(&config.passwords_directory_path())
+        .map(|dir_config| dir_config.policy)
+        .unwrap_or_default();
+    let timeout_seconds = policy.clipboard_timeout_seconds(config.clipboard_timeout_seconds);
+
+    if clipboard::osc52_preferred(&config.clipboard_osc52) {
+        clipboard::write_osc52(password)?;
+        term.say(Tone::Success, &format!("Copied the password of {}", name));
+        if timeout_seconds > 0 {
+            std::thread::sleep(std::time::Duration::from_secs(timeout_seconds));
+            clipboard::write_osc52("")?;
+        }
+        return Ok(());
+    }
+
+    let clipboard_error = |e: arboard::Error| RpmError::InvalidInput(format!("Cannot use the clipboard: {}", e));
+    let mut system_clipboard = Clipboard::new().map_err(clipboard_error)?;
+    system_clipboard.set_text(password).map_err(clipboard_error)?;
+    if config.clipboard_primary_selection {
+        clipboard::set_primary(&mut system_clipboard, password).map_err(clipboard_error)?;
+    }
+    let copied = CopiedText::new(password);
+    if timeout_seconds == 0 {
+        term.say(Tone::Success, &format!("Copied the password of {}", name));
+        return Ok(());
+    }
+    term.say(
+        Tone::Success,
+        &format!("Copied the password of {}; the clipboard is cleared in {} s", name, timeout_seconds),
+    );
+    std::thread::sleep(std::time::Duration::from_secs(timeout_seconds));
+    clipboard::clear_if_unchanged(&mut system_clipboard, &copied, config.clipboard_primary_selection);
+    Ok(())
+}
+
+/// `rpm add <name> [username]`: ask for the password twice and save a new entry
+pub fn add(term: &Term, name: &str, username: Option<&str>, config: &Config, crypto: CryptoManager) -> RpmResult<()> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(RpmError::InvalidInput("The entry name is empty".to_string()));
+    }
+    let key = unlock(term, config, &crypto)?;
+    let storage = PasswordStorage::new(config, crypto);
+    let mut names = storage.list_decrypted_names(key.as_slice())?;
+    let exists = names.iter().any(|(_, existing)| existing == name);
+    for (_, existing) in names.iter_mut() {
+        existing.zeroize();
+    }
+    if exists {
+        return Err(RpmError::InvalidInput(format!("An entry named '{}' already exists", name)));
+    }
+
+    let mut password = term.read_password(&format!("Password for {}:", name))?;
+    let mut repeated = term.read_password("Repeat the password:")?;
+    let same = password == repeated;
+    repeated.zeroize();
+    if password.is_empty() || !same {
+        password.zeroize();
+        return Err(RpmError::InvalidInput(if same { "The password is empty" } else { "The passwords differ" }.to_string()));
+    }
+
+    let details = EntryDetails {
+        username: username.map(str::to_string).filter(|username| !username.is_empty()),
+        ..EntryDetails::default()
+    };
+    let entries = [(name.to_string(), password, details)];
+    let result = storage.add_entries(&entries, key.as_slice());
+    let [(_, mut password, _)] = entries;
+    password.zeroize();
+    result?;
+    term.say(Tone::Success, &format!("Added {}", name));
+    Ok(())
+}
+
+/// `rpm list [--json [--show-password]]`: entry names, one per line and sorted,
+/// archived entries left out. With `--json`, an array of entries sorted by title
+pub fn list(term: &Term, show_password: bool, config: &Config, crypto: CryptoManager) -> RpmResult<()> {
+    let key = unlock(term, config, &crypto)?;
+    let storage = PasswordStorage::new(config, crypto);
+    if term.json() {
+        let entries = storage
+            .list_entry_meta(key.as_slice())?
+            .into_iter()
+            .map(|meta| EntryJson::load(&storage, meta, show_password, key.as_slice()))
+            .collect::<RpmResult<Vec<_>>>()?;
+        return term.print_json(&entries);
+    }
+    let archived = storage.list_archived_entries(key.as_slice())?;
+    let mut names: Vec<String> = storage
+        .list_decrypted_names(key.as_slice())?
+        .into_iter()
+        .filter(|(filename, _)| !archived.contains(filename))
+        .map(|(_, name)| name)
+        .collect();
+    names.sort_by_key(|name| name.to_lowercase());
+    for name in names.iter_mut() {
+        println!("{}", name);
+        name.zeroize();
+    }
+    Ok(())
+}
+
+/// `rpm rm <name>`: move the entry to the trash after asking
+pub fn remove(term: &Term, query: &str, config: &Config, crypto: CryptoManager) -> RpmResult<()> {
+    let key = unlock(term, config, &crypto)?;
+    let storage = PasswordStorage::new(config, crypto);
+    let names = storage.list_decrypted_names(key.as_slice())?;
+    let (filename, name) = find_entry(&names, query)?;
+    if !term.confirm(&format!("Move {} to the trash?", name))? {
+        return Ok(());
+    }
+    storage.delete_entry(filename, key.as_slice())?;
+    term.say(Tone::Success, &format!("Moved {} to the trash", name));
+    Ok(())
+}
@@ -0,0 +1,79 @@
+//! `rpm generate`: the TUI's password generator for scripts
+
+use super::{GenerateArgs, Term};
+use crate::config::Config;
+use crate::errors::{RpmError, RpmResult};
+use crate::i18n::Language;
+use crate::models::PasswordPolicy;
+use crate::tui::{generate_from_policy, DEFAULT_PASSPHRASE_WORDS};
+use zeroize::Zeroize;
+
+/// Generator settings and how many passwords to print, from the command line
+/// options: the generator defaults with symbols on, or the preset given with
+/// `--preset`, adjusted by the other options
+pub fn generator_options(options: &GenerateArgs, config: &Config) -> RpmResult<(PasswordPolicy, usize)> {
+    let mut policy = match options.preset {
+        Some(ref name) => config
+            .generator_presets
+            .get(name)
+            .cloned()
+            .ok_or_else(|| RpmError::InvalidInput(format!("No generator preset named '{}'", name)))?,
+        None => PasswordPolicy {
+            use_special: true,
+            ..PasswordPolicy::default()
+        },
+    };
+    if let Some(ref exclude) = options.exclude {
+        policy.exclude_chars = exclude.clone();
+    }
+    if let Some(ref chars) = options.chars {
+        policy.custom_chars = chars.clone();
+    }
+    if options.symbols {
+        policy.use_special = true;
+    }
+    if options.no_symbols {
+        policy.use_special = false;
+    }
+    if options.no_uppercase {
+        policy.use_uppercase = false;
+    }
+    if options.no_lowercase {
+        policy.use_lowercase = false;
+    }
+    if options.no_digits {
+        policy.use_digits = false;
+    }
+    if options.cyrillic {
+        policy.use_cyrillic = true;
+    }
+    if options.no_ambiguous {
+        policy.exclude_ambiguous = true;
+    }
+    if options.passphrase {
+        policy.passphrase_language = Some(Language::from_code(&config.language).to_code().to_string());
+    }
+    // Words rather than characters for a passphrase
+    policy.length = match options.length {
+        Some(length) => length,
+        None if options.passphrase => DEFAULT_PASSPHRASE_WORDS,
+        None => policy.length,
+    };
+    Ok((policy, options.count))
+}
+
+/// `rpm generate [options]`: print freshly generated passwords, one per line
+/// (a JSON array with `--json`). The vault is not needed
+pub fn generate(term: &Term, options: &GenerateArgs, config: &Config) -> RpmResult<()> {
+    let (policy, count) = generator_options(options, config)?;
+    let mut passwords = (0..count).map(|_| generate_from_policy(&policy)).collect::<RpmResult<Vec<_>>>()?;
+    if term.json() {
+        term.print_json(&passwords)?;
+    } else {
+        for password in &passwords {
+            println!("{}", password);
+        }
+    }
+    passwords.zeroize();
+    Ok(())
+}
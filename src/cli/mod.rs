@@ -0,0 +1,540 @@
+use crate::backup;
+use crate::config::{Config, DirectoryConfig};
+use crate::crypto::{CryptoManager, SecureKey};
+use crate::errors::{RpmError, RpmResult};
+use crate::export;
+use crate::import::credstore::CredentialStore;
+use crate::import::chromium::Browser;
+use crate::import::{browser, chromium, credstore, firefox, import_rows, kdbx, pass, saved_or_guessed_mapping, CsvTable, ImportField, ImportReport};
+use crate::keychain;
+use crate::native_messaging;
+use crate::server;
+use crate::storage::PasswordStorage;
+use crate::sync::git::GitSync;
+use crate::sync::webdav::{Side, SyncReport, WebDavSync};
+use crate::sync::PullOutcome;
+use crate::webhook::{WebhookEvent, Webhooks};
+use base64::engine::general_purpose::{STANDARD as BASE64_STANDARD, STANDARD_NO_PAD as BASE64_STANDARD_NO_PAD};
+use base64::Engine;
+use std::path::Path;
+use zeroize::Zeroize;
+
+mod args;
+mod entries;
+mod generate;
+mod term;
+pub use args::{
+    Cli, Command, GenerateArgs, GlobalArgs, ImportCommand, JsonArgs, NativeMessagingCommand, SyncCommand, WebDavCommand,
+};
+#[cfg(feature = "dev-tools")]
+pub use args::DevCommand;
+pub use entries::{find_entry, EntryJson};
+pub use generate::generator_options;
+pub use term::{PasswordSource, Term, Tone, ASKPASS_ENV};
+
+/// Run a command given on the command line instead of starting the TUI
+pub fn run(command: Command, global: &GlobalArgs, config: &Config, crypto: CryptoManager) -> RpmResult<()> {
+    let json = match command {
+        Command::Get { ref json, .. } | Command::List { ref json } => json.json,
+        Command::Generate(ref options) => options.json,
+        _ => false,
+    };
+    let term = &Term::new(config, global.yes).with_json(json).with_password_source(global.password_source());
+    match command {
+        Command::Get { entry, clip, json } => entries::get(term, &entry, clip, json.show_password, config, crypto),
+        Command::Add { entry, username } => entries::add(term, &entry, username.as_deref(), config, crypto),
+        Command::List { json } => entries::list(term, json.show_password, config, crypto),
+        Command::Rm { entry } => entries::remove(term, &entry, config, crypto),
+        Command::Generate(options) => generate::generate(term, &options, config),
+        Command::Import(import) => match import {
+            ImportCommand::Csv { file } => import_csv(term, &file, config, crypto),
+            ImportCommand::Kdbx { file } => import_kdbx(term, &file, config, crypto),
+            ImportCommand::Pass { dir } => import_pass(term, &dir.unwrap_or_else(pass::default_store_dir), config, crypto),
+            ImportCommand::Keychain => import_credential_store(term, CredentialStore::Keychain, config, crypto),
+            ImportCommand::Credman => import_credential_store(term, CredentialStore::CredentialManager, config, crypto),
+            ImportCommand::Firefox { profile } => import_firefox(term, profile.or_else(firefox::default_profile), config, crypto),
+            ImportCommand::Chrome { profile } => import_chromium(term, Browser::Chrome, profile, config, crypto),
+            ImportCommand::Chromium { profile } => import_chromium(term, Browser::Chromium, profile, config, crypto),
+        },
+        Command::Export => run_export(term, config, crypto),
+        Command::Backup { file } => create_backup(term, &file, config, &crypto),
+        Command::Restore { file } => restore_backup(term, &file, config, &crypto),
+        Command::RotateKey => rotate_key(term, config, crypto),
+        Command::Verify => verify(term, config, crypto),
+        Command::Sync { command } => match command {
+            None => sync(term, config),
+            Some(SyncCommand::Init { remote }) => sync_init(term, remote.as_deref(), config),
+            Some(SyncCommand::KeepLocal) => sync_resolve(term, config, true),
+            Some(SyncCommand::TakeRemote) => sync_resolve(term, config, false),
+            Some(SyncCommand::Webdav { resolve }) => {
+                let side = resolve.map(|resolve| match resolve {
+                    WebDavCommand::KeepLocal => Side::Local,
+                    WebDavCommand::TakeRemote => Side::Remote,
+                });
+                webdav_sync(term, config, side)
+            }
+        },
+        Command::NativeMessaging(NativeMessagingCommand::Install { extension_id }) => {
+            install_native_host(term, &extension_id, config)
+        }
+        #[cfg(feature = "dev-tools")]
+        Command::Dev(DevCommand::Seed { entries, seed, dir }) => dev_seed(term, entries, seed, &dir, &crypto),
+    }
+}
+
+/// `rpm import csv <file>`: import with the column mapping saved by the TUI wizard
+/// for this header row, or one guessed from the headers
+fn import_csv(term: &Term, path: &Path, config: &Config, crypto: CryptoManager) -> RpmResult<()> {
+    let table = CsvTable::load(path)?;
+    let (mapping, saved) = saved_or_guessed_mapping(&table.headers, &config.import_mappings);
+    if saved {
+        term.say(Tone::Dimmed, "Using the saved column mapping for this format");
+    }
+    if !mapping.contains(&ImportField::Name) {
+        return Err(RpmError::InvalidInput(
+            "No name/title column found in the CSV header".to_string(),
+        ));
+    }
+
+    for (header, field) in table.headers.iter().zip(mapping.iter()) {
+        println!("  {:<20} -> {:?}", header, field);
+    }
+
+    let key = unlock(term, config, &crypto)?;
+    let storage = PasswordStorage::new(config, crypto);
+    let report = import_rows(&storage, &table, &mapping, key.as_slice())?;
+    print_report(term, &report);
+    Ok(())
+}
+
+/// `rpm import kdbx <file>`: import every entry of a KeePass database,
+/// prefixing names with their group path
+fn import_kdbx(term: &Term, path: &Path, config: &Config, crypto: CryptoManager) -> RpmResult<()> {
+    let mut keepass_password = term.read_password("KeePass password:")?;
+    let entries = kdbx::load(path, &keepass_password);
+    keepass_password.zeroize();
+    let entries = entries?;
+    term.say(Tone::Accent, &format!("Found {} entries in {}", entries.len(), path.display()));
+
+    let key = unlock(term, config, &crypto)?;
+    let storage = PasswordStorage::new(config, crypto);
+    let report = kdbx::import_entries(&storage, &entries, key.as_slice())?;
+    print_report(term, &report);
+    Ok(())
+}
+
+/// `rpm import pass [dir]`: import a password-store, decrypting each entry with gpg
+fn import_pass(term: &Term, store: &Path, config: &Config, crypto: CryptoManager) -> RpmResult<()> {
+    let count = pass::list_entries(store)?.len();
+    term.say(Tone::Accent, &format!("Found {} entries in {}", count, store.display()));
+
+    let key = unlock(term, config, &crypto)?;
+    let storage = PasswordStorage::new(config, crypto);
+    let report = pass::import_store(&storage, store, key.as_slice())?;
+    print_report(term, &report);
+    Ok(())
+}
+
+/// `rpm import keychain` / `rpm import credman`: import saved credentials from the OS store
+/// after listing them and asking for confirmation
+fn import_credential_store(term: &Term, store: CredentialStore, config: &Config, crypto: CryptoManager) -> RpmResult<()> {
+    let credentials = store.list()?;
+    if credentials.is_empty() {
+        term.say(Tone::Warning, &format!("No credentials found in the {}", store.display_name()));
+        return Ok(());
+    }
+    term.say(Tone::Accent, &format!("Found {} credentials in the {}:", credentials.len(), store.display_name()));
+    for credential in &credentials {
+        match credential.username {
+            Some(ref username) => println!("  {} ({})", credential.name, username),
+            None => println!("  {}", credential.name),
+        }
+    }
+    if store == CredentialStore::Keychain {
+        term.say(Tone::Dimmed, "macOS will ask to allow access to each password.");
+    }
+    if !term.confirm("Import them into the vault?")? {
+        term.say(Tone::Warning, "Nothing imported");
+        return Ok(());
+    }
+
+    let key = unlock(term, config, &crypto)?;
+    let storage = PasswordStorage::new(config, crypto);
+    let report = credstore::import_credentials(&storage, store, &credentials, key.as_slice())?;
+    print_report(term, &report);
+    Ok(())
+}
+
+/// `rpm import firefox [profile]`: decrypt the logins of a Firefox profile (read-only)
+/// and import them, asking for the primary password if the profile has one
+fn import_firefox(term: &Term, profile: Option<std::path::PathBuf>, config: &Config, crypto: CryptoManager) -> RpmResult<()> {
+    let profile = profile.ok_or_else(|| RpmError::InvalidInput("No Firefox profile found; pass its directory".to_string()))?;
+    if !term.confirm(&format!("Read the saved logins of the Firefox profile {}?", profile.display()))? {
+        term.say(Tone::Warning, "Nothing imported");
+        return Ok(());
+    }
+    let logins = match firefox::load(&profile, "") {
+        Err(RpmError::AuthenticationFailed) => {
+            let mut primary_password = term.read_password("Firefox primary password:")?;
+            let logins = firefox::load(&profile, &primary_password);
+            primary_password.zeroize();
+            logins?
+        }
+        logins => logins?,
+    };
+    import_browser_logins(term, logins, config, crypto)
+}
+
+/// `rpm import chrome|chromium [profile]`: decrypt the logins of a browser profile (read-only) and import them
+fn import_chromium(term: &Term, browser: Browser, profile: Option<std::path::PathBuf>, config: &Config, crypto: CryptoManager) -> RpmResult<()> {
+    let profile = profile
+        .or_else(|| browser.default_profile())
+        .ok_or_else(|| RpmError::InvalidInput(format!("No {} profile found; pass its directory", browser.display_name())))?;
+    if !term.confirm(&format!("Read the saved logins of the {} profile {}?", browser.display_name(), profile.display()))? {
+        term.say(Tone::Warning, "Nothing imported");
+        return Ok(());
+    }
+    import_browser_logins(term, chromium::load(browser, &profile)?, config, crypto)
+}
+
+fn import_browser_logins(term: &Term, logins: browser::BrowserLogins, config: &Config, crypto: CryptoManager) -> RpmResult<()> {
+    term.say(Tone::Accent, &format!("Decrypted {} logins", logins.logins.len()));
+    if logins.unsupported > 0 {
+        term.say(Tone::Warning, &format!("{} logins use encryption rpm cannot read and are skipped", logins.unsupported));
+    }
+
+    let key = unlock(term, config, &crypto)?;
+    let storage = PasswordStorage::new(config, crypto);
+    let mut report = browser::import_logins(&storage, &logins.logins, key.as_slice())?;
+    report.skipped += logins.unsupported;
+    print_report(term, &report);
+    Ok(())
+}
+
+/// Items that failed, then the totals
+fn print_report(term: &Term, report: &ImportReport) {
+    for warning in &report.warnings {
+        term.say(Tone::Warning, warning);
+    }
+    term.say(Tone::Success, &format!("Created: {}, skipped: {}", report.created, report.skipped));
+}
+
+/// `rpm export`: write an encrypted snapshot now, as the scheduled export would
+fn run_export(term: &Term, config: &Config, crypto: CryptoManager) -> RpmResult<()> {
+    let key = unlock(term, config, &crypto)?;
+    let storage = PasswordStorage::new(config, crypto);
+    let path = export::run_export(&storage, config, key.as_slice())?;
+    if let Some(ref command) = config.export_upload_command {
+        export::upload(command, &path)?;
+    }
+    term.say(Tone::Success, &format!("Exported to {}", path.display()));
+    // The process exits right after, so wait for the webhook
+    if let Some(webhook) = Webhooks::from_config(config).fire(WebhookEvent::export(&path)) {
+        let _ = webhook.join();
+    }
+
+    let mut config = config.clone();
+    config.export_last_run = Some(chrono::Utc::now());
+    config.save().map_err(|e| RpmError::Config(e.to_string()))
+}
+
+/// `rpm backup <file>`: pack the vault into an archive protected by its own passphrase
+fn create_backup(term: &Term, path: &Path, config: &Config, crypto: &CryptoManager) -> RpmResult<()> {
+    let mut passphrase = term.read_password("Backup passphrase:")?;
+    let mut confirm = term.read_password("Repeat passphrase:")?;
+    let matches = passphrase == confirm;
+    confirm.zeroize();
+    let result = if matches {
+        backup::create_backup(&config.passwords_directory_path(), path, &passphrase, crypto)
+    } else {
+        Err(RpmError::InvalidInput("Passphrases do not match".to_string()))
+    };
+    passphrase.zeroize();
+
+    let info = result?;
+    term.say(Tone::Success, &format!("Backed up {} entries to {}", info.entry_count, path.display()));
+    Ok(())
+}
+
+/// `rpm restore <file>`: replace the vault with the contents of a backup archive
+fn restore_backup(term: &Term, path: &Path, config: &Config, crypto: &CryptoManager) -> RpmResult<()> {
+    let info = backup::read_info(path)?;
+    let passwords_dir = config.passwords_directory_path();
+    println!(
+        "Backup from {} with {} entries will replace the vault in {}",
+        info.created_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M"),
+        info.entry_count,
+        passwords_dir.display()
+    );
+    if !term.confirm("Restore it?")? {
+        term.say(Tone::Warning, "Nothing restored");
+        return Ok(());
+    }
+
+    let mut passphrase = if info.is_encrypted() {
+        term.read_password("Backup passphrase:")?
+    } else {
+        String::new()
+    };
+    let restored = backup::restore_backup(path, &passwords_dir, &passphrase, crypto);
+    passphrase.zeroize();
+    term.say(Tone::Success, &format!("Restored {} entries", restored?));
+    Ok(())
+}
+
+/// `rpm dev seed [--entries N] [--seed S] [--dir D]`: create a reproducible synthetic vault
+#[cfg(feature = "dev-tools")]
+fn dev_seed(term: &Term, entries: usize, seed: u64, dir: &Path, crypto: &CryptoManager) -> RpmResult<()> {
+    use crate::dev;
+
+    dev::seed_vault(dir, entries, seed, crypto)?;
+    term.say(
+        Tone::Success,
+        &format!(
+            "Created {} entries in {} (seed {}, master password \"{}\")",
+            entries,
+            dir.display(),
+            seed,
+            dev::SEED_MASTER_PASSWORD
+        ),
+    );
+    Ok(())
+}
+
+/// `rpm sync init [remote]`: make the passwords directory a git repository,
+/// enable auto-commits and remember the remote
+fn sync_init(term: &Term, remote: Option<&str>, config: &Config) -> RpmResult<()> {
+    let passwords_dir = config.passwords_directory_path();
+    GitSync::new(&passwords_dir).init(remote)?;
+
+    let mut config = config.clone();
+    config.git_sync = true;
+    if let Some(remote) = remote {
+        config.git_sync_remote = Some(remote.to_string());
+    }
+    config.save().map_err(|e| RpmError::Config(e.to_string()))?;
+    term.say(Tone::Success, &format!("Git sync enabled for {}", passwords_dir.display()));
+    Ok(())
+}
+
+/// Git backend of an initialized vault, with `origin` pointing at the configured remote
+fn git_sync(config: &Config) -> RpmResult<GitSync> {
+    let git_sync = GitSync::new(&config.passwords_directory_path());
+    if !config.git_sync || !git_sync.is_repo() {
+        return Err(RpmError::Sync("Git sync is not set up - run `rpm sync init <remote>`".to_string()));
+    }
+    let remote = config
+        .git_sync_remote
+        .as_deref()
+        .ok_or_else(|| RpmError::Sync("git_sync_remote is not set".to_string()))?;
+    git_sync.set_remote(remote)?;
+    Ok(git_sync)
+}
+
+/// `rpm native-messaging install <id>`: register the native host for a browser extension.
+/// A Chrome ID is its origin too, so it is allowed to call the API as well
+fn install_native_host(term: &Term, extension_id: &str, config: &Config) -> RpmResult<()> {
+    for path in native_messaging::install(extension_id)? {
+        term.say(Tone::Success, &format!("Wrote {}", path.display()));
+    }
+    if extension_id.contains('@') || extension_id.starts_with('{') {
+        term.say(
+            Tone::Dimmed,
+            "To use the HTTP API, add the extension's moz-extension://<uuid> from about:debugging to server_allowed_origins",
+        );
+        return Ok(());
+    }
+    let origin = server::extension_origin(&format!("chrome-extension://{}", extension_id))?;
+    if !config.server_allowed_origins.contains(&origin) {
+        let mut config = config.clone();
+        config.server_allowed_origins.push(origin.clone());
+        config.save().map_err(|e| RpmError::Config(e.to_string()))?;
+        term.say(Tone::Success, &format!("Allowed {} to call the API", origin));
+    }
+    Ok(())
+}
+
+/// `rpm rotate-key`: re-encrypt the whole vault under a new key derived with a
+/// fresh salt, e.g. after the key may have leaked. The master password stays the same
+fn rotate_key(term: &Term, config: &Config, crypto: CryptoManager) -> RpmResult<()> {
+    if !term.confirm("Re-encrypt the whole vault with a new key?")? {
+        term.say(Tone::Warning, "The key was not changed");
+        return Ok(());
+    }
+    let passwords_dir = config.passwords_directory_path();
+    let mut dir_config = DirectoryConfig::load(&passwords_dir).map_err(|e| RpmError::Config(e.to_string()))?;
+    let (key, mut password) = unlock_keeping_password(term, config, &crypto)?;
+    let result = PasswordStorage::new(config, crypto.clone()).rotate_key(key.as_slice(), &password, &mut dir_config);
+    password.zeroize();
+    let new_key = result?;
+    term.say(Tone::Success, "The vault is encrypted with a new key");
+    // The key saved in the keychain was derived with the old salt
+    if let (true, Some(salt)) = (config.keychain_unlock, &dir_config.encryption_key_salt) {
+        if let Err(e) = keychain::store(&crypto, &passwords_dir, salt, new_key.as_slice()) {
+            term.say(Tone::Warning, &e.to_string());
+        }
+    }
+    Ok(())
+}
+
+/// `rpm verify`: decrypt every entry name and password file and compare the def file
+/// with the files on disk. Fails when anything is wrong, so scripts can check a vault
+fn verify(term: &Term, config: &Config, crypto: CryptoManager) -> RpmResult<()> {
+    let key = unlock(term, config, &crypto)?;
+    let report = PasswordStorage::new(config, crypto).verify(key.as_slice())?;
+    for issue in &report.issues {
+        term.say(Tone::Error, &issue.to_string());
+    }
+    if !report.is_ok() {
+        return Err(RpmError::Integrity(format!(
+            "{} problem(s) in {} entries",
+            report.issues.len(),
+            report.entries_checked
+        )));
+    }
+    term.say(Tone::Success, &format!("All {} entries decrypt and match the files on disk", report.entries_checked));
+    Ok(())
+}
+
+/// `rpm sync`: pull remote changes, then push local ones
+fn sync(term: &Term, config: &Config) -> RpmResult<()> {
+    let git_sync = git_sync(config)?;
+    match git_sync.pull()? {
+        PullOutcome::Conflict(files) => {
+            term.say(Tone::Warning, "Both sides changed these vault files:");
+            for file in files {
+                println!("  {}", file);
+            }
+            term.say(Tone::Dimmed, "Run `rpm sync keep-local` or `rpm sync take-remote`");
+            return Ok(());
+        }
+        PullOutcome::Updated => term.say(Tone::Success, "Pulled remote changes"),
+        PullOutcome::UpToDate => term.say(Tone::Success, "Already up to date"),
+    }
+    git_sync.push()?;
+    term.say(Tone::Success, "Pushed");
+    Ok(())
+}
+
+/// `rpm sync keep-local` / `rpm sync take-remote`: settle a conflict reported by `rpm sync`
+/// after confirming which side is overwritten
+fn sync_resolve(term: &Term, config: &Config, keep_local: bool) -> RpmResult<()> {
+    let git_sync = git_sync(config)?;
+    let question = if keep_local {
+        "Overwrite the remote vault with the local one?"
+    } else {
+        "Replace the local vault with the remote one?"
+    };
+    if !term.confirm(question)? {
+        term.say(Tone::Warning, "Conflict left unresolved");
+        return Ok(());
+    }
+    if keep_local {
+        git_sync.keep_local()?;
+        git_sync.push()?;
+        term.say(Tone::Success, "Kept the local vault and pushed it");
+    } else {
+        git_sync.take_remote()?;
+        term.say(Tone::Success, "Replaced the local vault with the remote one");
+    }
+    Ok(())
+}
+
+/// `rpm sync webdav [keep-local | take-remote]`: mirror the vault to the WebDAV server now,
+/// or settle the conflicts the last run left
+fn webdav_sync(term: &Term, config: &Config, resolve: Option<Side>) -> RpmResult<()> {
+    let webdav = WebDavSync::from_config(config)?
+        .ok_or_else(|| RpmError::Sync("webdav_url is not set".to_string()))?;
+    let question = match resolve {
+        Some(Side::Local) => Some("Overwrite the conflicting files on the server with the local ones?"),
+        Some(Side::Remote) => Some("Replace the conflicting local files with the server's?"),
+        None => None,
+    };
+    if let Some(question) = question {
+        if !term.confirm(question)? {
+            term.say(Tone::Warning, "Conflicts left unresolved");
+            return Ok(());
+        }
+    }
+    let report: SyncReport = match resolve {
+        Some(side) => webdav.resolve(side)?,
+        None => webdav.sync()?,
+    };
+    term.say(
+        Tone::Success,
+        &format!(
+            "Uploaded: {}, downloaded: {}, deleted remotely: {}, deleted locally: {}",
+            report.uploaded, report.downloaded, report.deleted_remote, report.deleted_local
+        ),
+    );
+    if !report.conflicts.is_empty() {
+        term.say(Tone::Warning, "Changed on both sides, left untouched:");
+        for file in &report.conflicts {
+            println!("  {}", file);
+        }
+        term.say(Tone::Dimmed, "Run `rpm sync webdav keep-local` or `rpm sync webdav take-remote`");
+    }
+    Ok(())
+}
+
+/// Ask for the master password and derive the vault key. With `keychain_unlock`
+/// the key saved in the OS keychain is used instead, and a key derived from the
+/// master password is saved there for the next time
+fn unlock(term: &Term, config: &Config, crypto: &CryptoManager) -> RpmResult<SecureKey> {
+    let passwords_dir = config.passwords_directory_path();
+    let salt = if config.keychain_unlock {
+        let dir_config = DirectoryConfig::load(&passwords_dir)
+            .map_err(|e| RpmError::Config(e.to_string()))?;
+        dir_config.policy.check_unlock()?;
+        dir_config.encryption_key_salt
+    } else {
+        None
+    };
+    if let Some(ref salt) = salt {
+        match keychain::load(crypto, &passwords_dir, salt) {
+            Ok(Some(key)) => return Ok(key),
+            Ok(None) => {}
+            Err(e) => term.say(Tone::Warning, &e.to_string()),
+        }
+    }
+
+    let (key, mut password) = unlock_keeping_password(term, config, crypto)?;
+    password.zeroize();
+    if let Some(ref salt) = salt {
+        if let Err(e) = keychain::store(crypto, &passwords_dir, salt, key.as_slice()) {
+            term.say(Tone::Warning, &e.to_string());
+        }
+    }
+    Ok(key)
+}
+
+/// `unlock`, also returning the master password for operations that derive a new key
+fn unlock_keeping_password(term: &Term, config: &Config, crypto: &CryptoManager) -> RpmResult<(SecureKey, String)> {
+    let passwords_dir = config.passwords_directory_path();
+    let dir_config = DirectoryConfig::load(&passwords_dir)
+        .map_err(|e| RpmError::Config(e.to_string()))?;
+    dir_config.policy.check_unlock()?;
+    let (Some(hash), Some(salt)) = (dir_config.master_password_hash, dir_config.encryption_key_salt) else {
+        return Err(RpmError::Config(format!(
+            "No vault in {} - create one in the TUI first",
+            passwords_dir.display()
+        )));
+    };
+
+    let mut password = term.read_master_password()?;
+    if !crypto.verify_password(&password, &hash)? {
+        password.zeroize();
+        return Err(RpmError::AuthenticationFailed);
+    }
+
+    let salt = BASE64_STANDARD_NO_PAD.decode(&salt)
+        .or_else(|_| BASE64_STANDARD.decode(&salt))
+        .map_err(|e| RpmError::Crypto(format!("Invalid salt: {}", e)))?;
+    match dir_config.kdf.kdf().derive_key(&password, &salt) {
+        Ok(key) => Ok((SecureKey::new(key), password)),
+        Err(e) => {
+            password.zeroize();
+            Err(e)
+        }
+    }
+}
@@ -0,0 +1,297 @@
+use crate::config::Config;
+use crate::errors::{RpmError, RpmResult};
+use crate::tui::{get_theme_by_name, ConsoleCaps, Theme};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::style::{Color, Stylize};
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
+use std::fmt::Display;
+use std::io::{self, IsTerminal, Read, Write};
+use std::process::{Command, Stdio};
+use zeroize::{Zeroize, Zeroizing};
+
+/// Program asked for passwords instead of the terminal, e.g. a GUI askpass helper.
+/// It gets the prompt as its only argument and prints the password on stdout.
+pub const ASKPASS_ENV: &str = "RPM_ASKPASS";
+
+/// Where `--password-stdin` / `--password-fd <n>` say the master password comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PasswordSource {
+    Stdin,
+    /// A descriptor the caller opened for us, e.g. a pipe; it is closed after reading
+    Fd(i32),
+}
+
+impl PasswordSource {
+    /// The first line, without the line break. The rest of stdin is left for
+    /// confirmations
+    pub fn read_line(self) -> RpmResult<String> {
+        let line = match self {
+            Self::Stdin => read_line_from(&mut io::stdin().lock())?,
+            Self::Fd(fd) => read_line_from(&mut open_fd(fd)?)?,
+        };
+        let text = String::from_utf8(line.to_vec())
+            .map_err(|_| RpmError::InvalidInput("The master password is not UTF-8".to_string()))?;
+        if text.is_empty() {
+            return Err(RpmError::InvalidInput("No master password was given".to_string()));
+        }
+        Ok(text)
+    }
+}
+
+/// Into a buffer wiped afterwards, sized so that growing it rarely leaves copies behind
+fn read_line_from(reader: &mut impl Read) -> RpmResult<Zeroizing<Vec<u8>>> {
+    let mut line = Zeroizing::new(Vec::with_capacity(256));
+    let mut byte = [0u8; 1];
+    while reader.read(&mut byte)? == 1 && byte[0] != b'\n' {
+        line.push(byte[0]);
+    }
+    if line.last() == Some(&b'\r') {
+        line.pop();
+    }
+    Ok(line)
+}
+
+#[cfg(unix)]
+fn open_fd(fd: i32) -> RpmResult<std::fs::File> {
+    use std::os::unix::io::FromRawFd;
+
+    // SAFETY: fcntl checks the descriptor is open before the File takes it over
+    if fd < 0 || unsafe { libc::fcntl(fd, libc::F_GETFD) } == -1 {
+        return Err(RpmError::InvalidInput(format!("File descriptor {} is not open", fd)));
+    }
+    Ok(unsafe { std::fs::File::from_raw_fd(fd) })
+}
+
+#[cfg(not(unix))]
+fn open_fd(_fd: i32) -> RpmResult<std::fs::File> {
+    Err(RpmError::InvalidInput("--password-fd is only supported on Unix; use --password-stdin".to_string()))
+}
+
+/// What a message is about; each tone has a color of the configured theme
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tone {
+    Accent,
+    Success,
+    Warning,
+    Error,
+    Dimmed,
+}
+
+/// Output and prompts of the command line mode
+pub struct Term {
+    accent: Color,
+    success: Color,
+    warning: Color,
+    error: Color,
+    dimmed: Color,
+    /// Colors only go to a terminal, and not when `NO_COLOR` is set
+    color_stdout: bool,
+    color_stderr: bool,
+    /// `--yes`: confirmations are answered without asking
+    assume_yes: bool,
+    /// `--json`: stdout carries only the result, messages go to stderr
+    json: bool,
+    /// Master password from stdin or a descriptor instead of a prompt
+    password_source: Option<PasswordSource>,
+}
+
+impl Term {
+    /// Colors of the configured theme, or the ANSI palette where 24-bit color is missing
+    pub fn new(config: &Config, assume_yes: bool) -> Self {
+        let theme = if ConsoleCaps::from_mode(&config.console_mode).truecolor {
+            get_theme_by_name(&config.theme)
+        } else {
+            Theme::ansi16()
+        };
+        let color_allowed = std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+            && std::env::var("TERM").map_or(true, |term| term != "dumb");
+        Self {
+            accent: crossterm_color(theme.accent),
+            success: crossterm_color(theme.success),
+            warning: crossterm_color(theme.warning),
+            error: crossterm_color(theme.error),
+            dimmed: crossterm_color(theme.dimmed),
+            color_stdout: color_allowed && io::stdout().is_terminal(),
+            color_stderr: color_allowed && io::stderr().is_terminal(),
+            assume_yes,
+            json: false,
+            password_source: None,
+        }
+    }
+
+    /// Read the master password from `source` instead of asking for it
+    pub fn with_password_source(mut self, source: Option<PasswordSource>) -> Self {
+        self.password_source = source;
+        self
+    }
+
+    /// The master password, from `--password-stdin` / `--password-fd` if given.
+    /// Other passwords (backup passphrases, new entries) are always asked for
+    pub fn read_master_password(&self) -> RpmResult<String> {
+        match self.password_source {
+            Some(source) => source.read_line(),
+            None => self.read_password("Master password:"),
+        }
+    }
+
+    /// Print results as JSON, keeping stdout free of anything else
+    pub fn with_json(mut self, json: bool) -> Self {
+        self.json = json;
+        self
+    }
+
+    pub fn json(&self) -> bool {
+        self.json
+    }
+
+    /// Print a result as one line of JSON
+    pub fn print_json<T: serde::Serialize>(&self, value: &T) -> RpmResult<()> {
+        let text = Zeroizing::new(serde_json::to_string(value)?);
+        println!("{}", text.as_str());
+        Ok(())
+    }
+
+    fn color(&self, tone: Tone) -> Color {
+        match tone {
+            Tone::Accent => self.accent,
+            Tone::Success => self.success,
+            Tone::Warning => self.warning,
+            Tone::Error => self.error,
+            Tone::Dimmed => self.dimmed,
+        }
+    }
+
+    /// `text` in the tone's color when stdout is a terminal, as is otherwise
+    pub fn paint(&self, tone: Tone, text: &str) -> String {
+        if self.color_stdout {
+            text.with(self.color(tone)).to_string()
+        } else {
+            text.to_string()
+        }
+    }
+
+    /// Print a line to stdout in the tone's color
+    pub fn say(&self, tone: Tone, text: &str) {
+        if !self.json {
+            println!("{}", self.paint(tone, text));
+        } else if self.color_stderr {
+            eprintln!("{}", text.with(self.color(tone)));
+        } else {
+            eprintln!("{}", text);
+        }
+    }
+
+    /// Print a failed command's error to stderr
+    pub fn error(&self, error: &dyn Display) {
+        let text = format!("Error: {}", error);
+        if self.color_stderr {
+            eprintln!("{}", text.with(self.error));
+        } else {
+            eprintln!("{}", text);
+        }
+    }
+
+    /// Ask a yes/no question; anything but "y"/"yes" is a no. With `--yes` the answer is yes
+    pub fn confirm(&self, question: &str) -> RpmResult<bool> {
+        print!("{} {} ", self.paint(Tone::Accent, question), self.paint(Tone::Dimmed, "[y/N]"));
+        if self.assume_yes {
+            println!("y");
+            return Ok(true);
+        }
+        io::stdout().flush()?;
+        let mut answer = String::new();
+        io::stdin().read_line(&mut answer)?;
+        Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+    }
+
+    /// Ask for a password: through `RPM_ASKPASS` if it is set, otherwise on the
+    /// terminal with a `*` echoed for each character. The prompt goes to stderr,
+    /// so `rpm get` can be piped
+    pub fn read_password(&self, prompt: &str) -> RpmResult<String> {
+        if let Some(askpass) = std::env::var_os(ASKPASS_ENV).filter(|value| !value.is_empty()) {
+            return askpass_password(&askpass, prompt);
+        }
+
+        if self.color_stderr {
+            eprint!("{} ", prompt.with(self.accent));
+        } else {
+            eprint!("{} ", prompt);
+        }
+        io::stderr().flush()?;
+
+        enable_raw_mode()?;
+        let mut password = String::new();
+        let result = loop {
+            match event::read() {
+                Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => match key.code {
+                    KeyCode::Enter => break Ok(()),
+                    KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        break Err(RpmError::InvalidInput("Cancelled".to_string()));
+                    }
+                    KeyCode::Backspace if password.pop().is_some() => eprint!("\x08 \x08"),
+                    KeyCode::Char(c) => {
+                        password.push(c);
+                        eprint!("*");
+                    }
+                    _ => {}
+                },
+                Ok(_) => {}
+                Err(e) => break Err(RpmError::Io(e)),
+            }
+            let _ = io::stderr().flush();
+        };
+        disable_raw_mode()?;
+        eprintln!();
+
+        match result {
+            Ok(()) => Ok(password),
+            Err(e) => {
+                password.zeroize();
+                Err(e)
+            }
+        }
+    }
+}
+
+/// Theme color (ratatui) as a crossterm color for plain terminal output
+fn crossterm_color(color: ratatui::style::Color) -> Color {
+    use ratatui::style::Color as Ratatui;
+    match color {
+        Ratatui::Reset => Color::Reset,
+        Ratatui::Black => Color::Black,
+        Ratatui::Red => Color::DarkRed,
+        Ratatui::Green => Color::DarkGreen,
+        Ratatui::Yellow => Color::DarkYellow,
+        Ratatui::Blue => Color::DarkBlue,
+        Ratatui::Magenta => Color::DarkMagenta,
+        Ratatui::Cyan => Color::DarkCyan,
+        Ratatui::Gray => Color::Grey,
+        Ratatui::DarkGray => Color::DarkGrey,
+        Ratatui::LightRed => Color::Red,
+        Ratatui::LightGreen => Color::Green,
+        Ratatui::LightYellow => Color::Yellow,
+        Ratatui::LightBlue => Color::Blue,
+        Ratatui::LightMagenta => Color::Magenta,
+        Ratatui::LightCyan => Color::Cyan,
+        Ratatui::White => Color::White,
+        Ratatui::Rgb(r, g, b) => Color::Rgb { r, g, b },
+        Ratatui::Indexed(index) => Color::AnsiValue(index),
+    }
+}
+
+/// Run the askpass helper and take the first line it prints; a non-zero exit means cancelled
+fn askpass_password(askpass: &std::ffi::OsStr, prompt: &str) -> RpmResult<String> {
+    let output = Command::new(askpass)
+        .arg(prompt.trim_end().trim_end_matches(':'))
+        .stdin(Stdio::null())
+        .stderr(Stdio::inherit())
+        .output()
+        .map_err(|e| RpmError::InvalidInput(format!("Cannot run {} ({}): {}", ASKPASS_ENV, askpass.to_string_lossy(), e)))?;
+    let stdout = Zeroizing::new(output.stdout);
+    if !output.status.success() {
+        return Err(RpmError::InvalidInput("Cancelled".to_string()));
+    }
+    let text = std::str::from_utf8(&stdout)
+        .map_err(|_| RpmError::InvalidInput(format!("{} printed a password that is not UTF-8", ASKPASS_ENV)))?;
+    Ok(text.lines().next().unwrap_or_default().to_string())
+}
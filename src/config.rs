@@ -1,35 +1,262 @@
 use anyhow::Result;
-use dirs;
+use crate::errors::{RpmError, RpmResult};
+use crate::crypto::key_derivation::KdfParams;
+use crate::crypto::Cipher;
+use crate::import::ImportField;
+use crate::models::PasswordPolicy;
+use crate::storage::atomic::{write_atomic, write_atomic_private};
+use crate::storage::finish_interrupted_rekey;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub server_port: u16,
+    /// Сколько следующих портов пробовать, если server_port занят (0 = только server_port)
+    #[serde(default = "default_server_fallback_ports")]
+    pub server_fallback_ports: u16,
     pub server_host: String,
+    /// Обслуживать API и по HTTPS с самоподписанным сертификатом (отпечаток виден в настройках)
+    #[serde(default)]
+    pub server_tls: bool,
+    /// Порт HTTPS; если занят, пробуются следующие server_fallback_ports портов
+    #[serde(default = "default_server_tls_port")]
+    pub server_tls_port: u16,
+    /// Оставить HTTP на loopback, когда включен HTTPS (через него работает native messaging хост)
+    #[serde(default = "default_server_http_fallback")]
+    pub server_http_fallback: bool,
+    /// Неверных мастер-паролей подряд через API, после которых расширение ждет api_auth_lockout_seconds;
+    /// до этого пауза удваивается с каждой ошибкой (1, 2, 4... с)
+    #[serde(default = "default_api_auth_max_failures")]
+    pub api_auth_max_failures: u32,
+    /// Окно блокировки в секундах (не больше суток; 0 = без ограничений)
+    #[serde(default = "default_api_auth_lockout")]
+    pub api_auth_lockout_seconds: u64,
+    /// Расширения, которым браузер разрешит обращаться к API: chrome-extension://<id>, moz-extension://<uuid>
+    /// (UUID Firefox виден на about:debugging). Запросы с других страниц отклоняются
+    #[serde(default)]
+    pub server_allowed_origins: Vec<String>,
     pub master_password_hash: Option<String>,
+    /// Шифр новых записей: "aes256-gcm" или "xchacha20-poly1305". Каждый файл записи
+    /// хранит свой шифр, так что смена не мешает читать уже сохраненные записи
     pub encryption_algorithm: String,
     pub passwords_directory: Option<PathBuf>,
     pub encryption_key_salt: Option<String>, // Base64 encoded salt for key derivation
     /// Время хранения пароля в буфере обмена в секундах (0 = не очищать автоматически)
     #[serde(default = "default_clipboard_timeout")]
     pub clipboard_timeout_seconds: u64,
+    /// Копирование через escape-последовательность OSC 52 в буфер обмена терминала пользователя
+    /// (для работы по SSH): "auto" - в SSH-сессии или без системного буфера, "always", "never"
+    #[serde(default = "default_clipboard_osc52")]
+    pub clipboard_osc52: String,
+    /// В Linux (X11/Wayland) класть скопированное и в первичное выделение, чтобы вставлять
+    /// средней кнопкой мыши; очищается по тому же таймауту
+    #[serde(default)]
+    pub clipboard_primary_selection: bool,
+    /// Последовательность автонабора для записей без своей: {USERNAME}, {PASSWORD}, {URL},
+    /// {TAB}, {ENTER}, {DELAY мс}
+    #[serde(default = "default_autotype_sequence")]
+    pub autotype_sequence: String,
+    /// Пауза перед автонабором в миллисекундах, пока окно получает фокус
+    #[serde(default = "default_autotype_delay")]
+    pub autotype_delay_ms: u64,
+    /// Автоблокировка хранилища после бездействия в секундах (0 = не блокировать)
+    #[serde(default = "default_auto_lock")]
+    pub auto_lock_seconds: u64,
+    /// Через сколько секунд бездействия на главном экране стирать из памяти расшифрованные
+    /// имена записей (ключ остается, имена расшифровываются заново при нажатии клавиши; 0 = не стирать)
+    #[serde(default = "default_metadata_scrub")]
+    pub metadata_scrub_seconds: u64,
+    /// Сколько миллисекунд должно занимать получение ключа в новом хранилище: параметры
+    /// Argon2 подбираются под этот компьютер при создании (0 = параметры по умолчанию)
+    #[serde(default = "default_kdf_calibration")]
+    pub kdf_calibration_ms: u64,
+    /// Разблокировка через связку ключей ОС: ключ хранилища после ввода мастер-пароля
+    /// сохраняется в ней (зашифрованным), и при следующем запуске пароль не спрашивается
+    #[serde(default)]
+    pub keychain_unlock: bool,
     /// Выбранная тема TUI: "textual_dark", "vscode_style", "opencode_style"
     #[serde(default = "default_theme")]
     pub theme: String,
+    /// Режим консоли: "auto" - определить по терминалу, "full" - 24-битные цвета и Unicode,
+    /// "compat" - 16 цветов ANSI и ASCII-символы (классическая консоль Windows)
+    #[serde(default = "default_console_mode")]
+    pub console_mode: String,
+    /// Режим для программ чтения с экрана: без рамок, название экрана и поля в первой строке
+    #[serde(default)]
+    pub screen_reader: bool,
+    /// Сколько дней удаленные записи хранятся в корзине; 0 - хранить, пока не удалят вручную
+    #[serde(default = "default_trash_retention")]
+    pub trash_retention_days: u64,
     /// Выбранный язык интерфейса: "ru", "en", "zh"
     #[serde(default = "default_language")]
     pub language: String,
+    /// Формат даты и времени (strftime, например "%d.%m.%Y %H:%M"); по умолчанию - формат языка
+    #[serde(default)]
+    pub datetime_format: Option<String>,
+    /// Часовой пояс для отображения времени: "local" или "utc"
+    #[serde(default = "default_display_timezone")]
+    pub display_timezone: String,
+    /// Сколько дней хранить старые пароли в истории записи (0 = бессрочно)
+    #[serde(default = "default_history_retention")]
+    pub history_retention_days: u64,
+    /// Путь к локальной копии базы HIBP (SHA-1, ordered by hash) для офлайн-проверки утечек
+    #[serde(default)]
+    pub hibp_dataset_path: Option<PathBuf>,
+    /// Проверять утечки онлайн через HIBP range API, если офлайн-база не указана.
+    /// Отправляются только первые 5 символов SHA-1 пароля (k-анонимность)
+    #[serde(default)]
+    pub hibp_online: bool,
+    /// Расписание автоматического экспорта в формате cron ("0 3 * * 0" - по воскресеньям в 3:00)
+    #[serde(default)]
+    pub export_schedule: Option<String>,
+    /// Получатель age (age1...), на чей ключ шифруется экспорт
+    #[serde(default)]
+    pub export_recipient: Option<String>,
+    /// Каталог для зашифрованных снимков хранилища
+    #[serde(default)]
+    pub export_directory: Option<PathBuf>,
+    /// Команда для отправки снимка на удаленный ресурс; путь к файлу добавляется последним аргументом.
+    /// Разбирается по правилам оболочки: аргумент в кавычках может содержать пробелы
+    #[serde(default)]
+    pub export_upload_command: Option<String>,
+    /// Время последнего успешного экспорта
+    #[serde(default)]
+    pub export_last_run: Option<DateTime<Utc>>,
+    /// Каталог резервных копий (по умолчанию - backups в каталоге данных)
+    #[serde(default)]
+    pub backup_directory: Option<PathBuf>,
+    /// Интервал автоматических резервных копий в часах (0 = выключено)
+    #[serde(default)]
+    pub backup_interval_hours: u64,
+    /// Сколько автоматических копий хранить (старые удаляются)
+    #[serde(default = "default_backup_keep_count")]
+    pub backup_keep_count: usize,
+    /// Автоматически коммитить каждое изменение хранилища в git-репозиторий каталога паролей
+    #[serde(default)]
+    pub git_sync: bool,
+    /// URL удаленного git-репозитория для синхронизации (push/pull)
+    #[serde(default)]
+    pub git_sync_remote: Option<String>,
+    /// URL коллекции WebDAV (Nextcloud/ownCloud), куда зеркалируются зашифрованные файлы хранилища
+    #[serde(default)]
+    pub webdav_url: Option<String>,
+    #[serde(default)]
+    pub webdav_username: Option<String>,
+    /// Пароль WebDAV; лучше использовать отдельный пароль приложения
+    #[serde(default)]
+    pub webdav_password: Option<String>,
+    /// Конфликты (файл изменен с обеих сторон): "manual" или "last_writer_wins"
+    #[serde(default = "default_webdav_conflict_strategy")]
+    pub webdav_conflict_strategy: String,
+    /// Интервал получения изменений с сервера в минутах (0 = только при локальных изменениях)
+    #[serde(default = "default_webdav_pull_minutes")]
+    pub webdav_pull_minutes: u64,
+    /// URL вебхука (Slack, ntfy и т.п.), куда отправляются события из webhook_events
+    #[serde(default)]
+    pub webhook_url: Option<String>,
+    /// Секрет для подписи тела запроса HMAC-SHA256 (заголовок X-RPM-Signature); без него запросы не подписываются.
+    /// Хранится открытым текстом, поэтому config.toml доступен только владельцу
+    #[serde(default)]
+    pub webhook_secret: Option<String>,
+    /// События для вебхука: "client_unlock" - первая разблокировка новым расширением,
+    /// "export" - выполнен экспорт, "breach" - аудит нашел утекшие пароли
+    #[serde(default = "default_webhook_events")]
+    pub webhook_events: Vec<String>,
+    /// Сопоставления столбцов CSV, сохраненные после импорта; ключ - строка заголовков
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub import_mappings: BTreeMap<String, Vec<ImportField>>,
+    /// Именованные настройки генератора паролей (пресеты), выбираются на экране генератора
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub generator_presets: BTreeMap<String, PasswordPolicy>,
+}
+
+fn default_metadata_scrub() -> u64 {
+    60 // 1 минута по умолчанию
+}
+
+fn default_kdf_calibration() -> u64 {
+    500
 }
 
 fn default_theme() -> String {
     "textual_dark".to_string()
 }
 
+fn default_console_mode() -> String {
+    "auto".to_string()
+}
+
+fn default_trash_retention() -> u64 {
+    30 // 30 дней по умолчанию
+}
+
+fn default_server_fallback_ports() -> u16 {
+    10
+}
+
+fn default_server_tls_port() -> u16 {
+    8764
+}
+
+fn default_server_http_fallback() -> bool {
+    true
+}
+
+fn default_api_auth_max_failures() -> u32 {
+    5
+}
+
+fn default_api_auth_lockout() -> u64 {
+    300 // 5 минут
+}
+
 fn default_clipboard_timeout() -> u64 {
     30 // 30 секунд по умолчанию
 }
 
+fn default_clipboard_osc52() -> String {
+    "auto".to_string()
+}
+
+fn default_autotype_sequence() -> String {
+    "{USERNAME}{TAB}{PASSWORD}{ENTER}".to_string()
+}
+
+fn default_autotype_delay() -> u64 {
+    500
+}
+
+fn default_auto_lock() -> u64 {
+    300 // 5 минут по умолчанию
+}
+
+fn default_history_retention() -> u64 {
+    365
+}
+
+fn default_backup_keep_count() -> usize {
+    10
+}
+
+fn default_webdav_conflict_strategy() -> String {
+    "manual".to_string()
+}
+
+fn default_webdav_pull_minutes() -> u64 {
+    15
+}
+
+fn default_webhook_events() -> Vec<String> {
+    crate::webhook::EVENTS.iter().map(|event| event.to_string()).collect()
+}
+
+fn default_display_timezone() -> String {
+    "local".to_string()
+}
+
 fn default_language() -> String {
     "en".to_string()
 }
@@ -38,14 +265,57 @@ impl Default for Config {
     fn default() -> Self {
         Self {
             server_port: 8765,
+            server_fallback_ports: default_server_fallback_ports(),
             server_host: "127.0.0.1".to_string(),
+            server_tls: false,
+            server_tls_port: default_server_tls_port(),
+            server_http_fallback: default_server_http_fallback(),
+            api_auth_max_failures: default_api_auth_max_failures(),
+            api_auth_lockout_seconds: default_api_auth_lockout(),
+            server_allowed_origins: Vec::new(),
             master_password_hash: None,
             encryption_algorithm: "aes256-gcm".to_string(),
             passwords_directory: None,
             encryption_key_salt: None,
             clipboard_timeout_seconds: default_clipboard_timeout(),
+            clipboard_osc52: default_clipboard_osc52(),
+            clipboard_primary_selection: false,
+            autotype_sequence: default_autotype_sequence(),
+            autotype_delay_ms: default_autotype_delay(),
+            auto_lock_seconds: default_auto_lock(),
+            metadata_scrub_seconds: default_metadata_scrub(),
+            kdf_calibration_ms: default_kdf_calibration(),
+            keychain_unlock: false,
             theme: default_theme(),
+            console_mode: default_console_mode(),
+            screen_reader: false,
+            trash_retention_days: default_trash_retention(),
             language: default_language(),
+            datetime_format: None,
+            display_timezone: default_display_timezone(),
+            history_retention_days: default_history_retention(),
+            hibp_dataset_path: None,
+            hibp_online: false,
+            export_schedule: None,
+            export_recipient: None,
+            export_directory: None,
+            export_upload_command: None,
+            export_last_run: None,
+            backup_directory: None,
+            backup_interval_hours: 0,
+            backup_keep_count: default_backup_keep_count(),
+            git_sync: false,
+            git_sync_remote: None,
+            webdav_url: None,
+            webdav_username: None,
+            webdav_password: None,
+            webdav_conflict_strategy: default_webdav_conflict_strategy(),
+            webdav_pull_minutes: default_webdav_pull_minutes(),
+            webhook_url: None,
+            webhook_secret: None,
+            webhook_events: default_webhook_events(),
+            import_mappings: BTreeMap::new(),
+            generator_presets: BTreeMap::new(),
         }
     }
 }
@@ -55,6 +325,7 @@ impl Config {
         let config_path = Self::config_path()?;
         
         if config_path.exists() {
+            restrict_to_owner(&config_path)?;
             let content = std::fs::read_to_string(&config_path)?;
             let config: Config = toml::from_str(&content)?;
             Ok(config)
@@ -71,7 +342,8 @@ impl Config {
             std::fs::create_dir_all(parent)?;
         }
         let content = toml::to_string_pretty(self)?;
-        std::fs::write(&config_path, content)?;
+        // Пароль WebDAV и секрет вебхука лежат открытым текстом: файл читает только владелец
+        write_atomic_private(&config_path, content)?;
         Ok(())
     }
 
@@ -82,6 +354,11 @@ impl Config {
         Ok(config_dir.join("config.toml"))
     }
 
+    /// Шифр из encryption_algorithm; ошибка, если такого шифра нет
+    pub fn cipher(&self) -> RpmResult<Cipher> {
+        Cipher::from_name(&self.encryption_algorithm)
+    }
+
     /// Получить путь к директории с паролями
     /// Если не задана в конфиге, возвращает дефолтную директорию данных
     pub fn passwords_directory_path(&self) -> PathBuf {
@@ -93,28 +370,204 @@ impl Config {
         })
     }
 
+    /// Получить путь к каталогу резервных копий
+    /// Если не задан в конфиге, возвращает `backups` в каталоге данных
+    pub fn backup_directory_path(&self) -> PathBuf {
+        self.backup_directory.clone().unwrap_or_else(|| {
+            dirs::data_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("rpm")
+                .join("backups")
+        })
+    }
+
     /// Получить путь к файлу конфигурации
     pub fn config_file_path(&self) -> Result<PathBuf> {
         Self::config_path()
     }
+
+    /// Файл с портом запущенного API (рядом с конфигом): по нему расширение находит сервер,
+    /// если основной порт был занят
+    pub fn runtime_file_path() -> Result<PathBuf> {
+        Ok(Self::config_path()?.with_file_name("server.json"))
+    }
+
+    /// Ключ, которым зашифрован ключ хранилища в связке ключей ОС (рядом с конфигом)
+    pub fn keychain_key_path() -> Result<PathBuf> {
+        Ok(Self::config_path()?.with_file_name("keychain.key"))
+    }
+
+    /// Самоподписанный сертификат HTTPS API и его ключ (рядом с конфигом)
+    pub fn tls_certificate_path() -> Result<PathBuf> {
+        Ok(Self::config_path()?.with_file_name("server-cert.pem"))
+    }
+
+    pub fn tls_key_path() -> Result<PathBuf> {
+        Ok(Self::config_path()?.with_file_name("server-key.pem"))
+    }
+
+    /// Сопряжённые расширения браузера (рядом с конфигом); секреты хранятся только в виде хешей
+    pub fn paired_clients_path() -> Result<PathBuf> {
+        Ok(Self::config_path()?.with_file_name("paired_clients.json"))
+    }
+
+    /// Скрипт запуска native messaging хоста (рядом с конфигом): браузер запускает
+    /// программу из манифеста без наших аргументов
+    pub fn native_host_launcher_path() -> Result<PathBuf> {
+        Ok(Self::config_path()?.with_file_name("native-messaging-host.sh"))
+    }
+
+    /// Файл блокировки запущенного экземпляра (PID основного процесса)
+    pub fn instance_lock_path() -> Result<PathBuf> {
+        Ok(Self::config_path()?.with_file_name("instance.lock"))
+    }
+
+    /// Сокет, через который повторный запуск передает запрос основному экземпляру
+    pub fn instance_socket_path() -> Result<PathBuf> {
+        Ok(Self::config_path()?.with_file_name("instance.sock"))
+    }
 }
 
 /// Конфигурация директории с паролями
 /// Хранится в файле `.rpm_config` внутри директории
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct DirectoryConfig {
     pub master_password_hash: Option<String>,
     pub encryption_key_salt: Option<String>, // Base64 encoded salt for key derivation
+    /// Идентификатор хранилища для водяного знака экспорта; создается при первом экспорте
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vault_id: Option<String>,
+    /// Ограничения, заданные администратором хранилища (общие для всей команды)
+    #[serde(default, skip_serializing_if = "VaultPolicy::is_unrestricted")]
+    pub policy: VaultPolicy,
+    /// Функция получения ключа из мастер-пароля и ее параметры; не записывается
+    /// для Argon2id с параметрами по умолчанию. Меняется вместе с мастер-паролем
+    #[serde(default, skip_serializing_if = "KdfParams::is_default")]
+    pub kdf: KdfParams,
+    /// Подсказка к мастер-паролю, написанная пользователем. Хранится открытым текстом
+    /// и показывается любому, кто дважды ошибся при вводе, поэтому не должна быть секретом
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password_hint: Option<String>,
+}
+
+/// Выдает ли подсказка сам мастер-пароль (без учета регистра и пробелов)
+pub fn hint_reveals_password(hint: &str, password: &str) -> bool {
+    let normalize = |value: &str| -> String { value.chars().filter(|c| !c.is_whitespace()).flat_map(char::to_lowercase).collect() };
+    let password = normalize(password);
+    !password.is_empty() && normalize(hint).contains(&password)
+}
+
+/// Политика хранилища для командных хранилищ: секция `[policy]` в `.rpm_config`.
+/// Ее соблюдают TUI, CLI и HTTP API; пустая политика ничего не ограничивает.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VaultPolicy {
+    /// Минимальная длина мастер-пароля в символах
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_master_password_length: Option<usize>,
+    /// Требовать файл-ключ. Файлы-ключи пока не поддерживаются, поэтому такое
+    /// хранилище не открывается вовсе, а не открывается без файла-ключа
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub require_keyfile: bool,
+    /// Предел автоблокировки в секундах: большее значение и 0 (не блокировать) заменяются им
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_auto_lock_seconds: Option<u64>,
+    /// Предел времени хранения пароля в буфере обмена в секундах (так же, как автоблокировка)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_clipboard_timeout_seconds: Option<u64>,
+    /// Запретить экспорт без шифрования. Плановый экспорт (age) и резервные копии зашифрованы
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub disallow_plaintext_export: bool,
+    /// Запретить выгрузку всего хранилища (плановый экспорт и `rpm export`)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub disallow_export: bool,
+}
+
+impl VaultPolicy {
+    /// Политика ничего не ограничивает
+    pub fn is_unrestricted(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Проверить новый мастер-пароль
+    pub fn check_master_password(&self, password: &str) -> RpmResult<()> {
+        match self.min_master_password_length {
+            Some(min) if password.chars().count() < min => Err(RpmError::Policy(format!(
+                "master password must be at least {} characters long",
+                min
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Можно ли открыть хранилище этой версией программы
+    pub fn check_unlock(&self) -> RpmResult<()> {
+        if self.require_keyfile {
+            return Err(RpmError::Policy("a key file is required, which this version does not support".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Можно ли выгрузить данные хранилища в таком виде
+    pub fn check_export(&self, encrypted: bool) -> RpmResult<()> {
+        if self.disallow_export {
+            return Err(RpmError::Policy("export is not allowed".to_string()));
+        }
+        if self.disallow_plaintext_export && !encrypted {
+            return Err(RpmError::Policy("plaintext export is not allowed".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Время автоблокировки с учетом предела политики
+    pub fn auto_lock_seconds(&self, configured: u64) -> u64 {
+        cap_timeout(configured, self.max_auto_lock_seconds)
+    }
+
+    /// Время хранения в буфере обмена с учетом предела политики
+    pub fn clipboard_timeout_seconds(&self, configured: u64) -> u64 {
+        cap_timeout(configured, self.max_clipboard_timeout_seconds)
+    }
+}
+
+/// Закрыть файл от других пользователей, если он записан версией, не ограничивавшей права:
+/// в нем могут лежать секрет вебхука и пароль WebDAV
+fn restrict_to_owner(path: &Path) -> Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(path)?.permissions().mode();
+        if mode & 0o077 != 0 {
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode & 0o700))?;
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
+
+/// Таймаут, где 0 означает "никогда", ограниченный сверху `max`
+fn cap_timeout(configured: u64, max: Option<u64>) -> u64 {
+    match max {
+        Some(max) if configured == 0 || configured > max => max,
+        _ => configured,
+    }
 }
 
 impl DirectoryConfig {
     /// Путь к файлу конфигурации директории
-    fn config_path(directory: &Path) -> PathBuf {
+    pub(crate) fn config_path(directory: &Path) -> PathBuf {
         directory.join(".rpm_config")
     }
 
+    /// Содержимое файла конфигурации директории
+    pub(crate) fn to_toml(&self) -> Result<String> {
+        Ok(toml::to_string_pretty(self)?)
+    }
+
     /// Загрузить конфигурацию директории
     pub fn load(directory: &Path) -> Result<Self> {
+        // Прерванное перешифрование могло оставить новую соль в `.rpm_config.rekey`
+        finish_interrupted_rekey(directory)?;
         let config_path = Self::config_path(directory);
         
         if config_path.exists() {
@@ -123,10 +576,7 @@ impl DirectoryConfig {
             Ok(config)
         } else {
             // Возвращаем пустую конфигурацию, если файл не существует
-            Ok(DirectoryConfig {
-                master_password_hash: None,
-                encryption_key_salt: None,
-            })
+            Ok(DirectoryConfig::default())
         }
     }
 
@@ -135,9 +585,9 @@ impl DirectoryConfig {
         // Убеждаемся, что директория существует
         std::fs::create_dir_all(directory)?;
         
-        let config_path = Self::config_path(directory);
-        let content = toml::to_string_pretty(self)?;
-        std::fs::write(&config_path, content)?;
+        // Пишем во временный файл, сбрасываем на диск и переименовываем, чтобы хэш
+        // и соль мастер-пароля никогда не оказались записаны наполовину
+        write_atomic(&Self::config_path(directory), self.to_toml()?)?;
         Ok(())
     }
 
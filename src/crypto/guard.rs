@@ -0,0 +1,136 @@
+//! Runtime guards for the randomness AES-GCM depends on.
+//!
+//! A repeated nonce under the same key breaks AES-GCM completely (it leaks the XOR of
+//! the plaintexts and the authentication key), and with 96-bit random nonces a
+//! repeat only happens when the OS random generator is broken. The guard checks
+//! the generator once at startup and remembers recently issued nonces per key in a
+//! small bloom filter. A bloom filter can report a nonce it has not seen, so a hit
+//! only makes the caller draw a new nonce; a generator that keeps returning seen
+//! nonces is treated as broken.
+
+use crate::errors::{RpmError, RpmResult};
+use aes_gcm::aead::rand_core::{OsRng, RngCore};
+use sha2::{Digest, Sha256};
+use std::collections::VecDeque;
+
+/// Bits per filter (32 KiB) and hash functions per nonce
+const FILTER_BITS: usize = 1 << 18;
+const HASHES: u64 = 7;
+/// Nonces a filter takes before it is cleared: keeps false positives below 1 in 10^4
+const FILTER_CAPACITY: usize = 10_000;
+/// Keys tracked at once; the least recently used one is forgotten
+const MAX_KEYS: usize = 4;
+/// Fresh nonces drawn after a hit before the generator is considered broken
+pub const NONCE_ATTEMPTS: usize = 3;
+
+/// Check that the OS random generator works and does not return constant output
+pub fn check_rng() -> RpmResult<()> {
+    let mut first = [0u8; 32];
+    let mut second = [0u8; 32];
+    OsRng
+        .try_fill_bytes(&mut first)
+        .and_then(|_| OsRng.try_fill_bytes(&mut second))
+        .map_err(|e| RpmError::Crypto(format!("OS random generator unavailable: {}", e)))?;
+    check_samples(&first, &second)
+}
+
+/// The test of `check_rng` on two samples of the generator's output
+pub fn check_samples(first: &[u8; 32], second: &[u8; 32]) -> RpmResult<()> {
+    if *first == [0u8; 32] || first == second {
+        return Err(RpmError::Crypto("OS random generator returned constant output".to_string()));
+    }
+    Ok(())
+}
+
+struct NonceFilter {
+    key_id: [u8; 8],
+    bits: Vec<u64>,
+    len: usize,
+}
+
+impl NonceFilter {
+    fn new(key_id: [u8; 8]) -> Self {
+        Self { key_id, bits: vec![0; FILTER_BITS / 64], len: 0 }
+    }
+
+    /// Bit positions of a nonce (double hashing over its random bytes)
+    fn positions(nonce: &[u8]) -> impl Iterator<Item = usize> {
+        let digest = Sha256::digest(nonce);
+        let h1 = u64::from_le_bytes(digest[..8].try_into().unwrap_or_default());
+        let h2 = u64::from_le_bytes(digest[8..16].try_into().unwrap_or_default()) | 1;
+        (0..HASHES).map(move |i| (h1.wrapping_add(i.wrapping_mul(h2)) % FILTER_BITS as u64) as usize)
+    }
+
+    fn contains(&self, nonce: &[u8]) -> bool {
+        Self::positions(nonce).all(|bit| self.bits[bit / 64] & (1 << (bit % 64)) != 0)
+    }
+
+    fn insert(&mut self, nonce: &[u8]) {
+        if self.len >= FILTER_CAPACITY {
+            self.bits.fill(0);
+            self.len = 0;
+        }
+        for bit in Self::positions(nonce) {
+            self.bits[bit / 64] |= 1 << (bit % 64);
+        }
+        self.len += 1;
+    }
+}
+
+/// Recently issued nonces of the last few keys
+#[derive(Default)]
+pub struct NonceGuard {
+    filters: VecDeque<NonceFilter>,
+}
+
+impl NonceGuard {
+    /// Record a nonce about to be used with `key`. Returns `false` if it may have been
+    /// issued before, in which case the caller must draw another one.
+    pub fn register(&mut self, key: &[u8], nonce: &[u8]) -> bool {
+        if nonce.iter().all(|&b| b == 0) {
+            return false;
+        }
+        let key_id = key_id(key);
+        let filter = match self.filters.iter().position(|f| f.key_id == key_id) {
+            Some(index) => {
+                // Most recently used key goes to the front
+                let filter = self.filters.remove(index).unwrap_or_else(|| NonceFilter::new(key_id));
+                self.filters.push_front(filter);
+                &mut self.filters[0]
+            }
+            None => {
+                self.filters.truncate(MAX_KEYS - 1);
+                self.filters.push_front(NonceFilter::new(key_id));
+                &mut self.filters[0]
+            }
+        };
+        if filter.contains(nonce) {
+            return false;
+        }
+        filter.insert(nonce);
+        true
+    }
+}
+
+/// A nonce from `draw` that `guard` has not seen under `key`, drawing up to
+/// `NONCE_ATTEMPTS` times; an error if the generator keeps repeating itself
+pub fn draw_nonce<N: AsRef<[u8]>>(guard: &mut NonceGuard, key: &[u8], mut draw: impl FnMut() -> N) -> RpmResult<N> {
+    for _ in 0..NONCE_ATTEMPTS {
+        let nonce = draw();
+        if guard.register(key, nonce.as_ref()) {
+            return Ok(nonce);
+        }
+    }
+    Err(RpmError::Crypto("Nonce reuse detected: the OS random generator is not working".to_string()))
+}
+
+/// Identifies a key without keeping it (or anything it could be recovered from)
+fn key_id(key: &[u8]) -> [u8; 8] {
+    let mut hasher = Sha256::new();
+    hasher.update(b"rpm-nonce-guard-v1");
+    hasher.update(key);
+    let digest = hasher.finalize();
+    let mut id = [0u8; 8];
+    id.copy_from_slice(&digest[..8]);
+    id
+}
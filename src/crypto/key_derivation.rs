@@ -1,27 +1,268 @@
 use crate::errors::{RpmError, RpmResult};
-use argon2::Argon2;
+use argon2::{Algorithm, Argon2, Params, Version};
 use argon2::password_hash::{rand_core::OsRng, SaltString};
 use base64::engine::general_purpose::STANDARD_NO_PAD as BASE64_STANDARD_NO_PAD;
 use base64::Engine;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::time::{Duration, Instant};
+use tracing::{info, instrument};
 
-/// Derive a 32-byte encryption key from a password using Argon2id
+/// Most memory a vault may ask Argon2id for, so a mistyped setting can't make
+/// unlocking exhaust the machine
+pub const MAX_ARGON2_MEMORY_KIB: u32 = 4 * 1024 * 1024;
+
+/// Most Argon2id passes; calibration stays far below it on any machine
+pub const MAX_ARGON2_ITERATIONS: u32 = 256;
+
+/// Most Argon2id lanes
+pub const MAX_ARGON2_PARALLELISM: u32 = 16;
+
+/// Derive a 32-byte encryption key from a password using Argon2id with the default parameters
+#[instrument(level = "debug", skip_all)]
 pub fn derive_key(password: &str, salt: Option<&[u8]>) -> RpmResult<Vec<u8>> {
-    // Use Argon2id for key derivation
-    let salt_string = if let Some(salt) = salt {
-        // Convert bytes to base64 string for SaltString (without padding to avoid '=' character)
-        let salt_b64 = BASE64_STANDARD_NO_PAD.encode(salt);
-        SaltString::from_b64(&salt_b64)
-            .map_err(|e| RpmError::Crypto(format!("Invalid salt: {}", e)))?
-    } else {
-        SaltString::generate(&mut OsRng)
-    };
+    Argon2id::default().derive(password, salt)
+}
+
+/// A password-based key derivation function producing the 32-byte vault key
+pub trait Kdf {
+    fn derive_key(&self, password: &str, salt: &[u8]) -> RpmResult<Vec<u8>>;
+}
+
+/// Argon2id: the KDF of every vault rpm creates. Vaults from before the
+/// parameters were stored use the defaults of the argon2 crate (19 MiB, 2 passes, 1 lane)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Argon2id {
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+impl Default for Argon2id {
+    fn default() -> Self {
+        Self {
+            memory_kib: Params::DEFAULT_M_COST,
+            iterations: Params::DEFAULT_T_COST,
+            parallelism: Params::DEFAULT_P_COST,
+        }
+    }
+}
+
+impl Argon2id {
+    /// The parameters as argon2 takes them; an error if it rejects them or they
+    /// exceed `MAX_ARGON2_MEMORY_KIB`, `MAX_ARGON2_ITERATIONS` or `MAX_ARGON2_PARALLELISM`
+    pub fn params(&self) -> RpmResult<Params> {
+        if self.memory_kib > MAX_ARGON2_MEMORY_KIB {
+            return Err(RpmError::Crypto(format!(
+                "Argon2 memory is limited to {} MiB",
+                MAX_ARGON2_MEMORY_KIB / 1024
+            )));
+        }
+        if self.iterations > MAX_ARGON2_ITERATIONS {
+            return Err(RpmError::Crypto(format!(
+                "Argon2 passes are limited to {}",
+                MAX_ARGON2_ITERATIONS
+            )));
+        }
+        if self.parallelism > MAX_ARGON2_PARALLELISM {
+            return Err(RpmError::Crypto(format!(
+                "Argon2 parallelism is limited to {}",
+                MAX_ARGON2_PARALLELISM
+            )));
+        }
+        Params::new(self.memory_kib, self.iterations, self.parallelism, None)
+            .map_err(|e| RpmError::Crypto(format!("Invalid Argon2 parameters: {}", e)))
+    }
+
+    /// Derive with a random salt when `salt` is `None`
+    fn derive(&self, password: &str, salt: Option<&[u8]>) -> RpmResult<Vec<u8>> {
+        let salt_string = if let Some(salt) = salt {
+            // Convert bytes to base64 string for SaltString (without padding to avoid '=' character).
+            // The encoded string is the Argon2 salt, as it has always been
+            let salt_b64 = BASE64_STANDARD_NO_PAD.encode(salt);
+            SaltString::from_b64(&salt_b64)
+                .map_err(|e| RpmError::Crypto(format!("Invalid salt: {}", e)))?
+        } else {
+            SaltString::generate(&mut OsRng)
+        };
+
+        let argon2 = Argon2::new(Algorithm::Argon2id, Version::V0x13, self.params()?);
+        let mut output_key_material = [0u8; 32];
+        argon2
+            .hash_password_into(password.as_bytes(), salt_string.as_salt().as_str().as_bytes(), &mut output_key_material)
+            .map_err(|e| RpmError::Crypto(format!("Key derivation failed: {}", e)))?;
+
+        Ok(output_key_material.to_vec())
+    }
+
+    /// How long one key derivation takes with these parameters
+    fn time_derivation(&self) -> RpmResult<Duration> {
+        let started = Instant::now();
+        self.derive("calibration", Some(&[0u8; 32]))?;
+        Ok(started.elapsed().max(Duration::from_millis(1)))
+    }
+}
+
+/// Most memory calibration picks; beyond it, passes are added instead
+pub const CALIBRATION_MAX_MEMORY_KIB: u32 = 1024 * 1024;
+
+/// Argon2id parameters that take about `target` to derive a key on this machine.
+/// Memory grows first, as it is what makes guessing on GPUs expensive, then
+/// passes once memory is at `CALIBRATION_MAX_MEMORY_KIB`. Never weaker than the defaults
+#[instrument(level = "info", skip_all, fields(target_ms = target.as_millis() as u64))]
+pub fn calibrate(target: Duration) -> RpmResult<Argon2id> {
+    let mut argon2 = Argon2id::default();
+    let elapsed = argon2.time_derivation()?;
+    if elapsed >= target {
+        return Ok(argon2);
+    }
+
+    // Time grows about linearly with memory; whole MiB keep the setting readable
+    let scale = target.as_secs_f64() / elapsed.as_secs_f64();
+    let memory_kib = (f64::from(argon2.memory_kib) * scale).min(f64::from(CALIBRATION_MAX_MEMORY_KIB)) as u32;
+    argon2.memory_kib = (memory_kib / 1024 * 1024).max(argon2.memory_kib);
+
+    let elapsed = argon2.time_derivation()?;
+    if elapsed < target {
+        let scale = target.as_secs_f64() / elapsed.as_secs_f64();
+        let iterations = (f64::from(argon2.iterations) * scale).min(f64::from(MAX_ARGON2_ITERATIONS)) as u32;
+        argon2.iterations = iterations.max(argon2.iterations);
+    }
+    info!(memory_kib = argon2.memory_kib, iterations = argon2.iterations, "Argon2 calibrated");
+    Ok(argon2)
+}
 
-    let argon2 = Argon2::default();
-    let mut output_key_material = [0u8; 32];
-    argon2
-        .hash_password_into(password.as_bytes(), salt_string.as_salt().as_str().as_bytes(), &mut output_key_material)
-        .map_err(|e| RpmError::Crypto(format!("Key derivation failed: {}", e)))?;
+impl Kdf for Argon2id {
+    #[instrument(level = "debug", skip_all, fields(memory_kib = self.memory_kib, iterations = self.iterations))]
+    fn derive_key(&self, password: &str, salt: &[u8]) -> RpmResult<Vec<u8>> {
+        self.derive(password, Some(salt))
+    }
+}
+
+/// Most memory scrypt may ask for (128 * r * N bytes), as for Argon2id
+pub const MAX_SCRYPT_MEMORY_KIB: u64 = MAX_ARGON2_MEMORY_KIB as u64;
+
+/// Most scrypt lanes; each one repeats the whole memory-hard pass
+pub const MAX_SCRYPT_PARALLELISM: u32 = 16;
+
+/// Most PBKDF2 iterations, several seconds of work: far beyond any exporter's setting
+pub const MAX_PBKDF2_ITERATIONS: u32 = 10_000_000;
+
+/// scrypt with N = 2^log_n
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Scrypt {
+    pub log_n: u8,
+    pub r: u32,
+    pub p: u32,
+}
 
-    Ok(output_key_material.to_vec())
+impl Scrypt {
+    /// An error if the parameters exceed `MAX_SCRYPT_MEMORY_KIB` or `MAX_SCRYPT_PARALLELISM`,
+    /// so a tampered directory config can't make unlocking hang or exhaust memory
+    pub fn validate(&self) -> RpmResult<()> {
+        let memory_kib = 1u64
+            .checked_shl(u32::from(self.log_n))
+            .and_then(|n| n.checked_mul(128 * u64::from(self.r)))
+            .map(|bytes| bytes / 1024);
+        if memory_kib.is_none_or(|kib| kib > MAX_SCRYPT_MEMORY_KIB) {
+            return Err(RpmError::Crypto(format!(
+                "scrypt memory is limited to {} MiB",
+                MAX_SCRYPT_MEMORY_KIB / 1024
+            )));
+        }
+        if self.p > MAX_SCRYPT_PARALLELISM {
+            return Err(RpmError::Crypto(format!(
+                "scrypt parallelism is limited to {}",
+                MAX_SCRYPT_PARALLELISM
+            )));
+        }
+        Ok(())
+    }
 }
 
+impl Kdf for Scrypt {
+    #[instrument(level = "debug", skip_all)]
+    fn derive_key(&self, password: &str, salt: &[u8]) -> RpmResult<Vec<u8>> {
+        self.validate()?;
+        let params = scrypt::Params::new(self.log_n, self.r, self.p, 32)
+            .map_err(|e| RpmError::Crypto(format!("Invalid scrypt parameters: {}", e)))?;
+        let mut key = vec![0u8; 32];
+        scrypt::scrypt(password.as_bytes(), salt, &params, &mut key)
+            .map_err(|e| RpmError::Crypto(format!("Key derivation failed: {}", e)))?;
+        Ok(key)
+    }
+}
+
+/// PBKDF2-HMAC-SHA256
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Pbkdf2Sha256 {
+    pub iterations: u32,
+}
+
+impl Pbkdf2Sha256 {
+    /// An error for zero iterations or more than `MAX_PBKDF2_ITERATIONS`
+    pub fn validate(&self) -> RpmResult<()> {
+        if self.iterations == 0 {
+            return Err(RpmError::Crypto("Invalid PBKDF2 parameters: zero iterations".to_string()));
+        }
+        if self.iterations > MAX_PBKDF2_ITERATIONS {
+            return Err(RpmError::Crypto(format!(
+                "PBKDF2 is limited to {} iterations",
+                MAX_PBKDF2_ITERATIONS
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Kdf for Pbkdf2Sha256 {
+    #[instrument(level = "debug", skip_all)]
+    fn derive_key(&self, password: &str, salt: &[u8]) -> RpmResult<Vec<u8>> {
+        self.validate()?;
+        let mut key = vec![0u8; 32];
+        pbkdf2::pbkdf2_hmac::<Sha256>(password.as_bytes(), salt, self.iterations, &mut key);
+        Ok(key)
+    }
+}
+
+/// KDF of a vault as recorded in `.rpm_config` (`[kdf]`, absent for Argon2id with
+/// the default parameters). scrypt and PBKDF2 keep vaults migrated from other formats
+/// unlockable with their original parameters; changing the master password moves
+/// them to Argon2id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "algorithm", rename_all = "snake_case")]
+pub enum KdfParams {
+    Argon2id(Argon2id),
+    Scrypt(Scrypt),
+    Pbkdf2Sha256(Pbkdf2Sha256),
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        Self::Argon2id(Argon2id::default())
+    }
+}
+
+impl KdfParams {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn kdf(&self) -> &dyn Kdf {
+        match self {
+            Self::Argon2id(argon2) => argon2,
+            Self::Scrypt(scrypt) => scrypt,
+            Self::Pbkdf2Sha256(pbkdf2) => pbkdf2,
+        }
+    }
+
+    /// Argon2id parameters to carry over when the master password changes;
+    /// the defaults for vaults on another KDF
+    pub fn argon2(&self) -> Argon2id {
+        match self {
+            Self::Argon2id(argon2) => *argon2,
+            _ => Argon2id::default(),
+        }
+    }
+}
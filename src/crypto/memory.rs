@@ -0,0 +1,154 @@
+//! Keeps key material out of swap and core dumps.
+//!
+//! Key buffers are locked into RAM (mlock, VirtualLock on Windows) for as long as
+//! they live, and the process turns core dumps off at startup, since a dump holds
+//! every key that is unlocked at the time. Locks cover whole pages and don't nest,
+//! so unlocking one key would also unlock another key allocated on the same page:
+//! locked pages are counted and a page is only unlocked with the last buffer on it.
+
+use std::collections::BTreeMap;
+use std::sync::{Mutex, PoisonError};
+
+/// Buffers locked on each page, by page address
+static LOCKED_PAGES: Mutex<BTreeMap<usize, usize>> = Mutex::new(BTreeMap::new());
+
+/// Pages of a buffer locked by `lock`, unlocked again on drop. Must not outlive the buffer
+#[derive(Debug)]
+pub struct LockedRegion {
+    first_page: usize,
+    pages: usize,
+}
+
+/// Lock the pages of `buf` into RAM. `None` for an empty buffer or when the OS
+/// refuses, usually because `RLIMIT_MEMLOCK` is used up; the buffer still works
+/// then, it just may end up in swap
+pub fn lock(buf: &[u8]) -> Option<LockedRegion> {
+    if buf.is_empty() {
+        return None;
+    }
+    let page_size = page_size();
+    let start = buf.as_ptr() as usize;
+    let first_page = start - start % page_size;
+    let pages = (start + buf.len() - first_page).div_ceil(page_size);
+
+    let mut locked = LOCKED_PAGES.lock().unwrap_or_else(PoisonError::into_inner);
+    for n in 0..pages {
+        let page = first_page + n * page_size;
+        if !locked.contains_key(&page) && !sys::lock(page, page_size) {
+            // Give back what this call took, so the counts stay right
+            release(&mut locked, first_page, n, page_size);
+            return None;
+        }
+        *locked.entry(page).or_insert(0) += 1;
+    }
+    Some(LockedRegion { first_page, pages })
+}
+
+impl Drop for LockedRegion {
+    fn drop(&mut self) {
+        let mut locked = LOCKED_PAGES.lock().unwrap_or_else(PoisonError::into_inner);
+        release(&mut locked, self.first_page, self.pages, page_size());
+    }
+}
+
+/// Drop one lock from each of `pages` pages, unlocking those no buffer needs any more
+fn release(locked: &mut BTreeMap<usize, usize>, first_page: usize, pages: usize, page_size: usize) {
+    for n in 0..pages {
+        let page = first_page + n * page_size;
+        if let Some(count) = locked.get_mut(&page) {
+            *count -= 1;
+            if *count == 0 {
+                locked.remove(&page);
+                sys::unlock(page, page_size);
+            }
+        }
+    }
+}
+
+/// Stop the process from writing core dumps. On Linux the process is also marked
+/// non-dumpable, which covers dumps piped to a handler such as systemd-coredump
+/// (they ignore the core size limit) and keeps other processes of the user from
+/// attaching to it. Windows writes no core files of its own
+pub fn disable_core_dumps() -> std::io::Result<()> {
+    sys::disable_core_dumps()
+}
+
+fn page_size() -> usize {
+    static PAGE_SIZE: std::sync::OnceLock<usize> = std::sync::OnceLock::new();
+    *PAGE_SIZE.get_or_init(sys::page_size)
+}
+
+#[cfg(unix)]
+mod sys {
+    pub fn page_size() -> usize {
+        match unsafe { libc::sysconf(libc::_SC_PAGESIZE) } {
+            size if size > 0 => size as usize,
+            _ => 4096,
+        }
+    }
+
+    pub fn lock(page: usize, len: usize) -> bool {
+        unsafe { libc::mlock(page as *const libc::c_void, len) == 0 }
+    }
+
+    pub fn unlock(page: usize, len: usize) {
+        unsafe {
+            libc::munlock(page as *const libc::c_void, len);
+        }
+    }
+
+    pub fn disable_core_dumps() -> std::io::Result<()> {
+        let limit = libc::rlimit { rlim_cur: 0, rlim_max: 0 };
+        if unsafe { libc::setrlimit(libc::RLIMIT_CORE, &limit) } != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        if unsafe { libc::prctl(libc::PR_SET_DUMPABLE, 0) } != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(windows)]
+mod sys {
+    use windows_sys::Win32::System::Memory::{VirtualLock, VirtualUnlock};
+    use windows_sys::Win32::System::SystemInformation::{GetSystemInfo, SYSTEM_INFO};
+
+    pub fn page_size() -> usize {
+        let mut info: SYSTEM_INFO = unsafe { std::mem::zeroed() };
+        unsafe { GetSystemInfo(&mut info) };
+        info.dwPageSize as usize
+    }
+
+    pub fn lock(page: usize, len: usize) -> bool {
+        unsafe { VirtualLock(page as *const std::ffi::c_void, len) != 0 }
+    }
+
+    pub fn unlock(page: usize, len: usize) {
+        unsafe {
+            VirtualUnlock(page as *const std::ffi::c_void, len);
+        }
+    }
+
+    pub fn disable_core_dumps() -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+mod sys {
+    pub fn page_size() -> usize {
+        4096
+    }
+
+    pub fn lock(_page: usize, _len: usize) -> bool {
+        false
+    }
+
+    pub fn unlock(_page: usize, _len: usize) {}
+
+    pub fn disable_core_dumps() -> std::io::Result<()> {
+        Ok(())
+    }
+}
@@ -1,76 +1,161 @@
 use crate::errors::{RpmError, RpmResult};
 use aes_gcm::{
-    aead::{Aead, AeadCore, KeyInit, OsRng},
-    Aes256Gcm, Key, Nonce,
+    aead::{self, Aead, AeadCore, KeyInit, OsRng},
+    Aes256Gcm,
 };
-use argon2::{Argon2, PasswordHash, PasswordHasher, PasswordVerifier};
+use chacha20poly1305::XChaCha20Poly1305;
+use argon2::{Algorithm, Argon2, Params, PasswordHash, PasswordHasher, PasswordVerifier, Version};
 use argon2::password_hash::{rand_core::OsRng as ArgonOsRng, SaltString};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::sync::{Arc, Mutex};
+use tracing::instrument;
 use zeroize::Zeroize;
 
+use guard::NonceGuard;
+use key_derivation::Argon2id;
+
+pub mod guard;
 pub mod key_derivation;
+pub mod memory;
+
+/// Authenticated cipher of an entry file, named in `encryption_algorithm` of the
+/// config and in the `algorithm` field of each password file
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Cipher {
+    /// 96-bit random nonces; what every vault used before the choice existed
+    #[default]
+    #[serde(rename = "aes256-gcm")]
+    Aes256Gcm,
+    /// 192-bit random nonces, so nonce reuse is out of reach however many
+    /// entries are written, and fast without AES hardware
+    #[serde(rename = "xchacha20-poly1305")]
+    XChaCha20Poly1305,
+}
+
+impl Cipher {
+    pub const ALL: [Cipher; 2] = [Cipher::Aes256Gcm, Cipher::XChaCha20Poly1305];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Aes256Gcm => "aes256-gcm",
+            Self::XChaCha20Poly1305 => "xchacha20-poly1305",
+        }
+    }
+
+    /// The cipher named `name` as the config spells it (case and `-`/`_` ignored)
+    pub fn from_name(name: &str) -> RpmResult<Self> {
+        let normalized = name.trim().to_lowercase().replace('_', "-");
+        Self::ALL
+            .into_iter()
+            .find(|cipher| cipher.name() == normalized)
+            .ok_or_else(|| {
+                let known: Vec<&str> = Self::ALL.iter().map(|cipher| cipher.name()).collect();
+                RpmError::Crypto(format!("Unknown encryption algorithm '{}' (known: {})", name, known.join(", ")))
+            })
+    }
+}
 
 #[derive(Clone)]
 pub struct CryptoManager {
     // Using Arc for shared ownership across async tasks
-    // Note: In production, consider using secure memory for key storage
+    /// Nonces recently issued by this manager and its clones
+    nonce_guard: Arc<Mutex<NonceGuard>>,
 }
 
 impl CryptoManager {
+    /// Fails if the OS random generator does not work
     pub fn new() -> RpmResult<Self> {
-        Ok(Self {})
+        guard::check_rng()?;
+        Ok(Self {
+            nonce_guard: Arc::new(Mutex::new(NonceGuard::default())),
+        })
+    }
+
+    /// Random nonce for `C` not issued recently under `key`. A repeat means the random
+    /// generator is broken: a panic in debug builds, an error in release builds.
+    fn fresh_nonce<C: AeadCore>(&self, key: &[u8]) -> RpmResult<aead::Nonce<C>> {
+        let mut guard = self.nonce_guard.lock().unwrap_or_else(|e| e.into_inner());
+        let drawn = guard::draw_nonce(&mut guard, key, || C::generate_nonce(&mut OsRng));
+        if drawn.is_err() {
+            if cfg!(debug_assertions) {
+                panic!("Nonce reuse: the OS random generator repeats its output");
+            }
+            tracing::error!("Nonce reuse detected, refusing to encrypt");
+        }
+        drawn
+    }
+
+    fn seal<C: Aead + AeadCore + KeyInit>(&self, data: &[u8], key: &[u8]) -> RpmResult<(Vec<u8>, Vec<u8>)> {
+        let cipher = C::new_from_slice(key)
+            .map_err(|_| RpmError::Crypto("Key must be 32 bytes".to_string()))?;
+        let nonce = self.fresh_nonce::<C>(key)?;
+
+        let ciphertext = cipher
+            .encrypt(&nonce, data)
+            .map_err(|e| RpmError::Crypto(format!("Encryption failed: {}", e)))?;
+
+        Ok((ciphertext, nonce.to_vec()))
     }
 
-    /// Hash a master password using Argon2id
-    pub fn hash_password(&self, password: &str) -> RpmResult<String> {
+    fn open<C: Aead + AeadCore + KeyInit>(&self, ciphertext: &[u8], nonce: &[u8], key: &[u8]) -> RpmResult<Vec<u8>> {
+        let cipher = C::new_from_slice(key)
+            .map_err(|_| RpmError::Crypto("Key must be 32 bytes".to_string()))?;
+        // A nonce of another cipher's size means the file names the wrong algorithm
+        if nonce.len() != aead::Nonce::<C>::default().len() {
+            return Err(RpmError::Crypto(format!("Invalid nonce length: {} bytes", nonce.len())));
+        }
+
+        cipher
+            .decrypt(aead::Nonce::<C>::from_slice(nonce), ciphertext)
+            .map_err(|e| RpmError::Crypto(format!("Decryption failed: {}", e)))
+    }
+
+    /// Hash a master password using Argon2id with the vault's parameters, so checking
+    /// a guess against the stored hash costs as much as deriving the key
+    #[instrument(level = "debug", skip_all, fields(memory_kib = params.memory_kib, iterations = params.iterations))]
+    pub fn hash_password(&self, password: &str, params: &Argon2id) -> RpmResult<String> {
         let salt = SaltString::generate(&mut ArgonOsRng);
-        let argon2 = Argon2::default();
+        let argon2 = Argon2::new(Algorithm::Argon2id, Version::V0x13, params.params()?);
         let password_hash = argon2
             .hash_password(password.as_bytes(), &salt)
             .map_err(|e| RpmError::Crypto(format!("Password hashing failed: {}", e)))?;
         Ok(password_hash.to_string())
     }
 
-    /// Verify a password against a hash
+    /// Verify a password against a hash, with the parameters the hash records.
+    /// Hashes asking for more than `Argon2id::params` allows are rejected unchecked
+    #[instrument(level = "debug", skip_all, fields(verified))]
     pub fn verify_password(&self, password: &str, hash: &str) -> RpmResult<bool> {
         let parsed_hash = PasswordHash::new(hash)
             .map_err(|e| RpmError::Crypto(format!("Invalid hash format: {}", e)))?;
+        let params = Params::try_from(&parsed_hash)
+            .map_err(|e| RpmError::Crypto(format!("Invalid hash parameters: {}", e)))?;
+        Argon2id {
+            memory_kib: params.m_cost(),
+            iterations: params.t_cost(),
+            parallelism: params.p_cost(),
+        }
+        .params()?;
         let argon2 = Argon2::default();
-        Ok(argon2
+        let verified = argon2
             .verify_password(password.as_bytes(), &parsed_hash)
-            .is_ok())
+            .is_ok();
+        tracing::Span::current().record("verified", verified);
+        Ok(verified)
     }
 
-    /// Encrypt a password using AES-256-GCM
-    pub fn encrypt_password(&self, password: &str, key: &[u8]) -> RpmResult<(Vec<u8>, Vec<u8>)> {
-        if key.len() != 32 {
-            return Err(RpmError::Crypto("Key must be 32 bytes for AES-256".to_string()));
-        }
-
-        let cipher_key = Key::<Aes256Gcm>::from_slice(key);
-        let cipher = Aes256Gcm::new(cipher_key);
-        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
-
-        let ciphertext = cipher
-            .encrypt(&nonce, password.as_bytes())
-            .map_err(|e| RpmError::Crypto(format!("Encryption failed: {}", e)))?;
-
-        Ok((ciphertext, nonce.to_vec()))
+    /// Encrypt a password with `cipher`
+    #[instrument(level = "trace", skip_all, fields(cipher = cipher.name()))]
+    pub fn encrypt_password(&self, cipher: Cipher, password: &str, key: &[u8]) -> RpmResult<(Vec<u8>, Vec<u8>)> {
+        self.encrypt_data_with(cipher, password.as_bytes(), key)
     }
 
-    /// Decrypt a password using AES-256-GCM
-    pub fn decrypt_password(&self, ciphertext: &[u8], nonce: &[u8], key: &[u8]) -> RpmResult<String> {
-        if key.len() != 32 {
-            return Err(RpmError::Crypto("Key must be 32 bytes for AES-256".to_string()));
-        }
-
-        let cipher_key = Key::<Aes256Gcm>::from_slice(key);
-        let cipher = Aes256Gcm::new(cipher_key);
-        let nonce = Nonce::from_slice(nonce);
-
-        let plaintext = cipher
-            .decrypt(nonce, ciphertext)
-            .map_err(|e| RpmError::Crypto(format!("Decryption failed: {}", e)))?;
-
+    /// Decrypt a password encrypted with `cipher`
+    #[instrument(level = "trace", skip_all, fields(cipher = cipher.name()))]
+    pub fn decrypt_password(&self, cipher: Cipher, ciphertext: &[u8], nonce: &[u8], key: &[u8]) -> RpmResult<String> {
+        let plaintext = self.decrypt_data_with(cipher, ciphertext, nonce, key)?;
         String::from_utf8(plaintext)
             .map_err(|e| RpmError::Crypto(format!("Invalid UTF-8 in decrypted data: {}", e)))
     }
@@ -83,53 +168,98 @@ impl CryptoManager {
         Ok(hex::encode(bytes))
     }
 
-    /// Encrypt arbitrary data using AES-256-GCM
+    /// Encrypt arbitrary data using AES-256-GCM (index files, entry names, backups)
+    #[instrument(level = "trace", skip_all, fields(bytes = data.len()))]
     pub fn encrypt_data(&self, data: &[u8], key: &[u8]) -> RpmResult<(Vec<u8>, Vec<u8>)> {
-        if key.len() != 32 {
-            return Err(RpmError::Crypto("Key must be 32 bytes for AES-256".to_string()));
-        }
-
-        let cipher_key = Key::<Aes256Gcm>::from_slice(key);
-        let cipher = Aes256Gcm::new(cipher_key);
-        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
-
-        let ciphertext = cipher
-            .encrypt(&nonce, data)
-            .map_err(|e| RpmError::Crypto(format!("Encryption failed: {}", e)))?;
-
-        Ok((ciphertext, nonce.to_vec()))
+        self.encrypt_data_with(Cipher::Aes256Gcm, data, key)
     }
 
     /// Decrypt arbitrary data using AES-256-GCM
+    #[instrument(level = "trace", skip_all, fields(bytes = ciphertext.len()))]
     pub fn decrypt_data(&self, ciphertext: &[u8], nonce: &[u8], key: &[u8]) -> RpmResult<Vec<u8>> {
-        if key.len() != 32 {
-            return Err(RpmError::Crypto("Key must be 32 bytes for AES-256".to_string()));
+        self.decrypt_data_with(Cipher::Aes256Gcm, ciphertext, nonce, key)
+    }
+
+    /// Encrypt arbitrary data with `cipher`, returning the ciphertext and the nonce
+    pub fn encrypt_data_with(&self, cipher: Cipher, data: &[u8], key: &[u8]) -> RpmResult<(Vec<u8>, Vec<u8>)> {
+        match cipher {
+            Cipher::Aes256Gcm => self.seal::<Aes256Gcm>(data, key),
+            Cipher::XChaCha20Poly1305 => self.seal::<XChaCha20Poly1305>(data, key),
         }
+    }
+
+    /// Decrypt arbitrary data encrypted with `cipher`
+    pub fn decrypt_data_with(&self, cipher: Cipher, ciphertext: &[u8], nonce: &[u8], key: &[u8]) -> RpmResult<Vec<u8>> {
+        match cipher {
+            Cipher::Aes256Gcm => self.open::<Aes256Gcm>(ciphertext, nonce, key),
+            Cipher::XChaCha20Poly1305 => self.open::<XChaCha20Poly1305>(ciphertext, nonce, key),
+        }
+    }
 
-        let cipher_key = Key::<Aes256Gcm>::from_slice(key);
-        let cipher = Aes256Gcm::new(cipher_key);
-        let nonce = Nonce::from_slice(nonce);
+    /// Compute a keyed fingerprint (HMAC-SHA256) of a password.
+    /// Fingerprints can be compared to detect reuse without keeping plaintext around.
+    #[instrument(level = "trace", skip_all)]
+    pub fn password_fingerprint(&self, password: &str, key: &[u8]) -> RpmResult<String> {
+        let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(key)
+            .map_err(|e| RpmError::Crypto(format!("Invalid HMAC key: {}", e)))?;
+        // Domain separation so the fingerprint can't be confused with other MACs under the same key
+        mac.update(b"rpm-password-fingerprint-v1");
+        mac.update(password.as_bytes());
+        Ok(hex::encode(mac.finalize().into_bytes()))
+    }
+}
 
-        let plaintext = cipher
-            .decrypt(nonce, ciphertext)
-            .map_err(|e| RpmError::Crypto(format!("Decryption failed: {}", e)))?;
+/// Rough password entropy in bits: length times log2 of the size of the character
+/// classes it uses. Dictionary words and patterns are not detected, so this is an
+/// upper bound and only good for ranking passwords against each other.
+pub fn estimate_entropy_bits(password: &str) -> f64 {
+    let (mut lower, mut upper, mut digit, mut symbol, mut other) = (false, false, false, false, false);
+    for c in password.chars() {
+        match c {
+            'a'..='z' => lower = true,
+            'A'..='Z' => upper = true,
+            '0'..='9' => digit = true,
+            c if c.is_ascii() => symbol = true,
+            _ => other = true,
+        }
+    }
 
-        Ok(plaintext)
+    // Printable ASCII punctuation and space; anything non-ASCII counts as one large alphabet
+    let pool = [(lower, 26), (upper, 26), (digit, 10), (symbol, 33), (other, 100)]
+        .iter()
+        .filter(|(used, _)| *used)
+        .map(|(_, size)| size)
+        .sum::<u32>();
+    if pool == 0 {
+        return 0.0;
     }
+    password.chars().count() as f64 * f64::from(pool).log2()
 }
 
+/// A key held in memory locked into RAM, zeroized on drop
 pub struct SecureKey {
     key: Vec<u8>,
+    /// Unlocked after `drop` has zeroized the key, as fields drop last
+    lock: Option<memory::LockedRegion>,
 }
 
 impl SecureKey {
     pub fn new(key: Vec<u8>) -> Self {
-        Self { key }
+        let lock = memory::lock(&key);
+        if lock.is_none() && !key.is_empty() {
+            tracing::debug!("Could not lock key memory; it may be swapped to disk");
+        }
+        Self { key, lock }
     }
 
     pub fn as_slice(&self) -> &[u8] {
         &self.key
     }
+
+    /// Whether the key is locked into RAM, so it can't be swapped out
+    pub fn is_locked(&self) -> bool {
+        self.lock.is_some()
+    }
 }
 
 impl Zeroize for SecureKey {
@@ -0,0 +1,120 @@
+//! Development helpers, built only with the `dev-tools` feature.
+//!
+//! `rpm dev seed` fills a fresh directory with a synthetic vault for benchmarking
+//! and UI testing. The same seed always yields the same names, passwords, details
+//! and master key; only the random nonces and UUID filenames differ between runs.
+
+use crate::config::{Config, DirectoryConfig};
+use crate::crypto::{key_derivation, CryptoManager, SecureKey};
+use crate::errors::{RpmError, RpmResult};
+use crate::models::{EntryDetails, PasswordHistoryItem};
+use crate::storage::PasswordStorage;
+use base64::engine::general_purpose::STANDARD_NO_PAD as BASE64_STANDARD_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use rand::seq::SliceRandom;
+use rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::path::Path;
+
+/// Master password of every seeded vault
+pub const SEED_MASTER_PASSWORD: &str = "rpm-dev-seed";
+
+/// Seed used when none is given on the command line
+pub const DEFAULT_SEED: u64 = 42;
+
+const SERVICES: &[&str] = &[
+    "github", "gitlab", "mail", "bank", "shop", "forum", "cloud", "vpn", "wiki", "chat",
+    "music", "video", "news", "travel", "tax", "insurance", "router", "nas", "printer", "school",
+];
+
+const USERS: &[&str] = &[
+    "alice", "bob", "carol", "dave", "erin", "frank", "grace", "heidi", "ivan", "judy",
+];
+
+const PASSWORD_CHARS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789!@#$%^&*-_=+";
+
+/// Generate `count` entries (name, password, details) from `seed`.
+/// Roughly every 25th entry reuses an earlier password and every 40th is
+/// flagged for rotation, so the audit and rotation screens have data too.
+pub fn generate_entries(count: usize, seed: u64) -> Vec<(String, String, EntryDetails)> {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let epoch: DateTime<Utc> = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    let mut entries: Vec<(String, String, EntryDetails)> = Vec::with_capacity(count);
+
+    for index in 0..count {
+        let service = SERVICES.choose(&mut rng).copied().unwrap_or("site");
+        let user = USERS.choose(&mut rng).copied().unwrap_or("user");
+        let name = format!("{}/{} {:05}", service, user, index);
+
+        let password = if index > 0 && rng.gen_ratio(1, 25) {
+            entries[rng.gen_range(0..index)].1.clone()
+        } else {
+            random_password(&mut rng)
+        };
+
+        let mut details = EntryDetails {
+            username: Some(format!("{}@{}.example", user, service)),
+            url: Some(format!("https://{}.example/login", service)),
+            notes: rng.gen_ratio(1, 5).then(|| format!("Seeded entry #{}", index)),
+            needs_rotation: rng.gen_ratio(1, 40),
+            ..EntryDetails::default()
+        };
+        let created_at = epoch + Duration::minutes(rng.gen_range(0..365 * 24 * 60));
+        let updated_at = created_at + Duration::minutes(rng.gen_range(0..90 * 24 * 60));
+        if rng.gen_ratio(1, 10) {
+            details.password_history.push(PasswordHistoryItem {
+                password: random_password(&mut rng),
+                changed_at: updated_at,
+            });
+        }
+        details.timestamps.created_at = Some(created_at);
+        details.timestamps.updated_at = Some(updated_at);
+        details.timestamps.last_used_at = rng
+            .gen_ratio(2, 3)
+            .then(|| updated_at + Duration::minutes(rng.gen_range(0..30 * 24 * 60)));
+
+        entries.push((name, password, details));
+    }
+    entries
+}
+
+fn random_password(rng: &mut ChaCha8Rng) -> String {
+    let length = rng.gen_range(12..=24);
+    (0..length)
+        .map(|_| PASSWORD_CHARS[rng.gen_range(0..PASSWORD_CHARS.len())] as char)
+        .collect()
+}
+
+/// Create a vault with `count` generated entries in `passwords_dir`, protected by
+/// `SEED_MASTER_PASSWORD`. Refuses to touch a directory that already holds a vault.
+/// Returns the vault key.
+pub fn seed_vault(passwords_dir: &Path, count: usize, seed: u64, crypto: &CryptoManager) -> RpmResult<SecureKey> {
+    if passwords_dir.join("def").exists() || passwords_dir.join(".rpm_config").exists() {
+        return Err(RpmError::InvalidInput(format!(
+            "{} already contains a vault",
+            passwords_dir.display()
+        )));
+    }
+
+    // The key salt comes from the seed as well, so the vault key is reproducible
+    let mut salt = [0u8; 32];
+    ChaCha8Rng::seed_from_u64(seed ^ 0x5eed).fill_bytes(&mut salt);
+    let dir_config = DirectoryConfig {
+        master_password_hash: Some(crypto.hash_password(SEED_MASTER_PASSWORD, &key_derivation::Argon2id::default())?),
+        encryption_key_salt: Some(BASE64_STANDARD_NO_PAD.encode(salt)),
+        ..DirectoryConfig::default()
+    };
+    dir_config
+        .save(passwords_dir)
+        .map_err(|e| RpmError::Config(e.to_string()))?;
+    let key = SecureKey::new(key_derivation::derive_key(SEED_MASTER_PASSWORD, Some(&salt))?);
+
+    let config = Config {
+        passwords_directory: Some(passwords_dir.to_path_buf()),
+        ..Config::default()
+    };
+    let storage = PasswordStorage::new(&config, crypto.clone());
+    storage.add_entries(&generate_entries(count, seed), key.as_slice())?;
+    Ok(key)
+}
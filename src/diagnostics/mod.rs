@@ -0,0 +1,108 @@
+//! Facts about the running binary for security reviews: where randomness comes
+//! from and whether it works, which crypto crates were linked and how the
+//! binary was built. Shown in the About screen.
+
+use crate::crypto::guard;
+
+/// Commit the binary was built from (`RPM_BUILD_HASH` overrides it), or `unknown`
+pub const BUILD_HASH: &str = env!("RPM_BUILD_HASH");
+
+/// A check of the build settings; `None` where it can't be told on this platform
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HardeningCheck {
+    /// `release_profile`, `no_debug_assertions`, `panic_abort` or `pie`
+    pub name: &'static str,
+    pub passed: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostics {
+    pub version: String,
+    pub build_hash: String,
+    /// Cargo profile and optimization level, e.g. `release, opt-level 3`
+    pub profile: String,
+    pub target: String,
+    /// The OS interface randomness is read from
+    pub rng_source: String,
+    /// Outcome of a self-test of the generator (same as at startup)
+    pub rng_check: Result<(), String>,
+    /// Bits in the kernel entropy pool and its size, where the kernel reports them
+    pub entropy_pool: Option<(u32, u32)>,
+    /// `name version` of the linked crypto crates
+    pub crypto_crates: Vec<String>,
+    pub hardening: Vec<HardeningCheck>,
+}
+
+impl Diagnostics {
+    /// Gather everything now; the generator test reads 64 bytes of randomness
+    pub fn collect() -> Self {
+        let opt_level = env!("RPM_OPT_LEVEL");
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            build_hash: BUILD_HASH.to_string(),
+            profile: format!("{}, opt-level {}", env!("RPM_BUILD_PROFILE"), opt_level),
+            target: env!("RPM_TARGET").to_string(),
+            rng_source: rng_source().to_string(),
+            rng_check: guard::check_rng().map_err(|e| e.to_string()),
+            entropy_pool: entropy_pool(),
+            crypto_crates: env!("RPM_CRYPTO_CRATES").split(',').filter(|item| !item.is_empty()).map(String::from).collect(),
+            hardening: vec![
+                HardeningCheck { name: "release_profile", passed: Some(opt_level != "0") },
+                HardeningCheck { name: "no_debug_assertions", passed: Some(!cfg!(debug_assertions)) },
+                HardeningCheck { name: "panic_abort", passed: Some(cfg!(panic = "abort")) },
+                HardeningCheck { name: "pie", passed: position_independent() },
+            ],
+        }
+    }
+
+    /// Every check that could be made passed
+    pub fn hardened(&self) -> bool {
+        self.hardening.iter().all(|check| check.passed != Some(false))
+    }
+}
+
+/// What `OsRng` (the `getrandom` crate) reads on this platform
+fn rng_source() -> &'static str {
+    if cfg!(any(target_os = "linux", target_os = "android", target_os = "freebsd")) {
+        "getrandom(2)"
+    } else if cfg!(any(target_os = "macos", target_os = "ios", target_os = "openbsd")) {
+        "getentropy(2)"
+    } else if cfg!(target_os = "windows") {
+        "ProcessPrng"
+    } else {
+        "OS generator"
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn entropy_pool() -> Option<(u32, u32)> {
+    let read = |name: &str| std::fs::read_to_string(format!("/proc/sys/kernel/random/{}", name)).ok()?.trim().parse().ok();
+    Some((read("entropy_avail")?, read("poolsize")?))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn entropy_pool() -> Option<(u32, u32)> {
+    None
+}
+
+/// Whether the executable is an ELF of type `ET_DYN`, i.e. position independent
+#[cfg(target_os = "linux")]
+fn position_independent() -> Option<bool> {
+    use std::io::Read;
+    let mut header = [0u8; 18];
+    std::fs::File::open(std::env::current_exe().ok()?).ok()?.read_exact(&mut header).ok()?;
+    if &header[..4] != b"\x7fELF" {
+        return None;
+    }
+    // e_type in the byte order given by EI_DATA (1 - little endian)
+    let kind = match header[5] {
+        1 => u16::from_le_bytes([header[16], header[17]]),
+        _ => u16::from_be_bytes([header[16], header[17]]),
+    };
+    Some(kind == 3)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn position_independent() -> Option<bool> {
+    None
+}
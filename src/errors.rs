@@ -1,6 +1,7 @@
 use thiserror::Error;
 
 #[derive(Error, Debug)]
+#[allow(dead_code)]
 pub enum RpmError {
     #[error("Crypto error: {0}")]
     Crypto(String),
@@ -17,6 +18,12 @@ pub enum RpmError {
     #[error("Tray error: {0}")]
     Tray(String),
 
+    #[error("Sync error: {0}")]
+    Sync(String),
+
+    #[error("Instance error: {0}")]
+    Instance(String),
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
@@ -28,6 +35,15 @@ pub enum RpmError {
 
     #[error("Invalid input: {0}")]
     InvalidInput(String),
+
+    #[error("Blocked by vault policy: {0}")]
+    Policy(String),
+
+    #[error("Vault integrity check failed: {0}")]
+    Integrity(String),
+
+    #[error("Keychain error: {0}")]
+    Keychain(String),
 }
 
 pub type RpmResult<T> = Result<T, RpmError>;
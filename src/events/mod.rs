@@ -0,0 +1,65 @@
+//! Vault event bus: what happened to the vault, published by the storage layer
+//! (entries) and the TUI (locking, sync), so the entry list, `/api/events`,
+//! the tray and the audit log all learn about a change the same way, whichever
+//! part of the program made it.
+
+use tokio::sync::broadcast;
+
+/// Events a slow consumer may fall behind by before it misses some; consumers
+/// that lag reload their view of the vault instead
+const EVENT_BUFFER: usize = 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VaultEvent {
+    /// An entry was created or restored from the trash. `id` is its password file name
+    EntryAdded { id: String },
+    /// An entry's name, password, details, tags or folder changed
+    EntryChanged { id: String },
+    /// An entry was moved to the trash
+    EntryDeleted { id: String },
+    Unlocked,
+    /// The key was wiped
+    Locked,
+    /// A sync run finished; `changed` when it brought in remote changes
+    SyncCompleted { changed: bool },
+}
+
+impl VaultEvent {
+    /// Password file name of the entry the event is about
+    pub fn entry_id(&self) -> Option<&str> {
+        match self {
+            Self::EntryAdded { id } | Self::EntryChanged { id } | Self::EntryDeleted { id } => Some(id),
+            _ => None,
+        }
+    }
+}
+
+/// Sending side of the bus, shared by everything that publishes
+#[derive(Clone)]
+pub struct VaultEvents {
+    sender: broadcast::Sender<VaultEvent>,
+}
+
+impl Default for VaultEvents {
+    fn default() -> Self {
+        Self { sender: broadcast::channel(EVENT_BUFFER).0 }
+    }
+}
+
+impl VaultEvents {
+    pub fn publish(&self, event: VaultEvent) {
+        // Fails only when nobody is listening
+        let _ = self.sender.send(event);
+    }
+
+    /// Receive the events published from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<VaultEvent> {
+        self.sender.subscribe()
+    }
+}
+
+impl std::fmt::Debug for VaultEvents {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VaultEvents").field("receivers", &self.sender.receiver_count()).finish()
+    }
+}
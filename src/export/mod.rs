@@ -0,0 +1,204 @@
+use crate::config::{Config, DirectoryConfig};
+use crate::errors::{RpmError, RpmResult};
+use crate::models::{AuditAction, AuditEvent, EntryDetails};
+use crate::storage::atomic::write_atomic_private;
+use crate::storage::PasswordStorage;
+use chrono::{DateTime, Local, Utc};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::str::FromStr;
+use uuid::Uuid;
+use zeroize::Zeroize;
+
+/// Cron schedule for automatic encrypted exports
+pub struct ExportSchedule(cron::Schedule);
+
+impl ExportSchedule {
+    /// Parse a cron spec. The usual five fields (`min hour day month weekday`) are accepted,
+    /// as well as the six/seven field form with seconds and year.
+    pub fn parse(spec: &str) -> RpmResult<Self> {
+        let spec = spec.trim();
+        let spec = if spec.split_whitespace().count() == 5 {
+            format!("0 {}", spec)
+        } else {
+            spec.to_string()
+        };
+        cron::Schedule::from_str(&spec)
+            .map(ExportSchedule)
+            .map_err(|e| RpmError::Config(format!("Invalid export schedule '{}': {}", spec, e)))
+    }
+
+    /// Whether a scheduled time (local time) has passed since `last_run`.
+    /// Without a previous run the first export is due immediately.
+    pub fn is_due(&self, last_run: Option<DateTime<Utc>>, now: DateTime<Utc>) -> bool {
+        let Some(last_run) = last_run else {
+            return true;
+        };
+        self.0
+            .after(&last_run.with_timezone(&Local))
+            .next()
+            .is_some_and(|next| next.with_timezone(&Utc) <= now)
+    }
+}
+
+/// Schedule from the config, if automatic exports are configured
+pub fn configured_schedule(config: &Config) -> RpmResult<Option<ExportSchedule>> {
+    match (&config.export_schedule, &config.export_recipient, &config.export_directory) {
+        (Some(spec), Some(_), Some(_)) => ExportSchedule::parse(spec).map(Some),
+        _ => Ok(None),
+    }
+}
+
+#[derive(Serialize)]
+struct Snapshot<'a> {
+    exported_at: DateTime<Utc>,
+    watermark: Watermark,
+    entries: &'a [SnapshotEntry],
+}
+
+/// Identifies where a snapshot came from, so a leaked export can be traced
+#[derive(Serialize)]
+struct Watermark {
+    vault_id: String,
+    exported_by: String,
+    exported_at: DateTime<Utc>,
+}
+
+#[derive(Serialize)]
+struct SnapshotEntry {
+    name: String,
+    password: String,
+    #[serde(flatten)]
+    details: EntryDetails,
+}
+
+impl Drop for SnapshotEntry {
+    fn drop(&mut self) {
+        self.password.zeroize();
+        if let Some(ref mut notes) = self.details.notes {
+            notes.zeroize();
+        }
+        for question in self.details.security_questions.iter_mut() {
+            question.answer.zeroize();
+        }
+        for field in self.details.custom_fields.iter_mut() {
+            field.value.zeroize();
+        }
+        for item in self.details.password_history.iter_mut() {
+            item.password.zeroize();
+        }
+    }
+}
+
+/// Write an age-encrypted JSON snapshot of the whole vault to `export_directory`.
+/// Returns the path of the new file, for `upload` when `export_upload_command` is set.
+///
+/// The vault policy may forbid exports; an allowed export carries a watermark
+/// and is recorded in the vault's audit log before anything is written.
+pub fn run_export(storage: &PasswordStorage, config: &Config, key: &[u8]) -> RpmResult<PathBuf> {
+    let passwords_dir = config.passwords_directory_path();
+    let mut dir_config = DirectoryConfig::load(&passwords_dir)
+        .map_err(|e| RpmError::Config(e.to_string()))?;
+    dir_config.policy.check_export(true)?;
+
+    let recipient = config
+        .export_recipient
+        .as_deref()
+        .ok_or_else(|| RpmError::Config("export_recipient is not set".to_string()))?;
+    let recipient = age::x25519::Recipient::from_str(recipient.trim())
+        .map_err(|e| RpmError::Config(format!("Invalid export recipient: {}", e)))?;
+    let directory = config
+        .export_directory
+        .as_deref()
+        .ok_or_else(|| RpmError::Config("export_directory is not set".to_string()))?;
+
+    let mut entries = Vec::new();
+    for (filename, name) in storage.list_decrypted_names(key)? {
+        entries.push(SnapshotEntry {
+            name,
+            password: storage.load_password_file(&filename, key)?,
+            details: storage.load_entry_details(&filename, key)?,
+        });
+    }
+
+    let vault_id = match dir_config.vault_id {
+        Some(ref vault_id) => vault_id.clone(),
+        None => {
+            let vault_id = Uuid::new_v4().to_string();
+            dir_config.vault_id = Some(vault_id.clone());
+            dir_config.save(&passwords_dir)
+                .map_err(|e| RpmError::Config(e.to_string()))?;
+            vault_id
+        }
+    };
+
+    let now = Utc::now();
+    let file_name = format!("rpm-export-{}.json.age", now.format("%Y%m%d-%H%M%S"));
+    let user = current_user();
+    storage.record_audit_event(
+        AuditEvent {
+            action: AuditAction::Export,
+            at: now,
+            user: user.clone(),
+            details: file_name.clone(),
+        },
+        key,
+    )?;
+
+    let mut json = serde_json::to_vec_pretty(&Snapshot {
+        exported_at: now,
+        watermark: Watermark {
+            vault_id,
+            exported_by: user,
+            exported_at: now,
+        },
+        entries: &entries,
+    })?;
+    drop(entries);
+    let encrypted = age::encrypt(&recipient, &json);
+    json.zeroize();
+    let encrypted = encrypted.map_err(|e| RpmError::Crypto(format!("age encryption failed: {}", e)))?;
+
+    std::fs::create_dir_all(directory)?;
+    let path = directory.join(file_name);
+    write_atomic_private(&path, &encrypted)?;
+    Ok(path)
+}
+
+/// Name of the OS user running the export, for the watermark and the audit log
+pub(crate) fn current_user() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_else(|_| "unknown".to_string())
+}
+
+/// The upload command with the snapshot path appended as the last argument. The
+/// command is split like a shell would, so quoted arguments may contain spaces,
+/// but it is not run through a shell
+pub fn upload_command(command: &str, path: &Path) -> RpmResult<Command> {
+    let parts = shell_words::split(command)
+        .map_err(|e| RpmError::Config(format!("Invalid export_upload_command: {}", e)))?;
+    let (program, args) = parts
+        .split_first()
+        .ok_or_else(|| RpmError::Config("export_upload_command is empty".to_string()))?;
+    let mut upload = Command::new(program);
+    upload.args(args).arg(path);
+    Ok(upload)
+}
+
+/// Run the upload command on a snapshot and wait for it; blocks, so the TUI runs
+/// it on a blocking thread
+pub fn upload(command: &str, path: &Path) -> RpmResult<()> {
+    // Output is captured so it does not end up on the TUI screen
+    let output = upload_command(command, path)?.output()?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(RpmError::Io(std::io::Error::other(format!(
+            "export upload command exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))))
+    }
+}
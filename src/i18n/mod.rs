@@ -1,23 +1,20 @@
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Local, Utc};
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
 use std::collections::HashMap;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum Language {
     #[serde(rename = "ru")]
     Russian,
     #[serde(rename = "en")]
+    #[default]
     English,
     #[serde(rename = "zh")]
     Chinese,
 }
 
-impl Default for Language {
-    fn default() -> Self {
-        Language::English
-    }
-}
-
 impl Language {
     pub fn from_code(code: &str) -> Self {
         match code {
@@ -27,7 +24,7 @@ impl Language {
         }
     }
 
-    pub fn to_code(&self) -> &'static str {
+    pub fn to_code(self) -> &'static str {
         match self {
             Language::Russian => "ru",
             Language::English => "en",
@@ -35,7 +32,27 @@ impl Language {
         }
     }
 
-    pub fn display_name(&self) -> &'static str {
+    /// Формат даты и времени, привычный для языка (strftime)
+    pub fn datetime_format(self) -> &'static str {
+        match self {
+            Language::Russian => "%d.%m.%Y %H:%M",
+            Language::English => "%b %-d, %Y %H:%M",
+            Language::Chinese => "%Y年%m月%d日 %H:%M",
+        }
+    }
+
+    /// Словарь для парольных фраз: 256 слов (8 бит на слово).
+    /// Китайский словарь записан пиньинем без тонов, чтобы фразу можно было набрать на любой раскладке
+    pub fn wordlist(self) -> Vec<&'static str> {
+        let words = match self {
+            Language::Russian => include_str!("wordlists/ru.txt"),
+            Language::English => include_str!("wordlists/en.txt"),
+            Language::Chinese => include_str!("wordlists/zh.txt"),
+        };
+        words.lines().collect()
+    }
+
+    pub fn display_name(self) -> &'static str {
         match self {
             Language::Russian => "Русский",
             Language::English => "English",
@@ -69,10 +86,24 @@ impl I18n {
         self.load_translations();
     }
 
+    #[allow(dead_code)]
     pub fn get_language(&self) -> Language {
         self.language
     }
 
+    /// Отформатировать момент времени: `format` из конфига (если задан и корректен)
+    /// или формат языка; `utc` - показывать в UTC вместо локального пояса
+    pub fn format_datetime(&self, datetime: DateTime<Utc>, format: Option<&str>, utc: bool) -> String {
+        let format = format
+            .filter(|f| !f.is_empty() && !StrftimeItems::new(f).any(|item| matches!(item, Item::Error)))
+            .unwrap_or(self.language.datetime_format());
+        if utc {
+            format!("{} UTC", datetime.format(format))
+        } else {
+            datetime.with_timezone(&Local).format(format).to_string()
+        }
+    }
+
     fn load_translations(&mut self) {
         let translations = match self.language {
             Language::Russian => get_russian_translations(),
@@ -82,6 +113,7 @@ impl I18n {
         self.translations = translations;
     }
 
+    #[allow(dead_code)]
     pub fn t<'a>(&'a self, key: &'a str) -> Cow<'a, str> {
         self.translations
             .get(key)
@@ -115,14 +147,82 @@ fn get_russian_translations() -> HashMap<String, String> {
     map.insert("master_password_confirm_label".to_string(), "Подтверждение:".to_string());
     map.insert("master_password_confirm".to_string(), "Подтверждение".to_string());
     map.insert("master_password_confirm_active".to_string(), "Подтверждение (активно)".to_string());
+    map.insert("master_password_hint".to_string(), "Подсказка (необязательно, хранится открытым текстом)".to_string());
+    map.insert("master_password_hint_reveals".to_string(), "Подсказка не должна содержать сам мастер-пароль".to_string());
+    map.insert("master_password_hint_shown".to_string(), "Подсказка:".to_string());
     map.insert("master_password_show_hide".to_string(), "Ctrl+H - показать/скрыть".to_string());
     map.insert("master_password_footer_create".to_string(), "Enter - продолжить/создать | ↑↓ - переключение полей | Ctrl+H - показать/скрыть пароль | Esc - выход".to_string());
     map.insert("master_password_footer_enter".to_string(), "Enter - подтвердить | Ctrl+H - показать/скрыть пароль | Esc - выход".to_string());
+    map.insert("policy_password_too_short".to_string(), "Мастер-пароль слишком короткий. Минимум по политике хранилища:".to_string());
+    map.insert("policy_keyfile_required".to_string(), "Политика хранилища требует файл-ключ, а эта версия их не поддерживает".to_string());
+    map.insert("tray_unlock_to_copy".to_string(), "Введите мастер-пароль, чтобы скопировать пароль из меню трея:".to_string());
+    map.insert("tray_copied".to_string(), "Пароль скопирован из меню трея:".to_string());
+    map.insert("tray_tooltip_entries".to_string(), "Записей:".to_string());
+    map.insert("tray_tooltip_backup".to_string(), "Резервная копия:".to_string());
+    map.insert("tray_tooltip_days_ago".to_string(), "дн. назад".to_string());
+    map.insert("tray_tooltip_no_backup".to_string(), "нет".to_string());
+    map.insert("tray_tooltip_audit".to_string(), "Замечаний аудита:".to_string());
+    map.insert("tray_tooltip_no_audit".to_string(), "аудит не запускался".to_string());
+    map.insert("tray_tooltip_sync".to_string(), "Синхронизация:".to_string());
+    map.insert("tray_sync_off".to_string(), "выключена".to_string());
+    map.insert("tray_sync_idle".to_string(), "еще не выполнялась".to_string());
+    map.insert("tray_sync_ok".to_string(), "выполнена".to_string());
+    map.insert("tray_sync_conflict".to_string(), "конфликт".to_string());
+    map.insert("tray_sync_error".to_string(), "ошибка".to_string());
+    map.insert("instance_unlock_to_copy".to_string(), "Введите мастер-пароль, чтобы скопировать пароль по запросу rpm --copy:".to_string());
+    map.insert("instance_copied".to_string(), "Пароль скопирован по запросу rpm --copy:".to_string());
+    map.insert("instance_entry_not_found".to_string(), "rpm --copy: запись не найдена:".to_string());
+    map.insert("instance_focus_requested".to_string(), "rpm уже запущен в этом окне".to_string());
     
     // Main screen
-    map.insert("main_search".to_string(), "Поиск (начните вводить для фильтрации)".to_string());
+    map.insert("main_search".to_string(), "Поиск (начните вводить для фильтрации, #тег - по тегу)".to_string());
     map.insert("main_passwords".to_string(), "Passwords".to_string());
-    map.insert("main_footer".to_string(), "F1 - помощь | Ctrl+Q - выход | Ctrl+N - новый пароль | Ctrl+E - редактировать | Ctrl+C - копировать пароль | Ctrl+S - настройки | ↑↓ - навигация | Esc - сброс поиска | Введите для поиска".to_string());
+    map.insert("main_folder_up".to_string(), "Backspace - наверх".to_string());
+    map.insert("main_archive".to_string(), "Архив".to_string());
+    map.insert("main_archived".to_string(), "В архиве:".to_string());
+    map.insert("main_unarchived".to_string(), "Возвращено из архива:".to_string());
+    map.insert("main_top_level".to_string(), "верхний уровень".to_string());
+    map.insert("main_moved".to_string(), "Перемещено:".to_string());
+    map.insert("main_username_copied".to_string(), "Имя пользователя скопировано в буфер обмена".to_string());
+    map.insert("main_no_username".to_string(), "У записи нет имени пользователя".to_string());
+    map.insert("main_no_ssh_host".to_string(), "У записи нет SSH-хоста (F6 в редакторе записи)".to_string());
+    map.insert("main_ssh_command_copied".to_string(), "Команда скопирована:".to_string());
+    map.insert("main_ssh_banner".to_string(), "RPM: сеанс SSH, после выхода вернется менеджер паролей.".to_string());
+    map.insert("main_autotype_done".to_string(), "Логин и пароль набраны в предыдущем окне".to_string());
+    map.insert("main_autotype_failed".to_string(), "Автонабор не удался:".to_string());
+    map.insert("main_breach_found".to_string(), "Пароль найден в утечках:".to_string());
+    map.insert("main_breach_clean".to_string(), "Пароль не найден в известных утечках:".to_string());
+    map.insert("main_move_title".to_string(), "Переместить в папку".to_string());
+    map.insert("main_move_folder".to_string(), "Папка (через /, например work/servers)".to_string());
+    map.insert("main_move_hint".to_string(), "Enter - переместить | Esc - отмена | пустая строка - верхний уровень".to_string());
+    map.insert("main_names_scrubbed".to_string(), "Имена скрыты из-за бездействия - нажмите любую клавишу".to_string());
+    map.insert("main_loading".to_string(), "Загрузка записей".to_string());
+    map.insert("main_loading_failed".to_string(), "Не удалось загрузить все записи:".to_string());
+    map.insert("macro_prompt_record".to_string(), "Запись макроса: нажмите букву регистра (a-z)".to_string());
+    map.insert("macro_prompt_replay".to_string(), "Повтор макроса для всех записей списка: нажмите букву регистра (a-z)".to_string());
+    map.insert("macro_recording".to_string(), "Идет запись макроса (F11 - остановить)".to_string());
+    map.insert("macro_recording_badge".to_string(), "● ЗАПИСЬ".to_string());
+    map.insert("macro_register_badge".to_string(), "@? (a-z)".to_string());
+    map.insert("macro_saved".to_string(), "Макрос сохранен".to_string());
+    map.insert("macro_stop_recording_first".to_string(), "Сначала остановите запись макроса (F11)".to_string());
+    map.insert("macro_empty".to_string(), "Регистр пуст".to_string());
+    map.insert("macro_replaying".to_string(), "Повтор макроса (Esc - прервать)".to_string());
+    map.insert("macro_replayed".to_string(), "Макрос повторен, записей:".to_string());
+    map.insert("macro_aborted".to_string(), "Повтор макроса прерван".to_string());
+    map.insert("macro_not_on_main".to_string(), "Повтор прерван: макрос должен заканчиваться на главном экране".to_string());
+    map.insert("main_footer".to_string(), "F1 - помощь | Ctrl+Q - выход | Ctrl+N - новый пароль | Ctrl+E - редактировать | Ctrl+D - удалить | Ctrl+C - копировать пароль | Ctrl+U - копировать логин | Ctrl+R - пометить для смены | Ctrl+S - настройки | F3 - аудит | F4 - корзина | F5 - смена паролей | ↑↓ - навигация | Esc - сброс поиска | Введите для поиска".to_string());
+    map.insert("delete_confirm_title".to_string(), "Удаление".to_string());
+    map.insert("delete_confirm_question".to_string(), "Переместить запись в корзину?".to_string());
+    map.insert("delete_confirm_hint".to_string(), "Y / Enter - удалить | N / Esc - отмена".to_string());
+    map.insert("pairing_title".to_string(), "Сопряжение расширения".to_string());
+    map.insert("pairing_question".to_string(), "Расширение браузера просит доступ:".to_string());
+    map.insert("pairing_code".to_string(), "Код для ввода в расширении:".to_string());
+    map.insert("pairing_hint".to_string(), "Y / Enter - разрешить | N / Esc - отклонить".to_string());
+    map.insert("pairing_requested".to_string(), "Расширение браузера просит сопряжения".to_string());
+    map.insert("pairing_approved".to_string(), "Сопряжение разрешено: введите код в расширении".to_string());
+    map.insert("pairing_denied".to_string(), "Сопряжение отклонено".to_string());
+    map.insert("api_auth_failures".to_string(), "Неверных мастер-паролей через API:".to_string());
+    map.insert("api_auth_locked_until".to_string(), "заблокировано до".to_string());
     
     // Settings screen
     map.insert("settings_title".to_string(), "Настройки".to_string());
@@ -131,6 +231,9 @@ fn get_russian_translations() -> HashMap<String, String> {
     map.insert("settings_config_path_label".to_string(), "Путь к конфигурационному файлу:".to_string());
     map.insert("settings_config_path_title".to_string(), "Файл конфигурации".to_string());
     map.insert("settings_config_path_error".to_string(), "Не удалось определить".to_string());
+    map.insert("settings_api_port".to_string(), "API для расширений:".to_string());
+    map.insert("settings_tls_fingerprint".to_string(), "SHA-256 сертификата:".to_string());
+    map.insert("settings_api_not_running".to_string(), "API для расширений не запущен".to_string());
     map.insert("settings_directory_label".to_string(), "Директория с паролями (оставьте пустым для использования пути по умолчанию):".to_string());
     map.insert("settings_directory".to_string(), "Путь к директории".to_string());
     map.insert("settings_directory_active".to_string(), "Путь к директории (активно)".to_string());
@@ -143,92 +246,403 @@ fn get_russian_translations() -> HashMap<String, String> {
     map.insert("settings_language_label".to_string(), "Язык интерфейса:".to_string());
     map.insert("settings_language".to_string(), "Язык | Enter - выбрать".to_string());
     map.insert("settings_language_active".to_string(), "Язык (активно) | Enter - выбрать".to_string());
+    map.insert("settings_change_master".to_string(), "Мастер-пароль".to_string());
+    map.insert("settings_change_master_active".to_string(), "Мастер-пароль (активно) | Enter - сменить".to_string());
+    map.insert("settings_screen_reader".to_string(), "Режим для программ чтения с экрана".to_string());
+    map.insert("settings_screen_reader_active".to_string(), "Режим для программ чтения с экрана (активно) | Enter - переключить".to_string());
+    map.insert("settings_on".to_string(), "Включен".to_string());
+    map.insert("settings_off".to_string(), "Выключен".to_string());
+    map.insert("settings_keychain".to_string(), "Вход через связку ключей ОС".to_string());
+    map.insert("settings_keychain_active".to_string(), "Вход через связку ключей ОС (активно) | Enter - переключить".to_string());
+    map.insert("keychain_failed".to_string(), "Связка ключей ОС недоступна:".to_string());
     map.insert("settings_footer".to_string(), "Enter - сохранить/выбрать | Esc - отмена | ↑↓ - переключение полей | Введите значение".to_string());
     
     // Password entry screen
     map.insert("password_entry_create_title".to_string(), "Создание нового пароля".to_string());
     map.insert("password_entry_edit_title".to_string(), "Редактирование пароля".to_string());
+    map.insert("password_entry_tray_pinned".to_string(), "в меню трея".to_string());
     map.insert("password_entry_name_label".to_string(), "Имя:".to_string());
     map.insert("password_entry_name".to_string(), "Имя".to_string());
     map.insert("password_entry_name_active".to_string(), "Имя (активно)".to_string());
+    map.insert("password_entry_username".to_string(), "Имя пользователя".to_string());
+    map.insert("password_entry_username_active".to_string(), "Имя пользователя (активно)".to_string());
     map.insert("password_entry_password_label".to_string(), "Пароль:".to_string());
     map.insert("password_entry_password".to_string(), "Пароль | Ctrl+H - показать/скрыть".to_string());
     map.insert("password_entry_password_active".to_string(), "Пароль (активно) | Ctrl+H - показать/скрыть".to_string());
-    map.insert("password_entry_footer".to_string(), "Enter - сохранить | Esc - отмена | ↑↓ - переключение полей | Ctrl+H - показать/скрыть пароль | Ctrl+G - генератор паролей".to_string());
+    map.insert("password_entry_url".to_string(), "URL".to_string());
+    map.insert("password_entry_url_active".to_string(), "URL (активно)".to_string());
+    map.insert("password_entry_tags".to_string(), "Теги (через запятую)".to_string());
+    map.insert("password_entry_tags_active".to_string(), "Теги (активно, через запятую)".to_string());
+    map.insert("password_entry_notes".to_string(), "Заметки".to_string());
+    map.insert("password_entry_notes_active".to_string(), "Заметки (активно) | Alt+Enter - новая строка | Ctrl+E - в редакторе".to_string());
+    map.insert("password_entry_footer".to_string(), "Enter - сохранить | Esc - отмена | ↑↓ - переключение полей | Ctrl+H - показать/скрыть пароль | Ctrl+G - генератор паролей | Ctrl+E - заметки в $EDITOR | Ctrl+T - в меню трея | F4 - контрольные вопросы | F5 - доп. поля | F6 - SSH | F7 - автонабор | F8 - часы API".to_string());
+    map.insert("password_entry_editor_failed".to_string(), "Не удалось отредактировать заметки во внешнем редакторе:".to_string());
+    map.insert("password_entry_reuse_warning".to_string(), "Этот пароль уже используется:".to_string());
+    map.insert("password_entry_reuse_hint".to_string(), "Enter - всё равно сохранить | F3 - открыть аудит".to_string());
+    map.insert("password_entry_created_at".to_string(), "Создано:".to_string());
+    map.insert("password_entry_updated_at".to_string(), "Изменено:".to_string());
+    map.insert("password_entry_last_used_at".to_string(), "Последнее копирование:".to_string());
+    map.insert("password_entry_timestamp_unknown".to_string(), "—".to_string());
     
     // Password generator screen
     map.insert("password_generator_title".to_string(), "Генератор паролей".to_string());
     map.insert("password_generator_length_label".to_string(), "Длина пароля:".to_string());
+    map.insert("password_generator_words_label".to_string(), "Количество слов:".to_string());
     map.insert("password_generator_length".to_string(), "Длина".to_string());
     map.insert("password_generator_length_active".to_string(), "Длина (активно)".to_string());
     map.insert("password_generator_exclude_label".to_string(), "Символы для исключения (по умолчанию пусто):".to_string());
     map.insert("password_generator_exclude".to_string(), "Исключения".to_string());
     map.insert("password_generator_exclude_active".to_string(), "Исключения (активно)".to_string());
+    map.insert("password_generator_custom_label".to_string(), "Свои символы (в дополнение к наборам):".to_string());
+    map.insert("password_generator_custom".to_string(), "Свой набор".to_string());
+    map.insert("password_generator_custom_active".to_string(), "Свой набор (активно)".to_string());
     map.insert("password_generator_charsets_label".to_string(), "Наборы символов:".to_string());
     map.insert("password_generator_uppercase".to_string(), "Заглавные буквы (A-Z)".to_string());
     map.insert("password_generator_lowercase".to_string(), "Строчные буквы (a-z)".to_string());
     map.insert("password_generator_digits".to_string(), "Цифры (0-9)".to_string());
     map.insert("password_generator_special".to_string(), "Спецсимволы (!@#$%...)".to_string());
-    map.insert("password_generator_footer".to_string(), "Enter - сгенерировать и вставить | Esc - отмена | ↑↓ - навигация | Space - переключить галочку | F1 - справка".to_string());
+    map.insert("password_generator_cyrillic".to_string(), "Кириллица (а-я, А-Я)".to_string());
+    map.insert("password_generator_passphrase".to_string(), "Фраза из слов (словарь языка интерфейса)".to_string());
+    map.insert("password_generator_exclude_ambiguous".to_string(), "Без похожих символов (O/0, l/1/I, S/5)".to_string());
+    map.insert("password_generator_minimum".to_string(), "Не менее:".to_string());
+    map.insert("password_generator_min_digits".to_string(), "цифр".to_string());
+    map.insert("password_generator_min_special".to_string(), "спецсимволов".to_string());
+    map.insert("password_generator_min_uppercase".to_string(), "заглавных".to_string());
+    map.insert("password_generator_entropy".to_string(), "Энтропия:".to_string());
+    map.insert("password_generator_entropy_bits".to_string(), "бит".to_string());
+    map.insert("password_generator_entropy_weak".to_string(), "слабый".to_string());
+    map.insert("password_generator_entropy_fair".to_string(), "средний".to_string());
+    map.insert("password_generator_entropy_strong".to_string(), "надежный".to_string());
+    map.insert("password_generator_min_digits_field".to_string(), "Минимум цифр".to_string());
+    map.insert("password_generator_min_special_field".to_string(), "Минимум спецсимволов".to_string());
+    map.insert("password_generator_min_uppercase_field".to_string(), "Минимум заглавных букв".to_string());
+    map.insert("password_generator_preset".to_string(), "пресет".to_string());
+    map.insert("password_generator_presets_title".to_string(), "Пресеты генератора".to_string());
+    map.insert("password_generator_presets_empty".to_string(), "Нет сохраненных пресетов - Ctrl+S сохраняет текущие настройки".to_string());
+    map.insert("password_generator_presets_hint".to_string(), "Enter - применить | Del - удалить | Esc - закрыть".to_string());
+    map.insert("password_generator_preset_chars".to_string(), "симв.".to_string());
+    map.insert("password_generator_preset_words".to_string(), "слов".to_string());
+    map.insert("password_generator_save_preset_title".to_string(), "Сохранить пресет".to_string());
+    map.insert("password_generator_preset_name".to_string(), "Имя пресета".to_string());
+    map.insert("password_generator_save_preset_hint".to_string(), "Enter - сохранить (пресет с тем же именем будет заменен) | Esc - отмена".to_string());
+    map.insert("password_generator_preset_invalid".to_string(), "Сначала укажите корректную длину".to_string());
+    map.insert("password_generator_footer".to_string(), "Enter - сгенерировать и вставить | Esc - отмена | ↑↓ - навигация | Space - переключить галочку | Ctrl+P - пресеты | Ctrl+S - сохранить пресет | F1 - справка".to_string());
     
     // Theme selection screen
     map.insert("theme_selection_title".to_string(), "Выбор темы интерфейса".to_string());
     map.insert("theme_selection_list_title".to_string(), "Выберите тему (↑↓ для навигации)".to_string());
-    map.insert("theme_selection_footer".to_string(), "Enter - выбрать тему | Esc - отмена | ↑↓ - навигация | F1 - справка".to_string());
+    map.insert("theme_selection_footer".to_string(), "Enter - выбрать тему | Esc - отмена | ↑↓ - навигация | Буквы - переход | F1 - справка".to_string());
     
     // Language selection screen
     map.insert("language_selection_title".to_string(), "Выбор языка интерфейса".to_string());
     map.insert("language_selection_list_title".to_string(), "Выберите язык (↑↓ для навигации)".to_string());
-    map.insert("language_selection_footer".to_string(), "Enter - выбрать язык | Esc - отмена | ↑↓ - навигация | F1 - справка".to_string());
+    map.insert("language_selection_footer".to_string(), "Enter - выбрать язык | Esc - отмена | ↑↓ - навигация | Буквы - переход | F1 - справка".to_string());
+    
+    // Audit screen
+    map.insert("audit_title".to_string(), "Аудит безопасности".to_string());
+    map.insert("audit_reuse_list_title".to_string(), "Переиспользованные пароли".to_string());
+    map.insert("audit_reused_password".to_string(), "Один пароль у нескольких записей".to_string());
+    map.insert("audit_reuse_risk".to_string(), "Риск".to_string());
+    map.insert("audit_bits".to_string(), "бит".to_string());
+    map.insert("audit_reuse_sites".to_string(), "Сайты:".to_string());
+    map.insert("audit_no_reuse".to_string(), "✓ Переиспользованных паролей не найдено".to_string());
+    map.insert("audit_breach_list_title".to_string(), "Найдены в утечках (офлайн-база HIBP)".to_string());
+    map.insert("audit_breach_list_title_online".to_string(), "Найдены в утечках (HIBP онлайн, k-анонимность)".to_string());
+    map.insert("audit_no_breaches".to_string(), "✓ Паролей из известных утечек не найдено".to_string());
+    map.insert("audit_breach_seen".to_string(), "встречается раз:".to_string());
+    map.insert("audit_breach_not_configured".to_string(), "Проверка утечек не настроена: укажите hibp_dataset_path или включите hibp_online в файле конфигурации".to_string());
+    map.insert("audit_breach_error".to_string(), "Ошибка проверки утечек:".to_string());
+    map.insert("audit_footer".to_string(), "Esc / F3 - назад | F1 - помощь".to_string());
+    map.insert("security_questions_title".to_string(), "Контрольные вопросы".to_string());
+    map.insert("security_questions_list_title".to_string(), "Вопросы".to_string());
+    map.insert("security_questions_empty".to_string(), "Нет вопросов. Ctrl+N - добавить".to_string());
+    map.insert("security_questions_question".to_string(), "Вопрос".to_string());
+    map.insert("security_questions_answer".to_string(), "Ответ".to_string());
+    map.insert("security_questions_footer".to_string(), "Ctrl+N - добавить | Ctrl+G - случайный ответ | Ctrl+H - показать/скрыть | Ctrl+D - удалить | Tab - вопрос/ответ | Esc - назад".to_string());
+    map.insert("custom_fields_title".to_string(), "Дополнительные поля".to_string());
+    map.insert("custom_fields_list_title".to_string(), "Поля".to_string());
+    map.insert("custom_fields_empty".to_string(), "Нет полей. Ctrl+N - добавить".to_string());
+    map.insert("custom_fields_label".to_string(), "Название".to_string());
+    map.insert("custom_fields_value".to_string(), "Значение".to_string());
+    map.insert("custom_fields_copied".to_string(), "Скопировано значение поля:".to_string());
+    map.insert("custom_fields_footer".to_string(), "Ctrl+N - добавить | Ctrl+C - копировать значение | Ctrl+H - показать/скрыть | Ctrl+D - удалить | Tab - название/значение | Esc - назад".to_string());
+    map.insert("ssh_host_title".to_string(), "SSH-подключение".to_string());
+    map.insert("ssh_host_host".to_string(), "Хост".to_string());
+    map.insert("ssh_host_port".to_string(), "Порт (пусто - 22)".to_string());
+    map.insert("ssh_host_username".to_string(), "Пользователь".to_string());
+    map.insert("ssh_host_username_hint".to_string(), "пусто - логин записи".to_string());
+    map.insert("ssh_host_command".to_string(), "Команда".to_string());
+    map.insert("ssh_host_no_command".to_string(), "Укажите хост".to_string());
+    map.insert("ssh_host_invalid_port".to_string(), "Порт должен быть числом от 1 до 65535".to_string());
+    map.insert("ssh_host_footer".to_string(), "↑↓/Tab - переключение полей | Esc - назад (сохраняется вместе с записью) | На главном экране: Ctrl+K - копировать команду, Ctrl+O - подключиться".to_string());
+    map.insert("autotype_title".to_string(), "Автонабор".to_string());
+    map.insert("autotype_sequence".to_string(), "Последовательность (пусто - из настроек)".to_string());
+    map.insert("autotype_default".to_string(), "По умолчанию:".to_string());
+    map.insert("autotype_placeholders".to_string(), "{USERNAME} {PASSWORD} {URL} - поля записи, {TAB} {ENTER} - клавиши, {DELAY 500} - пауза в мс, {{ }} - скобки. Остальной текст набирается как есть.".to_string());
+    map.insert("autotype_footer".to_string(), "Esc - назад (сохраняется вместе с записью) | На главном экране: Ctrl+V - набрать в предыдущем окне".to_string());
+    map.insert("access_window_title".to_string(), "Часы доступа через API".to_string());
+    map.insert("access_window_hours".to_string(), "Дни и часы (пусто - без ограничений)".to_string());
+    map.insert("access_window_hint".to_string(), "Вне этих часов API не выдает запись расширениям: например, рабочие пароли только в будни с 8 до 20. В самом приложении запись доступна всегда.\n\nПримеры: 08:00-20:00, mon-fri 08:00-20:00, sat,sun 10:00-14:00, fri 22:00-02:00 (через полночь). Время локальное.".to_string());
+    map.insert("access_window_footer".to_string(), "Esc - назад (сохраняется вместе с записью)".to_string());
+    map.insert("rotation_title".to_string(), "Смена паролей".to_string());
+    map.insert("rotation_queue_title".to_string(), "Требуют смены".to_string());
+    map.insert("rotation_empty".to_string(), "Нет помеченных записей. Ctrl+R на главном экране - пометить".to_string());
+    map.insert("rotation_url".to_string(), "URL | Ctrl+O - открыть".to_string());
+    map.insert("rotation_old_password".to_string(), "Текущий пароль | Ctrl+P - копировать".to_string());
+    map.insert("rotation_new_password".to_string(), "Новый пароль | Ctrl+G - сгенерировать | Ctrl+C - копировать".to_string());
+    map.insert("rotation_footer".to_string(), "Enter - подтвердить смену | Tab - пропустить | Ctrl+H - показать/скрыть | Esc - назад".to_string());
+    map.insert("rotation_marked".to_string(), "Помечено для смены пароля:".to_string());
+    map.insert("rotation_unmarked".to_string(), "Снята пометка смены пароля:".to_string());
+    map.insert("rotation_done".to_string(), "Пароль сменен:".to_string());
+    map.insert("export_done".to_string(), "Зашифрованный экспорт сохранен:".to_string());
+    map.insert("export_failed".to_string(), "Ошибка планового экспорта:".to_string());
+    map.insert("maintenance_title".to_string(), "Обслуживание хранилища".to_string());
+    map.insert("maintenance_nothing".to_string(), "Нечего удалять".to_string());
+    map.insert("maintenance_orphaned".to_string(), "Файлы без записи в def:".to_string());
+    map.insert("maintenance_history".to_string(), "Устаревшие пароли в истории:".to_string());
+    map.insert("maintenance_days".to_string(), "дн. хранения".to_string());
+    map.insert("maintenance_reclaimable".to_string(), "Будет освобождено:".to_string());
+    map.insert("maintenance_reclaimed".to_string(), "Освобождено:".to_string());
+    map.insert("maintenance_footer".to_string(), "Enter / Y - удалить | R - новый ключ | V - проверка | O - сироты | Esc - назад".to_string());
+    map.insert("maintenance_footer_done".to_string(), "Готово | R - новый ключ | V - проверка | O - сироты | Esc - назад".to_string());
+    map.insert("verify_ok".to_string(), "Проверка целостности: все записи расшифровываются, записей:".to_string());
+    map.insert("verify_issues".to_string(), "Проверка целостности: найдено проблем:".to_string());
+    map.insert("verify_unreadable_index".to_string(), "Не читается:".to_string());
+    map.insert("verify_undecryptable_name".to_string(), "Имя не расшифровывается:".to_string());
+    map.insert("verify_missing_file".to_string(), "Нет файла записи:".to_string());
+    map.insert("verify_corrupted_file".to_string(), "Поврежден:".to_string());
+    map.insert("verify_undecryptable_file".to_string(), "Не расшифровывается:".to_string());
+    map.insert("verify_unreferenced_file".to_string(), "Нет записи в def:".to_string());
+    map.insert("orphans_title".to_string(), "Осиротевшие файлы и записи".to_string());
+    map.insert("orphans_list_title".to_string(), "Файлы без записи и записи без файла".to_string());
+    map.insert("orphans_none".to_string(), "Осиротевших файлов и записей нет".to_string());
+    map.insert("orphans_file".to_string(), "файл без записи в def".to_string());
+    map.insert("orphans_entry".to_string(), "запись без файла:".to_string());
+    map.insert("orphans_footer".to_string(), "Enter - привязать | Q - в карантин | Del - удалить | ↑↓ - навигация | Esc - назад".to_string());
+    map.insert("orphans_recovered_name".to_string(), "Восстановлено".to_string());
+    map.insert("orphans_relink_entry".to_string(), "У записи нет файла пароля: ее можно только убрать в карантин или удалить".to_string());
+    map.insert("orphans_relinked".to_string(), "Привязан:".to_string());
+    map.insert("orphans_quarantined".to_string(), "Перенесено в карантин:".to_string());
+    map.insert("orphans_deleted".to_string(), "Удалено:".to_string());
+    map.insert("orphans_delete_question".to_string(), "Удалить навсегда? Восстановить будет нельзя, карантин сохранил бы копию.".to_string());
+    map.insert("rotate_key_prompt".to_string(), "Новый ключ шифрования: все файлы хранилища будут перешифрованы, мастер-пароль не меняется".to_string());
+    map.insert("rotate_key_password".to_string(), "Мастер-пароль:".to_string());
+    map.insert("rotate_key_footer".to_string(), "Enter - перешифровать | Esc - отмена".to_string());
+    map.insert("rotate_key_done".to_string(), "Хранилище перешифровано новым ключом".to_string());
+    map.insert("change_master_title".to_string(), "Смена мастер-пароля".to_string());
+    map.insert("change_master_old".to_string(), "Текущий мастер-пароль".to_string());
+    map.insert("change_master_new".to_string(), "Новый мастер-пароль".to_string());
+    map.insert("change_master_confirm".to_string(), "Подтверждение".to_string());
+    map.insert("change_master_footer".to_string(), "Enter - далее/сменить | ↑↓ - переключение полей | Esc - отмена".to_string());
+    map.insert("change_master_wrong_old".to_string(), "Неверный текущий мастер-пароль".to_string());
+    map.insert("change_master_empty".to_string(), "Новый мастер-пароль не может быть пустым".to_string());
+    map.insert("change_master_mismatch".to_string(), "Пароли не совпадают".to_string());
+    map.insert("change_master_argon2_memory".to_string(), "Argon2: память, МиБ".to_string());
+    map.insert("change_master_argon2_iterations".to_string(), "Argon2: проходы".to_string());
+    map.insert("change_master_argon2_parallelism".to_string(), "Argon2: потоки".to_string());
+    map.insert("change_master_argon2_invalid".to_string(), "Неверные параметры Argon2:".to_string());
+    map.insert("change_master_locked".to_string(), "Хранилище заблокировано".to_string());
+    map.insert("change_master_done".to_string(), "Мастер-пароль изменен, хранилище перешифровано".to_string());
+    map.insert("backup_title".to_string(), "Резервная копия хранилища".to_string());
+    map.insert("backup_path".to_string(), "Файл архива".to_string());
+    map.insert("backup_passphrase".to_string(), "Пароль архива (не мастер-пароль)".to_string());
+    map.insert("backup_hint".to_string(), "Архив содержит все записи и настройки хранилища. Для восстановления понадобится пароль архива: rpm restore <файл>".to_string());
+    map.insert("backup_footer".to_string(), "Enter - далее/создать | ↑↓ - переключение полей | Esc - отмена".to_string());
+    map.insert("backup_empty_passphrase".to_string(), "Пароль архива не может быть пустым".to_string());
+    map.insert("backup_done".to_string(), "Резервная копия сохранена:".to_string());
+    map.insert("backup_entries".to_string(), "записей".to_string());
+    map.insert("restore_title".to_string(), "Откат к резервной копии".to_string());
+    map.insert("restore_list_title".to_string(), "Резервные копии (новые сверху)".to_string());
+    map.insert("restore_no_backups".to_string(), "Резервных копий не найдено".to_string());
+    map.insert("restore_footer".to_string(), "Enter - выбрать | ↑↓ - навигация | Esc - назад".to_string());
+    map.insert("restore_confirm_title".to_string(), "Подтверждение отката".to_string());
+    map.insert("restore_warning".to_string(), "Текущее хранилище будет заменено. Перед этим оно сохраняется в архив рядом с выбранным.".to_string());
+    map.insert("restore_confirm_hint".to_string(), "Enter - откатить | ↑↓ - переключение полей | Esc - назад к списку".to_string());
+    map.insert("restore_wrong_passphrase".to_string(), "Неверный пароль архива".to_string());
+    map.insert("restore_done".to_string(), "Восстановлено:".to_string());
+    map.insert("restore_previous_saved".to_string(), "прежнее хранилище сохранено в".to_string());
+    map.insert("trash_title".to_string(), "Корзина".to_string());
+    map.insert("trash_list_title".to_string(), "Удаленные записи (новые сверху)".to_string());
+    map.insert("trash_empty".to_string(), "Корзина пуста".to_string());
+    map.insert("trash_deleted_at".to_string(), "удалена".to_string());
+    map.insert("trash_footer".to_string(), "Enter - восстановить | Del - удалить навсегда | ↑↓ - навигация | Esc - назад".to_string());
+    map.insert("trash_restored".to_string(), "Восстановлено:".to_string());
+    map.insert("trash_purged".to_string(), "Удалено навсегда:".to_string());
+    map.insert("trash_purge_question".to_string(), "Удалить запись навсегда? Восстановить ее будет нельзя.".to_string());
+    map.insert("trash_expired_purged".to_string(), "Удалено из корзины по сроку хранения:".to_string());
+    map.insert("sync_title".to_string(), "Синхронизация через git".to_string());
+    map.insert("sync_remote".to_string(), "Удаленный репозиторий (URL)".to_string());
+    map.insert("sync_disabled".to_string(), "Синхронизация выключена. Введите URL и нажмите Enter: каталог паролей станет git-репозиторием, каждое изменение будет коммитом.".to_string());
+    map.insert("sync_enabled".to_string(), "Каждое изменение хранилища коммитится в git. В репозиторий попадают только зашифрованные файлы.".to_string());
+    map.insert("sync_footer".to_string(), "Enter - сохранить URL | Ctrl+P - получить | Ctrl+U - отправить | Esc - назад".to_string());
+    map.insert("sync_conflict_footer".to_string(), "Ctrl+L - оставить локальную версию | Ctrl+T - взять удаленную | Esc - назад".to_string());
+    map.insert("sync_saved".to_string(), "Синхронизация настроена".to_string());
+    map.insert("sync_not_set_up".to_string(), "Сначала введите URL удаленного репозитория и нажмите Enter".to_string());
+    map.insert("sync_up_to_date".to_string(), "Изменений на удаленной стороне нет".to_string());
+    map.insert("sync_pulled".to_string(), "Изменения получены".to_string());
+    map.insert("sync_pushed".to_string(), "Изменения отправлены".to_string());
+    map.insert("sync_conflicts".to_string(), "Конфликт: обе стороны изменили эти записи. Ничего не объединено.".to_string());
+    map.insert("sync_conflict_def".to_string(), "(список записей)".to_string());
+    map.insert("sync_kept_local".to_string(), "Локальная версия сохранена и отправлена".to_string());
+    map.insert("sync_took_remote".to_string(), "Хранилище заменено удаленной версией".to_string());
+    map.insert("sync_relock".to_string(), "Хранилище изменилось на другом устройстве, войдите заново".to_string());
+    map.insert("audit_log_failed".to_string(), "Не удалось записать журнал аудита:".to_string());
+    map.insert("import_title".to_string(), "Импорт из CSV".to_string());
+    map.insert("import_path".to_string(), "Путь к CSV-файлу".to_string());
+    map.insert("import_mapping_title".to_string(), "Столбцы".to_string());
+    map.insert("import_rows".to_string(), "строк".to_string());
+    map.insert("import_field_skip".to_string(), "пропустить".to_string());
+    map.insert("import_field_name".to_string(), "имя".to_string());
+    map.insert("import_field_username".to_string(), "пользователь".to_string());
+    map.insert("import_field_password".to_string(), "пароль".to_string());
+    map.insert("import_field_url".to_string(), "URL".to_string());
+    map.insert("import_field_notes".to_string(), "заметки".to_string());
+    map.insert("import_no_name_column".to_string(), "Назначьте столбец с именем записи".to_string());
+    map.insert("import_mapping_restored".to_string(), "Применено сопоставление с прошлого импорта этого формата".to_string());
+    map.insert("import_created".to_string(), "Создано:".to_string());
+    map.insert("import_skipped".to_string(), "пропущено (без имени):".to_string());
+    map.insert("import_footer_path".to_string(), "Enter - открыть файл | Esc - отмена".to_string());
+    map.insert("import_footer_mapping".to_string(), "↑↓ - столбец | ←→ / Пробел - поле | Enter - импортировать | Esc - отмена".to_string());
+    map.insert("import_footer_done".to_string(), "Enter / Esc - к списку".to_string());
     
     // Help screen
     map.insert("help_title".to_string(), "Справка - Горячие клавиши".to_string());
     map.insert("help_navigation".to_string(), "Навигация: используйте прокрутку для просмотра".to_string());
-    map.insert("help_footer".to_string(), "F1 / Esc - закрыть справку".to_string());
+    map.insert("help_footer".to_string(), "F1 / Esc - закрыть справку | A - о программе".to_string());
+    map.insert("about_title".to_string(), "О программе".to_string());
+    map.insert("about_build".to_string(), "Сборка:".to_string());
+    map.insert("about_rng".to_string(), "Генератор случайных чисел:".to_string());
+    map.insert("about_rng_ok".to_string(), "работает".to_string());
+    map.insert("about_rng_failed".to_string(), "ошибка:".to_string());
+    map.insert("about_entropy".to_string(), "Пул энтропии ядра:".to_string());
+    map.insert("about_bits".to_string(), "бит".to_string());
+    map.insert("about_unknown".to_string(), "нет данных".to_string());
+    map.insert("about_crypto_crates".to_string(), "Криптографические библиотеки:".to_string());
+    map.insert("about_hardening".to_string(), "Параметры сборки:".to_string());
+    map.insert("about_hardened".to_string(), "защищенная".to_string());
+    map.insert("about_not_hardened".to_string(), "не все защиты включены".to_string());
+    map.insert("about_hardening_release_profile".to_string(), "оптимизированная (release) сборка".to_string());
+    map.insert("about_hardening_no_debug_assertions".to_string(), "без отладочных проверок".to_string());
+    map.insert("about_hardening_panic_abort".to_string(), "panic = abort".to_string());
+    map.insert("about_hardening_pie".to_string(), "позиционно-независимый исполняемый файл (PIE)".to_string());
+    map.insert("about_footer".to_string(), "Esc / F1 - назад к справке".to_string());
     map.insert("help_separator".to_string(), "═══════════════════════════════════════════════════════════════".to_string());
+    map.insert("help_key_space".to_string(), "Пробел".to_string());
     map.insert("help_main_screen_title".to_string(), "ГЛАВНЫЙ ЭКРАН".to_string());
-    map.insert("help_main_ctrl_q".to_string(), "  Ctrl+Q          - Выход из приложения".to_string());
-    map.insert("help_main_ctrl_n".to_string(), "  Ctrl+N          - Создать новый пароль".to_string());
-    map.insert("help_main_ctrl_e".to_string(), "  Ctrl+E          - Редактировать выбранный пароль".to_string());
-    map.insert("help_main_ctrl_c".to_string(), "  Ctrl+C          - Копировать пароль в буфер обмена".to_string());
-    map.insert("help_main_ctrl_s".to_string(), "  Ctrl+S          - Открыть настройки".to_string());
-    map.insert("help_main_f1".to_string(), "  F1              - Открыть эту справку".to_string());
-    map.insert("help_main_f2".to_string(), "  F2              - Открыть настройки".to_string());
-    map.insert("help_main_arrows".to_string(), "  ↑ / ↓           - Навигация по списку".to_string());
-    map.insert("help_main_esc".to_string(), "  Esc             - Сбросить поиск".to_string());
-    map.insert("help_main_backspace".to_string(), "  Backspace       - Удалить символ из поиска".to_string());
-    map.insert("help_main_type".to_string(), "  Ввод текста     - Поиск по паролям (fuzzy search)".to_string());
+    map.insert("help_main_ctrl_q".to_string(), "Выход из приложения".to_string());
+    map.insert("help_main_ctrl_n".to_string(), "Создать новый пароль".to_string());
+    map.insert("help_main_ctrl_e".to_string(), "Редактировать выбранный пароль".to_string());
+    map.insert("help_main_ctrl_d".to_string(), "Удалить выбранный пароль (с подтверждением)".to_string());
+    map.insert("help_main_ctrl_c".to_string(), "Копировать пароль в буфер обмена".to_string());
+    map.insert("help_main_ctrl_u".to_string(), "Копировать имя пользователя в буфер обмена".to_string());
+    map.insert("help_main_ctrl_b".to_string(), "Проверить пароль записи по базе утечек (HIBP)".to_string());
+    map.insert("help_main_ctrl_k".to_string(), "Копировать команду ssh для записи сервера".to_string());
+    map.insert("help_main_ctrl_o".to_string(), "Подключиться к серверу записи по SSH".to_string());
+    map.insert("help_main_ctrl_v".to_string(), "Автонабор: ввести логин и пароль в предыдущем окне (xdotool, wtype, macOS)".to_string());
+    map.insert("help_main_ctrl_s".to_string(), "Открыть настройки".to_string());
+    map.insert("help_main_f1".to_string(), "Открыть эту справку".to_string());
+    map.insert("help_main_f2".to_string(), "Открыть настройки".to_string());
+    map.insert("help_main_f3".to_string(), "Аудит: переиспользованные пароли".to_string());
+    map.insert("help_main_f4".to_string(), "Корзина: восстановить удаленные записи".to_string());
+    map.insert("help_main_ctrl_r".to_string(), "Пометить/снять пометку «сменить пароль»".to_string());
+    map.insert("help_main_f5".to_string(), "Смена помеченных паролей".to_string());
+    map.insert("help_main_f6".to_string(), "Обслуживание: удалить осиротевшие файлы и старую историю".to_string());
+    map.insert("help_main_f7".to_string(), "Импорт из CSV".to_string());
+    map.insert("help_main_f8".to_string(), "Резервная копия хранилища".to_string());
+    map.insert("help_main_f9".to_string(), "Откат к резервной копии".to_string());
+    map.insert("help_main_f10".to_string(), "Синхронизация через git".to_string());
+    map.insert("help_main_arrows".to_string(), "Навигация по списку".to_string());
+    map.insert("help_main_esc".to_string(), "Сбросить поиск".to_string());
+    map.insert("help_main_backspace".to_string(), "Удалить символ из поиска".to_string());
+    map.insert("help_main_type".to_string(), "Поиск по паролям (fuzzy search)".to_string());
+    map.insert("help_key_search".to_string(), "Ввод текста".to_string());
+    map.insert("help_main_tag".to_string(), "Только записи с тегом (можно несколько: #work #mail)".to_string());
+    map.insert("help_key_tag".to_string(), "#тег".to_string());
+    map.insert("help_main_folders".to_string(), "Открыть папку / на уровень выше".to_string());
+    map.insert("help_main_ctrl_f".to_string(), "Переместить запись в папку".to_string());
+    map.insert("help_main_ctrl_a".to_string(), "Убрать запись в архив / вернуть из архива".to_string());
+    map.insert("help_main_ctrl_g".to_string(), "Показать архив / вернуться к записям (архивные скрыты из списка и расширения)".to_string());
+    map.insert("help_main_ctrl_z".to_string(), "Приостановить (вернуться командой fg) / открыть оболочку".to_string());
+    map.insert("help_main_f11".to_string(), "Начать/закончить запись макроса в регистр (с главного экрана, на записи)".to_string());
+    map.insert("help_main_f12".to_string(), "Повторить макрос для каждой записи списка (Esc - прервать)".to_string());
+    map.insert("shell_escape_banner".to_string(), "rpm ждет завершения оболочки: введите exit, чтобы вернуться".to_string());
     map.insert("help_master_password_title".to_string(), "ЭКРАН МАСТЕР-ПАРОЛЯ".to_string());
-    map.insert("help_master_password_enter".to_string(), "  Enter           - Продолжить/создать мастер-пароль".to_string());
-    map.insert("help_master_password_arrows".to_string(), "  ↑ / ↓           - Переключение между полями".to_string());
-    map.insert("help_master_password_ctrl_h".to_string(), "  Ctrl+H          - Показать/скрыть пароль".to_string());
-    map.insert("help_master_password_f1".to_string(), "  F1              - Открыть справку".to_string());
-    map.insert("help_master_password_esc".to_string(), "  Esc             - Выход из приложения".to_string());
-    map.insert("help_master_password_backspace".to_string(), "  Backspace       - Удалить символ".to_string());
+    map.insert("help_master_password_enter".to_string(), "Продолжить/создать мастер-пароль".to_string());
+    map.insert("help_master_password_arrows".to_string(), "Переключение между полями".to_string());
+    map.insert("help_master_password_ctrl_h".to_string(), "Показать/скрыть пароль".to_string());
+    map.insert("help_master_password_f1".to_string(), "Открыть справку".to_string());
+    map.insert("help_master_password_esc".to_string(), "Выход из приложения".to_string());
+    map.insert("help_master_password_backspace".to_string(), "Удалить символ".to_string());
     map.insert("help_password_entry_title".to_string(), "ЭКРАН СОЗДАНИЯ/РЕДАКТИРОВАНИЯ ПАРОЛЯ".to_string());
-    map.insert("help_password_entry_enter".to_string(), "  Enter           - Сохранить пароль".to_string());
-    map.insert("help_password_entry_esc".to_string(), "  Esc             - Отмена и возврат к главному экрану".to_string());
-    map.insert("help_password_entry_arrows".to_string(), "  ↑ / ↓           - Переключение между полями (имя/пароль)".to_string());
-    map.insert("help_password_entry_ctrl_h".to_string(), "  Ctrl+H          - Показать/скрыть пароль".to_string());
-    map.insert("help_password_entry_ctrl_g".to_string(), "  Ctrl+G          - Открыть генератор паролей".to_string());
-    map.insert("help_password_entry_f1".to_string(), "  F1              - Открыть справку".to_string());
-    map.insert("help_password_entry_backspace".to_string(), "  Backspace       - Удалить символ".to_string());
+    map.insert("help_password_entry_enter".to_string(), "Сохранить пароль".to_string());
+    map.insert("help_password_entry_esc".to_string(), "Отмена и возврат к главному экрану".to_string());
+    map.insert("help_password_entry_arrows".to_string(), "Переключение между полями (имя/пользователь/пароль/URL/заметки)".to_string());
+    map.insert("help_password_entry_ctrl_h".to_string(), "Показать/скрыть пароль".to_string());
+    map.insert("help_password_entry_ctrl_g".to_string(), "Открыть генератор паролей".to_string());
+    map.insert("help_password_entry_f3".to_string(), "Открыть аудит".to_string());
+    map.insert("help_password_entry_f4".to_string(), "Контрольные вопросы".to_string());
+    map.insert("help_password_entry_f5".to_string(), "Дополнительные поля".to_string());
+    map.insert("help_password_entry_f6".to_string(), "SSH-подключение (хост, порт, пользователь)".to_string());
+    map.insert("help_password_entry_f7".to_string(), "Последовательность автонабора".to_string());
+    map.insert("help_password_entry_ctrl_t".to_string(), "Закрепить запись в меню трея / открепить".to_string());
+    map.insert("help_password_entry_ctrl_e".to_string(), "Редактировать заметки во внешнем редакторе ($EDITOR)".to_string());
+    map.insert("help_password_entry_f1".to_string(), "Открыть справку".to_string());
+    map.insert("help_password_entry_backspace".to_string(), "Удалить символ".to_string());
     map.insert("help_password_generator_title".to_string(), "ЭКРАН ГЕНЕРАТОРА ПАРОЛЕЙ".to_string());
-    map.insert("help_password_generator_enter".to_string(), "  Enter           - Сгенерировать пароль и вставить".to_string());
-    map.insert("help_password_generator_esc".to_string(), "  Esc             - Отмена и возврат к экрану пароля".to_string());
-    map.insert("help_password_generator_arrows".to_string(), "  ↑ / ↓           - Навигация по элементам".to_string());
-    map.insert("help_password_generator_space".to_string(), "  Space           - Переключить галочку (для наборов символов)".to_string());
-    map.insert("help_password_generator_backspace".to_string(), "  Backspace       - Удалить символ в активном поле".to_string());
-    map.insert("help_password_generator_type".to_string(), "  Ввод символов   - Ввод в активное поле (длина/исключения)".to_string());
-    map.insert("help_password_generator_f1".to_string(), "  F1              - Открыть справку".to_string());
+    map.insert("help_password_generator_enter".to_string(), "Сгенерировать пароль и вставить".to_string());
+    map.insert("help_password_generator_esc".to_string(), "Отмена и возврат к экрану пароля".to_string());
+    map.insert("help_password_generator_arrows".to_string(), "Навигация по элементам".to_string());
+    map.insert("help_password_generator_space".to_string(), "Переключить галочку (наборы символов, похожие символы)".to_string());
+    map.insert("help_password_generator_ctrl_p".to_string(), "Выбрать сохраненный пресет".to_string());
+    map.insert("help_password_generator_ctrl_s".to_string(), "Сохранить настройки как пресет".to_string());
+    map.insert("help_password_generator_backspace".to_string(), "Удалить символ в активном поле".to_string());
+    map.insert("help_password_generator_type".to_string(), "Ввод в активное поле (длина/исключения/свой набор/минимумы)".to_string());
+    map.insert("help_key_input".to_string(), "Ввод символов".to_string());
+    map.insert("help_password_generator_f1".to_string(), "Открыть справку".to_string());
     map.insert("help_settings_title".to_string(), "ЭКРАН НАСТРОЕК".to_string());
-    map.insert("help_settings_enter".to_string(), "  Enter           - Сохранить настройки".to_string());
-    map.insert("help_settings_esc".to_string(), "  Esc / Q         - Отмена и возврат к главному экрану".to_string());
-    map.insert("help_settings_arrows".to_string(), "  ↑ / ↓           - Переключение между полями".to_string());
-    map.insert("help_settings_f1".to_string(), "  F1              - Открыть справку".to_string());
-    map.insert("help_settings_backspace".to_string(), "  Backspace       - Удалить символ".to_string());
+    map.insert("help_settings_enter".to_string(), "Сохранить настройки".to_string());
+    map.insert("help_settings_esc".to_string(), "Отмена и возврат к главному экрану".to_string());
+    map.insert("help_settings_arrows".to_string(), "Переключение между полями".to_string());
+    map.insert("help_settings_f1".to_string(), "Открыть справку".to_string());
+    map.insert("help_settings_backspace".to_string(), "Удалить символ".to_string());
+    map.insert("help_audit_title".to_string(), "ЭКРАН АУДИТА".to_string());
+    map.insert("help_audit_close".to_string(), "Вернуться на предыдущий экран".to_string());
+    map.insert("help_security_questions_title".to_string(), "КОНТРОЛЬНЫЕ ВОПРОСЫ".to_string());
+    map.insert("help_security_questions_ctrl_n".to_string(), "Добавить вопрос".to_string());
+    map.insert("help_security_questions_ctrl_g".to_string(), "Сгенерировать случайный ответ".to_string());
+    map.insert("help_security_questions_ctrl_h".to_string(), "Показать/скрыть ответы".to_string());
+    map.insert("help_security_questions_ctrl_d".to_string(), "Удалить вопрос".to_string());
+    map.insert("help_security_questions_tab".to_string(), "Переключение вопрос/ответ".to_string());
+    map.insert("help_security_questions_close".to_string(), "Вернуться к записи".to_string());
+    map.insert("help_custom_fields_title".to_string(), "ДОПОЛНИТЕЛЬНЫЕ ПОЛЯ".to_string());
+    map.insert("help_custom_fields_ctrl_n".to_string(), "Добавить поле".to_string());
+    map.insert("help_custom_fields_ctrl_c".to_string(), "Копировать значение поля".to_string());
+    map.insert("help_custom_fields_ctrl_h".to_string(), "Показать/скрыть значения".to_string());
+    map.insert("help_custom_fields_ctrl_d".to_string(), "Удалить поле".to_string());
+    map.insert("help_custom_fields_tab".to_string(), "Переключение название/значение".to_string());
+    map.insert("help_custom_fields_close".to_string(), "Вернуться к записи".to_string());
+    map.insert("help_ssh_host_title".to_string(), "SSH-ПОДКЛЮЧЕНИЕ".to_string());
+    map.insert("help_ssh_host_arrows".to_string(), "Хост, порт, пользователь".to_string());
+    map.insert("help_ssh_host_close".to_string(), "Вернуться к записи".to_string());
+    map.insert("help_autotype_title".to_string(), "АВТОНАБОР".to_string());
+    map.insert("help_autotype_placeholders".to_string(), "поля записи и клавиши, {DELAY 500} - пауза".to_string());
+    map.insert("help_autotype_close".to_string(), "Вернуться к записи".to_string());
+    map.insert("help_access_window_title".to_string(), "ЧАСЫ ДОСТУПА".to_string());
+    map.insert("help_access_window_format".to_string(), "дни и часы, когда API выдает запись".to_string());
+    map.insert("help_access_window_close".to_string(), "Вернуться к записи".to_string());
+    map.insert("help_rotation_title".to_string(), "СМЕНА ПАРОЛЕЙ".to_string());
+    map.insert("help_rotation_ctrl_o".to_string(), "Открыть сайт записи".to_string());
+    map.insert("help_rotation_ctrl_g".to_string(), "Сгенерировать пароль по политике записи".to_string());
+    map.insert("help_rotation_copy".to_string(), "Скопировать старый / новый пароль".to_string());
+    map.insert("help_rotation_enter".to_string(), "Подтвердить смену (старый пароль сохраняется в истории)".to_string());
+    map.insert("help_rotation_tab".to_string(), "Пропустить запись".to_string());
+    map.insert("help_rotation_esc".to_string(), "Вернуться к главному экрану".to_string());
+    map.insert("help_trash_title".to_string(), "КОРЗИНА".to_string());
+    map.insert("help_trash_enter".to_string(), "Вернуть запись в список паролей".to_string());
+    map.insert("help_trash_delete".to_string(), "Удалить запись навсегда (с подтверждением)".to_string());
+    map.insert("help_trash_retention".to_string(), "Записи старше срока хранения из настроек удаляются при разблокировке".to_string());
     map.insert("help_help_title".to_string(), "СПРАВКА".to_string());
-    map.insert("help_help_close".to_string(), "  F1 / Esc        - Закрыть справку и вернуться".to_string());
+    map.insert("help_help_close".to_string(), "Закрыть справку и вернуться".to_string());
     
     // Common
     map.insert("show".to_string(), "показать".to_string());
@@ -253,14 +667,82 @@ fn get_english_translations() -> HashMap<String, String> {
     map.insert("master_password_confirm_label".to_string(), "Confirm:".to_string());
     map.insert("master_password_confirm".to_string(), "Confirm".to_string());
     map.insert("master_password_confirm_active".to_string(), "Confirm (active)".to_string());
+    map.insert("master_password_hint".to_string(), "Hint (optional, stored unencrypted)".to_string());
+    map.insert("master_password_hint_reveals".to_string(), "The hint must not contain the master password itself".to_string());
+    map.insert("master_password_hint_shown".to_string(), "Hint:".to_string());
     map.insert("master_password_show_hide".to_string(), "Ctrl+H - show/hide".to_string());
     map.insert("master_password_footer_create".to_string(), "Enter - continue/create | ↑↓ - switch fields | Ctrl+H - show/hide password | Esc - exit".to_string());
     map.insert("master_password_footer_enter".to_string(), "Enter - confirm | Ctrl+H - show/hide password | Esc - exit".to_string());
+    map.insert("policy_password_too_short".to_string(), "Master password is too short. Vault policy minimum:".to_string());
+    map.insert("policy_keyfile_required".to_string(), "Vault policy requires a key file, which this version does not support".to_string());
+    map.insert("tray_unlock_to_copy".to_string(), "Enter the master password to copy the password from the tray menu:".to_string());
+    map.insert("tray_copied".to_string(), "Password copied from the tray menu:".to_string());
+    map.insert("tray_tooltip_entries".to_string(), "Entries:".to_string());
+    map.insert("tray_tooltip_backup".to_string(), "Last backup:".to_string());
+    map.insert("tray_tooltip_days_ago".to_string(), "days ago".to_string());
+    map.insert("tray_tooltip_no_backup".to_string(), "none".to_string());
+    map.insert("tray_tooltip_audit".to_string(), "Audit findings:".to_string());
+    map.insert("tray_tooltip_no_audit".to_string(), "audit not run yet".to_string());
+    map.insert("tray_tooltip_sync".to_string(), "Sync:".to_string());
+    map.insert("tray_sync_off".to_string(), "off".to_string());
+    map.insert("tray_sync_idle".to_string(), "not run yet".to_string());
+    map.insert("tray_sync_ok".to_string(), "done".to_string());
+    map.insert("tray_sync_conflict".to_string(), "conflict".to_string());
+    map.insert("tray_sync_error".to_string(), "failed".to_string());
+    map.insert("instance_unlock_to_copy".to_string(), "Enter the master password to copy the password requested by rpm --copy:".to_string());
+    map.insert("instance_copied".to_string(), "Password copied as requested by rpm --copy:".to_string());
+    map.insert("instance_entry_not_found".to_string(), "rpm --copy: no entry found for".to_string());
+    map.insert("instance_focus_requested".to_string(), "rpm is already running in this window".to_string());
     
     // Main screen
-    map.insert("main_search".to_string(), "Search (start typing to filter)".to_string());
+    map.insert("main_search".to_string(), "Search (start typing to filter, #tag - by tag)".to_string());
     map.insert("main_passwords".to_string(), "Passwords".to_string());
-    map.insert("main_footer".to_string(), "F1 - help | Ctrl+Q - quit | Ctrl+N - new password | Ctrl+E - edit | Ctrl+C - copy password | Ctrl+S - settings | ↑↓ - navigation | Esc - reset search | Type to search".to_string());
+    map.insert("main_folder_up".to_string(), "Backspace - up".to_string());
+    map.insert("main_archive".to_string(), "Archive".to_string());
+    map.insert("main_archived".to_string(), "Archived:".to_string());
+    map.insert("main_unarchived".to_string(), "Restored from the archive:".to_string());
+    map.insert("main_top_level".to_string(), "top level".to_string());
+    map.insert("main_moved".to_string(), "Moved:".to_string());
+    map.insert("main_username_copied".to_string(), "Username copied to clipboard".to_string());
+    map.insert("main_no_username".to_string(), "This entry has no username".to_string());
+    map.insert("main_no_ssh_host".to_string(), "This entry has no SSH host (F6 in the entry editor)".to_string());
+    map.insert("main_ssh_command_copied".to_string(), "Command copied:".to_string());
+    map.insert("main_ssh_banner".to_string(), "RPM: SSH session, the password manager returns when it ends.".to_string());
+    map.insert("main_autotype_done".to_string(), "Username and password typed into the previous window".to_string());
+    map.insert("main_autotype_failed".to_string(), "Auto-type failed:".to_string());
+    map.insert("main_breach_found".to_string(), "Password found in breaches:".to_string());
+    map.insert("main_breach_clean".to_string(), "Password not found in known breaches:".to_string());
+    map.insert("main_move_title".to_string(), "Move to folder".to_string());
+    map.insert("main_move_folder".to_string(), "Folder (use /, e.g. work/servers)".to_string());
+    map.insert("main_move_hint".to_string(), "Enter - move | Esc - cancel | empty - top level".to_string());
+    map.insert("main_names_scrubbed".to_string(), "Names hidden while idle - press any key".to_string());
+    map.insert("main_loading".to_string(), "Loading entries".to_string());
+    map.insert("main_loading_failed".to_string(), "Could not load all entries:".to_string());
+    map.insert("macro_prompt_record".to_string(), "Record a macro: press a register letter (a-z)".to_string());
+    map.insert("macro_prompt_replay".to_string(), "Replay a macro on every listed entry: press a register letter (a-z)".to_string());
+    map.insert("macro_recording".to_string(), "Recording macro (F11 to stop)".to_string());
+    map.insert("macro_recording_badge".to_string(), "● REC".to_string());
+    map.insert("macro_register_badge".to_string(), "@? (a-z)".to_string());
+    map.insert("macro_saved".to_string(), "Macro saved".to_string());
+    map.insert("macro_stop_recording_first".to_string(), "Stop recording the macro first (F11)".to_string());
+    map.insert("macro_empty".to_string(), "Register is empty".to_string());
+    map.insert("macro_replaying".to_string(), "Replaying macro (Esc to stop)".to_string());
+    map.insert("macro_replayed".to_string(), "Macro replayed, entries:".to_string());
+    map.insert("macro_aborted".to_string(), "Macro replay stopped".to_string());
+    map.insert("macro_not_on_main".to_string(), "Replay stopped: a macro must end on the main screen".to_string());
+    map.insert("main_footer".to_string(), "F1 - help | Ctrl+Q - quit | Ctrl+N - new password | Ctrl+E - edit | Ctrl+D - delete | Ctrl+C - copy password | Ctrl+U - copy username | Ctrl+R - flag for rotation | Ctrl+S - settings | F3 - audit | F4 - trash | F5 - rotation | ↑↓ - navigation | Esc - reset search | Type to search".to_string());
+    map.insert("delete_confirm_title".to_string(), "Delete".to_string());
+    map.insert("delete_confirm_question".to_string(), "Move this entry to the trash?".to_string());
+    map.insert("delete_confirm_hint".to_string(), "Y / Enter - delete | N / Esc - cancel".to_string());
+    map.insert("pairing_title".to_string(), "Pair extension".to_string());
+    map.insert("pairing_question".to_string(), "A browser extension asks for access:".to_string());
+    map.insert("pairing_code".to_string(), "Code to enter in the extension:".to_string());
+    map.insert("pairing_hint".to_string(), "Y / Enter - allow | N / Esc - deny".to_string());
+    map.insert("pairing_requested".to_string(), "A browser extension asks to be paired".to_string());
+    map.insert("pairing_approved".to_string(), "Pairing allowed: enter the code in the extension".to_string());
+    map.insert("pairing_denied".to_string(), "Pairing denied".to_string());
+    map.insert("api_auth_failures".to_string(), "Wrong master passwords via the API:".to_string());
+    map.insert("api_auth_locked_until".to_string(), "locked out until".to_string());
     
     // Settings screen
     map.insert("settings_title".to_string(), "Settings".to_string());
@@ -269,6 +751,9 @@ fn get_english_translations() -> HashMap<String, String> {
     map.insert("settings_config_path_label".to_string(), "Configuration file path:".to_string());
     map.insert("settings_config_path_title".to_string(), "Configuration file".to_string());
     map.insert("settings_config_path_error".to_string(), "Could not determine".to_string());
+    map.insert("settings_api_port".to_string(), "Extension API:".to_string());
+    map.insert("settings_tls_fingerprint".to_string(), "Certificate SHA-256:".to_string());
+    map.insert("settings_api_not_running".to_string(), "Extension API is not running".to_string());
     map.insert("settings_directory_label".to_string(), "Passwords directory (leave empty to use default path):".to_string());
     map.insert("settings_directory".to_string(), "Directory path".to_string());
     map.insert("settings_directory_active".to_string(), "Directory path (active)".to_string());
@@ -281,92 +766,403 @@ fn get_english_translations() -> HashMap<String, String> {
     map.insert("settings_language_label".to_string(), "Interface language:".to_string());
     map.insert("settings_language".to_string(), "Language | Enter - select".to_string());
     map.insert("settings_language_active".to_string(), "Language (active) | Enter - select".to_string());
+    map.insert("settings_change_master".to_string(), "Master password".to_string());
+    map.insert("settings_change_master_active".to_string(), "Master password (active) | Enter - change".to_string());
+    map.insert("settings_screen_reader".to_string(), "Screen reader mode".to_string());
+    map.insert("settings_screen_reader_active".to_string(), "Screen reader mode (active) | Enter - toggle".to_string());
+    map.insert("settings_on".to_string(), "On".to_string());
+    map.insert("settings_off".to_string(), "Off".to_string());
+    map.insert("settings_keychain".to_string(), "Unlock with the OS keychain".to_string());
+    map.insert("settings_keychain_active".to_string(), "Unlock with the OS keychain (active) | Enter - toggle".to_string());
+    map.insert("keychain_failed".to_string(), "OS keychain unavailable:".to_string());
     map.insert("settings_footer".to_string(), "Enter - save/select | Esc - cancel | ↑↓ - switch fields | Enter value".to_string());
     
     // Password entry screen
     map.insert("password_entry_create_title".to_string(), "Create New Password".to_string());
     map.insert("password_entry_edit_title".to_string(), "Edit Password".to_string());
+    map.insert("password_entry_tray_pinned".to_string(), "pinned to tray".to_string());
     map.insert("password_entry_name_label".to_string(), "Name:".to_string());
     map.insert("password_entry_name".to_string(), "Name".to_string());
     map.insert("password_entry_name_active".to_string(), "Name (active)".to_string());
+    map.insert("password_entry_username".to_string(), "Username".to_string());
+    map.insert("password_entry_username_active".to_string(), "Username (active)".to_string());
     map.insert("password_entry_password_label".to_string(), "Password:".to_string());
     map.insert("password_entry_password".to_string(), "Password | Ctrl+H - show/hide".to_string());
     map.insert("password_entry_password_active".to_string(), "Password (active) | Ctrl+H - show/hide".to_string());
-    map.insert("password_entry_footer".to_string(), "Enter - save | Esc - cancel | ↑↓ - switch fields | Ctrl+H - show/hide password | Ctrl+G - password generator".to_string());
+    map.insert("password_entry_url".to_string(), "URL".to_string());
+    map.insert("password_entry_url_active".to_string(), "URL (active)".to_string());
+    map.insert("password_entry_tags".to_string(), "Tags (comma separated)".to_string());
+    map.insert("password_entry_tags_active".to_string(), "Tags (active, comma separated)".to_string());
+    map.insert("password_entry_notes".to_string(), "Notes".to_string());
+    map.insert("password_entry_notes_active".to_string(), "Notes (active) | Alt+Enter - new line | Ctrl+E - open in editor".to_string());
+    map.insert("password_entry_footer".to_string(), "Enter - save | Esc - cancel | ↑↓ - switch fields | Ctrl+H - show/hide password | Ctrl+G - password generator | Ctrl+E - notes in $EDITOR | Ctrl+T - pin to tray | F4 - security questions | F5 - custom fields | F6 - SSH | F7 - auto-type | F8 - API hours".to_string());
+    map.insert("password_entry_editor_failed".to_string(), "Could not edit the notes in the external editor:".to_string());
+    map.insert("password_entry_reuse_warning".to_string(), "This password is already used by:".to_string());
+    map.insert("password_entry_reuse_hint".to_string(), "Enter - save anyway | F3 - open audit".to_string());
+    map.insert("password_entry_created_at".to_string(), "Created:".to_string());
+    map.insert("password_entry_updated_at".to_string(), "Updated:".to_string());
+    map.insert("password_entry_last_used_at".to_string(), "Last copied:".to_string());
+    map.insert("password_entry_timestamp_unknown".to_string(), "—".to_string());
     
     // Password generator screen
     map.insert("password_generator_title".to_string(), "Password Generator".to_string());
     map.insert("password_generator_length_label".to_string(), "Password length:".to_string());
+    map.insert("password_generator_words_label".to_string(), "Number of words:".to_string());
     map.insert("password_generator_length".to_string(), "Length".to_string());
     map.insert("password_generator_length_active".to_string(), "Length (active)".to_string());
     map.insert("password_generator_exclude_label".to_string(), "Characters to exclude (empty by default):".to_string());
     map.insert("password_generator_exclude".to_string(), "Exclude".to_string());
     map.insert("password_generator_exclude_active".to_string(), "Exclude (active)".to_string());
+    map.insert("password_generator_custom_label".to_string(), "Custom characters (added to the sets):".to_string());
+    map.insert("password_generator_custom".to_string(), "Custom set".to_string());
+    map.insert("password_generator_custom_active".to_string(), "Custom set (active)".to_string());
     map.insert("password_generator_charsets_label".to_string(), "Character sets:".to_string());
     map.insert("password_generator_uppercase".to_string(), "Uppercase letters (A-Z)".to_string());
     map.insert("password_generator_lowercase".to_string(), "Lowercase letters (a-z)".to_string());
     map.insert("password_generator_digits".to_string(), "Digits (0-9)".to_string());
     map.insert("password_generator_special".to_string(), "Special characters (!@#$%...)".to_string());
-    map.insert("password_generator_footer".to_string(), "Enter - generate and insert | Esc - cancel | ↑↓ - navigation | Space - toggle checkbox | F1 - help".to_string());
+    map.insert("password_generator_cyrillic".to_string(), "Cyrillic letters (а-я, А-Я)".to_string());
+    map.insert("password_generator_passphrase".to_string(), "Passphrase of words (interface language wordlist)".to_string());
+    map.insert("password_generator_exclude_ambiguous".to_string(), "No look-alike characters (O/0, l/1/I, S/5)".to_string());
+    map.insert("password_generator_minimum".to_string(), "At least:".to_string());
+    map.insert("password_generator_min_digits".to_string(), "digits".to_string());
+    map.insert("password_generator_min_special".to_string(), "special".to_string());
+    map.insert("password_generator_min_uppercase".to_string(), "uppercase".to_string());
+    map.insert("password_generator_entropy".to_string(), "Entropy:".to_string());
+    map.insert("password_generator_entropy_bits".to_string(), "bits".to_string());
+    map.insert("password_generator_entropy_weak".to_string(), "weak".to_string());
+    map.insert("password_generator_entropy_fair".to_string(), "fair".to_string());
+    map.insert("password_generator_entropy_strong".to_string(), "strong".to_string());
+    map.insert("password_generator_min_digits_field".to_string(), "Minimum digits".to_string());
+    map.insert("password_generator_min_special_field".to_string(), "Minimum special characters".to_string());
+    map.insert("password_generator_min_uppercase_field".to_string(), "Minimum uppercase letters".to_string());
+    map.insert("password_generator_preset".to_string(), "preset".to_string());
+    map.insert("password_generator_presets_title".to_string(), "Generator presets".to_string());
+    map.insert("password_generator_presets_empty".to_string(), "No saved presets - Ctrl+S saves the current settings".to_string());
+    map.insert("password_generator_presets_hint".to_string(), "Enter - apply | Del - delete | Esc - close".to_string());
+    map.insert("password_generator_preset_chars".to_string(), "chars".to_string());
+    map.insert("password_generator_preset_words".to_string(), "words".to_string());
+    map.insert("password_generator_save_preset_title".to_string(), "Save preset".to_string());
+    map.insert("password_generator_preset_name".to_string(), "Preset name".to_string());
+    map.insert("password_generator_save_preset_hint".to_string(), "Enter - save (a preset with the same name is replaced) | Esc - cancel".to_string());
+    map.insert("password_generator_preset_invalid".to_string(), "Enter a valid length first".to_string());
+    map.insert("password_generator_footer".to_string(), "Enter - generate and insert | Esc - cancel | ↑↓ - navigation | Space - toggle checkbox | Ctrl+P - presets | Ctrl+S - save preset | F1 - help".to_string());
     
     // Theme selection screen
     map.insert("theme_selection_title".to_string(), "Select Interface Theme".to_string());
     map.insert("theme_selection_list_title".to_string(), "Select theme (↑↓ for navigation)".to_string());
-    map.insert("theme_selection_footer".to_string(), "Enter - select theme | Esc - cancel | ↑↓ - navigation | F1 - help".to_string());
+    map.insert("theme_selection_footer".to_string(), "Enter - select theme | Esc - cancel | ↑↓ - navigation | Type - jump | F1 - help".to_string());
     
     // Language selection screen
     map.insert("language_selection_title".to_string(), "Select Interface Language".to_string());
     map.insert("language_selection_list_title".to_string(), "Select language (↑↓ for navigation)".to_string());
-    map.insert("language_selection_footer".to_string(), "Enter - select language | Esc - cancel | ↑↓ - navigation | F1 - help".to_string());
+    map.insert("language_selection_footer".to_string(), "Enter - select language | Esc - cancel | ↑↓ - navigation | Type - jump | F1 - help".to_string());
+    
+    // Audit screen
+    map.insert("audit_title".to_string(), "Security Audit".to_string());
+    map.insert("audit_reuse_list_title".to_string(), "Reused passwords".to_string());
+    map.insert("audit_reused_password".to_string(), "Same password shared by entries".to_string());
+    map.insert("audit_reuse_risk".to_string(), "Risk".to_string());
+    map.insert("audit_bits".to_string(), "bits".to_string());
+    map.insert("audit_reuse_sites".to_string(), "Sites:".to_string());
+    map.insert("audit_no_reuse".to_string(), "✓ No reused passwords found".to_string());
+    map.insert("audit_breach_list_title".to_string(), "Found in breaches (offline HIBP dataset)".to_string());
+    map.insert("audit_breach_list_title_online".to_string(), "Found in breaches (HIBP online, k-anonymity)".to_string());
+    map.insert("audit_no_breaches".to_string(), "✓ No passwords found in known breaches".to_string());
+    map.insert("audit_breach_seen".to_string(), "times seen:".to_string());
+    map.insert("audit_breach_not_configured".to_string(), "Breach check not configured: set hibp_dataset_path or enable hibp_online in the configuration file".to_string());
+    map.insert("audit_breach_error".to_string(), "Breach check failed:".to_string());
+    map.insert("audit_footer".to_string(), "Esc / F3 - back | F1 - help".to_string());
+    map.insert("security_questions_title".to_string(), "Security questions".to_string());
+    map.insert("security_questions_list_title".to_string(), "Questions".to_string());
+    map.insert("security_questions_empty".to_string(), "No questions yet. Ctrl+N - add one".to_string());
+    map.insert("security_questions_question".to_string(), "Question".to_string());
+    map.insert("security_questions_answer".to_string(), "Answer".to_string());
+    map.insert("security_questions_footer".to_string(), "Ctrl+N - add | Ctrl+G - random answer | Ctrl+H - show/hide | Ctrl+D - delete | Tab - question/answer | Esc - back".to_string());
+    map.insert("custom_fields_title".to_string(), "Custom fields".to_string());
+    map.insert("custom_fields_list_title".to_string(), "Fields".to_string());
+    map.insert("custom_fields_empty".to_string(), "No fields. Ctrl+N - add".to_string());
+    map.insert("custom_fields_label".to_string(), "Label".to_string());
+    map.insert("custom_fields_value".to_string(), "Value".to_string());
+    map.insert("custom_fields_copied".to_string(), "Copied the value of".to_string());
+    map.insert("custom_fields_footer".to_string(), "Ctrl+N - add | Ctrl+C - copy value | Ctrl+H - show/hide | Ctrl+D - delete | Tab - label/value | Esc - back".to_string());
+    map.insert("ssh_host_title".to_string(), "SSH connection".to_string());
+    map.insert("ssh_host_host".to_string(), "Host".to_string());
+    map.insert("ssh_host_port".to_string(), "Port (empty - 22)".to_string());
+    map.insert("ssh_host_username".to_string(), "User".to_string());
+    map.insert("ssh_host_username_hint".to_string(), "empty - entry username".to_string());
+    map.insert("ssh_host_command".to_string(), "Command".to_string());
+    map.insert("ssh_host_no_command".to_string(), "Enter a host".to_string());
+    map.insert("ssh_host_invalid_port".to_string(), "The port must be a number from 1 to 65535".to_string());
+    map.insert("ssh_host_footer".to_string(), "↑↓/Tab - switch fields | Esc - back (saved with the entry) | On the main screen: Ctrl+K - copy command, Ctrl+O - connect".to_string());
+    map.insert("autotype_title".to_string(), "Auto-type".to_string());
+    map.insert("autotype_sequence".to_string(), "Sequence (empty - from the settings)".to_string());
+    map.insert("autotype_default".to_string(), "Default:".to_string());
+    map.insert("autotype_placeholders".to_string(), "{USERNAME} {PASSWORD} {URL} - entry fields, {TAB} {ENTER} - keys, {DELAY 500} - pause in ms, {{ }} - braces. Other text is typed as is.".to_string());
+    map.insert("autotype_footer".to_string(), "Esc - back (saved with the entry) | On the main screen: Ctrl+V - type into the previous window".to_string());
+    map.insert("access_window_title".to_string(), "API access hours".to_string());
+    map.insert("access_window_hours".to_string(), "Days and hours (empty - no restriction)".to_string());
+    map.insert("access_window_hint".to_string(), "Outside these hours the API does not serve the entry to extensions: e.g. work credentials only on weekdays from 8 to 20. The entry is always available in the app itself.\n\nExamples: 08:00-20:00, mon-fri 08:00-20:00, sat,sun 10:00-14:00, fri 22:00-02:00 (past midnight). Local time.".to_string());
+    map.insert("access_window_footer".to_string(), "Esc - back (saved with the entry)".to_string());
+    map.insert("rotation_title".to_string(), "Password rotation".to_string());
+    map.insert("rotation_queue_title".to_string(), "Needs rotation".to_string());
+    map.insert("rotation_empty".to_string(), "No flagged entries. Ctrl+R on the main screen flags one".to_string());
+    map.insert("rotation_url".to_string(), "URL | Ctrl+O - open".to_string());
+    map.insert("rotation_old_password".to_string(), "Current password | Ctrl+P - copy".to_string());
+    map.insert("rotation_new_password".to_string(), "New password | Ctrl+G - generate | Ctrl+C - copy".to_string());
+    map.insert("rotation_footer".to_string(), "Enter - confirm change | Tab - skip | Ctrl+H - show/hide | Esc - back".to_string());
+    map.insert("rotation_marked".to_string(), "Marked for rotation:".to_string());
+    map.insert("rotation_unmarked".to_string(), "No longer marked for rotation:".to_string());
+    map.insert("rotation_done".to_string(), "Password rotated:".to_string());
+    map.insert("export_done".to_string(), "Encrypted export saved:".to_string());
+    map.insert("export_failed".to_string(), "Scheduled export failed:".to_string());
+    map.insert("maintenance_title".to_string(), "Vault maintenance".to_string());
+    map.insert("maintenance_nothing".to_string(), "Nothing to clean up".to_string());
+    map.insert("maintenance_orphaned".to_string(), "Files without a def entry:".to_string());
+    map.insert("maintenance_history".to_string(), "Expired history passwords:".to_string());
+    map.insert("maintenance_days".to_string(), "days retention".to_string());
+    map.insert("maintenance_reclaimable".to_string(), "Will be reclaimed:".to_string());
+    map.insert("maintenance_reclaimed".to_string(), "Reclaimed:".to_string());
+    map.insert("maintenance_footer".to_string(), "Enter / Y - clean up | R - rotate key | V - verify | O - orphans | Esc - back".to_string());
+    map.insert("maintenance_footer_done".to_string(), "Done | R - rotate key | V - verify | O - orphans | Esc - back".to_string());
+    map.insert("verify_ok".to_string(), "Integrity check: every entry decrypts, entries:".to_string());
+    map.insert("verify_issues".to_string(), "Integrity check: problems found:".to_string());
+    map.insert("verify_unreadable_index".to_string(), "Unreadable:".to_string());
+    map.insert("verify_undecryptable_name".to_string(), "Name does not decrypt:".to_string());
+    map.insert("verify_missing_file".to_string(), "Entry file missing:".to_string());
+    map.insert("verify_corrupted_file".to_string(), "Corrupted:".to_string());
+    map.insert("verify_undecryptable_file".to_string(), "Does not decrypt:".to_string());
+    map.insert("verify_unreferenced_file".to_string(), "Not in the def file:".to_string());
+    map.insert("orphans_title".to_string(), "Orphaned files and entries".to_string());
+    map.insert("orphans_list_title".to_string(), "Files without an entry and entries without a file".to_string());
+    map.insert("orphans_none".to_string(), "No orphaned files or entries".to_string());
+    map.insert("orphans_file".to_string(), "file not in the def file".to_string());
+    map.insert("orphans_entry".to_string(), "entry without a file:".to_string());
+    map.insert("orphans_footer".to_string(), "Enter - re-link | Q - quarantine | Del - delete | ↑↓ - navigate | Esc - back".to_string());
+    map.insert("orphans_recovered_name".to_string(), "Recovered".to_string());
+    map.insert("orphans_relink_entry".to_string(), "This entry has no password file: it can only be quarantined or deleted".to_string());
+    map.insert("orphans_relinked".to_string(), "Re-linked:".to_string());
+    map.insert("orphans_quarantined".to_string(), "Moved to quarantine:".to_string());
+    map.insert("orphans_deleted".to_string(), "Deleted:".to_string());
+    map.insert("orphans_delete_question".to_string(), "Delete forever? It cannot be restored; quarantine would keep a copy.".to_string());
+    map.insert("rotate_key_prompt".to_string(), "Rotate encryption key: every vault file is re-encrypted, the master password stays the same".to_string());
+    map.insert("rotate_key_password".to_string(), "Master password:".to_string());
+    map.insert("rotate_key_footer".to_string(), "Enter - re-encrypt | Esc - cancel".to_string());
+    map.insert("rotate_key_done".to_string(), "Vault re-encrypted with a new key".to_string());
+    map.insert("change_master_title".to_string(), "Change master password".to_string());
+    map.insert("change_master_old".to_string(), "Current master password".to_string());
+    map.insert("change_master_new".to_string(), "New master password".to_string());
+    map.insert("change_master_confirm".to_string(), "Confirm".to_string());
+    map.insert("change_master_footer".to_string(), "Enter - next/change | ↑↓ - switch fields | Esc - cancel".to_string());
+    map.insert("change_master_wrong_old".to_string(), "Current master password is incorrect".to_string());
+    map.insert("change_master_empty".to_string(), "New master password cannot be empty".to_string());
+    map.insert("change_master_mismatch".to_string(), "Passwords do not match".to_string());
+    map.insert("change_master_argon2_memory".to_string(), "Argon2: memory, MiB".to_string());
+    map.insert("change_master_argon2_iterations".to_string(), "Argon2: passes".to_string());
+    map.insert("change_master_argon2_parallelism".to_string(), "Argon2: lanes".to_string());
+    map.insert("change_master_argon2_invalid".to_string(), "Invalid Argon2 parameters:".to_string());
+    map.insert("change_master_locked".to_string(), "Vault is locked".to_string());
+    map.insert("change_master_done".to_string(), "Master password changed, vault re-encrypted".to_string());
+    map.insert("backup_title".to_string(), "Back up vault".to_string());
+    map.insert("backup_path".to_string(), "Archive file".to_string());
+    map.insert("backup_passphrase".to_string(), "Archive passphrase (not the master password)".to_string());
+    map.insert("backup_hint".to_string(), "The archive holds all entries and the vault settings. Restoring needs the archive passphrase: rpm restore <file>".to_string());
+    map.insert("backup_footer".to_string(), "Enter - next/create | ↑↓ - switch fields | Esc - cancel".to_string());
+    map.insert("backup_empty_passphrase".to_string(), "The archive passphrase must not be empty".to_string());
+    map.insert("backup_done".to_string(), "Backup saved:".to_string());
+    map.insert("backup_entries".to_string(), "entries".to_string());
+    map.insert("restore_title".to_string(), "Roll back to a backup".to_string());
+    map.insert("restore_list_title".to_string(), "Backups (newest first)".to_string());
+    map.insert("restore_no_backups".to_string(), "No backups found".to_string());
+    map.insert("restore_footer".to_string(), "Enter - select | ↑↓ - navigate | Esc - back".to_string());
+    map.insert("restore_confirm_title".to_string(), "Confirm rollback".to_string());
+    map.insert("restore_warning".to_string(), "The current vault will be replaced. It is archived next to the selected backup first.".to_string());
+    map.insert("restore_confirm_hint".to_string(), "Enter - roll back | ↑↓ - switch fields | Esc - back to list".to_string());
+    map.insert("restore_wrong_passphrase".to_string(), "Wrong archive passphrase".to_string());
+    map.insert("restore_done".to_string(), "Restored:".to_string());
+    map.insert("restore_previous_saved".to_string(), "previous vault saved to".to_string());
+    map.insert("trash_title".to_string(), "Trash".to_string());
+    map.insert("trash_list_title".to_string(), "Deleted entries (newest first)".to_string());
+    map.insert("trash_empty".to_string(), "Trash is empty".to_string());
+    map.insert("trash_deleted_at".to_string(), "deleted".to_string());
+    map.insert("trash_footer".to_string(), "Enter - restore | Del - delete forever | ↑↓ - navigate | Esc - back".to_string());
+    map.insert("trash_restored".to_string(), "Restored:".to_string());
+    map.insert("trash_purged".to_string(), "Deleted forever:".to_string());
+    map.insert("trash_purge_question".to_string(), "Delete this entry forever? It cannot be restored.".to_string());
+    map.insert("trash_expired_purged".to_string(), "Removed from trash after the retention period:".to_string());
+    map.insert("sync_title".to_string(), "Git sync".to_string());
+    map.insert("sync_remote".to_string(), "Remote repository (URL)".to_string());
+    map.insert("sync_disabled".to_string(), "Sync is off. Enter a URL and press Enter: the passwords directory becomes a git repository and every change is committed.".to_string());
+    map.insert("sync_enabled".to_string(), "Every vault change is committed to git. Only encrypted files go into the repository.".to_string());
+    map.insert("sync_footer".to_string(), "Enter - save URL | Ctrl+P - pull | Ctrl+U - push | Esc - back".to_string());
+    map.insert("sync_conflict_footer".to_string(), "Ctrl+L - keep local version | Ctrl+T - take remote version | Esc - back".to_string());
+    map.insert("sync_saved".to_string(), "Sync set up".to_string());
+    map.insert("sync_not_set_up".to_string(), "Enter the remote repository URL and press Enter first".to_string());
+    map.insert("sync_up_to_date".to_string(), "No remote changes".to_string());
+    map.insert("sync_pulled".to_string(), "Remote changes pulled".to_string());
+    map.insert("sync_pushed".to_string(), "Changes pushed".to_string());
+    map.insert("sync_conflicts".to_string(), "Conflict: both sides changed these entries. Nothing was merged.".to_string());
+    map.insert("sync_conflict_def".to_string(), "(entry list)".to_string());
+    map.insert("sync_kept_local".to_string(), "Local version kept and pushed".to_string());
+    map.insert("sync_took_remote".to_string(), "Vault replaced with the remote version".to_string());
+    map.insert("sync_relock".to_string(), "The vault was changed on another device, unlock it again".to_string());
+    map.insert("audit_log_failed".to_string(), "Failed to write the audit log:".to_string());
+    map.insert("import_title".to_string(), "Import from CSV".to_string());
+    map.insert("import_path".to_string(), "Path to the CSV file".to_string());
+    map.insert("import_mapping_title".to_string(), "Columns".to_string());
+    map.insert("import_rows".to_string(), "rows".to_string());
+    map.insert("import_field_skip".to_string(), "skip".to_string());
+    map.insert("import_field_name".to_string(), "name".to_string());
+    map.insert("import_field_username".to_string(), "username".to_string());
+    map.insert("import_field_password".to_string(), "password".to_string());
+    map.insert("import_field_url".to_string(), "URL".to_string());
+    map.insert("import_field_notes".to_string(), "notes".to_string());
+    map.insert("import_no_name_column".to_string(), "Map a column to the entry name".to_string());
+    map.insert("import_mapping_restored".to_string(), "Using the mapping saved from the last import of this format".to_string());
+    map.insert("import_created".to_string(), "Created:".to_string());
+    map.insert("import_skipped".to_string(), "skipped (no name):".to_string());
+    map.insert("import_footer_path".to_string(), "Enter - open file | Esc - cancel".to_string());
+    map.insert("import_footer_mapping".to_string(), "↑↓ - column | ←→ / Space - field | Enter - import | Esc - cancel".to_string());
+    map.insert("import_footer_done".to_string(), "Enter / Esc - back to the list".to_string());
     
     // Help screen
     map.insert("help_title".to_string(), "Help - Hotkeys".to_string());
     map.insert("help_navigation".to_string(), "Navigation: use scroll to view".to_string());
-    map.insert("help_footer".to_string(), "F1 / Esc - close help".to_string());
+    map.insert("help_footer".to_string(), "F1 / Esc - close help | A - about".to_string());
+    map.insert("about_title".to_string(), "About".to_string());
+    map.insert("about_build".to_string(), "Build:".to_string());
+    map.insert("about_rng".to_string(), "Random generator:".to_string());
+    map.insert("about_rng_ok".to_string(), "working".to_string());
+    map.insert("about_rng_failed".to_string(), "failed:".to_string());
+    map.insert("about_entropy".to_string(), "Kernel entropy pool:".to_string());
+    map.insert("about_bits".to_string(), "bits".to_string());
+    map.insert("about_unknown".to_string(), "not reported".to_string());
+    map.insert("about_crypto_crates".to_string(), "Crypto libraries:".to_string());
+    map.insert("about_hardening".to_string(), "Build settings:".to_string());
+    map.insert("about_hardened".to_string(), "hardened".to_string());
+    map.insert("about_not_hardened".to_string(), "not all protections enabled".to_string());
+    map.insert("about_hardening_release_profile".to_string(), "optimized (release) build".to_string());
+    map.insert("about_hardening_no_debug_assertions".to_string(), "no debug assertions".to_string());
+    map.insert("about_hardening_panic_abort".to_string(), "panic = abort".to_string());
+    map.insert("about_hardening_pie".to_string(), "position-independent executable (PIE)".to_string());
+    map.insert("about_footer".to_string(), "Esc / F1 - back to help".to_string());
     map.insert("help_separator".to_string(), "═══════════════════════════════════════════════════════════════".to_string());
+    map.insert("help_key_space".to_string(), "Space".to_string());
     map.insert("help_main_screen_title".to_string(), "MAIN SCREEN".to_string());
-    map.insert("help_main_ctrl_q".to_string(), "  Ctrl+Q          - Quit application".to_string());
-    map.insert("help_main_ctrl_n".to_string(), "  Ctrl+N          - Create new password".to_string());
-    map.insert("help_main_ctrl_e".to_string(), "  Ctrl+E          - Edit selected password".to_string());
-    map.insert("help_main_ctrl_c".to_string(), "  Ctrl+C          - Copy password to clipboard".to_string());
-    map.insert("help_main_ctrl_s".to_string(), "  Ctrl+S          - Open settings".to_string());
-    map.insert("help_main_f1".to_string(), "  F1              - Open this help".to_string());
-    map.insert("help_main_f2".to_string(), "  F2              - Open settings".to_string());
-    map.insert("help_main_arrows".to_string(), "  ↑ / ↓           - Navigate list".to_string());
-    map.insert("help_main_esc".to_string(), "  Esc             - Reset search".to_string());
-    map.insert("help_main_backspace".to_string(), "  Backspace       - Delete character from search".to_string());
-    map.insert("help_main_type".to_string(), "  Type text       - Search passwords (fuzzy search)".to_string());
+    map.insert("help_main_ctrl_q".to_string(), "Quit application".to_string());
+    map.insert("help_main_ctrl_n".to_string(), "Create new password".to_string());
+    map.insert("help_main_ctrl_e".to_string(), "Edit selected password".to_string());
+    map.insert("help_main_ctrl_d".to_string(), "Delete the selected password (asks for confirmation)".to_string());
+    map.insert("help_main_ctrl_c".to_string(), "Copy password to clipboard".to_string());
+    map.insert("help_main_ctrl_u".to_string(), "Copy username to clipboard".to_string());
+    map.insert("help_main_ctrl_b".to_string(), "Check the entry's password against breaches (HIBP)".to_string());
+    map.insert("help_main_ctrl_k".to_string(), "Copy the ssh command of a server entry".to_string());
+    map.insert("help_main_ctrl_o".to_string(), "Connect to the entry's server over SSH".to_string());
+    map.insert("help_main_ctrl_v".to_string(), "Auto-type the username and password into the previous window (xdotool, wtype, macOS)".to_string());
+    map.insert("help_main_ctrl_s".to_string(), "Open settings".to_string());
+    map.insert("help_main_f1".to_string(), "Open this help".to_string());
+    map.insert("help_main_f2".to_string(), "Open settings".to_string());
+    map.insert("help_main_f3".to_string(), "Audit: reused passwords".to_string());
+    map.insert("help_main_f4".to_string(), "Trash: restore deleted entries".to_string());
+    map.insert("help_main_ctrl_r".to_string(), "Mark/unmark the entry as needing rotation".to_string());
+    map.insert("help_main_f5".to_string(), "Rotate flagged passwords".to_string());
+    map.insert("help_main_f6".to_string(), "Maintenance: remove orphaned files and old history".to_string());
+    map.insert("help_main_f7".to_string(), "Import from CSV".to_string());
+    map.insert("help_main_f8".to_string(), "Back up the vault".to_string());
+    map.insert("help_main_f9".to_string(), "Roll back to a backup".to_string());
+    map.insert("help_main_f10".to_string(), "Git sync".to_string());
+    map.insert("help_main_arrows".to_string(), "Navigate list".to_string());
+    map.insert("help_main_esc".to_string(), "Reset search".to_string());
+    map.insert("help_main_backspace".to_string(), "Delete character from search".to_string());
+    map.insert("help_main_type".to_string(), "Search passwords (fuzzy search)".to_string());
+    map.insert("help_key_search".to_string(), "Type text".to_string());
+    map.insert("help_main_tag".to_string(), "Only entries with the tag (several allowed: #work #mail)".to_string());
+    map.insert("help_key_tag".to_string(), "#tag".to_string());
+    map.insert("help_main_folders".to_string(), "Open a folder / go up a level".to_string());
+    map.insert("help_main_ctrl_f".to_string(), "Move the entry to a folder".to_string());
+    map.insert("help_main_ctrl_a".to_string(), "Archive the entry / restore it from the archive".to_string());
+    map.insert("help_main_ctrl_g".to_string(), "Show the archive / back to the entries (archived ones are hidden from the list and the extension)".to_string());
+    map.insert("help_main_ctrl_z".to_string(), "Suspend (resume with fg) / open a shell".to_string());
+    map.insert("help_main_f11".to_string(), "Start/stop recording a macro into a register (from the main screen, on an entry)".to_string());
+    map.insert("help_main_f12".to_string(), "Replay a macro on every listed entry (Esc stops)".to_string());
+    map.insert("shell_escape_banner".to_string(), "rpm is waiting for the shell: type exit to return".to_string());
     map.insert("help_master_password_title".to_string(), "MASTER PASSWORD SCREEN".to_string());
-    map.insert("help_master_password_enter".to_string(), "  Enter           - Continue/create master password".to_string());
-    map.insert("help_master_password_arrows".to_string(), "  ↑ / ↓           - Switch between fields".to_string());
-    map.insert("help_master_password_ctrl_h".to_string(), "  Ctrl+H          - Show/hide password".to_string());
-    map.insert("help_master_password_f1".to_string(), "  F1              - Open help".to_string());
-    map.insert("help_master_password_esc".to_string(), "  Esc             - Quit application".to_string());
-    map.insert("help_master_password_backspace".to_string(), "  Backspace       - Delete character".to_string());
+    map.insert("help_master_password_enter".to_string(), "Continue/create master password".to_string());
+    map.insert("help_master_password_arrows".to_string(), "Switch between fields".to_string());
+    map.insert("help_master_password_ctrl_h".to_string(), "Show/hide password".to_string());
+    map.insert("help_master_password_f1".to_string(), "Open help".to_string());
+    map.insert("help_master_password_esc".to_string(), "Quit application".to_string());
+    map.insert("help_master_password_backspace".to_string(), "Delete character".to_string());
     map.insert("help_password_entry_title".to_string(), "PASSWORD ENTRY SCREEN".to_string());
-    map.insert("help_password_entry_enter".to_string(), "  Enter           - Save password".to_string());
-    map.insert("help_password_entry_esc".to_string(), "  Esc             - Cancel and return to main screen".to_string());
-    map.insert("help_password_entry_arrows".to_string(), "  ↑ / ↓           - Switch between fields (name/password)".to_string());
-    map.insert("help_password_entry_ctrl_h".to_string(), "  Ctrl+H          - Show/hide password".to_string());
-    map.insert("help_password_entry_ctrl_g".to_string(), "  Ctrl+G          - Open password generator".to_string());
-    map.insert("help_password_entry_f1".to_string(), "  F1              - Open help".to_string());
-    map.insert("help_password_entry_backspace".to_string(), "  Backspace       - Delete character".to_string());
+    map.insert("help_password_entry_enter".to_string(), "Save password".to_string());
+    map.insert("help_password_entry_esc".to_string(), "Cancel and return to main screen".to_string());
+    map.insert("help_password_entry_arrows".to_string(), "Switch between fields (name/username/password/URL/notes)".to_string());
+    map.insert("help_password_entry_ctrl_h".to_string(), "Show/hide password".to_string());
+    map.insert("help_password_entry_ctrl_g".to_string(), "Open password generator".to_string());
+    map.insert("help_password_entry_f3".to_string(), "Open audit".to_string());
+    map.insert("help_password_entry_f4".to_string(), "Security questions".to_string());
+    map.insert("help_password_entry_f5".to_string(), "Custom fields".to_string());
+    map.insert("help_password_entry_f6".to_string(), "SSH connection (host, port, user)".to_string());
+    map.insert("help_password_entry_f7".to_string(), "Auto-type sequence".to_string());
+    map.insert("help_password_entry_ctrl_t".to_string(), "Pin the entry to the tray menu / unpin".to_string());
+    map.insert("help_password_entry_ctrl_e".to_string(), "Edit notes in an external editor ($EDITOR)".to_string());
+    map.insert("help_password_entry_f1".to_string(), "Open help".to_string());
+    map.insert("help_password_entry_backspace".to_string(), "Delete character".to_string());
     map.insert("help_password_generator_title".to_string(), "PASSWORD GENERATOR SCREEN".to_string());
-    map.insert("help_password_generator_enter".to_string(), "  Enter           - Generate password and insert".to_string());
-    map.insert("help_password_generator_esc".to_string(), "  Esc             - Cancel and return to password screen".to_string());
-    map.insert("help_password_generator_arrows".to_string(), "  ↑ / ↓           - Navigate elements".to_string());
-    map.insert("help_password_generator_space".to_string(), "  Space           - Toggle checkbox (for character sets)".to_string());
-    map.insert("help_password_generator_backspace".to_string(), "  Backspace       - Delete character in active field".to_string());
-    map.insert("help_password_generator_type".to_string(), "  Type characters - Input in active field (length/exclude)".to_string());
-    map.insert("help_password_generator_f1".to_string(), "  F1              - Open help".to_string());
+    map.insert("help_password_generator_enter".to_string(), "Generate password and insert".to_string());
+    map.insert("help_password_generator_esc".to_string(), "Cancel and return to password screen".to_string());
+    map.insert("help_password_generator_arrows".to_string(), "Navigate elements".to_string());
+    map.insert("help_password_generator_space".to_string(), "Toggle checkbox (character sets, look-alikes)".to_string());
+    map.insert("help_password_generator_ctrl_p".to_string(), "Pick a saved preset".to_string());
+    map.insert("help_password_generator_ctrl_s".to_string(), "Save the settings as a preset".to_string());
+    map.insert("help_password_generator_backspace".to_string(), "Delete character in active field".to_string());
+    map.insert("help_password_generator_type".to_string(), "Input in active field (length/exclude/custom set/minimums)".to_string());
+    map.insert("help_key_input".to_string(), "Type characters".to_string());
+    map.insert("help_password_generator_f1".to_string(), "Open help".to_string());
     map.insert("help_settings_title".to_string(), "SETTINGS SCREEN".to_string());
-    map.insert("help_settings_enter".to_string(), "  Enter           - Save settings".to_string());
-    map.insert("help_settings_esc".to_string(), "  Esc / Q         - Cancel and return to main screen".to_string());
-    map.insert("help_settings_arrows".to_string(), "  ↑ / ↓           - Switch between fields".to_string());
-    map.insert("help_settings_f1".to_string(), "  F1              - Open help".to_string());
-    map.insert("help_settings_backspace".to_string(), "  Backspace       - Delete character".to_string());
+    map.insert("help_settings_enter".to_string(), "Save settings".to_string());
+    map.insert("help_settings_esc".to_string(), "Cancel and return to main screen".to_string());
+    map.insert("help_settings_arrows".to_string(), "Switch between fields".to_string());
+    map.insert("help_settings_f1".to_string(), "Open help".to_string());
+    map.insert("help_settings_backspace".to_string(), "Delete character".to_string());
+    map.insert("help_audit_title".to_string(), "AUDIT SCREEN".to_string());
+    map.insert("help_audit_close".to_string(), "Return to previous screen".to_string());
+    map.insert("help_security_questions_title".to_string(), "SECURITY QUESTIONS".to_string());
+    map.insert("help_security_questions_ctrl_n".to_string(), "Add a question".to_string());
+    map.insert("help_security_questions_ctrl_g".to_string(), "Generate a random answer".to_string());
+    map.insert("help_security_questions_ctrl_h".to_string(), "Show/hide answers".to_string());
+    map.insert("help_security_questions_ctrl_d".to_string(), "Delete the question".to_string());
+    map.insert("help_security_questions_tab".to_string(), "Switch question/answer".to_string());
+    map.insert("help_security_questions_close".to_string(), "Back to the entry".to_string());
+    map.insert("help_custom_fields_title".to_string(), "CUSTOM FIELDS".to_string());
+    map.insert("help_custom_fields_ctrl_n".to_string(), "Add a field".to_string());
+    map.insert("help_custom_fields_ctrl_c".to_string(), "Copy the field value".to_string());
+    map.insert("help_custom_fields_ctrl_h".to_string(), "Show/hide values".to_string());
+    map.insert("help_custom_fields_ctrl_d".to_string(), "Delete the field".to_string());
+    map.insert("help_custom_fields_tab".to_string(), "Switch label/value".to_string());
+    map.insert("help_custom_fields_close".to_string(), "Back to the entry".to_string());
+    map.insert("help_ssh_host_title".to_string(), "SSH CONNECTION".to_string());
+    map.insert("help_ssh_host_arrows".to_string(), "Host, port, user".to_string());
+    map.insert("help_ssh_host_close".to_string(), "Back to the entry".to_string());
+    map.insert("help_autotype_title".to_string(), "AUTO-TYPE".to_string());
+    map.insert("help_autotype_placeholders".to_string(), "entry fields and keys, {DELAY 500} - pause".to_string());
+    map.insert("help_autotype_close".to_string(), "Back to the entry".to_string());
+    map.insert("help_access_window_title".to_string(), "API ACCESS HOURS".to_string());
+    map.insert("help_access_window_format".to_string(), "days and hours the API serves the entry".to_string());
+    map.insert("help_access_window_close".to_string(), "Back to the entry".to_string());
+    map.insert("help_rotation_title".to_string(), "PASSWORD ROTATION".to_string());
+    map.insert("help_rotation_ctrl_o".to_string(), "Open the entry's site".to_string());
+    map.insert("help_rotation_ctrl_g".to_string(), "Generate a password using the entry's policy".to_string());
+    map.insert("help_rotation_copy".to_string(), "Copy the old / new password".to_string());
+    map.insert("help_rotation_enter".to_string(), "Confirm the change (old password is kept in history)".to_string());
+    map.insert("help_rotation_tab".to_string(), "Skip the entry".to_string());
+    map.insert("help_rotation_esc".to_string(), "Back to the main screen".to_string());
+    map.insert("help_trash_title".to_string(), "TRASH".to_string());
+    map.insert("help_trash_enter".to_string(), "Put the entry back into the password list".to_string());
+    map.insert("help_trash_delete".to_string(), "Delete the entry forever (asks to confirm)".to_string());
+    map.insert("help_trash_retention".to_string(), "Entries older than the configured retention period are removed on unlock".to_string());
     map.insert("help_help_title".to_string(), "HELP".to_string());
-    map.insert("help_help_close".to_string(), "  F1 / Esc        - Close help and return".to_string());
+    map.insert("help_help_close".to_string(), "Close help and return".to_string());
     
     // Common
     map.insert("show".to_string(), "show".to_string());
@@ -391,14 +1187,82 @@ fn get_chinese_translations() -> HashMap<String, String> {
     map.insert("master_password_confirm_label".to_string(), "确认：".to_string());
     map.insert("master_password_confirm".to_string(), "确认".to_string());
     map.insert("master_password_confirm_active".to_string(), "确认（活动）".to_string());
+    map.insert("master_password_hint".to_string(), "提示（可选，以明文存储）".to_string());
+    map.insert("master_password_hint_reveals".to_string(), "提示不能包含主密码本身".to_string());
+    map.insert("master_password_hint_shown".to_string(), "提示：".to_string());
     map.insert("master_password_show_hide".to_string(), "Ctrl+H - 显示/隐藏".to_string());
     map.insert("master_password_footer_create".to_string(), "Enter - 继续/创建 | ↑↓ - 切换字段 | Ctrl+H - 显示/隐藏密码 | Esc - 退出".to_string());
     map.insert("master_password_footer_enter".to_string(), "Enter - 确认 | Ctrl+H - 显示/隐藏密码 | Esc - 退出".to_string());
+    map.insert("policy_password_too_short".to_string(), "主密码太短。保管库策略要求的最小长度：".to_string());
+    map.insert("policy_keyfile_required".to_string(), "保管库策略要求密钥文件，但此版本不支持".to_string());
+    map.insert("tray_unlock_to_copy".to_string(), "输入主密码以从托盘菜单复制密码：".to_string());
+    map.insert("tray_copied".to_string(), "已从托盘菜单复制密码：".to_string());
+    map.insert("tray_tooltip_entries".to_string(), "条目：".to_string());
+    map.insert("tray_tooltip_backup".to_string(), "最近备份：".to_string());
+    map.insert("tray_tooltip_days_ago".to_string(), "天前".to_string());
+    map.insert("tray_tooltip_no_backup".to_string(), "无".to_string());
+    map.insert("tray_tooltip_audit".to_string(), "审计发现：".to_string());
+    map.insert("tray_tooltip_no_audit".to_string(), "尚未运行审计".to_string());
+    map.insert("tray_tooltip_sync".to_string(), "同步：".to_string());
+    map.insert("tray_sync_off".to_string(), "关闭".to_string());
+    map.insert("tray_sync_idle".to_string(), "尚未运行".to_string());
+    map.insert("tray_sync_ok".to_string(), "完成".to_string());
+    map.insert("tray_sync_conflict".to_string(), "冲突".to_string());
+    map.insert("tray_sync_error".to_string(), "失败".to_string());
+    map.insert("instance_unlock_to_copy".to_string(), "输入主密码以复制 rpm --copy 请求的密码：".to_string());
+    map.insert("instance_copied".to_string(), "已按 rpm --copy 请求复制密码：".to_string());
+    map.insert("instance_entry_not_found".to_string(), "rpm --copy：未找到条目：".to_string());
+    map.insert("instance_focus_requested".to_string(), "rpm 已在此窗口中运行".to_string());
     
     // Main screen
-    map.insert("main_search".to_string(), "搜索（开始输入以过滤）".to_string());
+    map.insert("main_search".to_string(), "搜索（开始输入以过滤，#标签 - 按标签）".to_string());
     map.insert("main_passwords".to_string(), "密码".to_string());
-    map.insert("main_footer".to_string(), "F1 - 帮助 | Ctrl+Q - 退出 | Ctrl+N - 新密码 | Ctrl+E - 编辑 | Ctrl+C - 复制密码 | Ctrl+S - 设置 | ↑↓ - 导航 | Esc - 重置搜索 | 输入以搜索".to_string());
+    map.insert("main_folder_up".to_string(), "Backspace - 上一级".to_string());
+    map.insert("main_archive".to_string(), "归档".to_string());
+    map.insert("main_archived".to_string(), "已归档：".to_string());
+    map.insert("main_unarchived".to_string(), "已从归档恢复：".to_string());
+    map.insert("main_top_level".to_string(), "顶层".to_string());
+    map.insert("main_moved".to_string(), "已移动：".to_string());
+    map.insert("main_username_copied".to_string(), "用户名已复制到剪贴板".to_string());
+    map.insert("main_no_username".to_string(), "此条目没有用户名".to_string());
+    map.insert("main_no_ssh_host".to_string(), "此条目没有 SSH 主机（在条目编辑器中按 F6）".to_string());
+    map.insert("main_ssh_command_copied".to_string(), "命令已复制：".to_string());
+    map.insert("main_ssh_banner".to_string(), "RPM：SSH 会话，结束后返回密码管理器。".to_string());
+    map.insert("main_autotype_done".to_string(), "用户名和密码已输入到上一个窗口".to_string());
+    map.insert("main_autotype_failed".to_string(), "自动输入失败：".to_string());
+    map.insert("main_breach_found".to_string(), "密码出现在泄露中：".to_string());
+    map.insert("main_breach_clean".to_string(), "密码未出现在已知泄露中：".to_string());
+    map.insert("main_move_title".to_string(), "移动到文件夹".to_string());
+    map.insert("main_move_folder".to_string(), "文件夹（用 / 分隔，例如 work/servers）".to_string());
+    map.insert("main_move_hint".to_string(), "Enter - 移动 | Esc - 取消 | 留空 - 顶层".to_string());
+    map.insert("main_names_scrubbed".to_string(), "因闲置已隐藏名称 - 按任意键显示".to_string());
+    map.insert("main_loading".to_string(), "正在加载条目".to_string());
+    map.insert("main_loading_failed".to_string(), "无法加载全部条目:".to_string());
+    map.insert("macro_prompt_record".to_string(), "录制宏：请按寄存器字母 (a-z)".to_string());
+    map.insert("macro_prompt_replay".to_string(), "对列表中所有条目重放宏：请按寄存器字母 (a-z)".to_string());
+    map.insert("macro_recording".to_string(), "正在录制宏（F11 停止）".to_string());
+    map.insert("macro_recording_badge".to_string(), "● 录制".to_string());
+    map.insert("macro_register_badge".to_string(), "@? (a-z)".to_string());
+    map.insert("macro_saved".to_string(), "宏已保存".to_string());
+    map.insert("macro_stop_recording_first".to_string(), "请先停止录制宏 (F11)".to_string());
+    map.insert("macro_empty".to_string(), "寄存器为空".to_string());
+    map.insert("macro_replaying".to_string(), "正在重放宏（Esc 停止）".to_string());
+    map.insert("macro_replayed".to_string(), "宏已重放，条目数：".to_string());
+    map.insert("macro_aborted".to_string(), "宏重放已停止".to_string());
+    map.insert("macro_not_on_main".to_string(), "重放已停止：宏必须在主屏幕结束".to_string());
+    map.insert("main_footer".to_string(), "F1 - 帮助 | Ctrl+Q - 退出 | Ctrl+N - 新密码 | Ctrl+E - 编辑 | Ctrl+D - 删除 | Ctrl+C - 复制密码 | Ctrl+U - 复制用户名 | Ctrl+R - 标记轮换 | Ctrl+S - 设置 | F3 - 审计 | F4 - 回收站 | F5 - 轮换 | ↑↓ - 导航 | Esc - 重置搜索 | 输入以搜索".to_string());
+    map.insert("delete_confirm_title".to_string(), "删除".to_string());
+    map.insert("delete_confirm_question".to_string(), "将此条目移至回收站？".to_string());
+    map.insert("delete_confirm_hint".to_string(), "Y / Enter - 删除 | N / Esc - 取消".to_string());
+    map.insert("pairing_title".to_string(), "配对扩展".to_string());
+    map.insert("pairing_question".to_string(), "浏览器扩展请求访问：".to_string());
+    map.insert("pairing_code".to_string(), "在扩展中输入的代码：".to_string());
+    map.insert("pairing_hint".to_string(), "Y / Enter - 允许 | N / Esc - 拒绝".to_string());
+    map.insert("pairing_requested".to_string(), "浏览器扩展请求配对".to_string());
+    map.insert("pairing_approved".to_string(), "已允许配对：请在扩展中输入代码".to_string());
+    map.insert("pairing_denied".to_string(), "已拒绝配对".to_string());
+    map.insert("api_auth_failures".to_string(), "通过 API 输入的错误主密码：".to_string());
+    map.insert("api_auth_locked_until".to_string(), "锁定至".to_string());
     
     // Settings screen
     map.insert("settings_title".to_string(), "设置".to_string());
@@ -407,6 +1271,9 @@ fn get_chinese_translations() -> HashMap<String, String> {
     map.insert("settings_config_path_label".to_string(), "配置文件路径：".to_string());
     map.insert("settings_config_path_title".to_string(), "配置文件".to_string());
     map.insert("settings_config_path_error".to_string(), "无法确定".to_string());
+    map.insert("settings_api_port".to_string(), "扩展 API：".to_string());
+    map.insert("settings_tls_fingerprint".to_string(), "证书 SHA-256：".to_string());
+    map.insert("settings_api_not_running".to_string(), "扩展 API 未运行".to_string());
     map.insert("settings_directory_label".to_string(), "密码目录（留空以使用默认路径）：".to_string());
     map.insert("settings_directory".to_string(), "目录路径".to_string());
     map.insert("settings_directory_active".to_string(), "目录路径（活动）".to_string());
@@ -419,92 +1286,403 @@ fn get_chinese_translations() -> HashMap<String, String> {
     map.insert("settings_language_label".to_string(), "界面语言：".to_string());
     map.insert("settings_language".to_string(), "语言 | Enter - 选择".to_string());
     map.insert("settings_language_active".to_string(), "语言（活动） | Enter - 选择".to_string());
+    map.insert("settings_change_master".to_string(), "主密码".to_string());
+    map.insert("settings_change_master_active".to_string(), "主密码（活动）| Enter - 更改".to_string());
+    map.insert("settings_screen_reader".to_string(), "屏幕阅读器模式".to_string());
+    map.insert("settings_screen_reader_active".to_string(), "屏幕阅读器模式（活动）| Enter - 切换".to_string());
+    map.insert("settings_on".to_string(), "开启".to_string());
+    map.insert("settings_off".to_string(), "关闭".to_string());
+    map.insert("settings_keychain".to_string(), "通过系统钥匙串解锁".to_string());
+    map.insert("settings_keychain_active".to_string(), "通过系统钥匙串解锁（活动）| Enter - 切换".to_string());
+    map.insert("keychain_failed".to_string(), "系统钥匙串不可用：".to_string());
     map.insert("settings_footer".to_string(), "Enter - 保存/选择 | Esc - 取消 | ↑↓ - 切换字段 | 输入值".to_string());
     
     // Password entry screen
     map.insert("password_entry_create_title".to_string(), "创建新密码".to_string());
     map.insert("password_entry_edit_title".to_string(), "编辑密码".to_string());
+    map.insert("password_entry_tray_pinned".to_string(), "已固定到托盘".to_string());
     map.insert("password_entry_name_label".to_string(), "名称：".to_string());
     map.insert("password_entry_name".to_string(), "名称".to_string());
     map.insert("password_entry_name_active".to_string(), "名称（活动）".to_string());
+    map.insert("password_entry_username".to_string(), "用户名".to_string());
+    map.insert("password_entry_username_active".to_string(), "用户名（活动）".to_string());
     map.insert("password_entry_password_label".to_string(), "密码：".to_string());
     map.insert("password_entry_password".to_string(), "密码 | Ctrl+H - 显示/隐藏".to_string());
     map.insert("password_entry_password_active".to_string(), "密码（活动） | Ctrl+H - 显示/隐藏".to_string());
-    map.insert("password_entry_footer".to_string(), "Enter - 保存 | Esc - 取消 | ↑↓ - 切换字段 | Ctrl+H - 显示/隐藏密码 | Ctrl+G - 密码生成器".to_string());
+    map.insert("password_entry_url".to_string(), "网址".to_string());
+    map.insert("password_entry_url_active".to_string(), "网址（活动）".to_string());
+    map.insert("password_entry_tags".to_string(), "标签（逗号分隔）".to_string());
+    map.insert("password_entry_tags_active".to_string(), "标签（活动，逗号分隔）".to_string());
+    map.insert("password_entry_notes".to_string(), "备注".to_string());
+    map.insert("password_entry_notes_active".to_string(), "备注（活动）| Alt+Enter - 换行 | Ctrl+E - 在编辑器中打开".to_string());
+    map.insert("password_entry_footer".to_string(), "Enter - 保存 | Esc - 取消 | ↑↓ - 切换字段 | Ctrl+H - 显示/隐藏密码 | Ctrl+G - 密码生成器 | Ctrl+E - 在 $EDITOR 中编辑备注 | Ctrl+T - 固定到托盘 | F4 - 安全问题 | F5 - 自定义字段 | F6 - SSH | F7 - 自动输入 | F8 - API 时间".to_string());
+    map.insert("password_entry_editor_failed".to_string(), "无法在外部编辑器中编辑备注：".to_string());
+    map.insert("password_entry_reuse_warning".to_string(), "此密码已被以下条目使用:".to_string());
+    map.insert("password_entry_reuse_hint".to_string(), "Enter - 仍然保存 | F3 - 打开审计".to_string());
+    map.insert("password_entry_created_at".to_string(), "创建于：".to_string());
+    map.insert("password_entry_updated_at".to_string(), "修改于：".to_string());
+    map.insert("password_entry_last_used_at".to_string(), "上次复制：".to_string());
+    map.insert("password_entry_timestamp_unknown".to_string(), "—".to_string());
     
     // Password generator screen
     map.insert("password_generator_title".to_string(), "密码生成器".to_string());
     map.insert("password_generator_length_label".to_string(), "密码长度：".to_string());
+    map.insert("password_generator_words_label".to_string(), "单词数量：".to_string());
     map.insert("password_generator_length".to_string(), "长度".to_string());
     map.insert("password_generator_length_active".to_string(), "长度（活动）".to_string());
     map.insert("password_generator_exclude_label".to_string(), "要排除的字符（默认为空）：".to_string());
     map.insert("password_generator_exclude".to_string(), "排除".to_string());
     map.insert("password_generator_exclude_active".to_string(), "排除（活动）".to_string());
+    map.insert("password_generator_custom_label".to_string(), "自定义字符（追加到字符集）：".to_string());
+    map.insert("password_generator_custom".to_string(), "自定义字符集".to_string());
+    map.insert("password_generator_custom_active".to_string(), "自定义字符集（活动）".to_string());
     map.insert("password_generator_charsets_label".to_string(), "字符集：".to_string());
     map.insert("password_generator_uppercase".to_string(), "大写字母 (A-Z)".to_string());
     map.insert("password_generator_lowercase".to_string(), "小写字母 (a-z)".to_string());
     map.insert("password_generator_digits".to_string(), "数字 (0-9)".to_string());
     map.insert("password_generator_special".to_string(), "特殊字符 (!@#$%...)".to_string());
-    map.insert("password_generator_footer".to_string(), "Enter - 生成并插入 | Esc - 取消 | ↑↓ - 导航 | Space - 切换复选框 | F1 - 帮助".to_string());
+    map.insert("password_generator_cyrillic".to_string(), "西里尔字母 (а-я, А-Я)".to_string());
+    map.insert("password_generator_passphrase".to_string(), "单词短语（界面语言词表）".to_string());
+    map.insert("password_generator_exclude_ambiguous".to_string(), "排除易混淆字符（O/0、l/1/I、S/5）".to_string());
+    map.insert("password_generator_minimum".to_string(), "至少：".to_string());
+    map.insert("password_generator_min_digits".to_string(), "个数字".to_string());
+    map.insert("password_generator_min_special".to_string(), "个特殊字符".to_string());
+    map.insert("password_generator_min_uppercase".to_string(), "个大写字母".to_string());
+    map.insert("password_generator_entropy".to_string(), "熵：".to_string());
+    map.insert("password_generator_entropy_bits".to_string(), "位".to_string());
+    map.insert("password_generator_entropy_weak".to_string(), "弱".to_string());
+    map.insert("password_generator_entropy_fair".to_string(), "中等".to_string());
+    map.insert("password_generator_entropy_strong".to_string(), "强".to_string());
+    map.insert("password_generator_min_digits_field".to_string(), "最少数字数".to_string());
+    map.insert("password_generator_min_special_field".to_string(), "最少特殊字符数".to_string());
+    map.insert("password_generator_min_uppercase_field".to_string(), "最少大写字母数".to_string());
+    map.insert("password_generator_preset".to_string(), "预设".to_string());
+    map.insert("password_generator_presets_title".to_string(), "生成器预设".to_string());
+    map.insert("password_generator_presets_empty".to_string(), "没有已保存的预设 - Ctrl+S 保存当前设置".to_string());
+    map.insert("password_generator_presets_hint".to_string(), "Enter - 应用 | Del - 删除 | Esc - 关闭".to_string());
+    map.insert("password_generator_preset_chars".to_string(), "个字符".to_string());
+    map.insert("password_generator_preset_words".to_string(), "个单词".to_string());
+    map.insert("password_generator_save_preset_title".to_string(), "保存预设".to_string());
+    map.insert("password_generator_preset_name".to_string(), "预设名称".to_string());
+    map.insert("password_generator_save_preset_hint".to_string(), "Enter - 保存（同名预设将被替换） | Esc - 取消".to_string());
+    map.insert("password_generator_preset_invalid".to_string(), "请先输入有效的长度".to_string());
+    map.insert("password_generator_footer".to_string(), "Enter - 生成并插入 | Esc - 取消 | ↑↓ - 导航 | Space - 切换复选框 | Ctrl+P - 预设 | Ctrl+S - 保存预设 | F1 - 帮助".to_string());
     
     // Theme selection screen
     map.insert("theme_selection_title".to_string(), "选择界面主题".to_string());
     map.insert("theme_selection_list_title".to_string(), "选择主题（↑↓ 导航）".to_string());
-    map.insert("theme_selection_footer".to_string(), "Enter - 选择主题 | Esc - 取消 | ↑↓ - 导航 | F1 - 帮助".to_string());
+    map.insert("theme_selection_footer".to_string(), "Enter - 选择主题 | Esc - 取消 | ↑↓ - 导航 | 输入字母 - 跳转 | F1 - 帮助".to_string());
     
     // Language selection screen
     map.insert("language_selection_title".to_string(), "选择界面语言".to_string());
     map.insert("language_selection_list_title".to_string(), "选择语言（↑↓ 导航）".to_string());
-    map.insert("language_selection_footer".to_string(), "Enter - 选择语言 | Esc - 取消 | ↑↓ - 导航 | F1 - 帮助".to_string());
+    map.insert("language_selection_footer".to_string(), "Enter - 选择语言 | Esc - 取消 | ↑↓ - 导航 | 输入字母 - 跳转 | F1 - 帮助".to_string());
+    
+    // Audit screen
+    map.insert("audit_title".to_string(), "安全审计".to_string());
+    map.insert("audit_reuse_list_title".to_string(), "重复使用的密码".to_string());
+    map.insert("audit_reused_password".to_string(), "多个条目共用同一密码".to_string());
+    map.insert("audit_reuse_risk".to_string(), "风险".to_string());
+    map.insert("audit_bits".to_string(), "位".to_string());
+    map.insert("audit_reuse_sites".to_string(), "网站：".to_string());
+    map.insert("audit_no_reuse".to_string(), "✓ 未发现重复使用的密码".to_string());
+    map.insert("audit_breach_list_title".to_string(), "在泄露中发现(离线 HIBP 数据集)".to_string());
+    map.insert("audit_breach_list_title_online".to_string(), "出现在泄露中（HIBP 在线，k-匿名）".to_string());
+    map.insert("audit_no_breaches".to_string(), "✓ 未发现已知泄露中的密码".to_string());
+    map.insert("audit_breach_seen".to_string(), "出现次数:".to_string());
+    map.insert("audit_breach_not_configured".to_string(), "未配置泄露检查：请在配置文件中设置 hibp_dataset_path 或启用 hibp_online".to_string());
+    map.insert("audit_breach_error".to_string(), "泄露检查失败:".to_string());
+    map.insert("audit_footer".to_string(), "Esc / F3 - 返回 | F1 - 帮助".to_string());
+    map.insert("security_questions_title".to_string(), "安全问题".to_string());
+    map.insert("security_questions_list_title".to_string(), "问题".to_string());
+    map.insert("security_questions_empty".to_string(), "暂无问题。Ctrl+N - 添加".to_string());
+    map.insert("security_questions_question".to_string(), "问题".to_string());
+    map.insert("security_questions_answer".to_string(), "答案".to_string());
+    map.insert("security_questions_footer".to_string(), "Ctrl+N - 添加 | Ctrl+G - 随机答案 | Ctrl+H - 显示/隐藏 | Ctrl+D - 删除 | Tab - 问题/答案 | Esc - 返回".to_string());
+    map.insert("custom_fields_title".to_string(), "自定义字段".to_string());
+    map.insert("custom_fields_list_title".to_string(), "字段".to_string());
+    map.insert("custom_fields_empty".to_string(), "没有字段。Ctrl+N - 添加".to_string());
+    map.insert("custom_fields_label".to_string(), "名称".to_string());
+    map.insert("custom_fields_value".to_string(), "值".to_string());
+    map.insert("custom_fields_copied".to_string(), "已复制字段值：".to_string());
+    map.insert("custom_fields_footer".to_string(), "Ctrl+N - 添加 | Ctrl+C - 复制值 | Ctrl+H - 显示/隐藏 | Ctrl+D - 删除 | Tab - 名称/值 | Esc - 返回".to_string());
+    map.insert("ssh_host_title".to_string(), "SSH 连接".to_string());
+    map.insert("ssh_host_host".to_string(), "主机".to_string());
+    map.insert("ssh_host_port".to_string(), "端口（留空 - 22）".to_string());
+    map.insert("ssh_host_username".to_string(), "用户".to_string());
+    map.insert("ssh_host_username_hint".to_string(), "留空 - 条目用户名".to_string());
+    map.insert("ssh_host_command".to_string(), "命令".to_string());
+    map.insert("ssh_host_no_command".to_string(), "请输入主机".to_string());
+    map.insert("ssh_host_invalid_port".to_string(), "端口必须是 1 到 65535 之间的数字".to_string());
+    map.insert("ssh_host_footer".to_string(), "↑↓/Tab - 切换字段 | Esc - 返回（随条目保存） | 在主屏幕：Ctrl+K - 复制命令，Ctrl+O - 连接".to_string());
+    map.insert("autotype_title".to_string(), "自动输入".to_string());
+    map.insert("autotype_sequence".to_string(), "序列（留空 - 使用设置）".to_string());
+    map.insert("autotype_default".to_string(), "默认：".to_string());
+    map.insert("autotype_placeholders".to_string(), "{USERNAME} {PASSWORD} {URL} - 条目字段，{TAB} {ENTER} - 按键，{DELAY 500} - 暂停（毫秒），{{ }} - 花括号。其他文本按原样输入。".to_string());
+    map.insert("autotype_footer".to_string(), "Esc - 返回（随条目保存） | 在主屏幕：Ctrl+V - 输入到上一个窗口".to_string());
+    map.insert("access_window_title".to_string(), "API 访问时间".to_string());
+    map.insert("access_window_hours".to_string(), "日期和时间（留空 - 不限制）".to_string());
+    map.insert("access_window_hint".to_string(), "在这些时间之外，API 不会向扩展提供此条目：例如工作凭据仅在工作日 8 点到 20 点可用。在应用内条目始终可用。\n\n示例：08:00-20:00、mon-fri 08:00-20:00、sat,sun 10:00-14:00、fri 22:00-02:00（跨午夜）。使用本地时间。".to_string());
+    map.insert("access_window_footer".to_string(), "Esc - 返回（随条目保存）".to_string());
+    map.insert("rotation_title".to_string(), "密码轮换".to_string());
+    map.insert("rotation_queue_title".to_string(), "需要轮换".to_string());
+    map.insert("rotation_empty".to_string(), "没有已标记的条目。在主屏幕按 Ctrl+R 标记".to_string());
+    map.insert("rotation_url".to_string(), "网址 | Ctrl+O - 打开".to_string());
+    map.insert("rotation_old_password".to_string(), "当前密码 | Ctrl+P - 复制".to_string());
+    map.insert("rotation_new_password".to_string(), "新密码 | Ctrl+G - 生成 | Ctrl+C - 复制".to_string());
+    map.insert("rotation_footer".to_string(), "Enter - 确认更改 | Tab - 跳过 | Ctrl+H - 显示/隐藏 | Esc - 返回".to_string());
+    map.insert("rotation_marked".to_string(), "已标记为需要轮换：".to_string());
+    map.insert("rotation_unmarked".to_string(), "已取消轮换标记：".to_string());
+    map.insert("rotation_done".to_string(), "密码已轮换：".to_string());
+    map.insert("export_done".to_string(), "加密导出已保存：".to_string());
+    map.insert("export_failed".to_string(), "计划导出失败：".to_string());
+    map.insert("maintenance_title".to_string(), "保险库维护".to_string());
+    map.insert("maintenance_nothing".to_string(), "没有需要清理的内容".to_string());
+    map.insert("maintenance_orphaned".to_string(), "没有 def 条目的文件：".to_string());
+    map.insert("maintenance_history".to_string(), "过期的历史密码：".to_string());
+    map.insert("maintenance_days".to_string(), "天保留期".to_string());
+    map.insert("maintenance_reclaimable".to_string(), "将释放：".to_string());
+    map.insert("maintenance_reclaimed".to_string(), "已释放：".to_string());
+    map.insert("maintenance_footer".to_string(), "Enter / Y - 清理 | R - 轮换密钥 | V - 校验 | O - 孤立项 | Esc - 返回".to_string());
+    map.insert("maintenance_footer_done".to_string(), "完成 | R - 轮换密钥 | V - 校验 | O - 孤立项 | Esc - 返回".to_string());
+    map.insert("verify_ok".to_string(), "完整性校验：所有条目均可解密，条目数：".to_string());
+    map.insert("verify_issues".to_string(), "完整性校验：发现问题数：".to_string());
+    map.insert("verify_unreadable_index".to_string(), "无法读取：".to_string());
+    map.insert("verify_undecryptable_name".to_string(), "名称无法解密：".to_string());
+    map.insert("verify_missing_file".to_string(), "条目文件缺失：".to_string());
+    map.insert("verify_corrupted_file".to_string(), "已损坏：".to_string());
+    map.insert("verify_undecryptable_file".to_string(), "无法解密：".to_string());
+    map.insert("verify_unreferenced_file".to_string(), "不在 def 文件中：".to_string());
+    map.insert("orphans_title".to_string(), "孤立文件和条目".to_string());
+    map.insert("orphans_list_title".to_string(), "没有条目的文件和没有文件的条目".to_string());
+    map.insert("orphans_none".to_string(), "没有孤立文件或条目".to_string());
+    map.insert("orphans_file".to_string(), "不在 def 文件中的文件".to_string());
+    map.insert("orphans_entry".to_string(), "缺少文件的条目：".to_string());
+    map.insert("orphans_footer".to_string(), "Enter - 重新关联 | Q - 隔离 | Del - 删除 | ↑↓ - 导航 | Esc - 返回".to_string());
+    map.insert("orphans_recovered_name".to_string(), "已恢复".to_string());
+    map.insert("orphans_relink_entry".to_string(), "该条目没有密码文件：只能隔离或删除".to_string());
+    map.insert("orphans_relinked".to_string(), "已重新关联：".to_string());
+    map.insert("orphans_quarantined".to_string(), "已移至隔离区：".to_string());
+    map.insert("orphans_deleted".to_string(), "已删除：".to_string());
+    map.insert("orphans_delete_question".to_string(), "永久删除？将无法恢复；隔离会保留一份副本。".to_string());
+    map.insert("rotate_key_prompt".to_string(), "轮换加密密钥：所有保险库文件将被重新加密，主密码保持不变".to_string());
+    map.insert("rotate_key_password".to_string(), "主密码：".to_string());
+    map.insert("rotate_key_footer".to_string(), "Enter - 重新加密 | Esc - 取消".to_string());
+    map.insert("rotate_key_done".to_string(), "保险库已使用新密钥重新加密".to_string());
+    map.insert("change_master_title".to_string(), "更改主密码".to_string());
+    map.insert("change_master_old".to_string(), "当前主密码".to_string());
+    map.insert("change_master_new".to_string(), "新主密码".to_string());
+    map.insert("change_master_confirm".to_string(), "确认".to_string());
+    map.insert("change_master_footer".to_string(), "Enter - 下一步/更改 | ↑↓ - 切换字段 | Esc - 取消".to_string());
+    map.insert("change_master_wrong_old".to_string(), "当前主密码不正确".to_string());
+    map.insert("change_master_empty".to_string(), "新主密码不能为空".to_string());
+    map.insert("change_master_mismatch".to_string(), "密码不匹配".to_string());
+    map.insert("change_master_argon2_memory".to_string(), "Argon2：内存（MiB）".to_string());
+    map.insert("change_master_argon2_iterations".to_string(), "Argon2：迭代次数".to_string());
+    map.insert("change_master_argon2_parallelism".to_string(), "Argon2：并行度".to_string());
+    map.insert("change_master_argon2_invalid".to_string(), "Argon2 参数无效：".to_string());
+    map.insert("change_master_locked".to_string(), "保险库已锁定".to_string());
+    map.insert("change_master_done".to_string(), "主密码已更改，保险库已重新加密".to_string());
+    map.insert("backup_title".to_string(), "备份密码库".to_string());
+    map.insert("backup_path".to_string(), "归档文件".to_string());
+    map.insert("backup_passphrase".to_string(), "归档密码（非主密码）".to_string());
+    map.insert("backup_hint".to_string(), "归档包含所有条目和密码库设置。恢复时需要归档密码：rpm restore <文件>".to_string());
+    map.insert("backup_footer".to_string(), "Enter - 下一步/创建 | ↑↓ - 切换字段 | Esc - 取消".to_string());
+    map.insert("backup_empty_passphrase".to_string(), "归档密码不能为空".to_string());
+    map.insert("backup_done".to_string(), "备份已保存：".to_string());
+    map.insert("backup_entries".to_string(), "个条目".to_string());
+    map.insert("restore_title".to_string(), "回滚到备份".to_string());
+    map.insert("restore_list_title".to_string(), "备份（最新在前）".to_string());
+    map.insert("restore_no_backups".to_string(), "未找到备份".to_string());
+    map.insert("restore_footer".to_string(), "Enter - 选择 | ↑↓ - 导航 | Esc - 返回".to_string());
+    map.insert("restore_confirm_title".to_string(), "确认回滚".to_string());
+    map.insert("restore_warning".to_string(), "当前密码库将被替换。替换前会将其归档到所选备份旁边。".to_string());
+    map.insert("restore_confirm_hint".to_string(), "Enter - 回滚 | ↑↓ - 切换字段 | Esc - 返回列表".to_string());
+    map.insert("restore_wrong_passphrase".to_string(), "归档密码错误".to_string());
+    map.insert("restore_done".to_string(), "已恢复：".to_string());
+    map.insert("restore_previous_saved".to_string(), "先前的密码库已保存到".to_string());
+    map.insert("trash_title".to_string(), "回收站".to_string());
+    map.insert("trash_list_title".to_string(), "已删除的条目（最新在前）".to_string());
+    map.insert("trash_empty".to_string(), "回收站为空".to_string());
+    map.insert("trash_deleted_at".to_string(), "删除于".to_string());
+    map.insert("trash_footer".to_string(), "Enter - 恢复 | Del - 永久删除 | ↑↓ - 导航 | Esc - 返回".to_string());
+    map.insert("trash_restored".to_string(), "已恢复：".to_string());
+    map.insert("trash_purged".to_string(), "已永久删除：".to_string());
+    map.insert("trash_purge_question".to_string(), "永久删除此条目？删除后无法恢复。".to_string());
+    map.insert("trash_expired_purged".to_string(), "超过保留期限已从回收站移除：".to_string());
+    map.insert("sync_title".to_string(), "Git 同步".to_string());
+    map.insert("sync_remote".to_string(), "远程仓库 (URL)".to_string());
+    map.insert("sync_disabled".to_string(), "同步已关闭。输入 URL 并按 Enter：密码目录将成为 git 仓库，每次更改都会提交。".to_string());
+    map.insert("sync_enabled".to_string(), "每次密码库更改都会提交到 git。仓库中只包含加密文件。".to_string());
+    map.insert("sync_footer".to_string(), "Enter - 保存 URL | Ctrl+P - 拉取 | Ctrl+U - 推送 | Esc - 返回".to_string());
+    map.insert("sync_conflict_footer".to_string(), "Ctrl+L - 保留本地版本 | Ctrl+T - 采用远程版本 | Esc - 返回".to_string());
+    map.insert("sync_saved".to_string(), "同步已设置".to_string());
+    map.insert("sync_not_set_up".to_string(), "请先输入远程仓库 URL 并按 Enter".to_string());
+    map.insert("sync_up_to_date".to_string(), "远程没有更改".to_string());
+    map.insert("sync_pulled".to_string(), "已拉取远程更改".to_string());
+    map.insert("sync_pushed".to_string(), "已推送更改".to_string());
+    map.insert("sync_conflicts".to_string(), "冲突：双方都修改了这些条目。未进行合并。".to_string());
+    map.insert("sync_conflict_def".to_string(), "(条目列表)".to_string());
+    map.insert("sync_kept_local".to_string(), "已保留并推送本地版本".to_string());
+    map.insert("sync_took_remote".to_string(), "密码库已替换为远程版本".to_string());
+    map.insert("sync_relock".to_string(), "密码库已在其他设备上更改，请重新解锁".to_string());
+    map.insert("audit_log_failed".to_string(), "无法写入审计日志：".to_string());
+    map.insert("import_title".to_string(), "从 CSV 导入".to_string());
+    map.insert("import_path".to_string(), "CSV 文件路径".to_string());
+    map.insert("import_mapping_title".to_string(), "列".to_string());
+    map.insert("import_rows".to_string(), "行".to_string());
+    map.insert("import_field_skip".to_string(), "跳过".to_string());
+    map.insert("import_field_name".to_string(), "名称".to_string());
+    map.insert("import_field_username".to_string(), "用户名".to_string());
+    map.insert("import_field_password".to_string(), "密码".to_string());
+    map.insert("import_field_url".to_string(), "网址".to_string());
+    map.insert("import_field_notes".to_string(), "备注".to_string());
+    map.insert("import_no_name_column".to_string(), "请为条目名称指定一列".to_string());
+    map.insert("import_mapping_restored".to_string(), "已应用上次导入此格式时保存的映射".to_string());
+    map.insert("import_created".to_string(), "已创建：".to_string());
+    map.insert("import_skipped".to_string(), "已跳过（无名称）：".to_string());
+    map.insert("import_footer_path".to_string(), "Enter - 打开文件 | Esc - 取消".to_string());
+    map.insert("import_footer_mapping".to_string(), "↑↓ - 列 | ←→ / 空格 - 字段 | Enter - 导入 | Esc - 取消".to_string());
+    map.insert("import_footer_done".to_string(), "Enter / Esc - 返回列表".to_string());
     
     // Help screen
     map.insert("help_title".to_string(), "帮助 - 快捷键".to_string());
     map.insert("help_navigation".to_string(), "导航：使用滚动查看".to_string());
-    map.insert("help_footer".to_string(), "F1 / Esc - 关闭帮助".to_string());
+    map.insert("help_footer".to_string(), "F1 / Esc - 关闭帮助 | A - 关于".to_string());
+    map.insert("about_title".to_string(), "关于".to_string());
+    map.insert("about_build".to_string(), "构建：".to_string());
+    map.insert("about_rng".to_string(), "随机数生成器：".to_string());
+    map.insert("about_rng_ok".to_string(), "正常".to_string());
+    map.insert("about_rng_failed".to_string(), "失败：".to_string());
+    map.insert("about_entropy".to_string(), "内核熵池：".to_string());
+    map.insert("about_bits".to_string(), "位".to_string());
+    map.insert("about_unknown".to_string(), "未提供".to_string());
+    map.insert("about_crypto_crates".to_string(), "加密库：".to_string());
+    map.insert("about_hardening".to_string(), "构建设置：".to_string());
+    map.insert("about_hardened".to_string(), "已加固".to_string());
+    map.insert("about_not_hardened".to_string(), "并非所有保护都已启用".to_string());
+    map.insert("about_hardening_release_profile".to_string(), "优化（release）构建".to_string());
+    map.insert("about_hardening_no_debug_assertions".to_string(), "无调试断言".to_string());
+    map.insert("about_hardening_panic_abort".to_string(), "panic = abort".to_string());
+    map.insert("about_hardening_pie".to_string(), "位置无关可执行文件（PIE）".to_string());
+    map.insert("about_footer".to_string(), "Esc / F1 - 返回帮助".to_string());
     map.insert("help_separator".to_string(), "═══════════════════════════════════════════════════════════════".to_string());
+    map.insert("help_key_space".to_string(), "空格".to_string());
     map.insert("help_main_screen_title".to_string(), "主屏幕".to_string());
-    map.insert("help_main_ctrl_q".to_string(), "  Ctrl+Q          - 退出应用程序".to_string());
-    map.insert("help_main_ctrl_n".to_string(), "  Ctrl+N          - 创建新密码".to_string());
-    map.insert("help_main_ctrl_e".to_string(), "  Ctrl+E          - 编辑所选密码".to_string());
-    map.insert("help_main_ctrl_c".to_string(), "  Ctrl+C          - 复制密码到剪贴板".to_string());
-    map.insert("help_main_ctrl_s".to_string(), "  Ctrl+S          - 打开设置".to_string());
-    map.insert("help_main_f1".to_string(), "  F1              - 打开此帮助".to_string());
-    map.insert("help_main_f2".to_string(), "  F2              - 打开设置".to_string());
-    map.insert("help_main_arrows".to_string(), "  ↑ / ↓           - 导航列表".to_string());
-    map.insert("help_main_esc".to_string(), "  Esc             - 重置搜索".to_string());
-    map.insert("help_main_backspace".to_string(), "  Backspace       - 从搜索中删除字符".to_string());
-    map.insert("help_main_type".to_string(), "  输入文本       - 搜索密码（模糊搜索）".to_string());
+    map.insert("help_main_ctrl_q".to_string(), "退出应用程序".to_string());
+    map.insert("help_main_ctrl_n".to_string(), "创建新密码".to_string());
+    map.insert("help_main_ctrl_e".to_string(), "编辑所选密码".to_string());
+    map.insert("help_main_ctrl_d".to_string(), "删除所选密码（需确认）".to_string());
+    map.insert("help_main_ctrl_c".to_string(), "复制密码到剪贴板".to_string());
+    map.insert("help_main_ctrl_u".to_string(), "复制用户名到剪贴板".to_string());
+    map.insert("help_main_ctrl_b".to_string(), "检查条目密码是否出现在泄露中 (HIBP)".to_string());
+    map.insert("help_main_ctrl_k".to_string(), "复制服务器条目的 ssh 命令".to_string());
+    map.insert("help_main_ctrl_o".to_string(), "通过 SSH 连接到条目的服务器".to_string());
+    map.insert("help_main_ctrl_v".to_string(), "自动输入：将用户名和密码输入到上一个窗口（xdotool、wtype、macOS）".to_string());
+    map.insert("help_main_ctrl_s".to_string(), "打开设置".to_string());
+    map.insert("help_main_f1".to_string(), "打开此帮助".to_string());
+    map.insert("help_main_f2".to_string(), "打开设置".to_string());
+    map.insert("help_main_f3".to_string(), "审计:重复使用的密码".to_string());
+    map.insert("help_main_f4".to_string(), "回收站:恢复已删除的条目".to_string());
+    map.insert("help_main_ctrl_r".to_string(), "标记/取消标记需要轮换".to_string());
+    map.insert("help_main_f5".to_string(), "轮换已标记的密码".to_string());
+    map.insert("help_main_f6".to_string(), "维护：删除孤立文件和旧历史".to_string());
+    map.insert("help_main_f7".to_string(), "从 CSV 导入".to_string());
+    map.insert("help_main_f8".to_string(), "备份密码库".to_string());
+    map.insert("help_main_f9".to_string(), "回滚到备份".to_string());
+    map.insert("help_main_f10".to_string(), "Git 同步".to_string());
+    map.insert("help_main_arrows".to_string(), "导航列表".to_string());
+    map.insert("help_main_esc".to_string(), "重置搜索".to_string());
+    map.insert("help_main_backspace".to_string(), "从搜索中删除字符".to_string());
+    map.insert("help_main_type".to_string(), "搜索密码（模糊搜索）".to_string());
+    map.insert("help_key_search".to_string(), "输入文本".to_string());
+    map.insert("help_main_tag".to_string(), "仅显示带该标签的条目(可多个:#work #mail)".to_string());
+    map.insert("help_key_tag".to_string(), "#标签".to_string());
+    map.insert("help_main_folders".to_string(), "打开文件夹 / 返回上一级".to_string());
+    map.insert("help_main_ctrl_f".to_string(), "将条目移动到文件夹".to_string());
+    map.insert("help_main_ctrl_a".to_string(), "归档条目 / 从归档恢复".to_string());
+    map.insert("help_main_ctrl_g".to_string(), "显示归档 / 返回条目（已归档的条目不在列表和浏览器扩展中显示）".to_string());
+    map.insert("help_main_ctrl_z".to_string(), "挂起（用 fg 恢复）/ 打开 shell".to_string());
+    map.insert("help_main_f11".to_string(), "开始/停止将宏录制到寄存器（在主屏幕的条目上开始）".to_string());
+    map.insert("help_main_f12".to_string(), "对列表中的每个条目重放宏（Esc 停止）".to_string());
+    map.insert("shell_escape_banner".to_string(), "rpm 正在等待 shell 结束：输入 exit 返回".to_string());
     map.insert("help_master_password_title".to_string(), "主密码屏幕".to_string());
-    map.insert("help_master_password_enter".to_string(), "  Enter           - 继续/创建主密码".to_string());
-    map.insert("help_master_password_arrows".to_string(), "  ↑ / ↓           - 在字段之间切换".to_string());
-    map.insert("help_master_password_ctrl_h".to_string(), "  Ctrl+H          - 显示/隐藏密码".to_string());
-    map.insert("help_master_password_f1".to_string(), "  F1              - 打开帮助".to_string());
-    map.insert("help_master_password_esc".to_string(), "  Esc             - 退出应用程序".to_string());
-    map.insert("help_master_password_backspace".to_string(), "  Backspace       - 删除字符".to_string());
+    map.insert("help_master_password_enter".to_string(), "继续/创建主密码".to_string());
+    map.insert("help_master_password_arrows".to_string(), "在字段之间切换".to_string());
+    map.insert("help_master_password_ctrl_h".to_string(), "显示/隐藏密码".to_string());
+    map.insert("help_master_password_f1".to_string(), "打开帮助".to_string());
+    map.insert("help_master_password_esc".to_string(), "退出应用程序".to_string());
+    map.insert("help_master_password_backspace".to_string(), "删除字符".to_string());
     map.insert("help_password_entry_title".to_string(), "密码输入屏幕".to_string());
-    map.insert("help_password_entry_enter".to_string(), "  Enter           - 保存密码".to_string());
-    map.insert("help_password_entry_esc".to_string(), "  Esc             - 取消并返回主屏幕".to_string());
-    map.insert("help_password_entry_arrows".to_string(), "  ↑ / ↓           - 在字段之间切换（名称/密码）".to_string());
-    map.insert("help_password_entry_ctrl_h".to_string(), "  Ctrl+H          - 显示/隐藏密码".to_string());
-    map.insert("help_password_entry_ctrl_g".to_string(), "  Ctrl+G          - 打开密码生成器".to_string());
-    map.insert("help_password_entry_f1".to_string(), "  F1              - 打开帮助".to_string());
-    map.insert("help_password_entry_backspace".to_string(), "  Backspace       - 删除字符".to_string());
+    map.insert("help_password_entry_enter".to_string(), "保存密码".to_string());
+    map.insert("help_password_entry_esc".to_string(), "取消并返回主屏幕".to_string());
+    map.insert("help_password_entry_arrows".to_string(), "在字段之间切换（名称/用户名/密码/网址/备注）".to_string());
+    map.insert("help_password_entry_ctrl_h".to_string(), "显示/隐藏密码".to_string());
+    map.insert("help_password_entry_ctrl_g".to_string(), "打开密码生成器".to_string());
+    map.insert("help_password_entry_f3".to_string(), "打开审计".to_string());
+    map.insert("help_password_entry_f4".to_string(), "安全问题".to_string());
+    map.insert("help_password_entry_f5".to_string(), "自定义字段".to_string());
+    map.insert("help_password_entry_f6".to_string(), "SSH 连接（主机、端口、用户）".to_string());
+    map.insert("help_password_entry_f7".to_string(), "自动输入序列".to_string());
+    map.insert("help_password_entry_ctrl_t".to_string(), "将条目固定到托盘菜单 / 取消固定".to_string());
+    map.insert("help_password_entry_ctrl_e".to_string(), "在外部编辑器中编辑备注（$EDITOR）".to_string());
+    map.insert("help_password_entry_f1".to_string(), "打开帮助".to_string());
+    map.insert("help_password_entry_backspace".to_string(), "删除字符".to_string());
     map.insert("help_password_generator_title".to_string(), "密码生成器屏幕".to_string());
-    map.insert("help_password_generator_enter".to_string(), "  Enter           - 生成密码并插入".to_string());
-    map.insert("help_password_generator_esc".to_string(), "  Esc             - 取消并返回密码屏幕".to_string());
-    map.insert("help_password_generator_arrows".to_string(), "  ↑ / ↓           - 导航元素".to_string());
-    map.insert("help_password_generator_space".to_string(), "  Space           - 切换复选框（字符集）".to_string());
-    map.insert("help_password_generator_backspace".to_string(), "  Backspace       - 删除活动字段中的字符".to_string());
-    map.insert("help_password_generator_type".to_string(), "  输入字符       - 在活动字段中输入（长度/排除）".to_string());
-    map.insert("help_password_generator_f1".to_string(), "  F1              - 打开帮助".to_string());
+    map.insert("help_password_generator_enter".to_string(), "生成密码并插入".to_string());
+    map.insert("help_password_generator_esc".to_string(), "取消并返回密码屏幕".to_string());
+    map.insert("help_password_generator_arrows".to_string(), "导航元素".to_string());
+    map.insert("help_password_generator_space".to_string(), "切换复选框（字符集、易混淆字符）".to_string());
+    map.insert("help_password_generator_ctrl_p".to_string(), "选择已保存的预设".to_string());
+    map.insert("help_password_generator_ctrl_s".to_string(), "将设置保存为预设".to_string());
+    map.insert("help_password_generator_backspace".to_string(), "删除活动字段中的字符".to_string());
+    map.insert("help_password_generator_type".to_string(), "在活动字段中输入（长度/排除/自定义字符集/最少数量）".to_string());
+    map.insert("help_key_input".to_string(), "输入字符".to_string());
+    map.insert("help_password_generator_f1".to_string(), "打开帮助".to_string());
     map.insert("help_settings_title".to_string(), "设置屏幕".to_string());
-    map.insert("help_settings_enter".to_string(), "  Enter           - 保存设置".to_string());
-    map.insert("help_settings_esc".to_string(), "  Esc / Q         - 取消并返回主屏幕".to_string());
-    map.insert("help_settings_arrows".to_string(), "  ↑ / ↓           - 在字段之间切换".to_string());
-    map.insert("help_settings_f1".to_string(), "  F1              - 打开帮助".to_string());
-    map.insert("help_settings_backspace".to_string(), "  Backspace       - 删除字符".to_string());
+    map.insert("help_settings_enter".to_string(), "保存设置".to_string());
+    map.insert("help_settings_esc".to_string(), "取消并返回主屏幕".to_string());
+    map.insert("help_settings_arrows".to_string(), "在字段之间切换".to_string());
+    map.insert("help_settings_f1".to_string(), "打开帮助".to_string());
+    map.insert("help_settings_backspace".to_string(), "删除字符".to_string());
+    map.insert("help_audit_title".to_string(), "审计界面".to_string());
+    map.insert("help_audit_close".to_string(), "返回上一个界面".to_string());
+    map.insert("help_security_questions_title".to_string(), "安全问题".to_string());
+    map.insert("help_security_questions_ctrl_n".to_string(), "添加问题".to_string());
+    map.insert("help_security_questions_ctrl_g".to_string(), "生成随机答案".to_string());
+    map.insert("help_security_questions_ctrl_h".to_string(), "显示/隐藏答案".to_string());
+    map.insert("help_security_questions_ctrl_d".to_string(), "删除问题".to_string());
+    map.insert("help_security_questions_tab".to_string(), "切换问题/答案".to_string());
+    map.insert("help_security_questions_close".to_string(), "返回条目".to_string());
+    map.insert("help_custom_fields_title".to_string(), "自定义字段".to_string());
+    map.insert("help_custom_fields_ctrl_n".to_string(), "添加字段".to_string());
+    map.insert("help_custom_fields_ctrl_c".to_string(), "复制字段值".to_string());
+    map.insert("help_custom_fields_ctrl_h".to_string(), "显示/隐藏值".to_string());
+    map.insert("help_custom_fields_ctrl_d".to_string(), "删除字段".to_string());
+    map.insert("help_custom_fields_tab".to_string(), "切换名称/值".to_string());
+    map.insert("help_custom_fields_close".to_string(), "返回条目".to_string());
+    map.insert("help_ssh_host_title".to_string(), "SSH 连接".to_string());
+    map.insert("help_ssh_host_arrows".to_string(), "主机、端口、用户".to_string());
+    map.insert("help_ssh_host_close".to_string(), "返回条目".to_string());
+    map.insert("help_autotype_title".to_string(), "自动输入".to_string());
+    map.insert("help_autotype_placeholders".to_string(), "条目字段和按键，{DELAY 500} - 暂停".to_string());
+    map.insert("help_autotype_close".to_string(), "返回条目".to_string());
+    map.insert("help_access_window_title".to_string(), "API 访问时间".to_string());
+    map.insert("help_access_window_format".to_string(), "API 提供条目的日期和时间".to_string());
+    map.insert("help_access_window_close".to_string(), "返回条目".to_string());
+    map.insert("help_rotation_title".to_string(), "密码轮换".to_string());
+    map.insert("help_rotation_ctrl_o".to_string(), "打开条目网站".to_string());
+    map.insert("help_rotation_ctrl_g".to_string(), "按条目策略生成密码".to_string());
+    map.insert("help_rotation_copy".to_string(), "复制旧/新密码".to_string());
+    map.insert("help_rotation_enter".to_string(), "确认更改（旧密码保留在历史中）".to_string());
+    map.insert("help_rotation_tab".to_string(), "跳过条目".to_string());
+    map.insert("help_rotation_esc".to_string(), "返回主屏幕".to_string());
+    map.insert("help_trash_title".to_string(), "回收站".to_string());
+    map.insert("help_trash_enter".to_string(), "将条目放回密码列表".to_string());
+    map.insert("help_trash_delete".to_string(), "永久删除条目(需确认)".to_string());
+    map.insert("help_trash_retention".to_string(), "超过设置中保留期限的条目会在解锁时删除".to_string());
     map.insert("help_help_title".to_string(), "帮助".to_string());
-    map.insert("help_help_close".to_string(), "  F1 / Esc        - 关闭帮助并返回".to_string());
+    map.insert("help_help_close".to_string(), "关闭帮助并返回".to_string());
     
     // Common
     map.insert("show".to_string(), "显示".to_string());
@@ -0,0 +1,143 @@
+//! Shared pieces of the browser profile importers (`firefox`, `chromium`).
+//!
+//! Browser databases are SQLite files. They are read with the `sqlite3` tool
+//! through an `immutable=1` URI: the file is opened read-only, nothing is ever
+//! written to the profile, and it works while the browser is running.
+
+use super::ImportReport;
+use crate::errors::{RpmError, RpmResult};
+use crate::models::EntryDetails;
+use crate::storage::PasswordStorage;
+use chrono::Utc;
+use serde_json::{Map, Value};
+use std::path::Path;
+use std::process::Command;
+use zeroize::Zeroize;
+
+/// Login decrypted from a browser profile
+#[derive(Debug, Default)]
+pub struct BrowserLogin {
+    pub url: String,
+    pub username: String,
+    pub password: String,
+}
+
+impl Drop for BrowserLogin {
+    fn drop(&mut self) {
+        self.password.zeroize();
+    }
+}
+
+impl BrowserLogin {
+    /// Vault entry name: the site's host, followed by the login when there is one
+    pub fn entry_name(&self) -> String {
+        let host = host_of(&self.url);
+        if self.username.is_empty() {
+            host.to_string()
+        } else {
+            format!("{}/{}", host, self.username)
+        }
+    }
+}
+
+/// Logins read from a profile; `unsupported` counts values that could not be decrypted
+#[derive(Debug, Default)]
+pub struct BrowserLogins {
+    pub logins: Vec<BrowserLogin>,
+    pub unsupported: usize,
+}
+
+/// Host part of a URL (`https://accounts.example.com/login` -> `accounts.example.com`)
+fn host_of(url: &str) -> &str {
+    let rest = url.split_once("://").map(|(_, rest)| rest).unwrap_or(url);
+    let host = rest.split(['/', '?', '#']).next().unwrap_or(rest);
+    host.rsplit_once('@').map(|(_, host)| host).unwrap_or(host)
+}
+
+/// `file:` URI opening the database read-only without taking locks
+fn sqlite_uri(db: &Path) -> String {
+    let path = db.to_string_lossy().replace('\\', "/");
+    let mut uri = String::from("file:");
+    if !path.starts_with('/') {
+        // Windows drive paths: file:///C:/...
+        uri.push_str("///");
+    }
+    for c in path.chars() {
+        match c {
+            '%' => uri.push_str("%25"),
+            '?' => uri.push_str("%3f"),
+            '#' => uri.push_str("%23"),
+            _ => uri.push(c),
+        }
+    }
+    uri.push_str("?immutable=1");
+    uri
+}
+
+/// Run a query with the `sqlite3` tool and return the rows as JSON objects.
+/// Blob columns should be selected as `hex(column)`.
+pub fn query_sqlite(db: &Path, sql: &str) -> RpmResult<Vec<Map<String, Value>>> {
+    if !db.is_file() {
+        return Err(RpmError::InvalidInput(format!("Database not found: {}", db.display())));
+    }
+    let output = Command::new("sqlite3")
+        .args(["-readonly", "-json"])
+        .arg(sqlite_uri(db))
+        .arg(sql)
+        .output()
+        .map_err(|e| RpmError::InvalidInput(format!("Failed to run sqlite3: {}", e)))?;
+    let mut stdout = output.stdout;
+    if !output.status.success() {
+        stdout.zeroize();
+        return Err(RpmError::InvalidInput(format!(
+            "sqlite3 could not read {}: {}",
+            db.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    // No output at all means no rows
+    let rows = if stdout.iter().all(u8::is_ascii_whitespace) {
+        Ok(Vec::new())
+    } else {
+        serde_json::from_slice(&stdout).map_err(RpmError::from)
+    };
+    stdout.zeroize();
+    rows
+}
+
+/// String column of a row; missing and NULL values are empty
+pub fn text_column(row: &Map<String, Value>, name: &str) -> String {
+    row.get(name).and_then(Value::as_str).unwrap_or("").to_string()
+}
+
+/// Column selected as `hex(column)`
+pub fn hex_column(row: &Map<String, Value>, name: &str) -> Option<Vec<u8>> {
+    row.get(name).and_then(Value::as_str).and_then(|value| hex::decode(value).ok())
+}
+
+/// Import decrypted logins; entries without a password are skipped
+pub fn import_logins(storage: &PasswordStorage, logins: &[BrowserLogin], key: &[u8]) -> RpmResult<ImportReport> {
+    let mut report = ImportReport::default();
+    let now = Utc::now();
+
+    for login in logins {
+        if login.password.is_empty() {
+            report.skipped += 1;
+            continue;
+        }
+        let mut details = EntryDetails {
+            username: Some(login.username.clone()).filter(|username| !username.is_empty()),
+            url: Some(login.url.clone()).filter(|url| !url.is_empty()),
+            ..EntryDetails::default()
+        };
+        details.timestamps.created_at = Some(now);
+        details.timestamps.updated_at = Some(now);
+
+        let filename = storage.add_entry(&login.entry_name(), key)?;
+        storage.update_password_file(&filename, &login.password, key)?;
+        storage.update_entry_details(&filename, &details, key)?;
+        report.created += 1;
+    }
+
+    Ok(report)
+}
@@ -0,0 +1,282 @@
+//! Importer for Chromium-based browsers (Google Chrome, Chromium): the `Login Data`
+//! database of a profile.
+//!
+//! Saved passwords are encrypted with a key the browser keeps in the OS:
+//! - Linux: AES-128-CBC with PBKDF2-SHA1(secret, "saltysalt", 1 round); the secret
+//!   is "peanuts" for `v10` values and the keyring secret (read with `secret-tool`)
+//!   for `v11` values
+//! - macOS: the same cipher with 1003 rounds and the "Safe Storage" keychain secret
+//!   (read with `security`, so macOS asks the user to allow it)
+//! - Windows: AES-256-GCM with the key from `Local State`, unwrapped with DPAPI
+//!
+//! Values the browser binds to itself (`v20` app-bound encryption) cannot be
+//! decrypted outside it and are counted as unsupported.
+
+use super::browser::{hex_column, query_sqlite, text_column, BrowserLogin, BrowserLogins};
+use crate::errors::RpmResult;
+use std::path::{Path, PathBuf};
+use zeroize::Zeroize;
+
+/// Chromium-based browser whose profile is imported
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Browser {
+    Chrome,
+    Chromium,
+}
+
+impl Browser {
+    pub fn display_name(self) -> &'static str {
+        match self {
+            Self::Chrome => "Google Chrome",
+            Self::Chromium => "Chromium",
+        }
+    }
+
+    /// `User Data` directory holding the browser's profiles and `Local State`
+    pub fn user_data_dir(self) -> Option<PathBuf> {
+        if cfg!(target_os = "macos") {
+            let dir = match self {
+                Self::Chrome => ["Google", "Chrome"],
+                Self::Chromium => ["Chromium", ""],
+            };
+            dirs::data_dir().map(|data| data.join(dir[0]).join(dir[1]))
+        } else if cfg!(windows) {
+            let dir = match self {
+                Self::Chrome => ["Google", "Chrome"],
+                Self::Chromium => ["Chromium", ""],
+            };
+            dirs::data_local_dir().map(|data| data.join(dir[0]).join(dir[1]).join("User Data"))
+        } else {
+            let dir = match self {
+                Self::Chrome => "google-chrome",
+                Self::Chromium => "chromium",
+            };
+            dirs::config_dir().map(|config| config.join(dir))
+        }
+    }
+
+    /// The profile the browser opens by default
+    pub fn default_profile(self) -> Option<PathBuf> {
+        self.user_data_dir().map(|dir| dir.join("Default"))
+    }
+
+    /// Application name under which the browser keeps its secret in the OS keyring/keychain
+    #[cfg_attr(windows, allow(dead_code))]
+    fn keyring_application(self) -> &'static str {
+        match self {
+            Self::Chrome => "chrome",
+            Self::Chromium => "chromium",
+        }
+    }
+}
+
+/// Read and decrypt the saved logins of a profile (never-save entries are left out)
+pub fn load(browser: Browser, profile: &Path) -> RpmResult<BrowserLogins> {
+    let rows = query_sqlite(
+        &profile.join("Login Data"),
+        "SELECT origin_url, username_value, hex(password_value) AS password \
+         FROM logins WHERE blacklisted_by_user = 0",
+    )?;
+    let mut keys = os::Keys::new(browser, profile);
+
+    let mut result = BrowserLogins::default();
+    for row in rows {
+        let Some(mut encrypted) = hex_column(&row, "password") else {
+            result.unsupported += 1;
+            continue;
+        };
+        let password = keys.decrypt(&encrypted);
+        encrypted.zeroize();
+        match password {
+            Some(password) => result.logins.push(BrowserLogin {
+                url: text_column(&row, "origin_url"),
+                username: text_column(&row, "username_value"),
+                password,
+            }),
+            None => result.unsupported += 1,
+        }
+    }
+    Ok(result)
+}
+
+/// Bytes to text; the bytes are wiped either way
+#[cfg_attr(not(any(unix, windows)), allow(dead_code))]
+fn into_text(mut bytes: Vec<u8>) -> Option<String> {
+    let text = std::str::from_utf8(&bytes).ok().map(str::to_string);
+    bytes.zeroize();
+    text
+}
+
+#[cfg(unix)]
+mod os {
+    use super::{into_text, Browser};
+    use aes::Aes128;
+    use cbc::cipher::block_padding::Pkcs7;
+    use cbc::cipher::{BlockDecryptMut, KeyIvInit};
+    use sha1::Sha1;
+    use std::path::Path;
+    use std::process::Command;
+    use zeroize::{Zeroize, Zeroizing};
+
+    const SALT: &[u8] = b"saltysalt";
+    const IV: [u8; 16] = [b' '; 16];
+    /// Secret of `v10` values on Linux, where no keyring was available to the browser
+    #[cfg(not(target_os = "macos"))]
+    const FALLBACK_SECRET: &str = "peanuts";
+    #[cfg(target_os = "macos")]
+    const ROUNDS: u32 = 1003;
+    #[cfg(not(target_os = "macos"))]
+    const ROUNDS: u32 = 1;
+
+    /// Keys derived on first use: reading the keyring may prompt the user
+    pub struct Keys {
+        browser: Browser,
+        v10: Option<Option<Zeroizing<[u8; 16]>>>,
+        v11: Option<Option<Zeroizing<[u8; 16]>>>,
+    }
+
+    impl Keys {
+        pub fn new(browser: Browser, _profile: &Path) -> Self {
+            Self { browser, v10: None, v11: None }
+        }
+
+        pub fn decrypt(&mut self, value: &[u8]) -> Option<String> {
+            let (prefix, ciphertext) = (value.get(..3)?, &value[3..]);
+            let browser = self.browser;
+            let key = match prefix {
+                b"v10" => self.v10.get_or_insert_with(|| v10_secret(browser).map(|secret| derive_key(&secret))),
+                b"v11" => self.v11.get_or_insert_with(|| keyring_secret(browser).map(|secret| derive_key(&secret))),
+                _ => return None,
+            };
+            let key = key.as_ref()?;
+            let decryptor = cbc::Decryptor::<Aes128>::new_from_slices(key.as_ref(), &IV).ok()?;
+            into_text(decryptor.decrypt_padded_vec_mut::<Pkcs7>(ciphertext).ok()?)
+        }
+    }
+
+    fn derive_key(secret: &str) -> Zeroizing<[u8; 16]> {
+        let mut key = Zeroizing::new([0u8; 16]);
+        pbkdf2::pbkdf2_hmac::<Sha1>(secret.as_bytes(), SALT, ROUNDS, key.as_mut());
+        key
+    }
+
+    /// macOS: the keychain item "<Browser> Safe Storage"; Linux: the hard-coded fallback
+    #[cfg(target_os = "macos")]
+    fn v10_secret(browser: Browser) -> Option<Zeroizing<String>> {
+        let service = match browser {
+            Browser::Chrome => "Chrome Safe Storage",
+            Browser::Chromium => "Chromium Safe Storage",
+        };
+        run_secret_tool("security", &["find-generic-password", "-w", "-s", service])
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    fn v10_secret(_browser: Browser) -> Option<Zeroizing<String>> {
+        Some(Zeroizing::new(FALLBACK_SECRET.to_string()))
+    }
+
+    /// `v11` values use the secret the browser stored in the desktop keyring
+    fn keyring_secret(browser: Browser) -> Option<Zeroizing<String>> {
+        run_secret_tool("secret-tool", &["lookup", "application", browser.keyring_application()])
+    }
+
+    fn run_secret_tool(program: &str, args: &[&str]) -> Option<Zeroizing<String>> {
+        let output = Command::new(program).args(args).output().ok()?;
+        let mut stdout = output.stdout;
+        if !output.status.success() {
+            stdout.zeroize();
+            return None;
+        }
+        let mut secret = into_text(stdout)?;
+        let len = secret.trim_end_matches('\n').len();
+        secret.truncate(len);
+        Some(Zeroizing::new(secret))
+    }
+}
+
+#[cfg(windows)]
+mod os {
+    use super::{into_text, Browser};
+    use aes_gcm::aead::Aead;
+    use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+    use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+    use base64::Engine;
+    use std::path::Path;
+    use windows_sys::Win32::Foundation::LocalFree;
+    use windows_sys::Win32::Security::Cryptography::{CryptUnprotectData, CRYPT_INTEGER_BLOB};
+    use zeroize::{Zeroize, Zeroizing};
+
+    /// AES-256-GCM key from `Local State`, read once per import
+    pub struct Keys {
+        key: Option<Zeroizing<Vec<u8>>>,
+    }
+
+    impl Keys {
+        pub fn new(_browser: Browser, profile: &Path) -> Self {
+            // `Local State` sits in the User Data directory, next to the profiles
+            let key = profile.parent().and_then(|dir| local_state_key(&dir.join("Local State")));
+            Self { key }
+        }
+
+        pub fn decrypt(&mut self, value: &[u8]) -> Option<String> {
+            match value.get(..3)? {
+                b"v10" => {
+                    let key = self.key.as_ref()?;
+                    let (nonce, ciphertext) = value[3..].split_at_checked(12)?;
+                    let cipher = Aes256Gcm::new_from_slice(key).ok()?;
+                    into_text(cipher.decrypt(Nonce::from_slice(nonce), ciphertext).ok()?)
+                }
+                // Values saved before Chrome 80 are plain DPAPI blobs
+                [0x01, 0x00, 0x00] => into_text(unprotect(value)?),
+                _ => None,
+            }
+        }
+    }
+
+    /// `os_crypt.encrypted_key`: base64 of "DPAPI" followed by the DPAPI-protected key
+    fn local_state_key(path: &Path) -> Option<Zeroizing<Vec<u8>>> {
+        let content = std::fs::read_to_string(path).ok()?;
+        let state: serde_json::Value = serde_json::from_str(&content).ok()?;
+        let encoded = state.get("os_crypt")?.get("encrypted_key")?.as_str()?;
+        let wrapped = BASE64_STANDARD.decode(encoded).ok()?;
+        unprotect(wrapped.strip_prefix(b"DPAPI")?).map(Zeroizing::new)
+    }
+
+    /// Decrypt data protected for the current Windows user
+    fn unprotect(data: &[u8]) -> Option<Vec<u8>> {
+        let input = CRYPT_INTEGER_BLOB {
+            cbData: data.len() as u32,
+            pbData: data.as_ptr() as *mut u8,
+        };
+        let mut output = CRYPT_INTEGER_BLOB { cbData: 0, pbData: std::ptr::null_mut() };
+        // SAFETY: the output buffer is copied and then released with LocalFree
+        unsafe {
+            if CryptUnprotectData(&input, std::ptr::null_mut(), std::ptr::null(), std::ptr::null(), std::ptr::null(), 0, &mut output) == 0 {
+                return None;
+            }
+            let plain = std::slice::from_raw_parts_mut(output.pbData, output.cbData as usize);
+            let copy = plain.to_vec();
+            plain.zeroize();
+            LocalFree(output.pbData as _);
+            Some(copy)
+        }
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+mod os {
+    use super::Browser;
+    use std::path::Path;
+
+    pub struct Keys;
+
+    impl Keys {
+        pub fn new(_browser: Browser, _profile: &Path) -> Self {
+            Self
+        }
+
+        pub fn decrypt(&mut self, _value: &[u8]) -> Option<String> {
+            None
+        }
+    }
+}
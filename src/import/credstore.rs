@@ -0,0 +1,358 @@
+//! Importers for the operating system credential stores.
+//!
+//! On macOS, internet passwords are listed from the login keychain with the
+//! `security` tool; every password is read separately, so macOS asks the user
+//! to allow access to each item. On Windows, generic credentials are read from
+//! the Credential Manager with `CredEnumerateW`/`CredReadW`. Listing never
+//! touches secrets: the CLI shows what was found and asks before importing.
+
+use super::ImportReport;
+use crate::errors::{RpmError, RpmResult};
+use crate::models::EntryDetails;
+use crate::storage::PasswordStorage;
+use chrono::Utc;
+use zeroize::Zeroize;
+
+/// Operating system credential store
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CredentialStore {
+    /// macOS Keychain (internet passwords)
+    Keychain,
+    /// Windows Credential Manager (generic credentials)
+    CredentialManager,
+}
+
+impl CredentialStore {
+    pub fn display_name(self) -> &'static str {
+        match self {
+            Self::Keychain => "macOS Keychain",
+            Self::CredentialManager => "Windows Credential Manager",
+        }
+    }
+
+    /// Whether the store exists on the platform rpm was built for
+    pub fn is_available(self) -> bool {
+        match self {
+            Self::Keychain => cfg!(target_os = "macos"),
+            Self::CredentialManager => cfg!(windows),
+        }
+    }
+
+    fn ensure_available(self) -> RpmResult<()> {
+        if self.is_available() {
+            Ok(())
+        } else {
+            Err(RpmError::InvalidInput(format!(
+                "The {} is not available on this platform",
+                self.display_name()
+            )))
+        }
+    }
+
+    /// Credentials in the store, without their secrets
+    pub fn list(self) -> RpmResult<Vec<StoredCredential>> {
+        self.ensure_available()?;
+        match self {
+            Self::Keychain => keychain::list(),
+            Self::CredentialManager => credman::list(),
+        }
+    }
+
+    /// Read the secret of one credential; on macOS this shows the keychain access prompt
+    pub fn read_secret(self, credential: &StoredCredential) -> RpmResult<String> {
+        self.ensure_available()?;
+        match self {
+            Self::Keychain => keychain::read_secret(credential),
+            Self::CredentialManager => credman::read_secret(credential),
+        }
+    }
+}
+
+/// Credential found in an OS store; `target` is what the store needs to read its secret
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StoredCredential {
+    /// Entry name in the vault (host name or target without the store's prefixes)
+    pub name: String,
+    pub username: Option<String>,
+    pub url: Option<String>,
+    pub target: String,
+}
+
+/// Import the given credentials. Items whose secret cannot be read (access denied in
+/// the macOS prompt, empty or binary secrets) are counted as skipped; read errors are
+/// reported in `warnings`.
+pub fn import_credentials(
+    storage: &PasswordStorage,
+    store: CredentialStore,
+    credentials: &[StoredCredential],
+    key: &[u8],
+) -> RpmResult<ImportReport> {
+    let mut report = ImportReport::default();
+    let now = Utc::now();
+
+    for credential in credentials {
+        let mut password = match store.read_secret(credential) {
+            Ok(password) if !password.is_empty() => password,
+            Ok(_) => {
+                report.skipped += 1;
+                continue;
+            }
+            Err(e) => {
+                report.warnings.push(format!("{}: {}", credential.name, e));
+                report.skipped += 1;
+                continue;
+            }
+        };
+
+        let mut details = EntryDetails {
+            username: credential.username.clone(),
+            url: credential.url.clone(),
+            ..EntryDetails::default()
+        };
+        details.timestamps.created_at = Some(now);
+        details.timestamps.updated_at = Some(now);
+
+        let result = storage.add_entry(&credential.name, key).and_then(|filename| {
+            storage
+                .update_password_file(&filename, &password, key)
+                .and_then(|_| storage.update_entry_details(&filename, &details, key))
+        });
+        password.zeroize();
+        result?;
+        report.created += 1;
+    }
+
+    Ok(report)
+}
+
+/// Parse the output of `security dump-keychain` (without `-d`, so no secrets):
+/// internet passwords (`class: "inet"`) with their server, account, protocol and path
+pub fn parse_keychain_dump(dump: &str) -> Vec<StoredCredential> {
+    let mut credentials = Vec::new();
+    for item in dump.split("keychain: ").skip(1) {
+        if !item.lines().any(|line| line.trim() == "class: \"inet\"") {
+            continue;
+        }
+        let attribute = |name: &str| {
+            let prefix = format!("\"{}\"<", name);
+            item.lines()
+                .map(str::trim)
+                .find(|line| line.starts_with(&prefix))
+                .and_then(|line| line.split_once('='))
+                .and_then(|(_, value)| parse_keychain_value(value))
+        };
+        let Some(server) = attribute("srvr") else {
+            continue;
+        };
+        let scheme = match attribute("ptcl").as_deref() {
+            Some("http") => "http",
+            _ => "https",
+        };
+        let path = attribute("path").filter(|path| path.starts_with('/')).unwrap_or_default();
+        credentials.push(StoredCredential {
+            name: server.clone(),
+            username: attribute("acct").filter(|acct| !acct.is_empty()),
+            url: Some(format!("{}://{}{}", scheme, server, path)),
+            target: server,
+        });
+    }
+    credentials
+}
+
+/// Attribute value: `"text"`, `0x<hex>  "escaped text"` or `<NULL>`
+fn parse_keychain_value(value: &str) -> Option<String> {
+    let value = value.trim();
+    if let Some(hex_value) = value.strip_prefix("0x") {
+        let hex_value = hex_value.split_whitespace().next().unwrap_or("");
+        return hex::decode(hex_value).ok().and_then(|bytes| String::from_utf8(bytes).ok());
+    }
+    let quoted = value.strip_prefix('"')?.strip_suffix('"')?;
+    Some(quoted.to_string())
+}
+
+/// Split a Credential Manager target into entry name and URL, e.g.
+/// `git:https://github.com` or `LegacyGeneric:target=example.com`
+pub fn parse_credman_target(target: &str) -> (String, Option<String>) {
+    let target = target
+        .strip_prefix("LegacyGeneric:target=")
+        .unwrap_or(target);
+    match target.find("://") {
+        Some(index) => {
+            let start = target[..index].rfind(':').map(|colon| colon + 1).unwrap_or(0);
+            let url = &target[start..];
+            let host = url[index - start + 3..].split(['/', '?', '#']).next().unwrap_or(url);
+            (host.to_string(), Some(url.to_string()))
+        }
+        None => (target.to_string(), None),
+    }
+}
+
+/// Credential blobs are UTF-16LE for most applications but UTF-8 for some (git);
+/// UTF-8 text never contains NUL, UTF-16 text of ASCII characters always does
+pub fn decode_credential_blob(blob: &[u8]) -> Option<String> {
+    if let Ok(text) = std::str::from_utf8(blob) {
+        if !text.contains('\0') {
+            return Some(text.to_string());
+        }
+    }
+    let pairs = blob.chunks_exact(2);
+    if !pairs.remainder().is_empty() {
+        return None;
+    }
+    let mut units: Vec<u16> = pairs
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .collect();
+    let text = String::from_utf16(&units).ok();
+    units.zeroize();
+    text
+}
+
+#[cfg(target_os = "macos")]
+mod keychain {
+    use super::{parse_keychain_dump, StoredCredential};
+    use crate::errors::{RpmError, RpmResult};
+    use std::process::{Command, Stdio};
+    use zeroize::Zeroize;
+
+    fn security(args: &[&str]) -> RpmResult<Vec<u8>> {
+        let output = Command::new("security")
+            .args(args)
+            .stdin(Stdio::inherit())
+            .stderr(Stdio::piped())
+            .output()
+            .map_err(|e| RpmError::InvalidInput(format!("Failed to run security: {}", e)))?;
+        if !output.status.success() {
+            let mut stdout = output.stdout;
+            stdout.zeroize();
+            return Err(RpmError::InvalidInput(format!(
+                "security {} failed: {}",
+                args[0],
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(output.stdout)
+    }
+
+    pub fn list() -> RpmResult<Vec<StoredCredential>> {
+        let dump = security(&["dump-keychain"])?;
+        Ok(parse_keychain_dump(&String::from_utf8_lossy(&dump)))
+    }
+
+    pub fn read_secret(credential: &StoredCredential) -> RpmResult<String> {
+        let mut args = vec!["find-internet-password", "-s", credential.target.as_str()];
+        if let Some(ref account) = credential.username {
+            args.extend(["-a", account.as_str()]);
+        }
+        args.push("-w");
+        let mut secret = String::from_utf8(security(&args)?).map_err(|e| {
+            e.into_bytes().zeroize();
+            RpmError::InvalidInput("Keychain password is not valid UTF-8".to_string())
+        })?;
+        // `-w` prints the password followed by a newline
+        let len = secret.trim_end_matches('\n').len();
+        secret.truncate(len);
+        Ok(secret)
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+mod keychain {
+    use super::StoredCredential;
+    use crate::errors::RpmResult;
+
+    pub fn list() -> RpmResult<Vec<StoredCredential>> {
+        Ok(Vec::new())
+    }
+
+    pub fn read_secret(_credential: &StoredCredential) -> RpmResult<String> {
+        Ok(String::new())
+    }
+}
+
+#[cfg(windows)]
+mod credman {
+    use super::{decode_credential_blob, parse_credman_target, StoredCredential};
+    use crate::errors::{RpmError, RpmResult};
+    use windows_sys::Win32::Security::Credentials::{
+        CredEnumerateW, CredFree, CredReadW, CREDENTIALW, CRED_TYPE_GENERIC,
+    };
+    use zeroize::Zeroize;
+
+    /// Read a NUL-terminated UTF-16 string owned by the credential
+    unsafe fn wide_to_string(ptr: *const u16) -> Option<String> {
+        if ptr.is_null() {
+            return None;
+        }
+        let mut len = 0;
+        while *ptr.add(len) != 0 {
+            len += 1;
+        }
+        String::from_utf16(std::slice::from_raw_parts(ptr, len)).ok()
+    }
+
+    pub fn list() -> RpmResult<Vec<StoredCredential>> {
+        let mut count = 0u32;
+        let mut credentials: *mut *mut CREDENTIALW = std::ptr::null_mut();
+        // SAFETY: the array returned by CredEnumerateW is read before CredFree releases it
+        unsafe {
+            if CredEnumerateW(std::ptr::null(), 0, &mut count, &mut credentials) == 0 {
+                // ERROR_NOT_FOUND: the store is empty
+                return Ok(Vec::new());
+            }
+            let mut found = Vec::new();
+            for index in 0..count as usize {
+                let credential = &**credentials.add(index);
+                if credential.Type != CRED_TYPE_GENERIC {
+                    continue;
+                }
+                let Some(target) = wide_to_string(credential.TargetName) else {
+                    continue;
+                };
+                let (name, url) = parse_credman_target(&target);
+                found.push(StoredCredential {
+                    name,
+                    username: wide_to_string(credential.UserName).filter(|u| !u.is_empty()),
+                    url,
+                    target,
+                });
+            }
+            CredFree(credentials as *const _);
+            Ok(found)
+        }
+    }
+
+    pub fn read_secret(credential: &StoredCredential) -> RpmResult<String> {
+        let target: Vec<u16> = credential.target.encode_utf16().chain(std::iter::once(0)).collect();
+        let mut pointer: *mut CREDENTIALW = std::ptr::null_mut();
+        // SAFETY: the blob is copied out before CredFree releases the credential
+        unsafe {
+            if CredReadW(target.as_ptr(), CRED_TYPE_GENERIC, 0, &mut pointer) == 0 {
+                return Err(RpmError::Io(std::io::Error::last_os_error()));
+            }
+            let credential = &*pointer;
+            let mut blob = if credential.CredentialBlob.is_null() {
+                Vec::new()
+            } else {
+                std::slice::from_raw_parts(credential.CredentialBlob, credential.CredentialBlobSize as usize).to_vec()
+            };
+            CredFree(pointer as *const _);
+            let secret = decode_credential_blob(&blob);
+            blob.zeroize();
+            secret.ok_or_else(|| RpmError::InvalidInput("Credential is not text".to_string()))
+        }
+    }
+}
+
+#[cfg(not(windows))]
+mod credman {
+    use super::StoredCredential;
+    use crate::errors::RpmResult;
+
+    pub fn list() -> RpmResult<Vec<StoredCredential>> {
+        Ok(Vec::new())
+    }
+
+    pub fn read_secret(_credential: &StoredCredential) -> RpmResult<String> {
+        Ok(String::new())
+    }
+}
@@ -0,0 +1,272 @@
+//! Importer for Firefox profiles: `logins.json`, decrypted with the key kept in `key4.db`.
+//!
+//! NSS stores its key in `key4.db` encrypted with PBES2 (PBKDF2-HMAC-SHA256 and
+//! AES-256-CBC) under SHA-1(global salt || primary password); a known
+//! "password-check" value tells whether the primary password is right. Every
+//! username and password in `logins.json` is a DER structure naming the cipher,
+//! its IV and the ciphertext. Only AES-256-CBC is implemented: values still
+//! encrypted with 3DES by older Firefox versions are counted as unsupported.
+
+use super::browser::{hex_column, query_sqlite, BrowserLogin, BrowserLogins};
+use crate::errors::{RpmError, RpmResult};
+use aes::Aes256;
+use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+use base64::Engine;
+use cbc::cipher::block_padding::Pkcs7;
+use cbc::cipher::{BlockDecryptMut, KeyIvInit};
+use serde::Deserialize;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use zeroize::{Zeroize, Zeroizing};
+
+/// DER encodings of the object identifiers used by NSS
+const OID_PBES2: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x05, 0x0d];
+const OID_PBKDF2: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x05, 0x0c];
+const OID_AES256_CBC: &[u8] = &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x01, 0x2a];
+
+const TAG_SEQUENCE: u8 = 0x30;
+const TAG_OCTET_STRING: u8 = 0x04;
+const TAG_INTEGER: u8 = 0x02;
+const TAG_OID: u8 = 0x06;
+
+/// Value `key4.db` encrypts to let NSS check the primary password
+const PASSWORD_CHECK: &[u8] = b"password-check";
+
+/// Directory holding the Firefox profiles of the current user
+fn profiles_root() -> Option<PathBuf> {
+    if cfg!(target_os = "macos") {
+        dirs::data_dir().map(|dir| dir.join("Firefox").join("Profiles"))
+    } else if cfg!(windows) {
+        dirs::config_dir().map(|dir| dir.join("Mozilla").join("Firefox").join("Profiles"))
+    } else {
+        dirs::home_dir().map(|dir| dir.join(".mozilla").join("firefox"))
+    }
+}
+
+/// Profile with the most recently saved logins
+pub fn default_profile() -> Option<PathBuf> {
+    std::fs::read_dir(profiles_root()?)
+        .ok()?
+        .filter_map(|item| item.ok())
+        .map(|item| item.path())
+        .filter_map(|dir| {
+            let modified = dir.join("logins.json").metadata().ok()?.modified().ok()?;
+            Some((modified, dir))
+        })
+        .max()
+        .map(|(_, dir)| dir)
+}
+
+#[derive(Deserialize)]
+struct LoginsFile {
+    #[serde(default)]
+    logins: Vec<StoredLogin>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StoredLogin {
+    hostname: String,
+    encrypted_username: String,
+    encrypted_password: String,
+}
+
+/// Read and decrypt the logins of a profile. Fails with `AuthenticationFailed`
+/// if the profile has a primary password and `primary_password` is not it.
+pub fn load(profile: &Path, primary_password: &str) -> RpmResult<BrowserLogins> {
+    let key = unlock_key(&profile.join("key4.db"), primary_password)?;
+
+    let content = std::fs::read_to_string(profile.join("logins.json"))?;
+    let file: LoginsFile = serde_json::from_str(&content)?;
+
+    let mut result = BrowserLogins::default();
+    for login in file.logins {
+        let username = decrypt_login_field(&login.encrypted_username, &key);
+        let password = decrypt_login_field(&login.encrypted_password, &key);
+        match (username, password) {
+            (Some(username), Some(password)) => result.logins.push(BrowserLogin {
+                url: login.hostname,
+                username,
+                password,
+            }),
+            (_, mut password) => {
+                if let Some(ref mut password) = password {
+                    password.zeroize();
+                }
+                result.unsupported += 1;
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Decrypt the NSS key from `key4.db`, checking the primary password first
+fn unlock_key(key_db: &Path, primary_password: &str) -> RpmResult<Zeroizing<Vec<u8>>> {
+    let unsupported = || RpmError::Crypto("Unsupported key4.db format".to_string());
+
+    let meta = query_sqlite(
+        key_db,
+        "SELECT hex(item1) AS salt, hex(item2) AS check_value FROM metaData WHERE id = 'password'",
+    )?;
+    let row = meta.first().ok_or_else(unsupported)?;
+    let global_salt = hex_column(row, "salt").ok_or_else(unsupported)?;
+    let check_value = hex_column(row, "check_value").ok_or_else(unsupported)?;
+
+    // With a wrong password the padding is almost always invalid, otherwise the value differs
+    let check = Pbes2::parse(&check_value).ok_or_else(unsupported)?.decrypt(&global_salt, primary_password);
+    if check.as_deref().map(Vec::as_slice) != Some(PASSWORD_CHECK) {
+        return Err(RpmError::AuthenticationFailed);
+    }
+
+    // CKA_ID of the key used for saved logins
+    let keys = query_sqlite(
+        key_db,
+        "SELECT hex(a11) AS key FROM nssPrivate WHERE hex(a102) = 'F8000000000000000000000000000001'",
+    )?;
+    let encrypted_key = keys.first().and_then(|row| hex_column(row, "key")).ok_or_else(unsupported)?;
+    Pbes2::parse(&encrypted_key)
+        .and_then(|pbes2| pbes2.decrypt(&global_salt, primary_password))
+        .ok_or_else(unsupported)
+}
+
+/// PBES2 structure (PBKDF2-HMAC-SHA256, AES-256-CBC) as stored in `key4.db`
+struct Pbes2<'a> {
+    salt: &'a [u8],
+    iterations: u32,
+    iv: Vec<u8>,
+    ciphertext: &'a [u8],
+}
+
+impl<'a> Pbes2<'a> {
+    fn parse(der: &'a [u8]) -> Option<Self> {
+        let [algorithm, ciphertext] = sequence(der)?[..] else {
+            return None;
+        };
+        let [oid, params] = expect(algorithm, TAG_SEQUENCE).and_then(sequence_items)?[..] else {
+            return None;
+        };
+        if expect(oid, TAG_OID)? != OID_PBES2 {
+            return None;
+        }
+        let [kdf, cipher] = expect(params, TAG_SEQUENCE).and_then(sequence_items)?[..] else {
+            return None;
+        };
+
+        let [kdf_oid, kdf_params] = expect(kdf, TAG_SEQUENCE).and_then(sequence_items)?[..] else {
+            return None;
+        };
+        if expect(kdf_oid, TAG_OID)? != OID_PBKDF2 {
+            return None;
+        }
+        let kdf_params = expect(kdf_params, TAG_SEQUENCE).and_then(sequence_items)?;
+        let salt = expect(*kdf_params.first()?, TAG_OCTET_STRING)?;
+        let iterations = integer(expect(*kdf_params.get(1)?, TAG_INTEGER)?)?;
+
+        let [cipher_oid, iv] = expect(cipher, TAG_SEQUENCE).and_then(sequence_items)?[..] else {
+            return None;
+        };
+        if expect(cipher_oid, TAG_OID)? != OID_AES256_CBC {
+            return None;
+        }
+        let iv = expect(iv, TAG_OCTET_STRING)?;
+        // NSS stores a 14-byte IV and prepends the DER header of an OCTET STRING of length 14
+        let iv: Vec<u8> = if iv.len() == 14 { [&[0x04, 0x0e][..], iv].concat() } else { iv.to_vec() };
+
+        Some(Self {
+            salt,
+            iterations,
+            iv,
+            ciphertext: expect(ciphertext, TAG_OCTET_STRING)?,
+        })
+    }
+
+    /// Decrypt with the key derived from the primary password; `None` if the padding is invalid
+    fn decrypt(&self, global_salt: &[u8], primary_password: &str) -> Option<Zeroizing<Vec<u8>>> {
+        let mut hasher = Sha1::new();
+        hasher.update(global_salt);
+        hasher.update(primary_password.as_bytes());
+        let mut password_hash = hasher.finalize();
+        let mut key = Zeroizing::new([0u8; 32]);
+        pbkdf2::pbkdf2_hmac::<Sha256>(&password_hash, self.salt, self.iterations, key.as_mut());
+        password_hash.zeroize();
+
+        decrypt_aes256_cbc(key.as_ref(), &self.iv, self.ciphertext)
+    }
+}
+
+/// Decrypt `encryptedUsername`/`encryptedPassword`: base64 of
+/// SEQUENCE { key id, SEQUENCE { cipher OID, IV }, ciphertext }
+fn decrypt_login_field(value: &str, key: &[u8]) -> Option<String> {
+    let der = BASE64_STANDARD.decode(value).ok()?;
+    let [_key_id, cipher, ciphertext] = sequence(&der)?[..] else {
+        return None;
+    };
+    let [cipher_oid, iv] = expect(cipher, TAG_SEQUENCE).and_then(sequence_items)?[..] else {
+        return None;
+    };
+    if expect(cipher_oid, TAG_OID)? != OID_AES256_CBC || key.len() < 32 {
+        return None;
+    }
+    let mut plaintext = decrypt_aes256_cbc(&key[..32], expect(iv, TAG_OCTET_STRING)?, expect(ciphertext, TAG_OCTET_STRING)?)?;
+    String::from_utf8(std::mem::take(&mut *plaintext)).ok()
+}
+
+fn decrypt_aes256_cbc(key: &[u8], iv: &[u8], ciphertext: &[u8]) -> Option<Zeroizing<Vec<u8>>> {
+    let decryptor = cbc::Decryptor::<Aes256>::new_from_slices(key, iv).ok()?;
+    decryptor.decrypt_padded_vec_mut::<Pkcs7>(ciphertext).ok().map(Zeroizing::new)
+}
+
+/// One DER element: (tag, content)
+type Element<'a> = (u8, &'a [u8]);
+
+/// Split the first DER element off `input`
+fn read_element(input: &[u8]) -> Option<(Element<'_>, &[u8])> {
+    let (&tag, rest) = input.split_first()?;
+    let (&first, rest) = rest.split_first()?;
+    let (len, rest) = if first < 0x80 {
+        (first as usize, rest)
+    } else {
+        let count = (first & 0x7f) as usize;
+        if count == 0 || count > 4 || rest.len() < count {
+            return None;
+        }
+        let len = rest[..count].iter().fold(0usize, |len, &b| (len << 8) | b as usize);
+        (len, &rest[count..])
+    };
+    if rest.len() < len {
+        return None;
+    }
+    Some(((tag, &rest[..len]), &rest[len..]))
+}
+
+/// Elements of a SEQUENCE's content
+fn sequence_items(content: &[u8]) -> Option<Vec<Element<'_>>> {
+    let mut items = Vec::new();
+    let mut rest = content;
+    while !rest.is_empty() {
+        let (item, tail) = read_element(rest)?;
+        items.push(item);
+        rest = tail;
+    }
+    Some(items)
+}
+
+/// Elements of the top-level SEQUENCE in `der`
+fn sequence(der: &[u8]) -> Option<Vec<Element<'_>>> {
+    let (element, _) = read_element(der)?;
+    expect(element, TAG_SEQUENCE).and_then(sequence_items)
+}
+
+fn expect(element: Element<'_>, tag: u8) -> Option<&[u8]> {
+    (element.0 == tag).then_some(element.1)
+}
+
+/// Small non-negative INTEGER
+fn integer(content: &[u8]) -> Option<u32> {
+    if content.is_empty() || content.len() > 5 {
+        return None;
+    }
+    let value = content.iter().fold(0u64, |value, &b| (value << 8) | b as u64);
+    u32::try_from(value).ok()
+}
@@ -0,0 +1,720 @@
+//! Reader for KeePass 2.x databases (KDBX 3.1 and 4.x), password-only composite keys.
+//!
+//! Supported: AES-256 and ChaCha20 outer ciphers, AES-KDF and Argon2d/Argon2id,
+//! gzip compression, Salsa20 and ChaCha20 inner streams for protected values.
+
+use super::ImportReport;
+use crate::errors::{RpmError, RpmResult};
+use crate::models::{EntryDetails, EntryTimestamps};
+use crate::storage::PasswordStorage;
+use aes::cipher::{BlockEncrypt, KeyInit};
+use aes::Aes256;
+use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+use base64::Engine;
+use cbc::cipher::block_padding::Pkcs7;
+use cbc::cipher::{BlockDecryptMut, KeyIvInit};
+use chacha20::cipher::{KeyIvInit as StreamKeyIvInit, StreamCipher};
+use chacha20::ChaCha20;
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use hmac::{Hmac, Mac};
+use salsa20::Salsa20;
+use sha2::{Digest, Sha256, Sha512};
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+use zeroize::Zeroize;
+
+const SIGNATURE_1: u32 = 0x9AA2_D903;
+const SIGNATURE_2: u32 = 0xB54B_FB67;
+
+const CIPHER_AES256: [u8; 16] = hex16("31c1f2e6bf714350be5805216afc5aff");
+const CIPHER_CHACHA20: [u8; 16] = hex16("d6038a2b8b6f4cb5a524339a31dbb59a");
+const KDF_AES: [u8; 16] = hex16("c9d9f39a628a4460bf740d08c18a4fea");
+const KDF_AES_KDBX4: [u8; 16] = hex16("7c02bb8279a74ac0927d114a00648238");
+const KDF_ARGON2D: [u8; 16] = hex16("ef636ddf8c29444b91f7a9a403e30a0c");
+const KDF_ARGON2ID: [u8; 16] = hex16("9e298b1956db4773b23dfc3ec6f0a1e6");
+
+const INNER_STREAM_SALSA20: u32 = 2;
+const INNER_STREAM_CHACHA20: u32 = 3;
+const SALSA20_IV: [u8; 8] = [0xE8, 0x30, 0x09, 0x4B, 0x97, 0x20, 0x5D, 0x2A];
+
+/// Seconds between 0001-01-01 (KDBX 4 time base) and the Unix epoch
+const KDBX4_EPOCH_OFFSET: i64 = 62_135_596_800;
+
+const fn hex16(s: &str) -> [u8; 16] {
+    const fn nibble(c: u8) -> u8 {
+        match c {
+            b'0'..=b'9' => c - b'0',
+            _ => c - b'a' + 10,
+        }
+    }
+    let bytes = s.as_bytes();
+    let mut out = [0u8; 16];
+    let mut i = 0;
+    while i < 16 {
+        out[i] = (nibble(bytes[2 * i]) << 4) | nibble(bytes[2 * i + 1]);
+        i += 1;
+    }
+    out
+}
+
+/// Entry read from a KeePass database
+#[derive(Debug, Clone, Default)]
+pub struct KdbxEntry {
+    /// Names of the enclosing groups, outermost first (the root group is omitted)
+    pub group_path: Vec<String>,
+    pub title: String,
+    pub username: String,
+    pub password: String,
+    pub url: String,
+    pub notes: String,
+    pub timestamps: EntryTimestamps,
+}
+
+impl KdbxEntry {
+    /// Vault entry name: group path and title joined with `/`
+    pub fn vault_name(&self) -> String {
+        let mut parts: Vec<&str> = self.group_path.iter().map(String::as_str).collect();
+        parts.push(self.title.trim());
+        parts.join("/")
+    }
+}
+
+impl Drop for KdbxEntry {
+    fn drop(&mut self) {
+        self.password.zeroize();
+        self.notes.zeroize();
+    }
+}
+
+/// Open a KDBX file with its master password and return all entries
+/// except those in the recycle bin and old versions kept in entry history
+pub fn load(path: &Path, password: &str) -> RpmResult<Vec<KdbxEntry>> {
+    let data = std::fs::read(path)?;
+    parse(&data, password)
+}
+
+/// Decrypt and parse KDBX file contents
+pub fn parse(data: &[u8], password: &str) -> RpmResult<Vec<KdbxEntry>> {
+    let mut reader = ByteReader::new(data);
+    if reader.u32()? != SIGNATURE_1 || reader.u32()? != SIGNATURE_2 {
+        return Err(invalid("not a KeePass 2.x database"));
+    }
+    let version = reader.u32()?;
+    let major = version >> 16;
+
+    let mut composite = composite_key(password);
+    let result = match major {
+        3 => parse_kdbx3(data, &mut reader, &composite),
+        4 => parse_kdbx4(data, &mut reader, &composite),
+        _ => Err(invalid(&format!("unsupported KDBX version {}.{}", major, version & 0xFFFF))),
+    };
+    composite.zeroize();
+    result
+}
+
+/// Outer header fields shared by KDBX 3 and 4
+#[derive(Default)]
+struct OuterHeader {
+    cipher: Vec<u8>,
+    compressed: bool,
+    master_seed: Vec<u8>,
+    iv: Vec<u8>,
+    // KDBX 3 only
+    transform_seed: Vec<u8>,
+    transform_rounds: u64,
+    protected_stream_key: Vec<u8>,
+    stream_start_bytes: Vec<u8>,
+    inner_stream: u32,
+    // KDBX 4 only
+    kdf_parameters: HashMap<String, Variant>,
+}
+
+impl OuterHeader {
+    fn set(&mut self, id: u8, value: &[u8]) -> RpmResult<()> {
+        match id {
+            2 => self.cipher = value.to_vec(),
+            3 => self.compressed = le_u32(value)? == 1,
+            4 => self.master_seed = value.to_vec(),
+            5 => self.transform_seed = value.to_vec(),
+            6 => self.transform_rounds = le_u64(value)?,
+            7 => self.iv = value.to_vec(),
+            8 => self.protected_stream_key = value.to_vec(),
+            9 => self.stream_start_bytes = value.to_vec(),
+            10 => self.inner_stream = le_u32(value)?,
+            11 => self.kdf_parameters = parse_variant_dictionary(value)?,
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+fn parse_kdbx3(data: &[u8], reader: &mut ByteReader, composite: &[u8; 32]) -> RpmResult<Vec<KdbxEntry>> {
+    let mut header = OuterHeader::default();
+    loop {
+        let id = reader.u8()?;
+        let len = reader.u16()? as usize;
+        let value = reader.take(len)?;
+        if id == 0 {
+            break;
+        }
+        header.set(id, value)?;
+    }
+
+    let mut transformed = aes_kdf(composite, &header.transform_seed, header.transform_rounds)?;
+    let mut master_key = sha256(&[&header.master_seed, &transformed]);
+    transformed.zeroize();
+
+    let payload = decrypt_payload(&header.cipher, &master_key, &header.iv, &data[reader.pos..]);
+    master_key.zeroize();
+    let mut payload = payload?;
+
+    if payload.len() < 32 || payload[..32] != header.stream_start_bytes[..] {
+        payload.zeroize();
+        return Err(RpmError::AuthenticationFailed);
+    }
+    let blocks = read_hashed_blocks(&payload[32..]);
+    payload.zeroize();
+    let mut content = blocks?;
+    if header.compressed {
+        let decompressed = gunzip(&content);
+        content.zeroize();
+        content = decompressed?;
+    }
+
+    let mut stream = InnerStream::new(header.inner_stream, &header.protected_stream_key)?;
+    let entries = parse_xml(&content, &mut stream);
+    content.zeroize();
+    entries
+}
+
+fn parse_kdbx4(data: &[u8], reader: &mut ByteReader, composite: &[u8; 32]) -> RpmResult<Vec<KdbxEntry>> {
+    let mut header = OuterHeader::default();
+    loop {
+        let id = reader.u8()?;
+        let len = reader.u32()? as usize;
+        let value = reader.take(len)?;
+        if id == 0 {
+            break;
+        }
+        header.set(id, value)?;
+    }
+    let header_bytes = &data[..reader.pos];
+    let header_hash = reader.take(32)?;
+    let header_hmac = reader.take(32)?;
+    if sha256(&[header_bytes])[..] != header_hash[..] {
+        return Err(invalid("header checksum mismatch"));
+    }
+
+    let mut transformed = derive_kdbx4_key(composite, &header.kdf_parameters)?;
+    let mut master_key = sha256(&[&header.master_seed, &transformed]);
+    let mut hmac_key: [u8; 64] = Sha512::new()
+        .chain_update(&header.master_seed)
+        .chain_update(transformed)
+        .chain_update([1u8])
+        .finalize()
+        .into();
+    transformed.zeroize();
+
+    let header_ok = block_hmac(&hmac_key, u64::MAX, &[header_bytes]).verify_slice(header_hmac).is_ok();
+    let blocks = if header_ok {
+        read_hmac_blocks(&data[reader.pos..], &hmac_key)
+    } else {
+        Err(RpmError::AuthenticationFailed)
+    };
+    hmac_key.zeroize();
+    let blocks = match blocks {
+        Ok(blocks) => blocks,
+        Err(e) => {
+            master_key.zeroize();
+            return Err(e);
+        }
+    };
+
+    let payload = decrypt_payload(&header.cipher, &master_key, &header.iv, &blocks);
+    master_key.zeroize();
+    let mut content = payload?;
+    if header.compressed {
+        let decompressed = gunzip(&content);
+        content.zeroize();
+        content = decompressed?;
+    }
+
+    // Inner header: protected stream settings and attachments
+    let mut inner = ByteReader::new(&content);
+    let mut inner_stream = 0;
+    let mut stream_key = Vec::new();
+    loop {
+        let id = inner.u8()?;
+        let len = inner.u32()? as usize;
+        let value = inner.take(len)?;
+        match id {
+            0 => break,
+            1 => inner_stream = le_u32(value)?,
+            2 => stream_key = value.to_vec(),
+            _ => {}
+        }
+    }
+
+    let mut stream = InnerStream::new(inner_stream, &stream_key);
+    stream_key.zeroize();
+    let entries = match stream {
+        Ok(ref mut stream) => parse_xml(&content[inner.pos..], stream),
+        Err(e) => Err(e),
+    };
+    content.zeroize();
+    entries
+}
+
+/// SHA-256 of SHA-256(password); key files and Windows user accounts are not supported
+fn composite_key(password: &str) -> [u8; 32] {
+    let mut password_hash = sha256(&[password.as_bytes()]);
+    let composite = sha256(&[&password_hash]);
+    password_hash.zeroize();
+    composite
+}
+
+fn derive_kdbx4_key(composite: &[u8; 32], params: &HashMap<String, Variant>) -> RpmResult<[u8; 32]> {
+    let uuid = params.get("$UUID").and_then(Variant::bytes).unwrap_or_default();
+    let salt = params.get("S").and_then(Variant::bytes).unwrap_or_default();
+
+    if uuid == KDF_AES || uuid == KDF_AES_KDBX4 {
+        let rounds = params.get("R").and_then(Variant::u64).unwrap_or(0);
+        return aes_kdf(composite, salt, rounds);
+    }
+
+    let algorithm = if uuid == KDF_ARGON2D {
+        argon2::Algorithm::Argon2d
+    } else if uuid == KDF_ARGON2ID {
+        argon2::Algorithm::Argon2id
+    } else {
+        return Err(invalid("unsupported key derivation function"));
+    };
+    let iterations = params.get("I").and_then(Variant::u64).unwrap_or(0);
+    let memory = params.get("M").and_then(Variant::u64).unwrap_or(0) / 1024;
+    let parallelism = params.get("P").and_then(Variant::u64).unwrap_or(0);
+    let version = match params.get("V").and_then(Variant::u64) {
+        Some(0x10) => argon2::Version::V0x10,
+        _ => argon2::Version::V0x13,
+    };
+
+    let argon_params = argon2::Params::new(
+        u32::try_from(memory).map_err(|_| invalid("Argon2 memory out of range"))?,
+        u32::try_from(iterations).map_err(|_| invalid("Argon2 iterations out of range"))?,
+        u32::try_from(parallelism).map_err(|_| invalid("Argon2 parallelism out of range"))?,
+        Some(32),
+    )
+    .map_err(|e| RpmError::Crypto(format!("Invalid Argon2 parameters: {}", e)))?;
+
+    let mut key = [0u8; 32];
+    argon2::Argon2::new(algorithm, version, argon_params)
+        .hash_password_into(composite, salt, &mut key)
+        .map_err(|e| RpmError::Crypto(format!("Argon2 failed: {}", e)))?;
+    Ok(key)
+}
+
+/// KeePass AES-KDF: encrypt the key with AES-256-ECB `rounds` times, then hash it
+fn aes_kdf(composite: &[u8; 32], seed: &[u8], rounds: u64) -> RpmResult<[u8; 32]> {
+    let cipher = Aes256::new_from_slice(seed).map_err(|_| invalid("invalid AES-KDF seed"))?;
+    let mut blocks = [
+        aes::Block::clone_from_slice(&composite[..16]),
+        aes::Block::clone_from_slice(&composite[16..]),
+    ];
+    for _ in 0..rounds {
+        cipher.encrypt_blocks(&mut blocks);
+    }
+    let key = sha256(&[&blocks[0], &blocks[1]]);
+    blocks.iter_mut().for_each(|b| b.as_mut_slice().zeroize());
+    Ok(key)
+}
+
+fn decrypt_payload(cipher: &[u8], key: &[u8; 32], iv: &[u8], data: &[u8]) -> RpmResult<Vec<u8>> {
+    if cipher == CIPHER_AES256 {
+        let decryptor = cbc::Decryptor::<Aes256>::new_from_slices(key, iv)
+            .map_err(|_| invalid("invalid AES IV"))?;
+        // A wrong key almost always shows up as broken padding
+        decryptor
+            .decrypt_padded_vec_mut::<Pkcs7>(data)
+            .map_err(|_| RpmError::AuthenticationFailed)
+    } else if cipher == CIPHER_CHACHA20 {
+        let mut chacha = <ChaCha20 as StreamKeyIvInit>::new_from_slices(key, iv)
+            .map_err(|_| invalid("invalid ChaCha20 nonce"))?;
+        let mut out = data.to_vec();
+        chacha.apply_keystream(&mut out);
+        Ok(out)
+    } else {
+        Err(invalid("unsupported cipher (only AES-256 and ChaCha20 are supported)"))
+    }
+}
+
+/// KDBX 3 payload: blocks of (index, SHA-256, size, data), terminated by an empty block
+fn read_hashed_blocks(data: &[u8]) -> RpmResult<Vec<u8>> {
+    let mut reader = ByteReader::new(data);
+    let mut out = Vec::new();
+    loop {
+        let _index = reader.u32()?;
+        let hash = reader.take(32)?;
+        let size = reader.u32()? as usize;
+        if size == 0 {
+            return Ok(out);
+        }
+        let block = reader.take(size)?;
+        if sha256(&[block])[..] != hash[..] {
+            out.zeroize();
+            return Err(invalid("block checksum mismatch"));
+        }
+        out.extend_from_slice(block);
+    }
+}
+
+/// KDBX 4 payload: blocks of (HMAC, size, data), terminated by an empty block
+fn read_hmac_blocks(data: &[u8], hmac_key: &[u8; 64]) -> RpmResult<Vec<u8>> {
+    let mut reader = ByteReader::new(data);
+    let mut out = Vec::new();
+    for index in 0u64.. {
+        let hmac = reader.take(32)?;
+        let size_bytes = reader.take(4)?;
+        let size = le_u32(size_bytes)? as usize;
+        let block = reader.take(size)?;
+        let mac = block_hmac(hmac_key, index, &[&index.to_le_bytes(), size_bytes, block]);
+        if mac.verify_slice(hmac).is_err() {
+            out.zeroize();
+            return Err(invalid("block authentication failed"));
+        }
+        if size == 0 {
+            break;
+        }
+        out.extend_from_slice(block);
+    }
+    Ok(out)
+}
+
+fn block_hmac(hmac_key: &[u8; 64], index: u64, parts: &[&[u8]]) -> Hmac<Sha256> {
+    let mut block_key: [u8; 64] = Sha512::new()
+        .chain_update(index.to_le_bytes())
+        .chain_update(hmac_key)
+        .finalize()
+        .into();
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(&block_key).expect("HMAC accepts any key length");
+    block_key.zeroize();
+    for part in parts {
+        mac.update(part);
+    }
+    mac
+}
+
+fn gunzip(data: &[u8]) -> RpmResult<Vec<u8>> {
+    let mut out = Vec::new();
+    GzDecoder::new(data)
+        .read_to_end(&mut out)
+        .map_err(|e| invalid(&format!("failed to decompress: {}", e)))?;
+    Ok(out)
+}
+
+/// Keystream used to hide protected values (passwords) inside the XML
+enum InnerStream {
+    Salsa20(Salsa20),
+    ChaCha20(ChaCha20),
+}
+
+impl InnerStream {
+    fn new(id: u32, key: &[u8]) -> RpmResult<Self> {
+        match id {
+            INNER_STREAM_SALSA20 => {
+                let mut key = sha256(&[key]);
+                let cipher = Salsa20::new(&key.into(), &SALSA20_IV.into());
+                key.zeroize();
+                Ok(InnerStream::Salsa20(cipher))
+            }
+            INNER_STREAM_CHACHA20 => {
+                let mut hash: [u8; 64] = Sha512::digest(key).into();
+                let cipher = <ChaCha20 as StreamKeyIvInit>::new_from_slices(&hash[..32], &hash[32..44])
+                    .map_err(|_| invalid("invalid inner stream key"));
+                hash.zeroize();
+                Ok(InnerStream::ChaCha20(cipher?))
+            }
+            _ => Err(invalid("unsupported inner stream cipher")),
+        }
+    }
+
+    fn apply(&mut self, data: &mut [u8]) {
+        match self {
+            InnerStream::Salsa20(cipher) => cipher.apply_keystream(data),
+            InnerStream::ChaCha20(cipher) => cipher.apply_keystream(data),
+        }
+    }
+}
+
+fn parse_xml(content: &[u8], stream: &mut InnerStream) -> RpmResult<Vec<KdbxEntry>> {
+    let text = std::str::from_utf8(content).map_err(|_| invalid("XML is not valid UTF-8"))?;
+    let doc = roxmltree::Document::parse(text).map_err(|e| invalid(&format!("invalid XML: {}", e)))?;
+
+    // The keystream runs over every protected value in document order,
+    // including those in entry history that are not imported
+    let mut protected: HashMap<roxmltree::NodeId, String> = HashMap::new();
+    for node in doc.descendants().filter(|n| n.has_tag_name("Value")) {
+        if !node.attribute("Protected").is_some_and(|p| p.eq_ignore_ascii_case("true")) {
+            continue;
+        }
+        let mut bytes = BASE64_STANDARD
+            .decode(node.text().unwrap_or("").trim())
+            .map_err(|_| invalid("invalid protected value"))?;
+        stream.apply(&mut bytes);
+        let value = String::from_utf8(bytes).map_err(|e| {
+            let mut bytes = e.into_bytes();
+            bytes.zeroize();
+            invalid("protected value is not valid UTF-8")
+        })?;
+        protected.insert(node.id(), value);
+    }
+
+    let recycle_bin = doc
+        .descendants()
+        .find(|n| n.has_tag_name("RecycleBinUUID"))
+        .and_then(|n| n.text())
+        .map(str::trim)
+        .filter(|uuid| !uuid.is_empty() && *uuid != "AAAAAAAAAAAAAAAAAAAAAA==");
+
+    let root_group = doc
+        .descendants()
+        .find(|n| n.has_tag_name("Root"))
+        .and_then(|root| child(root, "Group"))
+        .ok_or_else(|| invalid("database has no root group"))?;
+
+    let mut entries = Vec::new();
+    collect_group(root_group, &[], recycle_bin, &mut protected, &mut entries);
+    for value in protected.values_mut() {
+        value.zeroize();
+    }
+    Ok(entries)
+}
+
+fn collect_group(
+    group: roxmltree::Node,
+    path: &[String],
+    recycle_bin: Option<&str>,
+    protected: &mut HashMap<roxmltree::NodeId, String>,
+    entries: &mut Vec<KdbxEntry>,
+) {
+    for node in group.children().filter(|n| n.is_element()) {
+        match node.tag_name().name() {
+            "Entry" => entries.push(read_entry(node, path, protected)),
+            "Group" => {
+                let uuid = child_text(node, "UUID");
+                if recycle_bin.is_some_and(|bin| uuid.trim() == bin) {
+                    continue;
+                }
+                let mut sub_path = path.to_vec();
+                sub_path.push(child_text(node, "Name").trim().to_string());
+                collect_group(node, &sub_path, recycle_bin, protected, entries);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn read_entry(
+    node: roxmltree::Node,
+    path: &[String],
+    protected: &mut HashMap<roxmltree::NodeId, String>,
+) -> KdbxEntry {
+    let mut entry = KdbxEntry::default();
+    entry.group_path = path.to_vec();
+
+    for string in node.children().filter(|n| n.has_tag_name("String")) {
+        let Some(value_node) = child(string, "Value") else {
+            continue;
+        };
+        let value = protected
+            .remove(&value_node.id())
+            .unwrap_or_else(|| value_node.text().unwrap_or("").to_string());
+        match child_text(string, "Key").as_str() {
+            "Title" => entry.title = value,
+            "UserName" => entry.username = value,
+            "Password" => entry.password = value,
+            "URL" => entry.url = value,
+            "Notes" => entry.notes = value,
+            _ => {
+                let mut value = value;
+                value.zeroize();
+            }
+        }
+    }
+
+    if let Some(times) = child(node, "Times") {
+        entry.timestamps.created_at = parse_time(&child_text(times, "CreationTime"));
+        entry.timestamps.updated_at = parse_time(&child_text(times, "LastModificationTime"));
+        entry.timestamps.last_used_at = parse_time(&child_text(times, "LastAccessTime"));
+    }
+    entry
+}
+
+/// KDBX 3 stores ISO 8601 times, KDBX 4 base64 seconds since 0001-01-01
+fn parse_time(text: &str) -> Option<DateTime<Utc>> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(text) {
+        return Some(datetime.with_timezone(&Utc));
+    }
+    let bytes = BASE64_STANDARD.decode(text).ok()?;
+    let seconds = i64::from_le_bytes(bytes.try_into().ok()?);
+    DateTime::from_timestamp(seconds.checked_sub(KDBX4_EPOCH_OFFSET)?, 0)
+}
+
+fn child<'a, 'input>(node: roxmltree::Node<'a, 'input>, name: &str) -> Option<roxmltree::Node<'a, 'input>> {
+    node.children().find(|n| n.has_tag_name(name))
+}
+
+fn child_text(node: roxmltree::Node, name: &str) -> String {
+    child(node, name).and_then(|n| n.text()).unwrap_or("").to_string()
+}
+
+/// Values of a KDBX 4 variant dictionary (KDF parameters)
+enum Variant {
+    UInt(u64),
+    Bytes(Vec<u8>),
+    Other,
+}
+
+impl Variant {
+    fn u64(&self) -> Option<u64> {
+        match self {
+            Variant::UInt(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    fn bytes(&self) -> Option<&[u8]> {
+        match self {
+            Variant::Bytes(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+fn parse_variant_dictionary(data: &[u8]) -> RpmResult<HashMap<String, Variant>> {
+    let mut reader = ByteReader::new(data);
+    let _version = reader.u16()?;
+    let mut map = HashMap::new();
+    loop {
+        let kind = reader.u8()?;
+        if kind == 0 {
+            return Ok(map);
+        }
+        let key_len = reader.u32()? as usize;
+        let key = String::from_utf8_lossy(reader.take(key_len)?).into_owned();
+        let value_len = reader.u32()? as usize;
+        let value = reader.take(value_len)?;
+        let variant = match kind {
+            0x04 => Variant::UInt(le_u32(value)? as u64),
+            0x05 => Variant::UInt(le_u64(value)?),
+            0x42 => Variant::Bytes(value.to_vec()),
+            _ => Variant::Other,
+        };
+        map.insert(key, variant);
+    }
+}
+
+/// Bounds-checked little-endian reader
+struct ByteReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn take(&mut self, len: usize) -> RpmResult<&'a [u8]> {
+        let end = self.pos.checked_add(len).filter(|end| *end <= self.data.len())
+            .ok_or_else(|| invalid("file is truncated"))?;
+        let slice = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn u8(&mut self) -> RpmResult<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> RpmResult<u16> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u32(&mut self) -> RpmResult<u32> {
+        le_u32(self.take(4)?)
+    }
+}
+
+fn le_u32(bytes: &[u8]) -> RpmResult<u32> {
+    Ok(u32::from_le_bytes(bytes.try_into().map_err(|_| invalid("bad header field"))?))
+}
+
+fn le_u64(bytes: &[u8]) -> RpmResult<u64> {
+    Ok(u64::from_le_bytes(bytes.try_into().map_err(|_| invalid("bad header field"))?))
+}
+
+fn sha256(parts: &[&[u8]]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.finalize().into()
+}
+
+fn invalid(message: &str) -> RpmError {
+    RpmError::InvalidInput(format!("KeePass database: {}", message))
+}
+
+/// Create a vault entry for every KeePass entry with a title. Group names become
+/// a `Group/Subgroup/` prefix of the entry name.
+pub fn import_entries(storage: &PasswordStorage, entries: &[KdbxEntry], key: &[u8]) -> RpmResult<ImportReport> {
+    let mut report = ImportReport::default();
+    let now = Utc::now();
+
+    for entry in entries {
+        if entry.title.trim().is_empty() {
+            report.skipped += 1;
+            continue;
+        }
+
+        let details = EntryDetails {
+            username: non_empty(&entry.username),
+            url: non_empty(&entry.url),
+            notes: non_empty(&entry.notes),
+            timestamps: EntryTimestamps {
+                created_at: entry.timestamps.created_at.or(Some(now)),
+                updated_at: entry.timestamps.updated_at.or(Some(now)),
+                last_used_at: entry.timestamps.last_used_at,
+            },
+            ..EntryDetails::default()
+        };
+
+        let filename = storage.add_entry(&entry.vault_name(), key)?;
+        let result = storage
+            .update_password_file(&filename, &entry.password, key)
+            .and_then(|_| storage.update_entry_details(&filename, &details, key));
+        let mut details = details;
+        if let Some(ref mut notes) = details.notes {
+            notes.zeroize();
+        }
+        result?;
+        report.created += 1;
+    }
+
+    Ok(report)
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    let trimmed = value.trim();
+    if trimmed.is_empty() {
+        None
+    } else {
+        Some(value.to_string())
+    }
+}
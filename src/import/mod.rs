@@ -0,0 +1,257 @@
+use crate::errors::{RpmError, RpmResult};
+use crate::models::EntryDetails;
+use crate::storage::PasswordStorage;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use zeroize::Zeroize;
+
+pub mod browser;
+pub mod chromium;
+pub mod credstore;
+pub mod firefox;
+pub mod kdbx;
+pub mod pass;
+
+/// Parsed CSV file: header row and data rows
+#[derive(Debug, Clone, Default)]
+pub struct CsvTable {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl CsvTable {
+    /// Read and parse a CSV file
+    pub fn load(path: &Path) -> RpmResult<Self> {
+        let mut text = std::fs::read_to_string(path)?;
+        let table = parse_csv(&text);
+        text.zeroize();
+        table
+    }
+}
+
+impl Drop for CsvTable {
+    fn drop(&mut self) {
+        // Rows contain plaintext passwords
+        for row in self.rows.iter_mut() {
+            for field in row.iter_mut() {
+                field.zeroize();
+            }
+        }
+    }
+}
+
+/// Parse CSV text (RFC 4180: quoted fields, doubled quotes, CRLF or LF line endings).
+/// The first record is the header row; empty records are skipped.
+pub fn parse_csv(text: &str) -> RpmResult<CsvTable> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let mut records: Vec<Vec<String>> = Vec::new();
+    let mut record: Vec<String> = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+
+        match c {
+            '"' => in_quotes = true,
+            ',' => record.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' | '\r' => {
+                record.push(std::mem::take(&mut field));
+                if record.iter().any(|f| !f.is_empty()) {
+                    records.push(std::mem::take(&mut record));
+                } else {
+                    record.clear();
+                }
+            }
+            _ => field.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err(RpmError::InvalidInput("Unterminated quoted field in CSV".to_string()));
+    }
+    record.push(field);
+    if record.iter().any(|f| !f.is_empty()) {
+        records.push(record);
+    }
+
+    let mut records = records.into_iter();
+    let headers = records
+        .next()
+        .ok_or_else(|| RpmError::InvalidInput("CSV file is empty".to_string()))?
+        .into_iter()
+        .map(|h| h.trim().to_string())
+        .collect();
+
+    Ok(CsvTable {
+        headers,
+        rows: records.collect(),
+    })
+}
+
+/// Entry field a CSV column is imported into
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportField {
+    Skip,
+    Name,
+    Username,
+    Password,
+    Url,
+    Notes,
+}
+
+impl ImportField {
+    pub const ALL: [ImportField; 6] = [
+        ImportField::Skip,
+        ImportField::Name,
+        ImportField::Username,
+        ImportField::Password,
+        ImportField::Url,
+        ImportField::Notes,
+    ];
+
+    /// Translation key for the field name
+    pub fn i18n_key(self) -> &'static str {
+        match self {
+            ImportField::Skip => "import_field_skip",
+            ImportField::Name => "import_field_name",
+            ImportField::Username => "import_field_username",
+            ImportField::Password => "import_field_password",
+            ImportField::Url => "import_field_url",
+            ImportField::Notes => "import_field_notes",
+        }
+    }
+
+    /// Next field in `ALL`, wrapping around
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|f| *f == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+
+    /// Previous field in `ALL`, wrapping around
+    pub fn prev(self) -> Self {
+        let i = Self::ALL.iter().position(|f| *f == self).unwrap_or(0);
+        Self::ALL[(i + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+
+    /// Guess the field from a header as written by common password managers and browsers
+    fn guess(header: &str) -> Self {
+        match header.trim().to_lowercase().as_str() {
+            "name" | "title" | "account" | "site" | "entry" => ImportField::Name,
+            "username" | "user" | "login" | "login_username" | "email" | "user name" => ImportField::Username,
+            "password" | "pass" | "login_password" => ImportField::Password,
+            "url" | "website" | "uri" | "login_uri" | "web site" => ImportField::Url,
+            "notes" | "note" | "comment" | "comments" | "extra" => ImportField::Notes,
+            _ => ImportField::Skip,
+        }
+    }
+}
+
+/// Guess a column mapping from the headers; each field is assigned to at most one column
+pub fn guess_mapping(headers: &[String]) -> Vec<ImportField> {
+    let mut mapping: Vec<ImportField> = Vec::with_capacity(headers.len());
+    for header in headers {
+        let field = ImportField::guess(header);
+        if field != ImportField::Skip && mapping.contains(&field) {
+            mapping.push(ImportField::Skip);
+        } else {
+            mapping.push(field);
+        }
+    }
+    mapping
+}
+
+/// Key identifying a CSV format by its header row (case and surrounding spaces ignored)
+pub fn format_signature(headers: &[String]) -> String {
+    headers
+        .iter()
+        .map(|h| h.trim().to_lowercase())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Mapping saved for this header row by a previous import, or a guessed one.
+/// Returns whether the saved mapping was used.
+pub fn saved_or_guessed_mapping(
+    headers: &[String],
+    saved: &BTreeMap<String, Vec<ImportField>>,
+) -> (Vec<ImportField>, bool) {
+    match saved.get(&format_signature(headers)) {
+        Some(mapping) if mapping.len() == headers.len() => (mapping.clone(), true),
+        _ => (guess_mapping(headers), false),
+    }
+}
+
+/// Outcome of an import
+#[derive(Debug, Clone, Default)]
+pub struct ImportReport {
+    pub created: usize,
+    /// Rows without a name
+    pub skipped: usize,
+    /// Why individual items could not be imported, for the caller to show
+    pub warnings: Vec<String>,
+}
+
+/// Create one entry per row using the column mapping. Rows without a name are skipped.
+/// When several columns map to the same field, the last non-empty one wins.
+pub fn import_rows(
+    storage: &PasswordStorage,
+    table: &CsvTable,
+    mapping: &[ImportField],
+    key: &[u8],
+) -> RpmResult<ImportReport> {
+    let mut report = ImportReport::default();
+
+    for row in &table.rows {
+        let mut name = String::new();
+        let mut password = String::new();
+        let mut details = EntryDetails::default();
+        details.timestamps.created_at = Some(Utc::now());
+        details.timestamps.updated_at = details.timestamps.created_at;
+
+        for (value, field) in row.iter().zip(mapping.iter()) {
+            if value.trim().is_empty() {
+                continue;
+            }
+            match field {
+                ImportField::Skip => {}
+                ImportField::Name => name = value.trim().to_string(),
+                ImportField::Username => details.username = Some(value.clone()),
+                ImportField::Password => password = value.clone(),
+                ImportField::Url => details.url = Some(value.trim().to_string()),
+                ImportField::Notes => details.notes = Some(value.clone()),
+            }
+        }
+
+        if name.is_empty() {
+            report.skipped += 1;
+            password.zeroize();
+            continue;
+        }
+
+        let filename = storage.add_entry(&name, key)?;
+        let result = storage
+            .update_password_file(&filename, &password, key)
+            .and_then(|_| storage.update_entry_details(&filename, &details, key));
+        password.zeroize();
+        result?;
+        report.created += 1;
+    }
+
+    Ok(report)
+}
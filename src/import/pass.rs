@@ -0,0 +1,184 @@
+//! Importer for `pass` (the standard unix password manager) stores.
+//!
+//! Every `*.gpg` file under the store is decrypted with the user's `gpg` binary.
+//! The first line is the password; following `key: value` lines are metadata
+//! (login and URL fields are recognised), everything else becomes notes.
+
+use super::ImportReport;
+use crate::errors::{RpmError, RpmResult};
+use crate::models::EntryDetails;
+use crate::storage::PasswordStorage;
+use chrono::Utc;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use zeroize::Zeroize;
+
+/// Store location: `$PASSWORD_STORE_DIR` or `~/.password-store`
+pub fn default_store_dir() -> PathBuf {
+    std::env::var_os("PASSWORD_STORE_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| {
+            dirs::home_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join(".password-store")
+        })
+}
+
+/// Entry decoded from a pass file
+#[derive(Debug, Default)]
+pub struct PassEntry {
+    pub password: String,
+    pub username: Option<String>,
+    pub url: Option<String>,
+    pub notes: Option<String>,
+}
+
+impl Drop for PassEntry {
+    fn drop(&mut self) {
+        self.password.zeroize();
+        if let Some(ref mut notes) = self.notes {
+            notes.zeroize();
+        }
+    }
+}
+
+/// Split decrypted pass content into password, known metadata fields and notes
+pub fn parse_entry(content: &str) -> PassEntry {
+    let mut lines = content.lines();
+    let mut entry = PassEntry::default();
+    entry.password = lines.next().unwrap_or("").to_string();
+
+    let mut notes: Vec<&str> = Vec::new();
+    for line in lines {
+        let field = line.split_once(':').and_then(|(key, value)| {
+            let value = value.trim();
+            if value.is_empty() {
+                return None;
+            }
+            match key.trim().to_lowercase().as_str() {
+                "user" | "username" | "login" | "email" | "e-mail" => Some((true, value)),
+                "url" | "website" | "site" => Some((false, value)),
+                _ => None,
+            }
+        });
+        match field {
+            Some((true, value)) if entry.username.is_none() => entry.username = Some(value.to_string()),
+            Some((false, value)) if entry.url.is_none() => entry.url = Some(value.to_string()),
+            _ => notes.push(line),
+        }
+    }
+
+    let mut notes = notes.join("\n");
+    if !notes.trim().is_empty() {
+        entry.notes = Some(notes.trim().to_string());
+    }
+    notes.zeroize();
+    entry
+}
+
+/// All `*.gpg` files under the store, sorted; hidden directories (`.git`) are skipped
+pub fn list_entries(store: &Path) -> RpmResult<Vec<PathBuf>> {
+    if !store.is_dir() {
+        return Err(RpmError::InvalidInput(format!(
+            "Password store not found: {}",
+            store.display()
+        )));
+    }
+    let mut files = Vec::new();
+    let mut dirs = vec![store.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        for item in std::fs::read_dir(&dir)? {
+            let path = item?.path();
+            let hidden = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with('.'));
+            if hidden {
+                continue;
+            }
+            if path.is_dir() {
+                dirs.push(path);
+            } else if path.extension().is_some_and(|ext| ext == "gpg") {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Entry name: path relative to the store without the `.gpg` extension
+pub fn entry_name(store: &Path, file: &Path) -> String {
+    let relative = file.strip_prefix(store).unwrap_or(file).with_extension("");
+    relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Decrypt a file with `gpg`; prompts (pinentry) go to the terminal as usual
+fn decrypt(file: &Path) -> RpmResult<String> {
+    let output = Command::new("gpg")
+        .args(["--quiet", "--decrypt"])
+        .arg(file)
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .output()
+        .map_err(|e| RpmError::InvalidInput(format!("Failed to run gpg: {}", e)))?;
+    if !output.status.success() {
+        let mut stdout = output.stdout;
+        stdout.zeroize();
+        return Err(RpmError::Crypto(format!(
+            "gpg could not decrypt {} ({})",
+            file.display(),
+            output.status
+        )));
+    }
+    String::from_utf8(output.stdout).map_err(|e| {
+        let mut bytes = e.into_bytes();
+        bytes.zeroize();
+        RpmError::InvalidInput(format!("{} is not valid UTF-8", file.display()))
+    })
+}
+
+/// Import every entry of the store. Files gpg fails to decrypt are counted as skipped
+/// and reported in `warnings`.
+pub fn import_store(storage: &PasswordStorage, store: &Path, key: &[u8]) -> RpmResult<ImportReport> {
+    let mut report = ImportReport::default();
+    let now = Utc::now();
+
+    for file in list_entries(store)? {
+        let mut content = match decrypt(&file) {
+            Ok(content) => content,
+            Err(e) => {
+                report.warnings.push(e.to_string());
+                report.skipped += 1;
+                continue;
+            }
+        };
+        let entry = parse_entry(&content);
+        content.zeroize();
+
+        let mut details = EntryDetails {
+            username: entry.username.clone(),
+            url: entry.url.clone(),
+            notes: entry.notes.clone(),
+            ..EntryDetails::default()
+        };
+        details.timestamps.created_at = Some(now);
+        details.timestamps.updated_at = Some(now);
+
+        let filename = storage.add_entry(&entry_name(store, &file), key)?;
+        let result = storage
+            .update_password_file(&filename, &entry.password, key)
+            .and_then(|_| storage.update_entry_details(&filename, &details, key));
+        if let Some(ref mut notes) = details.notes {
+            notes.zeroize();
+        }
+        result?;
+        report.created += 1;
+    }
+
+    Ok(report)
+}
@@ -0,0 +1,242 @@
+//! Single-instance guard. The first `rpm` started without a command holds an
+//! exclusive lock on a lock file and owns a local socket next to the config; a
+//! second launch finds the lock taken, hands its request (raise the window, copy
+//! an entry) to the running instance over the socket and exits instead of opening
+//! the vault twice.
+//!
+//! The socket is a Unix domain socket, so on other platforms every launch runs
+//! as the primary instance.
+
+use crate::config::Config;
+use crate::errors::{RpmError, RpmResult};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+/// How long a second launch waits for the running instance to answer
+#[cfg(unix)]
+const SEND_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(3);
+
+/// Longest request line accepted from the socket
+#[cfg(unix)]
+const MAX_REQUEST_LEN: u64 = 4096;
+
+/// Request passed from a second launch to the running instance
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InstanceRequest {
+    /// Bring the running instance to the user's attention
+    Focus,
+    /// Copy the password of the entry with this name (`rpm --copy <name>`)
+    Copy(String),
+}
+
+impl InstanceRequest {
+    /// One line of the socket protocol: `focus` or `copy <name>`
+    pub fn to_line(&self) -> String {
+        match self {
+            Self::Focus => "focus".to_string(),
+            Self::Copy(name) => format!("copy {}", name),
+        }
+    }
+
+    pub fn parse(line: &str) -> Option<Self> {
+        let line = line.trim_end_matches(['\r', '\n']);
+        if line == "focus" {
+            return Some(Self::Focus);
+        }
+        line.strip_prefix("copy ")
+            .filter(|name| !name.trim().is_empty())
+            .map(|name| Self::Copy(name.to_string()))
+    }
+}
+
+/// Requests received from other launches, drained by the TUI loop
+#[derive(Clone, Default)]
+pub struct InstanceHandle {
+    requests: Arc<Mutex<Vec<InstanceRequest>>>,
+}
+
+impl InstanceHandle {
+    pub fn push(&self, request: InstanceRequest) {
+        self.requests.lock().unwrap_or_else(|e| e.into_inner()).push(request);
+    }
+
+    /// Take the pending requests, oldest first
+    pub fn take_requests(&self) -> Vec<InstanceRequest> {
+        std::mem::take(&mut *self.requests.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+}
+
+/// Held by the primary instance: keeps the lock file locked and removes the socket on drop
+pub struct InstanceGuard {
+    #[cfg_attr(not(unix), allow(dead_code))]
+    socket_path: PathBuf,
+    /// Locked exclusively for as long as the instance runs
+    #[cfg(unix)]
+    _lock_file: std::fs::File,
+    #[cfg(unix)]
+    listener: Option<tokio::net::UnixListener>,
+}
+
+fn paths() -> RpmResult<(PathBuf, PathBuf)> {
+    let lock_path = Config::instance_lock_path().map_err(|e| RpmError::Config(e.to_string()))?;
+    let socket_path = Config::instance_socket_path().map_err(|e| RpmError::Config(e.to_string()))?;
+    Ok((lock_path, socket_path))
+}
+
+/// Become the primary instance, or return `None` if another instance holds the lock
+/// and answers on the socket. Whoever holds the exclusive lock on the lock file owns
+/// the socket, so two launches at once can't both bind it; a socket left behind by
+/// a crashed instance is replaced, as the lock died with it.
+#[cfg(unix)]
+pub async fn acquire() -> RpmResult<Option<InstanceGuard>> {
+    use std::io::Write;
+    use std::os::fd::AsRawFd;
+    use std::os::unix::fs::OpenOptionsExt;
+
+    let (lock_path, socket_path) = paths()?;
+    if let Some(parent) = lock_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut lock_file = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .mode(0o600)
+        .open(&lock_path)?;
+    // SAFETY: flock on a descriptor owned by `lock_file`
+    if unsafe { libc::flock(lock_file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) } != 0 {
+        let e = std::io::Error::last_os_error();
+        if e.kind() != std::io::ErrorKind::WouldBlock {
+            return Err(e.into());
+        }
+        // The holder may still be starting up: give it a moment to bind the socket
+        let connect = async {
+            while tokio::net::UnixStream::connect(&socket_path).await.is_err() {
+                tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            }
+        };
+        return match tokio::time::timeout(SEND_TIMEOUT, connect).await {
+            Ok(()) => Ok(None),
+            Err(_) => Err(RpmError::Instance("another instance holds the lock but does not answer".to_string())),
+        };
+    }
+
+    // For diagnostics only: the lock, not the contents, decides who owns the socket
+    lock_file.set_len(0)?;
+    lock_file.write_all(std::process::id().to_string().as_bytes())?;
+    match std::fs::remove_file(&socket_path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+        _ => {}
+    }
+    // Only the owner may send requests to the vault: the socket is created 0600
+    // rather than narrowed after bind, when others could already connect
+    // SAFETY: umask only swaps the process file mode mask
+    let umask = unsafe { libc::umask(0o177) };
+    let bound = tokio::net::UnixListener::bind(&socket_path);
+    unsafe { libc::umask(umask) };
+    let listener = bound?;
+
+    Ok(Some(InstanceGuard {
+        socket_path,
+        _lock_file: lock_file,
+        listener: Some(listener),
+    }))
+}
+
+#[cfg(not(unix))]
+pub async fn acquire() -> RpmResult<Option<InstanceGuard>> {
+    let (_, socket_path) = paths()?;
+    Ok(Some(InstanceGuard { socket_path }))
+}
+
+/// Send a request to the running instance and wait for it to be accepted
+#[cfg(unix)]
+pub async fn send(request: &InstanceRequest) -> RpmResult<()> {
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+    let (_, socket_path) = paths()?;
+    let exchange = async {
+        let mut stream = tokio::net::UnixStream::connect(&socket_path).await?;
+        stream.write_all(format!("{}\n", request.to_line()).as_bytes()).await?;
+        let mut reply = String::new();
+        BufReader::new(stream).read_line(&mut reply).await?;
+        Ok::<_, std::io::Error>(reply)
+    };
+    let reply = tokio::time::timeout(SEND_TIMEOUT, exchange)
+        .await
+        .map_err(|_| RpmError::Instance("the running instance did not answer".to_string()))??;
+    match reply.trim_end() {
+        "ok" => Ok(()),
+        other => Err(RpmError::Instance(format!("request rejected: {}", other))),
+    }
+}
+
+#[cfg(not(unix))]
+pub async fn send(_request: &InstanceRequest) -> RpmResult<()> {
+    Err(RpmError::Instance("not supported on this platform".to_string()))
+}
+
+impl InstanceGuard {
+    /// Start accepting requests from other launches; they are queued on `handle`
+    #[cfg(unix)]
+    pub fn listen(&mut self, handle: InstanceHandle, shutdown_rx: watch::Receiver<()>) -> Option<JoinHandle<()>> {
+        let listener = self.listener.take()?;
+        Some(tokio::spawn(serve(listener, handle, shutdown_rx)))
+    }
+
+    #[cfg(not(unix))]
+    pub fn listen(&mut self, _handle: InstanceHandle, _shutdown_rx: watch::Receiver<()>) -> Option<JoinHandle<()>> {
+        None
+    }
+}
+
+impl Drop for InstanceGuard {
+    fn drop(&mut self) {
+        // Removed while the lock is still held, so it can't be a newer instance's socket.
+        // The lock file stays: unlinking it would let a launch lock a new file while
+        // another still waits on the old one
+        #[cfg(unix)]
+        let _ = std::fs::remove_file(&self.socket_path);
+    }
+}
+
+#[cfg(unix)]
+async fn serve(listener: tokio::net::UnixListener, handle: InstanceHandle, mut shutdown_rx: watch::Receiver<()>) {
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => {
+                    let handle = handle.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = handle_connection(stream, &handle).await {
+                            tracing::warn!("Instance request failed: {}", e);
+                        }
+                    });
+                }
+                Err(e) => tracing::warn!("Instance socket error: {}", e),
+            },
+            _ = shutdown_rx.changed() => break,
+        }
+    }
+}
+
+#[cfg(unix)]
+async fn handle_connection(stream: tokio::net::UnixStream, handle: &InstanceHandle) -> std::io::Result<()> {
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+
+    let (reader, mut writer) = stream.into_split();
+    let mut line = String::new();
+    BufReader::new(reader.take(MAX_REQUEST_LEN)).read_line(&mut line).await?;
+    let reply = match InstanceRequest::parse(&line) {
+        Some(request) => {
+            tracing::info!("Received a request from another launch");
+            handle.push(request);
+            "ok"
+        }
+        None => "unknown request",
+    };
+    writer.write_all(format!("{}\n", reply).as_bytes()).await
+}
@@ -0,0 +1,154 @@
+//! Unlock through the OS keychain (Secret Service, macOS Keychain, Windows
+//! Credential Manager) instead of the master password, when `keychain_unlock`
+//! is on in the config.
+//!
+//! The keychain holds the vault key wrapped (encrypted) with a random key kept
+//! in `keychain.key` next to the config, so neither the keychain entry nor the
+//! file unlocks the vault on its own. The entry also remembers the vault's salt:
+//! once the master password or the key changes the entry is stale, gets removed,
+//! and the master password is asked for once more.
+
+use crate::config::Config;
+use crate::crypto::{CryptoManager, SecureKey};
+use crate::errors::{RpmError, RpmResult};
+use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+use base64::Engine;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use zeroize::{Zeroize, Zeroizing};
+
+/// Keychain service of the entries; the account is the vault directory
+pub const SERVICE: &str = "rpm";
+
+/// A vault key as stored in the keychain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WrappedKey {
+    /// `encryption_key_salt` of the vault the key was derived for
+    pub salt: String,
+    pub nonce: String,
+    /// The vault key encrypted with the wrapping key
+    pub key: String,
+}
+
+impl WrappedKey {
+    pub fn wrap(crypto: &CryptoManager, key: &[u8], salt: &str, wrapping_key: &[u8]) -> RpmResult<Self> {
+        let (ciphertext, nonce) = crypto.encrypt_data(key, wrapping_key)?;
+        Ok(Self {
+            salt: salt.to_string(),
+            nonce: BASE64_STANDARD.encode(nonce),
+            key: BASE64_STANDARD.encode(ciphertext),
+        })
+    }
+
+    /// The vault key, or `None` if the vault has had another salt since it was wrapped
+    pub fn unwrap(&self, crypto: &CryptoManager, salt: &str, wrapping_key: &[u8]) -> RpmResult<Option<SecureKey>> {
+        if self.salt != salt {
+            return Ok(None);
+        }
+        let decode = |value: &str| {
+            BASE64_STANDARD
+                .decode(value)
+                .map_err(|e| RpmError::Keychain(format!("Invalid stored key: {}", e)))
+        };
+        let key = crypto.decrypt_data(&decode(&self.key)?, &decode(&self.nonce)?, wrapping_key)?;
+        Ok(Some(SecureKey::new(key)))
+    }
+}
+
+/// Store `key` of the vault in `passwords_dir`, replacing what was stored before
+pub fn store(crypto: &CryptoManager, passwords_dir: &Path, salt: &str, key: &[u8]) -> RpmResult<()> {
+    let wrapping_key = match read_wrapping_key()? {
+        Some(wrapping_key) => wrapping_key,
+        None => create_wrapping_key()?,
+    };
+    let wrapped = WrappedKey::wrap(crypto, key, salt, &wrapping_key)?;
+    let json = Zeroizing::new(serde_json::to_string(&wrapped)?);
+    entry(passwords_dir)?.set_password(&json).map_err(keychain_error)
+}
+
+/// The stored key of the vault in `passwords_dir`, `None` if there is none or it
+/// is stale. A stale or unreadable entry is removed
+pub fn load(crypto: &CryptoManager, passwords_dir: &Path, salt: &str) -> RpmResult<Option<SecureKey>> {
+    let entry = entry(passwords_dir)?;
+    let json = match entry.get_password() {
+        Ok(json) => Zeroizing::new(json),
+        Err(keyring::Error::NoEntry) => return Ok(None),
+        Err(e) => return Err(keychain_error(e)),
+    };
+    let Some(wrapping_key) = read_wrapping_key()? else {
+        // The entry can't be unwrapped without the file any more
+        forget(passwords_dir)?;
+        return Ok(None);
+    };
+    let key = serde_json::from_str::<WrappedKey>(&json)
+        .map_err(RpmError::from)
+        .and_then(|wrapped| wrapped.unwrap(crypto, salt, &wrapping_key));
+    match key {
+        Ok(Some(key)) => Ok(Some(key)),
+        Ok(None) | Err(_) => {
+            forget(passwords_dir)?;
+            Ok(None)
+        }
+    }
+}
+
+/// Remove the stored key of the vault in `passwords_dir`, if there is one
+pub fn forget(passwords_dir: &Path) -> RpmResult<()> {
+    match entry(passwords_dir)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(keychain_error(e)),
+    }
+}
+
+fn entry(passwords_dir: &Path) -> RpmResult<keyring::Entry> {
+    // The same vault under another spelling of its path is the same entry
+    let account = passwords_dir
+        .canonicalize()
+        .unwrap_or_else(|_| passwords_dir.to_path_buf());
+    keyring::Entry::new(SERVICE, &account.to_string_lossy()).map_err(keychain_error)
+}
+
+fn keychain_error(e: keyring::Error) -> RpmError {
+    RpmError::Keychain(e.to_string())
+}
+
+fn wrapping_key_path() -> RpmResult<PathBuf> {
+    Config::keychain_key_path().map_err(|e| RpmError::Config(e.to_string()))
+}
+
+fn read_wrapping_key() -> RpmResult<Option<Zeroizing<Vec<u8>>>> {
+    match std::fs::read(wrapping_key_path()?) {
+        Ok(key) if key.len() == 32 => Ok(Some(Zeroizing::new(key))),
+        Ok(mut key) => {
+            key.zeroize();
+            Err(RpmError::Keychain("keychain.key is not a 32-byte key".to_string()))
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(RpmError::Io(e)),
+    }
+}
+
+/// A new random wrapping key, readable by the owner only
+fn create_wrapping_key() -> RpmResult<Zeroizing<Vec<u8>>> {
+    let path = wrapping_key_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut key = Zeroizing::new(vec![0u8; 32]);
+    rand::thread_rng().fill_bytes(&mut key);
+
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(&path)?;
+    file.write_all(&key)?;
+    file.sync_all()?;
+    Ok(key)
+}
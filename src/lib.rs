@@ -0,0 +1,28 @@
+//! RPM - Rust Password Manager.
+//!
+//! The binary (`src/main.rs`) wires these modules together; they are exposed
+//! as a library so benches and integration tests can use them.
+
+pub mod backup;
+pub mod breach;
+pub mod cli;
+pub mod config;
+pub mod crypto;
+pub mod diagnostics;
+#[cfg(feature = "dev-tools")]
+pub mod dev;
+pub mod errors;
+pub mod events;
+pub mod export;
+pub mod i18n;
+pub mod import;
+pub mod instance;
+pub mod keychain;
+pub mod models;
+pub mod native_messaging;
+pub mod server;
+pub mod storage;
+pub mod sync;
+pub mod tui;
+pub mod tray;
+pub mod webhook;
@@ -1,62 +1,220 @@
 use anyhow::Result;
-use tracing::{info, error};
-use tracing_subscriber;
+use clap::Parser;
+use tracing::{info, error, warn};
+use std::sync::Arc;
 use tokio::sync::watch;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::EnvFilter;
 
-mod config;
-mod crypto;
-mod errors;
-mod i18n;
-mod models;
-mod server;
-mod storage;
-mod tui;
-mod tray;
-
-use config::Config;
+use rpm::config::{Config, DirectoryConfig};
+use rpm::{backup, cli, crypto, events, instance, native_messaging, server, sync, tray, tui, webhook};
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize logging
+    // Started by the browser for the extension: stdin/stdout belong to the browser,
+    // and the arguments it appends (extension origin, manifest path) are not ours
+    let native_messaging = std::env::args().nth(1).is_some_and(|arg| arg == "--native-messaging");
+    let mut args = if native_messaging { cli::Cli::default() } else { cli::Cli::parse() };
+
+    // Initialize logging: --log-level wins over RUST_LOG. Logs go to stderr so a
+    // trace can be redirected to a file; closed spans report their duration.
+    let filter = match args.log_level.take() {
+        Some(level) => EnvFilter::try_new(&level)
+            .map_err(|e| anyhow::anyhow!("Invalid --log-level '{}': {}", level, e))?,
+        None => EnvFilter::from_default_env(),
+    };
     tracing_subscriber::fmt()
-        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .with_env_filter(filter)
+        .with_span_events(FmtSpan::CLOSE)
+        .with_writer(std::io::stderr)
         .init();
 
+    // A core dump would hold the vault key and passwords in the clear
+    if let Err(e) = crypto::memory::disable_core_dumps() {
+        warn!("Could not disable core dumps: {}", e);
+    }
+
+    if native_messaging {
+        native_messaging::run()?;
+        return Ok(());
+    }
+
     info!("Starting RPM - Rust Password Manager");
 
     // Load configuration
     let config = Config::load()?;
+    // An unknown cipher stops here instead of new entries quietly using another one
+    config.cipher()?;
     info!("Configuration loaded");
 
     // Initialize cryptography module
     let crypto = crypto::CryptoManager::new()?;
     info!("Cryptography module initialized");
 
+    // Command line mode: run the command and exit without starting the TUI
+    if let Some(command) = args.command {
+        if args.copy.is_some() {
+            anyhow::bail!("--copy cannot be combined with a command");
+        }
+        if let Err(e) = cli::run(command, &args.global, &config, crypto) {
+            cli::Term::new(&config, false).error(&e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    // Single instance: hand the request to an already running rpm and exit
+    let request = match args.copy {
+        Some(name) => instance::InstanceRequest::Copy(name),
+        None => instance::InstanceRequest::Focus,
+    };
+    let Some(mut instance_guard) = instance::acquire().await? else {
+        instance::send(&request).await?;
+        match request {
+            instance::InstanceRequest::Copy(_) => println!("rpm is already running: the password will be copied there"),
+            instance::InstanceRequest::Focus => println!("rpm is already running: switch to its window"),
+        }
+        return Ok(());
+    };
+
     // Create shutdown channel
     let (shutdown_tx, shutdown_rx) = watch::channel(());
 
+    // Requests from later launches; our own --copy is served like one of them
+    let instance_handle = instance::InstanceHandle::default();
+    if let instance::InstanceRequest::Copy(_) = request {
+        instance_handle.push(request);
+    }
+    let instance_listener = instance_guard.listen(instance_handle.clone(), shutdown_rx.clone());
+
     // Start system tray
     let tray_manager = tray::TrayManager::new()?;
     let tray_handle = tray_manager.handle.clone();
     info!("System tray initialized");
 
-    // Start HTTP server for browser extensions
-    let server_handle = {
-        let crypto_clone = crypto.clone();
-        let shutdown_rx = shutdown_rx.clone();
-        tokio::spawn(async move {
-            if let Err(e) = server::start_server(config.server_port, crypto_clone, shutdown_rx).await {
+    // Vault changes, whichever part of the program made them: the TUI, the
+    // extension API, sync and the tray all publish and follow them here
+    let vault_events = events::VaultEvents::default();
+
+    // Entry metadata the TUI shares with the extension API
+    let entry_metadata = server::MetadataHandle::new(vault_events.clone());
+
+    // Paired browser extensions; the TUI shows the code of a new pairing request
+    let pairing = Config::paired_clients_path()
+        .and_then(|path| Ok(server::PairingHandle::load(path)?))
+        .unwrap_or_else(|e| {
+            error!("Failed to load paired clients: {}", e);
+            server::PairingHandle::default()
+        });
+
+    // Wrong master passwords through the API; the TUI shows how many there were
+    let auth_throttle = server::AuthThrottle::new(config.api_auth_max_failures, config.api_auth_lockout_seconds);
+
+    let passwords_dir = config.passwords_directory_path();
+    let policy = DirectoryConfig::load(&passwords_dir)
+        .map(|dir_config| dir_config.policy)
+        .unwrap_or_default();
+    let api_state = Arc::new(server::AppState {
+        crypto: crypto.clone(),
+        metadata: entry_metadata.clone(),
+        policy,
+        passwords_dir,
+        tokens: server::TokenSigner::new(),
+        pairing: pairing.clone(),
+        webhooks: webhook::Webhooks::from_config(&config),
+        throttle: auth_throttle.clone(),
+        allowed_origins: server::AllowedOrigins::new(&config.server_allowed_origins),
+    });
+
+    // HTTPS with the self-signed certificate kept next to the config
+    let mut tls = None;
+    if config.server_tls {
+        match server::TlsIdentity::load_or_generate() {
+            Ok(identity) => match server::bind(config.server_tls_port, config.server_fallback_ports).await {
+                Ok(listener) => tls = Some((listener, identity)),
+                Err(e) => error!("HTTPS server error: {}", e),
+            },
+            Err(e) => error!("TLS certificate error: {}", e),
+        }
+    }
+    let tls_port = tls
+        .as_ref()
+        .and_then(|(listener, _)| listener.local_addr().ok())
+        .map(|addr| addr.port());
+    let tls_fingerprint = tls.as_ref().and_then(|(_, identity)| identity.fingerprint().ok());
+    if let Some(port) = tls_port {
+        info!("HTTPS server started on port {}", port);
+    }
+
+    // Start HTTP server for browser extensions, falling back to the next ports if ours is taken.
+    // With HTTPS running it is optional; without, it is the fallback
+    let listener = if tls.is_some() && !config.server_http_fallback {
+        None
+    } else {
+        match server::bind(config.server_port, config.server_fallback_ports).await {
+            Ok(listener) => Some(listener),
+            Err(e) => {
                 error!("Server error: {}", e);
+                None
             }
-        })
+        }
     };
-    info!("HTTP server started on port {}", config.server_port);
+    let server_port = listener
+        .as_ref()
+        .and_then(|listener| listener.local_addr().ok())
+        .map(|addr| addr.port());
+    if let Some(port) = server_port {
+        if port != config.server_port {
+            warn!("Port {} is in use, HTTP server falls back to port {}", config.server_port, port);
+        }
+        info!("HTTP server started on port {}", port);
+    }
+
+    let runtime = (server_port.is_some() || tls_port.is_some()).then(|| server::RuntimeInfo {
+        port: server_port,
+        pid: std::process::id(),
+        tls_port,
+        tls_fingerprint,
+    });
+    if let Some(ref runtime) = runtime {
+        if let Err(e) = server::write_runtime_info(runtime) {
+            error!("Failed to write the server runtime file: {}", e);
+        }
+    }
+
+    let mut server_handles = Vec::new();
+    if let Some(listener) = listener {
+        let (state, shutdown_rx) = (api_state.clone(), shutdown_rx.clone());
+        server_handles.push(tokio::spawn(async move {
+            if let Err(e) = server::start_server(listener, state, shutdown_rx).await {
+                error!("Server error: {}", e);
+            }
+        }));
+    }
+    if let Some((listener, identity)) = tls {
+        let (state, shutdown_rx) = (api_state, shutdown_rx.clone());
+        server_handles.push(tokio::spawn(async move {
+            if let Err(e) = server::start_tls_server(listener, &identity, state, shutdown_rx).await {
+                error!("HTTPS server error: {}", e);
+            }
+        }));
+    }
+
+    // Start scheduled backups (disabled while backup_interval_hours is 0)
+    let backup_handle = tokio::spawn(backup::run_scheduler(shutdown_rx.clone()));
+
+    // Start WebDAV sync (idle while webdav_url is not set)
+    let webdav_handle = tokio::spawn(sync::webdav::run_scheduler(vault_events.clone(), shutdown_rx.clone()));
+
+    // Keep the tray menu and lock badge in step with the vault
+    let tray_events_handle = tokio::spawn(tray::follow_events(tray_handle.clone(), vault_events.subscribe(), shutdown_rx.clone()));
 
     // Start TUI with shutdown sender
     info!("Starting TUI...");
     let shutdown_tx_for_tui = shutdown_tx.clone();
+    let tui_runtime = runtime.clone();
     let tui_handle = tokio::spawn(async move {
-        if let Err(e) = tui::run_tui(crypto, tray_handle, config, shutdown_tx_for_tui).await {
+        if let Err(e) = tui::run_tui(crypto, tray_handle, instance_handle, entry_metadata, pairing, auth_throttle, tui_runtime, config, shutdown_tx_for_tui).await {
             error!("TUI error: {}", e);
         }
     });
@@ -68,8 +226,20 @@ async fn main() -> Result<()> {
     info!("Shutting down...");
     let _ = shutdown_tx.send(());
 
-    // Wait for server to finish gracefully
-    let _ = server_handle.await;
+    // Wait for the servers to finish gracefully
+    for server_handle in server_handles {
+        let _ = server_handle.await;
+    }
+    if runtime.is_some() {
+        server::remove_runtime_info();
+    }
+    let _ = backup_handle.await;
+    let _ = webdav_handle.await;
+    let _ = tray_events_handle.await;
+    if let Some(instance_listener) = instance_listener {
+        let _ = instance_listener.await;
+    }
+    drop(instance_guard);
 
     info!("RPM shutdown complete");
     Ok(())
@@ -1,8 +1,11 @@
-use chrono::{DateTime, Utc};
+use crate::crypto::Cipher;
+use crate::errors::{RpmError, RpmResult};
+use chrono::{DateTime, Datelike, NaiveDateTime, NaiveTime, Utc, Weekday};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize)]
+#[allow(dead_code)]
 pub struct PasswordEntry {
     pub id: Uuid,
     pub title: String,
@@ -17,6 +20,7 @@ pub struct PasswordEntry {
 }
 
 #[derive(Debug, Clone)]
+#[allow(dead_code)]
 pub struct EncryptedPassword {
     pub ciphertext: Vec<u8>,
     pub nonce: Vec<u8>,
@@ -24,6 +28,7 @@ pub struct EncryptedPassword {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[allow(dead_code)]
 pub struct PasswordEntryDto {
     pub id: Uuid,
     pub title: String,
@@ -44,9 +49,12 @@ pub struct CreatePasswordRequest {
     pub password: String,
     pub url: Option<String>,
     pub notes: Option<String>,
+    #[serde(default)]
     pub tags: Vec<String>,
 }
 
+/// Changes to an entry; fields left out stay as they are, and an empty
+/// username, URL or notes clears it
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UpdatePasswordRequest {
     pub title: Option<String>,
@@ -57,22 +65,170 @@ pub struct UpdatePasswordRequest {
     pub tags: Option<Vec<String>>,
 }
 
+/// Entry as returned by the password endpoints, password included
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PasswordRecord {
+    /// Filename of the entry, used in `/api/passwords/:id`
+    pub id: String,
+    pub title: String,
+    pub username: Option<String>,
+    pub password: String,
+    pub url: Option<String>,
+    pub notes: Option<String>,
+    pub tags: Vec<String>,
+    pub folder: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AuthRequest {
     pub master_password: String,
 }
 
+/// Days without a backup after which `/health` and the tray flag the vault
+pub const BACKUP_WARNING_DAYS: i64 = 30;
+
+/// Outcome of the last sync in the running TUI
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncStatus {
+    /// Neither git nor WebDAV sync is configured
+    #[default]
+    Off,
+    /// Configured, but not synced since the TUI started
+    Idle,
+    Ok,
+    Conflict,
+    Error,
+}
+
+/// Vault-wide numbers the TUI publishes for `/health` and the tray tooltip.
+/// Counts only: nothing here needs the vault to be unlocked to be shown
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VaultStats {
+    pub entries: usize,
+    pub last_backup_at: Option<DateTime<Utc>>,
+    /// Reuse clusters and breached passwords found by the last audit; `None` until one runs
+    pub audit_findings: Option<usize>,
+    pub sync: SyncStatus,
+}
+
+impl VaultStats {
+    /// Whole days since the newest backup archive
+    pub fn backup_age_days(&self, now: DateTime<Utc>) -> Option<i64> {
+        self.last_backup_at.map(|at| (now - at).num_days())
+    }
+
+    /// Problems worth surfacing: `no_backup`, `backup_overdue`, `audit_findings`,
+    /// `sync_conflict` and `sync_error`
+    pub fn warnings(&self, now: DateTime<Utc>) -> Vec<&'static str> {
+        let mut warnings = Vec::new();
+        match self.backup_age_days(now) {
+            None => warnings.push("no_backup"),
+            Some(days) if days >= BACKUP_WARNING_DAYS => warnings.push("backup_overdue"),
+            Some(_) => {}
+        }
+        if self.audit_findings.is_some_and(|findings| findings > 0) {
+            warnings.push("audit_findings");
+        }
+        match self.sync {
+            SyncStatus::Conflict => warnings.push("sync_conflict"),
+            SyncStatus::Error => warnings.push("sync_error"),
+            _ => {}
+        }
+        warnings
+    }
+}
+
+/// Entry as listed by `GET /api/passwords/meta`: everything an extension needs
+/// to render its list, and never the password, notes or other secrets
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EntryMeta {
+    /// Entry file name, stable across renames
+    pub id: String,
+    pub title: String,
+    pub username: Option<String>,
+    pub url: Option<String>,
+    /// Favicon of the entry's site, derived from its URL
+    pub icon_url: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub folder: Option<String>,
+    /// Last change of the entry, password included, so caches can tell it is stale
+    #[serde(default)]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// What happened to an entry, as pushed over `/api/events`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryChange {
+    Added,
+    Changed,
+    Deleted,
+}
+
+/// Message sent to extensions listening on `/api/events`. Only the entry id:
+/// the extension fetches what it needs through the authenticated endpoints
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EntryEvent {
+    #[serde(rename = "type")]
+    pub change: EntryChange,
+    pub id: String,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AuthResponse {
     pub token: String,
     pub expires_at: DateTime<Utc>,
 }
 
+/// `POST /api/pair`: an extension asks to be paired
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PairRequest {
+    /// Shown to the user next to the code, e.g. "Firefox on laptop"
+    pub client_name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PairResponse {
+    pub request_id: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// `POST /api/pair/:id`: the code the TUI showed, as typed into the extension
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PairCodeRequest {
+    pub code: String,
+}
+
+/// The long-lived client credential, sent as `X-RPM-Client` on later requests
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PairedResponse {
+    pub client_id: String,
+    pub credential: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DefFileEntry {
     pub encrypted_filename: String, // UUID filename
     pub encrypted_name: String,      // Base64 encoded encrypted name
     pub nonce: String,               // Base64 encoded nonce
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password_fingerprint: Option<String>, // Hex encoded HMAC of the password (reuse detection)
+    /// Tags for filtering the list; kept here (the def file is encrypted as a whole)
+    /// so filtering doesn't need to open every password file
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Folder path, segments separated by `/` (e.g. `work/servers`); `None` is the top level.
+    /// Folders exist only through the entries in them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub folder: Option<String>,
+    /// Archived entries stay in the vault but are left out of the default list
+    /// and of the extension API
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub archived: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -80,9 +236,359 @@ pub struct DefFile {
     pub entries: Vec<DefFileEntry>,
 }
 
+/// Deleted entry kept in the trash: its def file record and when it was deleted.
+/// The password file stays in place until the entry is purged.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashEntry {
+    #[serde(flatten)]
+    pub entry: DefFileEntry,
+    pub deleted_at: DateTime<Utc>,
+}
+
+/// Trash index, encrypted like the def file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TrashFile {
+    pub entries: Vec<TrashEntry>,
+}
+
+/// Kind of action recorded in the vault's audit log
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    Export,
+    EntryAdded,
+    EntryDeleted,
+    /// The vault was re-encrypted under a key with a new salt
+    KeyRotated,
+}
+
+/// One audit log record: what was done, when and by whom
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEvent {
+    pub action: AuditAction,
+    pub at: DateTime<Utc>,
+    pub user: String,
+    /// Free-form context, e.g. the export file name
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub details: String,
+}
+
+/// Append-only audit log, encrypted like the def file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuditLog {
+    pub events: Vec<AuditEvent>,
+}
+
+/// Where the main list was left, restored after the next unlock. Encrypted like the
+/// def file, but kept per device: it is neither synced nor backed up
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UiState {
+    /// Open folder ("" = top level)
+    #[serde(default)]
+    pub current_folder: String,
+    /// Tags of the `#tag` filter in the search field (the rest of the query is not kept)
+    #[serde(default)]
+    pub tag_filter: Vec<String>,
+    /// File of the selected entry
+    #[serde(default)]
+    pub selected_entry: Option<String>,
+    /// First visible row of the list
+    #[serde(default)]
+    pub list_offset: usize,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PasswordFile {
     pub encrypted_password: String, // Base64 encoded encrypted password
     pub nonce: String,              // Base64 encoded nonce
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encrypted_details: Option<String>, // Base64 encoded encrypted EntryDetails JSON
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details_nonce: Option<String>,     // Base64 encoded nonce for the details
+    /// Cipher of both the password and the details; files written before the
+    /// choice existed are AES-256-GCM
+    #[serde(default)]
+    pub algorithm: Cipher,
+}
+
+/// Additional entry fields stored encrypted next to the password
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EntryDetails {
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub notes: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub security_questions: Vec<SecurityQuestion>,
+    /// Extra labeled fields (recovery email, PIN, ...)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub custom_fields: Vec<CustomField>,
+    /// Entry is flagged for the rotation workflow
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub needs_rotation: bool,
+    /// Entry is pinned to the tray quick menu
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub tray_shortcut: bool,
+    /// Generator settings last used for this entry
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password_policy: Option<PasswordPolicy>,
+    /// Previous passwords, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub password_history: Vec<PasswordHistoryItem>,
+    /// Server the entry logs into over SSH
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ssh: Option<SshHost>,
+    /// Auto-type sequence overriding the configured default, e.g. `{USERNAME}{ENTER}{DELAY 500}{PASSWORD}{ENTER}`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub autotype_sequence: Option<String>,
+    /// Hours the API may serve this entry; always available in the TUI
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access_window: Option<AccessWindow>,
+    #[serde(default, flatten)]
+    pub timestamps: EntryTimestamps,
+}
+
+/// When an entry was created, last changed and last copied
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct EntryTimestamps {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+/// Password generator settings remembered per entry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PasswordPolicy {
+    pub length: usize,
+    #[serde(default)]
+    pub exclude_chars: String,
+    /// Extra characters added to the candidate set, e.g. the only symbols a legacy system accepts
+    #[serde(default)]
+    pub custom_chars: String,
+    pub use_uppercase: bool,
+    pub use_lowercase: bool,
+    pub use_digits: bool,
+    pub use_special: bool,
+    /// Cyrillic letters (а-я, А-Я, ё, Ё) for sites that accept non-ASCII passwords
+    #[serde(default)]
+    pub use_cyrillic: bool,
+    /// Leave out characters that are easy to confuse when read aloud or typed from paper (O/0, l/1/I, S/5)
+    #[serde(default)]
+    pub exclude_ambiguous: bool,
+    /// At least this many digits, special characters and uppercase letters (ignored for passphrases)
+    #[serde(default)]
+    pub min_digits: usize,
+    #[serde(default)]
+    pub min_special: usize,
+    #[serde(default)]
+    pub min_uppercase: usize,
+    /// Generate a passphrase of `length` words from this language's wordlist instead of characters
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub passphrase_language: Option<String>,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self {
+            length: 16,
+            exclude_chars: String::new(),
+            custom_chars: String::new(),
+            use_uppercase: true,
+            use_lowercase: true,
+            use_digits: true,
+            use_special: false,
+            use_cyrillic: false,
+            exclude_ambiguous: false,
+            min_digits: 0,
+            min_special: 0,
+            min_uppercase: 0,
+            passphrase_language: None,
+        }
+    }
+}
+
+/// A password that was replaced, kept for recovery
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PasswordHistoryItem {
+    pub password: String,
+    pub changed_at: DateTime<Utc>,
+}
+
+/// Security question with its answer (shown masked in the UI)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SecurityQuestion {
+    pub question: String,
+    pub answer: String,
+}
+
+/// SSH connection of a server entry
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SshHost {
+    pub host: String,
+    /// `None` for the default port 22
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    /// Login on the server when it differs from the entry's username
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+}
+
+impl SshHost {
+    /// Arguments for `ssh`: `[-p port] [user@]host`. `entry_username` is used when the
+    /// host has no login of its own. `None` if the host is empty or a value would be
+    /// read by ssh as an option or split into several arguments.
+    pub fn ssh_args(&self, entry_username: Option<&str>) -> Option<Vec<String>> {
+        let host = self.host.trim();
+        let username = self
+            .username
+            .as_deref()
+            .or(entry_username)
+            .map(str::trim)
+            .filter(|username| !username.is_empty());
+        let unsafe_value = |value: &str| value.starts_with('-') || value.chars().any(char::is_whitespace);
+        if host.is_empty() || unsafe_value(host) || username.is_some_and(unsafe_value) {
+            return None;
+        }
+
+        let mut args = Vec::new();
+        if let Some(port) = self.port.filter(|&port| port != 22) {
+            args.push("-p".to_string());
+            args.push(port.to_string());
+        }
+        args.push(match username {
+            Some(username) => format!("{}@{}", username, host),
+            None => host.to_string(),
+        });
+        Some(args)
+    }
+
+    /// Command line to paste into a shell, e.g. `ssh -p 2222 alice@example.com`
+    pub fn ssh_command(&self, entry_username: Option<&str>) -> Option<String> {
+        let args = self.ssh_args(entry_username)?;
+        let quoted: Vec<String> = args
+            .iter()
+            .map(|arg| {
+                if arg.chars().all(|c| c.is_ascii_alphanumeric() || "@._-:[]%".contains(c)) {
+                    arg.clone()
+                } else {
+                    format!("'{}'", arg.replace('\'', "'\\''"))
+                }
+            })
+            .collect();
+        Some(format!("ssh {}", quoted.join(" ")))
+    }
+}
+
+/// Hours the API may serve an entry, in local time: `mon-fri 08:00-20:00`.
+/// A window ending before it starts runs past midnight into the next day.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccessWindow {
+    /// Days the window opens on, Monday first; empty means every day
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub days: Vec<Weekday>,
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl AccessWindow {
+    /// Parse `[days] HH:MM-HH:MM`, where days are names or ranges separated by commas:
+    /// `08:00-20:00`, `mon-fri 08:00-20:00`, `sat,sun 10:00-14:00`, `fri 22:00-02:00`
+    pub fn parse(text: &str) -> RpmResult<Self> {
+        let invalid = || RpmError::InvalidInput(format!("Invalid access window '{}', expected e.g. mon-fri 08:00-20:00", text.trim()));
+        let mut parts = text.split_whitespace();
+        let (days, hours) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(hours), None, None) => ("", hours),
+            (Some(days), Some(hours), None) => (days, hours),
+            _ => return Err(invalid()),
+        };
+        let (start, end) = hours.split_once('-').ok_or_else(invalid)?;
+        let time = |value: &str| NaiveTime::parse_from_str(value, "%H:%M").map_err(|_| invalid());
+        let (start, end) = (time(start)?, time(end)?);
+        if start == end {
+            return Err(invalid());
+        }
+
+        let day = |name: &str| name.parse::<Weekday>().map_err(|_| invalid());
+        let mut selected = [false; 7];
+        for item in days.split(',').filter(|item| !item.is_empty()) {
+            let (first, last) = match item.split_once('-') {
+                Some((first, last)) => (day(first)?, day(last)?),
+                None => (day(item)?, day(item)?),
+            };
+            // Ranges may wrap around the week: fri-mon
+            let mut current = first;
+            loop {
+                selected[current.num_days_from_monday() as usize] = true;
+                if current == last {
+                    break;
+                }
+                current = current.succ();
+            }
+        }
+        let days = if selected.iter().all(|&on| on) {
+            Vec::new()
+        } else {
+            (0..7u8).filter(|&i| selected[i as usize]).map(|i| Weekday::try_from(i).expect("0..7 are weekdays")).collect()
+        };
+        Ok(Self { days, start, end })
+    }
+
+    fn opens_on(&self, day: Weekday) -> bool {
+        self.days.is_empty() || self.days.contains(&day)
+    }
+
+    /// Whether the window is open at local time `at`
+    pub fn allows(&self, at: NaiveDateTime) -> bool {
+        let (day, time) = (at.weekday(), at.time());
+        if self.start < self.end {
+            self.opens_on(day) && self.start <= time && time < self.end
+        } else {
+            (self.opens_on(day) && time >= self.start) || (self.opens_on(day.pred()) && time < self.end)
+        }
+    }
+}
+
+/// The form `parse` reads, with runs of three or more days written as ranges
+impl std::fmt::Display for AccessWindow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = |day: Weekday| day.to_string().to_lowercase();
+        let mut runs: Vec<(Weekday, Weekday)> = Vec::new();
+        for &day in &self.days {
+            match runs.last_mut() {
+                Some((_, last)) if last.succ() == day => *last = day,
+                _ => runs.push((day, day)),
+            }
+        }
+        let mut days = Vec::new();
+        for (first, last) in runs {
+            let length = last.num_days_from_monday() - first.num_days_from_monday() + 1;
+            if length >= 3 {
+                days.push(format!("{}-{}", name(first), name(last)));
+            } else {
+                let mut day = first;
+                days.push(name(day));
+                while day != last {
+                    day = day.succ();
+                    days.push(name(day));
+                }
+            }
+        }
+        if !days.is_empty() {
+            write!(f, "{} ", days.join(","))?;
+        }
+        write!(f, "{}-{}", self.start.format("%H:%M"), self.end.format("%H:%M"))
+    }
+}
+
+/// User-defined field of an entry; the value is shown masked in the UI and copied on its own
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CustomField {
+    pub label: String,
+    pub value: String,
 }
 
@@ -0,0 +1,251 @@
+//! Native messaging host for the browser extension (`rpm --native-messaging`).
+//!
+//! Chrome and Firefox start the host themselves and talk to it over stdin/stdout:
+//! every message is UTF-8 JSON preceded by its length as a 32-bit integer in
+//! native byte order. The host does not open the vault: it forwards each request
+//! to the HTTP API of the running `rpm`, found through the runtime file, so the
+//! extension gets the same answers (and the same locking) as over HTTP without
+//! needing access to a local port.
+//!
+//! `rpm native-messaging install <extension id>` writes the host manifest the
+//! browser looks for, plus a small launcher script, because browsers start the
+//! host without extra arguments of our choosing.
+
+use crate::config::Config;
+use crate::errors::{RpmError, RpmResult};
+use crate::server::RuntimeInfo;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Host name the extension passes to `runtime.connectNative`
+pub const HOST_NAME: &str = "rpm_password_manager";
+
+/// Browsers reject host messages over 1 MiB; requests are held to the same limit
+const MAX_MESSAGE_LEN: usize = 1024 * 1024;
+
+/// How long a forwarded request may take
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Request from the extension: an API call to forward
+#[derive(Debug, Deserialize)]
+struct HostRequest {
+    /// Echoed back so the extension can match answers to requests
+    #[serde(default)]
+    id: Value,
+    #[serde(default = "default_method")]
+    method: String,
+    path: String,
+    /// API token, sent as `Authorization: Bearer`
+    #[serde(default)]
+    token: Option<String>,
+    /// Credential from pairing, sent as `X-RPM-Client`
+    #[serde(default)]
+    client: Option<String>,
+    #[serde(default)]
+    body: Option<Value>,
+}
+
+fn default_method() -> String {
+    "GET".to_string()
+}
+
+/// Answer to the extension: the API status and JSON body, or why the call failed
+#[derive(Debug, Serialize)]
+struct HostResponse {
+    id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    body: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl HostResponse {
+    fn error(id: Value, message: impl Into<String>) -> Self {
+        Self { id, status: None, body: None, error: Some(message.into()) }
+    }
+}
+
+/// Read one message; `None` when the browser has closed the pipe
+pub fn read_message(input: &mut impl Read) -> RpmResult<Option<Vec<u8>>> {
+    let mut len = [0u8; 4];
+    match input.read_exact(&mut len) {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e.into()),
+    }
+    let len = u32::from_ne_bytes(len) as usize;
+    if len > MAX_MESSAGE_LEN {
+        return Err(RpmError::InvalidInput(format!("native message of {} bytes is too large", len)));
+    }
+    let mut message = vec![0u8; len];
+    input.read_exact(&mut message)?;
+    Ok(Some(message))
+}
+
+/// Write one message
+pub fn write_message(output: &mut impl Write, message: &[u8]) -> RpmResult<()> {
+    output.write_all(&(message.len() as u32).to_ne_bytes())?;
+    output.write_all(message)?;
+    output.flush()?;
+    Ok(())
+}
+
+/// Serve the extension until the browser closes stdin
+pub fn run() -> RpmResult<()> {
+    let agent = ureq::AgentBuilder::new().timeout(REQUEST_TIMEOUT).build();
+    let mut stdin = io::stdin().lock();
+    let mut stdout = io::stdout().lock();
+    while let Some(message) = read_message(&mut stdin)? {
+        let response = match serde_json::from_slice::<HostRequest>(&message) {
+            Ok(request) => forward(&agent, request),
+            Err(e) => HostResponse::error(Value::Null, format!("invalid request: {}", e)),
+        };
+        let mut encoded = serde_json::to_vec(&response)?;
+        // The browser would drop the host instead, so the extension still learns what happened
+        if encoded.len() > MAX_MESSAGE_LEN {
+            encoded = serde_json::to_vec(&HostResponse::error(response.id, "response is too large"))?;
+        }
+        write_message(&mut stdout, &encoded)?;
+    }
+    Ok(())
+}
+
+/// Plain HTTP port of the running API from its runtime file; `None` also while
+/// the API serves HTTPS only (`server_http_fallback = false`)
+fn api_port() -> Option<u16> {
+    let path = Config::runtime_file_path().ok()?;
+    let content = std::fs::read(path).ok()?;
+    serde_json::from_slice::<RuntimeInfo>(&content).ok().and_then(|info| info.port)
+}
+
+fn forward(agent: &ureq::Agent, request: HostRequest) -> HostResponse {
+    let HostRequest { id, method, path, token, client, body } = request;
+    // Only the extension API, never an arbitrary URL
+    if !(path == "/health" || path.starts_with("/api/")) || path.contains("://") {
+        return HostResponse::error(id, format!("path not allowed: {}", path));
+    }
+    let Some(port) = api_port() else {
+        return HostResponse::error(id, "rpm is not running");
+    };
+
+    let mut call = agent.request(&method, &format!("http://127.0.0.1:{}{}", port, path));
+    if let Some(token) = token {
+        call = call.set("Authorization", &format!("Bearer {}", token));
+    }
+    if let Some(client) = client {
+        call = call.set("X-RPM-Client", &client);
+    }
+    let result = match body {
+        Some(body) => call.set("Content-Type", "application/json").send_string(&body.to_string()),
+        None => call.call(),
+    };
+    let response = match result {
+        Ok(response) => response,
+        // Error statuses (locked vault, missing token) are answers for the extension too
+        Err(ureq::Error::Status(_, response)) => response,
+        Err(e) => return HostResponse::error(id, e.to_string()),
+    };
+    let status = response.status();
+    let body = response
+        .into_string()
+        .ok()
+        .filter(|text| !text.is_empty())
+        .map(|text| serde_json::from_str(&text).unwrap_or(Value::String(text)));
+    HostResponse { id, status: Some(status), body, error: None }
+}
+
+/// Directories where the browsers look for host manifests of the current user,
+/// `(browser, directory)`, for the browsers whose profile directory exists
+fn manifest_dirs(firefox: bool) -> RpmResult<Vec<(&'static str, PathBuf)>> {
+    if cfg!(windows) {
+        return Err(RpmError::InvalidInput(
+            "on Windows the host is registered in the registry; this is not automated yet".to_string(),
+        ));
+    }
+    let home = dirs::home_dir().ok_or_else(|| RpmError::Config("home directory not found".to_string()))?;
+    let candidates: Vec<(&'static str, PathBuf, &'static str)> = if cfg!(target_os = "macos") {
+        let support = home.join("Library").join("Application Support");
+        if firefox {
+            vec![("Firefox", support.join("Mozilla"), "NativeMessagingHosts")]
+        } else {
+            vec![
+                ("Chrome", support.join("Google").join("Chrome"), "NativeMessagingHosts"),
+                ("Chromium", support.join("Chromium"), "NativeMessagingHosts"),
+            ]
+        }
+    } else if firefox {
+        vec![("Firefox", home.join(".mozilla"), "native-messaging-hosts")]
+    } else {
+        let config = home.join(".config");
+        vec![
+            ("Chrome", config.join("google-chrome"), "NativeMessagingHosts"),
+            ("Chromium", config.join("chromium"), "NativeMessagingHosts"),
+        ]
+    };
+    Ok(candidates
+        .into_iter()
+        .filter(|(_, profile, _)| profile.is_dir())
+        .map(|(browser, profile, dir)| (browser, profile.join(dir)))
+        .collect())
+}
+
+/// Install the host for an extension. Firefox IDs look like `name@example.com`
+/// or `{uuid}`, Chrome IDs are 32 letters. Returns the manifests written.
+pub fn install(extension_id: &str) -> RpmResult<Vec<PathBuf>> {
+    let firefox = extension_id.contains('@') || extension_id.starts_with('{');
+    let chrome = extension_id.len() == 32 && extension_id.chars().all(|c| ('a'..='p').contains(&c));
+    if !firefox && !chrome {
+        return Err(RpmError::InvalidInput(format!("not a Chrome or Firefox extension ID: {}", extension_id)));
+    }
+    let dirs = manifest_dirs(firefox)?;
+    if dirs.is_empty() {
+        return Err(RpmError::InvalidInput(format!(
+            "no {} profile found for the current user",
+            if firefox { "Firefox" } else { "Chrome or Chromium" }
+        )));
+    }
+
+    let launcher = write_launcher()?;
+    let mut manifest = serde_json::json!({
+        "name": HOST_NAME,
+        "description": "RPM - Rust Password Manager",
+        "path": launcher,
+        "type": "stdio",
+    });
+    if firefox {
+        manifest["allowed_extensions"] = serde_json::json!([extension_id]);
+    } else {
+        manifest["allowed_origins"] = serde_json::json!([format!("chrome-extension://{}/", extension_id)]);
+    }
+
+    let mut written = Vec::new();
+    for (_, dir) in dirs {
+        std::fs::create_dir_all(&dir)?;
+        let path = dir.join(format!("{}.json", HOST_NAME));
+        std::fs::write(&path, serde_json::to_vec_pretty(&manifest)?)?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+/// Script next to the config that starts this binary in native messaging mode
+fn write_launcher() -> RpmResult<PathBuf> {
+    let exe = std::env::current_exe()?;
+    let path = Config::native_host_launcher_path().map_err(|e| RpmError::Config(e.to_string()))?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let quoted = exe.to_string_lossy().replace('\'', "'\\''");
+    std::fs::write(&path, format!("#!/bin/sh\nexec '{}' --native-messaging \"$@\"\n", quoted))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))?;
+    }
+    Ok(path)
+}
@@ -0,0 +1,111 @@
+use super::{AppState, CLIENT_HEADER};
+use crate::events::VaultEvent;
+use crate::models::{EntryChange, EntryEvent};
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        State,
+    },
+    http::{header, HeaderMap, StatusCode},
+    response::Response,
+};
+use chrono::Utc;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+/// Subprotocol the server accepts the connection with. Browsers can't set
+/// headers on a WebSocket, so the extension offers its token and client
+/// credential as two more subprotocols: `token.<token>` and `client.<credential>`
+pub const EVENTS_PROTOCOL: &str = "rpm-events";
+
+const TOKEN_PROTOCOL_PREFIX: &str = "token.";
+const CLIENT_PROTOCOL_PREFIX: &str = "client.";
+
+/// What `/api/events` sends for a vault event; `None` for events clients don't get
+fn entry_event(event: &VaultEvent) -> Option<EntryEvent> {
+    let (change, id) = match event {
+        VaultEvent::EntryAdded { id } => (EntryChange::Added, id),
+        VaultEvent::EntryChanged { id } => (EntryChange::Changed, id),
+        VaultEvent::EntryDeleted { id } => (EntryChange::Deleted, id),
+        _ => return None,
+    };
+    Some(EntryEvent { change, id: id.clone() })
+}
+
+/// Subprotocols offered in `Sec-WebSocket-Protocol`, possibly over several headers
+fn offered_protocols(headers: &HeaderMap) -> Vec<&str> {
+    headers
+        .get_all(header::SEC_WEBSOCKET_PROTOCOL)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .collect()
+}
+
+/// A header if the client could send one, otherwise the subprotocol with `prefix`
+fn credential<'a>(headers: &'a HeaderMap, from_header: Option<&'a str>, prefix: &str) -> Option<&'a str> {
+    from_header.or_else(|| offered_protocols(headers).into_iter().find_map(|protocol| protocol.strip_prefix(prefix)))
+}
+
+/// `GET /api/events`: upgrade to a WebSocket that pushes an `EntryEvent` as JSON
+/// text whenever an entry is added, changed or deleted. Same guards as the
+/// password endpoints; the socket is closed when the token expires, when the
+/// vault is locked, and when the client may have missed changes (it fell too
+/// far behind, or a sync brought in remote ones), after which it should refetch its list.
+pub(super) async fn entry_events(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    upgrade: WebSocketUpgrade,
+) -> Result<Response, StatusCode> {
+    let bearer = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    let token = credential(&headers, bearer, TOKEN_PROTOCOL_PREFIX).ok_or(StatusCode::UNAUTHORIZED)?;
+    let claims = state.tokens.verify(token).map_err(|_| StatusCode::UNAUTHORIZED)?;
+
+    let client = headers.get(&CLIENT_HEADER).and_then(|value| value.to_str().ok());
+    let paired = credential(&headers, client, CLIENT_PROTOCOL_PREFIX).is_some_and(|credential| state.pairing.verify(credential));
+    if !paired {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let events = state.metadata.subscribe();
+    Ok(upgrade
+        .protocols([EVENTS_PROTOCOL])
+        .on_upgrade(move |socket| forward_events(socket, events, claims.exp)))
+}
+
+async fn forward_events(mut socket: WebSocket, mut events: broadcast::Receiver<VaultEvent>, expires_at: i64) {
+    let remaining = u64::try_from(expires_at - Utc::now().timestamp()).unwrap_or(0);
+    let expiry = tokio::time::sleep(std::time::Duration::from_secs(remaining));
+    tokio::pin!(expiry);
+
+    loop {
+        tokio::select! {
+            event = events.recv() => {
+                // Lagged: events were dropped, so the client's cache can't be trusted anymore
+                let Ok(event) = event else {
+                    break;
+                };
+                if matches!(event, VaultEvent::Locked | VaultEvent::SyncCompleted { changed: true }) {
+                    break;
+                }
+                let Some(Ok(text)) = entry_event(&event).map(|event| serde_json::to_string(&event)) else {
+                    continue;
+                };
+                if socket.send(Message::Text(text)).await.is_err() {
+                    return;
+                }
+            }
+            message = socket.recv() => match message {
+                // Pings are answered by axum; nothing else is expected from the client
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                Some(Ok(_)) => {}
+            },
+            _ = &mut expiry => break,
+        }
+    }
+    let _ = socket.send(Message::Close(None)).await;
+}
@@ -0,0 +1,101 @@
+use crate::crypto::{CryptoManager, SecureKey};
+use crate::errors::{RpmError, RpmResult};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+/// Header of every token; tokens with another header are rejected
+const HEADER: &str = r#"{"alg":"HS256","typ":"JWT"}"#;
+
+/// Subject of the tokens handed to browser extensions
+const SUBJECT: &str = "rpm-extension";
+
+/// Claims of an API token
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Claims {
+    pub sub: String,
+    /// Issued at, seconds since the epoch
+    pub iat: i64,
+    /// Expires at, seconds since the epoch
+    pub exp: i64,
+    /// Random token id, so two tokens issued in the same second differ
+    pub jti: String,
+}
+
+/// Issues and checks HS256 JWTs for the API. The signing key is random and lives
+/// only in memory, so a restart of rpm invalidates every token it handed out.
+pub struct TokenSigner {
+    secret: SecureKey,
+}
+
+impl Default for TokenSigner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TokenSigner {
+    pub fn new() -> Self {
+        let mut secret = vec![0u8; 32];
+        rand::thread_rng().fill_bytes(&mut secret);
+        Self { secret: SecureKey::new(secret) }
+    }
+
+    fn mac(&self) -> Hmac<Sha256> {
+        <Hmac<Sha256> as Mac>::new_from_slice(self.secret.as_slice()).expect("HMAC accepts any key length")
+    }
+
+    /// A token valid for `lifetime`, with its expiry time
+    pub fn issue(&self, crypto: &CryptoManager, lifetime: Duration) -> RpmResult<(String, DateTime<Utc>)> {
+        let now = Utc::now();
+        let expires_at = now + lifetime;
+        let claims = Claims {
+            sub: SUBJECT.to_string(),
+            iat: now.timestamp(),
+            exp: expires_at.timestamp(),
+            jti: crypto.generate_token()?,
+        };
+        let signing_input = format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(HEADER),
+            URL_SAFE_NO_PAD.encode(serde_json::to_vec(&claims)?)
+        );
+        let mut mac = self.mac();
+        mac.update(signing_input.as_bytes());
+        let signature = URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes());
+        // Second precision, as in the claims
+        let expires_at = DateTime::from_timestamp(claims.exp, 0).unwrap_or(expires_at);
+        Ok((format!("{}.{}", signing_input, signature), expires_at))
+    }
+
+    /// Claims of a token this signer issued that has not expired yet
+    pub fn verify(&self, token: &str) -> RpmResult<Claims> {
+        let invalid = || RpmError::AuthenticationFailed;
+        let mut parts = token.split('.');
+        let (Some(header), Some(payload), Some(signature), None) = (parts.next(), parts.next(), parts.next(), parts.next()) else {
+            return Err(invalid());
+        };
+
+        let signature = URL_SAFE_NO_PAD.decode(signature).map_err(|_| invalid())?;
+        let mut mac = self.mac();
+        mac.update(header.as_bytes());
+        mac.update(b".");
+        mac.update(payload.as_bytes());
+        // Constant-time comparison
+        mac.verify_slice(&signature).map_err(|_| invalid())?;
+
+        if URL_SAFE_NO_PAD.decode(header).map_err(|_| invalid())? != HEADER.as_bytes() {
+            return Err(invalid());
+        }
+        let payload = URL_SAFE_NO_PAD.decode(payload).map_err(|_| invalid())?;
+        let claims: Claims = serde_json::from_slice(&payload).map_err(|_| invalid())?;
+        if claims.sub != SUBJECT || claims.exp <= Utc::now().timestamp() {
+            return Err(invalid());
+        }
+        Ok(claims)
+    }
+}
@@ -1,44 +1,221 @@
-use crate::crypto::CryptoManager;
-use crate::errors::RpmResult;
-use crate::models::{AuthRequest, AuthResponse, CreatePasswordRequest};
+use crate::config::{Config, DirectoryConfig, VaultPolicy};
+use crate::crypto::{CryptoManager, SecureKey};
+use crate::errors::{RpmError, RpmResult};
+use crate::events::{VaultEvent, VaultEvents};
+use crate::models::{
+    AuthRequest, AuthResponse, CreatePasswordRequest, DefFileEntry, EntryDetails, EntryMeta, PairCodeRequest,
+    PairRequest, PairResponse, PairedResponse, PasswordRecord, UpdatePasswordRequest, VaultStats,
+};
+use crate::storage::atomic::write_atomic;
+use crate::storage::PasswordStorage;
+use crate::webhook::{WebhookEvent, Webhooks};
+use axum_server::tls_rustls::RustlsConfig;
+use axum_server::Handle;
 use axum::{
-    extract::State,
-    http::StatusCode,
-    response::Json,
+    extract::{Path, Request, State},
+    http::{header, HeaderMap, HeaderName, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Json, Response},
     routing::{get, post},
     Router,
 };
-use chrono::{Duration, Utc};
-use std::sync::Arc;
-use tokio::sync::watch;
-use tower_http::cors::{Any, CorsLayer};
+use chrono::{Duration, Local, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpListener;
+use tokio::sync::{broadcast, watch};
+use tower_http::trace::TraceLayer;
+use tracing::{error, instrument};
+use zeroize::Zeroize;
+
+mod events;
+mod jwt;
+mod origins;
+mod pairing;
+mod throttle;
+mod tls;
+pub use events::EVENTS_PROTOCOL;
+pub use jwt::{Claims, TokenSigner};
+pub use origins::{extension_origin, AllowedOrigins};
+pub use pairing::{PairedClient, PairingHandle, PairingOutcome, PairingRequest};
+pub use throttle::{AuthAttempt, AuthThrottle};
+pub use tls::TlsIdentity;
+
+/// Header carrying the credential a paired extension got from `/api/pair/:id`
+pub const CLIENT_HEADER: HeaderName = HeaderName::from_static("x-rpm-client");
+
+/// Entry metadata the TUI publishes while the vault is unlocked and its names
+/// are decrypted, so the API can list entries without holding the key itself.
+/// The password endpoints need the vault itself, which the TUI lends alongside.
+/// `/api/events` forwards the vault events this handle was created with.
+#[derive(Clone, Default)]
+pub struct MetadataHandle {
+    /// `None` while the vault is locked
+    entries: Arc<Mutex<Option<Vec<EntryMeta>>>>,
+    /// Storage handle and a copy of the key, `None` while the vault is locked
+    vault: Arc<Mutex<Option<(PasswordStorage, SecureKey)>>>,
+    /// Counts for `/health`; kept while the vault is locked, `None` until the TUI publishes them
+    stats: Arc<Mutex<Option<VaultStats>>>,
+    events: VaultEvents,
+}
+
+impl MetadataHandle {
+    /// Handle whose lent vault announces its changes on `events`
+    pub fn new(events: VaultEvents) -> Self {
+        Self { events, ..Self::default() }
+    }
+
+    /// Replace the entry list
+    pub fn publish(&self, entries: Vec<EntryMeta>) {
+        *self.entries.lock().unwrap() = Some(entries);
+    }
+
+    pub fn events(&self) -> &VaultEvents {
+        &self.events
+    }
+
+    /// Receive the vault events published from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<VaultEvent> {
+        self.events.subscribe()
+    }
+
+    /// Re-read the entry list after the API changed the vault, while one is published
+    fn republish(&self, storage: &PasswordStorage, key: &[u8]) {
+        if self.entries.lock().unwrap().is_none() {
+            return;
+        }
+        match storage.list_entry_meta(key) {
+            Ok(entries) => self.publish(entries),
+            Err(e) => error!("Failed to refresh the entry list: {}", e),
+        }
+    }
+
+    /// Give the password endpoints access to the unlocked vault; their changes are
+    /// announced like the TUI's
+    pub fn publish_vault(&self, storage: PasswordStorage, key: SecureKey) {
+        *self.vault.lock().unwrap() = Some((storage.with_events(self.events.clone()), key));
+    }
+
+    /// Forget the published entries and the vault (vault locked or names wiped)
+    pub fn clear(&self) {
+        *self.entries.lock().unwrap() = None;
+        // Dropping the key zeroizes it
+        *self.vault.lock().unwrap() = None;
+    }
+
+    pub fn entries(&self) -> Option<Vec<EntryMeta>> {
+        self.entries.lock().unwrap().clone()
+    }
+
+    pub fn publish_stats(&self, stats: VaultStats) {
+        *self.stats.lock().unwrap() = Some(stats);
+    }
+
+    pub fn stats(&self) -> Option<VaultStats> {
+        self.stats.lock().unwrap().clone()
+    }
+
+    /// Run `f` on the unlocked vault; `LOCKED` while the TUI has not lent it.
+    /// Requests are served one at a time, so they don't overwrite each other's def file changes.
+    /// File I/O and decryption block, so `f` runs off the async workers
+    async fn with_vault<T: Send + 'static>(
+        &self,
+        f: impl FnOnce(&MetadataHandle, &PasswordStorage, &[u8]) -> Result<T, StatusCode> + Send + 'static,
+    ) -> Result<T, StatusCode> {
+        let handle = self.clone();
+        tokio::task::spawn_blocking(move || {
+            let vault = handle.vault.lock().unwrap();
+            let (storage, key) = vault.as_ref().ok_or(StatusCode::LOCKED)?;
+            f(&handle, storage, key.as_slice())
+        })
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    }
+}
 
 pub struct AppState {
     pub crypto: CryptoManager,
+    pub metadata: MetadataHandle,
+    /// Policy of the vault the API serves; caps token lifetime and blocks auth
+    /// for vaults this version cannot open
+    pub policy: VaultPolicy,
+    /// Vault whose master password `/api/auth` checks
+    pub passwords_dir: PathBuf,
+    /// Signs the tokens the password endpoints require
+    pub tokens: TokenSigner,
+    /// Paired extensions and the request the TUI is showing a code for
+    pub pairing: PairingHandle,
+    /// Alerts when a paired extension unlocks the vault for the first time
+    pub webhooks: Webhooks,
+    /// Backoff and lockout after wrong master passwords, per paired client
+    pub throttle: AuthThrottle,
+    /// Extension origins browsers may call the API from
+    pub allowed_origins: AllowedOrigins,
 }
 
-pub async fn start_server(
-    port: u16,
-    crypto: CryptoManager,
-    mut shutdown_rx: watch::Receiver<()>,
-) -> RpmResult<()> {
-    let state = Arc::new(AppState { crypto });
+/// Default API token lifetime, shortened by the vault's auto-lock cap
+const TOKEN_LIFETIME_HOURS: i64 = 24;
 
-    let cors = CorsLayer::new()
-        .allow_origin(Any)
-        .allow_methods(Any)
-        .allow_headers(Any);
+/// Contents of the runtime file, written while the API is running so the
+/// extension's native host can discover the port actually in use
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuntimeInfo {
+    /// Plain HTTP on loopback; `None` when only HTTPS is served
+    pub port: Option<u16>,
+    pub pid: u32,
+    #[serde(default)]
+    pub tls_port: Option<u16>,
+    /// SHA-256 of the HTTPS certificate, for the extension to pin
+    #[serde(default)]
+    pub tls_fingerprint: Option<String>,
+}
+
+/// Bind the API on `port`, or on the first free one of the next `fallback_ports`
+/// ports when it is taken (e.g. by another RPM instance)
+pub async fn bind(port: u16, fallback_ports: u16) -> RpmResult<TcpListener> {
+    let last = port.saturating_add(fallback_ports);
+    for candidate in port..=last {
+        match TcpListener::bind(format!("127.0.0.1:{}", candidate)).await {
+            Ok(listener) => return Ok(listener),
+            Err(e) if e.kind() == ErrorKind::AddrInUse => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Err(RpmError::Server(format!("Ports {}-{} are all in use", port, last)))
+}
+
+/// Record the bound ports in the runtime file
+pub fn write_runtime_info(info: &RuntimeInfo) -> RpmResult<()> {
+    let path = Config::runtime_file_path().map_err(|e| RpmError::Config(e.to_string()))?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    write_atomic(&path, serde_json::to_vec(info)?)?;
+    Ok(())
+}
+
+/// Remove the runtime file on shutdown, unless another instance has replaced it since
+pub fn remove_runtime_info() {
+    let Ok(path) = Config::runtime_file_path() else {
+        return;
+    };
+    let ours = std::fs::read(&path)
+        .ok()
+        .and_then(|content| serde_json::from_slice::<RuntimeInfo>(&content).ok())
+        .is_some_and(|info| info.pid == std::process::id());
+    if ours {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+/// Serve plain HTTP. The HTTP and HTTPS servers share `state`, so tokens and
+/// pairings are valid on both
+pub async fn start_server(listener: TcpListener, state: Arc<AppState>, mut shutdown_rx: watch::Receiver<()>) -> RpmResult<()> {
+    let app = router(state);
 
-    let app = Router::new()
-        .route("/health", get(health_check))
-        .route("/api/auth", post(authenticate))
-        .route("/api/passwords", post(create_password))
-        .route("/api/passwords", get(list_passwords))
-        .layer(cors)
-        .with_state(state);
 
-    let listener = tokio::net::TcpListener::bind(format!("127.0.0.1:{}", port)).await?;
-    
     // Create shutdown signal from watch channel
     // Wait for shutdown signal to be sent
     let shutdown = async move {
@@ -53,42 +230,415 @@ pub async fn start_server(
     Ok(())
 }
 
-async fn health_check() -> Json<serde_json::Value> {
-    Json(serde_json::json!({
+/// Serve HTTPS with `identity`, stopping gracefully on shutdown
+pub async fn start_tls_server(
+    listener: TcpListener,
+    identity: &TlsIdentity,
+    state: Arc<AppState>,
+    mut shutdown_rx: watch::Receiver<()>,
+) -> RpmResult<()> {
+    let config = RustlsConfig::from_config(identity.server_config()?);
+    let handle = Handle::new();
+    let shutdown_handle = handle.clone();
+    tokio::spawn(async move {
+        let _ = shutdown_rx.changed().await;
+        shutdown_handle.graceful_shutdown(None);
+    });
+
+    axum_server::from_tcp_rustls(listener.into_std()?, config)
+        .handle(handle)
+        .serve(router(state).into_make_service())
+        .await?;
+    Ok(())
+}
+
+/// All API routes with their middleware. Used by `start_server` and by the
+/// integration tests, which call it without binding a socket.
+pub fn router(state: Arc<AppState>) -> Router {
+    let cors = state.allowed_origins.cors_layer();
+
+    // Everything under /api/passwords needs a paired client and a token from /api/auth
+    let passwords = Router::new()
+        .route("/api/passwords", post(create_password))
+        .route("/api/passwords", get(list_passwords))
+        .route("/api/passwords/meta", get(list_password_meta))
+        .route(
+            "/api/passwords/:id",
+            get(get_password).put(update_password).delete(delete_password),
+        )
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_token))
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_paired_client));
+
+    // Only paired extensions may try the master password
+    let auth = Router::new()
+        .route("/api/auth", post(authenticate))
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_paired_client));
+
+    Router::new()
+        .route("/health", get(health_check))
+        // Checks the token and client itself: browsers can't send headers with a WebSocket
+        .route("/api/events", get(events::entry_events))
+        .route("/api/pair", post(request_pairing))
+        .route("/api/pair/:id", post(complete_pairing))
+        .merge(auth)
+        .merge(passwords)
+        .layer(middleware::from_fn_with_state(state.clone(), origins::reject_foreign_origins))
+        .layer(cors)
+        // One span per request with method, path, status and latency
+        .layer(TraceLayer::new_for_http())
+        .with_state(state)
+}
+
+/// Liveness, plus the vault statistics once the TUI has published them
+async fn health_check(State(state): State<Arc<AppState>>) -> Json<serde_json::Value> {
+    let mut health = serde_json::json!({
         "status": "ok",
         "service": "rpm-api"
-    }))
+    });
+    if let Some(stats) = state.metadata.stats() {
+        let now = Utc::now();
+        health["vault"] = serde_json::json!({
+            "entries": stats.entries,
+            "last_backup_at": stats.last_backup_at,
+            "backup_age_days": stats.backup_age_days(now),
+            "audit_findings": stats.audit_findings,
+            "sync": stats.sync,
+            "warnings": stats.warnings(now),
+        });
+    }
+    Json(health)
+}
+
+/// `TOO_MANY_REQUESTS` telling the client how many seconds to wait
+fn too_many_requests(wait: Duration) -> Response {
+    let seconds = (wait.num_milliseconds() + 999) / 1000;
+    (StatusCode::TOO_MANY_REQUESTS, [(header::RETRY_AFTER, seconds.max(1).to_string())]).into_response()
 }
 
+/// Exchange the master password for a signed token. `UNAUTHORIZED` for a wrong
+/// password or a directory without a vault, `FORBIDDEN` if the policy blocks unlocking here,
+/// `TOO_MANY_REQUESTS` with `Retry-After` while the client is backing off after wrong passwords
+#[instrument(skip_all)]
 async fn authenticate(
     State(state): State<Arc<AppState>>,
-    Json(_payload): Json<AuthRequest>,
-) -> Result<Json<AuthResponse>, StatusCode> {
-    // TODO: Verify master password
-    // TODO: Generate JWT token
-    let token = state
-        .crypto
-        .generate_token()
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    headers: HeaderMap,
+    Json(payload): Json<AuthRequest>,
+) -> Result<Json<AuthResponse>, Response> {
+    let mut password = payload.master_password;
+    if state.policy.check_unlock().is_err() {
+        password.zeroize();
+        return Err(StatusCode::FORBIDDEN.into_response());
+    }
+
+    // `require_paired_client` has already checked the credential
+    let credential = headers.get(&CLIENT_HEADER).and_then(|value| value.to_str().ok()).unwrap_or_default();
+    let client_id = credential.split_once('.').map_or(credential, |(id, _)| id);
+    let attempt = match state.throttle.begin(client_id, Utc::now()) {
+        Ok(attempt) => attempt,
+        Err(wait) => {
+            password.zeroize();
+            return Err(too_many_requests(wait));
+        }
+    };
+
+    // Argon2 takes a while, keep it off the async workers
+    let verify_state = state.clone();
+    let verified = tokio::task::spawn_blocking(move || {
+        let hash = DirectoryConfig::load(&verify_state.passwords_dir)
+            .map_err(|e| RpmError::Config(e.to_string()))?
+            .master_password_hash;
+        let verified = match hash {
+            Some(hash) => verify_state.crypto.verify_password(&password, &hash),
+            None => Ok(false),
+        };
+        password.zeroize();
+        verified
+    })
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())?
+    .map_err(|e| internal_error(e).into_response())?;
+    attempt.finish(verified, Utc::now());
+    if !verified {
+        return Err(StatusCode::UNAUTHORIZED.into_response());
+    }
+
+    match state.pairing.record_unlock(credential) {
+        Ok(Some(client)) => {
+            state.webhooks.fire(WebhookEvent::ClientUnlock { client });
+        }
+        Ok(None) => {}
+        Err(e) => error!("Failed to save paired clients: {}", e),
+    }
+
+    // A token must not outlive the session the policy allows in the TUI
+    let mut lifetime = Duration::hours(TOKEN_LIFETIME_HOURS);
+    if let Some(max) = state.policy.max_auto_lock_seconds {
+        lifetime = lifetime.min(Duration::seconds(max as i64));
+    }
+
+    let (token, expires_at) = state
+        .tokens
+        .issue(&state.crypto, lifetime)
+        .map_err(|e| internal_error(e).into_response())?;
+    Ok(Json(AuthResponse { token, expires_at }))
+}
+
+/// Let a request through only with a valid `Authorization: Bearer` token from `/api/auth`
+async fn require_token(State(state): State<Arc<AppState>>, request: Request, next: Next) -> Result<Response, StatusCode> {
+    let token = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+    state.tokens.verify(token).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    Ok(next.run(request).await)
+}
+
+/// Let a request through only from an extension the user has paired
+async fn require_paired_client(State(state): State<Arc<AppState>>, request: Request, next: Next) -> Result<Response, StatusCode> {
+    let paired = request
+        .headers()
+        .get(&CLIENT_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|credential| state.pairing.verify(credential));
+    if !paired {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    Ok(next.run(request).await)
+}
+
+/// Ask to be paired. The TUI shows the user a code for this request;
+/// `CONFLICT` while another request is waiting
+#[instrument(skip_all)]
+async fn request_pairing(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<PairRequest>,
+) -> Result<(StatusCode, Json<PairResponse>), StatusCode> {
+    let client_name = payload.client_name.trim();
+    if client_name.is_empty() {
+        return Err(StatusCode::UNPROCESSABLE_ENTITY);
+    }
+    let request = state.pairing.request(client_name).map_err(|_| StatusCode::CONFLICT)?;
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(PairResponse { request_id: request.id, expires_at: request.expires_at }),
+    ))
+}
+
+/// Send the code the user typed in. `ACCEPTED` until the user approves in the TUI,
+/// then the client credential; `FORBIDDEN` if denied or the code is wrong,
+/// `NOT_FOUND` for unknown or expired requests
+#[instrument(skip_all)]
+async fn complete_pairing(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Json(payload): Json<PairCodeRequest>,
+) -> Result<Response, StatusCode> {
+    match state.pairing.complete(&id, payload.code.trim()).map_err(internal_error)? {
+        PairingOutcome::Pending => Ok(StatusCode::ACCEPTED.into_response()),
+        PairingOutcome::Paired { client_id, credential } => Ok(Json(PairedResponse { client_id, credential }).into_response()),
+        PairingOutcome::Denied => Err(StatusCode::FORBIDDEN),
+        PairingOutcome::Unknown => Err(StatusCode::NOT_FOUND),
+    }
+}
+
+/// Storage failures are logged; the client only learns that the request failed
+fn internal_error(e: RpmError) -> StatusCode {
+    error!("API request failed: {}", e);
+    StatusCode::INTERNAL_SERVER_ERROR
+}
+
+/// Empty strings clear a field
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.filter(|value| !value.trim().is_empty())
+}
+
+/// Def file entry of an existing entry; `NOT_FOUND` for anything else, so a client
+/// can't make the storage touch files outside the vault
+fn find_entry(storage: &PasswordStorage, id: &str, key: &[u8]) -> Result<DefFileEntry, StatusCode> {
+    storage
+        .load_def_file(key)
+        .map_err(internal_error)?
+        .entries
+        .into_iter()
+        .find(|entry| entry.encrypted_filename == id)
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+/// Whether an entry other than `except` already has this title
+fn title_taken(storage: &PasswordStorage, title: &str, except: Option<&str>, key: &[u8]) -> Result<bool, StatusCode> {
+    let existing = storage.find_filename_by_name(title, key).map_err(internal_error)?;
+    Ok(existing.is_some_and(|filename| Some(filename.as_str()) != except))
+}
+
+/// Whether the entry's access window, if it has one, is open now
+fn within_access_window(details: &EntryDetails) -> bool {
+    details.access_window.as_ref().is_none_or(|window| window.allows(Local::now().naive_local()))
+}
+
+/// Details of an entry the API may serve now; `FORBIDDEN` outside its access window
+fn served_details(storage: &PasswordStorage, id: &str, key: &[u8]) -> Result<EntryDetails, StatusCode> {
+    let details = storage.load_entry_details(id, key).map_err(internal_error)?;
+    if !within_access_window(&details) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    Ok(details)
+}
 
-    Ok(Json(AuthResponse {
-        token,
-        expires_at: Utc::now() + Duration::hours(24),
-    }))
+fn password_record(storage: &PasswordStorage, entry: DefFileEntry, details: EntryDetails, key: &[u8]) -> RpmResult<PasswordRecord> {
+    let title = storage.decrypt_filename(&entry.encrypted_name, &entry.nonce, key)?;
+    let password = storage.load_password_file(&entry.encrypted_filename, key)?;
+    Ok(PasswordRecord {
+        id: entry.encrypted_filename,
+        title,
+        username: details.username,
+        password,
+        url: details.url,
+        notes: details.notes,
+        tags: entry.tags,
+        folder: entry.folder,
+        created_at: details.timestamps.created_at,
+        updated_at: details.timestamps.updated_at,
+    })
 }
 
+/// Create an entry; `CONFLICT` if the title is already used
+#[instrument(skip_all)]
 async fn create_password(
-    State(_state): State<Arc<AppState>>,
-    Json(_payload): Json<CreatePasswordRequest>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
-    // TODO: Implement password creation
-    Err(StatusCode::NOT_IMPLEMENTED)
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<CreatePasswordRequest>,
+) -> Result<(StatusCode, Json<PasswordRecord>), StatusCode> {
+    if payload.title.trim().is_empty() {
+        return Err(StatusCode::UNPROCESSABLE_ENTITY);
+    }
+    state.metadata.with_vault(move |metadata, storage, key| storage.grouped("Add entry", || {
+        let title = payload.title.trim();
+        if title_taken(storage, title, None, key)? {
+            return Err(StatusCode::CONFLICT);
+        }
+        let now = Utc::now();
+        let mut details = EntryDetails {
+            username: non_empty(payload.username),
+            url: non_empty(payload.url),
+            notes: non_empty(payload.notes),
+            ..EntryDetails::default()
+        };
+        details.timestamps.created_at = Some(now);
+        details.timestamps.updated_at = Some(now);
+
+        let filename = storage.add_entry(title, key).map_err(internal_error)?;
+        storage.update_password_file(&filename, &payload.password, key).map_err(internal_error)?;
+        storage.update_entry_details(&filename, &details, key).map_err(internal_error)?;
+        storage.set_entry_tags(&filename, &payload.tags, key).map_err(internal_error)?;
+
+        metadata.republish(storage, key);
+        let entry = find_entry(storage, &filename, key)?;
+        let record = password_record(storage, entry, details, key).map_err(internal_error)?;
+        Ok((StatusCode::CREATED, Json(record)))
+    })).await
+}
+
+/// Titles, URLs and usernames only, for rendering the extension's list.
+/// Kept apart from the secret-returning endpoints so those can be guarded more strictly.
+#[instrument(skip_all)]
+async fn list_password_meta(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<EntryMeta>>, StatusCode> {
+    state.metadata.entries().map(Json).ok_or(StatusCode::LOCKED)
 }
 
+/// Every entry with its password, sorted by title. Archived entries and entries
+/// outside their access window are left out.
+#[instrument(skip_all)]
 async fn list_passwords(
-    State(_state): State<Arc<AppState>>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
-    // TODO: Implement password listing
-    Err(StatusCode::NOT_IMPLEMENTED)
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<PasswordRecord>>, StatusCode> {
+    state.metadata.with_vault(|_, storage, key| {
+        let mut records = Vec::new();
+        for entry in storage.load_def_file(key).map_err(internal_error)?.entries {
+            if entry.archived {
+                continue;
+            }
+            let details = storage.load_entry_details(&entry.encrypted_filename, key).map_err(internal_error)?;
+            if within_access_window(&details) {
+                records.push(password_record(storage, entry, details, key).map_err(internal_error)?);
+            }
+        }
+        records.sort_by(|a, b| a.title.cmp(&b.title));
+        Ok(Json(records))
+    }).await
+}
+
+#[instrument(skip_all)]
+async fn get_password(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<PasswordRecord>, StatusCode> {
+    state.metadata.with_vault(move |_, storage, key| {
+        let entry = find_entry(storage, &id, key)?;
+        let details = served_details(storage, &id, key)?;
+        password_record(storage, entry, details, key).map(Json).map_err(internal_error)
+    }).await
+}
+
+/// Change the given fields of an entry; `CONFLICT` if the new title is already used
+#[instrument(skip_all)]
+async fn update_password(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Json(payload): Json<UpdatePasswordRequest>,
+) -> Result<Json<PasswordRecord>, StatusCode> {
+    state.metadata.with_vault(move |metadata, storage, key| storage.grouped("Update entry", || {
+        find_entry(storage, &id, key)?;
+        let mut details = served_details(storage, &id, key)?;
+        if let Some(ref title) = payload.title {
+            let title = title.trim();
+            if title.is_empty() {
+                return Err(StatusCode::UNPROCESSABLE_ENTITY);
+            }
+            if title_taken(storage, title, Some(&id), key)? {
+                return Err(StatusCode::CONFLICT);
+            }
+            storage.update_entry(&id, title, key).map_err(internal_error)?;
+        }
+        if let Some(ref password) = payload.password {
+            storage.update_password_file(&id, password, key).map_err(internal_error)?;
+        }
+        if let Some(ref tags) = payload.tags {
+            storage.set_entry_tags(&id, tags, key).map_err(internal_error)?;
+        }
+
+        if payload.username.is_some() {
+            details.username = non_empty(payload.username);
+        }
+        if payload.url.is_some() {
+            details.url = non_empty(payload.url);
+        }
+        if payload.notes.is_some() {
+            details.notes = non_empty(payload.notes);
+        }
+        details.timestamps.updated_at = Some(Utc::now());
+        storage.update_entry_details(&id, &details, key).map_err(internal_error)?;
+        metadata.republish(storage, key);
+
+        let entry = find_entry(storage, &id, key)?;
+        password_record(storage, entry, details, key).map(Json).map_err(internal_error)
+    })).await
+}
+
+/// Move an entry to the trash, as deleting it in the TUI does
+#[instrument(skip_all)]
+async fn delete_password(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, StatusCode> {
+    state.metadata.with_vault(move |metadata, storage, key| {
+        find_entry(storage, &id, key)?;
+        served_details(storage, &id, key)?;
+        storage.delete_entry(&id, key).map_err(internal_error)?;
+        metadata.republish(storage, key);
+        Ok(StatusCode::NO_CONTENT)
+    }).await
 }
 
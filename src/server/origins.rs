@@ -0,0 +1,81 @@
+use super::{AppState, CLIENT_HEADER};
+use crate::errors::{RpmError, RpmResult};
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use std::sync::Arc;
+use tower_http::cors::{AllowOrigin, CorsLayer};
+use tracing::warn;
+
+const EXTENSION_SCHEMES: &[&str] = &["chrome-extension://", "moz-extension://"];
+
+/// `chrome-extension://<id>` or `moz-extension://<uuid>` without a trailing slash,
+/// as browsers send it in `Origin`
+pub fn extension_origin(entry: &str) -> RpmResult<String> {
+    let origin = entry.trim().trim_end_matches('/');
+    let valid = EXTENSION_SCHEMES.iter().any(|scheme| {
+        origin.strip_prefix(scheme).is_some_and(|id| {
+            !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+    });
+    if !valid {
+        return Err(RpmError::InvalidInput(format!("not a browser extension origin: {}", entry)));
+    }
+    Ok(origin.to_ascii_lowercase())
+}
+
+/// Extension origins browsers may call the API from. Requests without an
+/// `Origin` header (the native host, command line tools) are not affected
+#[derive(Debug, Clone, Default)]
+pub struct AllowedOrigins {
+    origins: Vec<String>,
+}
+
+impl AllowedOrigins {
+    /// Entries from `server_allowed_origins`; malformed ones are skipped with a warning
+    pub fn new<S: AsRef<str>>(entries: &[S]) -> Self {
+        let origins = entries
+            .iter()
+            .filter_map(|entry| {
+                extension_origin(entry.as_ref())
+                    .map_err(|e| warn!("Ignoring server_allowed_origins entry: {}", e))
+                    .ok()
+            })
+            .collect();
+        Self { origins }
+    }
+
+    pub fn allows(&self, origin: &HeaderValue) -> bool {
+        origin
+            .to_str()
+            .is_ok_and(|origin| self.origins.iter().any(|allowed| allowed.eq_ignore_ascii_case(origin)))
+    }
+
+    /// Preflight and response headers for the allowed origins only
+    pub(super) fn cors_layer(&self) -> CorsLayer {
+        let origins = self.clone();
+        CorsLayer::new()
+            .allow_origin(AllowOrigin::predicate(move |origin, _| origins.allows(origin)))
+            .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
+            .allow_headers([header::AUTHORIZATION, header::CONTENT_TYPE, CLIENT_HEADER])
+    }
+}
+
+/// `FORBIDDEN` for any request a browser sends from a page or extension not in
+/// the allowlist. CORS alone would only hide the response; this also covers
+/// WebSockets, which browsers open without asking
+pub(super) async fn reject_foreign_origins(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    if let Some(origin) = request.headers().get(header::ORIGIN) {
+        if !state.allowed_origins.allows(origin) {
+            return Err(StatusCode::FORBIDDEN);
+        }
+    }
+    Ok(next.run(request).await)
+}
@@ -0,0 +1,229 @@
+use crate::errors::{RpmError, RpmResult};
+use crate::storage::atomic::write_atomic_private;
+use chrono::{DateTime, Duration, Utc};
+use rand::rngs::OsRng;
+use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+/// How long a pairing request waits for the user
+const REQUEST_LIFETIME_MINUTES: i64 = 5;
+
+/// Wrong codes a request survives before it is dropped
+const MAX_CODE_ATTEMPTS: u32 = 5;
+
+/// A browser extension the user paired; its secret is only stored hashed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PairedClient {
+    pub id: String,
+    pub name: String,
+    secret_sha256: String,
+    pub paired_at: DateTime<Utc>,
+    /// When it first unlocked the vault through `/api/auth`
+    #[serde(default)]
+    pub first_unlock_at: Option<DateTime<Utc>>,
+}
+
+/// A pairing request waiting for the user, shown in the TUI with its code
+#[derive(Debug, Clone)]
+pub struct PairingRequest {
+    pub id: String,
+    pub client_name: String,
+    /// Six digits the user types into the extension after approving
+    pub code: String,
+    pub expires_at: DateTime<Utc>,
+    answer: Answer,
+    attempts: u32,
+}
+
+/// What the user said about a request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Answer {
+    Waiting,
+    Approved,
+    Denied,
+}
+
+/// What the extension learns when it sends the code
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PairingOutcome {
+    /// The user has not answered yet
+    Pending,
+    /// Paired: the credential to send as `X-RPM-Client` from now on
+    Paired { client_id: String, credential: String },
+    /// Denied by the user, or the code was wrong
+    Denied,
+    /// No such request, or it expired
+    Unknown,
+}
+
+#[derive(Default)]
+struct Pairing {
+    /// File the paired clients are kept in; `None` keeps them in memory only
+    store: Option<PathBuf>,
+    clients: Vec<PairedClient>,
+    request: Option<PairingRequest>,
+}
+
+/// Paired clients and the pending request, shared by the API and the TUI,
+/// which shows the code and asks the user to approve it
+#[derive(Clone, Default)]
+pub struct PairingHandle {
+    inner: Arc<Mutex<Pairing>>,
+}
+
+impl Pairing {
+    fn client_index(&self, credential: &str) -> Option<usize> {
+        let (id, secret) = credential.split_once('.')?;
+        let hash = sha256_hex(secret);
+        self.clients.iter().position(|client| client.id == id && client.secret_sha256 == hash)
+    }
+
+    fn save(&self) -> RpmResult<()> {
+        if let Some(ref store) = self.store {
+            if let Some(parent) = store.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            write_atomic_private(store, serde_json::to_vec_pretty(&self.clients)?)?;
+        }
+        Ok(())
+    }
+}
+
+fn sha256_hex(secret: &str) -> String {
+    hex::encode(Sha256::digest(secret.as_bytes()))
+}
+
+impl PairingHandle {
+    /// Paired clients from `store`; the file is created on the first pairing
+    pub fn load(store: PathBuf) -> RpmResult<Self> {
+        let clients = if store.exists() {
+            serde_json::from_slice(&std::fs::read(&store)?)?
+        } else {
+            Vec::new()
+        };
+        Ok(Self {
+            inner: Arc::new(Mutex::new(Pairing { store: Some(store), clients, request: None })),
+        })
+    }
+
+    /// Start pairing `client_name`. Only one request waits at a time, so a second
+    /// extension can't slip its request in while the user reads the first code
+    pub fn request(&self, client_name: &str) -> RpmResult<PairingRequest> {
+        let mut pairing = self.inner.lock().unwrap();
+        if pairing
+            .request
+            .as_ref()
+            .is_some_and(|request| request.answer != Answer::Denied && request.expires_at > Utc::now())
+        {
+            return Err(RpmError::InvalidInput("Another pairing request is waiting".to_string()));
+        }
+        let request = PairingRequest {
+            id: Uuid::new_v4().to_string(),
+            client_name: client_name.to_string(),
+            code: format!("{:06}", OsRng.gen_range(0..1_000_000)),
+            expires_at: Utc::now() + Duration::minutes(REQUEST_LIFETIME_MINUTES),
+            answer: Answer::Waiting,
+            attempts: 0,
+        };
+        pairing.request = Some(request.clone());
+        Ok(request)
+    }
+
+    /// The request waiting for the user's answer, if any
+    pub fn pending(&self) -> Option<PairingRequest> {
+        let pairing = self.inner.lock().unwrap();
+        pairing
+            .request
+            .as_ref()
+            .filter(|request| request.answer == Answer::Waiting && request.expires_at > Utc::now())
+            .cloned()
+    }
+
+    fn answer(&self, request_id: &str, answer: Answer) {
+        if let Some(request) = self.inner.lock().unwrap().request.as_mut().filter(|request| request.id == request_id) {
+            request.answer = answer;
+        }
+    }
+
+    /// The user confirmed the code shown for this request
+    pub fn approve(&self, request_id: &str) {
+        self.answer(request_id, Answer::Approved);
+    }
+
+    /// The user refused this request; the extension learns it on its next attempt
+    pub fn deny(&self, request_id: &str) {
+        self.answer(request_id, Answer::Denied);
+    }
+
+    /// The extension sends the code the user typed in. Once the request is approved
+    /// and the code matches, the client is paired and stored
+    pub fn complete(&self, request_id: &str, code: &str) -> RpmResult<PairingOutcome> {
+        let mut pairing = self.inner.lock().unwrap();
+        let Some(request) = pairing.request.as_mut().filter(|request| request.id == request_id && request.expires_at > Utc::now()) else {
+            return Ok(PairingOutcome::Unknown);
+        };
+        if request.answer == Answer::Denied {
+            pairing.request = None;
+            return Ok(PairingOutcome::Denied);
+        }
+        if request.code != code {
+            request.attempts += 1;
+            if request.attempts >= MAX_CODE_ATTEMPTS {
+                pairing.request = None;
+            }
+            return Ok(PairingOutcome::Denied);
+        }
+        if request.answer == Answer::Waiting {
+            return Ok(PairingOutcome::Pending);
+        }
+
+        let client_name = request.client_name.clone();
+        pairing.request = None;
+        let mut secret = [0u8; 32];
+        OsRng.fill_bytes(&mut secret);
+        let secret = hex::encode(secret);
+        let client = PairedClient {
+            id: Uuid::new_v4().to_string(),
+            name: client_name,
+            secret_sha256: sha256_hex(&secret),
+            paired_at: Utc::now(),
+            first_unlock_at: None,
+        };
+        let client_id = client.id.clone();
+        pairing.clients.push(client);
+        pairing.save()?;
+        Ok(PairingOutcome::Paired {
+            credential: format!("{}.{}", client_id, secret),
+            client_id,
+        })
+    }
+
+    /// Whether `credential` (`<client id>.<secret>`) belongs to a paired client
+    pub fn verify(&self, credential: &str) -> bool {
+        self.inner.lock().unwrap().client_index(credential).is_some()
+    }
+
+    /// Note that the client unlocked the vault; its name if it never did before
+    pub fn record_unlock(&self, credential: &str) -> RpmResult<Option<String>> {
+        let mut pairing = self.inner.lock().unwrap();
+        let Some(index) = pairing.client_index(credential) else {
+            return Ok(None);
+        };
+        let client = &mut pairing.clients[index];
+        if client.first_unlock_at.is_some() {
+            return Ok(None);
+        }
+        client.first_unlock_at = Some(Utc::now());
+        let name = client.name.clone();
+        pairing.save()?;
+        Ok(Some(name))
+    }
+
+    pub fn clients(&self) -> Vec<PairedClient> {
+        self.inner.lock().unwrap().clients.clone()
+    }
+}
@@ -0,0 +1,138 @@
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Longest lockout window accepted from the config
+const MAX_LOCKOUT_SECONDS: u64 = 24 * 60 * 60;
+
+/// Failed attempts of one client
+#[derive(Debug, Clone)]
+struct Failures {
+    count: u32,
+    last_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default)]
+struct Throttle {
+    /// Consecutive failures after which a client waits the whole lockout window
+    max_failures: u32,
+    /// Longest wait; failures older than this are forgotten. Zero disables throttling
+    lockout: Duration,
+    failures: HashMap<String, Failures>,
+    /// Clients whose master password is being checked right now
+    in_flight: Vec<String>,
+    /// Failed attempts since start, for the TUI
+    total_failures: u64,
+}
+
+impl Throttle {
+    /// Wait after `count` consecutive failures: 1, 2, 4... seconds, the lockout window from `max_failures` on
+    fn backoff(&self, count: u32) -> Duration {
+        if count >= self.max_failures.max(1) {
+            return self.lockout;
+        }
+        Duration::seconds(1 << count.saturating_sub(1).min(30)).min(self.lockout)
+    }
+
+    fn wait(&self, client: &str, now: DateTime<Utc>) -> Option<Duration> {
+        let failures = self.failures.get(client)?;
+        let wait = failures.last_at + self.backoff(failures.count) - now;
+        (wait > Duration::zero()).then_some(wait)
+    }
+}
+
+/// Per-client brake on `/api/auth`: every wrong master password doubles the
+/// wait before the client may try again, up to a lockout window once
+/// `max_failures` attempts in a row failed. Shared with the TUI, which shows the count.
+#[derive(Clone, Default)]
+pub struct AuthThrottle {
+    inner: Arc<Mutex<Throttle>>,
+}
+
+impl AuthThrottle {
+    pub fn new(max_failures: u32, lockout_seconds: u64) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Throttle {
+                max_failures,
+                lockout: Duration::seconds(lockout_seconds.min(MAX_LOCKOUT_SECONDS) as i64),
+                ..Throttle::default()
+            })),
+        }
+    }
+
+    /// Start an attempt of `client`, or how long it has to wait. One attempt per
+    /// client at a time, so parallel requests can't get around the backoff
+    pub fn begin(&self, client: &str, now: DateTime<Utc>) -> Result<AuthAttempt, Duration> {
+        let attempt = AuthAttempt { throttle: self.clone(), client: client.to_string() };
+        let mut throttle = self.inner.lock().unwrap();
+        if throttle.lockout <= Duration::zero() {
+            return Ok(attempt);
+        }
+        if throttle.in_flight.iter().any(|busy| busy == client) {
+            return Err(Duration::seconds(1));
+        }
+        if let Some(wait) = throttle.wait(client, now) {
+            return Err(wait);
+        }
+        // A failure long enough ago no longer counts towards the lockout
+        let lockout = throttle.lockout;
+        if throttle.failures.get(client).is_some_and(|failures| now - failures.last_at >= lockout) {
+            throttle.failures.remove(client);
+        }
+        throttle.in_flight.push(client.to_string());
+        Ok(attempt)
+    }
+
+    /// Wrong master passwords sent through the API since start
+    pub fn failed_attempts(&self) -> u64 {
+        self.inner.lock().unwrap().total_failures
+    }
+
+    /// Clients that have to wait the whole lockout window, with when it ends
+    pub fn locked_out(&self, now: DateTime<Utc>) -> Vec<(String, DateTime<Utc>)> {
+        let throttle = self.inner.lock().unwrap();
+        let mut locked: Vec<_> = throttle
+            .failures
+            .iter()
+            .filter(|(_, failures)| failures.count >= throttle.max_failures.max(1))
+            .map(|(client, failures)| (client.clone(), failures.last_at + throttle.lockout))
+            .filter(|(_, until)| *until > now)
+            .collect();
+        locked.sort();
+        locked
+    }
+}
+
+/// An attempt `AuthThrottle::begin` let through. Dropping it without `finish`
+/// (an internal error, or the client went away) counts for nothing
+pub struct AuthAttempt {
+    throttle: AuthThrottle,
+    client: String,
+}
+
+impl AuthAttempt {
+    /// Record the outcome; `verified` clears the client's failures
+    pub fn finish(self, verified: bool, now: DateTime<Utc>) {
+        let mut throttle = self.throttle.inner.lock().unwrap();
+        if throttle.lockout <= Duration::zero() {
+            return;
+        }
+        if verified {
+            throttle.failures.remove(&self.client);
+            return;
+        }
+        throttle.total_failures += 1;
+        let failures = throttle
+            .failures
+            .entry(self.client.clone())
+            .or_insert(Failures { count: 0, last_at: now });
+        failures.count += 1;
+        failures.last_at = now;
+    }
+}
+
+impl Drop for AuthAttempt {
+    fn drop(&mut self) {
+        self.throttle.inner.lock().unwrap().in_flight.retain(|busy| *busy != self.client);
+    }
+}
@@ -0,0 +1,104 @@
+use crate::config::Config;
+use crate::errors::{RpmError, RpmResult};
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::ServerConfig;
+use sha2::{Digest, Sha256};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Arc;
+use zeroize::Zeroizing;
+
+/// Names the certificate is issued for: the API only listens on loopback
+const SUBJECT_NAMES: [&str; 2] = ["localhost", "127.0.0.1"];
+
+fn tls_error(context: &str, e: impl std::fmt::Display) -> RpmError {
+    RpmError::Server(format!("{}: {}", context, e))
+}
+
+/// Self-signed certificate of the HTTPS API. Browsers can't verify it, so the
+/// extension pins its fingerprint, which the settings screen shows.
+pub struct TlsIdentity {
+    certificate_pem: String,
+    key_pem: Zeroizing<String>,
+}
+
+impl TlsIdentity {
+    /// The certificate kept next to the config; generated and saved on first use
+    pub fn load_or_generate() -> RpmResult<Self> {
+        let certificate_path = Config::tls_certificate_path().map_err(|e| RpmError::Config(e.to_string()))?;
+        let key_path = Config::tls_key_path().map_err(|e| RpmError::Config(e.to_string()))?;
+        if certificate_path.exists() && key_path.exists() {
+            return Self::load(&certificate_path, &key_path);
+        }
+        let identity = Self::generate()?;
+        identity.save(&certificate_path, &key_path)?;
+        Ok(identity)
+    }
+
+    /// A new certificate for `localhost` and `127.0.0.1`
+    pub fn generate() -> RpmResult<Self> {
+        let certified = rcgen::generate_simple_self_signed(SUBJECT_NAMES.map(String::from).to_vec())
+            .map_err(|e| tls_error("Cannot generate the TLS certificate", e))?;
+        Ok(Self {
+            certificate_pem: certified.cert.pem(),
+            key_pem: Zeroizing::new(certified.key_pair.serialize_pem()),
+        })
+    }
+
+    pub fn load(certificate_path: &Path, key_path: &Path) -> RpmResult<Self> {
+        let identity = Self {
+            certificate_pem: std::fs::read_to_string(certificate_path)?,
+            key_pem: Zeroizing::new(std::fs::read_to_string(key_path)?),
+        };
+        // Fail here rather than when the server starts
+        identity.server_config()?;
+        Ok(identity)
+    }
+
+    /// Write the certificate, and the key readable by the owner only
+    pub fn save(&self, certificate_path: &Path, key_path: &Path) -> RpmResult<()> {
+        if let Some(parent) = certificate_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(certificate_path, &self.certificate_pem)?;
+
+        let mut options = OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        options.open(key_path)?.write_all(self.key_pem.as_bytes())?;
+        Ok(())
+    }
+
+    /// The certificate in PEM, e.g. for a client that trusts exactly this certificate
+    pub fn certificate_pem(&self) -> &str {
+        &self.certificate_pem
+    }
+
+    fn certificate_der(&self) -> RpmResult<CertificateDer<'static>> {
+        CertificateDer::from_pem_slice(self.certificate_pem.as_bytes()).map_err(|e| tls_error("Invalid TLS certificate", e))
+    }
+
+    /// SHA-256 of the certificate (DER), lowercase hex: what the extension pins
+    pub fn fingerprint(&self) -> RpmResult<String> {
+        Ok(hex::encode(Sha256::digest(self.certificate_der()?.as_ref())))
+    }
+
+    /// rustls configuration serving this certificate, with ring as the crypto provider
+    pub fn server_config(&self) -> RpmResult<Arc<ServerConfig>> {
+        let key = PrivateKeyDer::from_pem_slice(self.key_pem.as_bytes()).map_err(|e| tls_error("Invalid TLS key", e))?;
+        let mut config = ServerConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .map_err(|e| tls_error("TLS setup failed", e))?
+            .with_no_client_auth()
+            .with_single_cert(vec![self.certificate_der()?], key)
+            .map_err(|e| tls_error("TLS setup failed", e))?;
+        config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+        Ok(Arc::new(config))
+    }
+}
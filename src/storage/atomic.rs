@@ -0,0 +1,73 @@
+//! Crash-safe file writes. A file is written next to its target under a temporary
+//! name, flushed to disk and renamed over the target, so after a crash or power
+//! loss the target holds either its old or its new contents, never a mix.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Distinguishes temporary files of concurrent writes to the same target
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Replace `path` with `contents` atomically
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    replace(path, contents.as_ref(), false)
+}
+
+/// `write_atomic` for files holding secrets in plaintext: the new file is readable by
+/// the owner only, also when it replaces one that was readable by others
+pub fn write_atomic_private(path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    replace(path, contents.as_ref(), true)
+}
+
+/// Write `contents` to `path` and wait until they are on disk. For files staged
+/// to be renamed into place later
+pub fn write_synced(path: &Path, contents: &[u8]) -> io::Result<()> {
+    write_new(path, contents, false)
+}
+
+fn replace(path: &Path, contents: &[u8], private: bool) -> io::Result<()> {
+    let tmp_path = temp_path(path);
+    let result = write_new(&tmp_path, contents, private).and_then(|()| std::fs::rename(&tmp_path, path));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp_path);
+        return result;
+    }
+    sync_parent(path)
+}
+
+/// `write_synced`, creating the file with mode 0600 when `private` (Unix only)
+fn write_new(path: &Path, contents: &[u8], private: bool) -> io::Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    if private {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    #[cfg(not(unix))]
+    let _ = private;
+    let mut file = options.open(path)?;
+    file.write_all(contents)?;
+    file.sync_all()
+}
+
+/// Flush the directory entry of `path`, so a rename into it survives a crash.
+/// Windows has no directory handles to flush; its renames are journaled by NTFS
+pub fn sync_parent(path: &Path) -> io::Result<()> {
+    #[cfg(unix)]
+    if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        File::open(parent)?.sync_all()?;
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
+
+/// `.<name>.<pid>.<n>.tmp` next to `path`: git-ignored, and never taken for a vault file
+fn temp_path(path: &Path) -> PathBuf {
+    let name = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+    let n = TEMP_COUNTER.fetch_add(1, Ordering::Relaxed);
+    path.with_file_name(format!(".{}.{}.{}.tmp", name, std::process::id(), n))
+}
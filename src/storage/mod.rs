@@ -1,22 +1,346 @@
-use crate::config::Config;
-use crate::crypto::CryptoManager;
+use crate::config::{Config, DirectoryConfig};
+use crate::crypto::key_derivation::{Argon2id, Kdf, KdfParams};
+use crate::crypto::{estimate_entropy_bits, Cipher, CryptoManager, SecureKey};
 use crate::errors::{RpmError, RpmResult};
-use crate::models::{DefFile, DefFileEntry, PasswordFile};
-use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+use crate::events::{VaultEvent, VaultEvents};
+use crate::export::current_user;
+use crate::models::{AuditAction, AuditEvent, AuditLog, DefFile, DefFileEntry, EntryDetails, EntryMeta, PasswordFile, PasswordHistoryItem, TrashEntry, TrashFile, UiState};
+use crate::sync::git::GitSync;
+use crate::sync::tombstones::{self, Tombstones};
+use base64::engine::general_purpose::{STANDARD as BASE64_STANDARD, STANDARD_NO_PAD as BASE64_STANDARD_NO_PAD};
 use base64::Engine;
-use std::path::PathBuf;
+use chrono::{DateTime, Utc};
+use rand::RngCore;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use tracing::{error, instrument, Span};
 use uuid::Uuid;
+use zeroize::Zeroize;
+
+use atomic::{sync_parent, write_atomic, write_synced};
+
+pub mod atomic;
+
+/// UI state file in the passwords directory: device-local, so it is git-ignored
+/// and left out of backups and WebDAV sync
+const UI_STATE_FILE: &str = ".rpm_ui_state";
+
+/// Marker written once a re-encryption has staged all its `*.rekey` files and removed
+/// once they are all renamed into place. While it exists the staged files are the
+/// vault, and the next open finishes renaming them
+const REKEY_MARKER: &str = "rekey.pending";
+
+/// Directory in the passwords directory that orphans are moved to instead of being
+/// deleted. Not a vault file: neither synced nor backed up
+pub const QUARANTINE_DIR: &str = "quarantine";
+
+/// Result of a vault compaction (what would be or was removed)
+#[derive(Debug, Clone, Default)]
+pub struct CompactionReport {
+    /// `.pwd` files that have no entry in the def file
+    pub orphaned_files: Vec<String>,
+    /// Password history items older than the retention period
+    pub expired_history: usize,
+    /// Bytes freed on disk (estimated for a dry run)
+    pub reclaimed_bytes: u64,
+}
+
+impl CompactionReport {
+    pub fn is_empty(&self) -> bool {
+        self.orphaned_files.is_empty() && self.expired_history == 0
+    }
+}
+
+/// Problem found by `PasswordStorage::verify`. Files are named as in the passwords
+/// directory (`def`, `<uuid>.pwd`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IntegrityIssue {
+    /// The def file, trash or audit log can't be read or decrypted
+    UnreadableIndex { file: String, error: String },
+    /// The encrypted name of an entry doesn't decrypt
+    UndecryptableName { filename: String },
+    /// The def file or the trash refers to a password file that isn't there
+    MissingFile { filename: String },
+    /// A password file that can't be parsed: not JSON, bad base64 or broken details
+    CorruptedFile { filename: String, error: String },
+    /// A password file whose password or details fail to decrypt (wrong key or tampering)
+    UndecryptableFile { filename: String },
+    /// A `.pwd` file neither the def file nor the trash refers to
+    UnreferencedFile { filename: String },
+}
+
+impl IntegrityIssue {
+    /// The vault file the issue is about
+    pub fn file(&self) -> &str {
+        match self {
+            Self::UnreadableIndex { file, .. } => file,
+            Self::UndecryptableName { filename }
+            | Self::MissingFile { filename }
+            | Self::CorruptedFile { filename, .. }
+            | Self::UndecryptableFile { filename }
+            | Self::UnreferencedFile { filename } => filename,
+        }
+    }
+}
+
+impl std::fmt::Display for IntegrityIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnreadableIndex { file, error } => write!(f, "{}: cannot be read: {}", file, error),
+            Self::UndecryptableName { filename } => write!(f, "{}: the entry name does not decrypt", filename),
+            Self::MissingFile { filename } => write!(f, "{}: listed in the def file, but the file is missing", filename),
+            Self::CorruptedFile { filename, error } => write!(f, "{}: corrupted: {}", filename, error),
+            Self::UndecryptableFile { filename } => write!(f, "{}: does not decrypt", filename),
+            Self::UnreferencedFile { filename } => write!(f, "{}: no entry refers to this file", filename),
+        }
+    }
+}
+
+/// Result of a vault integrity check
+#[derive(Debug, Clone, Default)]
+pub struct IntegrityReport {
+    /// Entries of the def file and the trash whose name and file were checked
+    pub entries_checked: usize,
+    pub issues: Vec<IntegrityIssue>,
+}
+
+impl IntegrityReport {
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// One half of an entry without the other, found by `PasswordStorage::find_orphans`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Orphan {
+    /// A `.pwd` file neither the def file nor the trash refers to
+    File { filename: String },
+    /// A def file entry whose password file is missing
+    Entry { filename: String },
+}
+
+impl Orphan {
+    /// Password file name of the orphan
+    pub fn filename(&self) -> &str {
+        match self {
+            Self::File { filename } | Self::Entry { filename } => filename,
+        }
+    }
+}
+
+/// What `PasswordStorage::repair_orphan` does with an orphan
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OrphanRepair {
+    /// Add a def file entry named `name` for an orphaned file
+    Relink { name: String },
+    /// Move the file, or the def file entry, into `QUARANTINE_DIR`
+    Quarantine,
+    /// Remove the file, or the def file entry, for good
+    Delete,
+}
+
+/// Entries sharing one password, with how risky the reuse is
+#[derive(Debug, Clone, Default)]
+pub struct ReuseCluster {
+    /// Names of the entries sharing the password, sorted
+    pub entries: Vec<String>,
+    /// Distinct sites of those entries: the URL host, or the entry name without a URL
+    pub sites: Vec<String>,
+    /// Estimated strength of the shared password
+    pub entropy_bits: f64,
+    /// 0-100, higher means rotate first: half from weakness, half from how widely it is shared
+    pub risk_score: u8,
+}
+
+impl ReuseCluster {
+    fn new(entries: Vec<String>, sites: Vec<String>, entropy_bits: f64) -> Self {
+        // Passwords of 80 bits and more are considered strong
+        let weakness = 1.0 - entropy_bits.min(80.0) / 80.0;
+        let spread = 1.0 - 1.0 / entries.len().max(1) as f64;
+        let risk_score = (50.0 * weakness + 50.0 * spread).round() as u8;
+        Self { entries, sites, entropy_bits, risk_score }
+    }
+}
+
+impl Zeroize for ReuseCluster {
+    fn zeroize(&mut self) {
+        self.entries.zeroize();
+        self.sites.zeroize();
+        self.entropy_bits = 0.0;
+        self.risk_score = 0;
+    }
+}
+
+/// Host part of a URL without scheme, credentials, port and leading `www.`
+fn site_of(url: &str) -> Option<String> {
+    let rest = url.trim().split_once("://").map_or(url.trim(), |(_, rest)| rest);
+    let host = rest.split(['/', '?', '#']).next()?;
+    let host = host.rsplit_once('@').map_or(host, |(_, host)| host);
+    let host = host.split(':').next()?.to_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+    (!host.is_empty()).then(|| host.to_string())
+}
 
 pub struct PasswordStorage {
     passwords_dir: PathBuf,
     crypto: CryptoManager,
+    /// Cipher of new password files; existing ones keep the cipher they name
+    cipher: Cipher,
+    /// Auto-commit target when `git_sync` is enabled
+    git_sync: Option<GitSync>,
+    /// Nesting depth of running changes; the commit happens when the outermost one ends
+    change_depth: Cell<usize>,
+    /// Where entry changes are announced, once the outermost change ends
+    events: Option<VaultEvents>,
+    pending_events: RefCell<Vec<VaultEvent>>,
+}
+
+/// A change in progress. Dropping the outermost guard commits whatever the
+/// operation wrote, whether it succeeded or not.
+struct ChangeGuard<'a> {
+    storage: &'a PasswordStorage,
+    message: &'static str,
+}
+
+impl Drop for ChangeGuard<'_> {
+    fn drop(&mut self) {
+        let depth = self.storage.change_depth.get() - 1;
+        self.storage.change_depth.set(depth);
+        if depth > 0 {
+            return;
+        }
+        if let Some(ref git_sync) = self.storage.git_sync {
+            // A failed commit must not fail the change itself; the next one picks it up
+            if let Err(e) = git_sync.commit_all(self.message) {
+                error!("Auto-commit failed: {}", e);
+            }
+        }
+        let pending = self.storage.pending_events.take();
+        if let Some(ref events) = self.storage.events {
+            for event in pending {
+                events.publish(event);
+            }
+        }
+    }
+}
+
+/// Canonical folder path: segments trimmed, empty segments dropped, joined with `/`
+pub fn normalize_folder(path: &str) -> String {
+    path.split('/')
+        .map(str::trim)
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Finish a re-encryption that was interrupted after `REKEY_MARKER` was written:
+/// rename the `*.rekey` files still left over the files they replace and remove the
+/// marker. `true` if there was one to finish. Staged files without the marker are
+/// from a re-encryption that failed before committing and are left alone.
+pub fn finish_interrupted_rekey(passwords_dir: &Path) -> std::io::Result<bool> {
+    let marker = passwords_dir.join(REKEY_MARKER);
+    if !marker.exists() {
+        return Ok(false);
+    }
+
+    let mut staged = Vec::new();
+    for dir_entry in std::fs::read_dir(passwords_dir)? {
+        let name = dir_entry?.file_name().to_string_lossy().into_owned();
+        if let Some(target) = name.strip_suffix(".rekey") {
+            staged.push((name.clone(), target.to_string()));
+        }
+    }
+    // The def file and the directory config last, as when the re-encryption ran
+    staged.sort_by_key(|(_, target)| matches!(target.as_str(), "def" | ".rpm_config"));
+    for (name, target) in &staged {
+        match std::fs::rename(passwords_dir.join(name), passwords_dir.join(target)) {
+            // Another process finishing the same re-encryption got there first
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            result => result?,
+        }
+    }
+    sync_parent(&marker)?;
+    match std::fs::remove_file(&marker) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e),
+        _ => {}
+    }
+    sync_parent(&marker)?;
+    tracing::warn!(files = staged.len(), "Finished an interrupted re-encryption");
+    Ok(true)
+}
+
+/// Size of the base64 encoded, encrypted JSON of entry details as stored on disk
+fn encrypted_details_len(details: &EntryDetails) -> u64 {
+    let json_len = serde_json::to_vec(details).map(|v| v.len()).unwrap_or(0) as u64;
+    // 16-byte authentication tag, then base64 (4 chars per 3 bytes, padded)
+    (json_len + 16).div_ceil(3) * 4
 }
 
 impl PasswordStorage {
     pub fn new(config: &Config, crypto: CryptoManager) -> Self {
+        let passwords_dir = config.passwords_directory_path();
+        if let Err(e) = finish_interrupted_rekey(&passwords_dir) {
+            error!("Could not finish an interrupted re-encryption: {}", e);
+        }
         Self {
-            passwords_dir: config.passwords_directory_path(),
+            git_sync: config.git_sync.then(|| GitSync::new(&passwords_dir)),
+            passwords_dir,
             crypto,
+            cipher: config.cipher().unwrap_or_else(|e| {
+                error!("{}; new entries use {}", e, Cipher::default().name());
+                Cipher::default()
+            }),
+            change_depth: Cell::new(0),
+            events: None,
+            pending_events: RefCell::default(),
+        }
+    }
+
+    /// Announce entry changes on `events`
+    pub fn with_events(mut self, events: VaultEvents) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Another handle on the same vault, e.g. for the API while the TUI keeps its own.
+    /// Each handle commits its own changes and announces them on the same events.
+    pub fn handle(&self) -> Self {
+        Self {
+            passwords_dir: self.passwords_dir.clone(),
+            crypto: self.crypto.clone(),
+            cipher: self.cipher,
+            git_sync: self.git_sync.clone(),
+            change_depth: Cell::new(0),
+            events: self.events.clone(),
+            pending_events: RefCell::default(),
+        }
+    }
+
+    /// Start a change that is committed to git when it (and any change it calls) ends
+    fn change(&self, message: &'static str) -> ChangeGuard<'_> {
+        self.change_depth.set(self.change_depth.get() + 1);
+        ChangeGuard { storage: self, message }
+    }
+
+    /// Make the changes `f` makes one: a single git commit, and one event per entry
+    pub fn grouped<T>(&self, message: &'static str, f: impl FnOnce() -> T) -> T {
+        let _change = self.change(message);
+        f()
+    }
+
+    /// Queue an event for when the running change ends. Repeats within one change
+    /// are dropped, and so are changes to an entry the same change added
+    fn emit(&self, event: VaultEvent) {
+        let mut pending = self.pending_events.borrow_mut();
+        let added = match event {
+            VaultEvent::EntryChanged { ref id } => pending.contains(&VaultEvent::EntryAdded { id: id.clone() }),
+            _ => false,
+        };
+        if !added && !pending.contains(&event) {
+            pending.push(event);
         }
     }
 
@@ -25,6 +349,21 @@ impl PasswordStorage {
         self.passwords_dir.join("def")
     }
 
+    /// Get the path to the trash index
+    fn trash_file_path(&self) -> PathBuf {
+        self.passwords_dir.join("trash")
+    }
+
+    /// Get the path to the audit log
+    fn audit_file_path(&self) -> PathBuf {
+        self.passwords_dir.join("audit")
+    }
+
+    /// Get the path to the saved UI state (per device, git-ignored)
+    fn ui_state_file_path(&self) -> PathBuf {
+        self.passwords_dir.join(UI_STATE_FILE)
+    }
+
     /// Get the path to a password file by UUID
     pub fn password_file_path(&self, filename: &str) -> PathBuf {
         self.passwords_dir.join(filename)
@@ -33,28 +372,36 @@ impl PasswordStorage {
     /// Ensure passwords directory exists
     fn ensure_passwords_dir(&self) -> RpmResult<()> {
         std::fs::create_dir_all(&self.passwords_dir)
-            .map_err(|e| RpmError::Io(e))?;
+            .map_err(RpmError::Io)?;
         Ok(())
     }
 
-    /// Load and decrypt the def file
+    /// Load and decrypt the def file. Entries whose password file was purged on
+    /// another replica are left out, in case a stale def file won a sync
+    #[instrument(level = "debug", skip_all, fields(entries))]
     pub fn load_def_file(&self, key: &[u8]) -> RpmResult<DefFile> {
-        let def_path = self.def_file_path();
-        
-        if !def_path.exists() {
-            // Return empty def file if it doesn't exist
-            return Ok(DefFile { entries: Vec::new() });
-        }
+        // Return empty def file if it doesn't exist
+        let mut def_file = self
+            .read_encrypted_json(&self.def_file_path(), "def file", key)?
+            .unwrap_or(DefFile { entries: Vec::new() });
+        let tombstones = self.load_tombstones()?;
+        def_file.entries.retain(|entry| tombstones.deleted_at(&entry.encrypted_filename).is_none());
 
-        let encrypted_content = std::fs::read(&def_path)
-            .map_err(|e| RpmError::Io(e))?;
+        Span::current().record("entries", def_file.entries.len());
+        Ok(def_file)
+    }
+
+    /// Read and decrypt an index file (def file or trash), `None` if it doesn't exist.
+    /// Format: first 12 bytes are nonce, rest is ciphertext of the JSON.
+    fn read_encrypted_json<T: DeserializeOwned>(&self, path: &std::path::Path, what: &str, key: &[u8]) -> RpmResult<Option<T>> {
+        if !path.exists() {
+            return Ok(None);
+        }
 
-        // Decrypt the def file
-        // The def file itself is encrypted, so we need to handle it
-        // For now, we'll store it as JSON encrypted with the key
-        // Format: first 12 bytes are nonce, rest is ciphertext
+        let encrypted_content = std::fs::read(path)
+            .map_err(RpmError::Io)?;
         if encrypted_content.len() < 12 {
-            return Err(RpmError::Crypto("Invalid def file format".to_string()));
+            return Err(RpmError::Crypto(format!("Invalid {} format", what)));
         }
 
         let nonce = &encrypted_content[0..12];
@@ -62,31 +409,112 @@ impl PasswordStorage {
 
         let plaintext = self.crypto.decrypt_data(ciphertext, nonce, key)?;
         let json_str = String::from_utf8(plaintext)
-            .map_err(|e| RpmError::Crypto(format!("Invalid UTF-8 in def file: {}", e)))?;
+            .map_err(|e| RpmError::Crypto(format!("Invalid UTF-8 in {}: {}", what, e)))?;
 
-        let def_file: DefFile = serde_json::from_str(&json_str)
-            .map_err(|e| RpmError::Serialization(e.into()))?;
+        serde_json::from_str(&json_str)
+            .map(Some)
+            .map_err(RpmError::Serialization)
+    }
 
-        Ok(def_file)
+    /// Files purged here or on a synced replica
+    fn load_tombstones(&self) -> RpmResult<Tombstones> {
+        if !self.passwords_dir.exists() {
+            return Ok(Tombstones::default());
+        }
+        Tombstones::load(&self.passwords_dir)
     }
 
     /// Save the def file encrypted
+    #[instrument(level = "debug", skip_all, fields(entries = def_file.entries.len()))]
     pub fn save_def_file(&self, def_file: &DefFile, key: &[u8]) -> RpmResult<()> {
         self.ensure_passwords_dir()?;
 
+        let encrypted_content = self.encrypt_def_file(def_file, key)?;
+        write_atomic(&self.def_file_path(), encrypted_content)
+            .map_err(RpmError::Io)?;
+
+        Ok(())
+    }
+
+    /// Load and decrypt the trash index, without entries purged on another replica
+    pub fn load_trash_file(&self, key: &[u8]) -> RpmResult<TrashFile> {
+        let mut trash_file: TrashFile = self
+            .read_encrypted_json(&self.trash_file_path(), "trash", key)?
+            .unwrap_or_default();
+        let tombstones = self.load_tombstones()?;
+        trash_file.entries.retain(|trashed| tombstones.deleted_at(&trashed.entry.encrypted_filename).is_none());
+        Ok(trash_file)
+    }
+
+    /// Save the trash index encrypted
+    fn save_trash_file(&self, trash_file: &TrashFile, key: &[u8]) -> RpmResult<()> {
+        self.ensure_passwords_dir()?;
+
+        let encrypted_content = self.encrypt_def_file(trash_file, key)?;
+        write_atomic(&self.trash_file_path(), encrypted_content)
+            .map_err(RpmError::Io)?;
+
+        Ok(())
+    }
+
+    /// Load and decrypt the audit log (empty if there is none yet)
+    pub fn load_audit_log(&self, key: &[u8]) -> RpmResult<AuditLog> {
+        Ok(self
+            .read_encrypted_json(&self.audit_file_path(), "audit log", key)?
+            .unwrap_or_default())
+    }
+
+    /// Append an event to the audit log
+    #[instrument(level = "info", skip_all, fields(action = ?event.action))]
+    pub fn record_audit_event(&self, event: AuditEvent, key: &[u8]) -> RpmResult<()> {
+        self.record_audit_events(vec![event], key)
+    }
+
+    /// Append several events with one write (and one git commit)
+    pub fn record_audit_events(&self, events: Vec<AuditEvent>, key: &[u8]) -> RpmResult<()> {
+        if events.is_empty() {
+            return Ok(());
+        }
+        let _change = self.change("Record audit event");
+        let mut audit_log = self.load_audit_log(key)?;
+        audit_log.events.extend(events);
+
+        self.ensure_passwords_dir()?;
+        let encrypted_content = self.encrypt_def_file(&audit_log, key)?;
+        write_atomic(&self.audit_file_path(), encrypted_content)
+            .map_err(RpmError::Io)?;
+
+        Ok(())
+    }
+
+    /// Load the UI state saved at the last lock or exit (default if there is none)
+    pub fn load_ui_state(&self, key: &[u8]) -> RpmResult<UiState> {
+        Ok(self
+            .read_encrypted_json(&self.ui_state_file_path(), "UI state", key)?
+            .unwrap_or_default())
+    }
+
+    /// Save the UI state encrypted. Not a vault change: nothing is committed to git
+    pub fn save_ui_state(&self, ui_state: &UiState, key: &[u8]) -> RpmResult<()> {
+        self.ensure_passwords_dir()?;
+
+        let encrypted_content = self.encrypt_def_file(ui_state, key)?;
+        write_atomic(&self.ui_state_file_path(), encrypted_content)
+            .map_err(RpmError::Io)?;
+
+        Ok(())
+    }
+
+    /// Serialize and encrypt an index file (def file, trash, audit log or UI state): nonce (12 bytes) + ciphertext
+    fn encrypt_def_file<T: Serialize>(&self, def_file: &T, key: &[u8]) -> RpmResult<Vec<u8>> {
         let json_str = serde_json::to_string(def_file)
-            .map_err(|e| RpmError::Serialization(e.into()))?;
+            .map_err(RpmError::Serialization)?;
 
         let (ciphertext, nonce) = self.crypto.encrypt_data(json_str.as_bytes(), key)?;
 
-        // Write nonce (12 bytes) + ciphertext
         let mut encrypted_content = nonce;
         encrypted_content.extend_from_slice(&ciphertext);
-
-        std::fs::write(self.def_file_path(), encrypted_content)
-            .map_err(|e| RpmError::Io(e))?;
-
-        Ok(())
+        Ok(encrypted_content)
     }
 
     /// Encrypt a filename (name) and return encrypted data with nonce
@@ -111,70 +539,687 @@ impl PasswordStorage {
     }
 
     /// Save a password to a file
+    #[allow(dead_code)]
     pub fn save_password_file(&self, password: &str, key: &[u8]) -> RpmResult<String> {
-        self.ensure_passwords_dir()?;
-
-        let (ciphertext, nonce) = self.crypto.encrypt_password(password, key)?;
+        let _change = self.change("Add password");
+        let (ciphertext, nonce) = self.crypto.encrypt_password(self.cipher, password, key)?;
 
         let password_file = PasswordFile {
             encrypted_password: BASE64_STANDARD.encode(&ciphertext),
             nonce: BASE64_STANDARD.encode(&nonce),
+            encrypted_details: None,
+            details_nonce: None,
+            algorithm: self.cipher,
         };
 
         // Generate UUID for filename
         let filename = format!("{}.pwd", Uuid::new_v4());
-        let file_path = self.password_file_path(&filename);
-
-        let json_str = serde_json::to_string(&password_file)
-            .map_err(|e| RpmError::Serialization(e.into()))?;
-
-        std::fs::write(file_path, json_str)
-            .map_err(|e| RpmError::Io(e))?;
+        self.write_password_file(&filename, &password_file)?;
 
         Ok(filename)
     }
 
-    /// Load and decrypt a password from a file
-    pub fn load_password_file(&self, filename: &str, key: &[u8]) -> RpmResult<String> {
+    /// Read a raw (still encrypted) password file, `None` if it doesn't exist yet
+    fn read_password_file(&self, filename: &str) -> RpmResult<Option<PasswordFile>> {
         let file_path = self.password_file_path(filename);
+        if !file_path.exists() {
+            return Ok(None);
+        }
 
         let json_str = std::fs::read_to_string(&file_path)
-            .map_err(|e| RpmError::Io(e))?;
+            .map_err(RpmError::Io)?;
 
         let password_file: PasswordFile = serde_json::from_str(&json_str)
-            .map_err(|e| RpmError::Serialization(e.into()))?;
+            .map_err(RpmError::Serialization)?;
+
+        Ok(Some(password_file))
+    }
+
+    /// Write a raw password file
+    fn write_password_file(&self, filename: &str, password_file: &PasswordFile) -> RpmResult<()> {
+        self.ensure_passwords_dir()?;
+
+        let json_str = serde_json::to_string(password_file)
+            .map_err(RpmError::Serialization)?;
+
+        write_atomic(&self.password_file_path(filename), json_str)
+            .map_err(RpmError::Io)?;
+
+        Ok(())
+    }
+
+    /// Load and decrypt a password from a file
+    #[instrument(level = "debug", skip_all, fields(filename = %filename))]
+    pub fn load_password_file(&self, filename: &str, key: &[u8]) -> RpmResult<String> {
+        let password_file = self.read_password_file(filename)?
+            .ok_or_else(|| RpmError::Io(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("Password file not found: {}", filename),
+            )))?;
 
         let ciphertext = BASE64_STANDARD.decode(&password_file.encrypted_password)
             .map_err(|e| RpmError::Crypto(format!("Invalid base64 in encrypted password: {}", e)))?;
         let nonce = BASE64_STANDARD.decode(&password_file.nonce)
             .map_err(|e| RpmError::Crypto(format!("Invalid base64 in nonce: {}", e)))?;
 
-        self.crypto.decrypt_password(&ciphertext, &nonce, key)
+        self.crypto.decrypt_password(password_file.algorithm, &ciphertext, &nonce, key)
     }
 
-    /// Update password in an existing file
+    /// Update password in an existing file (entry details are kept as is)
+    #[instrument(level = "debug", skip_all, fields(filename = %filename))]
     pub fn update_password_file(&self, filename: &str, password: &str, key: &[u8]) -> RpmResult<()> {
-        self.ensure_passwords_dir()?;
-
-        let (ciphertext, nonce) = self.crypto.encrypt_password(password, key)?;
+        let _change = self.change("Update password");
+        let existing = self.read_password_file(filename)?;
+        // The details are kept as they are, so the password keeps the file's cipher
+        let algorithm = existing.as_ref().map_or(self.cipher, |f| f.algorithm);
+        let (ciphertext, nonce) = self.crypto.encrypt_password(algorithm, password, key)?;
 
         let password_file = PasswordFile {
             encrypted_password: BASE64_STANDARD.encode(&ciphertext),
             nonce: BASE64_STANDARD.encode(&nonce),
+            encrypted_details: existing.as_ref().and_then(|f| f.encrypted_details.clone()),
+            details_nonce: existing.and_then(|f| f.details_nonce),
+            algorithm,
         };
 
-        let file_path = self.password_file_path(filename);
+        self.write_password_file(filename, &password_file)?;
+
+        // Keep the reuse fingerprint in the def file in sync with the new password
+        let fingerprint = self.crypto.password_fingerprint(password, key)?;
+        let mut def_file = self.load_def_file(key)?;
+        if let Some(entry) = def_file.entries.iter_mut().find(|e| e.encrypted_filename == filename) {
+            entry.password_fingerprint = Some(fingerprint);
+            self.save_def_file(&def_file, key)?;
+        }
+
+        self.emit(VaultEvent::EntryChanged { id: filename.to_string() });
+        Ok(())
+    }
+
+    /// Load and decrypt the additional fields (username, URL, notes) of an entry.
+    /// Entries saved before details existed return empty details.
+    #[instrument(level = "debug", skip_all, fields(filename = %filename))]
+    pub fn load_entry_details(&self, filename: &str, key: &[u8]) -> RpmResult<EntryDetails> {
+        let Some(password_file) = self.read_password_file(filename)? else {
+            return Ok(EntryDetails::default());
+        };
+        let (Some(encrypted_details), Some(details_nonce)) = (password_file.encrypted_details, password_file.details_nonce) else {
+            return Ok(EntryDetails::default());
+        };
 
-        let json_str = serde_json::to_string(&password_file)
-            .map_err(|e| RpmError::Serialization(e.into()))?;
+        let ciphertext = BASE64_STANDARD.decode(&encrypted_details)
+            .map_err(|e| RpmError::Crypto(format!("Invalid base64 in encrypted details: {}", e)))?;
+        let nonce = BASE64_STANDARD.decode(&details_nonce)
+            .map_err(|e| RpmError::Crypto(format!("Invalid base64 in nonce: {}", e)))?;
 
-        std::fs::write(file_path, json_str)
-            .map_err(|e| RpmError::Io(e))?;
+        let mut plaintext = self.crypto.decrypt_data_with(password_file.algorithm, &ciphertext, &nonce, key)?;
+        let details = serde_json::from_slice(&plaintext)
+            .map_err(RpmError::Serialization);
+        plaintext.zeroize();
+        details
+    }
 
+    /// Encrypt and store the additional fields of an entry (the password is kept as is)
+    #[instrument(level = "debug", skip_all, fields(filename = %filename))]
+    pub fn update_entry_details(&self, filename: &str, details: &EntryDetails, key: &[u8]) -> RpmResult<()> {
+        let _change = self.change("Update entry details");
+        self.write_entry_details(filename, details, key)?;
+        self.emit(VaultEvent::EntryChanged { id: filename.to_string() });
+        Ok(())
+    }
+
+    /// Store entry details without announcing a change, for bookkeeping nobody else shows
+    fn write_entry_details(&self, filename: &str, details: &EntryDetails, key: &[u8]) -> RpmResult<()> {
+        let mut password_file = match self.read_password_file(filename)? {
+            Some(password_file) => password_file,
+            None => {
+                // No password saved yet - store an empty one so the file is complete
+                let (ciphertext, nonce) = self.crypto.encrypt_password(self.cipher, "", key)?;
+                PasswordFile {
+                    encrypted_password: BASE64_STANDARD.encode(&ciphertext),
+                    nonce: BASE64_STANDARD.encode(&nonce),
+                    encrypted_details: None,
+                    details_nonce: None,
+                    algorithm: self.cipher,
+                }
+            }
+        };
+        let (encrypted_details, details_nonce) = self.encrypt_details(password_file.algorithm, details, key)?;
+        password_file.encrypted_details = Some(encrypted_details);
+        password_file.details_nonce = Some(details_nonce);
+
+        self.write_password_file(filename, &password_file)
+    }
+
+    /// Encrypt entry details with `cipher`, returning base64 ciphertext and nonce
+    fn encrypt_details(&self, cipher: Cipher, details: &EntryDetails, key: &[u8]) -> RpmResult<(String, String)> {
+        let mut json = serde_json::to_vec(details)
+            .map_err(RpmError::Serialization)?;
+        let encrypted = self.crypto.encrypt_data_with(cipher, &json, key);
+        json.zeroize();
+        let (ciphertext, nonce) = encrypted?;
+        Ok((BASE64_STANDARD.encode(&ciphertext), BASE64_STANDARD.encode(&nonce)))
+    }
+
+    /// Flag or unflag an entry for the rotation workflow
+    #[instrument(level = "info", skip_all, fields(filename = %filename, needs_rotation))]
+    pub fn set_needs_rotation(&self, filename: &str, needs_rotation: bool, key: &[u8]) -> RpmResult<()> {
+        let _change = self.change("Flag entry for rotation");
+        let mut details = self.load_entry_details(filename, key)?;
+        details.needs_rotation = needs_rotation;
+        self.write_entry_details(filename, &details, key)
+    }
+
+    /// Record that the password of an entry was just copied
+    #[instrument(level = "debug", skip_all, fields(filename = %filename))]
+    pub fn mark_used(&self, filename: &str, key: &[u8]) -> RpmResult<()> {
+        let _change = self.change("Record password use");
+        let mut details = self.load_entry_details(filename, key)?;
+        details.timestamps.last_used_at = Some(Utc::now());
+        self.write_entry_details(filename, &details, key)
+    }
+
+    /// Replace the password of an entry, keeping the old one in its history
+    /// and clearing the rotation flag
+    #[instrument(level = "info", skip_all, fields(filename = %filename))]
+    pub fn rotate_password(&self, filename: &str, new_password: &str, key: &[u8]) -> RpmResult<()> {
+        let _change = self.change("Rotate password");
+        let old_password = self.load_password_file(filename, key)?;
+        let mut details = self.load_entry_details(filename, key)?;
+        if !old_password.is_empty() {
+            details.password_history.push(PasswordHistoryItem {
+                password: old_password,
+                changed_at: Utc::now(),
+            });
+        }
+        details.needs_rotation = false;
+        details.timestamps.updated_at = Some(Utc::now());
+
+        self.update_password_file(filename, new_password, key)?;
+        let result = self.update_entry_details(filename, &details, key);
+        for item in details.password_history.iter_mut() {
+            item.password.zeroize();
+        }
+        result
+    }
+
+    /// Remove orphaned `.pwd` files and password history older than `history_retention_days`
+    /// (0 = keep history forever). With `dry_run` nothing is changed, only reported.
+    #[instrument(level = "info", skip_all, fields(history_retention_days, dry_run, orphaned_files, expired_history))]
+    pub fn compact(&self, history_retention_days: u64, dry_run: bool, key: &[u8]) -> RpmResult<CompactionReport> {
+        let _change = self.change("Compact vault");
+        let mut report = CompactionReport::default();
+        if !self.passwords_dir.exists() {
+            return Ok(report);
+        }
+
+        let def_file = self.load_def_file(key)?;
+        let trash_file = self.load_trash_file(key)?;
+        let referenced: HashSet<&str> = def_file
+            .entries
+            .iter()
+            .chain(trash_file.entries.iter().map(|t| &t.entry))
+            .map(|e| e.encrypted_filename.as_str())
+            .collect();
+
+        // Orphaned password files
+        for dir_entry in std::fs::read_dir(&self.passwords_dir).map_err(RpmError::Io)? {
+            let dir_entry = dir_entry.map_err(RpmError::Io)?;
+            let filename = dir_entry.file_name().to_string_lossy().to_string();
+            if !filename.ends_with(".pwd") || referenced.contains(filename.as_str()) {
+                continue;
+            }
+            report.reclaimed_bytes += dir_entry.metadata().map(|m| m.len()).unwrap_or(0);
+            if !dry_run {
+                std::fs::remove_file(dir_entry.path()).map_err(RpmError::Io)?;
+            }
+            report.orphaned_files.push(filename);
+        }
+        report.orphaned_files.sort();
+        if !dry_run {
+            tombstones::record_deletions(&self.passwords_dir, &report.orphaned_files)?;
+        }
+
+        // Expired password history
+        if history_retention_days > 0 {
+            let cutoff = Utc::now() - chrono::Duration::days(history_retention_days as i64);
+            for entry in &def_file.entries {
+                let mut details = self.load_entry_details(&entry.encrypted_filename, key)?;
+                let before = details.password_history.len();
+                let size_before = encrypted_details_len(&details);
+                details.password_history.retain_mut(|item| {
+                    if item.changed_at < cutoff {
+                        item.password.zeroize();
+                        false
+                    } else {
+                        true
+                    }
+                });
+                let expired = before - details.password_history.len();
+                if expired > 0 {
+                    report.expired_history += expired;
+                    report.reclaimed_bytes += size_before.saturating_sub(encrypted_details_len(&details));
+                    if !dry_run {
+                        self.update_entry_details(&entry.encrypted_filename, &details, key)?;
+                    }
+                }
+                for item in details.password_history.iter_mut() {
+                    item.password.zeroize();
+                }
+            }
+        }
+
+        Span::current().record("orphaned_files", report.orphaned_files.len());
+        Span::current().record("expired_history", report.expired_history);
+        Ok(report)
+    }
+
+    /// Try to decrypt every index file, entry name and password file, and compare the
+    /// entries with the password files on disk. Nothing is changed
+    #[instrument(level = "info", skip_all, fields(entries_checked, issues))]
+    pub fn verify(&self, key: &[u8]) -> RpmResult<IntegrityReport> {
+        let mut report = IntegrityReport::default();
+        if !self.passwords_dir.exists() {
+            return Ok(report);
+        }
+
+        let unreadable = |file: &str, e: RpmError| IntegrityIssue::UnreadableIndex { file: file.to_string(), error: e.to_string() };
+        let def_file = self.load_def_file(key).map_err(|e| unreadable("def", e));
+        let trash_file = self.load_trash_file(key).map_err(|e| unreadable("trash", e));
+        if let Err(issue) = self.load_audit_log(key) {
+            report.issues.push(unreadable("audit", issue));
+        }
+
+        let mut referenced = HashSet::new();
+        let entries = def_file
+            .iter()
+            .flat_map(|def_file| def_file.entries.iter())
+            .chain(trash_file.iter().flat_map(|trash_file| trash_file.entries.iter().map(|t| &t.entry)));
+        for entry in entries {
+            report.entries_checked += 1;
+            referenced.insert(entry.encrypted_filename.clone());
+            match self.decrypt_filename(&entry.encrypted_name, &entry.nonce, key) {
+                Ok(mut name) => name.zeroize(),
+                Err(_) => report.issues.push(IntegrityIssue::UndecryptableName { filename: entry.encrypted_filename.clone() }),
+            }
+            if let Err(issue) = self.verify_password_file(&entry.encrypted_filename, key) {
+                report.issues.push(issue);
+            }
+        }
+
+        // Without both indexes every file would look unreferenced
+        let indexes_read = def_file.is_ok() && trash_file.is_ok();
+        report.issues.extend(def_file.err());
+        report.issues.extend(trash_file.err());
+        if indexes_read {
+            let mut unreferenced = Vec::new();
+            for dir_entry in std::fs::read_dir(&self.passwords_dir).map_err(RpmError::Io)? {
+                let filename = dir_entry.map_err(RpmError::Io)?.file_name().to_string_lossy().to_string();
+                if filename.ends_with(".pwd") && !referenced.contains(&filename) {
+                    unreferenced.push(IntegrityIssue::UnreferencedFile { filename });
+                }
+            }
+            unreferenced.sort_by(|a, b| a.file().cmp(b.file()));
+            report.issues.extend(unreferenced);
+        }
+
+        Span::current().record("entries_checked", report.entries_checked);
+        Span::current().record("issues", report.issues.len());
+        Ok(report)
+    }
+
+    /// Decrypt the password and the details of one password file, discarding them
+    fn verify_password_file(&self, filename: &str, key: &[u8]) -> Result<(), IntegrityIssue> {
+        let corrupted = |error: String| IntegrityIssue::CorruptedFile { filename: filename.to_string(), error };
+        let undecryptable = |_| IntegrityIssue::UndecryptableFile { filename: filename.to_string() };
+        let json = match std::fs::read_to_string(self.password_file_path(filename)) {
+            Ok(json) => json,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Err(IntegrityIssue::MissingFile { filename: filename.to_string() })
+            }
+            Err(e) => return Err(corrupted(e.to_string())),
+        };
+        let password_file: PasswordFile = serde_json::from_str(&json).map_err(|e| corrupted(e.to_string()))?;
+        let decode = |text: &str| BASE64_STANDARD.decode(text).map_err(|e| corrupted(format!("invalid base64: {}", e)));
+
+        let ciphertext = decode(&password_file.encrypted_password)?;
+        let nonce = decode(&password_file.nonce)?;
+        let mut password = self.crypto
+            .decrypt_password(password_file.algorithm, &ciphertext, &nonce, key)
+            .map_err(undecryptable)?;
+        password.zeroize();
+
+        match (password_file.encrypted_details, password_file.details_nonce) {
+            (Some(encrypted_details), Some(details_nonce)) => {
+                let ciphertext = decode(&encrypted_details)?;
+                let nonce = decode(&details_nonce)?;
+                let mut plaintext = self.crypto
+                    .decrypt_data_with(password_file.algorithm, &ciphertext, &nonce, key)
+                    .map_err(undecryptable)?;
+                let details = serde_json::from_slice::<EntryDetails>(&plaintext);
+                plaintext.zeroize();
+                details.map(drop).map_err(|e| corrupted(format!("invalid entry details: {}", e)))
+            }
+            (None, None) => Ok(()),
+            _ => Err(corrupted("entry details without a nonce".to_string())),
+        }
+    }
+
+    /// Password files no entry refers to, then def file entries without a password file
+    #[instrument(level = "info", skip_all, fields(orphans))]
+    pub fn find_orphans(&self, key: &[u8]) -> RpmResult<Vec<Orphan>> {
+        if !self.passwords_dir.exists() {
+            return Ok(Vec::new());
+        }
+        let def_file = self.load_def_file(key)?;
+        let trash_file = self.load_trash_file(key)?;
+        let referenced: HashSet<&str> = def_file
+            .entries
+            .iter()
+            .chain(trash_file.entries.iter().map(|t| &t.entry))
+            .map(|e| e.encrypted_filename.as_str())
+            .collect();
+
+        let mut orphans = Vec::new();
+        for dir_entry in std::fs::read_dir(&self.passwords_dir).map_err(RpmError::Io)? {
+            let filename = dir_entry.map_err(RpmError::Io)?.file_name().to_string_lossy().to_string();
+            if filename.ends_with(".pwd") && !referenced.contains(filename.as_str()) {
+                orphans.push(Orphan::File { filename });
+            }
+        }
+        orphans.sort_by(|a, b| a.filename().cmp(b.filename()));
+        orphans.extend(
+            def_file
+                .entries
+                .iter()
+                .filter(|e| !self.password_file_path(&e.encrypted_filename).exists())
+                .map(|e| Orphan::Entry { filename: e.encrypted_filename.clone() }),
+        );
+
+        Span::current().record("orphans", orphans.len());
+        Ok(orphans)
+    }
+
+    /// Repair an orphan found by `find_orphans`. Only files can be re-linked, and only
+    /// when `key` decrypts them; files moved to quarantine or deleted are recorded as
+    /// deleted, so sync doesn't bring them back
+    #[instrument(level = "info", skip_all, fields(orphan = ?orphan, repair = ?repair))]
+    pub fn repair_orphan(&self, orphan: &Orphan, repair: &OrphanRepair, key: &[u8]) -> RpmResult<()> {
+        let _change = self.change("Repair orphan");
+        match (orphan, repair) {
+            (Orphan::File { filename }, OrphanRepair::Relink { name }) => {
+                let mut password = self.load_password_file(filename, key)?;
+                let fingerprint = self.crypto.password_fingerprint(&password, key);
+                password.zeroize();
+                let (encrypted_name, nonce) = self.encrypt_filename(name, key)?;
+                let mut def_file = self.load_def_file(key)?;
+                def_file.entries.push(DefFileEntry {
+                    encrypted_filename: filename.clone(),
+                    encrypted_name,
+                    nonce,
+                    password_fingerprint: Some(fingerprint?),
+                    tags: Vec::new(),
+                    folder: None,
+                    archived: false,
+                });
+                self.save_def_file(&def_file, key)?;
+                self.emit(VaultEvent::EntryAdded { id: filename.clone() });
+            }
+            (Orphan::Entry { .. }, OrphanRepair::Relink { .. }) => {
+                return Err(RpmError::InvalidInput("An entry without a password file has no file to re-link".to_string()));
+            }
+            (Orphan::File { filename }, OrphanRepair::Quarantine) => {
+                let quarantine = self.passwords_dir.join(QUARANTINE_DIR);
+                std::fs::create_dir_all(&quarantine).map_err(RpmError::Io)?;
+                std::fs::rename(self.password_file_path(filename), quarantine.join(filename)).map_err(RpmError::Io)?;
+                tombstones::record_deletions(&self.passwords_dir, &[filename])?;
+            }
+            (Orphan::File { filename }, OrphanRepair::Delete) => {
+                std::fs::remove_file(self.password_file_path(filename)).map_err(RpmError::Io)?;
+                tombstones::record_deletions(&self.passwords_dir, &[filename])?;
+            }
+            (Orphan::Entry { filename }, OrphanRepair::Quarantine | OrphanRepair::Delete) => {
+                let mut def_file = self.load_def_file(key)?;
+                let Some(index) = def_file.entries.iter().position(|e| e.encrypted_filename == *filename) else {
+                    return Ok(());
+                };
+                let entry = def_file.entries.remove(index);
+                if *repair == OrphanRepair::Quarantine {
+                    // The name stays encrypted, as it was in the def file
+                    let quarantine = self.passwords_dir.join(QUARANTINE_DIR);
+                    std::fs::create_dir_all(&quarantine).map_err(RpmError::Io)?;
+                    let json = serde_json::to_string(&entry).map_err(RpmError::Serialization)?;
+                    write_atomic(&quarantine.join(format!("{}.entry", filename)), json).map_err(RpmError::Io)?;
+                }
+                self.save_def_file(&def_file, key)?;
+                self.emit(VaultEvent::EntryDeleted { id: filename.clone() });
+            }
+        }
+        Ok(())
+    }
+
+    /// Rotate the encryption key after a suspected compromise: derive a new key from
+    /// the same master password with a fresh salt and the vault's KDF, re-encrypt every
+    /// file under it, and store the new salt in `dir_config`. The directory config is
+    /// staged and renamed with the vault files, so the salt and the files change together.
+    #[instrument(level = "info", skip_all)]
+    pub fn rotate_key(&self, old_key: &[u8], master_password: &str, dir_config: &mut DirectoryConfig) -> RpmResult<SecureKey> {
+        let verified = match dir_config.master_password_hash {
+            Some(ref hash) => self.crypto.verify_password(master_password, hash)?,
+            None => false,
+        };
+        if !verified {
+            return Err(RpmError::AuthenticationFailed);
+        }
+
+        let _change = self.change("Rotate encryption key");
+        let mut salt = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut salt);
+        let new_key = SecureKey::new(dir_config.kdf.kdf().derive_key(master_password, &salt)?);
+        let mut rotated = dir_config.clone();
+        rotated.encryption_key_salt = Some(BASE64_STANDARD_NO_PAD.encode(salt));
+        // Hashes from before the vault's parameters were used for them get them now
+        rotated.master_password_hash = Some(self.crypto.hash_password(master_password, &dir_config.kdf.argon2())?);
+        salt.zeroize();
+
+        self.reencrypt(old_key, new_key.as_slice(), &rotated)?;
+        *dir_config = rotated;
+        self.record_audit_event(
+            AuditEvent {
+                action: AuditAction::KeyRotated,
+                at: Utc::now(),
+                user: current_user(),
+                details: String::new(),
+            },
+            new_key.as_slice(),
+        )?;
+        Ok(new_key)
+    }
+
+    /// Change the master password: derive a new key from `new_password` with a fresh
+    /// salt and `argon2`, re-encrypt every file under it, and store the new salt, hash,
+    /// KDF and `hint` in `dir_config`. Vaults on scrypt or PBKDF2 move to Argon2id.
+    /// The caller checks the old password; the directory config changes together
+    /// with the files, as in `rotate_key`.
+    #[instrument(level = "info", skip_all)]
+    pub fn change_master_password(
+        &self,
+        old_key: &[u8],
+        new_password: &str,
+        argon2: Argon2id,
+        hint: Option<String>,
+        dir_config: &mut DirectoryConfig,
+    ) -> RpmResult<SecureKey> {
+        let _change = self.change("Change master password");
+        let mut salt = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut salt);
+        let new_key = SecureKey::new(argon2.derive_key(new_password, &salt)?);
+        let mut changed = dir_config.clone();
+        changed.master_password_hash = Some(self.crypto.hash_password(new_password, &argon2)?);
+        changed.encryption_key_salt = Some(BASE64_STANDARD_NO_PAD.encode(salt));
+        changed.kdf = KdfParams::Argon2id(argon2);
+        changed.password_hint = hint;
+        salt.zeroize();
+
+        self.reencrypt(old_key, new_key.as_slice(), &changed)?;
+        *dir_config = changed;
+        Ok(new_key)
+    }
+
+    /// Re-encrypt the whole vault (def file, trash, audit log, UI state and every password file)
+    /// from `old_key` to `new_key`, with `dir_config` as the new directory config.
+    /// Everything is first written next to the originals as `*.rekey` files, so a failure
+    /// while staging leaves the vault untouched. Then `REKEY_MARKER` commits the change
+    /// and the files are renamed over the originals, the def file and the directory config
+    /// last; if that is interrupted, `finish_interrupted_rekey` completes it on the next open.
+    #[instrument(level = "info", skip_all, fields(entries))]
+    fn reencrypt(&self, old_key: &[u8], new_key: &[u8], dir_config: &DirectoryConfig) -> RpmResult<()> {
+        let _change = self.change("Re-encrypt vault");
+        let mut staged = Vec::new();
+        let staging = self
+            .stage_reencrypted(old_key, new_key, &mut staged)
+            .and_then(|()| self.stage_dir_config(dir_config, &mut staged));
+        if let Err(e) = staging {
+            for (staged_path, _) in &staged {
+                let _ = std::fs::remove_file(staged_path);
+            }
+            return Err(e);
+        }
+
+        Span::current().record("entries", staged.iter().filter(|(_, target)| target.extension().is_some_and(|ext| ext == "pwd")).count());
+
+        let marker = self.passwords_dir.join(REKEY_MARKER);
+        if let Err(e) = write_synced(&marker, b"").and_then(|()| sync_parent(&marker)) {
+            let _ = std::fs::remove_file(&marker);
+            for (staged_path, _) in &staged {
+                let _ = std::fs::remove_file(staged_path);
+            }
+            return Err(RpmError::Io(e));
+        }
+
+        // The def file and the directory config are staged last, so they are also renamed last.
+        // From here on the marker stays until every file is in place
+        for (staged_path, target) in staged {
+            std::fs::rename(&staged_path, &target)
+                .map_err(RpmError::Io)?;
+        }
+        sync_parent(&self.def_file_path()).map_err(RpmError::Io)?;
+        std::fs::remove_file(&marker).map_err(RpmError::Io)?;
+        sync_parent(&marker).map_err(RpmError::Io)?;
+
+        Ok(())
+    }
+
+    /// Write re-encrypted copies of all vault files, collecting (staged, target) paths
+    fn stage_reencrypted(&self, old_key: &[u8], new_key: &[u8], staged: &mut Vec<(PathBuf, PathBuf)>) -> RpmResult<()> {
+        let mut def_file = self.load_def_file(old_key)?;
+        let mut trash_file = self.load_trash_file(old_key)?;
+        let audit_log = self.load_audit_log(old_key)?;
+
+        for entry in def_file
+            .entries
+            .iter_mut()
+            .chain(trash_file.entries.iter_mut().map(|t| &mut t.entry))
+        {
+            let mut name = self.decrypt_filename(&entry.encrypted_name, &entry.nonce, old_key)?;
+            let encrypted = self.encrypt_filename(&name, new_key);
+            name.zeroize();
+            let (encrypted_name, nonce) = encrypted?;
+            entry.encrypted_name = encrypted_name;
+            entry.nonce = nonce;
+
+            let Some(password_file) = self.read_password_file(&entry.encrypted_filename)? else {
+                continue;
+            };
+
+            // Password; the file keeps its cipher
+            let algorithm = password_file.algorithm;
+            let ciphertext = BASE64_STANDARD.decode(&password_file.encrypted_password)
+                .map_err(|e| RpmError::Crypto(format!("Invalid base64 in encrypted password: {}", e)))?;
+            let nonce = BASE64_STANDARD.decode(&password_file.nonce)
+                .map_err(|e| RpmError::Crypto(format!("Invalid base64 in nonce: {}", e)))?;
+            let mut password = self.crypto.decrypt_password(algorithm, &ciphertext, &nonce, old_key)?;
+            let encrypted = self.crypto.encrypt_password(algorithm, &password, new_key);
+            let fingerprint = self.crypto.password_fingerprint(&password, new_key);
+            password.zeroize();
+            let (ciphertext, nonce) = encrypted?;
+            entry.password_fingerprint = Some(fingerprint?);
+
+            // Details
+            let (encrypted_details, details_nonce) = match (password_file.encrypted_details, password_file.details_nonce) {
+                (Some(encrypted_details), Some(details_nonce)) => {
+                    let details_ciphertext = BASE64_STANDARD.decode(&encrypted_details)
+                        .map_err(|e| RpmError::Crypto(format!("Invalid base64 in encrypted details: {}", e)))?;
+                    let details_nonce = BASE64_STANDARD.decode(&details_nonce)
+                        .map_err(|e| RpmError::Crypto(format!("Invalid base64 in nonce: {}", e)))?;
+                    let mut plaintext = self.crypto.decrypt_data_with(algorithm, &details_ciphertext, &details_nonce, old_key)?;
+                    let encrypted = self.crypto.encrypt_data_with(algorithm, &plaintext, new_key);
+                    plaintext.zeroize();
+                    let (details_ciphertext, details_nonce) = encrypted?;
+                    (Some(BASE64_STANDARD.encode(&details_ciphertext)), Some(BASE64_STANDARD.encode(&details_nonce)))
+                }
+                _ => (None, None),
+            };
+
+            let new_file = PasswordFile {
+                encrypted_password: BASE64_STANDARD.encode(&ciphertext),
+                nonce: BASE64_STANDARD.encode(&nonce),
+                encrypted_details,
+                details_nonce,
+                algorithm,
+            };
+            let json_str = serde_json::to_string(&new_file)
+                .map_err(RpmError::Serialization)?;
+
+            let target = self.password_file_path(&entry.encrypted_filename);
+            let staged_path = self.passwords_dir.join(format!("{}.rekey", entry.encrypted_filename));
+            write_synced(&staged_path, json_str.as_bytes())
+                .map_err(RpmError::Io)?;
+            staged.push((staged_path, target));
+        }
+
+        let encrypted_content = self.encrypt_def_file(&trash_file, new_key)?;
+        let staged_path = self.passwords_dir.join("trash.rekey");
+        write_synced(&staged_path, &encrypted_content)
+            .map_err(RpmError::Io)?;
+        staged.push((staged_path, self.trash_file_path()));
+
+        let encrypted_content = self.encrypt_def_file(&audit_log, new_key)?;
+        let staged_path = self.passwords_dir.join("audit.rekey");
+        write_synced(&staged_path, &encrypted_content)
+            .map_err(RpmError::Io)?;
+        staged.push((staged_path, self.audit_file_path()));
+
+        // The UI state is per device and optional: one that no longer decrypts is left to be dropped
+        if let Ok(Some(ui_state)) = self.read_encrypted_json::<UiState>(&self.ui_state_file_path(), "UI state", old_key) {
+            let encrypted_content = self.encrypt_def_file(&ui_state, new_key)?;
+            let staged_path = self.passwords_dir.join(format!("{}.rekey", UI_STATE_FILE));
+            write_synced(&staged_path, &encrypted_content)
+                .map_err(RpmError::Io)?;
+            staged.push((staged_path, self.ui_state_file_path()));
+        }
+
+        let encrypted_content = self.encrypt_def_file(&def_file, new_key)?;
+        let staged_path = self.passwords_dir.join("def.rekey");
+        write_synced(&staged_path, &encrypted_content)
+            .map_err(RpmError::Io)?;
+        staged.push((staged_path, self.def_file_path()));
+
+        Ok(())
+    }
+
+    /// Write `dir_config` next to the directory config, collecting (staged, target) paths
+    fn stage_dir_config(&self, dir_config: &DirectoryConfig, staged: &mut Vec<(PathBuf, PathBuf)>) -> RpmResult<()> {
+        let content = dir_config.to_toml().map_err(|e| RpmError::Config(e.to_string()))?;
+        let staged_path = self.passwords_dir.join(".rpm_config.rekey");
+        write_synced(&staged_path, content.as_bytes())
+            .map_err(RpmError::Io)?;
+        staged.push((staged_path, DirectoryConfig::config_path(&self.passwords_dir)));
         Ok(())
     }
 
     /// Get list of decrypted names from def file
+    #[instrument(level = "debug", skip_all, fields(entries))]
     pub fn list_decrypted_names(&self, key: &[u8]) -> RpmResult<Vec<(String, String)>> {
         let def_file = self.load_def_file(key)?;
         let mut names = Vec::new();
@@ -184,11 +1229,14 @@ impl PasswordStorage {
             names.push((entry.encrypted_filename, decrypted_name));
         }
 
+        Span::current().record("entries", names.len());
         Ok(names)
     }
 
     /// Add a new entry to def file
+    #[instrument(level = "info", skip_all, fields(filename))]
     pub fn add_entry(&self, name: &str, key: &[u8]) -> RpmResult<String> {
+        let _change = self.change("Add entry");
         let mut def_file = self.load_def_file(key)?;
 
         // Encrypt the name
@@ -201,16 +1249,65 @@ impl PasswordStorage {
             encrypted_filename: filename.clone(),
             encrypted_name,
             nonce,
+            password_fingerprint: None,
+            tags: Vec::new(),
+            folder: None,
+            archived: false,
         };
 
         def_file.entries.push(entry);
         self.save_def_file(&def_file, key)?;
+        self.emit(VaultEvent::EntryAdded { id: filename.clone() });
 
+        Span::current().record("filename", filename.as_str());
         Ok(filename)
     }
 
+    /// Add many complete entries (name, password, details) at once.
+    /// Unlike `add_entry` the def file is loaded and saved only once, so this stays
+    /// linear in the number of entries. Returns the new filenames in input order.
+    #[instrument(level = "info", skip_all, fields(entries = entries.len()))]
+    pub fn add_entries(&self, entries: &[(String, String, EntryDetails)], key: &[u8]) -> RpmResult<Vec<String>> {
+        let _change = self.change("Add entries");
+        let mut def_file = self.load_def_file(key)?;
+        let mut filenames = Vec::with_capacity(entries.len());
+
+        for (name, password, details) in entries {
+            let filename = format!("{}.pwd", Uuid::new_v4());
+            let (ciphertext, nonce) = self.crypto.encrypt_password(self.cipher, password, key)?;
+            let (encrypted_details, details_nonce) = self.encrypt_details(self.cipher, details, key)?;
+            self.write_password_file(&filename, &PasswordFile {
+                encrypted_password: BASE64_STANDARD.encode(&ciphertext),
+                nonce: BASE64_STANDARD.encode(&nonce),
+                encrypted_details: Some(encrypted_details),
+                details_nonce: Some(details_nonce),
+                algorithm: self.cipher,
+            })?;
+
+            let (encrypted_name, nonce) = self.encrypt_filename(name, key)?;
+            def_file.entries.push(DefFileEntry {
+                encrypted_filename: filename.clone(),
+                encrypted_name,
+                nonce,
+                password_fingerprint: Some(self.crypto.password_fingerprint(password, key)?),
+                tags: Vec::new(),
+                folder: None,
+                archived: false,
+            });
+            filenames.push(filename);
+        }
+
+        self.save_def_file(&def_file, key)?;
+        for filename in &filenames {
+            self.emit(VaultEvent::EntryAdded { id: filename.clone() });
+        }
+        Ok(filenames)
+    }
+
     /// Update an entry in def file (by filename)
+    #[instrument(level = "info", skip_all, fields(filename = %filename))]
     pub fn update_entry(&self, filename: &str, new_name: &str, key: &[u8]) -> RpmResult<()> {
+        let _change = self.change("Rename entry");
         let mut def_file = self.load_def_file(key)?;
 
         // Find and update the entry
@@ -224,26 +1321,240 @@ impl PasswordStorage {
         }
 
         self.save_def_file(&def_file, key)?;
+        self.emit(VaultEvent::EntryChanged { id: filename.to_string() });
         Ok(())
     }
 
-    /// Delete an entry from def file
-    pub fn delete_entry(&self, filename: &str, key: &[u8]) -> RpmResult<()> {
+    /// Tags of every entry that has any, by filename
+    pub fn list_entry_tags(&self, key: &[u8]) -> RpmResult<HashMap<String, Vec<String>>> {
+        Ok(self
+            .load_def_file(key)?
+            .entries
+            .into_iter()
+            .filter(|entry| !entry.tags.is_empty())
+            .map(|entry| (entry.encrypted_filename, entry.tags))
+            .collect())
+    }
+
+    /// Non-secret metadata of every entry for the extension API, sorted by title.
+    /// Archived entries are left out, so the extension never offers them.
+    pub fn list_entry_meta(&self, key: &[u8]) -> RpmResult<Vec<EntryMeta>> {
+        let mut entries = Vec::new();
+        for entry in self.load_def_file(key)?.entries.into_iter().filter(|entry| !entry.archived) {
+            entries.push(self.meta_of(entry, key)?);
+        }
+        entries.sort_by(|a, b| a.title.cmp(&b.title));
+        Ok(entries)
+    }
+
+    /// Non-secret metadata of one entry, archived or not; `None` if there is no such entry
+    pub fn entry_meta(&self, filename: &str, key: &[u8]) -> RpmResult<Option<EntryMeta>> {
+        let def_file = self.load_def_file(key)?;
+        match def_file.entries.into_iter().find(|entry| entry.encrypted_filename == filename) {
+            Some(entry) => self.meta_of(entry, key).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn meta_of(&self, entry: DefFileEntry, key: &[u8]) -> RpmResult<EntryMeta> {
+        let title = self.decrypt_filename(&entry.encrypted_name, &entry.nonce, key)?;
+        let details = self.load_entry_details(&entry.encrypted_filename, key)?;
+        let icon_url = details
+            .url
+            .as_deref()
+            .and_then(site_of)
+            .map(|site| format!("https://{}/favicon.ico", site));
+        Ok(EntryMeta {
+            id: entry.encrypted_filename,
+            title,
+            username: details.username,
+            url: details.url,
+            icon_url,
+            tags: entry.tags,
+            folder: entry.folder,
+            updated_at: details.timestamps.updated_at,
+        })
+    }
+
+    /// Replace the tags of an entry. Tags are trimmed, a leading `#` is dropped
+    /// and duplicates (ignoring case) are removed.
+    #[instrument(level = "info", skip_all, fields(filename = %filename))]
+    pub fn set_entry_tags(&self, filename: &str, tags: &[String], key: &[u8]) -> RpmResult<()> {
+        let mut normalized: Vec<String> = Vec::new();
+        for tag in tags {
+            let tag = tag.trim().trim_start_matches('#').trim();
+            if !tag.is_empty() && !normalized.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                normalized.push(tag.to_string());
+            }
+        }
+
         let mut def_file = self.load_def_file(key)?;
-        def_file.entries.retain(|e| e.encrypted_filename != filename);
+        let Some(entry) = def_file.entries.iter_mut().find(|e| e.encrypted_filename == filename) else {
+            return Err(RpmError::InvalidInput(format!("Entry {} not found", filename)));
+        };
+        if entry.tags == normalized {
+            return Ok(());
+        }
+        let _change = self.change("Update entry tags");
+        entry.tags = normalized;
         self.save_def_file(&def_file, key)?;
+        self.emit(VaultEvent::EntryChanged { id: filename.to_string() });
+        Ok(())
+    }
 
-        // Also delete the password file
-        let file_path = self.password_file_path(filename);
-        if file_path.exists() {
-            std::fs::remove_file(file_path)
-                .map_err(|e| RpmError::Io(e))?;
+    /// Folder of every entry that is not at the top level, by filename
+    pub fn list_entry_folders(&self, key: &[u8]) -> RpmResult<HashMap<String, String>> {
+        Ok(self
+            .load_def_file(key)?
+            .entries
+            .into_iter()
+            .filter_map(|entry| entry.folder.map(|folder| (entry.encrypted_filename, folder)))
+            .collect())
+    }
+
+    /// Move an entry to a folder (see `normalize_folder`); an empty path is the top level
+    #[instrument(level = "info", skip_all, fields(filename = %filename))]
+    pub fn set_entry_folder(&self, filename: &str, folder: &str, key: &[u8]) -> RpmResult<()> {
+        let folder = normalize_folder(folder);
+        let folder = (!folder.is_empty()).then_some(folder);
+
+        let mut def_file = self.load_def_file(key)?;
+        let Some(entry) = def_file.entries.iter_mut().find(|e| e.encrypted_filename == filename) else {
+            return Err(RpmError::InvalidInput(format!("Entry {} not found", filename)));
+        };
+        if entry.folder == folder {
+            return Ok(());
         }
+        let _change = self.change("Move entry to folder");
+        entry.folder = folder;
+        self.save_def_file(&def_file, key)?;
+        self.emit(VaultEvent::EntryChanged { id: filename.to_string() });
+        Ok(())
+    }
+
+    /// Filenames of the archived entries
+    pub fn list_archived_entries(&self, key: &[u8]) -> RpmResult<HashSet<String>> {
+        Ok(self
+            .load_def_file(key)?
+            .entries
+            .into_iter()
+            .filter(|entry| entry.archived)
+            .map(|entry| entry.encrypted_filename)
+            .collect())
+    }
 
+    /// Archive an entry or bring it back from the archive
+    #[instrument(level = "info", skip_all, fields(filename = %filename))]
+    pub fn set_entry_archived(&self, filename: &str, archived: bool, key: &[u8]) -> RpmResult<()> {
+        let mut def_file = self.load_def_file(key)?;
+        let Some(entry) = def_file.entries.iter_mut().find(|e| e.encrypted_filename == filename) else {
+            return Err(RpmError::InvalidInput(format!("Entry {} not found", filename)));
+        };
+        if entry.archived == archived {
+            return Ok(());
+        }
+        let _change = self.change(if archived { "Archive entry" } else { "Unarchive entry" });
+        entry.archived = archived;
+        self.save_def_file(&def_file, key)?;
+        self.emit(VaultEvent::EntryChanged { id: filename.to_string() });
         Ok(())
     }
 
+    /// Move an entry from the def file to the trash. The password file is kept
+    /// until the entry is purged.
+    #[instrument(level = "info", skip_all, fields(filename = %filename))]
+    pub fn delete_entry(&self, filename: &str, key: &[u8]) -> RpmResult<()> {
+        let _change = self.change("Move entry to trash");
+        let mut def_file = self.load_def_file(key)?;
+        let Some(index) = def_file.entries.iter().position(|e| e.encrypted_filename == filename) else {
+            return Ok(());
+        };
+        let entry = def_file.entries.remove(index);
+
+        // Trash first: if saving the def file fails, the entry is in both rather than lost
+        let mut trash_file = self.load_trash_file(key)?;
+        trash_file.entries.push(TrashEntry { entry, deleted_at: Utc::now() });
+        self.save_trash_file(&trash_file, key)?;
+        self.save_def_file(&def_file, key)?;
+        self.emit(VaultEvent::EntryDeleted { id: filename.to_string() });
+
+        Ok(())
+    }
+
+    /// Entries in the trash as (filename, decrypted name, deletion time), newest first
+    #[instrument(level = "debug", skip_all, fields(entries))]
+    pub fn list_trash(&self, key: &[u8]) -> RpmResult<Vec<(String, String, DateTime<Utc>)>> {
+        let trash_file = self.load_trash_file(key)?;
+        let mut entries = Vec::with_capacity(trash_file.entries.len());
+        for trashed in trash_file.entries {
+            let name = self.decrypt_filename(&trashed.entry.encrypted_name, &trashed.entry.nonce, key)?;
+            entries.push((trashed.entry.encrypted_filename, name, trashed.deleted_at));
+        }
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.2));
+
+        Span::current().record("entries", entries.len());
+        Ok(entries)
+    }
+
+    /// Move an entry from the trash back to the def file
+    #[instrument(level = "info", skip_all, fields(filename = %filename))]
+    pub fn restore_from_trash(&self, filename: &str, key: &[u8]) -> RpmResult<()> {
+        let _change = self.change("Restore entry from trash");
+        let mut trash_file = self.load_trash_file(key)?;
+        let Some(index) = trash_file.entries.iter().position(|t| t.entry.encrypted_filename == filename) else {
+            return Err(RpmError::InvalidInput(format!("Entry {} is not in the trash", filename)));
+        };
+        let trashed = trash_file.entries.remove(index);
+
+        // Def file first: if saving the trash fails, the entry is in both rather than lost
+        let mut def_file = self.load_def_file(key)?;
+        def_file.entries.push(trashed.entry);
+        self.save_def_file(&def_file, key)?;
+        self.emit(VaultEvent::EntryAdded { id: filename.to_string() });
+        self.save_trash_file(&trash_file, key)
+    }
+
+    /// Permanently delete entries from the trash together with their password files.
+    /// Returns the number of purged entries.
+    #[instrument(level = "info", skip_all, fields(purged))]
+    pub fn purge_trash(&self, filter: impl Fn(&str, DateTime<Utc>) -> bool, key: &[u8]) -> RpmResult<usize> {
+        let _change = self.change("Purge trash");
+        let mut trash_file = self.load_trash_file(key)?;
+        let (purged, kept): (Vec<TrashEntry>, Vec<TrashEntry>) = trash_file
+            .entries
+            .into_iter()
+            .partition(|t| filter(&t.entry.encrypted_filename, t.deleted_at));
+        if purged.is_empty() {
+            return Ok(0);
+        }
+        trash_file.entries = kept;
+        self.save_trash_file(&trash_file, key)?;
+
+        for trashed in &purged {
+            let file_path = self.password_file_path(&trashed.entry.encrypted_filename);
+            if file_path.exists() {
+                std::fs::remove_file(file_path)
+                    .map_err(RpmError::Io)?;
+            }
+        }
+        let purged_files: Vec<&str> = purged.iter().map(|trashed| trashed.entry.encrypted_filename.as_str()).collect();
+        tombstones::record_deletions(&self.passwords_dir, &purged_files)?;
+
+        Span::current().record("purged", purged.len());
+        Ok(purged.len())
+    }
+
+    /// Purge trash entries deleted more than `retention_days` ago (0 = keep forever)
+    pub fn purge_expired_trash(&self, retention_days: u64, key: &[u8]) -> RpmResult<usize> {
+        if retention_days == 0 {
+            return Ok(0);
+        }
+        let cutoff = Utc::now() - chrono::Duration::days(retention_days as i64);
+        self.purge_trash(|_, deleted_at| deleted_at < cutoff, key)
+    }
+
     /// Find filename by decrypted name
+    #[allow(dead_code)]
     pub fn find_filename_by_name(&self, name: &str, key: &[u8]) -> RpmResult<Option<String>> {
         let def_file = self.load_def_file(key)?;
 
@@ -256,5 +1567,109 @@ impl PasswordStorage {
 
         Ok(None)
     }
-}
 
+    /// Find entries (other than `exclude_filename`) whose password matches the given one.
+    /// Comparison is done on keyed fingerprints; returns the decrypted names of matching entries.
+    #[instrument(level = "debug", skip_all, fields(matches))]
+    pub fn find_password_reuse(&self, password: &str, exclude_filename: Option<&str>, key: &[u8]) -> RpmResult<Vec<String>> {
+        if password.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let fingerprint = self.crypto.password_fingerprint(password, key)?;
+        let mut names = Vec::new();
+
+        for (filename, name, entry_fingerprint) in self.list_fingerprints(key)? {
+            if Some(filename.as_str()) == exclude_filename {
+                continue;
+            }
+            if entry_fingerprint == fingerprint {
+                names.push(name);
+            }
+        }
+
+        Span::current().record("matches", names.len());
+        Ok(names)
+    }
+
+    /// Cluster entries that share the same password, riskiest cluster first.
+    /// Only clusters with two or more entries are returned.
+    #[instrument(level = "debug", skip_all, fields(groups))]
+    pub fn password_reuse_groups(&self, key: &[u8]) -> RpmResult<Vec<ReuseCluster>> {
+        // Entries without a password are not considered reused
+        let empty_fingerprint = self.crypto.password_fingerprint("", key)?;
+
+        let mut groups: HashMap<String, Vec<(String, String)>> = HashMap::new();
+        for (filename, name, fingerprint) in self.list_fingerprints(key)? {
+            if fingerprint != empty_fingerprint {
+                groups.entry(fingerprint).or_default().push((filename, name));
+            }
+        }
+
+        let mut clusters = Vec::new();
+        for mut group in groups.into_values().filter(|g| g.len() > 1) {
+            group.sort_by(|a, b| a.1.cmp(&b.1));
+
+            // All entries share the password, so one of them is enough to estimate it
+            let mut password = self.load_password_file(&group[0].0, key)?;
+            let entropy_bits = estimate_entropy_bits(&password);
+            password.zeroize();
+
+            let mut sites = Vec::new();
+            for (filename, name) in &group {
+                let url = self.load_entry_details(filename, key)?.url;
+                let site = url.as_deref().and_then(site_of).unwrap_or_else(|| name.clone());
+                if !sites.contains(&site) {
+                    sites.push(site);
+                }
+            }
+            sites.sort();
+
+            let entries = group.into_iter().map(|(_, name)| name).collect();
+            clusters.push(ReuseCluster::new(entries, sites, entropy_bits));
+        }
+        clusters.sort_by(|a, b| {
+            b.risk_score
+                .cmp(&a.risk_score)
+                .then_with(|| b.entries.len().cmp(&a.entries.len()))
+                .then_with(|| a.entries.cmp(&b.entries))
+        });
+
+        Span::current().record("groups", clusters.len());
+        Ok(clusters)
+    }
+
+    /// List (filename, decrypted name, password fingerprint) for every entry.
+    /// Entries created before fingerprints existed are fingerprinted from their
+    /// password file and the def file is updated so this only happens once.
+    fn list_fingerprints(&self, key: &[u8]) -> RpmResult<Vec<(String, String, String)>> {
+        let mut def_file = self.load_def_file(key)?;
+        let mut result = Vec::new();
+        let mut backfilled = false;
+
+        for entry in &mut def_file.entries {
+            let fingerprint = match &entry.password_fingerprint {
+                Some(fingerprint) => fingerprint.clone(),
+                None => {
+                    // Entry without a password file yet - nothing to compare
+                    let Ok(mut password) = self.load_password_file(&entry.encrypted_filename, key) else {
+                        continue;
+                    };
+                    let fingerprint = self.crypto.password_fingerprint(&password, key)?;
+                    password.zeroize();
+                    entry.password_fingerprint = Some(fingerprint.clone());
+                    backfilled = true;
+                    fingerprint
+                }
+            };
+            let name = self.decrypt_filename(&entry.encrypted_name, &entry.nonce, key)?;
+            result.push((entry.encrypted_filename.clone(), name, fingerprint));
+        }
+
+        if backfilled {
+            self.save_def_file(&def_file, key)?;
+        }
+
+        Ok(result)
+    }
+}
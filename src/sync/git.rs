@@ -0,0 +1,198 @@
+//! Git backend: the passwords directory is a git repository, every change made
+//! through `PasswordStorage` becomes a commit, and the history is pushed to and
+//! pulled from a remote named `origin` with the user's `git` binary.
+//!
+//! Vault files are encrypted blobs that git cannot merge, so when both sides
+//! changed the same file the merge is aborted and the conflict is reported;
+//! the user then keeps either the local or the remote version.
+
+use super::PullOutcome;
+use crate::errors::{RpmError, RpmResult};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use tracing::{info, instrument};
+
+const REMOTE: &str = "origin";
+
+/// Temporary files of storage, config and restore operations and the WebDAV
+/// sync state, never committed
+const GITIGNORE: &str = "*.tmp\n*.rekey\nrekey.pending\n*.restore\n.rpm_webdav_state\n.rpm_ui_state\nquarantine/\n";
+
+/// Tombstones are merged line by line instead of conflicting
+const GITATTRIBUTES: &str = "tombstones merge=union\n";
+
+#[derive(Debug, Clone)]
+pub struct GitSync {
+    dir: PathBuf,
+}
+
+impl GitSync {
+    pub fn new(dir: &Path) -> Self {
+        Self { dir: dir.to_path_buf() }
+    }
+
+    /// Whether the passwords directory is already a git repository
+    pub fn is_repo(&self) -> bool {
+        self.dir.join(".git").exists()
+    }
+
+    /// Make the passwords directory a repository (if it is not one yet), commit the
+    /// current vault and point `origin` at `remote`
+    #[instrument(level = "info", skip_all)]
+    pub fn init(&self, remote: Option<&str>) -> RpmResult<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        if !self.is_repo() {
+            self.run(&["init", "-q"])?;
+            std::fs::write(self.dir.join(".gitignore"), GITIGNORE)?;
+            // Commits must not fail just because git has no global identity
+            if self.git(&["config", "user.email"])?.stdout.is_empty() {
+                self.run(&["config", "user.name", "RPM"])?;
+                self.run(&["config", "user.email", "rpm@localhost"])?;
+            }
+        }
+        self.ensure_attributes()?;
+        if let Some(remote) = remote {
+            self.set_remote(remote)?;
+        }
+        self.commit_all("Initialize vault repository")?;
+        Ok(())
+    }
+
+    /// Point `origin` at `remote`
+    pub fn set_remote(&self, remote: &str) -> RpmResult<()> {
+        let remote = remote.trim();
+        if remote.is_empty() {
+            return Err(RpmError::Sync("Remote URL is empty".to_string()));
+        }
+        if self.git(&["remote", "get-url", REMOTE])?.status.success() {
+            self.run(&["remote", "set-url", REMOTE, remote])?;
+        } else {
+            self.run(&["remote", "add", REMOTE, remote])?;
+        }
+        Ok(())
+    }
+
+    /// Stage everything and commit it. Returns `false` if there was nothing to commit.
+    #[instrument(level = "debug", skip_all)]
+    pub fn commit_all(&self, message: &str) -> RpmResult<bool> {
+        self.run(&["add", "-A"])?;
+        if self.git(&["diff", "--cached", "--quiet"])?.status.success() {
+            return Ok(false);
+        }
+        self.run(&["commit", "-q", "--no-gpg-sign", "-m", message])?;
+        Ok(true)
+    }
+
+    /// Fetch `origin` and merge its branch. On conflicting changes the merge is
+    /// aborted, so the local vault stays exactly as it was.
+    #[instrument(level = "info", skip_all)]
+    pub fn pull(&self) -> RpmResult<PullOutcome> {
+        self.ensure_attributes()?;
+        self.commit_all("Local changes")?;
+        self.run(&["fetch", "-q", REMOTE])?;
+        let upstream = self.upstream()?;
+        if !self.git(&["rev-parse", "--verify", "-q", &upstream])?.status.success() {
+            // The remote has no vault yet
+            return Ok(PullOutcome::UpToDate);
+        }
+
+        if !self.git(&["rev-parse", "--verify", "-q", "HEAD"])?.status.success() {
+            // Nothing local yet: start from the remote history
+            self.run(&["reset", "-q", "--hard", &upstream])?;
+            return Ok(PullOutcome::Updated);
+        }
+
+        let before = self.run(&["rev-parse", "HEAD"])?;
+        let merge = self.git(&["merge", "-q", "--no-edit", "--no-gpg-sign", "--allow-unrelated-histories", &upstream])?;
+        if !merge.status.success() {
+            let conflicts: Vec<String> = self
+                .run(&["diff", "--name-only", "--diff-filter=U"])?
+                .lines()
+                .map(str::to_string)
+                .collect();
+            let _ = self.git(&["merge", "--abort"]);
+            if conflicts.is_empty() {
+                return Err(command_error("merge", &merge));
+            }
+            info!(conflicts = conflicts.len(), "Pull stopped by conflicting changes");
+            return Ok(PullOutcome::Conflict(conflicts));
+        }
+        if self.run(&["rev-parse", "HEAD"])? == before {
+            Ok(PullOutcome::UpToDate)
+        } else {
+            Ok(PullOutcome::Updated)
+        }
+    }
+
+    /// Push the current branch to `origin`
+    #[instrument(level = "info", skip_all)]
+    pub fn push(&self) -> RpmResult<()> {
+        self.commit_all("Local changes")?;
+        self.run(&["push", "-q", "-u", REMOTE, "HEAD"]).map(|_| ())
+    }
+
+    /// Resolve a conflict by keeping the local vault: record a merge that takes
+    /// every local file, so the next push succeeds
+    #[instrument(level = "info", skip_all)]
+    pub fn keep_local(&self) -> RpmResult<()> {
+        self.commit_all("Local changes")?;
+        self.run(&["fetch", "-q", REMOTE])?;
+        let upstream = self.upstream()?;
+        self.run(&["merge", "-q", "--no-edit", "--no-gpg-sign", "--allow-unrelated-histories", "-s", "ours", &upstream])
+            .map(|_| ())
+    }
+
+    /// Resolve a conflict by replacing the local vault with the remote one.
+    /// Local commits stay reachable through the reflog.
+    #[instrument(level = "info", skip_all)]
+    pub fn take_remote(&self) -> RpmResult<()> {
+        self.commit_all("Local changes")?;
+        self.run(&["fetch", "-q", REMOTE])?;
+        let upstream = self.upstream()?;
+        self.run(&["reset", "-q", "--hard", &upstream]).map(|_| ())
+    }
+
+    /// Write `.gitattributes` in repositories created before tombstones existed
+    fn ensure_attributes(&self) -> RpmResult<()> {
+        let path = self.dir.join(".gitattributes");
+        if !path.exists() {
+            std::fs::write(path, GITATTRIBUTES)?;
+        }
+        Ok(())
+    }
+
+    /// Remote tracking ref of the current branch, e.g. `origin/main`
+    fn upstream(&self) -> RpmResult<String> {
+        let branch = self.run(&["symbolic-ref", "--short", "HEAD"])?;
+        Ok(format!("{}/{}", REMOTE, branch))
+    }
+
+    fn git(&self, args: &[&str]) -> RpmResult<Output> {
+        Command::new("git")
+            .arg("-C")
+            .arg(&self.dir)
+            .args(args)
+            // Credentials must come from an agent or helper: a prompt would break the TUI
+            .env("GIT_TERMINAL_PROMPT", "0")
+            .stdin(Stdio::null())
+            .output()
+            .map_err(|e| RpmError::Sync(format!("Failed to run git: {}", e)))
+    }
+
+    /// Run git and return its trimmed stdout, failing on a non-zero exit status
+    fn run(&self, args: &[&str]) -> RpmResult<String> {
+        let output = self.git(args)?;
+        if !output.status.success() {
+            return Err(command_error(args[0], &output));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+}
+
+fn command_error(command: &str, output: &Output) -> RpmError {
+    RpmError::Sync(format!(
+        "git {} failed: {}",
+        command,
+        String::from_utf8_lossy(&output.stderr).trim()
+    ))
+}
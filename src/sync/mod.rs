@@ -0,0 +1,21 @@
+//! Synchronisation of the passwords directory with other machines.
+//!
+//! Only already encrypted vault files leave the machine; nothing here ever
+//! sees the vault key. Files deleted for good leave a tombstone behind, so
+//! replicas that missed the deletion drop their copies instead of restoring them.
+
+pub mod git;
+pub mod tombstones;
+pub mod webdav;
+
+/// Result of pulling remote changes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PullOutcome {
+    /// Nothing new on the remote
+    UpToDate,
+    /// Remote changes were merged into the local vault
+    Updated,
+    /// Both sides changed the same files; nothing was merged.
+    /// Holds the conflicting vault file names (`def`, `trash`, `audit` or `<uuid>.pwd`).
+    Conflict(Vec<String>),
+}
@@ -0,0 +1,116 @@
+//! Tombstones: vault files that were deleted for good, with when.
+//!
+//! A replica that missed a deletion still has the file and no way to tell it
+//! apart from one created since, so without a record it would bring the file
+//! back on the next sync. Tombstones are synced like any other vault file and
+//! merged instead of conflicting: one `<RFC 3339 time> <file name>` line per
+//! deletion, so git merges them line by line with the `union` driver.
+
+use crate::errors::{RpmError, RpmResult};
+use crate::storage::atomic::write_atomic;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Tombstones file in the passwords directory
+pub const TOMBSTONES_FILE: &str = "tombstones";
+
+/// Tombstones older than this are dropped. A replica offline for longer can
+/// bring files deleted in the meantime back
+pub const TOMBSTONE_RETENTION_DAYS: i64 = 365;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Tombstones {
+    files: BTreeMap<String, DateTime<Utc>>,
+}
+
+impl Tombstones {
+    /// Tombstones of the vault in `dir`; none if the file doesn't exist yet
+    pub fn load(dir: &Path) -> RpmResult<Self> {
+        let path = dir.join(TOMBSTONES_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// Parse the file contents. Lines git's union merge duplicated are fine:
+    /// the latest deletion of a file counts
+    pub fn parse(text: &str) -> RpmResult<Self> {
+        let mut tombstones = Self::default();
+        for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let parsed = line
+                .split_once(' ')
+                .and_then(|(at, name)| Some((DateTime::parse_from_rfc3339(at).ok()?.with_timezone(&Utc), name)));
+            let Some((at, name)) = parsed else {
+                return Err(RpmError::Sync(format!("Invalid tombstone line: {}", line)));
+            };
+            tombstones.record(name, at);
+        }
+        Ok(tombstones)
+    }
+
+    pub fn save(&self, dir: &Path) -> RpmResult<()> {
+        write_atomic(&dir.join(TOMBSTONES_FILE), self.to_string())?;
+        Ok(())
+    }
+
+    /// Note that `name` was deleted at `at`
+    pub fn record(&mut self, name: &str, at: DateTime<Utc>) {
+        let deleted_at = self.files.entry(name.to_string()).or_insert(at);
+        *deleted_at = (*deleted_at).max(at);
+    }
+
+    /// Take over the deletions another replica knows about. Returns whether anything was new
+    pub fn merge(&mut self, other: &Tombstones) -> bool {
+        let before = self.clone();
+        for (name, at) in &other.files {
+            self.record(name, *at);
+        }
+        *self != before
+    }
+
+    pub fn deleted_at(&self, name: &str) -> Option<DateTime<Utc>> {
+        self.files.get(name).copied()
+    }
+
+    /// Whether a copy of `name` last modified at `modified` predates its deletion
+    /// and should go too. Copies of unknown age are taken to be older
+    pub fn outdated(&self, name: &str, modified: Option<DateTime<Utc>>) -> bool {
+        self.deleted_at(name)
+            .is_some_and(|deleted_at| modified.is_none_or(|modified| modified <= deleted_at))
+    }
+
+    /// Drop tombstones older than `TOMBSTONE_RETENTION_DAYS`
+    pub fn prune(&mut self, now: DateTime<Utc>) {
+        let cutoff = now - Duration::days(TOMBSTONE_RETENTION_DAYS);
+        self.files.retain(|_, deleted_at| *deleted_at >= cutoff);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+}
+
+impl std::fmt::Display for Tombstones {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (name, at) in &self.files {
+            writeln!(f, "{} {}", at.to_rfc3339_opts(chrono::SecondsFormat::Millis, true), name)?;
+        }
+        Ok(())
+    }
+}
+
+/// Record that the vault files `names` in `dir` were just deleted
+pub fn record_deletions<S: AsRef<str>>(dir: &Path, names: &[S]) -> RpmResult<()> {
+    if names.is_empty() {
+        return Ok(());
+    }
+    let now = Utc::now();
+    let mut tombstones = Tombstones::load(dir)?;
+    for name in names {
+        tombstones.record(name.as_ref(), now);
+    }
+    tombstones.prune(now);
+    tombstones.save(dir)
+}
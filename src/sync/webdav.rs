@@ -0,0 +1,507 @@
+//! WebDAV backend: mirrors the encrypted vault files to a WebDAV collection
+//! (Nextcloud, ownCloud, any RFC 4918 server).
+//!
+//! What was last synced is remembered in `.rpm_webdav_state` (content hashes of
+//! local files and ETags of remote ones), so each side's changes can be told
+//! apart. A file changed on both sides is a conflict: with `last_writer_wins`
+//! the newer copy wins, with `manual` it is left alone until the user picks a side.
+//! The def file and the trash list the entries of the whole vault, so a conflict on
+//! them is always left to the user: keeping either copy drops the other replica's
+//! entries, whose files are then found as orphans and can be relinked.
+//! Tombstones are merged first, and copies of files deleted after they were last
+//! modified are removed from either side rather than restored.
+
+use crate::backup::is_vault_file;
+use crate::config::Config;
+use crate::errors::{RpmError, RpmResult};
+use crate::events::{VaultEvent, VaultEvents};
+use super::tombstones::{Tombstones, TOMBSTONES_FILE};
+use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Read;
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::sync::watch;
+use tracing::{error, info, instrument, warn};
+
+/// Sync state file in the passwords directory
+const STATE_FILE: &str = ".rpm_webdav_state";
+
+/// How often local changes are looked for (no network traffic unless there are some)
+const CHANGE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+const PROPFIND_BODY: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:"><d:prop><d:getetag/><d:getlastmodified/><d:resourcetype/></d:prop></d:propfind>"#;
+
+/// Index files listing entry files. Both replicas adding an entry conflict on them,
+/// so `last_writer_wins` does not apply to them
+const INDEX_FILES: [&str; 2] = ["def", "trash"];
+
+/// What to do with a file changed both locally and remotely since the last sync
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictStrategy {
+    /// Keep the copy modified last (a deletion without a tombstone loses against a modification);
+    /// the def file and the trash are still left to the user
+    LastWriterWins,
+    /// Leave both sides untouched and report the file
+    Manual,
+}
+
+impl ConflictStrategy {
+    pub fn parse(value: &str) -> RpmResult<Self> {
+        match value {
+            "last_writer_wins" => Ok(Self::LastWriterWins),
+            "manual" => Ok(Self::Manual),
+            _ => Err(RpmError::Config(format!(
+                "Invalid webdav_conflict_strategy '{}': expected last_writer_wins or manual",
+                value
+            ))),
+        }
+    }
+}
+
+/// Which side `resolve` keeps
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Local,
+    Remote,
+}
+
+/// What a sync run did
+#[derive(Debug, Clone, Default)]
+pub struct SyncReport {
+    pub uploaded: usize,
+    pub downloaded: usize,
+    pub deleted_remote: usize,
+    pub deleted_local: usize,
+    /// Files changed on both sides and left as they are (manual strategy, or the def file and the trash)
+    pub conflicts: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct SyncState {
+    files: BTreeMap<String, FileState>,
+    #[serde(default)]
+    conflicts: Vec<String>,
+}
+
+/// A file as it was after the last sync
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct FileState {
+    /// SHA-256 of the contents
+    hash: String,
+    /// ETag on the server, if it reported one
+    etag: Option<String>,
+}
+
+struct RemoteFile {
+    etag: Option<String>,
+    modified: Option<DateTime<Utc>>,
+}
+
+enum Action {
+    Upload,
+    Download,
+    DeleteRemote,
+    DeleteLocal,
+    /// Both sides deleted the file: only the state entry goes
+    Forget,
+}
+
+pub struct WebDavSync {
+    dir: PathBuf,
+    /// Collection URL, always ending with `/`
+    url: String,
+    authorization: Option<String>,
+    strategy: ConflictStrategy,
+    agent: ureq::Agent,
+}
+
+impl WebDavSync {
+    /// Backend for the configured server, `None` while `webdav_url` is not set
+    pub fn from_config(config: &Config) -> RpmResult<Option<Self>> {
+        let Some(url) = config.webdav_url.as_deref().map(str::trim).filter(|u| !u.is_empty()) else {
+            return Ok(None);
+        };
+        let authorization = config.webdav_username.as_deref().map(|username| {
+            let credentials = format!("{}:{}", username, config.webdav_password.as_deref().unwrap_or(""));
+            format!("Basic {}", BASE64_STANDARD.encode(credentials))
+        });
+        Ok(Some(Self {
+            dir: config.passwords_directory_path(),
+            url: format!("{}/", url.trim_end_matches('/')),
+            authorization,
+            strategy: ConflictStrategy::parse(&config.webdav_conflict_strategy)?,
+            agent: ureq::AgentBuilder::new().timeout(Duration::from_secs(60)).build(),
+        }))
+    }
+
+    /// Whether vault files changed locally since the last sync (no network access)
+    pub fn has_local_changes(&self) -> RpmResult<bool> {
+        let state = self.load_state()?;
+        let local = self.local_files()?;
+        Ok(local.len() != state.files.len()
+            || local
+                .iter()
+                .any(|(name, hash)| state.files.get(name).is_none_or(|file| &file.hash != hash)))
+    }
+
+    /// Upload local changes, download remote ones and apply the conflict strategy
+    #[instrument(level = "info", skip_all)]
+    pub fn sync(&self) -> RpmResult<SyncReport> {
+        self.run(None)
+    }
+
+    /// Settle the pending conflicts by copying every conflicting file from `side`
+    #[instrument(level = "info", skip_all)]
+    pub fn resolve(&self, side: Side) -> RpmResult<SyncReport> {
+        self.run(Some(side))
+    }
+
+    fn run(&self, resolve: Option<Side>) -> RpmResult<SyncReport> {
+        let mut state = self.load_state()?;
+        let local = self.local_files()?;
+        let remote = self.remote_files()?;
+        let mut report = SyncReport::default();
+        let tombstones = self.sync_tombstones(&mut state, &remote)?;
+
+        let names: BTreeSet<&String> = local
+            .keys()
+            .chain(remote.keys())
+            .chain(state.files.keys())
+            .filter(|name| *name != TOMBSTONES_FILE)
+            .collect();
+        let mut actions = Vec::new();
+        for name in names {
+            let last = state.files.get(name);
+            let local_hash = local.get(name);
+            let remote_file = remote.get(name);
+
+            // A replica that missed the deletion must not bring the file back
+            let local_outdated = local_hash.is_some() && tombstones.outdated(name, self.local_modified(name));
+            let remote_outdated = remote_file.is_some_and(|file| tombstones.outdated(name, file.modified));
+            if local_outdated {
+                actions.push((name.clone(), Action::DeleteLocal));
+            }
+            if remote_outdated {
+                actions.push((name.clone(), Action::DeleteRemote));
+            }
+            if local_outdated || remote_outdated {
+                continue;
+            }
+
+            let local_changed = local_hash != last.map(|f| &f.hash);
+            let remote_changed = remote_file.map(|f| &f.etag) != last.map(|f| &f.etag);
+
+            let action = match (local_changed, remote_changed) {
+                (false, false) => continue,
+                (true, false) if local_hash.is_some() => Action::Upload,
+                (true, false) if remote_file.is_some() => Action::DeleteRemote,
+                (false, true) if remote_file.is_some() => Action::Download,
+                (false, true) if local_hash.is_some() => Action::DeleteLocal,
+                _ if local_hash.is_none() && remote_file.is_none() => Action::Forget,
+                _ => {
+                    let side = match (resolve, self.strategy) {
+                        (Some(side), _) if state.conflicts.contains(name) => side,
+                        (_, ConflictStrategy::LastWriterWins) if !INDEX_FILES.contains(&name.as_str()) => {
+                            self.newer_side(name, local_hash.is_some(), remote_file)
+                        }
+                        _ => {
+                            report.conflicts.push(name.clone());
+                            continue;
+                        }
+                    };
+                    match (side, local_hash.is_some(), remote_file.is_some()) {
+                        (Side::Local, true, _) => Action::Upload,
+                        (Side::Local, false, _) => Action::DeleteRemote,
+                        (Side::Remote, _, true) => Action::Download,
+                        (Side::Remote, _, false) => Action::DeleteLocal,
+                    }
+                }
+            };
+            actions.push((name.clone(), action));
+        }
+
+        // The def file goes last, so neither side ever references entry files it lacks
+        actions.sort_by_key(|(name, _)| name == "def");
+        for (name, action) in actions {
+            match action {
+                Action::Upload => {
+                    let data = std::fs::read(self.dir.join(&name))?;
+                    let etag = self.put(&name, &data)?;
+                    state.files.insert(name, FileState { hash: hash(&data), etag });
+                    report.uploaded += 1;
+                }
+                Action::Download => {
+                    let data = self.get(&name)?;
+                    let target = self.dir.join(&name);
+                    let tmp_path = self.dir.join(format!("{}.tmp", name));
+                    std::fs::write(&tmp_path, &data)?;
+                    std::fs::rename(&tmp_path, &target)?;
+                    let etag = remote.get(&name).and_then(|f| f.etag.clone());
+                    state.files.insert(name, FileState { hash: hash(&data), etag });
+                    report.downloaded += 1;
+                }
+                Action::DeleteRemote => {
+                    self.delete(&name)?;
+                    state.files.remove(&name);
+                    report.deleted_remote += 1;
+                }
+                Action::DeleteLocal => {
+                    std::fs::remove_file(self.dir.join(&name))?;
+                    state.files.remove(&name);
+                    report.deleted_local += 1;
+                }
+                Action::Forget => {
+                    state.files.remove(&name);
+                }
+            }
+            // Saved after every file, so an interrupted sync resumes where it stopped
+            self.save_state(&state)?;
+        }
+
+        state.conflicts = report.conflicts.clone();
+        self.save_state(&state)?;
+        if !report.conflicts.is_empty() {
+            warn!(conflicts = report.conflicts.len(), "WebDAV sync left conflicting files untouched");
+        }
+        Ok(report)
+    }
+
+    /// Merge the remote tombstones into the local ones and upload the result if
+    /// either side had something the other lacked
+    fn sync_tombstones(&self, state: &mut SyncState, remote: &BTreeMap<String, RemoteFile>) -> RpmResult<Tombstones> {
+        let mut tombstones = Tombstones::load(&self.dir)?;
+        let last = state.files.get(TOMBSTONES_FILE);
+        let remote_file = remote.get(TOMBSTONES_FILE);
+        if let Some(file) = remote_file.filter(|file| last.is_none_or(|last| last.etag != file.etag)) {
+            let data = self.get(TOMBSTONES_FILE)?;
+            tombstones.merge(&Tombstones::parse(&String::from_utf8_lossy(&data))?);
+            // Stays marked as changed if it still differs from what was merged
+            let etag = file.etag.clone();
+            state.files.insert(TOMBSTONES_FILE.to_string(), FileState { hash: hash(&data), etag });
+        }
+        if tombstones.is_empty() && remote_file.is_none() {
+            return Ok(tombstones);
+        }
+
+        let data = tombstones.to_string().into_bytes();
+        if std::fs::read(self.dir.join(TOMBSTONES_FILE)).ok().as_deref() != Some(data.as_slice()) {
+            tombstones.save(&self.dir)?;
+        }
+        if state.files.get(TOMBSTONES_FILE).is_none_or(|last| last.hash != hash(&data)) || remote_file.is_none() {
+            let etag = self.put(TOMBSTONES_FILE, &data)?;
+            state.files.insert(TOMBSTONES_FILE.to_string(), FileState { hash: hash(&data), etag });
+            self.save_state(state)?;
+        }
+        Ok(tombstones)
+    }
+
+    fn local_modified(&self, name: &str) -> Option<DateTime<Utc>> {
+        std::fs::metadata(self.dir.join(name))
+            .and_then(|m| m.modified())
+            .map(DateTime::<Utc>::from)
+            .ok()
+    }
+
+    /// Side modified last; a deleted side always loses
+    fn newer_side(&self, name: &str, local_exists: bool, remote: Option<&RemoteFile>) -> Side {
+        let Some(remote) = remote else {
+            return Side::Local;
+        };
+        if !local_exists {
+            return Side::Remote;
+        }
+        match (self.local_modified(name), remote.modified) {
+            (Some(local), Some(remote)) if remote > local => Side::Remote,
+            _ => Side::Local,
+        }
+    }
+
+    /// Vault files in the passwords directory with their content hashes
+    fn local_files(&self) -> RpmResult<BTreeMap<String, String>> {
+        let mut files = BTreeMap::new();
+        if !self.dir.exists() {
+            return Ok(files);
+        }
+        for item in std::fs::read_dir(&self.dir)? {
+            let item = item?;
+            let name = item.file_name().to_string_lossy().into_owned();
+            if item.file_type()?.is_file() && is_vault_file(&name) {
+                files.insert(name, hash(&std::fs::read(item.path())?));
+            }
+        }
+        Ok(files)
+    }
+
+    /// Vault files in the remote collection (created if it does not exist yet)
+    fn remote_files(&self) -> RpmResult<BTreeMap<String, RemoteFile>> {
+        let response = match self
+            .request("PROPFIND", &self.url)
+            .set("Depth", "1")
+            .set("Content-Type", "application/xml; charset=utf-8")
+            .send_string(PROPFIND_BODY)
+        {
+            Ok(response) => response,
+            Err(ureq::Error::Status(404, _)) => {
+                self.request("MKCOL", &self.url).call().map_err(http_error)?;
+                return Ok(BTreeMap::new());
+            }
+            Err(e) => return Err(http_error(e)),
+        };
+        let body = response.into_string()?;
+        parse_multistatus(&body)
+    }
+
+    fn put(&self, name: &str, data: &[u8]) -> RpmResult<Option<String>> {
+        let response = self
+            .request("PUT", &self.file_url(name))
+            .set("Content-Type", "application/octet-stream")
+            .send_bytes(data)
+            .map_err(http_error)?;
+        Ok(response.header("ETag").map(str::to_string))
+    }
+
+    fn get(&self, name: &str) -> RpmResult<Vec<u8>> {
+        let response = self.request("GET", &self.file_url(name)).call().map_err(http_error)?;
+        let mut data = Vec::new();
+        response.into_reader().read_to_end(&mut data)?;
+        Ok(data)
+    }
+
+    fn delete(&self, name: &str) -> RpmResult<()> {
+        match self.request("DELETE", &self.file_url(name)).call() {
+            Ok(_) | Err(ureq::Error::Status(404, _)) => Ok(()),
+            Err(e) => Err(http_error(e)),
+        }
+    }
+
+    fn request(&self, method: &str, url: &str) -> ureq::Request {
+        let request = self.agent.request(method, url);
+        match self.authorization {
+            Some(ref authorization) => request.set("Authorization", authorization),
+            None => request,
+        }
+    }
+
+    fn file_url(&self, name: &str) -> String {
+        // Vault file names are UUIDs, `def` and `.rpm_config`: nothing to escape
+        format!("{}{}", self.url, name)
+    }
+
+    fn load_state(&self) -> RpmResult<SyncState> {
+        let path = self.dir.join(STATE_FILE);
+        if !path.exists() {
+            return Ok(SyncState::default());
+        }
+        Ok(serde_json::from_slice(&std::fs::read(path)?)?)
+    }
+
+    fn save_state(&self, state: &SyncState) -> RpmResult<()> {
+        let tmp_path = self.dir.join(format!("{}.tmp", STATE_FILE));
+        std::fs::write(&tmp_path, serde_json::to_vec(state)?)?;
+        std::fs::rename(&tmp_path, self.dir.join(STATE_FILE))?;
+        Ok(())
+    }
+}
+
+fn hash(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+fn http_error(e: ureq::Error) -> RpmError {
+    match e {
+        ureq::Error::Status(code, response) => RpmError::Sync(format!(
+            "WebDAV server answered {} {} for {}",
+            code,
+            response.status_text(),
+            response.get_url()
+        )),
+        ureq::Error::Transport(e) => RpmError::Sync(format!("WebDAV request failed: {}", e)),
+    }
+}
+
+/// Vault files listed in a PROPFIND multistatus response
+fn parse_multistatus(body: &str) -> RpmResult<BTreeMap<String, RemoteFile>> {
+    let document = roxmltree::Document::parse(body)
+        .map_err(|e| RpmError::Sync(format!("Invalid PROPFIND response: {}", e)))?;
+    let child_text = |node: roxmltree::Node, name: &str| {
+        node.descendants()
+            .find(|n| n.tag_name().name() == name)
+            .and_then(|n| n.text())
+            .map(str::trim)
+            .map(str::to_string)
+    };
+
+    let mut files = BTreeMap::new();
+    for response in document.descendants().filter(|n| n.tag_name().name() == "response") {
+        let Some(href) = child_text(response, "href") else {
+            continue;
+        };
+        let is_collection = response.descendants().any(|n| n.tag_name().name() == "collection");
+        let name = href.trim_end_matches('/').rsplit('/').next().unwrap_or("").to_string();
+        if is_collection || !is_vault_file(&name) {
+            continue;
+        }
+        files.insert(name, RemoteFile {
+            etag: child_text(response, "getetag"),
+            modified: child_text(response, "getlastmodified")
+                .and_then(|value| DateTime::parse_from_rfc2822(&value).ok())
+                .map(|value| value.with_timezone(&Utc)),
+        });
+    }
+    Ok(files)
+}
+
+/// Background task: syncs soon after local changes and every `webdav_pull_minutes`
+/// (0 disables periodic pulls). The config is re-read on every check; finished
+/// runs are published on `events`
+pub async fn run_scheduler(events: VaultEvents, mut shutdown_rx: watch::Receiver<()>) {
+    let mut last_pull: Option<std::time::Instant> = None;
+    loop {
+        let pull_due = last_pull;
+        let result = tokio::task::spawn_blocking(move || {
+            let config = Config::load().map_err(|e| RpmError::Config(e.to_string()))?;
+            let Some(webdav) = WebDavSync::from_config(&config)? else {
+                return Ok(None);
+            };
+            let pull_interval = Duration::from_secs(config.webdav_pull_minutes * 60);
+            let pull_due = config.webdav_pull_minutes > 0
+                && pull_due.is_none_or(|last: std::time::Instant| last.elapsed() >= pull_interval);
+            if !pull_due && !webdav.has_local_changes()? {
+                return Ok(None);
+            }
+            webdav.sync().map(|report| Some((report, pull_due)))
+        })
+        .await;
+        match result {
+            Ok(Ok(Some((report, pulled)))) => {
+                if pulled {
+                    last_pull = Some(std::time::Instant::now());
+                }
+                info!(
+                    uploaded = report.uploaded,
+                    downloaded = report.downloaded,
+                    deleted_remote = report.deleted_remote,
+                    deleted_local = report.deleted_local,
+                    "WebDAV sync finished"
+                );
+                events.publish(VaultEvent::SyncCompleted {
+                    changed: report.downloaded + report.deleted_local > 0,
+                });
+            }
+            Ok(Ok(None)) => {}
+            Ok(Err(e)) => error!("WebDAV sync failed: {}", e),
+            Err(e) => error!("WebDAV sync task failed: {}", e),
+        }
+
+        tokio::select! {
+            _ = tokio::time::sleep(CHANGE_CHECK_INTERVAL) => {}
+            _ = shutdown_rx.changed() => break,
+        }
+    }
+}
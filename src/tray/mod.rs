@@ -1,8 +1,37 @@
 use crate::errors::RpmResult;
+use crate::events::VaultEvent;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{broadcast, watch};
+
+/// Entry pinned to the tray quick menu. Only the name is kept: the password is
+/// read from the vault when the item is clicked, once the vault is unlocked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrayShortcut {
+    pub filename: String,
+    pub name: String,
+}
 
 #[derive(Clone)]
 pub struct TrayHandle {
-    // Placeholder for tray handle
+    /// Menu items, sorted by name
+    shortcuts: Arc<Mutex<Vec<TrayShortcut>>>,
+    /// Filenames of clicked shortcuts that have not been copied yet
+    copy_requests: Arc<Mutex<Vec<String>>>,
+    /// Vault summary shown when hovering the icon
+    tooltip: Arc<Mutex<String>>,
+    /// Lock badge on the icon; the vault starts locked
+    locked: Arc<Mutex<bool>>,
+}
+
+impl Default for TrayHandle {
+    fn default() -> Self {
+        Self {
+            shortcuts: Arc::default(),
+            copy_requests: Arc::default(),
+            tooltip: Arc::default(),
+            locked: Arc::new(Mutex::new(true)),
+        }
+    }
 }
 
 pub struct TrayManager {
@@ -14,11 +43,12 @@ impl TrayManager {
         // TODO: Implement system tray
         // This will vary by platform (Linux, Windows, macOS)
         Ok(Self {
-            handle: TrayHandle {},
+            handle: TrayHandle::default(),
         })
     }
 }
 
+#[allow(dead_code)]
 impl TrayHandle {
     pub fn show(&self) -> RpmResult<()> {
         // TODO: Show tray icon
@@ -29,5 +59,64 @@ impl TrayHandle {
         // TODO: Hide tray icon
         Ok(())
     }
+
+    /// Replace the pinned entries shown in the quick menu
+    pub fn set_shortcuts(&self, mut shortcuts: Vec<TrayShortcut>) {
+        shortcuts.sort_by(|a, b| a.name.cmp(&b.name));
+        *self.shortcuts.lock().unwrap() = shortcuts;
+    }
+
+    pub fn shortcuts(&self) -> Vec<TrayShortcut> {
+        self.shortcuts.lock().unwrap().clone()
+    }
+
+    /// Called when a shortcut is clicked: the TUI copies its password, asking
+    /// for the master password first if the vault is locked
+    pub fn request_copy(&self, filename: &str) {
+        self.copy_requests.lock().unwrap().push(filename.to_string());
+    }
+
+    /// Replace the tooltip, e.g. with fresh vault statistics
+    pub fn set_tooltip(&self, tooltip: String) {
+        *self.tooltip.lock().unwrap() = tooltip;
+    }
+
+    pub fn tooltip(&self) -> String {
+        self.tooltip.lock().unwrap().clone()
+    }
+
+    /// Take the pending copy requests, oldest first
+    pub fn take_copy_requests(&self) -> Vec<String> {
+        std::mem::take(&mut *self.copy_requests.lock().unwrap())
+    }
+
+    pub fn locked(&self) -> bool {
+        *self.locked.lock().unwrap()
+    }
+
+    /// Follow a vault change: a deleted entry leaves the quick menu, locking
+    /// and unlocking switch the badge
+    pub fn apply(&self, event: &VaultEvent) {
+        match event {
+            VaultEvent::EntryDeleted { id } => self.shortcuts.lock().unwrap().retain(|shortcut| &shortcut.filename != id),
+            VaultEvent::Locked => *self.locked.lock().unwrap() = true,
+            VaultEvent::Unlocked => *self.locked.lock().unwrap() = false,
+            _ => {}
+        }
+    }
 }
 
+/// Background task: apply vault events to the tray until shutdown
+pub async fn follow_events(handle: TrayHandle, mut events: broadcast::Receiver<VaultEvent>, mut shutdown_rx: watch::Receiver<()>) {
+    loop {
+        tokio::select! {
+            event = events.recv() => match event {
+                Ok(event) => handle.apply(&event),
+                // Missed events only cost a stale menu item until the TUI republishes the shortcuts
+                Err(broadcast::error::RecvError::Lagged(_)) => {}
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            _ = shutdown_rx.changed() => break,
+        }
+    }
+}
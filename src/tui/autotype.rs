@@ -0,0 +1,206 @@
+use crate::errors::{RpmError, RpmResult};
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+use zeroize::Zeroizing;
+
+/// Шаг набора: текст печатается как есть, клавиши нажимаются, паузы выдерживаются
+enum Step {
+    Text(Zeroizing<String>),
+    Key(SpecialKey),
+    Delay(Duration),
+}
+
+#[derive(Clone, Copy)]
+enum SpecialKey {
+    Tab,
+    Enter,
+}
+
+/// Значения, подставляемые в последовательность
+pub struct AutoTypeValues<'a> {
+    pub username: &'a str,
+    pub password: &'a str,
+    pub url: &'a str,
+}
+
+/// Разобрать последовательность вида `{USERNAME}{TAB}{PASSWORD}{ENTER}`.
+/// Поддерживаются {USERNAME}, {PASSWORD}, {URL}, {TAB}, {ENTER}, {DELAY мс};
+/// текст вне фигурных скобок печатается как есть, `{{` и `}}` - сами скобки
+fn parse(sequence: &str, values: &AutoTypeValues) -> RpmResult<Vec<Step>> {
+    let mut steps = Vec::new();
+    let mut text = Zeroizing::new(String::new());
+    let mut chars = sequence.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                text.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                text.push('}');
+            }
+            '{' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => name.push(c),
+                        None => return Err(RpmError::Tui(format!("unclosed placeholder {{{}", name))),
+                    }
+                }
+                let step = match name.trim().to_uppercase().as_str() {
+                    "USERNAME" => {
+                        text.push_str(values.username);
+                        continue;
+                    }
+                    "PASSWORD" => {
+                        text.push_str(values.password);
+                        continue;
+                    }
+                    "URL" => {
+                        text.push_str(values.url);
+                        continue;
+                    }
+                    "TAB" => Step::Key(SpecialKey::Tab),
+                    "ENTER" => Step::Key(SpecialKey::Enter),
+                    other => match other.strip_prefix("DELAY").map(|ms| ms.trim_start_matches([' ', '=']).parse::<u64>()) {
+                        Some(Ok(ms)) => Step::Delay(Duration::from_millis(ms)),
+                        _ => return Err(RpmError::Tui(format!("unknown placeholder {{{}}}", name))),
+                    },
+                };
+                if !text.is_empty() {
+                    steps.push(Step::Text(Zeroizing::new(std::mem::take(&mut *text))));
+                }
+                steps.push(step);
+            }
+            c => text.push(c),
+        }
+    }
+    if !text.is_empty() {
+        steps.push(Step::Text(text));
+    }
+    Ok(steps)
+}
+
+/// Чем набирать текст: xdotool (X11), wtype (Wayland), System Events (macOS)
+#[derive(Clone, Copy)]
+enum Backend {
+    Xdotool,
+    Wtype,
+    Osascript,
+}
+
+fn backend() -> RpmResult<Backend> {
+    if cfg!(target_os = "macos") {
+        Ok(Backend::Osascript)
+    } else if cfg!(windows) {
+        Err(RpmError::Tui("auto-type is not supported on Windows".to_string()))
+    } else if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        Ok(Backend::Wtype)
+    } else if std::env::var_os("DISPLAY").is_some() {
+        Ok(Backend::Xdotool)
+    } else {
+        Err(RpmError::Tui("auto-type needs a graphical session (X11, Wayland or macOS)".to_string()))
+    }
+}
+
+/// Запустить программу; секретный текст передается через stdin, а не в аргументах,
+/// где его видно другим пользователям в списке процессов
+fn run(program: &str, args: &[&str], stdin: Option<&str>) -> RpmResult<()> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(if stdin.is_some() { Stdio::piped() } else { Stdio::null() })
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| RpmError::Tui(format!("failed to start {}: {}", program, e)))?;
+    if let (Some(input), Some(mut pipe)) = (stdin, child.stdin.take()) {
+        pipe.write_all(input.as_bytes())?;
+    }
+    let status = child.wait()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(RpmError::Tui(format!("{} exited with {}", program, status)))
+    }
+}
+
+/// Строка AppleScript в кавычках
+fn applescript_string(text: &str) -> Zeroizing<String> {
+    Zeroizing::new(format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\"")))
+}
+
+/// Вернуть фокус окну, которое было активно до терминала: в X11 терминал сворачивается
+/// (оконный менеджер сам отдает фокус предыдущему окну), в macOS - Cmd+Tab.
+/// В Wayland сменить фокус программно нельзя: пользователь переключается сам за время паузы
+fn focus_previous_window(backend: Backend) -> RpmResult<()> {
+    match backend {
+        Backend::Xdotool => run("xdotool", &["getactivewindow", "windowminimize", "--sync"], None),
+        Backend::Osascript => run(
+            "osascript",
+            &[],
+            Some("tell application \"System Events\" to keystroke tab using command down"),
+        ),
+        Backend::Wtype => Ok(()),
+    }
+}
+
+fn type_step(backend: Backend, step: &Step) -> RpmResult<()> {
+    match (backend, step) {
+        (_, Step::Delay(delay)) => {
+            std::thread::sleep(*delay);
+            Ok(())
+        }
+        (Backend::Xdotool, Step::Text(text)) => run("xdotool", &["type", "--clearmodifiers", "--file", "-"], Some(text)),
+        (Backend::Xdotool, Step::Key(key)) => {
+            let name = match key {
+                SpecialKey::Tab => "Tab",
+                SpecialKey::Enter => "Return",
+            };
+            run("xdotool", &["key", "--clearmodifiers", name], None)
+        }
+        (Backend::Wtype, Step::Text(text)) => run("wtype", &["-"], Some(text)),
+        (Backend::Wtype, Step::Key(key)) => {
+            let name = match key {
+                SpecialKey::Tab => "Tab",
+                SpecialKey::Enter => "Return",
+            };
+            run("wtype", &["-k", name], None)
+        }
+        (Backend::Osascript, Step::Text(text)) => {
+            let script = Zeroizing::new(format!(
+                "tell application \"System Events\" to keystroke {}",
+                applescript_string(text).as_str()
+            ));
+            run("osascript", &[], Some(&script))
+        }
+        (Backend::Osascript, Step::Key(key)) => {
+            let code = match key {
+                SpecialKey::Tab => 48,
+                SpecialKey::Enter => 36,
+            };
+            run("osascript", &[], Some(&format!("tell application \"System Events\" to key code {}", code)))
+        }
+    }
+}
+
+/// Набрать последовательность в окне, которое было активно до терминала.
+/// `delay` - пауза после переключения фокуса, пока окно не примет ввод
+pub fn auto_type(sequence: &str, values: &AutoTypeValues, delay: Duration) -> RpmResult<()> {
+    // Ошибки в последовательности проверяем до того, как уводить фокус
+    let steps = parse(sequence, values)?;
+    let backend = backend()?;
+    focus_previous_window(backend)?;
+    std::thread::sleep(delay);
+    for step in &steps {
+        type_step(backend, step)?;
+    }
+    Ok(())
+}
+
+/// Проверить последовательность без набора (для экрана редактирования)
+pub fn validate(sequence: &str) -> RpmResult<()> {
+    parse(sequence, &AutoTypeValues { username: "", password: "", url: "" }).map(|_| ())
+}
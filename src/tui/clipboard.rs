@@ -0,0 +1,117 @@
+use arboard::Clipboard;
+use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+use base64::Engine;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use std::io::{self, Write};
+use zeroize::Zeroize;
+
+/// Отпечаток скопированного текста: по нему очистка по таймауту узнает, что в буфере
+/// все еще наш пароль, не держа сам пароль в памяти. Соль случайная для каждого
+/// копирования, чтобы отпечаток нельзя было перебирать по словарю
+pub struct CopiedText {
+    salt: [u8; 16],
+    digest: [u8; 32],
+}
+
+impl CopiedText {
+    pub fn new(text: &str) -> Self {
+        let mut salt = [0u8; 16];
+        OsRng.fill_bytes(&mut salt);
+        let digest = Self::hash(&salt, text);
+        Self { salt, digest }
+    }
+
+    fn hash(salt: &[u8], text: &str) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(salt);
+        hasher.update(text.as_bytes());
+        hasher.finalize().into()
+    }
+
+    /// Совпадает ли прочитанное из буфера с тем, что копировали, то есть надо ли его
+    /// очищать; прочитанный текст стирается
+    pub fn matches(&self, current: Result<String, arboard::Error>) -> bool {
+        match current {
+            Ok(mut text) => {
+                let same = Self::hash(&self.salt, &text) == self.digest;
+                text.zeroize();
+                same
+            }
+            // Пусто или там уже не текст (картинка, файлы): пользователь скопировал другое
+            Err(_) => false,
+        }
+    }
+}
+
+/// Очистить буфер обмена (и первичное выделение), только если в них все еще скопированный
+/// нами текст: то, что пользователь скопировал после, не трогаем
+pub fn clear_if_unchanged(clipboard: &mut Clipboard, copied: &CopiedText, primary_selection: bool) {
+    if copied.matches(clipboard.get_text()) {
+        let _ = clipboard.set_text("");
+    }
+    if primary_selection && copied.matches(get_primary(clipboard)) {
+        let _ = set_primary(clipboard, "");
+    }
+}
+
+/// Запущены ли мы в SSH-сессии: системный буфер обмена здесь - буфер удаленной машины
+pub fn is_remote_session() -> bool {
+    ["SSH_CONNECTION", "SSH_CLIENT", "SSH_TTY"]
+        .iter()
+        .any(|name| std::env::var_os(name).is_some_and(|value| !value.is_empty()))
+}
+
+/// Копировать ли через OSC 52 по настройке `clipboard_osc52`: "always", "never" или "auto"
+/// (в SSH-сессии; без системного буфера обмена `auto` тоже переходит на OSC 52)
+pub fn osc52_preferred(mode: &str) -> bool {
+    match mode {
+        "always" => true,
+        "never" => false,
+        _ => is_remote_session(),
+    }
+}
+
+/// Положить текст в буфер обмена терминала пользователя escape-последовательностью OSC 52.
+/// Пустой текст очищает буфер. Внутри tmux последовательность передается ему насквозь
+/// (нужен `set -g allow-passthrough on` или `set-clipboard on`)
+pub fn write_osc52(text: &str) -> io::Result<()> {
+    let mut payload = BASE64_STANDARD.encode(text.as_bytes());
+    let mut sequence = if std::env::var_os("TMUX").is_some() {
+        format!("\x1bPtmux;\x1b\x1b]52;c;{}\x07\x1b\\", payload)
+    } else {
+        format!("\x1b]52;c;{}\x07", payload)
+    };
+    payload.zeroize();
+
+    let mut stdout = io::stdout();
+    let result = stdout.write_all(sequence.as_bytes()).and_then(|_| stdout.flush());
+    sequence.zeroize();
+    result
+}
+
+/// Положить текст в первичное выделение X11/Wayland (вставка средней кнопкой мыши).
+/// На Wayland нужен композитор с протоколом data-control; пустой текст очищает выделение
+#[cfg(all(unix, not(target_os = "macos")))]
+pub fn set_primary(clipboard: &mut Clipboard, text: &str) -> Result<(), arboard::Error> {
+    use arboard::{LinuxClipboardKind, SetExtLinux};
+    clipboard.set().clipboard(LinuxClipboardKind::Primary).text(text)
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn get_primary(clipboard: &mut Clipboard) -> Result<String, arboard::Error> {
+    use arboard::{GetExtLinux, LinuxClipboardKind};
+    clipboard.get().clipboard(LinuxClipboardKind::Primary).text()
+}
+
+/// Первичного выделения нет за пределами X11/Wayland
+#[cfg(not(all(unix, not(target_os = "macos"))))]
+pub fn set_primary(_clipboard: &mut Clipboard, _text: &str) -> Result<(), arboard::Error> {
+    Ok(())
+}
+
+#[cfg(not(all(unix, not(target_os = "macos"))))]
+fn get_primary(_clipboard: &mut Clipboard) -> Result<String, arboard::Error> {
+    Err(arboard::Error::ClipboardNotSupported)
+}
@@ -0,0 +1,112 @@
+use ratatui::buffer::Buffer;
+
+/// Что умеет терминал, в котором запущен TUI
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConsoleCaps {
+    /// 24-битные цвета; без них используется палитра из 16 цветов ANSI
+    pub truecolor: bool,
+    /// Рамки, стрелки и галочки Unicode; без них они заменяются на ASCII
+    pub unicode: bool,
+}
+
+impl ConsoleCaps {
+    pub const FULL: Self = Self { truecolor: true, unicode: true };
+    pub const COMPAT: Self = Self { truecolor: false, unicode: false };
+
+    /// Режим из настройки `console_mode`: "full", "compat" или "auto" (определить по терминалу)
+    pub fn from_mode(mode: &str) -> Self {
+        match mode {
+            "full" => Self::FULL,
+            "compat" => Self::COMPAT,
+            _ => Self::detect(),
+        }
+    }
+
+    /// Определить возможности терминала по окружению
+    pub fn detect() -> Self {
+        let var = |name: &str| std::env::var(name).unwrap_or_default();
+        let term = var("TERM");
+
+        if cfg!(windows) {
+            // Windows Terminal, ConEmu, терминал VS Code и mintty (Git Bash) умеют все;
+            // классическая консоль cmd.exe/conhost - нет
+            let modern = !var("WT_SESSION").is_empty()
+                || !var("TERM_PROGRAM").is_empty()
+                || var("ConEmuANSI") == "ON"
+                || (!term.is_empty() && term != "dumb");
+            return if modern { Self::FULL } else { Self::COMPAT };
+        }
+
+        // Консоль Linux (TERM=linux) и Terminal.app не поддерживают 24-битные цвета,
+        // если сам терминал не сообщил об этом через COLORTERM
+        let colorterm = var("COLORTERM");
+        let truecolor = colorterm == "truecolor"
+            || colorterm == "24bit"
+            || !(term == "linux" || term == "dumb" || var("TERM_PROGRAM") == "Apple_Terminal");
+
+        // Первая непустая из LC_ALL, LC_CTYPE, LANG задает кодировку; без локали считаем UTF-8
+        let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+            .iter()
+            .map(|name| var(name))
+            .find(|value| !value.is_empty())
+            .unwrap_or_default()
+            .to_lowercase();
+        let unicode = term != "dumb"
+            && (locale.is_empty() || locale.contains("utf-8") || locale.contains("utf8"));
+
+        Self { truecolor, unicode }
+    }
+}
+
+/// ASCII-замена символа псевдографики; `None` - символ остается как есть
+fn ascii_symbol(symbol: &str) -> Option<&'static str> {
+    Some(match symbol {
+        "─" | "━" => "-",
+        "═" => "=",
+        "│" | "┃" | "║" => "|",
+        "╭" | "╮" | "╰" | "╯" | "┌" | "┐" | "└" | "┘" | "╔" | "╗" | "╚" | "╝" | "├" | "┤" | "┬"
+        | "┴" | "┼" => "+",
+        "↑" => "^",
+        "↓" => "v",
+        "←" => "<",
+        "→" => ">",
+        "▸" => ">",
+        "✓" => "+",
+        "✗" => "x",
+        "⚠" => "!",
+        "•" | "●" => "*",
+        "—" | "–" => "-",
+        "…" => ".",
+        _ => return None,
+    })
+}
+
+/// Символы рамок
+fn is_border(symbol: &str) -> bool {
+    matches!(
+        symbol,
+        "─" | "━" | "═" | "│" | "┃" | "║" | "╭" | "╮" | "╰" | "╯" | "┌" | "┐" | "└" | "┘" | "╔" | "╗" | "╚"
+            | "╝" | "├" | "┤" | "┬" | "┴" | "┼"
+    )
+}
+
+/// Убрать рамки для программ чтения с экрана: они читаются как шум.
+/// Рамки заменяются пробелами, поэтому раскладка экрана не сдвигается,
+/// а заголовки полей в рамках остаются обычным текстом.
+pub fn strip_borders(buffer: &mut Buffer) {
+    for cell in buffer.content.iter_mut() {
+        if is_border(cell.symbol()) {
+            cell.set_symbol(" ");
+        }
+    }
+}
+
+/// Заменить в отрисованном кадре рамки, стрелки и галочки на ASCII.
+/// Текст (кириллица, иероглифы, имена записей) не меняется.
+pub fn to_ascii(buffer: &mut Buffer) {
+    for cell in buffer.content.iter_mut() {
+        if let Some(ascii) = ascii_symbol(cell.symbol()) {
+            cell.set_symbol(ascii);
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use super::suspend;
+use crate::errors::{RpmError, RpmResult};
+use ratatui::backend::CrosstermBackend;
+use ratatui::Terminal;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Stdout, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use zeroize::Zeroize;
+
+/// Редактор, если не заданы ни $VISUAL, ни $EDITOR
+const DEFAULT_EDITOR: &str = "vi";
+
+/// Временный файл с заметками: при удалении перезаписывается нулями, чтобы текст не остался в памяти tmpfs
+struct ShreddedFile {
+    path: PathBuf,
+}
+
+impl Drop for ShreddedFile {
+    fn drop(&mut self) {
+        if let Ok(mut file) = OpenOptions::new().write(true).open(&self.path) {
+            let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+            let _ = file.write_all(&vec![0u8; len as usize]);
+            let _ = file.sync_all();
+        }
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Каталог в оперативной памяти: $XDG_RUNTIME_DIR или /dev/shm.
+/// Заметки не должны попасть на диск, поэтому без tmpfs редактор не открывается
+fn ram_temp_dir() -> RpmResult<PathBuf> {
+    std::env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .into_iter()
+        .chain(std::iter::once(PathBuf::from("/dev/shm")))
+        .find(|dir| dir.is_dir())
+        .ok_or_else(|| RpmError::Tui("no RAM-backed temporary directory ($XDG_RUNTIME_DIR or /dev/shm)".to_string()))
+}
+
+/// Создать файл, доступный только владельцу
+fn create_private_file(path: &Path) -> io::Result<File> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)
+}
+
+/// Команда редактора: $VISUAL, затем $EDITOR; может содержать аргументы ("code --wait")
+fn editor_command() -> Vec<String> {
+    let editor = std::env::var("VISUAL")
+        .ok()
+        .filter(|v| !v.trim().is_empty())
+        .or_else(|| std::env::var("EDITOR").ok().filter(|v| !v.trim().is_empty()))
+        .unwrap_or_else(|| DEFAULT_EDITOR.to_string());
+    editor.split_whitespace().map(str::to_string).collect()
+}
+
+/// Приостановить TUI, открыть заметки во внешнем редакторе и вернуть отредактированный текст.
+/// Текст живет только во временном файле в памяти, который затирается сразу после чтения
+pub fn edit_notes(terminal: &mut Terminal<CrosstermBackend<Stdout>>, notes: &str) -> RpmResult<String> {
+    let dir = ram_temp_dir()?;
+    let mut suffix = [0u8; 8];
+    rand::RngCore::fill_bytes(&mut rand::thread_rng(), &mut suffix);
+    let path = dir.join(format!("rpm-notes-{}.txt", hex::encode(suffix)));
+
+    let mut file = create_private_file(&path)?;
+    let temp = ShreddedFile { path: path.clone() };
+    file.write_all(notes.as_bytes())?;
+    drop(file);
+
+    // Отдаем терминал редактору и возвращаем его TUI в любом случае
+    suspend::leave(terminal)?;
+    let command = editor_command();
+    let status = Command::new(&command[0]).args(&command[1..]).arg(&temp.path).status();
+    suspend::enter(terminal)?;
+
+    let status = status.map_err(|e| RpmError::Tui(format!("failed to start {}: {}", command[0], e)))?;
+    if !status.success() {
+        return Err(RpmError::Tui(format!("{} exited with {}", command[0], status)));
+    }
+
+    let mut bytes = Vec::new();
+    File::open(&temp.path)?.read_to_end(&mut bytes)?;
+    drop(temp);
+    let mut edited = match String::from_utf8(bytes) {
+        Ok(text) => text,
+        Err(e) => {
+            e.into_bytes().zeroize();
+            return Err(RpmError::Tui("notes are not valid UTF-8".to_string()));
+        }
+    };
+    // Редакторы дописывают перевод строки в конец файла
+    if edited.ends_with('\n') {
+        edited.pop();
+        if edited.ends_with('\r') {
+            edited.pop();
+        }
+    }
+    Ok(edited)
+}
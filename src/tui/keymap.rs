@@ -0,0 +1,287 @@
+use crate::i18n::I18n;
+use crossterm::event::{KeyCode, KeyModifiers};
+
+/// Клавиша (или способ ввода) в таблице горячих клавиш
+#[derive(Debug, Clone, Copy)]
+pub enum HelpKey {
+    /// Нажатие клавиши с модификаторами
+    Press(KeyModifiers, KeyCode),
+    /// Нажатие, за которым следует буква регистра (макросы)
+    WithRegister(KeyCode),
+    /// Не нажатие, а ввод (поиск, `#тег`): ключ перевода подписи
+    Label(&'static str),
+    /// Подпись без перевода (`:sh`, плейсхолдеры автонабора)
+    Literal(&'static str),
+}
+
+/// Действие экрана и клавиши, которые его вызывают.
+/// Без клавиш строка справки - просто пояснение
+#[derive(Debug)]
+pub struct Binding {
+    pub keys: &'static [HelpKey],
+    /// Ключ перевода описания действия
+    pub action: &'static str,
+}
+
+/// Раздел справки: экран и его горячие клавиши
+#[derive(Debug)]
+pub struct HelpSection {
+    pub title: &'static str,
+    pub bindings: &'static [Binding],
+}
+
+const fn ctrl(c: char) -> HelpKey {
+    HelpKey::Press(KeyModifiers::CONTROL, KeyCode::Char(c))
+}
+
+const fn key(code: KeyCode) -> HelpKey {
+    HelpKey::Press(KeyModifiers::NONE, code)
+}
+
+const fn f(n: u8) -> HelpKey {
+    key(KeyCode::F(n))
+}
+
+const UP: HelpKey = key(KeyCode::Up);
+const DOWN: HelpKey = key(KeyCode::Down);
+const ENTER: HelpKey = key(KeyCode::Enter);
+const ESC: HelpKey = key(KeyCode::Esc);
+const BACKSPACE: HelpKey = key(KeyCode::Backspace);
+const TAB: HelpKey = key(KeyCode::Tab);
+
+const fn bind(keys: &'static [HelpKey], action: &'static str) -> Binding {
+    Binding { keys, action }
+}
+
+/// Горячие клавиши всех экранов в порядке справки; справка строится только из этой таблицы
+pub const HELP_SECTIONS: &[HelpSection] = &[
+    HelpSection {
+        title: "help_main_screen_title",
+        bindings: &[
+            bind(&[ctrl('q')], "help_main_ctrl_q"),
+            bind(&[ctrl('n')], "help_main_ctrl_n"),
+            bind(&[ctrl('e')], "help_main_ctrl_e"),
+            bind(&[ctrl('d')], "help_main_ctrl_d"),
+            bind(&[ctrl('c')], "help_main_ctrl_c"),
+            bind(&[ctrl('u')], "help_main_ctrl_u"),
+            bind(&[ctrl('b')], "help_main_ctrl_b"),
+            bind(&[ctrl('k')], "help_main_ctrl_k"),
+            bind(&[ctrl('o')], "help_main_ctrl_o"),
+            bind(&[ctrl('v')], "help_main_ctrl_v"),
+            bind(&[ctrl('s')], "help_main_ctrl_s"),
+            bind(&[f(1)], "help_main_f1"),
+            bind(&[f(2)], "help_main_f2"),
+            bind(&[f(3)], "help_main_f3"),
+            bind(&[ctrl('r')], "help_main_ctrl_r"),
+            bind(&[f(4)], "help_main_f4"),
+            bind(&[f(5)], "help_main_f5"),
+            bind(&[f(6)], "help_main_f6"),
+            bind(&[f(7)], "help_main_f7"),
+            bind(&[f(8)], "help_main_f8"),
+            bind(&[f(9)], "help_main_f9"),
+            bind(&[f(10)], "help_main_f10"),
+            bind(&[UP, DOWN], "help_main_arrows"),
+            bind(&[ESC], "help_main_esc"),
+            bind(&[BACKSPACE], "help_main_backspace"),
+            bind(&[HelpKey::Label("help_key_search")], "help_main_type"),
+            bind(&[HelpKey::Label("help_key_tag")], "help_main_tag"),
+            bind(&[ENTER, BACKSPACE], "help_main_folders"),
+            bind(&[ctrl('f')], "help_main_ctrl_f"),
+            bind(&[ctrl('a')], "help_main_ctrl_a"),
+            bind(&[ctrl('g')], "help_main_ctrl_g"),
+            bind(&[ctrl('z'), HelpKey::Literal(":sh")], "help_main_ctrl_z"),
+            bind(&[HelpKey::WithRegister(KeyCode::F(11))], "help_main_f11"),
+            bind(&[HelpKey::WithRegister(KeyCode::F(12))], "help_main_f12"),
+        ],
+    },
+    HelpSection {
+        title: "help_master_password_title",
+        bindings: &[
+            bind(&[ENTER], "help_master_password_enter"),
+            bind(&[UP, DOWN], "help_master_password_arrows"),
+            bind(&[ctrl('h')], "help_master_password_ctrl_h"),
+            bind(&[f(1)], "help_master_password_f1"),
+            bind(&[ESC], "help_master_password_esc"),
+            bind(&[BACKSPACE], "help_master_password_backspace"),
+        ],
+    },
+    HelpSection {
+        title: "help_password_entry_title",
+        bindings: &[
+            bind(&[ENTER], "help_password_entry_enter"),
+            bind(&[ESC], "help_password_entry_esc"),
+            bind(&[UP, DOWN], "help_password_entry_arrows"),
+            bind(&[ctrl('h')], "help_password_entry_ctrl_h"),
+            bind(&[ctrl('g')], "help_password_entry_ctrl_g"),
+            bind(&[f(3)], "help_password_entry_f3"),
+            bind(&[f(4)], "help_password_entry_f4"),
+            bind(&[f(5)], "help_password_entry_f5"),
+            bind(&[f(6)], "help_password_entry_f6"),
+            bind(&[f(7)], "help_password_entry_f7"),
+            bind(&[ctrl('t')], "help_password_entry_ctrl_t"),
+            bind(&[ctrl('e')], "help_password_entry_ctrl_e"),
+            bind(&[f(1)], "help_password_entry_f1"),
+            bind(&[BACKSPACE], "help_password_entry_backspace"),
+        ],
+    },
+    HelpSection {
+        title: "help_password_generator_title",
+        bindings: &[
+            bind(&[ENTER], "help_password_generator_enter"),
+            bind(&[ESC], "help_password_generator_esc"),
+            bind(&[UP, DOWN], "help_password_generator_arrows"),
+            bind(&[key(KeyCode::Char(' '))], "help_password_generator_space"),
+            bind(&[ctrl('p')], "help_password_generator_ctrl_p"),
+            bind(&[ctrl('s')], "help_password_generator_ctrl_s"),
+            bind(&[BACKSPACE], "help_password_generator_backspace"),
+            bind(&[HelpKey::Label("help_key_input")], "help_password_generator_type"),
+            bind(&[f(1)], "help_password_generator_f1"),
+        ],
+    },
+    HelpSection {
+        title: "help_settings_title",
+        bindings: &[
+            bind(&[ENTER], "help_settings_enter"),
+            bind(&[ESC, key(KeyCode::Char('q'))], "help_settings_esc"),
+            bind(&[UP, DOWN], "help_settings_arrows"),
+            bind(&[f(1)], "help_settings_f1"),
+            bind(&[BACKSPACE], "help_settings_backspace"),
+        ],
+    },
+    HelpSection {
+        title: "help_audit_title",
+        bindings: &[bind(&[ESC, f(3)], "help_audit_close")],
+    },
+    HelpSection {
+        title: "help_security_questions_title",
+        bindings: &[
+            bind(&[ctrl('n')], "help_security_questions_ctrl_n"),
+            bind(&[ctrl('g')], "help_security_questions_ctrl_g"),
+            bind(&[ctrl('h')], "help_security_questions_ctrl_h"),
+            bind(&[ctrl('d')], "help_security_questions_ctrl_d"),
+            bind(&[TAB], "help_security_questions_tab"),
+            bind(&[ESC, f(4)], "help_security_questions_close"),
+        ],
+    },
+    HelpSection {
+        title: "help_custom_fields_title",
+        bindings: &[
+            bind(&[ctrl('n')], "help_custom_fields_ctrl_n"),
+            bind(&[ctrl('c')], "help_custom_fields_ctrl_c"),
+            bind(&[ctrl('h')], "help_custom_fields_ctrl_h"),
+            bind(&[ctrl('d')], "help_custom_fields_ctrl_d"),
+            bind(&[TAB], "help_custom_fields_tab"),
+            bind(&[ESC, f(5)], "help_custom_fields_close"),
+        ],
+    },
+    HelpSection {
+        title: "help_ssh_host_title",
+        bindings: &[
+            bind(&[UP, DOWN, TAB], "help_ssh_host_arrows"),
+            bind(&[ESC, f(6)], "help_ssh_host_close"),
+        ],
+    },
+    HelpSection {
+        title: "help_autotype_title",
+        bindings: &[
+            bind(&[HelpKey::Literal("{USERNAME}{TAB}{PASSWORD}{ENTER}")], "help_autotype_placeholders"),
+            bind(&[ESC, f(7)], "help_autotype_close"),
+        ],
+    },
+    HelpSection {
+        title: "help_access_window_title",
+        bindings: &[
+            bind(&[HelpKey::Literal("mon-fri 08:00-20:00")], "help_access_window_format"),
+            bind(&[ESC, f(8)], "help_access_window_close"),
+        ],
+    },
+    HelpSection {
+        title: "help_rotation_title",
+        bindings: &[
+            bind(&[ctrl('o')], "help_rotation_ctrl_o"),
+            bind(&[ctrl('g')], "help_rotation_ctrl_g"),
+            bind(&[ctrl('p'), ctrl('c')], "help_rotation_copy"),
+            bind(&[ENTER], "help_rotation_enter"),
+            bind(&[TAB], "help_rotation_tab"),
+            bind(&[ESC], "help_rotation_esc"),
+        ],
+    },
+    HelpSection {
+        title: "help_trash_title",
+        bindings: &[
+            bind(&[ENTER], "help_trash_enter"),
+            bind(&[key(KeyCode::Delete)], "help_trash_delete"),
+            bind(&[], "help_trash_retention"),
+        ],
+    },
+    HelpSection {
+        title: "help_help_title",
+        bindings: &[bind(&[f(1), ESC], "help_help_close")],
+    },
+];
+
+/// Подпись клавиши на языке интерфейса
+pub fn key_label(key: &HelpKey, i18n: &I18n) -> String {
+    match *key {
+        HelpKey::Press(modifiers, code) => {
+            let mut label = String::new();
+            if modifiers.contains(KeyModifiers::CONTROL) {
+                label.push_str("Ctrl+");
+            }
+            if modifiers.contains(KeyModifiers::ALT) {
+                label.push_str("Alt+");
+            }
+            if modifiers.contains(KeyModifiers::SHIFT) {
+                label.push_str("Shift+");
+            }
+            label.push_str(&code_label(code, i18n));
+            label
+        }
+        HelpKey::WithRegister(code) => format!("{} <a-z>", code_label(code, i18n)),
+        HelpKey::Label(label) => i18n.ts(label).to_string(),
+        HelpKey::Literal(label) => label.to_string(),
+    }
+}
+
+fn code_label(code: KeyCode, i18n: &I18n) -> String {
+    match code {
+        KeyCode::Char(' ') => i18n.ts("help_key_space").to_string(),
+        KeyCode::Char(c) => c.to_uppercase().to_string(),
+        KeyCode::F(n) => format!("F{}", n),
+        KeyCode::Up => "↑".to_string(),
+        KeyCode::Down => "↓".to_string(),
+        KeyCode::Left => "←".to_string(),
+        KeyCode::Right => "→".to_string(),
+        KeyCode::Enter => "Enter".to_string(),
+        KeyCode::Esc => "Esc".to_string(),
+        KeyCode::Backspace => "Backspace".to_string(),
+        KeyCode::Tab => "Tab".to_string(),
+        KeyCode::Delete => "Del".to_string(),
+        other => format!("{:?}", other),
+    }
+}
+
+/// Строки справки: заголовок каждого раздела между разделителями и
+/// `клавиши - описание`, выровненные по колонке
+pub fn help_lines(i18n: &I18n) -> Vec<String> {
+    let separator = i18n.ts("help_separator");
+    let mut lines = Vec::new();
+    for section in HELP_SECTIONS {
+        lines.push(separator.to_string());
+        lines.push(i18n.ts(section.title).to_string());
+        lines.push(separator.to_string());
+        lines.push(String::new());
+        for binding in section.bindings {
+            let action = i18n.ts(binding.action);
+            if binding.keys.is_empty() {
+                lines.push(format!("  {}", action));
+                continue;
+            }
+            let keys = binding.keys.iter().map(|key| key_label(key, i18n)).collect::<Vec<_>>().join(" / ");
+            let padding = 15usize.saturating_sub(keys.chars().count());
+            lines.push(format!("  {}{} - {}", keys, " ".repeat(padding), action));
+        }
+        lines.push(String::new());
+    }
+    lines
+}
@@ -0,0 +1,97 @@
+use crate::config::Config;
+use crate::crypto::CryptoManager;
+use crate::models::{DefFileEntry, EntryMeta};
+use crate::storage::PasswordStorage;
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use zeroize::{Zeroize, Zeroizing};
+
+/// Сколько имен расшифровывается между обновлениями списка
+const BATCH_SIZE: usize = 200;
+
+enum LoadEvent {
+    Names(Vec<(String, String)>),
+    /// Все имена загружены; метаданные для API расширения (или ошибка их чтения)
+    Finished(Result<Vec<EntryMeta>, String>),
+    Failed(String),
+}
+
+/// Что пришло из фоновой загрузки с прошлого опроса
+#[derive(Default)]
+pub struct LoadUpdate {
+    /// Новые пары (файл, имя) в порядке def-файла
+    pub names: Vec<(String, String)>,
+    /// `Some`, когда загрузка закончилась: метаданные или текст ошибки
+    pub finished: Option<Result<Vec<EntryMeta>, String>>,
+}
+
+/// Фоновая расшифровка имен записей после разблокировки: главный экран
+/// открывается сразу, а имена добавляются в список пачками.
+/// Если загрузчик удален, поток останавливается на следующей пачке
+pub struct NameLoader {
+    receiver: Receiver<LoadEvent>,
+}
+
+impl NameLoader {
+    /// Запустить расшифровку `entries` (уже прочитанного def-файла) копией ключа
+    pub fn start(config: &Config, crypto: CryptoManager, entries: Vec<DefFileEntry>, key: &[u8]) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let storage = PasswordStorage::new(config, crypto);
+        let key = Zeroizing::new(key.to_vec());
+        tokio::task::spawn_blocking(move || decrypt_names(&storage, &entries, &key, &sender));
+        Self { receiver }
+    }
+
+    /// Забрать все готовые пачки, не блокируясь
+    pub fn poll(&self) -> LoadUpdate {
+        let mut update = LoadUpdate::default();
+        loop {
+            match self.receiver.try_recv() {
+                Ok(LoadEvent::Names(mut names)) => update.names.append(&mut names),
+                Ok(LoadEvent::Finished(meta)) => {
+                    update.finished = Some(meta);
+                    break;
+                }
+                Ok(LoadEvent::Failed(e)) => {
+                    update.finished = Some(Err(e));
+                    break;
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    update.finished = Some(Err("loader stopped".to_string()));
+                    break;
+                }
+            }
+        }
+        update
+    }
+}
+
+fn decrypt_names(storage: &PasswordStorage, entries: &[DefFileEntry], key: &[u8], sender: &Sender<LoadEvent>) {
+    for chunk in entries.chunks(BATCH_SIZE) {
+        let mut names = Vec::with_capacity(chunk.len());
+        for entry in chunk {
+            match storage.decrypt_filename(&entry.encrypted_name, &entry.nonce, key) {
+                Ok(name) => names.push((entry.encrypted_filename.clone(), name)),
+                Err(e) => {
+                    zeroize_names(&mut names);
+                    let _ = sender.send(LoadEvent::Failed(e.to_string()));
+                    return;
+                }
+            }
+        }
+        // Загрузку отменили (блокировка, перезагрузка списка): имена никому не нужны
+        if let Err(mpsc::SendError(LoadEvent::Names(mut names))) = sender.send(LoadEvent::Names(names)) {
+            zeroize_names(&mut names);
+            return;
+        }
+    }
+    // Метаданные читают файлы всех записей, поэтому тоже в фоне, после имен
+    let meta = storage.list_entry_meta(key).map_err(|e| e.to_string());
+    let _ = sender.send(LoadEvent::Finished(meta));
+}
+
+fn zeroize_names(names: &mut [(String, String)]) {
+    for (_, name) in names.iter_mut() {
+        name.zeroize();
+    }
+}
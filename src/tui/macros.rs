@@ -0,0 +1,117 @@
+use crossterm::event::KeyEvent;
+use std::collections::{HashMap, VecDeque};
+
+/// Какой регистр ждет нажатия буквы: для записи (F11) или для повтора (F12)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MacroPrompt {
+    Record,
+    Replay,
+}
+
+/// Шаг повтора: выбрать запись главного списка (по файлу) или обработать клавишу
+#[derive(Debug, Clone)]
+pub enum ReplayStep {
+    Select(String),
+    Key(KeyEvent),
+}
+
+/// Макросы в духе `q` из vim: нажатия клавиш, записанные в регистр a-z и повторенные
+/// для каждой записи списка. Хранятся только в памяти и стираются при блокировке,
+/// потому что могут содержать набранные пароли
+#[derive(Debug, Default)]
+pub struct Macros {
+    registers: HashMap<char, Vec<KeyEvent>>,
+    recording: Option<(char, Vec<KeyEvent>)>,
+    prompt: Option<MacroPrompt>,
+    replay: VecDeque<ReplayStep>,
+    /// Сколько записей в текущем повторе (для итогового сообщения)
+    replay_entries: Option<usize>,
+}
+
+impl Macros {
+    /// Регистр, в который идет запись
+    pub fn recording(&self) -> Option<char> {
+        self.recording.as_ref().map(|(register, _)| *register)
+    }
+
+    pub fn is_replaying(&self) -> bool {
+        !self.replay.is_empty()
+    }
+
+    pub fn prompt(&self) -> Option<MacroPrompt> {
+        self.prompt
+    }
+
+    pub fn set_prompt(&mut self, prompt: MacroPrompt) {
+        self.prompt = Some(prompt);
+    }
+
+    pub fn take_prompt(&mut self) -> Option<MacroPrompt> {
+        self.prompt.take()
+    }
+
+    pub fn start_recording(&mut self, register: char) {
+        self.recording = Some((register, Vec::new()));
+    }
+
+    /// Закончить запись; возвращает регистр и число клавиш
+    pub fn stop_recording(&mut self) -> Option<(char, usize)> {
+        let (register, keys) = self.recording.take()?;
+        let len = keys.len();
+        self.registers.insert(register, keys);
+        Some((register, len))
+    }
+
+    /// Запомнить клавишу, если идет запись (клавиши повтора не записываются)
+    pub fn record(&mut self, key: KeyEvent) {
+        if let Some((_, ref mut keys)) = self.recording {
+            if self.replay.is_empty() {
+                keys.push(key);
+            }
+        }
+    }
+
+    /// Поставить в очередь повтор регистра для каждой записи; `false`, если регистр пуст
+    pub fn queue_replay(&mut self, register: char, filenames: &[String]) -> bool {
+        let Some(keys) = self.registers.get(&register).filter(|keys| !keys.is_empty()) else {
+            return false;
+        };
+        for filename in filenames {
+            self.replay.push_back(ReplayStep::Select(filename.clone()));
+            self.replay.extend(keys.iter().copied().map(ReplayStep::Key));
+        }
+        self.replay_entries = Some(filenames.len());
+        true
+    }
+
+    pub fn next_step(&mut self) -> Option<ReplayStep> {
+        self.replay.pop_front()
+    }
+
+    /// Пропустить оставшиеся клавиши текущей записи (запись не найдена)
+    pub fn skip_entry(&mut self) {
+        while matches!(self.replay.front(), Some(ReplayStep::Key(_))) {
+            self.replay.pop_front();
+        }
+    }
+
+    /// Прервать повтор (Esc или макрос не вернулся на главный экран)
+    pub fn abort(&mut self) {
+        self.replay.clear();
+        self.replay_entries = None;
+    }
+
+    /// Число записей, когда повтор только что закончился
+    pub fn take_finished(&mut self) -> Option<usize> {
+        if self.replay.is_empty() {
+            self.replay_entries.take()
+        } else {
+            None
+        }
+    }
+
+    /// Забыть все регистры и остановить запись и повтор
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
@@ -1,40 +1,104 @@
-use crate::config::{Config, DirectoryConfig};
+use crate::backup;
+use crate::breach::BreachChecker;
+use crate::config::{hint_reveals_password, Config, DirectoryConfig, VaultPolicy};
 use crate::crypto::{CryptoManager, SecureKey};
-use crate::crypto::key_derivation;
-use crate::errors::RpmResult;
+use crate::diagnostics::Diagnostics;
+use crate::crypto::key_derivation::{self, Argon2id, KdfParams};
+use crate::errors::{RpmError, RpmResult};
+use crate::events::VaultEvent;
+use crate::export;
 use crate::i18n::{I18n, Language};
-use crate::storage::PasswordStorage;
-use crate::tray::TrayHandle;
+use crate::import::{format_signature, import_rows, saved_or_guessed_mapping, CsvTable, ImportField, ImportReport};
+use crate::instance::{InstanceHandle, InstanceRequest};
+use crate::keychain;
+use crate::models::{
+    AccessWindow, AuditAction, AuditEvent, CustomField, EntryDetails, EntryMeta, EntryTimestamps, PasswordPolicy, SecurityQuestion, SshHost, SyncStatus, UiState,
+    VaultStats,
+};
+use crate::storage::{
+    normalize_folder, CompactionReport, IntegrityIssue, IntegrityReport, Orphan, OrphanRepair, PasswordStorage, ReuseCluster,
+};
+use crate::sync::git::GitSync;
+use crate::sync::PullOutcome;
+use crate::server::{AuthThrottle, MetadataHandle, PairingHandle, PairingRequest, RuntimeInfo};
+use crate::tray::{TrayHandle, TrayShortcut};
+use crate::webhook::{WebhookEvent, Webhooks};
 use arboard::Clipboard;
 use base64::engine::general_purpose::{STANDARD as BASE64_STANDARD, STANDARD_NO_PAD as BASE64_STANDARD_NO_PAD};
 use base64::Engine;
-use crossterm::event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use crossterm::execute;
 use crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
+use chrono::{DateTime, Local, Utc};
 use fuzzy_matcher::FuzzyMatcher;
 use fuzzy_matcher::skim::SkimMatcherV2;
 use rand::RngCore;
 use rand::rngs::OsRng;
+use rand::seq::SliceRandom;
 use rand::Rng;
 use ratatui::backend::CrosstermBackend;
-use ratatui::layout::{Alignment, Constraint, Direction, Layout};
-use ratatui::widgets::{Block, BorderType, Borders, List, ListItem, ListState, Paragraph};
+use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::block::{Position, Title};
+use ratatui::widgets::{Block, BorderType, Borders, Clear, Gauge, List, ListItem, ListState, Paragraph, Wrap};
 use ratatui::Frame;
 use ratatui::Terminal;
 
+mod autotype;
+pub mod clipboard;
+mod console;
+mod editor;
+mod keymap;
+mod loader;
+mod macros;
+mod picker;
+mod suspend;
 mod theme;
-use theme::{get_theme_by_name, Theme};
-use std::collections::HashSet;
+pub use console::ConsoleCaps;
+use loader::{LoadUpdate, NameLoader};
+use macros::{MacroPrompt, Macros, ReplayStep};
+use picker::{find_prefix, ListPicker, PickerAction, PickerItem};
+pub use theme::{get_theme_by_name, Theme};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::io;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex as StdMutex};
-use tokio::sync::watch;
+use std::time::Instant;
+use tokio::sync::{broadcast, watch};
 use tokio::task::JoinHandle;
 use tokio::time::{sleep, Duration};
-use zeroize::Zeroize;
+use zeroize::{Zeroize, Zeroizing};
+
+/// Доступные темы: (название, идентификатор в конфиге, описание)
+pub const THEMES: [(&str, &str, &str); 3] = [
+    ("Textual / Modern Web", "textual_dark", "Глубокий темный фон с яркими зелеными акцентами"),
+    ("VS Code Dark+", "vscode_style", "Классический стиль IDE с мягкими цветами"),
+    ("OpenCode / Dark Modern", "opencode_style", "Нейтральный современный вид"),
+];
+
+/// Число слов в парольной фразе по умолчанию и максимум (256 слов в словаре - 8 бит на слово)
+pub const DEFAULT_PASSPHRASE_WORDS: usize = 6;
+const MAX_PASSPHRASE_WORDS: usize = 32;
+/// Поля экрана смены мастер-пароля: три пароля, подсказка и три параметра Argon2
+const CHANGE_MASTER_FIELDS: usize = 7;
+/// Самый длинный пароль, который создает генератор
+const MAX_PASSWORD_LENGTH: usize = 256;
+/// Символы, которые легко спутать при чтении вслух или вводе с бумаги
+/// (кириллические О и З выглядят как O и 3)
+const AMBIGUOUS_CHARS: &str = "O0Il1S5ОЗ";
+
+/// Пороги энтропии генератора: ниже первого пароль слабый, от второго - надежный
+const WEAK_ENTROPY_BITS: f64 = 50.0;
+const STRONG_ENTROPY_BITS: f64 = 80.0;
+
+/// Риск переиспользования, начиная с которого кластер на экране аудита выделяется как опасный
+const HIGH_REUSE_RISK: u8 = 60;
+
+/// Как часто перечитывать дату последней резервной копии для статистики хранилища
+const STATS_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
 
 #[derive(Debug, Clone, PartialEq)]
-enum Screen {
+pub enum Screen {
     MasterPassword,
     Main,
     Settings,
@@ -43,6 +107,22 @@ enum Screen {
     Help,
     ThemeSelection,
     LanguageSelection,
+    Audit { return_to: Box<Screen> },
+    Rotation,
+    Trash,
+    Maintenance,
+    Orphans,
+    About,
+    SecurityQuestions { is_edit: bool, filename: Option<String> },
+    CustomFields { is_edit: bool, filename: Option<String> },
+    SshHost { is_edit: bool, filename: Option<String> },
+    AutoType { is_edit: bool, filename: Option<String> },
+    AccessWindow { is_edit: bool, filename: Option<String> },
+    ChangeMasterPassword,
+    Import,
+    Backup,
+    Restore,
+    Sync,
 }
 
 pub struct TuiState {
@@ -57,43 +137,420 @@ pub struct TuiState {
     // Master password and encryption key
     pub master_password_input: String,
     pub master_password_confirm: String,
-    pub master_password_field: usize, // For creation: 0 = directory, 1 = password, 2 = confirm. For entry: 0 = password
+    pub master_password_field: usize, // For creation: 0 = directory, 1 = password, 2 = confirm, 3 = hint. For entry: 0 = password
     pub master_password_show_password: bool, // Show password in plain text
+    pub master_password_hint_input: String, // Optional hint typed when creating the vault
+    // Wrong master passwords entered since the unlock screen opened; the hint is shown after two
+    pub master_password_failed_attempts: u32,
+    pub master_password_hint: Option<String>,
     pub is_creating_master_password: bool, // true if creating new, false if entering existing
     pub encryption_key: Option<SecureKey>,
     // Password entry screen state
     pub password_entry_name: String,
+    pub password_entry_username: String,
     pub password_entry_password: String,
+    pub password_entry_url: String,
+    pub password_entry_tags: String, // comma separated
+    pub password_entry_notes: String,
     pub password_entry_show_password: bool,
-    pub password_entry_field: usize, // 0 = name, 1 = password
+    pub password_entry_field: usize, // 0 = name, 1 = username, 2 = password, 3 = url, 4 = notes
+    // Entries already using the password being saved (Some = warning shown, next Enter saves anyway)
+    pub password_entry_reuse_warning: Option<Vec<String>>,
+    pub password_entry_security_questions: Vec<SecurityQuestion>,
+    pub password_entry_custom_fields: Vec<CustomField>,
+    // SSH host of a server entry; the port is kept as typed until the entry is saved
+    pub password_entry_ssh_host: String,
+    pub password_entry_ssh_port: String,
+    pub password_entry_ssh_username: String,
+    // Auto-type sequence of the entry; empty = the configured default
+    pub password_entry_autotype: String,
+    // Часы, когда API выдает запись (`mon-fri 08:00-20:00`); пусто - всегда
+    pub password_entry_access_window: String,
+    // Generator settings applied to this entry (saved as its policy)
+    pub password_entry_policy: Option<PasswordPolicy>,
+    // Created/updated/last used times of the entry being edited
+    pub password_entry_timestamps: EntryTimestamps,
+    // Entry is pinned to the tray quick menu
+    pub password_entry_tray_shortcut: bool,
+    // Почему не удалось отредактировать заметки во внешнем редакторе
+    pub password_entry_editor_error: Option<String>,
+    // Запись, выбранная в меню трея, пока хранилище заблокировано: копируется после разблокировки
+    pub tray_pending_copy: Option<TrayShortcut>,
+    /// Имя записи из `rpm --copy <name>`, пока хранилище заблокировано: копируется после разблокировки
+    pub instance_pending_copy: Option<String>,
+    /// Метаданные записей для API расширений (без паролей); пусто, пока хранилище заблокировано
+    pub entry_metadata: MetadataHandle,
+    /// Запросы сопряжения расширений: пока хранилище разблокировано, код показывается поверх любого экрана
+    pub pairing: PairingHandle,
+    /// Запрос сопряжения, о котором уже сообщили звонком
+    pub pairing_announced: Option<String>,
+    /// Неверные мастер-пароли, присланные через API, и блокировки расширений
+    pub auth_throttle: AuthThrottle,
+    /// Сколько неверных попыток через API уже показано в строке статуса
+    pub auth_failures_announced: u64,
+    /// Порт, на котором на самом деле запущен API (может отличаться от server_port); None - не запущен
+    pub server_port: Option<u16>,
+    /// Порт HTTPS API и SHA-256 его сертификата; None - HTTPS не запущен
+    pub server_tls: Option<(u16, String)>,
+    /// Статистика хранилища для /health и подсказки трея
+    pub vault_stats: VaultStats,
+    /// Диагностика для экрана "О программе"; собирается при его открытии
+    pub about: Option<Diagnostics>,
+    /// Политика текущего хранилища (из `.rpm_config` директории с паролями)
+    pub policy: VaultPolicy,
+    /// Почему мастер-пароль не принят (нарушение политики хранилища)
+    pub master_password_error: Option<String>,
+    // Decrypted names were wiped after inactivity; re-decrypted on the next key press
+    pub metadata_scrubbed: bool,
+    /// Ключ хранилища, пока имена стерты: зашифрован случайным ключом, который живет
+    /// только до возобновления работы
+    pub scrubbed_key: Option<(keychain::WrappedKey, SecureKey)>,
+    /// Фоновая расшифровка имен после разблокировки и ее прогресс (загружено, всего)
+    pub name_loader: Option<NameLoader>,
+    pub names_loading: Option<(usize, usize)>,
+    /// Выбранная запись и прокрутка списка из сохраненного состояния; применяются, когда догрузятся имена
+    pub pending_list_position: Option<UiState>,
+    /// Макросы клавиатуры (F11 - запись, F12 - повтор для записей списка)
+    pub macros: Macros,
+    // Security questions screen state
+    pub security_questions_index: usize,
+    pub security_questions_field: usize, // 0 = question, 1 = answer
+    pub security_questions_show_answers: bool,
+    // Custom fields screen state
+    pub custom_fields_index: usize,
+    pub custom_fields_field: usize, // 0 = label, 1 = value
+    pub custom_fields_show_values: bool,
+    // SSH host screen state
+    pub ssh_host_field: usize, // 0 = host, 1 = port, 2 = username
+    // One-line message shown in the main screen footer until the next key press
+    pub status_message: Option<String>,
+    // Entry awaiting delete confirmation on the main screen: (filename, display name)
+    pub delete_confirmation: Option<(String, String)>,
     // Mapping from displayed name to filename
     pub name_to_filename: Vec<(String, String)>, // (display_name, filename)
+    pub entry_tags: HashMap<String, Vec<String>>, // filename -> tags
+    pub entry_folders: HashMap<String, String>, // filename -> folder path (top level entries are absent)
+    // Архивные записи (имена файлов) и режим просмотра архива (Ctrl+G): вне его архивные скрыты,
+    // в нем видны только они
+    pub archived_entries: HashSet<String>,
+    pub show_archived: bool,
+    // Folder shown in the main list ("" = top level) and its subfolders, listed before the entries
+    pub current_folder: String,
+    pub visible_folders: Vec<String>,
+    // Move-to-folder dialog: (filename, name) of the entry and the folder path being typed
+    pub move_to_folder: Option<(String, String)>,
+    pub move_folder_input: String,
     // Clipboard cleanup task handle
     pub clipboard_cleanup_handle: Option<JoinHandle<()>>,
     // Persistent clipboard instance to avoid "dropped very quickly" warning
     pub clipboard: Option<Arc<StdMutex<Clipboard>>>,
+    /// Когда очистить буфер, заполненный через OSC 52 (очищает главный цикл, а не фоновая задача,
+    /// чтобы escape-последовательность не попала в середину отрисовки)
+    pub osc52_clear_at: Option<Instant>,
     // Settings screen state
     pub clipboard_timeout_input: String,
-    pub settings_field: usize, // 0 = directory, 1 = clipboard timeout, 2 = theme, 3 = language
+    pub settings_field: usize, // 0 = directory, 1 = clipboard timeout, 2 = theme, 3 = language, 4 = change master password, 5 = screen reader mode, 6 = keychain unlock
+    // Change master password screen state
+    pub change_master_old: String,
+    pub change_master_new: String,
+    pub change_master_confirm: String,
+    pub change_master_hint: String,
+    pub change_master_field: usize, // 0 = old, 1 = new, 2 = confirm, 3 = hint, 4-6 = Argon2
+    /// Параметры Argon2id для нового ключа: память в МиБ, проходы, потоки
+    pub change_master_argon2: [String; 3],
+    pub change_master_error: Option<String>,
+    // Backup screen state
+    pub backup_path_input: String,
+    pub backup_passphrase: String,
+    pub backup_confirm: String,
+    pub backup_field: usize, // 0 = path, 1 = passphrase, 2 = confirm
+    pub backup_error: Option<String>,
+    // Restore screen state: archives list, then re-authentication for the selected one
+    pub restore_backups: Vec<backup::BackupInfo>,
+    pub restore_picker: ListPicker,
+    pub restore_selected: Option<usize>,
+    pub restore_master: String,
+    pub restore_passphrase: String,
+    pub restore_field: usize, // 0 = master password, 1 = archive passphrase
+    pub restore_error: Option<String>,
+    // Trash screen state: deleted entries (filename, name, deleted at), newest first
+    pub trash_items: Vec<(String, String, DateTime<Utc>)>,
+    pub trash_picker: ListPicker,
+    pub trash_purge_confirm: bool,
+    pub trash_message: Option<String>,
+    pub trash_error: Option<String>,
+    // Git sync screen state
+    pub sync_remote_input: String,
+    pub sync_conflicts: Vec<String>, // entry names (or files) changed on both sides
+    pub sync_message: Option<String>,
+    pub sync_error: Option<String>,
     // Theme selection screen state
-    pub theme_selection_index: usize, // 0 = textual_dark, 1 = vscode_style, 2 = opencode_style
+    pub theme_picker: ListPicker, // index into THEMES
     // Language selection screen state
-    pub language_selection_index: usize, // 0 = Russian, 1 = English (default), 2 = Chinese
+    pub language_picker: ListPicker, // index into Language::all()
     // Localization
     pub i18n: I18n,
+    // Возможности терминала (режим совместимости для старых консолей)
+    pub console: ConsoleCaps,
     // Password generator screen state
     pub password_generator_length: String,
     pub password_generator_exclude_chars: String,
+    /// Свой набор символов в дополнение к галочкам
+    pub password_generator_custom_chars: String,
     pub password_generator_use_uppercase: bool,
     pub password_generator_use_lowercase: bool,
     pub password_generator_use_digits: bool,
     pub password_generator_use_special: bool,
-    pub password_generator_selected_field: usize, // 0 = length, 1 = exclude_chars, 2-5 = checkboxes
+    pub password_generator_use_cyrillic: bool,
+    /// Фраза из слов словаря языка интерфейса вместо случайных символов
+    pub password_generator_passphrase: bool,
+    /// Без похожих символов (O/0, l/1/I, S/5)
+    pub password_generator_exclude_ambiguous: bool,
+    pub password_generator_selected_field: usize, // 0 = length, 1 = exclude_chars, 2 = custom_chars, 3-9 = checkboxes, 10-12 = minimums
+    /// Не менее стольких цифр, спецсимволов и заглавных букв
+    pub password_generator_min_digits: usize,
+    pub password_generator_min_special: usize,
+    pub password_generator_min_uppercase: usize,
+    /// Почему не удалось сгенерировать пароль с текущими настройками
+    pub password_generator_error: Option<String>,
+    /// Открыт список пресетов генератора (Ctrl+P)
+    pub password_generator_presets_open: bool,
+    pub password_generator_preset_picker: ListPicker,
+    /// Имя нового пресета, пока открыт диалог сохранения (Ctrl+S), и ошибка сохранения
+    pub password_generator_preset_name: Option<String>,
+    pub password_generator_preset_error: Option<String>,
+    // Audit screen state: groups of entries sharing the same password
+    pub audit_reuse_groups: Vec<ReuseCluster>,
+    // Entries found in the offline breach dataset: (name, times seen)
+    pub audit_breached: Vec<(String, u64)>,
+    // Breach check status line (not configured / dataset error)
+    pub audit_breach_status: Option<String>,
+    // Rotation workflow state: flagged entries (filename, name) and the current one
+    pub rotation_queue: Vec<(String, String)>,
+    pub rotation_index: usize,
+    pub rotation_old_password: String,
+    pub rotation_new_password: String,
+    pub rotation_url: Option<String>,
+    pub rotation_policy: Option<PasswordPolicy>,
+    pub rotation_show_passwords: bool,
+    // Maintenance screen: dry-run report, then the result once confirmed
+    pub maintenance_report: Option<CompactionReport>,
+    pub maintenance_done: bool,
+    pub maintenance_error: Option<String>,
+    /// Результат проверки целостности (V на экране обслуживания)
+    pub maintenance_integrity: Option<IntegrityReport>,
+    /// Мастер-пароль для смены ключа шифрования (`Some`, пока открыт запрос)
+    pub rotate_key_password: Option<String>,
+    pub rotate_key_error: Option<String>,
+    /// Осиротевшие файлы и записи (O на экране обслуживания)
+    pub orphans: Vec<Orphan>,
+    pub orphans_picker: ListPicker,
+    pub orphans_delete_confirm: bool,
+    pub orphans_message: Option<String>,
+    pub orphans_error: Option<String>,
+    // CSV import wizard: file path, parsed table, column mapping and result
+    pub import_path_input: String,
+    pub import_table: Option<CsvTable>,
+    pub import_mapping: Vec<ImportField>,
+    pub import_column: usize,
+    // The mapping was restored from a previous import of the same format
+    pub import_mapping_restored: bool,
+    pub import_report: Option<ImportReport>,
+    pub import_error: Option<String>,
 }
 
+impl TuiState {
+    /// Число строк главного списка: папки, затем записи
+    pub fn list_len(&self) -> usize {
+        self.visible_folders.len() + self.filtered_items.len()
+    }
+
+    /// Начальное состояние TUI: экран мастер-пароля (создание или ввод)
+    pub fn new(config: Config, is_creating_master_password: bool) -> Self {
+        // Initialize i18n
+        let language = Language::from_code(&config.language);
+        let i18n = I18n::new(language);
+
+        Self {
+            should_quit: false,
+            selected_index: 0,
+            current_screen: Screen::MasterPassword,
+            passwords_dir_input: config
+                .passwords_directory
+                .as_ref()
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            config: config.clone(),
+            search_query: String::new(),
+            all_items: Vec::new(),
+            filtered_items: Vec::new(),
+            master_password_input: String::new(),
+            master_password_confirm: String::new(),
+            master_password_field: 0,
+            master_password_show_password: false,
+            master_password_hint_input: String::new(),
+            master_password_failed_attempts: 0,
+            master_password_hint: None,
+            is_creating_master_password,
+            encryption_key: None,
+            password_entry_name: String::new(),
+            password_entry_username: String::new(),
+            password_entry_password: String::new(),
+            password_entry_url: String::new(),
+            password_entry_tags: String::new(),
+            password_entry_notes: String::new(),
+            password_entry_show_password: false,
+            password_entry_field: 0,
+            password_entry_reuse_warning: None,
+            password_entry_security_questions: Vec::new(),
+            password_entry_custom_fields: Vec::new(),
+            password_entry_ssh_host: String::new(),
+            password_entry_ssh_port: String::new(),
+            password_entry_ssh_username: String::new(),
+            password_entry_autotype: String::new(),
+            password_entry_access_window: String::new(),
+            password_entry_policy: None,
+            password_entry_timestamps: EntryTimestamps::default(),
+            password_entry_tray_shortcut: false,
+            password_entry_editor_error: None,
+            tray_pending_copy: None,
+            instance_pending_copy: None,
+            entry_metadata: MetadataHandle::default(),
+            pairing: PairingHandle::default(),
+            pairing_announced: None,
+            auth_throttle: AuthThrottle::default(),
+            auth_failures_announced: 0,
+            server_port: None,
+            server_tls: None,
+            vault_stats: VaultStats::default(),
+            about: None,
+            policy: VaultPolicy::default(),
+            master_password_error: None,
+            metadata_scrubbed: false,
+            scrubbed_key: None,
+            name_loader: None,
+            names_loading: None,
+            pending_list_position: None,
+            macros: Macros::default(),
+            security_questions_index: 0,
+            security_questions_field: 0,
+            security_questions_show_answers: false,
+            custom_fields_index: 0,
+            custom_fields_field: 0,
+            custom_fields_show_values: false,
+            ssh_host_field: 0,
+            status_message: None,
+            delete_confirmation: None,
+            name_to_filename: Vec::new(),
+            entry_tags: HashMap::new(),
+            entry_folders: HashMap::new(),
+            archived_entries: HashSet::new(),
+            show_archived: false,
+            current_folder: String::new(),
+            visible_folders: Vec::new(),
+            move_to_folder: None,
+            move_folder_input: String::new(),
+            clipboard_cleanup_handle: None,
+            clipboard: None,
+            osc52_clear_at: None,
+            clipboard_timeout_input: config.clipboard_timeout_seconds.to_string(),
+            settings_field: 0,
+            change_master_old: String::new(),
+            change_master_new: String::new(),
+            change_master_confirm: String::new(),
+            change_master_hint: String::new(),
+            change_master_field: 0,
+            change_master_argon2: Default::default(),
+            change_master_error: None,
+            backup_path_input: String::new(),
+            backup_passphrase: String::new(),
+            backup_confirm: String::new(),
+            backup_field: 0,
+            backup_error: None,
+            restore_backups: Vec::new(),
+            restore_picker: ListPicker::default(),
+            trash_items: Vec::new(),
+            trash_picker: ListPicker::default(),
+            trash_purge_confirm: false,
+            trash_message: None,
+            trash_error: None,
+            restore_selected: None,
+            restore_master: String::new(),
+            restore_passphrase: String::new(),
+            restore_field: 0,
+            restore_error: None,
+            sync_remote_input: String::new(),
+            sync_conflicts: Vec::new(),
+            sync_message: None,
+            sync_error: None,
+            theme_picker: ListPicker::new(
+                THEMES.iter().position(|(_, id, _)| *id == config.theme).unwrap_or(0), // textual_dark по умолчанию
+            ),
+            password_generator_length: String::new(),
+            password_generator_exclude_chars: String::new(),
+            password_generator_custom_chars: String::new(),
+            password_generator_use_uppercase: true,
+            password_generator_use_lowercase: true,
+            password_generator_use_digits: true,
+            password_generator_use_special: false,
+            password_generator_use_cyrillic: false,
+            password_generator_passphrase: false,
+            password_generator_exclude_ambiguous: false,
+            password_generator_selected_field: 0,
+            password_generator_min_digits: 0,
+            password_generator_min_special: 0,
+            password_generator_min_uppercase: 0,
+            password_generator_error: None,
+            password_generator_presets_open: false,
+            password_generator_preset_picker: ListPicker::default(),
+            password_generator_preset_name: None,
+            password_generator_preset_error: None,
+            language_picker: ListPicker::new(
+                Language::all().iter().position(|l| l.to_code() == config.language).unwrap_or(1), // English by default
+            ),
+            i18n,
+            console: ConsoleCaps::from_mode(&config.console_mode),
+            audit_reuse_groups: Vec::new(),
+            audit_breached: Vec::new(),
+            audit_breach_status: None,
+            rotation_queue: Vec::new(),
+            rotation_index: 0,
+            rotation_old_password: String::new(),
+            rotation_new_password: String::new(),
+            rotation_url: None,
+            rotation_policy: None,
+            rotation_show_passwords: false,
+            maintenance_report: None,
+            maintenance_done: false,
+            maintenance_error: None,
+            maintenance_integrity: None,
+            rotate_key_password: None,
+            orphans: Vec::new(),
+            orphans_picker: ListPicker::default(),
+            orphans_delete_confirm: false,
+            orphans_message: None,
+            orphans_error: None,
+            rotate_key_error: None,
+            import_path_input: String::new(),
+            import_table: None,
+            import_mapping: Vec::new(),
+            import_column: 0,
+            import_mapping_restored: false,
+            import_report: None,
+            import_error: None,
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub async fn run_tui(
     crypto: CryptoManager,
-    _tray: TrayHandle,
+    tray: TrayHandle,
+    instance: InstanceHandle,
+    entry_metadata: MetadataHandle,
+    pairing: PairingHandle,
+    auth_throttle: AuthThrottle,
+    server: Option<RuntimeInfo>,
     config: Config,
     shutdown_tx: watch::Sender<()>,
 ) -> RpmResult<()> {
@@ -104,74 +561,251 @@ pub async fn run_tui(
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let mut storage = PasswordStorage::new(&config, crypto.clone());
+    let mut storage = PasswordStorage::new(&config, crypto.clone()).with_events(entry_metadata.events().clone());
 
     // Check if master password is already set for the current directory
     let passwords_dir = config.passwords_directory_path();
     let dir_config = DirectoryConfig::load(&passwords_dir)
-        .unwrap_or_else(|_| DirectoryConfig {
-            master_password_hash: None,
-            encryption_key_salt: None,
-        });
+        .unwrap_or_default();
     let is_creating_master_password = !dir_config.has_master_password();
 
-    // Initialize i18n
-    let language = Language::from_code(&config.language);
-    let i18n = I18n::new(language);
-
-    let mut state = TuiState {
-        should_quit: false,
-        selected_index: 0,
-        current_screen: Screen::MasterPassword,
-        passwords_dir_input: config
-            .passwords_directory
-            .as_ref()
-            .map(|p| p.to_string_lossy().to_string())
-            .unwrap_or_default(),
-        config: config.clone(),
-        search_query: String::new(),
-        all_items: Vec::new(),
-        filtered_items: Vec::new(),
-        master_password_input: String::new(),
-        master_password_confirm: String::new(),
-        master_password_field: 0,
-        master_password_show_password: false,
-        is_creating_master_password,
-        encryption_key: None,
-        password_entry_name: String::new(),
-        password_entry_password: String::new(),
-        password_entry_show_password: false,
-        password_entry_field: 0,
-        name_to_filename: Vec::new(),
-        clipboard_cleanup_handle: None,
-        clipboard: None,
-        clipboard_timeout_input: config.clipboard_timeout_seconds.to_string(),
-        settings_field: 0,
-        theme_selection_index: match config.theme.as_str() {
-            "vscode_style" => 1,
-            "opencode_style" => 2,
-            _ => 0, // textual_dark по умолчанию
-        },
-        password_generator_length: String::new(),
-        password_generator_exclude_chars: String::new(),
-        password_generator_use_uppercase: true,
-        password_generator_use_lowercase: true,
-        password_generator_use_digits: true,
-        password_generator_use_special: false,
-        password_generator_selected_field: 0,
-        language_selection_index: match config.language.as_str() {
-            "ru" => 0,
-            "zh" => 2,
-            _ => 1, // English by default
-        },
-        i18n,
-    };
+    let mut state = TuiState::new(config, is_creating_master_password);
+    state.policy = dir_config.policy;
+    state.entry_metadata = entry_metadata;
+    if state.config.git_sync || state.config.webdav_url.is_some() {
+        state.vault_stats.sync = SyncStatus::Idle;
+    }
+    state.pairing = pairing;
+    state.auth_throttle = auth_throttle;
+    if let Some(server) = server {
+        state.server_port = server.port;
+        state.server_tls = server.tls_port.zip(server.tls_fingerprint);
+    }
     let mut list_state = ListState::default();
+    // Ключ из связки ключей ОС: мастер-пароль при запуске не спрашивается
+    if !state.is_creating_master_password && state.config.keychain_unlock {
+        if let Some(key) = load_key_from_keychain(&mut state, &crypto) {
+            open_vault(&mut state, &storage, &crypto, key, &mut list_state);
+        }
+    }
+    let mut last_activity = Instant::now();
+
+    // Расписание экспорта читается один раз: в TUI оно не редактируется
+    let export_schedule = match export::configured_schedule(&state.config) {
+        Ok(schedule) => schedule,
+        Err(e) => {
+            state.status_message = Some(format!("{} {}", state.i18n.ts("export_failed"), e));
+            None
+        }
+    };
+    // Неудачная попытка тоже откладывает экспорт до следующего срока по расписанию
+    let mut export_last_attempt = None;
+    // Команда загрузки последнего экспорта, выполняемая в фоне
+    let mut export_upload: Option<JoinHandle<RpmResult<()>>> = None;
+    // Когда перечитана дата резервной копии и какая статистика уже отдана API и трею
+    let mut stats_checked_at: Option<Instant> = None;
+    let mut published_stats = None;
+    // События хранилища и признак того, что список имен надо перечитать
+    let mut vault_events = state.entry_metadata.subscribe();
+    let mut names_stale = false;
 
     loop {
+        // Автоблокировка: по истечении времени бездействия забываем ключ и возвращаемся к вводу мастер-пароля
+        let auto_lock_seconds = state.policy.auto_lock_seconds(state.config.auto_lock_seconds);
+        if (state.encryption_key.is_some() || state.scrubbed_key.is_some())
+            && auto_lock_seconds > 0
+            && last_activity.elapsed() >= Duration::from_secs(auto_lock_seconds)
+        {
+            save_ui_state(&state, &storage, &list_state);
+            lock_vault(&mut state);
+            list_state.select(None);
+        }
+
+        // Буфер обмена терминала очищается здесь, между отрисовками
+        if state.osc52_clear_at.is_some_and(|at| Instant::now() >= at) {
+            state.osc52_clear_at = None;
+            let _ = clipboard::write_osc52("");
+        }
+
+        // Раньше блокировки стираем расшифрованные имена, если главный экран простаивает
+        if state.encryption_key.is_some()
+            && !state.metadata_scrubbed
+            && state.current_screen == Screen::Main
+            && state.delete_confirmation.is_none()
+            && state.config.metadata_scrub_seconds > 0
+            && last_activity.elapsed() >= Duration::from_secs(state.config.metadata_scrub_seconds)
+        {
+            scrub_metadata(&mut state, &crypto);
+            list_state.select(None);
+        }
+
+        // Плановый экспорт выполняется, только пока хранилище разблокировано
+        if let (Some(schedule), Some(key)) = (export_schedule.as_ref(), state.encryption_key.as_ref()) {
+            let now = Utc::now();
+            if schedule.is_due(state.config.export_last_run.max(export_last_attempt), now) {
+                export_last_attempt = Some(now);
+                state.status_message = Some(match export::run_export(&storage, &state.config, key.as_slice()) {
+                    Ok(path) => {
+                        state.config.export_last_run = Some(now);
+                        if let Err(e) = state.config.save() {
+                            eprintln!("Failed to save config: {}", e);
+                        }
+                        Webhooks::from_config(&state.config).fire(WebhookEvent::export(&path));
+                        // Загрузка может идти долго, поэтому не в этом цикле
+                        if let Some(command) = state.config.export_upload_command.clone() {
+                            let path = path.clone();
+                            export_upload = Some(tokio::task::spawn_blocking(move || export::upload(&command, &path)));
+                        }
+                        format!("{} {}", state.i18n.ts("export_done"), path.display())
+                    }
+                    Err(e) => format!("{} {}", state.i18n.ts("export_failed"), e),
+                });
+            }
+        }
+
+        if export_upload.as_ref().is_some_and(|upload| upload.is_finished()) {
+            if let Some(upload) = export_upload.take() {
+                let result = upload.await.map_err(|e| e.to_string()).and_then(|uploaded| uploaded.map_err(|e| e.to_string()));
+                if let Err(e) = result {
+                    state.status_message = Some(format!("{} {}", state.i18n.ts("export_failed"), e));
+                }
+            }
+        }
+
+        // Резервные копии делает и фоновый планировщик, поэтому дата перечитывается с диска
+        if stats_checked_at.is_none_or(|at| at.elapsed() >= STATS_REFRESH_INTERVAL) {
+            stats_checked_at = Some(Instant::now());
+            state.vault_stats.last_backup_at = backup::list_backups(&state.config.backup_directory_path())
+                .ok()
+                .and_then(|backups| backups.first().map(|info| info.created_at));
+        }
+        if published_stats.as_ref() != Some(&state.vault_stats) {
+            state.entry_metadata.publish_stats(state.vault_stats.clone());
+            tray.set_tooltip(tray_tooltip(&state));
+            published_stats = Some(state.vault_stats.clone());
+        }
+
+        // Записи, выбранные в меню трея: копируем сразу или после ввода мастер-пароля
+        for filename in tray.take_copy_requests() {
+            if let Some(shortcut) = tray.shortcuts().into_iter().find(|s| s.filename == filename) {
+                state.tray_pending_copy = Some(shortcut);
+            }
+        }
+        if state.tray_pending_copy.is_some() && state.metadata_scrubbed {
+            resume_after_scrub(&mut state, &storage, &crypto, &mut list_state);
+        }
+        if state.encryption_key.is_some() {
+            if let Some(shortcut) = state.tray_pending_copy.take() {
+                copy_shortcut_password(&mut state, &storage, &shortcut, "tray_copied");
+            }
+        }
+
+        // Запросы от повторного запуска rpm: показать это окно или скопировать запись по имени
+        for request in instance.take_requests() {
+            match request {
+                InstanceRequest::Focus => {
+                    state.status_message = Some(state.i18n.ts("instance_focus_requested").to_string());
+                    // Звонок терминала: многие эмуляторы помечают окно как требующее внимания
+                    print!("\x07");
+                    let _ = io::Write::flush(&mut io::stdout());
+                }
+                InstanceRequest::Copy(name) => state.instance_pending_copy = Some(name),
+            }
+        }
+        if state.instance_pending_copy.is_some() && state.metadata_scrubbed {
+            resume_after_scrub(&mut state, &storage, &crypto, &mut list_state);
+        }
+        if state.encryption_key.is_some() {
+            if let Some(name) = state.instance_pending_copy.take() {
+                copy_requested_entry(&mut state, &storage, &name);
+            }
+        }
+
+        // Новый запрос сопряжения: звонок и сообщение, один раз на запрос
+        if let Some(request) = pending_pairing(&state) {
+            if state.pairing_announced.as_deref() != Some(request.id.as_str()) {
+                state.status_message = Some(state.i18n.ts("pairing_requested").to_string());
+                state.pairing_announced = Some(request.id);
+                print!("\x07");
+                let _ = io::Write::flush(&mut io::stdout());
+            }
+        }
+
+        // Счетчик неверных мастер-паролей через API обновляется в строке статуса
+        let auth_failures = state.auth_throttle.failed_attempts();
+        if auth_failures != state.auth_failures_announced {
+            state.auth_failures_announced = auth_failures;
+            state.status_message = Some(api_auth_failures_message(&state));
+        }
+
+        // Имена из фоновой загрузки появляются в списке по мере расшифровки
+        if let Some(update) = state.name_loader.as_ref().map(NameLoader::poll) {
+            apply_loaded_names(&mut state, &storage, &tray, update);
+            if list_state.selected().is_none() && state.list_len() > 0 {
+                state.selected_index = 0;
+                list_state.select(Some(0));
+            }
+            if state.name_loader.is_none() {
+                apply_pending_list_position(&mut state, &mut list_state);
+            }
+        }
+
+        // Изменения хранилища из API, синхронизации и самого TUI
+        follow_vault_events(&mut state, &storage, &mut list_state, &mut vault_events, &mut names_stale);
+
+        if let Some(entries) = state.macros.take_finished() {
+            state.status_message = Some(format!("{} {}", state.i18n.ts("macro_replayed"), entries));
+        }
+
         terminal.draw(|f| ui(f, &state, &mut list_state))?;
 
-        if let Event::Key(key) = event::read()? {
+        // Клавиши повторяемого макроса обрабатываются так же, как нажатые
+        let event = match next_replayed_key(&mut state, &mut list_state)? {
+            Some(key) => Event::Key(key),
+            None => {
+                // Ждем событие с таймаутом, чтобы таймер автоблокировки срабатывал без ввода;
+                // пока имена загружаются, список обновляется чаще
+                let poll_timeout = if state.name_loader.is_some() { 50 } else { 500 };
+                if !event::poll(Duration::from_millis(poll_timeout))? {
+                    continue;
+                }
+                event::read()?
+            }
+        };
+
+        if let Event::Key(key) = event {
+            last_activity = Instant::now();
+            if state.metadata_scrubbed {
+                resume_after_scrub(&mut state, &storage, &crypto, &mut list_state);
+            }
+            // Ctrl+Z на любом экране: вернуть терминал оболочке и продолжить после `fg`
+            if key.kind == KeyEventKind::Press
+                && key.modifiers.contains(KeyModifiers::CONTROL)
+                && key.code == KeyCode::Char('z')
+            {
+                suspend::suspend(&mut terminal, state.i18n.ts("shell_escape_banner"))?;
+                continue;
+            }
+            // Диалог сопряжения перехватывает клавиши, пока пользователь не ответит
+            if key.kind == KeyEventKind::Press {
+                if let Some(request) = pending_pairing(&state) {
+                    match key.code {
+                        KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') => {
+                            state.pairing.approve(&request.id);
+                            state.status_message = Some(state.i18n.ts("pairing_approved").to_string());
+                        }
+                        KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('N') => {
+                            state.pairing.deny(&request.id);
+                            state.status_message = Some(state.i18n.ts("pairing_denied").to_string());
+                        }
+                        _ => {}
+                    }
+                    continue;
+                }
+            }
+            if key.kind == KeyEventKind::Press && handle_macro_key(&mut state, key) {
+                continue;
+            }
             if key.kind == KeyEventKind::Press {
                 match state.current_screen.clone() {
                     Screen::MasterPassword => {
@@ -197,7 +831,7 @@ pub async fn run_tui(
                                         }
                                         
                                         // Пересоздаем storage с новой директорией
-                                        storage = PasswordStorage::new(&state.config, crypto.clone());
+                                        storage = open_storage(&state, &crypto);
                                         
                                         // Move to password field
                                         state.master_password_field = 1;
@@ -227,24 +861,48 @@ pub async fn run_tui(
                                         }
                                         
                                         // Пересоздаем storage с правильной директорией
-                                        storage = PasswordStorage::new(&state.config, crypto.clone());
+                                        storage = open_storage(&state, &crypto);
 
                                         // Save master password hash to directory config
                                         let passwords_dir = state.config.passwords_directory_path();
                                         let mut dir_config = DirectoryConfig::load(&passwords_dir)
-                                            .unwrap_or_else(|_| DirectoryConfig {
-                                                master_password_hash: None,
-                                                encryption_key_salt: None,
-                                            });
-                                        
-                                        let hash = crypto.hash_password(&state.master_password_input)?;
+                                            .unwrap_or_default();
+                                        state.policy = dir_config.policy.clone();
+
+                                        // Политику могли положить в директорию заранее, до создания хранилища
+                                        if state.policy.check_master_password(&state.master_password_input).is_err() {
+                                            state.master_password_error = Some(policy_password_too_short(&state));
+                                            state.master_password_input.clear();
+                                            state.master_password_confirm.clear();
+                                            state.master_password_field = 1;
+                                            continue;
+                                        }
+
+                                        // Подсказка лежит открытым текстом: пароль в ней не допускаем
+                                        if hint_reveals_password(&state.master_password_hint_input, &state.master_password_input) {
+                                            state.master_password_error = Some(state.i18n.ts("master_password_hint_reveals").to_string());
+                                            state.master_password_field = 3;
+                                            continue;
+                                        }
+                                        dir_config.password_hint = non_empty(&state.master_password_hint_input);
+
+                                        // Параметры Argon2 под этот компьютер, если администратор не задал свои
+                                        if dir_config.kdf.is_default() && state.config.kdf_calibration_ms > 0 {
+                                            match key_derivation::calibrate(Duration::from_millis(state.config.kdf_calibration_ms)) {
+                                                Ok(argon2) => dir_config.kdf = KdfParams::Argon2id(argon2),
+                                                Err(e) => eprintln!("KDF calibration failed: {}", e),
+                                            }
+                                        }
+
+                                        // Хэш с теми же параметрами, что и ключ: проверка догадки по нему не дешевле
+                                        let hash = crypto.hash_password(&state.master_password_input, &dir_config.kdf.argon2())?;
                                         dir_config.master_password_hash = Some(hash);
-                                        
+
                                         // Generate salt if not exists
                                         if dir_config.encryption_key_salt.is_none() {
                                             let mut salt_bytes = [0u8; 32];
                                             rand::thread_rng().fill_bytes(&mut salt_bytes);
-                                            dir_config.encryption_key_salt = Some(BASE64_STANDARD_NO_PAD.encode(&salt_bytes));
+                                            dir_config.encryption_key_salt = Some(BASE64_STANDARD_NO_PAD.encode(salt_bytes));
                                         }
                                         
                                         if let Err(e) = dir_config.save(&passwords_dir) {
@@ -256,19 +914,29 @@ pub async fn run_tui(
                                     // Verify password against directory config
                                     let passwords_dir = state.config.passwords_directory_path();
                                     let dir_config = DirectoryConfig::load(&passwords_dir)
-                                        .unwrap_or_else(|_| DirectoryConfig {
-                                            master_password_hash: None,
-                                            encryption_key_salt: None,
-                                        });
-                                    
+                                        .unwrap_or_default();
+                                    state.policy = dir_config.policy.clone();
+
+                                    if state.policy.check_unlock().is_err() {
+                                        state.master_password_error = Some(state.i18n.ts("policy_keyfile_required").to_string());
+                                        state.master_password_input.clear();
+                                        continue;
+                                    }
+
                                     if let Some(ref stored_hash) = dir_config.master_password_hash {
                                         match crypto.verify_password(&state.master_password_input, stored_hash) {
                                             Ok(true) => {
                                                 // Password correct
+                                                state.master_password_failed_attempts = 0;
+                                                state.master_password_hint = None;
                                             }
                                             Ok(false) => {
-                                                // Password incorrect, reset
+                                                // Password incorrect, reset; after two attempts show the hint
                                                 state.master_password_input.clear();
+                                                state.master_password_failed_attempts += 1;
+                                                if state.master_password_failed_attempts >= 2 {
+                                                    state.master_password_hint = dir_config.password_hint.clone();
+                                                }
                                                 continue;
                                             }
                                             Err(_) => {
@@ -287,10 +955,7 @@ pub async fn run_tui(
                                 // Derive encryption key from master password
                                 let passwords_dir = state.config.passwords_directory_path();
                                 let dir_config = DirectoryConfig::load(&passwords_dir)
-                                    .unwrap_or_else(|_| DirectoryConfig {
-                                        master_password_hash: None,
-                                        encryption_key_salt: None,
-                                    });
+                                    .unwrap_or_default();
                                 
                                 let salt = if let Some(salt_str) = &dir_config.encryption_key_salt {
                                     // Try decoding without padding first (new format), then with padding (old format for compatibility)
@@ -301,12 +966,9 @@ pub async fn run_tui(
                                     // Generate new salt (should not happen if creating, but handle it)
                                     let mut salt_bytes = [0u8; 32];
                                     rand::thread_rng().fill_bytes(&mut salt_bytes);
-                                    let salt_str = BASE64_STANDARD_NO_PAD.encode(&salt_bytes);
+                                    let salt_str = BASE64_STANDARD_NO_PAD.encode(salt_bytes);
                                     let mut dir_config = DirectoryConfig::load(&passwords_dir)
-                                        .unwrap_or_else(|_| DirectoryConfig {
-                                            master_password_hash: None,
-                                            encryption_key_salt: None,
-                                        });
+                                        .unwrap_or_default();
                                     dir_config.encryption_key_salt = Some(salt_str.clone());
                                     if let Err(e) = dir_config.save(&passwords_dir) {
                                         eprintln!("Failed to save directory config: {}", e);
@@ -314,57 +976,38 @@ pub async fn run_tui(
                                     salt_bytes.to_vec()
                                 };
 
-                                let key = key_derivation::derive_key(&state.master_password_input, Some(&salt))?;
-                                state.encryption_key = Some(SecureKey::new(key));
+                                let key = SecureKey::new(dir_config.kdf.kdf().derive_key(&state.master_password_input, &salt)?);
 
                                 // Clear master password from memory
                                 state.master_password_input.zeroize();
                                 state.master_password_input.clear();
                                 state.master_password_confirm.zeroize();
                                 state.master_password_confirm.clear();
+                                state.master_password_hint_input.clear();
 
-                                // Load def file and decrypt names
-                                if let Some(ref key) = state.encryption_key {
-                                    match storage.list_decrypted_names(key.as_slice()) {
-                                        Ok(names) => {
-                                            state.name_to_filename = names.clone();
-                                            state.all_items = names.iter().map(|(_, name)| name.clone()).collect();
-                                            state.filtered_items = state.all_items.clone();
-                                        }
-                                        Err(_) => {
-                                            // Empty list if def file doesn't exist or can't be decrypted
-                                            state.all_items = Vec::new();
-                                            state.filtered_items = Vec::new();
-                                        }
-                                    }
-                                }
-
-                                state.current_screen = Screen::Main;
-                                if !state.filtered_items.is_empty() {
-                                    list_state.select(Some(0));
+                                if state.config.keychain_unlock {
+                                    save_key_to_keychain(&mut state, &crypto, key.as_slice());
                                 }
+                                open_vault(&mut state, &storage, &crypto, key, &mut list_state);
                             }
-                            KeyCode::Up => {
-                                if state.is_creating_master_password {
-                                    // Switch between directory, password and confirm fields (backward)
-                                    if state.master_password_field > 0 {
-                                        state.master_password_field -= 1;
-                                    } else {
-                                        state.master_password_field = 2; // Wrap to last field
-                                    }
+                            KeyCode::Up if state.is_creating_master_password => {
+                                // Switch between directory, password, confirm and hint fields (backward)
+                                if state.master_password_field > 0 {
+                                    state.master_password_field -= 1;
+                                } else {
+                                    state.master_password_field = 3; // Wrap to last field
                                 }
                             }
-                            KeyCode::Down => {
-                                if state.is_creating_master_password {
-                                    // Switch between directory, password and confirm fields (forward)
-                                    state.master_password_field = (state.master_password_field + 1) % 3;
-                                }
+                            KeyCode::Down if state.is_creating_master_password => {
+                                // Switch between directory, password, confirm and hint fields (forward)
+                                state.master_password_field = (state.master_password_field + 1) % 4;
                             }
                             KeyCode::Esc => {
                                 state.should_quit = true;
                                 let _ = shutdown_tx.send(());
                             }
                             KeyCode::Backspace => {
+                                state.master_password_error = None;
                                 if state.is_creating_master_password {
                                     match state.master_password_field {
                                         0 => {
@@ -376,7 +1019,9 @@ pub async fn run_tui(
                                         2 => {
                                             state.master_password_confirm.pop();
                                         }
-                                        _ => {}
+                                        _ => {
+                                            state.master_password_hint_input.pop();
+                                        }
                                     }
                                 } else {
                                     // Entering existing password - only one field
@@ -386,11 +1031,12 @@ pub async fn run_tui(
                             KeyCode::Char(c) => {
                                 // Handle Ctrl+H for password visibility (only for password fields, not directory)
                                 if key.modifiers.contains(KeyModifiers::CONTROL) && c == 'h' {
-                                    if !state.is_creating_master_password || state.master_password_field != 0 {
+                                    if !state.is_creating_master_password || matches!(state.master_password_field, 1 | 2) {
                                         state.master_password_show_password = !state.master_password_show_password;
                                     }
                                 } else if !key.modifiers.contains(KeyModifiers::CONTROL) {
                                     // Only process regular characters without Ctrl modifier
+                                    state.master_password_error = None;
                                     if state.is_creating_master_password {
                                         match state.master_password_field {
                                             0 => {
@@ -402,7 +1048,9 @@ pub async fn run_tui(
                                             2 => {
                                                 state.master_password_confirm.push(c);
                                             }
-                                            _ => {}
+                                            _ => {
+                                                state.master_password_hint_input.push(c);
+                                            }
                                         }
                                     } else {
                                         // Entering existing password - only one field
@@ -431,14 +1079,62 @@ pub async fn run_tui(
                         // Проверяем F1 для открытия help
                         else if key.code == KeyCode::F(1) {
                             state.current_screen = Screen::Help;
+                        }
+                        // F3 - аудит (переиспользованные пароли), с возвратом в редактор
+                        else if key.code == KeyCode::F(3) {
+                            open_audit(&mut state, &storage, Screen::PasswordEntry { is_edit, filename: filename.clone() });
+                        }
+                        // Ctrl+T - закрепить запись в меню трея (сохраняется вместе с записью)
+                        else if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('t') {
+                            state.password_entry_tray_shortcut = !state.password_entry_tray_shortcut;
+                        }
+                        // Ctrl+E - редактировать заметки во внешнем редакторе ($EDITOR)
+                        else if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('e') {
+                            match editor::edit_notes(&mut terminal, &state.password_entry_notes) {
+                                Ok(notes) => {
+                                    state.password_entry_notes.zeroize();
+                                    state.password_entry_notes = notes;
+                                    state.password_entry_field = 4;
+                                    state.password_entry_editor_error = None;
+                                }
+                                Err(e) => {
+                                    state.password_entry_editor_error = Some(format!("{} {}", state.i18n.ts("password_entry_editor_failed"), e));
+                                }
+                            }
+                            // Пока был открыт редактор, таймер бездействия не должен блокировать хранилище
+                            last_activity = Instant::now();
+                        }
+                        // F4 - контрольные вопросы записи
+                        else if key.code == KeyCode::F(4) {
+                            state.security_questions_index = 0;
+                            state.security_questions_field = 0;
+                            state.security_questions_show_answers = false;
+                            state.current_screen = Screen::SecurityQuestions { is_edit, filename: filename.clone() };
+                        }
+                        // F5 - дополнительные поля записи
+                        else if key.code == KeyCode::F(5) {
+                            state.custom_fields_index = 0;
+                            state.custom_fields_field = 0;
+                            state.custom_fields_show_values = false;
+                            state.current_screen = Screen::CustomFields { is_edit, filename: filename.clone() };
+                        }
+                        // F6 - SSH-подключение записи сервера
+                        else if key.code == KeyCode::F(6) {
+                            state.ssh_host_field = 0;
+                            state.current_screen = Screen::SshHost { is_edit, filename: filename.clone() };
+                        }
+                        // F7 - последовательность автонабора
+                        else if key.code == KeyCode::F(7) {
+                            state.current_screen = Screen::AutoType { is_edit, filename: filename.clone() };
+                        }
+                        // F8 - часы доступа через API
+                        else if key.code == KeyCode::F(8) {
+                            state.current_screen = Screen::AccessWindow { is_edit, filename: filename.clone() };
                         } else {
                             match key.code {
                             KeyCode::Esc => {
                                 // Cancel and return to main screen
-                                state.password_entry_name.clear();
-                                state.password_entry_password.clear();
-                                state.password_entry_show_password = false;
-                                state.password_entry_field = 0;
+                                reset_password_entry(&mut state);
                                 state.current_screen = Screen::Main;
                             }
                             KeyCode::Up => {
@@ -446,12 +1142,16 @@ pub async fn run_tui(
                                 if state.password_entry_field > 0 {
                                     state.password_entry_field -= 1;
                                 } else {
-                                    state.password_entry_field = 1; // Wrap to last field
+                                    state.password_entry_field = 5; // Wrap to last field
                                 }
                             }
                             KeyCode::Down => {
                                 // Switch between fields (forward)
-                                state.password_entry_field = (state.password_entry_field + 1) % 2;
+                                state.password_entry_field = (state.password_entry_field + 1) % 6;
+                            }
+                            KeyCode::Enter if state.password_entry_field == 4 && key.modifiers.contains(KeyModifiers::ALT) => {
+                                // Alt+Enter - new line in notes
+                                state.password_entry_notes.push('\n');
                             }
                             KeyCode::Enter => {
                                 // Save password
@@ -461,61 +1161,136 @@ pub async fn run_tui(
                                 }
 
                                 if let Some(ref key) = state.encryption_key {
+                                    // Поля, которые не редактируются на этом экране (флаг ротации,
+                                    // история паролей), берем из сохраненной записи
+                                    let mut details = match filename {
+                                        Some(ref filename) if is_edit => storage.load_entry_details(filename, key.as_slice()).unwrap_or_default(),
+                                        _ => EntryDetails::default(),
+                                    };
+                                    details.username = non_empty(&state.password_entry_username);
+                                    details.url = non_empty(&state.password_entry_url);
+                                    details.notes = non_empty(&state.password_entry_notes);
+                                    details.tray_shortcut = state.password_entry_tray_shortcut;
+                                    // Пары без вопроса и ответа не сохраняем
+                                    details.security_questions = state
+                                        .password_entry_security_questions
+                                        .iter()
+                                        .filter(|q| !q.question.trim().is_empty() || !q.answer.is_empty())
+                                        .cloned()
+                                        .collect();
+                                    details.custom_fields = state
+                                        .password_entry_custom_fields
+                                        .iter()
+                                        .filter(|f| !f.label.trim().is_empty() || !f.value.is_empty())
+                                        .cloned()
+                                        .collect();
+                                    details.ssh = entry_ssh_host(&state);
+                                    details.autotype_sequence = non_empty(&state.password_entry_autotype);
+                                    // Разобрано при выходе с экрана часов доступа
+                                    details.access_window = AccessWindow::parse(&state.password_entry_access_window).ok();
+                                    if let Some(ref policy) = state.password_entry_policy {
+                                        details.password_policy = Some(policy.clone());
+                                    }
+                                    let now = Utc::now();
+                                    if !is_edit {
+                                        details.timestamps.created_at = Some(now);
+                                    }
+                                    details.timestamps.updated_at = Some(now);
+
+                                    // Warn once if the password is already used by another entry;
+                                    // a second Enter saves anyway
+                                    if state.password_entry_reuse_warning.is_none() {
+                                        let reused_by = storage
+                                            .find_password_reuse(&state.password_entry_password, filename.as_deref(), key.as_slice())
+                                            .unwrap_or_default();
+                                        if !reused_by.is_empty() {
+                                            state.password_entry_reuse_warning = Some(reused_by);
+                                            continue;
+                                        }
+                                    }
+
                                     if is_edit {
                                         // Update existing entry
                                         if let Some(ref filename) = filename {
                                             // Update password file
                                             let _ = storage.update_password_file(filename, &state.password_entry_password, key.as_slice());
+                                            // Update username, URL and notes
+                                            let _ = storage.update_entry_details(filename, &details, key.as_slice());
                                             // Update name in def file
                                             let _ = storage.update_entry(filename, &state.password_entry_name, key.as_slice());
+                                            let _ = storage.set_entry_tags(filename, &parse_tags(&state.password_entry_tags), key.as_slice());
                                         }
                                     } else {
                                         // Create new entry
                                         let new_filename = storage.add_entry(&state.password_entry_name, key.as_slice())?;
                                         // Save password to the file with the generated filename
                                         let _ = storage.update_password_file(&new_filename, &state.password_entry_password, key.as_slice());
+                                        let _ = storage.update_entry_details(&new_filename, &details, key.as_slice());
+                                        let _ = storage.set_entry_tags(&new_filename, &parse_tags(&state.password_entry_tags), key.as_slice());
+                                        // Новая запись попадает в открытую папку
+                                        if !state.current_folder.is_empty() {
+                                            let _ = storage.set_entry_folder(&new_filename, &state.current_folder, key.as_slice());
+                                        }
                                     }
 
                                     // Reload list
-                                    match storage.list_decrypted_names(key.as_slice()) {
-                                        Ok(names) => {
-                                            state.name_to_filename = names.clone();
-                                            state.all_items = names.iter().map(|(_, name)| name.clone()).collect();
-                                            filter_items(&mut state);
-                                        }
-                                        Err(_) => {}
+                                    if let Ok(names) = storage.list_decrypted_names(key.as_slice()) {
+                                        state.name_to_filename = names.clone();
+                                        state.all_items = names.iter().map(|(_, name)| name.clone()).collect();
+                                        load_entry_metadata(&mut state, &storage);
+                                        filter_items(&mut state);
                                     }
+                                    refresh_tray_shortcuts(&state, &storage, &tray);
 
                                     // Clear and return to main
-                                    state.password_entry_name.clear();
-                                    state.password_entry_password.clear();
-                                    state.password_entry_show_password = false;
-                                    state.password_entry_field = 0;
+                                    reset_password_entry(&mut state);
                                     state.current_screen = Screen::Main;
-                                    if !state.filtered_items.is_empty() {
+                                    if state.list_len() > 0 {
                                         list_state.select(Some(0));
                                     }
                                 }
                             }
                             KeyCode::Backspace => {
-                                if state.password_entry_field == 0 {
-                                    state.password_entry_name.pop();
-                                } else {
-                                    state.password_entry_password.pop();
+                                match state.password_entry_field {
+                                    0 => {
+                                        state.password_entry_name.pop();
+                                    }
+                                    1 => {
+                                        state.password_entry_username.pop();
+                                    }
+                                    2 => {
+                                        state.password_entry_password.pop();
+                                        state.password_entry_reuse_warning = None;
+                                    }
+                                    3 => {
+                                        state.password_entry_url.pop();
+                                    }
+                                    5 => {
+                                        state.password_entry_tags.pop();
+                                    }
+                                    _ => {
+                                        state.password_entry_notes.pop();
+                                    }
                                 }
                             }
                             KeyCode::Char(c) => {
                                 // Handle Ctrl+H for password visibility
                                 if key.modifiers.contains(KeyModifiers::CONTROL) && c == 'h' {
-                                    if state.password_entry_field == 1 {
+                                    if state.password_entry_field == 2 {
                                         state.password_entry_show_password = !state.password_entry_show_password;
                                     }
                                 } else if !key.modifiers.contains(KeyModifiers::CONTROL) {
                                     // Only process regular characters without Ctrl modifier
-                                    if state.password_entry_field == 0 {
-                                        state.password_entry_name.push(c);
-                                    } else {
-                                        state.password_entry_password.push(c);
+                                    match state.password_entry_field {
+                                        0 => state.password_entry_name.push(c),
+                                        1 => state.password_entry_username.push(c),
+                                        2 => {
+                                            state.password_entry_password.push(c);
+                                            state.password_entry_reuse_warning = None;
+                                        }
+                                        3 => state.password_entry_url.push(c),
+                                        5 => state.password_entry_tags.push(c),
+                                        _ => state.password_entry_notes.push(c),
                                     }
                                 }
                             }
@@ -524,24 +1299,87 @@ pub async fn run_tui(
                         }
                     }
                     Screen::Main => {
+                        state.status_message = None;
+                        // Открыт диалог подтверждения удаления - обрабатываем только его
+                        if let Some((filename, _)) = state.delete_confirmation.clone() {
+                            match key.code {
+                                KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => {
+                                    state.delete_confirmation = None;
+                                    if let Some(ref key) = state.encryption_key {
+                                        let _ = storage.delete_entry(&filename, key.as_slice());
+
+                                        // Reload list
+                                        if let Ok(names) = storage.list_decrypted_names(key.as_slice()) {
+                                            state.name_to_filename = names.clone();
+                                            state.all_items = names.iter().map(|(_, name)| name.clone()).collect();
+                                            load_entry_metadata(&mut state, &storage);
+                                            filter_items(&mut state);
+                                        }
+                                        refresh_tray_shortcuts(&state, &storage, &tray);
+                                        if state.selected_index >= state.list_len() {
+                                            state.selected_index = state.list_len().saturating_sub(1);
+                                        }
+                                        list_state.select(if state.list_len() == 0 {
+                                            None
+                                        } else {
+                                            Some(state.selected_index)
+                                        });
+                                    }
+                                }
+                                KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                                    state.delete_confirmation = None;
+                                }
+                                _ => {}
+                            }
+                        }
+                        // Открыт диалог перемещения в папку
+                        else if let Some((filename, name)) = state.move_to_folder.clone() {
+                            match key.code {
+                                KeyCode::Enter => {
+                                    state.move_to_folder = None;
+                                    if let Some(ref key) = state.encryption_key {
+                                        let folder = normalize_folder(&state.move_folder_input);
+                                        state.status_message = Some(match storage.set_entry_folder(&filename, &folder, key.as_slice()) {
+                                            Ok(()) => {
+                                                let folder = if folder.is_empty() { state.i18n.ts("main_top_level").to_string() } else { folder };
+                                                format!("{} {} → {}", state.i18n.ts("main_moved"), name, folder)
+                                            }
+                                            Err(e) => e.to_string(),
+                                        });
+                                        load_entry_metadata(&mut state, &storage);
+                                        filter_items(&mut state);
+                                        state.selected_index = state.selected_index.min(state.list_len().saturating_sub(1));
+                                        list_state.select(if state.list_len() == 0 { None } else { Some(state.selected_index) });
+                                    }
+                                    state.move_folder_input.clear();
+                                }
+                                KeyCode::Esc => {
+                                    state.move_to_folder = None;
+                                    state.move_folder_input.clear();
+                                }
+                                KeyCode::Backspace => {
+                                    state.move_folder_input.pop();
+                                }
+                                KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                    state.move_folder_input.push(c);
+                                }
+                                _ => {}
+                            }
+                        }
                         // Проверяем Ctrl+Q для выхода
-                        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('q') {
+                        else if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('q') {
                             state.should_quit = true;
                             // Send shutdown signal to stop all components
                             let _ = shutdown_tx.send(());
                         }
                         // Проверяем Ctrl+N для создания нового пароля
                         else if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('n') {
-                            state.password_entry_name.clear();
-                            state.password_entry_password.clear();
-                            state.password_entry_show_password = false;
-                            state.password_entry_field = 0;
+                            reset_password_entry(&mut state);
                             state.current_screen = Screen::PasswordEntry { is_edit: false, filename: None };
                         }
                         // Проверяем Ctrl+E для редактирования выбранного пароля
                         else if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('e') {
-                            if !state.filtered_items.is_empty() && state.selected_index < state.filtered_items.len() {
-                                let selected_name = &state.filtered_items[state.selected_index];
+                            if let Some(selected_name) = selected_entry_name(&state) {
                                 // Find filename for this name
                                 let filename = state.name_to_filename.iter()
                                     .find(|(_, name)| name == selected_name)
@@ -549,34 +1387,113 @@ pub async fn run_tui(
 
                                 if let Some(ref filename) = filename {
                                     if let Some(ref key) = state.encryption_key {
-                                        // Load password
-                                        match storage.load_password_file(filename, key.as_slice()) {
-                                            Ok(password) => {
-                                                state.password_entry_name = selected_name.clone();
-                                                state.password_entry_password = password;
-                                                state.password_entry_show_password = false;
-                                                state.password_entry_field = 0;
-                                                state.current_screen = Screen::PasswordEntry { 
-                                                    is_edit: true, 
-                                                    filename: Some(filename.clone()) 
-                                                };
-                                            }
-                                            Err(_) => {
-                                                // Could not load password, still allow editing name
-                                                state.password_entry_name = selected_name.clone();
-                                                state.password_entry_password.clear();
-                                                state.password_entry_show_password = false;
-                                                state.password_entry_field = 0;
-                                                state.current_screen = Screen::PasswordEntry { 
-                                                    is_edit: true, 
-                                                    filename: Some(filename.clone()) 
-                                                };
-                                            }
+                                        let selected_name = selected_name.clone();
+                                        // Load password (if it can't be loaded, still allow editing the rest)
+                                        let password = storage.load_password_file(filename, key.as_slice()).unwrap_or_default();
+                                        // Load username, URL and notes
+                                        let details = storage.load_entry_details(filename, key.as_slice()).unwrap_or_default();
+
+                                        reset_password_entry(&mut state);
+                                        state.password_entry_name = selected_name;
+                                        state.password_entry_password = password;
+                                        state.password_entry_username = details.username.unwrap_or_default();
+                                        state.password_entry_url = details.url.unwrap_or_default();
+                                        state.password_entry_notes = details.notes.unwrap_or_default();
+                                        state.password_entry_security_questions = details.security_questions;
+                                        state.password_entry_custom_fields = details.custom_fields;
+                                        if let Some(ssh) = details.ssh {
+                                            state.password_entry_ssh_host = ssh.host;
+                                            state.password_entry_ssh_port = ssh.port.map(|port| port.to_string()).unwrap_or_default();
+                                            state.password_entry_ssh_username = ssh.username.unwrap_or_default();
                                         }
+                                        state.password_entry_autotype = details.autotype_sequence.unwrap_or_default();
+                                        state.password_entry_access_window = details.access_window.map(|window| window.to_string()).unwrap_or_default();
+                                        state.password_entry_timestamps = details.timestamps;
+                                        state.password_entry_tray_shortcut = details.tray_shortcut;
+                                        state.password_entry_tags = state.entry_tags.get(filename).map(|tags| tags.join(", ")).unwrap_or_default();
+                                        state.current_screen = Screen::PasswordEntry { 
+                                            is_edit: true, 
+                                            filename: Some(filename.clone()) 
+                                        };
                                     }
                                 }
                             }
                         }
+                        // Проверяем Ctrl+D для удаления выбранного пароля
+                        else if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('d') {
+                            if let Some(selected_name) = selected_entry_name(&state) {
+                                let filename = state.name_to_filename.iter()
+                                    .find(|(_, name)| name == selected_name)
+                                    .map(|(filename, _)| filename.clone());
+
+                                if let Some(filename) = filename {
+                                    state.delete_confirmation = Some((filename, selected_name.clone()));
+                                }
+                            }
+                        }
+                        // Проверяем Ctrl+R для пометки "требует смены пароля"
+                        else if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('r') {
+                            if let Some(selected_name) = selected_entry_name(&state).cloned() {
+                                let filename = state.name_to_filename.iter()
+                                    .find(|(_, name)| *name == selected_name)
+                                    .map(|(filename, _)| filename.clone());
+
+                                if let (Some(filename), Some(key)) = (filename, state.encryption_key.as_ref()) {
+                                    let flagged = storage
+                                        .load_entry_details(&filename, key.as_slice())
+                                        .map(|d| d.needs_rotation)
+                                        .unwrap_or(false);
+                                    let message_key = if flagged { "rotation_unmarked" } else { "rotation_marked" };
+                                    state.status_message = match storage.set_needs_rotation(&filename, !flagged, key.as_slice()) {
+                                        Ok(()) => Some(format!("{} {}", state.i18n.ts(message_key), selected_name)),
+                                        Err(e) => Some(e.to_string()),
+                                    };
+                                }
+                            }
+                        }
+                        // Ctrl+A - убрать выбранную запись в архив или вернуть из архива
+                        else if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('a') {
+                            let selected = selected_entry_name(&state).and_then(|selected_name| {
+                                state.name_to_filename.iter()
+                                    .find(|(_, name)| name == selected_name)
+                                    .cloned()
+                            });
+                            if let Some((filename, name)) = selected {
+                                if let Some(ref key) = state.encryption_key {
+                                    let archive = !state.archived_entries.contains(&filename);
+                                    let message_key = if archive { "main_archived" } else { "main_unarchived" };
+                                    state.status_message = Some(match storage.set_entry_archived(&filename, archive, key.as_slice()) {
+                                        Ok(()) => format!("{} {}", state.i18n.ts(message_key), name),
+                                        Err(e) => e.to_string(),
+                                    });
+                                    load_entry_metadata(&mut state, &storage);
+                                    filter_items(&mut state);
+                                    state.selected_index = state.selected_index.min(state.list_len().saturating_sub(1));
+                                    list_state.select(if state.list_len() == 0 { None } else { Some(state.selected_index) });
+                                }
+                            }
+                        }
+                        // Ctrl+G - переключить просмотр архива
+                        else if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('g') {
+                            state.show_archived = !state.show_archived;
+                            state.current_folder.clear();
+                            filter_items(&mut state);
+                            state.selected_index = 0;
+                            list_state.select(if state.list_len() == 0 { None } else { Some(0) });
+                        }
+                        // Ctrl+F - переместить выбранную запись в папку
+                        else if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('f') {
+                            if let Some(selected_name) = selected_entry_name(&state) {
+                                let filename = state.name_to_filename.iter()
+                                    .find(|(_, name)| name == selected_name)
+                                    .map(|(filename, _)| filename.clone());
+
+                                if let Some(filename) = filename {
+                                    state.move_to_folder = Some((filename.clone(), selected_name.clone()));
+                                    state.move_folder_input = state.entry_folders.get(&filename).cloned().unwrap_or_default();
+                                }
+                            }
+                        }
                         // Проверяем Ctrl+S для настроек
                         else if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('s') {
                             // Переход в настройки по Ctrl+S
@@ -584,8 +1501,7 @@ pub async fn run_tui(
                         }
                         // Проверяем Ctrl+C для копирования пароля в буфер обмена
                         else if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
-                            if !state.filtered_items.is_empty() && state.selected_index < state.filtered_items.len() {
-                                let selected_name = &state.filtered_items[state.selected_index];
+                            if let Some(selected_name) = selected_entry_name(&state) {
                                 // Find filename for this name
                                 let filename = state.name_to_filename.iter()
                                     .find(|(_, name)| name == selected_name)
@@ -593,55 +1509,14 @@ pub async fn run_tui(
 
                                 if let Some(ref filename) = filename {
                                     if let Some(ref key) = state.encryption_key {
-                                        // Cancel previous cleanup task if exists
-                                        if let Some(handle) = state.clipboard_cleanup_handle.take() {
-                                            handle.abort();
-                                        }
-
                                         // Load password
                                         match storage.load_password_file(filename, key.as_slice()) {
                                             Ok(mut password) => {
-                                                // Get or create persistent clipboard instance
-                                                let clipboard_arc = if let Some(ref existing) = state.clipboard {
-                                                    existing.clone()
-                                                } else {
-                                                    match Clipboard::new() {
-                                                        Ok(clipboard) => {
-                                                            let arc = Arc::new(StdMutex::new(clipboard));
-                                                            state.clipboard = Some(arc.clone());
-                                                            arc
-                                                        }
-                                                        Err(e) => {
-                                                            eprintln!("Failed to initialize clipboard: {}", e);
-                                                            password.zeroize();
-                                                            continue;
-                                                        }
+                                                if copy_to_clipboard(&mut state, &password) {
+                                                    if let Some(ref key) = state.encryption_key {
+                                                        let _ = storage.mark_used(filename, key.as_slice());
                                                     }
-                                                };
-
-                                                // Copy to clipboard using persistent instance
-                                                {
-                                                    let mut clipboard = clipboard_arc.lock().unwrap();
-                                                    if let Err(e) = clipboard.set_text(&password) {
-                                                        eprintln!("Failed to copy to clipboard: {}", e);
-                                                        password.zeroize();
-                                                        continue;
-                                                    }
-                                                }
-
-                                                // Schedule clipboard cleanup if timeout is set
-                                                let timeout_seconds = state.config.clipboard_timeout_seconds;
-                                                if timeout_seconds > 0 {
-                                                    let clipboard_for_cleanup = clipboard_arc.clone();
-                                                    let handle = tokio::spawn(async move {
-                                                        sleep(Duration::from_secs(timeout_seconds)).await;
-                                                        let mut clipboard = clipboard_for_cleanup.lock().unwrap();
-                                                        // Clear clipboard by setting empty string
-                                                        let _ = clipboard.set_text("");
-                                                    });
-                                                    state.clipboard_cleanup_handle = Some(handle);
                                                 }
-
                                                 // Clear password from memory
                                                 password.zeroize();
                                             }
@@ -653,69 +1528,238 @@ pub async fn run_tui(
                                 }
                             }
                         }
-                        // Обработка обычных клавиш (без Ctrl)
-                        else if !key.modifiers.contains(KeyModifiers::CONTROL) {
-                            match key.code {
-                            KeyCode::Esc => {
-                                // Сброс поиска при нажатии Esc
-                                if !state.search_query.is_empty() {
-                                    state.search_query.clear();
-                                    filter_items(&mut state);
-                                    state.selected_index = 0;
-                                    list_state.select(if state.filtered_items.is_empty() {
-                                        None
-                                    } else {
-                                        Some(0)
-                                    });
-                                }
-                            }
-                            KeyCode::F(1) => {
-                                // Переход в help по F1
-                                state.current_screen = Screen::Help;
-                            }
-                            KeyCode::F(2) => {
-                                // Переход в настройки по F2
-                                state.current_screen = Screen::Settings;
-                            }
-                            KeyCode::Up => {
-                                if !state.filtered_items.is_empty() && state.selected_index > 0 {
-                                    state.selected_index -= 1;
-                                    list_state.select(Some(state.selected_index));
+                        // Ctrl+U - копировать имя пользователя (с той же очисткой буфера по таймауту)
+                        else if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('u') {
+                            let filename = selected_entry_name(&state).and_then(|selected_name| {
+                                state.name_to_filename.iter()
+                                    .find(|(_, name)| name == selected_name)
+                                    .map(|(filename, _)| filename.clone())
+                            });
+                            if let (Some(filename), Some(key)) = (filename, state.encryption_key.as_ref()) {
+                                match storage.load_entry_details(&filename, key.as_slice()) {
+                                    Ok(details) => match details.username.filter(|username| !username.is_empty()) {
+                                        Some(username) => {
+                                            if copy_to_clipboard(&mut state, &username) {
+                                                state.status_message = Some(state.i18n.ts("main_username_copied").to_string());
+                                            }
+                                        }
+                                        None => {
+                                            state.status_message = Some(state.i18n.ts("main_no_username").to_string());
+                                        }
+                                    },
+                                    Err(e) => {
+                                        eprintln!("Failed to load entry details: {}", e);
+                                    }
                                 }
                             }
-                            KeyCode::Down => {
-                                if !state.filtered_items.is_empty() 
-                                    && state.selected_index < state.filtered_items.len().saturating_sub(1) {
-                                    state.selected_index += 1;
-                                    list_state.select(Some(state.selected_index));
+                        }
+                        // Ctrl+K - копировать команду ssh, Ctrl+O - подключиться по SSH прямо из TUI
+                        else if key.modifiers.contains(KeyModifiers::CONTROL)
+                            && matches!(key.code, KeyCode::Char('k') | KeyCode::Char('o'))
+                        {
+                            let copy_only = key.code == KeyCode::Char('k');
+                            let filename = selected_entry_name(&state).and_then(|selected_name| {
+                                state.name_to_filename.iter()
+                                    .find(|(_, name)| name == selected_name)
+                                    .map(|(filename, _)| filename.clone())
+                            });
+                            if let (Some(filename), Some(key)) = (filename, state.encryption_key.as_ref()) {
+                                let details = storage.load_entry_details(&filename, key.as_slice()).unwrap_or_default();
+                                let username = details.username.as_deref();
+                                match details.ssh.as_ref().and_then(|ssh| ssh.ssh_args(username).zip(ssh.ssh_command(username))) {
+                                    None => {
+                                        state.status_message = Some(state.i18n.ts("main_no_ssh_host").to_string());
+                                    }
+                                    Some((_, command)) if copy_only => {
+                                        if copy_to_clipboard(&mut state, &command) {
+                                            state.status_message = Some(format!("{} {}", state.i18n.ts("main_ssh_command_copied"), command));
+                                        }
+                                    }
+                                    Some((args, _)) => {
+                                        if let Err(e) = suspend::run_ssh(&mut terminal, &args, state.i18n.ts("main_ssh_banner")) {
+                                            state.status_message = Some(e.to_string());
+                                        }
+                                        // Пока шел сеанс SSH, таймер бездействия не должен блокировать хранилище
+                                        last_activity = Instant::now();
+                                    }
                                 }
                             }
-                            KeyCode::Backspace => {
-                                if !state.search_query.is_empty() {
-                                    state.search_query.pop();
-                                    filter_items(&mut state);
-                                    // Сбрасываем индекс если он выходит за границы
-                                    if state.selected_index >= state.filtered_items.len() {
-                                        state.selected_index = state.filtered_items.len().saturating_sub(1);
+                        }
+                        // Ctrl+V - набрать логин и пароль в окне, которое было активно до терминала
+                        else if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('v') {
+                            let filename = selected_entry_name(&state).and_then(|selected_name| {
+                                state.name_to_filename.iter()
+                                    .find(|(_, name)| name == selected_name)
+                                    .map(|(filename, _)| filename.clone())
+                            });
+                            if let (Some(filename), Some(key)) = (filename, state.encryption_key.as_ref()) {
+                                let result = storage.load_password_file(&filename, key.as_slice()).and_then(|password| {
+                                    let password = Zeroizing::new(password);
+                                    let details = storage.load_entry_details(&filename, key.as_slice()).unwrap_or_default();
+                                    let sequence = details.autotype_sequence.as_deref().unwrap_or(&state.config.autotype_sequence);
+                                    let values = autotype::AutoTypeValues {
+                                        username: details.username.as_deref().unwrap_or_default(),
+                                        password: &password,
+                                        url: details.url.as_deref().unwrap_or_default(),
+                                    };
+                                    autotype::auto_type(sequence, &values, Duration::from_millis(state.config.autotype_delay_ms))
+                                });
+                                state.status_message = Some(match result {
+                                    Ok(()) => {
+                                        let _ = storage.mark_used(&filename, key.as_slice());
+                                        state.i18n.ts("main_autotype_done").to_string()
                                     }
-                                    list_state.select(if state.filtered_items.is_empty() {
-                                        None
-                                    } else {
-                                        Some(state.selected_index.min(state.filtered_items.len().saturating_sub(1)))
-                                    });
+                                    Err(e) => format!("{} {}", state.i18n.ts("main_autotype_failed"), e),
+                                });
+                            }
+                            // Набор мог занять несколько секунд
+                            last_activity = Instant::now();
+                        }
+                        // Ctrl+B - проверить пароль выбранной записи по базе утечек
+                        else if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('b') {
+                            let selected = selected_entry_name(&state).and_then(|selected_name| {
+                                state.name_to_filename.iter()
+                                    .find(|(_, name)| name == selected_name)
+                                    .cloned()
+                            });
+                            if let Some((filename, name)) = selected {
+                                state.status_message = Some(check_entry_breach(&state, &storage, &filename, &name));
+                            }
+                        }
+                        // Обработка обычных клавиш (без Ctrl)
+                        else if !key.modifiers.contains(KeyModifiers::CONTROL) {
+                            match key.code {
+                            KeyCode::Esc if !state.search_query.is_empty() => {
+                                // Сброс поиска при нажатии Esc
+                                state.search_query.clear();
+                                filter_items(&mut state);
+                                state.selected_index = 0;
+                                list_state.select(if state.list_len() == 0 {
+                                    None
+                                } else {
+                                    Some(0)
+                                });
+                            }
+                            KeyCode::F(1) => {
+                                // Переход в help по F1
+                                state.current_screen = Screen::Help;
+                            }
+                            KeyCode::F(2) => {
+                                // Переход в настройки по F2
+                                state.current_screen = Screen::Settings;
+                            }
+                            KeyCode::F(3) => {
+                                // Переход в аудит по F3
+                                open_audit(&mut state, &storage, Screen::Main);
+                            }
+                            KeyCode::F(4) => {
+                                // Корзина с удаленными записями
+                                open_trash(&mut state, &storage);
+                            }
+                            KeyCode::F(5) => {
+                                // Смена паролей, помеченных Ctrl+R
+                                open_rotation(&mut state, &storage);
+                            }
+                            KeyCode::F(7) => {
+                                // Мастер импорта CSV
+                                clear_import(&mut state);
+                                state.current_screen = Screen::Import;
+                            }
+                            KeyCode::F(8) => {
+                                // Резервная копия хранилища
+                                clear_backup(&mut state);
+                                state.current_screen = Screen::Backup;
+                            }
+                            KeyCode::F(9) => {
+                                // Откат к резервной копии
+                                clear_restore(&mut state);
+                                match backup::list_backups(&state.config.backup_directory_path()) {
+                                    Ok(backups) => state.restore_backups = backups,
+                                    Err(e) => state.restore_error = Some(e.to_string()),
+                                }
+                                state.current_screen = Screen::Restore;
+                            }
+                            KeyCode::F(10) => {
+                                // Синхронизация через git
+                                clear_sync(&mut state);
+                                state.sync_remote_input = state.config.git_sync_remote.clone().unwrap_or_default();
+                                state.current_screen = Screen::Sync;
+                            }
+                            KeyCode::F(6) => {
+                                // Обслуживание хранилища: сначала только отчет, без изменений
+                                state.maintenance_done = false;
+                                state.maintenance_error = None;
+                                state.maintenance_report = None;
+                                state.maintenance_integrity = None;
+                                if let Some(ref key) = state.encryption_key {
+                                    match storage.compact(state.config.history_retention_days, true, key.as_slice()) {
+                                        Ok(report) => state.maintenance_report = Some(report),
+                                        Err(e) => state.maintenance_error = Some(e.to_string()),
+                                    }
+                                }
+                                state.current_screen = Screen::Maintenance;
+                            }
+                            KeyCode::Up if state.list_len() > 0 && state.selected_index > 0 => {
+                                state.selected_index -= 1;
+                                list_state.select(Some(state.selected_index));
+                            }
+                            KeyCode::Down if state.list_len() > 0
+                                && state.selected_index < state.list_len().saturating_sub(1) => {
+                                state.selected_index += 1;
+                                list_state.select(Some(state.selected_index));
+                            }
+                            KeyCode::Enter if state.search_query.trim() == suspend::SHELL_COMMAND => {
+                                // ":sh" - открыть оболочку, после выхода из нее вернуться в TUI
+                                state.search_query.clear();
+                                filter_items(&mut state);
+                                if let Err(e) = suspend::run_shell(&mut terminal, state.i18n.ts("shell_escape_banner")) {
+                                    state.status_message = Some(e.to_string());
+                                }
+                            }
+                            KeyCode::Enter if state.selected_index < state.visible_folders.len() => {
+                                // Открыть папку
+                                state.current_folder = state.visible_folders[state.selected_index].clone();
+                                filter_items(&mut state);
+                                state.selected_index = 0;
+                                list_state.select(if state.list_len() == 0 { None } else { Some(0) });
+                            }
+                            KeyCode::Backspace if state.search_query.is_empty() && !state.current_folder.is_empty() => {
+                                // На уровень выше; курсор встает на папку, из которой вышли
+                                let previous = std::mem::take(&mut state.current_folder);
+                                state.current_folder = previous.rsplit_once('/').map(|(parent, _)| parent.to_string()).unwrap_or_default();
+                                filter_items(&mut state);
+                                state.selected_index = state.visible_folders.iter().position(|f| *f == previous).unwrap_or(0);
+                                list_state.select(if state.list_len() == 0 { None } else { Some(state.selected_index) });
+                            }
+                            KeyCode::Backspace if !state.search_query.is_empty() => {
+                                state.search_query.pop();
+                                filter_items(&mut state);
+                                // Сбрасываем индекс если он выходит за границы
+                                if state.selected_index >= state.list_len() {
+                                    state.selected_index = state.list_len().saturating_sub(1);
                                 }
+                                list_state.select(if state.list_len() == 0 {
+                                    None
+                                } else {
+                                    Some(state.selected_index.min(state.list_len().saturating_sub(1)))
+                                });
                             }
                             KeyCode::Char(c) => {
                                 state.search_query.push(c);
                                 filter_items(&mut state);
+                                // Переходим к первой записи, имя которой начинается с набранного текста
+                                let query = state.search_query.to_lowercase();
+                                if let Some(index) = find_prefix(state.filtered_items.iter().map(String::as_str), &query) {
+                                    state.selected_index = index;
+                                }
                                 // Сбрасываем индекс если он выходит за границы
-                                if state.selected_index >= state.filtered_items.len() {
-                                    state.selected_index = state.filtered_items.len().saturating_sub(1);
+                                if state.selected_index >= state.list_len() {
+                                    state.selected_index = state.list_len().saturating_sub(1);
                                 }
-                                list_state.select(if state.filtered_items.is_empty() {
+                                list_state.select(if state.list_len() == 0 {
                                     None
                                 } else {
-                                    Some(state.selected_index.min(state.filtered_items.len().saturating_sub(1)))
+                                    Some(state.selected_index.min(state.list_len().saturating_sub(1)))
                                 });
                             }
                             _ => {}
@@ -728,523 +1772,4319 @@ pub async fn run_tui(
                                 // Закрыть help и вернуться к предыдущему экрану
                                 state.current_screen = Screen::Main;
                             }
+                            KeyCode::Char('a') | KeyCode::Char('A') => {
+                                // Генератор проверяется заново при каждом открытии
+                                state.about = Some(Diagnostics::collect());
+                                state.current_screen = Screen::About;
+                            }
                             _ => {}
                         }
                     }
-                    Screen::Settings => {
-                        // Проверяем F1 для открытия help
+                    Screen::About => {
+                        if matches!(key.code, KeyCode::Esc | KeyCode::F(1)) {
+                            state.current_screen = Screen::Help;
+                        }
+                    }
+                    Screen::Audit { return_to } => {
+                        match key.code {
+                            KeyCode::Esc | KeyCode::F(3) => {
+                                // Возвращаемся туда, откуда открыли аудит
+                                state.current_screen = *return_to;
+                            }
+                            KeyCode::F(1) => {
+                                state.current_screen = Screen::Help;
+                            }
+                            _ => {}
+                        }
+                    }
+                    Screen::SecurityQuestions { is_edit, filename } => {
+                        let count = state.password_entry_security_questions.len();
                         if key.code == KeyCode::F(1) {
                             state.current_screen = Screen::Help;
-                        } else {
+                        } else if key.modifiers.contains(KeyModifiers::CONTROL) {
                             match key.code {
-                            KeyCode::Esc | KeyCode::Char('q') => {
-                                // Сохраняем настройки перед выходом
-                                if !state.passwords_dir_input.trim().is_empty() {
-                                    state.config.passwords_directory =
-                                        Some(PathBuf::from(state.passwords_dir_input.trim()));
-                                } else {
-                                    state.config.passwords_directory = None;
+                                // Ctrl+N - новый вопрос
+                                KeyCode::Char('n') => {
+                                    state.password_entry_security_questions.push(SecurityQuestion::default());
+                                    state.security_questions_index = count;
+                                    state.security_questions_field = 0;
                                 }
-                                
-                                // Сохраняем время хранения в буфере обмена
-                                if let Ok(timeout) = state.clipboard_timeout_input.trim().parse::<u64>() {
-                                    state.config.clipboard_timeout_seconds = timeout;
+                                // Ctrl+G - сгенерировать случайный ответ
+                                KeyCode::Char('g') if state.security_questions_index < count => {
+                                    let question = &mut state.password_entry_security_questions[state.security_questions_index];
+                                    question.answer.zeroize();
+                                    question.answer = generate_security_answer();
                                 }
-                                
-                                if let Err(e) = state.config.save() {
-                                    // В реальном приложении здесь должна быть обработка ошибки
-                                    eprintln!("Failed to save config: {}", e);
+                                // Ctrl+H - показать/скрыть ответы
+                                KeyCode::Char('h') => {
+                                    state.security_questions_show_answers = !state.security_questions_show_answers;
                                 }
-                                
-                                // Пересоздаем storage с новой директорией
-                                storage = PasswordStorage::new(&state.config, crypto.clone());
-                                
-                                // Проверяем наличие мастер-пароля для новой директории
-                                let passwords_dir = state.config.passwords_directory_path();
-                                let dir_config = DirectoryConfig::load(&passwords_dir)
-                                    .unwrap_or_else(|_| DirectoryConfig {
-                                        master_password_hash: None,
-                                        encryption_key_salt: None,
-                                    });
-                                
-                                if !dir_config.has_master_password() {
-                                    // Нужно установить мастер-пароль для директории
-                                    state.master_password_input.clear();
-                                    state.master_password_confirm.clear();
-                                    state.master_password_field = 0;
-                                    state.master_password_show_password = false;
-                                    state.is_creating_master_password = true;
-                                    state.encryption_key = None; // Сбрасываем ключ при смене директории
-                                    state.current_screen = Screen::MasterPassword;
-                                } else {
-                                    // Мастер-пароль уже установлен, но нужно запросить его для входа
-                                    state.master_password_input.clear();
-                                    state.master_password_confirm.clear();
-                                    state.master_password_field = 0;
-                                    state.master_password_show_password = false;
-                                    state.is_creating_master_password = false;
-                                    state.encryption_key = None; // Сбрасываем ключ при смене директории
-                                    state.current_screen = Screen::MasterPassword;
+                                // Ctrl+D - удалить вопрос
+                                KeyCode::Char('d') if state.security_questions_index < count => {
+                                    let mut removed = state.password_entry_security_questions.remove(state.security_questions_index);
+                                    removed.answer.zeroize();
+                                    if state.security_questions_index > 0 && state.security_questions_index >= count - 1 {
+                                        state.security_questions_index -= 1;
+                                    }
                                 }
+                                _ => {}
                             }
-                            KeyCode::Up => {
-                                // Switch between fields (backward)
-                                if state.settings_field > 0 {
-                                    state.settings_field -= 1;
-                                } else {
-                                    state.settings_field = 3; // Wrap to last field (language)
+                        } else {
+                            match key.code {
+                                KeyCode::Esc | KeyCode::F(4) => {
+                                    // Назад к записи; изменения сохранятся вместе с ней
+                                    state.current_screen = Screen::PasswordEntry { is_edit, filename };
                                 }
-                            }
-                            KeyCode::Down => {
-                                // Switch between fields (forward)
-                                state.settings_field = (state.settings_field + 1) % 4;
-                            }
-                            KeyCode::Backspace => {
-                                if state.settings_field == 0 {
-                                    state.passwords_dir_input.pop();
-                                } else if state.settings_field == 1 {
-                                    state.clipboard_timeout_input.pop();
+                                KeyCode::Up if state.security_questions_index > 0 => {
+                                    state.security_questions_index -= 1;
                                 }
-                                // Fields 2 (theme) and 3 (language) не редактируются через Backspace
-                            }
-                            KeyCode::Enter => {
-                                // Если выбрано поле темы, открываем экран выбора темы
-                                if state.settings_field == 2 {
-                                    state.current_screen = Screen::ThemeSelection;
-                                } else if state.settings_field == 3 {
-                                    // Если выбрано поле языка, открываем экран выбора языка
-                                    state.current_screen = Screen::LanguageSelection;
-                                } else {
-                                    // Сохраняем и выходим
-                                    if !state.passwords_dir_input.trim().is_empty() {
-                                        state.config.passwords_directory =
-                                            Some(PathBuf::from(state.passwords_dir_input.trim()));
-                                    } else {
-                                        state.config.passwords_directory = None;
-                                    }
-                                    
-                                    // Сохраняем время хранения в буфере обмена
-                                    if let Ok(timeout) = state.clipboard_timeout_input.trim().parse::<u64>() {
-                                        state.config.clipboard_timeout_seconds = timeout;
-                                    }
-                                    
-                                    if let Err(e) = state.config.save() {
-                                        eprintln!("Failed to save config: {}", e);
-                                    }
-                                    
-                                    // Пересоздаем storage с новой директорией
-                                    storage = PasswordStorage::new(&state.config, crypto.clone());
-                                    
-                                    // Проверяем наличие мастер-пароля для новой директории
-                                    let passwords_dir = state.config.passwords_directory_path();
-                                    let dir_config = DirectoryConfig::load(&passwords_dir)
-                                        .unwrap_or_else(|_| DirectoryConfig {
-                                            master_password_hash: None,
-                                            encryption_key_salt: None,
-                                        });
-                                    
-                                    if !dir_config.has_master_password() {
-                                        // Нужно установить мастер-пароль для директории
-                                        state.master_password_input.clear();
-                                        state.master_password_confirm.clear();
-                                        state.master_password_field = 0;
-                                        state.master_password_show_password = false;
-                                        state.is_creating_master_password = true;
-                                        state.encryption_key = None; // Сбрасываем ключ при смене директории
-                                        state.current_screen = Screen::MasterPassword;
+                                KeyCode::Down if state.security_questions_index + 1 < count => {
+                                    state.security_questions_index += 1;
+                                }
+                                KeyCode::Tab => {
+                                    state.security_questions_field = (state.security_questions_field + 1) % 2;
+                                }
+                                KeyCode::Backspace if state.security_questions_index < count => {
+                                    let question = &mut state.password_entry_security_questions[state.security_questions_index];
+                                    if state.security_questions_field == 0 {
+                                        question.question.pop();
                                     } else {
-                                        // Мастер-пароль уже установлен, но нужно запросить его для входа
-                                        state.master_password_input.clear();
-                                        state.master_password_confirm.clear();
-                                        state.master_password_field = 0;
-                                        state.master_password_show_password = false;
-                                        state.is_creating_master_password = false;
-                                        state.encryption_key = None; // Сбрасываем ключ при смене директории
-                                        state.current_screen = Screen::MasterPassword;
+                                        question.answer.pop();
                                     }
                                 }
-                            }
-                            KeyCode::Char(c) => {
-                                if state.settings_field == 0 {
-                                    state.passwords_dir_input.push(c);
-                                } else {
-                                    // Only allow digits for timeout
-                                    if c.is_ascii_digit() {
-                                        state.clipboard_timeout_input.push(c);
+                                KeyCode::Char(c) if state.security_questions_index < count => {
+                                    let question = &mut state.password_entry_security_questions[state.security_questions_index];
+                                    if state.security_questions_field == 0 {
+                                        question.question.push(c);
+                                    } else {
+                                        question.answer.push(c);
                                     }
                                 }
-                            }
-                            _ => {}
+                                _ => {}
                             }
                         }
                     }
-                    Screen::ThemeSelection => {
-                        // Проверяем F1 для открытия help
+                    Screen::CustomFields { is_edit, filename } => {
+                        let count = state.password_entry_custom_fields.len();
+                        state.status_message = None;
                         if key.code == KeyCode::F(1) {
                             state.current_screen = Screen::Help;
+                        } else if key.modifiers.contains(KeyModifiers::CONTROL) {
+                            match key.code {
+                                // Ctrl+N - новое поле
+                                KeyCode::Char('n') => {
+                                    state.password_entry_custom_fields.push(CustomField::default());
+                                    state.custom_fields_index = count;
+                                    state.custom_fields_field = 0;
+                                }
+                                // Ctrl+C - скопировать значение выбранного поля
+                                KeyCode::Char('c') if state.custom_fields_index < count => {
+                                    let field = state.password_entry_custom_fields[state.custom_fields_index].clone();
+                                    if copy_to_clipboard(&mut state, &field.value) {
+                                        state.status_message = Some(format!("{} {}", state.i18n.ts("custom_fields_copied"), field.label));
+                                    }
+                                    let mut value = field.value;
+                                    value.zeroize();
+                                }
+                                // Ctrl+H - показать/скрыть значения
+                                KeyCode::Char('h') => {
+                                    state.custom_fields_show_values = !state.custom_fields_show_values;
+                                }
+                                // Ctrl+D - удалить поле
+                                KeyCode::Char('d') if state.custom_fields_index < count => {
+                                    let mut removed = state.password_entry_custom_fields.remove(state.custom_fields_index);
+                                    removed.value.zeroize();
+                                    if state.custom_fields_index > 0 && state.custom_fields_index >= count - 1 {
+                                        state.custom_fields_index -= 1;
+                                    }
+                                }
+                                _ => {}
+                            }
                         } else {
                             match key.code {
-                            KeyCode::Esc => {
-                                // Возвращаемся к настройкам
-                                state.current_screen = Screen::Settings;
+                                KeyCode::Esc | KeyCode::F(5) => {
+                                    // Назад к записи; изменения сохранятся вместе с ней
+                                    state.current_screen = Screen::PasswordEntry { is_edit, filename };
+                                }
+                                KeyCode::Up if state.custom_fields_index > 0 => {
+                                    state.custom_fields_index -= 1;
+                                }
+                                KeyCode::Down if state.custom_fields_index + 1 < count => {
+                                    state.custom_fields_index += 1;
+                                }
+                                KeyCode::Tab => {
+                                    state.custom_fields_field = (state.custom_fields_field + 1) % 2;
+                                }
+                                KeyCode::Backspace if state.custom_fields_index < count => {
+                                    let field = &mut state.password_entry_custom_fields[state.custom_fields_index];
+                                    if state.custom_fields_field == 0 {
+                                        field.label.pop();
+                                    } else {
+                                        field.value.pop();
+                                    }
+                                }
+                                KeyCode::Char(c) if state.custom_fields_index < count => {
+                                    let field = &mut state.password_entry_custom_fields[state.custom_fields_index];
+                                    if state.custom_fields_field == 0 {
+                                        field.label.push(c);
+                                    } else {
+                                        field.value.push(c);
+                                    }
+                                }
+                                _ => {}
                             }
-                            KeyCode::Up => {
-                                if state.theme_selection_index > 0 {
-                                    state.theme_selection_index -= 1;
+                        }
+                    }
+                    Screen::SshHost { is_edit, filename } => {
+                        state.status_message = None;
+                        let field = match state.ssh_host_field {
+                            0 => &mut state.password_entry_ssh_host,
+                            1 => &mut state.password_entry_ssh_port,
+                            _ => &mut state.password_entry_ssh_username,
+                        };
+                        match key.code {
+                            KeyCode::F(1) => state.current_screen = Screen::Help,
+                            KeyCode::Esc | KeyCode::F(6) => {
+                                // Назад к записи, если порт разобрался; сохранится вместе с записью
+                                if ssh_port_valid(&state.password_entry_ssh_port) {
+                                    state.current_screen = Screen::PasswordEntry { is_edit, filename };
                                 } else {
-                                    state.theme_selection_index = 2; // Wrap to last
+                                    state.ssh_host_field = 1;
+                                    state.status_message = Some(state.i18n.ts("ssh_host_invalid_port").to_string());
                                 }
                             }
-                            KeyCode::Down => {
-                                state.theme_selection_index = (state.theme_selection_index + 1) % 3;
+                            KeyCode::Up => state.ssh_host_field = (state.ssh_host_field + 2) % 3,
+                            KeyCode::Down | KeyCode::Tab => state.ssh_host_field = (state.ssh_host_field + 1) % 3,
+                            KeyCode::Backspace => {
+                                field.pop();
                             }
-                            KeyCode::Enter => {
-                                // Сохраняем выбранную тему
-                                let theme_name = match state.theme_selection_index {
-                                    1 => "vscode_style",
-                                    2 => "opencode_style",
-                                    _ => "textual_dark",
-                                };
-                                state.config.theme = theme_name.to_string();
-                                
-                                if let Err(e) = state.config.save() {
-                                    eprintln!("Failed to save config: {}", e);
-                                }
-                                
-                                // Возвращаемся к настройкам
-                                state.current_screen = Screen::Settings;
+                            // Пробелы ssh разделил бы на отдельные аргументы, в порт идут только цифры
+                            KeyCode::Char(c)
+                                if !key.modifiers.contains(KeyModifiers::CONTROL)
+                                    && !c.is_whitespace()
+                                    && (state.ssh_host_field != 1 || c.is_ascii_digit()) =>
+                            {
+                                field.push(c);
                             }
                             _ => {}
-                            }
                         }
                     }
-                    Screen::LanguageSelection => {
-                        // Проверяем F1 для открытия help
-                        if key.code == KeyCode::F(1) {
-                            state.current_screen = Screen::Help;
-                        } else {
-                            match key.code {
-                            KeyCode::Esc => {
-                                // Возвращаемся к настройкам
-                                state.current_screen = Screen::Settings;
-                            }
-                            KeyCode::Up => {
-                                if state.language_selection_index > 0 {
-                                    state.language_selection_index -= 1;
-                                } else {
-                                    state.language_selection_index = 2; // Wrap to last
+                    Screen::AutoType { is_edit, filename } => {
+                        state.status_message = None;
+                        match key.code {
+                            KeyCode::F(1) => state.current_screen = Screen::Help,
+                            KeyCode::Esc | KeyCode::F(7) => {
+                                // Назад к записи, если последовательность разбирается (пустая - по умолчанию)
+                                match autotype::validate(&state.password_entry_autotype) {
+                                    Ok(()) => state.current_screen = Screen::PasswordEntry { is_edit, filename },
+                                    Err(e) => state.status_message = Some(e.to_string()),
                                 }
                             }
-                            KeyCode::Down => {
-                                state.language_selection_index = (state.language_selection_index + 1) % 3;
+                            KeyCode::Backspace => {
+                                state.password_entry_autotype.pop();
                             }
-                            KeyCode::Enter => {
-                                // Сохраняем выбранный язык
-                                let language_code = match state.language_selection_index {
-                                    1 => "en",
-                                    2 => "zh",
-                                    _ => "ru",
-                                };
-                                state.config.language = language_code.to_string();
-                                
-                                // Обновляем i18n
-                                let language = Language::from_code(language_code);
-                                state.i18n.set_language(language);
-                                
-                                if let Err(e) = state.config.save() {
-                                    eprintln!("Failed to save config: {}", e);
-                                }
-                                
-                                // Возвращаемся к настройкам
-                                state.current_screen = Screen::Settings;
+                            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                state.password_entry_autotype.push(c);
                             }
                             _ => {}
-                            }
                         }
                     }
-                    Screen::PasswordGenerator { return_to_edit, return_filename } => {
-                        // Проверяем F1 для открытия help
+                    Screen::AccessWindow { is_edit, filename } => {
+                        state.status_message = None;
+                        match key.code {
+                            KeyCode::F(1) => state.current_screen = Screen::Help,
+                            KeyCode::Esc | KeyCode::F(8) => {
+                                // Назад к записи, если окно разбирается (пустое - без ограничений)
+                                let text = state.password_entry_access_window.trim();
+                                match AccessWindow::parse(text) {
+                                    Ok(window) => {
+                                        state.password_entry_access_window = window.to_string();
+                                        state.current_screen = Screen::PasswordEntry { is_edit, filename };
+                                    }
+                                    Err(_) if text.is_empty() => {
+                                        state.password_entry_access_window.clear();
+                                        state.current_screen = Screen::PasswordEntry { is_edit, filename };
+                                    }
+                                    Err(e) => state.status_message = Some(e.to_string()),
+                                }
+                            }
+                            KeyCode::Backspace => {
+                                state.password_entry_access_window.pop();
+                            }
+                            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                state.password_entry_access_window.push(c);
+                            }
+                            _ => {}
+                        }
+                    }
+                    Screen::Rotation => {
                         if key.code == KeyCode::F(1) {
                             state.current_screen = Screen::Help;
+                        } else if key.modifiers.contains(KeyModifiers::CONTROL) {
+                            match key.code {
+                                // Ctrl+O - открыть сайт записи
+                                KeyCode::Char('o') => {
+                                    if let Some(ref url) = state.rotation_url {
+                                        let _ = open_url(url);
+                                    }
+                                }
+                                // Ctrl+G - новый пароль по политике записи
+                                KeyCode::Char('g') => {
+                                    let policy = state
+                                        .rotation_policy
+                                        .clone()
+                                        .or_else(|| generator_policy(&state).ok())
+                                        .unwrap_or_default();
+                                    if let Ok(password) = generate_from_policy(&policy) {
+                                        state.rotation_new_password.zeroize();
+                                        state.rotation_new_password = password;
+                                    }
+                                }
+                                // Ctrl+P - скопировать старый пароль
+                                KeyCode::Char('p') => {
+                                    let old_password = state.rotation_old_password.clone();
+                                    copy_to_clipboard(&mut state, &old_password);
+                                }
+                                // Ctrl+C - скопировать новый пароль
+                                KeyCode::Char('c') if !state.rotation_new_password.is_empty() => {
+                                    let new_password = state.rotation_new_password.clone();
+                                    copy_to_clipboard(&mut state, &new_password);
+                                }
+                                // Ctrl+H - показать/скрыть пароли
+                                KeyCode::Char('h') => {
+                                    state.rotation_show_passwords = !state.rotation_show_passwords;
+                                }
+                                _ => {}
+                            }
                         } else {
                             match key.code {
+                                KeyCode::Esc => {
+                                    clear_rotation(&mut state);
+                                    state.current_screen = Screen::Main;
+                                }
+                                KeyCode::Tab if !state.rotation_queue.is_empty() => {
+                                    // Пропустить запись (флаг остается)
+                                    state.rotation_index = (state.rotation_index + 1) % state.rotation_queue.len();
+                                    load_rotation_item(&mut state, &storage);
+                                }
+                                KeyCode::Enter if !state.rotation_new_password.is_empty() && state.rotation_index < state.rotation_queue.len() => {
+                                    // Подтверждение: сохраняем новый пароль, старый уходит в историю
+                                    let (filename, name) = state.rotation_queue[state.rotation_index].clone();
+                                    if let Some(ref key) = state.encryption_key {
+                                        if storage.rotate_password(&filename, &state.rotation_new_password, key.as_slice()).is_ok() {
+                                            state.rotation_queue.remove(state.rotation_index);
+                                            state.status_message = Some(format!("{} {}", state.i18n.ts("rotation_done"), name));
+                                            if state.rotation_queue.is_empty() {
+                                                clear_rotation(&mut state);
+                                                state.current_screen = Screen::Main;
+                                            } else {
+                                                if state.rotation_index >= state.rotation_queue.len() {
+                                                    state.rotation_index = 0;
+                                                }
+                                                load_rotation_item(&mut state, &storage);
+                                            }
+                                        }
+                                    }
+                                }
+                                KeyCode::Backspace => {
+                                    state.rotation_new_password.pop();
+                                }
+                                KeyCode::Char(c) => {
+                                    state.rotation_new_password.push(c);
+                                }
+                                _ => {}
+                            }
+                        }
+                    }
+                    Screen::Maintenance if state.rotate_key_password.is_some() => {
+                        // Запрос мастер-пароля для смены ключа шифрования
+                        match key.code {
                             KeyCode::Esc => {
-                                // Закрыть генератор и вернуться к PasswordEntry
-                                // Восстанавливаем предыдущий экран с сохраненными параметрами
-                                state.current_screen = Screen::PasswordEntry { 
-                                    is_edit: return_to_edit, 
-                                    filename: return_filename.clone() 
-                                };
+                                state.rotate_key_password.zeroize();
+                                state.rotate_key_password = None;
+                                state.rotate_key_error = None;
                             }
-                            KeyCode::Up => {
-                                if state.password_generator_selected_field > 0 {
-                                    state.password_generator_selected_field -= 1;
+                            KeyCode::Enter => {
+                                match rotate_encryption_key(&mut state, &storage) {
+                                    Ok(()) => {
+                                        // API получает новый ключ
+                                        load_entry_metadata(&mut state, &storage);
+                                        state.status_message = Some(state.i18n.ts("rotate_key_done").to_string());
+                                        if state.config.keychain_unlock {
+                                            save_current_key_to_keychain(&mut state, &crypto);
+                                        }
+                                        state.current_screen = Screen::Main;
+                                    }
+                                    Err(message) => state.rotate_key_error = Some(message),
                                 }
+                                state.rotate_key_password.zeroize();
+                                state.rotate_key_password = None;
                             }
-                            KeyCode::Down => {
-                                // Максимум 5 полей: 0=length, 1=exclude_chars, 2-5=checkboxes
-                                if state.password_generator_selected_field < 5 {
-                                    state.password_generator_selected_field += 1;
+                            KeyCode::Backspace => {
+                                if let Some(ref mut password) = state.rotate_key_password {
+                                    password.pop();
                                 }
                             }
-                            KeyCode::Char(' ') => {
-                                // Переключение галочек только для полей 2-5
-                                // Для полей ввода (0-1) пробел обрабатывается в KeyCode::Char(c)
-                                if state.password_generator_selected_field >= 2 && state.password_generator_selected_field <= 5 {
-                                    match state.password_generator_selected_field {
-                                        2 => state.password_generator_use_uppercase = !state.password_generator_use_uppercase,
-                                        3 => state.password_generator_use_lowercase = !state.password_generator_use_lowercase,
-                                        4 => state.password_generator_use_digits = !state.password_generator_use_digits,
-                                        5 => state.password_generator_use_special = !state.password_generator_use_special,
-                                        _ => {}
+                            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                if let Some(ref mut password) = state.rotate_key_password {
+                                    password.push(c);
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+                    Screen::Maintenance => {
+                        match key.code {
+                            KeyCode::Char('r') | KeyCode::Char('R') if state.encryption_key.is_some() => {
+                                state.rotate_key_password = Some(String::new());
+                                state.rotate_key_error = None;
+                            }
+                            KeyCode::Char('o') | KeyCode::Char('O') if state.encryption_key.is_some() => {
+                                open_orphans(&mut state, &storage);
+                            }
+                            KeyCode::Char('v') | KeyCode::Char('V') => {
+                                // Проверка целостности: расшифровать все, ничего не меняя
+                                if let Some(ref key) = state.encryption_key {
+                                    match storage.verify(key.as_slice()) {
+                                        Ok(report) => state.maintenance_integrity = Some(report),
+                                        Err(e) => state.maintenance_error = Some(e.to_string()),
                                     }
-                                } else {
-                                    // Если пробел в поле ввода, обрабатываем как обычный символ
-                                    match state.password_generator_selected_field {
-                                        0 => {
-                                            // Поле длины - пробел не добавляем
+                                }
+                            }
+                            KeyCode::Esc => {
+                                state.rotate_key_error = None;
+                                state.maintenance_report = None;
+                                state.maintenance_error = None;
+                                state.current_screen = Screen::Main;
+                            }
+                            KeyCode::F(1) => {
+                                state.current_screen = Screen::Help;
+                            }
+                            KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y')
+                                if !state.maintenance_done
+                                    && state.maintenance_report.as_ref().is_some_and(|r| !r.is_empty()) =>
+                            {
+                                // Подтверждение: удаляем найденное
+                                if let Some(ref key) = state.encryption_key {
+                                    match storage.compact(state.config.history_retention_days, false, key.as_slice()) {
+                                        Ok(report) => state.maintenance_report = Some(report),
+                                        Err(e) => state.maintenance_error = Some(e.to_string()),
+                                    }
+                                    state.maintenance_done = true;
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+                    Screen::ChangeMasterPassword => {
+                        if key.code == KeyCode::F(1) {
+                            state.current_screen = Screen::Help;
+                        } else {
+                            match key.code {
+                                KeyCode::Esc => {
+                                    clear_change_master(&mut state);
+                                    state.current_screen = Screen::Settings;
+                                }
+                                KeyCode::Up => {
+                                    state.change_master_field = (state.change_master_field + CHANGE_MASTER_FIELDS - 1) % CHANGE_MASTER_FIELDS;
+                                }
+                                KeyCode::Down | KeyCode::Tab => {
+                                    state.change_master_field = (state.change_master_field + 1) % CHANGE_MASTER_FIELDS;
+                                }
+                                KeyCode::Enter if state.change_master_field < 2 => {
+                                    state.change_master_field += 1;
+                                }
+                                KeyCode::Enter => {
+                                    match change_master_password(&mut state, &storage, &crypto) {
+                                        Ok(()) => {
+                                            clear_change_master(&mut state);
+                                            // API получает новый ключ
+                                            load_entry_metadata(&mut state, &storage);
+                                            state.status_message = Some(state.i18n.ts("change_master_done").to_string());
+                                            if state.config.keychain_unlock {
+                                                save_current_key_to_keychain(&mut state, &crypto);
+                                            }
+                                            state.current_screen = Screen::Main;
                                         }
-                                        1 => {
-                                            // Поле исключений - добавляем пробел
-                                            state.password_generator_exclude_chars.push(' ');
+                                        Err(message) => {
+                                            state.change_master_error = Some(message);
                                         }
+                                    }
+                                }
+                                KeyCode::Backspace => {
+                                    match state.change_master_field {
+                                        0 => state.change_master_old.pop(),
+                                        1 => state.change_master_new.pop(),
+                                        2 => state.change_master_confirm.pop(),
+                                        3 => state.change_master_hint.pop(),
+                                        field => state.change_master_argon2[field - 4].pop(),
+                                    };
+                                }
+                                KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                    state.change_master_error = None;
+                                    match state.change_master_field {
+                                        0 => state.change_master_old.push(c),
+                                        1 => state.change_master_new.push(c),
+                                        2 => state.change_master_confirm.push(c),
+                                        3 => state.change_master_hint.push(c),
+                                        // Параметры Argon2 - только числа
+                                        field if c.is_ascii_digit() => state.change_master_argon2[field - 4].push(c),
                                         _ => {}
                                     }
                                 }
+                                _ => {}
                             }
-                            KeyCode::Enter => {
-                                // Генерируем пароль и вставляем его
-                                match generate_password(&state) {
-                                    Ok(password) => {
-                                        state.password_entry_password = password;
-                                        // Возвращаемся к экрану PasswordEntry с сохраненными параметрами
-                                        state.current_screen = Screen::PasswordEntry { 
-                                            is_edit: return_to_edit, 
-                                            filename: return_filename.clone() 
-                                        };
+                        }
+                    }
+                    Screen::Backup => {
+                        if key.code == KeyCode::F(1) {
+                            state.current_screen = Screen::Help;
+                        } else {
+                            match key.code {
+                                KeyCode::Esc => {
+                                    clear_backup(&mut state);
+                                    state.current_screen = Screen::Main;
+                                }
+                                KeyCode::Up => {
+                                    state.backup_field = (state.backup_field + 2) % 3;
+                                }
+                                KeyCode::Down | KeyCode::Tab => {
+                                    state.backup_field = (state.backup_field + 1) % 3;
+                                }
+                                KeyCode::Enter if state.backup_field < 2 => {
+                                    state.backup_field += 1;
+                                }
+                                KeyCode::Enter => {
+                                    if state.backup_passphrase.is_empty() {
+                                        state.backup_error = Some(state.i18n.ts("backup_empty_passphrase").to_string());
+                                    } else if state.backup_passphrase != state.backup_confirm {
+                                        state.backup_error = Some(state.i18n.ts("change_master_mismatch").to_string());
+                                    } else {
+                                        let target = PathBuf::from(state.backup_path_input.trim());
+                                        match backup::create_backup(&state.config.passwords_directory_path(), &target, &state.backup_passphrase, &crypto) {
+                                            Ok(info) => {
+                                                state.status_message = Some(format!(
+                                                    "{} {} ({} {})",
+                                                    state.i18n.ts("backup_done"),
+                                                    target.display(),
+                                                    info.entry_count,
+                                                    state.i18n.ts("backup_entries")
+                                                ));
+                                                clear_backup(&mut state);
+                                                stats_checked_at = None;
+                                                state.current_screen = Screen::Main;
+                                            }
+                                            Err(e) => state.backup_error = Some(e.to_string()),
+                                        }
                                     }
-                                    Err(e) => {
-                                        // Ошибка генерации - можно показать сообщение, но пока просто игнорируем
-                                        eprintln!("Ошибка генерации пароля: {}", e);
+                                }
+                                KeyCode::Backspace => {
+                                    match state.backup_field {
+                                        0 => state.backup_path_input.pop(),
+                                        1 => state.backup_passphrase.pop(),
+                                        _ => state.backup_confirm.pop(),
+                                    };
+                                }
+                                KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                    state.backup_error = None;
+                                    match state.backup_field {
+                                        0 => state.backup_path_input.push(c),
+                                        1 => state.backup_passphrase.push(c),
+                                        _ => state.backup_confirm.push(c),
                                     }
                                 }
+                                _ => {}
                             }
-                            KeyCode::Backspace => {
-                                // Удаление символа в активном поле ввода
-                                match state.password_generator_selected_field {
-                                    0 => {
-                                        state.password_generator_length.pop();
+                        }
+                    }
+                    Screen::Trash => {
+                        if state.trash_purge_confirm {
+                            // Диалог подтверждения окончательного удаления
+                            match key.code {
+                                KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => {
+                                    state.trash_purge_confirm = false;
+                                    purge_trash_item(&mut state, &storage);
+                                }
+                                KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                                    state.trash_purge_confirm = false;
+                                }
+                                _ => {}
+                            }
+                        } else if key.code == KeyCode::F(1) {
+                            state.current_screen = Screen::Help;
+                        } else if key.code == KeyCode::Delete {
+                            if !state.trash_items.is_empty() {
+                                state.trash_purge_confirm = true;
+                            }
+                        } else {
+                            let labels = trash_labels(&state);
+                            let labels: Vec<&str> = labels.iter().map(String::as_str).collect();
+                            match state.trash_picker.handle_key(key.code, &labels) {
+                                PickerAction::Selected if !state.trash_items.is_empty() => {
+                                    restore_trash_item(&mut state, &storage);
+                                    if state.selected_index >= state.list_len() {
+                                        state.selected_index = state.list_len().saturating_sub(1);
                                     }
-                                    1 => {
-                                        state.password_generator_exclude_chars.pop();
+                                    list_state.select(if state.list_len() == 0 {
+                                        None
+                                    } else {
+                                        Some(state.selected_index)
+                                    });
+                                    refresh_tray_shortcuts(&state, &storage, &tray);
+                                }
+                                PickerAction::Cancelled => {
+                                    clear_trash(&mut state);
+                                    state.current_screen = Screen::Main;
+                                }
+                                _ => {}
+                            }
+                        }
+                    }
+                    Screen::Orphans => {
+                        if state.orphans_delete_confirm {
+                            match key.code {
+                                KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => {
+                                    state.orphans_delete_confirm = false;
+                                    repair_selected_orphan(&mut state, &storage, OrphanRepair::Delete);
+                                }
+                                KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                                    state.orphans_delete_confirm = false;
+                                }
+                                _ => {}
+                            }
+                        } else if key.code == KeyCode::F(1) {
+                            state.current_screen = Screen::Help;
+                        } else if key.code == KeyCode::Delete {
+                            if !state.orphans.is_empty() {
+                                state.orphans_delete_confirm = true;
+                            }
+                        } else if matches!(key.code, KeyCode::Char('q') | KeyCode::Char('Q')) {
+                            // В карантин: файл или запись переносятся в подкаталог, а не удаляются
+                            repair_selected_orphan(&mut state, &storage, OrphanRepair::Quarantine);
+                        } else {
+                            let labels = orphan_labels(&state);
+                            let labels: Vec<&str> = labels.iter().map(String::as_str).collect();
+                            match state.orphans_picker.handle_key(key.code, &labels) {
+                                PickerAction::Selected => relink_selected_orphan(&mut state, &storage),
+                                PickerAction::Cancelled => {
+                                    clear_orphans(&mut state);
+                                    state.current_screen = Screen::Maintenance;
+                                }
+                                _ => {}
+                            }
+                        }
+                    }
+                    Screen::Restore => {
+                        if key.code == KeyCode::F(1) {
+                            state.current_screen = Screen::Help;
+                        } else if state.restore_selected.is_none() {
+                            // Шаг 1: выбор архива
+                            let labels = restore_labels(&state);
+                            let labels: Vec<&str> = labels.iter().map(String::as_str).collect();
+                            match state.restore_picker.handle_key(key.code, &labels) {
+                                PickerAction::Selected if !state.restore_backups.is_empty() => {
+                                    state.restore_selected = Some(state.restore_picker.index);
+                                    state.restore_error = None;
+                                }
+                                PickerAction::Cancelled => {
+                                    clear_restore(&mut state);
+                                    state.current_screen = Screen::Main;
+                                }
+                                _ => {}
+                            }
+                        } else {
+                            // Шаг 2: повторная аутентификация и откат
+                            match key.code {
+                                KeyCode::Esc => {
+                                    state.restore_master.zeroize();
+                                    state.restore_passphrase.zeroize();
+                                    state.restore_field = 0;
+                                    state.restore_error = None;
+                                    state.restore_selected = None;
+                                }
+                                KeyCode::Up | KeyCode::Down | KeyCode::Tab => {
+                                    state.restore_field = 1 - state.restore_field;
+                                }
+                                KeyCode::Enter if state.restore_field == 0 => {
+                                    state.restore_field = 1;
+                                }
+                                KeyCode::Enter => {
+                                    match restore_vault(&state, &crypto) {
+                                        Ok(message) => {
+                                            // Хранилище могло получить другой мастер-пароль: входим заново
+                                            lock_vault(&mut state);
+                                            list_state.select(None);
+                                            state.status_message = Some(message);
+                                        }
+                                        Err(message) => state.restore_error = Some(message),
+                                    }
+                                }
+                                KeyCode::Backspace => {
+                                    if state.restore_field == 0 {
+                                        state.restore_master.pop();
+                                    } else {
+                                        state.restore_passphrase.pop();
+                                    }
+                                }
+                                KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                    state.restore_error = None;
+                                    if state.restore_field == 0 {
+                                        state.restore_master.push(c);
+                                    } else {
+                                        state.restore_passphrase.push(c);
                                     }
-                                    _ => {}
                                 }
+                                _ => {}
                             }
-                            KeyCode::Char(c) => {
-                                // Ввод символов в активное поле
-                                match state.password_generator_selected_field {
-                                    0 => {
-                                        // Поле длины - только цифры
-                                        if c.is_ascii_digit() {
-                                            state.password_generator_length.push(c);
+                        }
+                    }
+                    Screen::Sync => {
+                        if key.code == KeyCode::F(1) {
+                            state.current_screen = Screen::Help;
+                        } else if key.code == KeyCode::Esc {
+                            clear_sync(&mut state);
+                            state.current_screen = Screen::Main;
+                        } else if key.modifiers.contains(KeyModifiers::CONTROL) {
+                            let git_sync = GitSync::new(&state.config.passwords_directory_path());
+                            if !state.config.git_sync || !git_sync.is_repo() {
+                                state.sync_error = Some(state.i18n.ts("sync_not_set_up").to_string());
+                                continue;
+                            }
+                            state.sync_message = None;
+                            state.sync_error = None;
+                            let conflict = !state.sync_conflicts.is_empty();
+                            let result = match key.code {
+                                KeyCode::Char('p') => git_sync.pull().map(|outcome| match outcome {
+                                    PullOutcome::UpToDate => "sync_up_to_date",
+                                    PullOutcome::Updated => "sync_pulled",
+                                    PullOutcome::Conflict(files) => {
+                                        state.sync_conflicts = conflict_labels(&state, &files);
+                                        "sync_conflicts"
+                                    }
+                                }),
+                                KeyCode::Char('u') => git_sync.push().map(|_| "sync_pushed"),
+                                KeyCode::Char('l') if conflict => git_sync
+                                    .keep_local()
+                                    .and_then(|_| git_sync.push())
+                                    .map(|_| "sync_kept_local"),
+                                KeyCode::Char('t') if conflict => git_sync.take_remote().map(|_| "sync_took_remote"),
+                                _ => continue,
+                            };
+                            state.vault_stats.sync = match result {
+                                Ok("sync_conflicts") => SyncStatus::Conflict,
+                                Ok(_) => SyncStatus::Ok,
+                                Err(_) => SyncStatus::Error,
+                            };
+                            match result {
+                                Ok(message) => {
+                                    if message != "sync_conflicts" {
+                                        state.sync_conflicts.clear();
+                                    }
+                                    // Записи (или даже мастер-пароль) могли измениться: список
+                                    // перечитывается по событию, как после синхронизации WebDAV
+                                    state.entry_metadata.events().publish(VaultEvent::SyncCompleted {
+                                        changed: matches!(message, "sync_pulled" | "sync_took_remote"),
+                                    });
+                                    state.sync_message = Some(state.i18n.ts(message).to_string());
+                                }
+                                Err(e) => state.sync_error = Some(e.to_string()),
+                            }
+                        } else {
+                            match key.code {
+                                KeyCode::Enter => {
+                                    let remote = state.sync_remote_input.trim().to_string();
+                                    let git_sync = GitSync::new(&state.config.passwords_directory_path());
+                                    let remote = (!remote.is_empty()).then_some(remote);
+                                    match git_sync.init(remote.as_deref()) {
+                                        Ok(()) => {
+                                            state.config.git_sync = true;
+                                            state.config.git_sync_remote = remote;
+                                            if let Err(e) = state.config.save() {
+                                                state.sync_error = Some(e.to_string());
+                                            } else {
+                                                state.sync_message = Some(state.i18n.ts("sync_saved").to_string());
+                                            }
+                                            // Новые изменения коммитятся только через storage с включенным git_sync
+                                            storage = open_storage(&state, &crypto);
                                         }
+                                        Err(e) => state.sync_error = Some(e.to_string()),
                                     }
-                                    1 => {
-                                        // Поле исключений - любые символы
-                                        state.password_generator_exclude_chars.push(c);
+                                }
+                                KeyCode::Backspace => {
+                                    state.sync_remote_input.pop();
+                                }
+                                KeyCode::Char(c) => {
+                                    state.sync_error = None;
+                                    state.sync_remote_input.push(c);
+                                }
+                                _ => {}
+                            }
+                        }
+                    }
+                    Screen::Import => {
+                        if key.code == KeyCode::F(1) {
+                            state.current_screen = Screen::Help;
+                        } else if key.code == KeyCode::Esc || (state.import_report.is_some() && key.code == KeyCode::Enter) {
+                            // Обновляем список, если что-то импортировали
+                            if state.import_report.is_some() {
+                                if let Some(ref key) = state.encryption_key {
+                                    if let Ok(names) = storage.list_decrypted_names(key.as_slice()) {
+                                        state.name_to_filename = names.clone();
+                                        state.all_items = names.iter().map(|(_, name)| name.clone()).collect();
+                                        load_entry_metadata(&mut state, &storage);
+                                        filter_items(&mut state);
                                     }
-                                    _ => {}
                                 }
+                                state.selected_index = 0;
+                                list_state.select(if state.list_len() == 0 { None } else { Some(0) });
                             }
-                            _ => {}
+                            clear_import(&mut state);
+                            state.current_screen = Screen::Main;
+                        } else if state.import_table.is_none() {
+                            // Шаг 1: путь к файлу
+                            match key.code {
+                                KeyCode::Enter => {
+                                    match CsvTable::load(std::path::Path::new(state.import_path_input.trim())) {
+                                        Ok(table) => {
+                                            let (mapping, restored) = saved_or_guessed_mapping(&table.headers, &state.config.import_mappings);
+                                            state.import_mapping = mapping;
+                                            state.import_mapping_restored = restored;
+                                            state.import_column = 0;
+                                            state.import_table = Some(table);
+                                            state.import_error = None;
+                                        }
+                                        Err(e) => state.import_error = Some(e.to_string()),
+                                    }
+                                }
+                                KeyCode::Backspace => {
+                                    state.import_path_input.pop();
+                                }
+                                KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                    state.import_path_input.push(c);
+                                }
+                                _ => {}
+                            }
+                        } else if state.import_report.is_none() {
+                            // Шаг 2: сопоставление столбцов
+                            let columns = state.import_mapping.len();
+                            match key.code {
+                                KeyCode::Up if state.import_column > 0 => {
+                                    state.import_column -= 1;
+                                }
+                                KeyCode::Down if state.import_column + 1 < columns => {
+                                    state.import_column += 1;
+                                }
+                                KeyCode::Right | KeyCode::Char(' ') if state.import_column < columns => {
+                                    state.import_mapping[state.import_column] = state.import_mapping[state.import_column].next();
+                                }
+                                KeyCode::Left if state.import_column < columns => {
+                                    state.import_mapping[state.import_column] = state.import_mapping[state.import_column].prev();
+                                }
+                                KeyCode::Enter => {
+                                    if !state.import_mapping.contains(&ImportField::Name) {
+                                        state.import_error = Some(state.i18n.ts("import_no_name_column").to_string());
+                                    } else if let (Some(table), Some(key)) = (state.import_table.as_ref(), state.encryption_key.as_ref()) {
+                                        match import_rows(&storage, table, &state.import_mapping, key.as_slice()) {
+                                            Ok(report) => {
+                                                // Запоминаем сопоставление для следующего импорта того же формата
+                                                state.config.import_mappings.insert(format_signature(&table.headers), state.import_mapping.clone());
+                                                if let Err(e) = state.config.save() {
+                                                    eprintln!("Failed to save config: {}", e);
+                                                }
+                                                state.import_report = Some(report);
+                                                state.import_error = None;
+                                            }
+                                            Err(e) => state.import_error = Some(e.to_string()),
+                                        }
+                                    }
+                                }
+                                _ => {}
                             }
                         }
                     }
-                }
-            }
-        }
-
-        if state.should_quit {
-            break;
-        }
-    }
-
-    // Cancel clipboard cleanup task if exists
-    if let Some(handle) = state.clipboard_cleanup_handle {
-        handle.abort();
-    }
-
-    // Clear encryption key from memory before exit
-    if let Some(mut key) = state.encryption_key {
-        key.zeroize();
-    }
-    state.master_password_input.zeroize();
-    state.master_password_confirm.zeroize();
-    state.password_entry_password.zeroize();
-
-    // Restore terminal
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
+                    Screen::Settings => {
+                        // Проверяем F1 для открытия help
+                        if key.code == KeyCode::F(1) {
+                            state.current_screen = Screen::Help;
+                        } else {
+                            match key.code {
+                            KeyCode::Esc | KeyCode::Char('q') => {
+                                // Сохраняем настройки перед выходом
+                                if !state.passwords_dir_input.trim().is_empty() {
+                                    state.config.passwords_directory =
+                                        Some(PathBuf::from(state.passwords_dir_input.trim()));
+                                } else {
+                                    state.config.passwords_directory = None;
+                                }
+                                
+                                // Сохраняем время хранения в буфере обмена
+                                if let Ok(timeout) = state.clipboard_timeout_input.trim().parse::<u64>() {
+                                    state.config.clipboard_timeout_seconds = timeout;
+                                }
+                                
+                                if let Err(e) = state.config.save() {
+                                    // В реальном приложении здесь должна быть обработка ошибки
+                                    eprintln!("Failed to save config: {}", e);
+                                }
+                                
+                                // Пересоздаем storage с новой директорией
+                                storage = open_storage(&state, &crypto);
+                                // Прежнее хранилище API больше не отдает
+                                state.entry_metadata.clear();
+                                
+                                // Проверяем наличие мастер-пароля для новой директории
+                                let passwords_dir = state.config.passwords_directory_path();
+                                let dir_config = DirectoryConfig::load(&passwords_dir)
+                                    .unwrap_or_default();
+                                state.policy = dir_config.policy.clone();
+                                
+                                if !dir_config.has_master_password() {
+                                    // Нужно установить мастер-пароль для директории
+                                    state.master_password_input.clear();
+                                    state.master_password_confirm.clear();
+                                    state.master_password_field = 0;
+                                    state.master_password_show_password = false;
+                                    state.is_creating_master_password = true;
+                                    state.encryption_key = None; // Сбрасываем ключ при смене директории
+                                    state.current_screen = Screen::MasterPassword;
+                                } else {
+                                    // Мастер-пароль уже установлен, но нужно запросить его для входа
+                                    state.master_password_input.clear();
+                                    state.master_password_confirm.clear();
+                                    state.master_password_field = 0;
+                                    state.master_password_show_password = false;
+                                    state.is_creating_master_password = false;
+                                    state.encryption_key = None; // Сбрасываем ключ при смене директории
+                                    state.current_screen = Screen::MasterPassword;
+                                }
+                            }
+                            KeyCode::Up => {
+                                // Switch between fields (backward)
+                                if state.settings_field > 0 {
+                                    state.settings_field -= 1;
+                                } else {
+                                    state.settings_field = 6; // Wrap to last field (keychain unlock)
+                                }
+                            }
+                            KeyCode::Down => {
+                                // Switch between fields (forward)
+                                state.settings_field = (state.settings_field + 1) % 7;
+                            }
+                            KeyCode::Backspace => {
+                                if state.settings_field == 0 {
+                                    state.passwords_dir_input.pop();
+                                } else if state.settings_field == 1 {
+                                    state.clipboard_timeout_input.pop();
+                                }
+                                // Fields 2 (theme) and 3 (language) не редактируются через Backspace
+                            }
+                            KeyCode::Enter => {
+                                // Если выбрано поле темы, открываем экран выбора темы
+                                if state.settings_field == 2 {
+                                    state.current_screen = Screen::ThemeSelection;
+                                } else if state.settings_field == 3 {
+                                    // Если выбрано поле языка, открываем экран выбора языка
+                                    state.current_screen = Screen::LanguageSelection;
+                                } else if state.settings_field == 4 {
+                                    // Смена мастер-пароля (только для разблокированного хранилища)
+                                    if state.encryption_key.is_some() {
+                                        clear_change_master(&mut state);
+                                        // Текущая подсказка, чтобы ее можно было поправить под новый пароль
+                                        let dir_config = DirectoryConfig::load(&state.config.passwords_directory_path()).unwrap_or_default();
+                                        state.change_master_hint = dir_config.password_hint.unwrap_or_default();
+                                        // Текущие параметры Argon2, чтобы их можно было усилить вместе с паролем
+                                        let argon2 = dir_config.kdf.argon2();
+                                        state.change_master_argon2 = [
+                                            (argon2.memory_kib / 1024).to_string(),
+                                            argon2.iterations.to_string(),
+                                            argon2.parallelism.to_string(),
+                                        ];
+                                        state.current_screen = Screen::ChangeMasterPassword;
+                                    }
+                                } else if state.settings_field == 5 {
+                                    // Режим для программ чтения с экрана применяется сразу
+                                    state.config.screen_reader = !state.config.screen_reader;
+                                    if let Err(e) = state.config.save() {
+                                        eprintln!("Failed to save config: {}", e);
+                                    }
+                                } else if state.settings_field == 6 {
+                                    // Разблокировка через связку ключей ОС: при выключении ключ оттуда удаляется
+                                    state.config.keychain_unlock = !state.config.keychain_unlock;
+                                    if let Err(e) = state.config.save() {
+                                        eprintln!("Failed to save config: {}", e);
+                                    }
+                                    if !state.config.keychain_unlock {
+                                        if let Err(e) = keychain::forget(&state.config.passwords_directory_path()) {
+                                            state.status_message = Some(format!("{} {}", state.i18n.ts("keychain_failed"), e));
+                                        }
+                                    } else {
+                                        save_current_key_to_keychain(&mut state, &crypto);
+                                    }
+                                } else {
+                                    // Сохраняем и выходим
+                                    if !state.passwords_dir_input.trim().is_empty() {
+                                        state.config.passwords_directory =
+                                            Some(PathBuf::from(state.passwords_dir_input.trim()));
+                                    } else {
+                                        state.config.passwords_directory = None;
+                                    }
+                                    
+                                    // Сохраняем время хранения в буфере обмена
+                                    if let Ok(timeout) = state.clipboard_timeout_input.trim().parse::<u64>() {
+                                        state.config.clipboard_timeout_seconds = timeout;
+                                    }
+                                    
+                                    if let Err(e) = state.config.save() {
+                                        eprintln!("Failed to save config: {}", e);
+                                    }
+                                    
+                                    // Пересоздаем storage с новой директорией
+                                    storage = open_storage(&state, &crypto);
+                                    // Прежнее хранилище API больше не отдает
+                                    state.entry_metadata.clear();
+                                    
+                                    // Проверяем наличие мастер-пароля для новой директории
+                                    let passwords_dir = state.config.passwords_directory_path();
+                                    let dir_config = DirectoryConfig::load(&passwords_dir)
+                                        .unwrap_or_default();
+                                    state.policy = dir_config.policy.clone();
+                                    
+                                    if !dir_config.has_master_password() {
+                                        // Нужно установить мастер-пароль для директории
+                                        state.master_password_input.clear();
+                                        state.master_password_confirm.clear();
+                                        state.master_password_field = 0;
+                                        state.master_password_show_password = false;
+                                        state.is_creating_master_password = true;
+                                        state.encryption_key = None; // Сбрасываем ключ при смене директории
+                                        state.current_screen = Screen::MasterPassword;
+                                    } else {
+                                        // Мастер-пароль уже установлен, но нужно запросить его для входа
+                                        state.master_password_input.clear();
+                                        state.master_password_confirm.clear();
+                                        state.master_password_field = 0;
+                                        state.master_password_show_password = false;
+                                        state.is_creating_master_password = false;
+                                        state.encryption_key = None; // Сбрасываем ключ при смене директории
+                                        state.current_screen = Screen::MasterPassword;
+                                    }
+                                }
+                            }
+                            KeyCode::Char(c) => {
+                                if state.settings_field == 0 {
+                                    state.passwords_dir_input.push(c);
+                                } else if state.settings_field == 1 {
+                                    // Only allow digits for timeout
+                                    if c.is_ascii_digit() {
+                                        state.clipboard_timeout_input.push(c);
+                                    }
+                                }
+                            }
+                            _ => {}
+                            }
+                        }
+                    }
+                    Screen::ThemeSelection => {
+                        // Проверяем F1 для открытия help
+                        if key.code == KeyCode::F(1) {
+                            state.current_screen = Screen::Help;
+                        } else {
+                            let labels: Vec<&str> = THEMES.iter().map(|(name, _, _)| *name).collect();
+                            match state.theme_picker.handle_key(key.code, &labels) {
+                                PickerAction::Selected => {
+                                    // Сохраняем выбранную тему
+                                    state.config.theme = THEMES[state.theme_picker.index].1.to_string();
+
+                                    if let Err(e) = state.config.save() {
+                                        eprintln!("Failed to save config: {}", e);
+                                    }
+
+                                    // Возвращаемся к настройкам
+                                    state.current_screen = Screen::Settings;
+                                }
+                                PickerAction::Cancelled => {
+                                    // Возвращаемся к настройкам
+                                    state.current_screen = Screen::Settings;
+                                }
+                                PickerAction::None => {}
+                            }
+                        }
+                    }
+                    Screen::LanguageSelection => {
+                        // Проверяем F1 для открытия help
+                        if key.code == KeyCode::F(1) {
+                            state.current_screen = Screen::Help;
+                        } else {
+                            let languages = Language::all();
+                            let labels: Vec<&str> = languages.iter().map(|l| l.display_name()).collect();
+                            match state.language_picker.handle_key(key.code, &labels) {
+                                PickerAction::Selected => {
+                                    // Сохраняем выбранный язык
+                                    let language = languages[state.language_picker.index];
+                                    state.config.language = language.to_code().to_string();
+
+                                    // Обновляем i18n
+                                    state.i18n.set_language(language);
+
+                                    if let Err(e) = state.config.save() {
+                                        eprintln!("Failed to save config: {}", e);
+                                    }
+
+                                    // Возвращаемся к настройкам
+                                    state.current_screen = Screen::Settings;
+                                }
+                                PickerAction::Cancelled => {
+                                    // Возвращаемся к настройкам
+                                    state.current_screen = Screen::Settings;
+                                }
+                                PickerAction::None => {}
+                            }
+                        }
+                    }
+                    Screen::PasswordGenerator { return_to_edit, return_filename } => {
+                        // Ошибка генерации видна до следующего нажатия
+                        state.password_generator_error = None;
+                        // Проверяем F1 для открытия help
+                        if key.code == KeyCode::F(1) {
+                            state.current_screen = Screen::Help;
+                        } else if state.password_generator_presets_open {
+                            // Список пресетов: Enter - применить, Del - удалить, Esc - закрыть
+                            let names: Vec<String> = state.config.generator_presets.keys().cloned().collect();
+                            if key.code == KeyCode::Delete {
+                                if let Some(name) = names.get(state.password_generator_preset_picker.index) {
+                                    state.config.generator_presets.remove(name);
+                                    if let Err(e) = state.config.save() {
+                                        eprintln!("Failed to save config: {}", e);
+                                    }
+                                    let len = state.config.generator_presets.len();
+                                    state.password_generator_preset_picker.index = state.password_generator_preset_picker.index.min(len.saturating_sub(1));
+                                }
+                            } else {
+                                let labels: Vec<&str> = names.iter().map(String::as_str).collect();
+                                match state.password_generator_preset_picker.handle_key(key.code, &labels) {
+                                    PickerAction::Selected => {
+                                        if let Some(policy) = names
+                                            .get(state.password_generator_preset_picker.index)
+                                            .and_then(|name| state.config.generator_presets.get(name))
+                                            .cloned()
+                                        {
+                                            apply_generator_policy(&mut state, &policy);
+                                        }
+                                        state.password_generator_presets_open = false;
+                                    }
+                                    PickerAction::Cancelled => state.password_generator_presets_open = false,
+                                    PickerAction::None => {}
+                                }
+                            }
+                        } else if let Some(mut name) = state.password_generator_preset_name.take() {
+                            // Диалог сохранения пресета: имя существующего пресета перезаписывает его
+                            match key.code {
+                                KeyCode::Enter if !name.trim().is_empty() => match generator_policy(&state) {
+                                    Ok(policy) => {
+                                        state.config.generator_presets.insert(name.trim().to_string(), policy);
+                                        if let Err(e) = state.config.save() {
+                                            eprintln!("Failed to save config: {}", e);
+                                        }
+                                        state.password_generator_preset_error = None;
+                                    }
+                                    Err(_) => {
+                                        state.password_generator_preset_error = Some(state.i18n.ts("password_generator_preset_invalid").to_string());
+                                        state.password_generator_preset_name = Some(name);
+                                    }
+                                },
+                                KeyCode::Esc => state.password_generator_preset_error = None,
+                                KeyCode::Backspace => {
+                                    name.pop();
+                                    state.password_generator_preset_name = Some(name);
+                                }
+                                KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                    name.push(c);
+                                    state.password_generator_preset_name = Some(name);
+                                }
+                                _ => state.password_generator_preset_name = Some(name),
+                            }
+                        } else if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('p') {
+                            // Открываем список на пресете, совпадающем с текущими настройками
+                            let current = current_generator_preset(&state);
+                            state.password_generator_preset_picker = ListPicker::new(
+                                current
+                                    .and_then(|name| state.config.generator_presets.keys().position(|n| n == name))
+                                    .unwrap_or(0),
+                            );
+                            state.password_generator_presets_open = true;
+                        } else if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('s') {
+                            state.password_generator_preset_name = Some(current_generator_preset(&state).unwrap_or_default().to_string());
+                            state.password_generator_preset_error = None;
+                        } else {
+                            match key.code {
+                            KeyCode::Esc => {
+                                // Закрыть генератор и вернуться к PasswordEntry
+                                // Восстанавливаем предыдущий экран с сохраненными параметрами
+                                state.current_screen = Screen::PasswordEntry { 
+                                    is_edit: return_to_edit, 
+                                    filename: return_filename.clone() 
+                                };
+                            }
+                            KeyCode::Up if state.password_generator_selected_field > 0 => {
+                                state.password_generator_selected_field -= 1;
+                            }
+                            // Поля: 0=length, 1=exclude_chars, 2=custom_chars, 3-9=checkboxes, 10-12=минимумы
+                            KeyCode::Down if state.password_generator_selected_field < 12 => {
+                                state.password_generator_selected_field += 1;
+                            }
+                            KeyCode::Char(' ') => {
+                                // Переключение галочек только для полей 3-9
+                                // Для полей ввода (0-2) пробел обрабатывается в KeyCode::Char(c)
+                                if state.password_generator_selected_field >= 3 && state.password_generator_selected_field <= 9 {
+                                    match state.password_generator_selected_field {
+                                        3 => state.password_generator_use_uppercase = !state.password_generator_use_uppercase,
+                                        4 => state.password_generator_use_lowercase = !state.password_generator_use_lowercase,
+                                        5 => state.password_generator_use_digits = !state.password_generator_use_digits,
+                                        6 => state.password_generator_use_special = !state.password_generator_use_special,
+                                        7 => state.password_generator_use_cyrillic = !state.password_generator_use_cyrillic,
+                                        8 => {
+                                            // Длина во фразе - это число слов, поэтому подставляем разумное значение
+                                            state.password_generator_passphrase = !state.password_generator_passphrase;
+                                            state.password_generator_length = if state.password_generator_passphrase {
+                                                DEFAULT_PASSPHRASE_WORDS.to_string()
+                                            } else {
+                                                PasswordPolicy::default().length.to_string()
+                                            };
+                                        }
+                                        9 => state.password_generator_exclude_ambiguous = !state.password_generator_exclude_ambiguous,
+                                        _ => {}
+                                    }
+                                } else {
+                                    // Если пробел в поле ввода, обрабатываем как обычный символ
+                                    match state.password_generator_selected_field {
+                                        0 => {
+                                            // Поле длины - пробел не добавляем
+                                        }
+                                        1 => {
+                                            // Поле исключений - добавляем пробел
+                                            state.password_generator_exclude_chars.push(' ');
+                                        }
+                                        2 => state.password_generator_custom_chars.push(' '),
+                                        _ => {}
+                                    }
+                                }
+                            }
+                            KeyCode::Enter => {
+                                // Генерируем пароль и вставляем его
+                                match generate_password(&state) {
+                                    Ok(password) => {
+                                        state.password_entry_password = password;
+                                        state.password_entry_reuse_warning = None;
+                                        // Запоминаем настройки как политику записи (для ротации)
+                                        state.password_entry_policy = generator_policy(&state).ok();
+                                        // Возвращаемся к экрану PasswordEntry с сохраненными параметрами
+                                        state.current_screen = Screen::PasswordEntry { 
+                                            is_edit: return_to_edit, 
+                                            filename: return_filename.clone() 
+                                        };
+                                    }
+                                    Err(e) => {
+                                        state.password_generator_error = Some(e.to_string());
+                                    }
+                                }
+                            }
+                            KeyCode::Backspace => {
+                                // Удаление символа в активном поле ввода
+                                match state.password_generator_selected_field {
+                                    0 => {
+                                        state.password_generator_length.pop();
+                                    }
+                                    1 => {
+                                        state.password_generator_exclude_chars.pop();
+                                    }
+                                    2 => {
+                                        state.password_generator_custom_chars.pop();
+                                    }
+                                    10..=12 => {
+                                        if let Some(min) = generator_minimum(&mut state) {
+                                            *min /= 10;
+                                        }
+                                    }
+                                    _ => {}
+                                }
+                            }
+                            KeyCode::Char(c) => {
+                                // Ввод символов в активное поле
+                                match state.password_generator_selected_field {
+                                    // Поле длины - только цифры
+                                    0 if c.is_ascii_digit() => {
+                                        state.password_generator_length.push(c);
+                                    }
+                                    1 => {
+                                        // Поле исключений - любые символы
+                                        state.password_generator_exclude_chars.push(c);
+                                    }
+                                    // Свой набор - любые символы
+                                    2 => state.password_generator_custom_chars.push(c),
+                                    // Минимумы - только цифры, не больше максимальной длины пароля
+                                    10..=12 if c.is_ascii_digit() => {
+                                        if let Some(min) = generator_minimum(&mut state) {
+                                            *min = (*min * 10 + c.to_digit(10).unwrap_or(0) as usize).min(MAX_PASSWORD_LENGTH);
+                                        }
+                                    }
+                                    _ => {}
+                                }
+                            }
+                            _ => {}
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        if state.should_quit {
+            break;
+        }
+    }
+
+    save_ui_state(&state, &storage, &list_state);
+
+    // Буфер терминала после выхода уже не очистить по таймауту: очищаем сразу
+    if state.osc52_clear_at.is_some() {
+        let _ = clipboard::write_osc52("");
+    }
+
+    // Cancel clipboard cleanup task if exists
+    if let Some(handle) = state.clipboard_cleanup_handle {
+        handle.abort();
+    }
+
+    // Clear encryption key from memory before exit
+    if let Some(mut key) = state.encryption_key {
+        key.zeroize();
+    }
+    state.master_password_input.zeroize();
+    state.master_password_confirm.zeroize();
+    state.password_entry_password.zeroize();
+    state.password_entry_notes.zeroize();
+
+    // Restore terminal
+    disable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture
     )?;
     terminal.show_cursor()?;
 
-    Ok(())
-}
+    Ok(())
+}
+
+/// Отрисовать текущий экран
+pub fn ui(f: &mut Frame, state: &TuiState, list_state: &mut ListState) {
+    // Загружаем тему из конфига; без 24-битного цвета - палитра ANSI
+    let theme = if state.console.truecolor {
+        get_theme_by_name(&state.config.theme)
+    } else {
+        Theme::ansi16()
+    };
+    
+    // Устанавливаем фон для всего экрана
+    f.render_widget(
+        Block::default()
+            .style(theme.bg_style()),
+        f.size()
+    );
+    
+    match state.current_screen {
+        Screen::MasterPassword => render_master_password_screen(f, state, &theme),
+        Screen::Main => render_main_screen(f, state, list_state, &theme),
+        Screen::Settings => render_settings_screen(f, state, &theme),
+        Screen::PasswordEntry { .. } => render_password_entry_screen(f, state, &theme),
+        Screen::PasswordGenerator { .. } => render_password_generator_screen(f, state, &theme),
+        Screen::Help => render_help_screen(f, state, &theme),
+        Screen::ThemeSelection => render_theme_selection_screen(f, state, &theme),
+        Screen::LanguageSelection => render_language_selection_screen(f, state, &theme),
+        Screen::Audit { .. } => render_audit_screen(f, state, &theme),
+        Screen::Rotation => render_rotation_screen(f, state, &theme),
+        Screen::Trash => render_trash_screen(f, state, &theme),
+        Screen::Maintenance => render_maintenance_screen(f, state, &theme),
+        Screen::Orphans => render_orphans_screen(f, state, &theme),
+        Screen::About => render_about_screen(f, state, &theme),
+        Screen::ChangeMasterPassword => render_change_master_password_screen(f, state, &theme),
+        Screen::Import => render_import_screen(f, state, &theme),
+        Screen::Backup => render_backup_screen(f, state, &theme),
+        Screen::Restore => render_restore_screen(f, state, &theme),
+        Screen::Sync => render_sync_screen(f, state, &theme),
+        Screen::SecurityQuestions { .. } => render_security_questions_screen(f, state, &theme),
+        Screen::CustomFields { .. } => render_custom_fields_screen(f, state, &theme),
+        Screen::SshHost { .. } => render_ssh_host_screen(f, state, &theme),
+        Screen::AutoType { .. } => render_autotype_screen(f, state, &theme),
+        Screen::AccessWindow { .. } => render_access_window_screen(f, state, &theme),
+    }
+
+    if let Some(request) = pending_pairing(state) {
+        render_pairing_dialog(f, state, &request, &theme);
+    }
+
+    // Индикатор записи макроса (или ожидания буквы регистра) в правом верхнем углу любого экрана
+    let badge = match (state.macros.recording(), state.macros.prompt()) {
+        (_, Some(_)) => Some(format!(" {} ", state.i18n.ts("macro_register_badge"))),
+        (Some(register), None) => Some(format!(" {} @{} ", state.i18n.ts("macro_recording_badge"), register)),
+        (None, None) => None,
+    };
+    if let Some(badge) = badge {
+        let width = (badge.chars().count() as u16).min(f.size().width);
+        let area = Rect::new(f.size().width - width, 0, width, 1);
+        f.render_widget(Paragraph::new(badge).style(theme.error_style()), area);
+    }
+
+    if state.config.screen_reader {
+        console::strip_borders(f.buffer_mut());
+    }
+    if !state.console.unicode {
+        console::to_ascii(f.buffer_mut());
+    }
+    if state.config.screen_reader {
+        // Первая строка всегда называет экран и активное поле
+        let area = f.size();
+        let line = Rect { height: area.height.min(1), ..area };
+        f.render_widget(Clear, line);
+        f.render_widget(
+            Paragraph::new(screen_reader_announcement(state)).style(theme.bg_style().patch(theme.title_style())),
+            line,
+        );
+    }
+}
+
+/// Название экрана и активного поля для программ чтения с экрана
+fn screen_reader_announcement(state: &TuiState) -> String {
+    let (screen, fields, field): (&str, &[&str], usize) = match &state.current_screen {
+        Screen::MasterPassword if state.is_creating_master_password => (
+            "master_password_create_title",
+            &["master_password_directory", "master_password", "master_password_confirm", "master_password_hint"],
+            state.master_password_field,
+        ),
+        Screen::MasterPassword => ("master_password_title", &["master_password_enter"], 0),
+        Screen::Main => ("main_passwords", &[], 0),
+        Screen::Settings => (
+            "settings_title",
+            &[
+                "settings_directory",
+                "settings_clipboard_timeout",
+                "settings_theme",
+                "settings_language",
+                "settings_change_master",
+                "settings_screen_reader",
+                "settings_keychain",
+            ],
+            state.settings_field,
+        ),
+        Screen::PasswordEntry { is_edit, .. } => (
+            if *is_edit { "password_entry_edit_title" } else { "password_entry_create_title" },
+            &[
+                "password_entry_name",
+                "password_entry_username",
+                "password_entry_password",
+                "password_entry_url",
+                "password_entry_notes",
+                "password_entry_tags",
+            ],
+            state.password_entry_field,
+        ),
+        Screen::PasswordGenerator { .. } => (
+            "password_generator_title",
+            &[
+                "password_generator_length",
+                "password_generator_exclude",
+                "password_generator_custom",
+                "password_generator_uppercase",
+                "password_generator_lowercase",
+                "password_generator_digits",
+                "password_generator_special",
+                "password_generator_cyrillic",
+                "password_generator_passphrase",
+                "password_generator_exclude_ambiguous",
+                "password_generator_min_digits_field",
+                "password_generator_min_special_field",
+                "password_generator_min_uppercase_field",
+            ],
+            state.password_generator_selected_field,
+        ),
+        Screen::Help => ("help_title", &[], 0),
+        Screen::ThemeSelection => ("theme_selection_title", &[], 0),
+        Screen::LanguageSelection => ("language_selection_title", &[], 0),
+        Screen::Audit { .. } => ("audit_title", &[], 0),
+        Screen::Rotation => ("rotation_title", &[], 0),
+        Screen::Trash => ("trash_title", &[], 0),
+        Screen::Maintenance => ("maintenance_title", &[], 0),
+        Screen::Orphans => ("orphans_title", &[], 0),
+        Screen::About => ("about_title", &[], 0),
+        Screen::SecurityQuestions { .. } => (
+            "security_questions_title",
+            &["security_questions_question", "security_questions_answer"],
+            state.security_questions_field,
+        ),
+        Screen::CustomFields { .. } => (
+            "custom_fields_title",
+            &["custom_fields_label", "custom_fields_value"],
+            state.custom_fields_field,
+        ),
+        Screen::SshHost { .. } => (
+            "ssh_host_title",
+            &["ssh_host_host", "ssh_host_port", "ssh_host_username"],
+            state.ssh_host_field,
+        ),
+        Screen::AutoType { .. } => ("autotype_title", &["autotype_sequence"], 0),
+        Screen::AccessWindow { .. } => ("access_window_title", &["access_window_hours"], 0),
+        Screen::ChangeMasterPassword => (
+            "change_master_title",
+            &[
+                "change_master_old",
+                "change_master_new",
+                "change_master_confirm",
+                "master_password_hint",
+                "change_master_argon2_memory",
+                "change_master_argon2_iterations",
+                "change_master_argon2_parallelism",
+            ],
+            state.change_master_field,
+        ),
+        Screen::Import => ("import_title", &[], 0),
+        Screen::Backup => (
+            "backup_title",
+            &["backup_path", "backup_passphrase", "change_master_confirm"],
+            state.backup_field,
+        ),
+        Screen::Restore if state.restore_selected.is_some() => (
+            "restore_confirm_title",
+            &["change_master_old", "backup_passphrase"],
+            state.restore_field,
+        ),
+        Screen::Restore => ("restore_title", &[], 0),
+        Screen::Sync => ("sync_title", &[], 0),
+    };
+    match fields.get(field) {
+        Some(key) => format!("{} | {}", state.i18n.ts(screen), state.i18n.ts(key)),
+        None => state.i18n.ts(screen).to_string(),
+    }
+}
+
+/// Пересчитать группы переиспользованных паролей, проверить утечки и открыть экран аудита
+fn open_audit(state: &mut TuiState, storage: &PasswordStorage, return_to: Screen) {
+    state.audit_reuse_groups = match state.encryption_key {
+        Some(ref key) => storage.password_reuse_groups(key.as_slice()).unwrap_or_default(),
+        None => Vec::new(),
+    };
+
+    // Проверка утечек: по локальной базе HIBP или онлайн через range API (k-анонимность)
+    state.audit_breached.clear();
+    state.audit_breach_status = None;
+    match BreachChecker::from_config(&state.config) {
+        Ok(None) => {
+            state.audit_breach_status = Some(state.i18n.ts("audit_breach_not_configured").to_string());
+        }
+        Ok(Some(checker)) => {
+            if let Some(ref key) = state.encryption_key {
+                for (filename, name) in &state.name_to_filename {
+                    let Ok(mut password) = storage.load_password_file(filename, key.as_slice()) else {
+                        continue;
+                    };
+                    if !password.is_empty() {
+                        match checker.check_password(&password) {
+                            Ok(Some(count)) => state.audit_breached.push((name.clone(), count)),
+                            Ok(None) => {}
+                            Err(e) => {
+                                state.audit_breach_status = Some(format!("{} {}", state.i18n.ts("audit_breach_error"), e));
+                                password.zeroize();
+                                break;
+                            }
+                        }
+                    }
+                    password.zeroize();
+                }
+            }
+        }
+        Err(e) => {
+            state.audit_breach_status = Some(format!("{} {}", state.i18n.ts("audit_breach_error"), e));
+        }
+    }
+    state.vault_stats.audit_findings = Some(state.audit_reuse_groups.len() + state.audit_breached.len());
+    if !state.audit_breached.is_empty() {
+        Webhooks::from_config(&state.config).fire(WebhookEvent::Breach { entries: state.audit_breached.len() });
+    }
+
+    state.current_screen = Screen::Audit { return_to: Box::new(return_to) };
+}
+
+/// Проверить пароль одной записи по базе утечек и вернуть текст для строки статуса
+fn check_entry_breach(state: &TuiState, storage: &PasswordStorage, filename: &str, name: &str) -> String {
+    let Some(ref key) = state.encryption_key else {
+        return String::new();
+    };
+    let checker = match BreachChecker::from_config(&state.config) {
+        Ok(Some(checker)) => checker,
+        Ok(None) => return state.i18n.ts("audit_breach_not_configured").to_string(),
+        Err(e) => return format!("{} {}", state.i18n.ts("audit_breach_error"), e),
+    };
+    let mut password = match storage.load_password_file(filename, key.as_slice()) {
+        Ok(password) => password,
+        Err(e) => return format!("{} {}", state.i18n.ts("audit_breach_error"), e),
+    };
+    let result = checker.check_password(&password);
+    password.zeroize();
+    match result {
+        Ok(Some(count)) => format!("{} {} ({} {})", state.i18n.ts("main_breach_found"), name, state.i18n.ts("audit_breach_seen"), count),
+        Ok(None) => format!("{} {}", state.i18n.ts("main_breach_clean"), name),
+        Err(e) => format!("{} {}", state.i18n.ts("audit_breach_error"), e),
+    }
+}
+
+/// Подсказка значка трея: число записей, возраст резервной копии, замечания аудита
+/// и синхронизация; строки с проблемами помечены ⚠
+fn tray_tooltip(state: &TuiState) -> String {
+    let stats = &state.vault_stats;
+    let now = Utc::now();
+    let warnings = stats.warnings(now);
+    let flag = |warning: &[&str]| if warnings.iter().any(|w| warning.contains(w)) { "⚠ " } else { "" };
+
+    let backup = match stats.backup_age_days(now) {
+        Some(days) => format!("{} {}", days, state.i18n.ts("tray_tooltip_days_ago")),
+        None => state.i18n.ts("tray_tooltip_no_backup").to_string(),
+    };
+    let audit = match stats.audit_findings {
+        Some(findings) => findings.to_string(),
+        None => state.i18n.ts("tray_tooltip_no_audit").to_string(),
+    };
+    let sync = match stats.sync {
+        SyncStatus::Off => "tray_sync_off",
+        SyncStatus::Idle => "tray_sync_idle",
+        SyncStatus::Ok => "tray_sync_ok",
+        SyncStatus::Conflict => "tray_sync_conflict",
+        SyncStatus::Error => "tray_sync_error",
+    };
+    [
+        "RPM".to_string(),
+        format!("{} {}", state.i18n.ts("tray_tooltip_entries"), stats.entries),
+        format!("{}{} {}", flag(&["no_backup", "backup_overdue"]), state.i18n.ts("tray_tooltip_backup"), backup),
+        format!("{}{} {}", flag(&["audit_findings"]), state.i18n.ts("tray_tooltip_audit"), audit),
+        format!("{}{} {}", flag(&["sync_conflict", "sync_error"]), state.i18n.ts("tray_tooltip_sync"), state.i18n.ts(sync)),
+    ]
+    .join("\n")
+}
+
+/// Передать в меню трея записи, закрепленные в нем
+fn refresh_tray_shortcuts(state: &TuiState, storage: &PasswordStorage, tray: &TrayHandle) {
+    let Some(ref key) = state.encryption_key else {
+        return;
+    };
+    let shortcuts = state
+        .name_to_filename
+        .iter()
+        .filter(|(filename, _)| {
+            storage
+                .load_entry_details(filename, key.as_slice())
+                .map(|d| d.tray_shortcut)
+                .unwrap_or(false)
+        })
+        .map(|(filename, name)| TrayShortcut { filename: filename.clone(), name: name.clone() })
+        .collect();
+    tray.set_shortcuts(shortcuts);
+}
+
+/// Найти запись по имени из `rpm --copy`: точное совпадение, без учета регистра, затем нечеткий поиск
+fn find_entry_by_name(names: &[(String, String)], query: &str) -> Option<TrayShortcut> {
+    let found = names
+        .iter()
+        .find(|(_, name)| name == query)
+        .or_else(|| names.iter().find(|(_, name)| name.to_lowercase() == query.to_lowercase()))
+        .or_else(|| {
+            let items: Vec<String> = names.iter().map(|(_, name)| name.clone()).collect();
+            let best = fuzzy_filter(&items, query).into_iter().next()?;
+            names.iter().find(|(_, name)| *name == best)
+        })?;
+    Some(TrayShortcut { filename: found.0.clone(), name: found.1.clone() })
+}
+
+/// Скопировать пароль записи, запрошенной повторным запуском (`rpm --copy <name>`)
+fn copy_requested_entry(state: &mut TuiState, storage: &PasswordStorage, query: &str) {
+    let mut names = match state.encryption_key {
+        // Имена читаем из хранилища: в состоянии они могут быть стерты по таймауту
+        Some(ref key) => storage.list_decrypted_names(key.as_slice()).unwrap_or_default(),
+        None => return,
+    };
+    match find_entry_by_name(&names, query) {
+        Some(entry) => copy_shortcut_password(state, storage, &entry, "instance_copied"),
+        None => {
+            state.status_message = Some(format!("{} {}", state.i18n.ts("instance_entry_not_found"), query));
+        }
+    }
+    for (_, name) in names.iter_mut() {
+        name.zeroize();
+    }
+}
+
+/// Скопировать пароль записи из меню трея или из запроса повторного запуска (хранилище уже разблокировано)
+fn copy_shortcut_password(state: &mut TuiState, storage: &PasswordStorage, shortcut: &TrayShortcut, copied_key: &str) {
+    let password = match state.encryption_key {
+        Some(ref key) => storage.load_password_file(&shortcut.filename, key.as_slice()),
+        None => return,
+    };
+    match password {
+        Ok(mut password) => {
+            if copy_to_clipboard(state, &password) {
+                if let Some(ref key) = state.encryption_key {
+                    let _ = storage.mark_used(&shortcut.filename, key.as_slice());
+                }
+                state.status_message = Some(format!("{} {}", state.i18n.ts(copied_key), shortcut.name));
+            }
+            password.zeroize();
+        }
+        Err(e) => {
+            eprintln!("Failed to load password: {}", e);
+        }
+    }
+}
+
+/// Скопировать секрет в буфер обмена и запланировать его очистку по таймауту из настроек
+fn copy_to_clipboard(state: &mut TuiState, text: &str) -> bool {
+    // Cancel previous cleanup task if exists
+    if let Some(handle) = state.clipboard_cleanup_handle.take() {
+        handle.abort();
+    }
+    state.osc52_clear_at = None;
+
+    if clipboard::osc52_preferred(&state.config.clipboard_osc52) {
+        return copy_with_osc52(state, text);
+    }
+
+    // Get or create persistent clipboard instance
+    let clipboard_arc = if let Some(ref existing) = state.clipboard {
+        existing.clone()
+    } else {
+        match Clipboard::new() {
+            Ok(clipboard) => {
+                let arc = Arc::new(StdMutex::new(clipboard));
+                state.clipboard = Some(arc.clone());
+                arc
+            }
+            // Нет системного буфера обмена (например, нет X-сервера): пробуем буфер терминала
+            Err(_) if state.config.clipboard_osc52 == "auto" => return copy_with_osc52(state, text),
+            Err(e) => {
+                eprintln!("Failed to initialize clipboard: {}", e);
+                return false;
+            }
+        }
+    };
+
+    // Copy to clipboard using persistent instance
+    {
+        let mut clipboard = clipboard_arc.lock().unwrap();
+        if let Err(e) = clipboard.set_text(text) {
+            eprintln!("Failed to copy to clipboard: {}", e);
+            return false;
+        }
+        if state.config.clipboard_primary_selection {
+            if let Err(e) = clipboard::set_primary(&mut clipboard, text) {
+                eprintln!("Failed to copy to primary selection: {}", e);
+            }
+        }
+    }
+
+    // Schedule clipboard cleanup if timeout is set
+    let timeout_seconds = state.policy.clipboard_timeout_seconds(state.config.clipboard_timeout_seconds);
+    if timeout_seconds > 0 {
+        let clipboard_for_cleanup = clipboard_arc.clone();
+        let primary_selection = state.config.clipboard_primary_selection;
+        let copied = clipboard::CopiedText::new(text);
+        let handle = tokio::spawn(async move {
+            sleep(Duration::from_secs(timeout_seconds)).await;
+            let mut clipboard = clipboard_for_cleanup.lock().unwrap();
+            // Clear only if the user has not copied something else since
+            clipboard::clear_if_unchanged(&mut clipboard, &copied, primary_selection);
+        });
+        state.clipboard_cleanup_handle = Some(handle);
+    }
+
+    true
+}
+
+/// Скопировать через OSC 52; очистка по таймауту - в главном цикле. Прочитать буфер
+/// терминала нельзя (запрос OSC 52 почти везде запрещен), поэтому он очищается без проверки
+fn copy_with_osc52(state: &mut TuiState, text: &str) -> bool {
+    if let Err(e) = clipboard::write_osc52(text) {
+        eprintln!("Failed to copy to clipboard: {}", e);
+        return false;
+    }
+    let timeout_seconds = state.policy.clipboard_timeout_seconds(state.config.clipboard_timeout_seconds);
+    if timeout_seconds > 0 {
+        state.osc52_clear_at = Some(Instant::now() + Duration::from_secs(timeout_seconds));
+    }
+    true
+}
+
+/// Собрать очередь записей, помеченных для смены пароля, и открыть экран ротации
+fn open_rotation(state: &mut TuiState, storage: &PasswordStorage) {
+    clear_rotation(state);
+    if let Some(ref key) = state.encryption_key {
+        for (filename, name) in &state.name_to_filename {
+            let flagged = storage
+                .load_entry_details(filename, key.as_slice())
+                .map(|d| d.needs_rotation)
+                .unwrap_or(false);
+            if flagged {
+                state.rotation_queue.push((filename.clone(), name.clone()));
+            }
+        }
+    }
+    state.rotation_queue.sort_by(|a, b| a.1.cmp(&b.1));
+    load_rotation_item(state, storage);
+    state.current_screen = Screen::Rotation;
+}
+
+/// Загрузить текущий пароль, URL и политику записи под `rotation_index`
+fn load_rotation_item(state: &mut TuiState, storage: &PasswordStorage) {
+    state.rotation_old_password.zeroize();
+    state.rotation_new_password.zeroize();
+    state.rotation_url = None;
+    state.rotation_policy = None;
+
+    let (Some((filename, _)), Some(key)) = (state.rotation_queue.get(state.rotation_index), state.encryption_key.as_ref()) else {
+        return;
+    };
+    state.rotation_old_password = storage.load_password_file(filename, key.as_slice()).unwrap_or_default();
+    if let Ok(details) = storage.load_entry_details(filename, key.as_slice()) {
+        state.rotation_url = details.url;
+        state.rotation_policy = details.password_policy;
+    }
+}
+
+/// Сбросить состояние экрана ротации, затерев пароли
+fn clear_rotation(state: &mut TuiState) {
+    state.rotation_queue.clear();
+    state.rotation_index = 0;
+    state.rotation_old_password.zeroize();
+    state.rotation_new_password.zeroize();
+    state.rotation_url = None;
+    state.rotation_policy = None;
+    state.rotation_show_passwords = false;
+}
+
+/// Открыть URL в браузере по умолчанию (без схемы подставляется https://)
+fn open_url(url: &str) -> io::Result<()> {
+    use std::process::{Command, Stdio};
+
+    let url = if url.contains("://") {
+        url.to_string()
+    } else {
+        format!("https://{}", url)
+    };
+
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", "", &url]);
+        command
+    };
+    #[cfg(target_os = "macos")]
+    let mut command = {
+        let mut command = Command::new("open");
+        command.arg(&url);
+        command
+    };
+    #[cfg(all(unix, not(target_os = "macos")))]
+    let mut command = {
+        let mut command = Command::new("xdg-open");
+        command.arg(&url);
+        command
+    };
+
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(|_| ())
+}
+
+/// Проверить старый мастер-пароль, вывести новый ключ со свежей солью, перешифровать
+/// все хранилище и обновить конфигурацию директории. Ошибка - текст для пользователя.
+fn change_master_password(state: &mut TuiState, storage: &PasswordStorage, crypto: &CryptoManager) -> Result<(), String> {
+    let Some(ref old_key) = state.encryption_key else {
+        return Err(state.i18n.ts("change_master_locked").to_string());
+    };
+
+    let passwords_dir = state.config.passwords_directory_path();
+    let mut dir_config = DirectoryConfig::load(&passwords_dir).map_err(|e| e.to_string())?;
+    let verified = dir_config
+        .master_password_hash
+        .as_ref()
+        .map(|hash| crypto.verify_password(&state.change_master_old, hash).unwrap_or(false))
+        .unwrap_or(false);
+    if !verified {
+        return Err(state.i18n.ts("change_master_wrong_old").to_string());
+    }
+    if state.change_master_new.is_empty() {
+        return Err(state.i18n.ts("change_master_empty").to_string());
+    }
+    if dir_config.policy.check_master_password(&state.change_master_new).is_err() {
+        return Err(policy_password_too_short(state));
+    }
+    if state.change_master_new != state.change_master_confirm {
+        return Err(state.i18n.ts("change_master_mismatch").to_string());
+    }
+    if hint_reveals_password(&state.change_master_hint, &state.change_master_new) {
+        return Err(state.i18n.ts("master_password_hint_reveals").to_string());
+    }
+
+    let argon2 = change_master_argon2(state, dir_config.kdf.argon2())?;
+
+    // API не пишет в хранилище, пока оно перешифровывается: его запись между подготовкой
+    // файлов и их переименованием пропала бы. Ключ API получает снова после смены
+    state.entry_metadata.clear();
+    // Новая соль и ключ; конфигурация директории переименовывается вместе с файлами хранилища
+    let result = storage.change_master_password(
+        old_key.as_slice(),
+        &state.change_master_new,
+        argon2,
+        non_empty(&state.change_master_hint),
+        &mut dir_config,
+    );
+    let new_key = match result {
+        Ok(new_key) => new_key,
+        Err(e) => {
+            load_entry_metadata(state, storage);
+            return Err(e.to_string());
+        }
+    };
+
+    if let Some(mut old_key) = state.encryption_key.replace(new_key) {
+        old_key.zeroize();
+    }
+    Ok(())
+}
+
+/// Перешифровать хранилище ключом с новой солью (мастер-пароль прежний).
+/// Ошибка - текст для пользователя
+fn rotate_encryption_key(state: &mut TuiState, storage: &PasswordStorage) -> Result<(), String> {
+    let Some(ref old_key) = state.encryption_key else {
+        return Err(state.i18n.ts("change_master_locked").to_string());
+    };
+    let password = state.rotate_key_password.as_deref().unwrap_or_default();
+    let passwords_dir = state.config.passwords_directory_path();
+    let mut dir_config = DirectoryConfig::load(&passwords_dir).map_err(|e| e.to_string())?;
+    // API не пишет в хранилище, пока оно перешифровывается (как при смене мастер-пароля)
+    state.entry_metadata.clear();
+    let new_key = match storage.rotate_key(old_key.as_slice(), password, &mut dir_config) {
+        Ok(new_key) => new_key,
+        Err(e) => {
+            load_entry_metadata(state, storage);
+            return Err(match e {
+                RpmError::AuthenticationFailed => state.i18n.ts("change_master_wrong_old").to_string(),
+                e => e.to_string(),
+            });
+        }
+    };
+    if let Some(mut old_key) = state.encryption_key.replace(new_key) {
+        old_key.zeroize();
+    }
+    Ok(())
+}
+
+/// Параметры Argon2id с экрана смены мастер-пароля; пустое поле оставляет текущее значение.
+/// Ошибка - текст для пользователя
+fn change_master_argon2(state: &TuiState, current: Argon2id) -> Result<Argon2id, String> {
+    let field = |index: usize| -> Result<Option<u32>, String> {
+        let text = state.change_master_argon2[index].trim();
+        if text.is_empty() {
+            return Ok(None);
+        }
+        text.parse()
+            .map(Some)
+            .map_err(|_| format!("{} {}", state.i18n.ts("change_master_argon2_invalid"), text))
+    };
+    let argon2 = Argon2id {
+        memory_kib: field(0)?.map_or(current.memory_kib, |mib| mib.saturating_mul(1024)),
+        iterations: field(1)?.unwrap_or(current.iterations),
+        parallelism: field(2)?.unwrap_or(current.parallelism),
+    };
+    argon2.params().map_err(|e| e.to_string())?;
+    Ok(argon2)
+}
+
+/// Сообщение о слишком коротком мастер-пароле с минимумом из политики
+fn policy_password_too_short(state: &TuiState) -> String {
+    format!(
+        "{} {}",
+        state.i18n.ts("policy_password_too_short"),
+        state.policy.min_master_password_length.unwrap_or_default()
+    )
+}
+
+/// Сбросить состояние мастера импорта (таблица с паролями затирается при удалении)
+fn clear_import(state: &mut TuiState) {
+    state.import_path_input.clear();
+    state.import_table = None;
+    state.import_mapping.clear();
+    state.import_column = 0;
+    state.import_mapping_restored = false;
+    state.import_report = None;
+    state.import_error = None;
+}
+
+/// Сбросить экран резервного копирования; путь - новый файл в каталоге резервных копий
+fn clear_backup(state: &mut TuiState) {
+    state.backup_path_input = state.config.backup_directory_path()
+        .join(format!("rpm-{}.{}", Local::now().format("%Y%m%d-%H%M%S"), backup::BACKUP_EXTENSION))
+        .to_string_lossy()
+        .into_owned();
+    state.backup_passphrase.zeroize();
+    state.backup_confirm.zeroize();
+    state.backup_field = 0;
+    state.backup_error = None;
+}
+
+/// Сбросить экран отката к резервной копии
+fn clear_restore(state: &mut TuiState) {
+    state.restore_backups.clear();
+    state.restore_picker = ListPicker::default();
+    state.restore_selected = None;
+    state.restore_master.zeroize();
+    state.restore_passphrase.zeroize();
+    state.restore_field = 0;
+    state.restore_error = None;
+}
+
+/// Сбросить экран синхронизации
+fn clear_sync(state: &mut TuiState) {
+    state.sync_remote_input.clear();
+    state.sync_conflicts.clear();
+    state.sync_message = None;
+    state.sync_error = None;
+}
+
+/// Имена записей для конфликтующих файлов хранилища (сами файлы названы по UUID)
+fn conflict_labels(state: &TuiState, files: &[String]) -> Vec<String> {
+    files
+        .iter()
+        .map(|file| {
+            if file == "def" {
+                return state.i18n.ts("sync_conflict_def").to_string();
+            }
+            state
+                .name_to_filename
+                .iter()
+                .find(|(filename, _)| filename == file)
+                .map(|(_, name)| name.clone())
+                .unwrap_or_else(|| file.clone())
+        })
+        .collect()
+}
+
+/// Открыть хранилище полученным ключом: удалить просроченное из корзины, запустить
+/// расшифровку имен в фоне и перейти на главный экран
+fn open_vault(state: &mut TuiState, storage: &PasswordStorage, crypto: &CryptoManager, key: SecureKey, list_state: &mut ListState) {
+    state.encryption_key = Some(key);
+    state.entry_metadata.events().publish(VaultEvent::Unlocked);
+
+    // Записи, пролежавшие в корзине дольше срока хранения, удаляются насовсем
+    if let Some(ref key) = state.encryption_key {
+        if let Ok(purged) = storage.purge_expired_trash(state.config.trash_retention_days, key.as_slice()) {
+            if purged > 0 {
+                state.status_message = Some(format!("{} {}", state.i18n.ts("trash_expired_purged"), purged));
+            }
+        }
+    }
+
+    // Главный экран открывается сразу, а имена расшифровываются в фоне
+    let mut saved_ui_state = None;
+    if let Some(ref key) = state.encryption_key {
+        match storage.load_def_file(key.as_slice()) {
+            Ok(def_file) => {
+                state.entry_tags = storage.list_entry_tags(key.as_slice()).unwrap_or_default();
+                state.entry_folders = storage.list_entry_folders(key.as_slice()).unwrap_or_default();
+                state.archived_entries = storage.list_archived_entries(key.as_slice()).unwrap_or_default();
+                // Состояние от другого мастер-пароля или поврежденное просто не восстанавливается
+                saved_ui_state = storage.load_ui_state(key.as_slice()).ok();
+                state.names_loading = Some((0, def_file.entries.len()));
+                state.name_loader = Some(NameLoader::start(&state.config, crypto.clone(), def_file.entries, key.as_slice()));
+            }
+            Err(_) => {
+                // Empty list if def file doesn't exist or can't be decrypted
+                state.all_items = Vec::new();
+                state.filtered_items = Vec::new();
+            }
+        }
+    }
+    if let Some(ui_state) = saved_ui_state {
+        restore_ui_state(state, ui_state);
+    }
+
+    state.current_screen = Screen::Main;
+    if state.list_len() > 0 {
+        list_state.select(Some(0));
+    }
+}
+
+/// Ключ из связки ключей ОС при запуске, если разблокировка через нее включена и
+/// политика хранилища не требует ключевой файл
+fn load_key_from_keychain(state: &mut TuiState, crypto: &CryptoManager) -> Option<SecureKey> {
+    let passwords_dir = state.config.passwords_directory_path();
+    let dir_config = DirectoryConfig::load(&passwords_dir).ok()?;
+    dir_config.policy.check_unlock().ok()?;
+    let salt = dir_config.encryption_key_salt?;
+    match keychain::load(crypto, &passwords_dir, &salt) {
+        Ok(key) => key,
+        Err(e) => {
+            state.status_message = Some(format!("{} {}", state.i18n.ts("keychain_failed"), e));
+            None
+        }
+    }
+}
+
+/// Сохранить ключ хранилища в связке ключей ОС для следующего запуска
+fn save_key_to_keychain(state: &mut TuiState, crypto: &CryptoManager, key: &[u8]) {
+    let passwords_dir = state.config.passwords_directory_path();
+    let salt = DirectoryConfig::load(&passwords_dir).ok().and_then(|dir_config| dir_config.encryption_key_salt);
+    if let Some(salt) = salt {
+        if let Err(e) = keychain::store(crypto, &passwords_dir, &salt, key) {
+            state.status_message = Some(format!("{} {}", state.i18n.ts("keychain_failed"), e));
+        }
+    }
+}
+
+/// Сохранить ключ открытого хранилища в связке ключей ОС: после смены ключа прежний
+/// там устарел
+fn save_current_key_to_keychain(state: &mut TuiState, crypto: &CryptoManager) {
+    if let Some(key) = state.encryption_key.as_ref().map(|key| Zeroizing::new(key.as_slice().to_vec())) {
+        save_key_to_keychain(state, crypto, &key);
+    }
+}
+
+/// Открыть корзину: загрузить удаленные записи
+fn open_trash(state: &mut TuiState, storage: &PasswordStorage) {
+    clear_trash(state);
+    load_trash(state, storage);
+    state.current_screen = Screen::Trash;
+}
+
+/// Перечитать корзину, сохранив позицию курсора в пределах списка
+fn load_trash(state: &mut TuiState, storage: &PasswordStorage) {
+    state.trash_items.clear();
+    if let Some(ref key) = state.encryption_key {
+        match storage.list_trash(key.as_slice()) {
+            Ok(items) => state.trash_items = items,
+            Err(e) => state.trash_error = Some(e.to_string()),
+        }
+    }
+    state.trash_picker.index = state.trash_picker.index.min(state.trash_items.len().saturating_sub(1));
+}
+
+/// Вернуть выбранную запись из корзины в список паролей
+fn restore_trash_item(state: &mut TuiState, storage: &PasswordStorage) {
+    let Some((filename, name, _)) = state.trash_items.get(state.trash_picker.index).cloned() else {
+        return;
+    };
+    state.trash_message = None;
+    state.trash_error = None;
+    if let Some(ref key) = state.encryption_key {
+        match storage.restore_from_trash(&filename, key.as_slice()) {
+            Ok(()) => {
+                state.trash_message = Some(format!("{} {}", state.i18n.ts("trash_restored"), name));
+                if let Ok(names) = storage.list_decrypted_names(key.as_slice()) {
+                    state.name_to_filename = names.clone();
+                    state.all_items = names.iter().map(|(_, name)| name.clone()).collect();
+                    load_entry_metadata(state, storage);
+                    filter_items(state);
+                }
+            }
+            Err(e) => state.trash_error = Some(e.to_string()),
+        }
+    }
+    load_trash(state, storage);
+}
+
+/// Удалить выбранную запись из корзины вместе с файлом пароля
+fn purge_trash_item(state: &mut TuiState, storage: &PasswordStorage) {
+    let Some((filename, name, _)) = state.trash_items.get(state.trash_picker.index).cloned() else {
+        return;
+    };
+    state.trash_message = None;
+    state.trash_error = None;
+    if let Some(ref key) = state.encryption_key {
+        match storage.purge_trash(|f, _| f == filename, key.as_slice()) {
+            Ok(_) => state.trash_message = Some(format!("{} {}", state.i18n.ts("trash_purged"), name)),
+            Err(e) => state.trash_error = Some(e.to_string()),
+        }
+    }
+    load_trash(state, storage);
+}
+
+/// Сбросить состояние экрана корзины
+fn clear_trash(state: &mut TuiState) {
+    state.trash_items.clear();
+    state.trash_picker = ListPicker::default();
+    state.trash_purge_confirm = false;
+    state.trash_message = None;
+    state.trash_error = None;
+}
+
+/// Открыть список осиротевших файлов и записей
+fn open_orphans(state: &mut TuiState, storage: &PasswordStorage) {
+    clear_orphans(state);
+    load_orphans(state, storage);
+    state.current_screen = Screen::Orphans;
+}
+
+/// Перечитать осиротевшие файлы и записи, сохранив позицию курсора
+fn load_orphans(state: &mut TuiState, storage: &PasswordStorage) {
+    state.orphans.clear();
+    if let Some(ref key) = state.encryption_key {
+        match storage.find_orphans(key.as_slice()) {
+            Ok(orphans) => state.orphans = orphans,
+            Err(e) => state.orphans_error = Some(e.to_string()),
+        }
+    }
+    state.orphans_picker.index = state.orphans_picker.index.min(state.orphans.len().saturating_sub(1));
+}
+
+/// Вернуть выбранный файл в список под именем «Восстановлено <id>». Записи без
+/// файла привязывать не к чему
+fn relink_selected_orphan(state: &mut TuiState, storage: &PasswordStorage) {
+    let Some(orphan) = state.orphans.get(state.orphans_picker.index) else {
+        return;
+    };
+    if let Orphan::Entry { .. } = orphan {
+        state.orphans_message = None;
+        state.orphans_error = Some(state.i18n.ts("orphans_relink_entry").to_string());
+        return;
+    }
+    let id: String = orphan.filename().trim_end_matches(".pwd").chars().take(8).collect();
+    let name = format!("{} {}", state.i18n.ts("orphans_recovered_name"), id);
+    repair_selected_orphan(state, storage, OrphanRepair::Relink { name });
+}
+
+/// Применить `repair` к выбранному элементу. Список имен перечитывается по
+/// событиям хранилища
+fn repair_selected_orphan(state: &mut TuiState, storage: &PasswordStorage, repair: OrphanRepair) {
+    let Some(orphan) = state.orphans.get(state.orphans_picker.index).cloned() else {
+        return;
+    };
+    state.orphans_message = None;
+    state.orphans_error = None;
+    let message_key = match repair {
+        OrphanRepair::Relink { .. } => "orphans_relinked",
+        OrphanRepair::Quarantine => "orphans_quarantined",
+        OrphanRepair::Delete => "orphans_deleted",
+    };
+    if let Some(ref key) = state.encryption_key {
+        match storage.repair_orphan(&orphan, &repair, key.as_slice()) {
+            Ok(()) => state.orphans_message = Some(format!("{} {}", state.i18n.ts(message_key), orphan.filename())),
+            Err(e) => state.orphans_error = Some(e.to_string()),
+        }
+    }
+    load_orphans(state, storage);
+}
+
+/// Сбросить состояние экрана осиротевших записей
+fn clear_orphans(state: &mut TuiState) {
+    state.orphans.clear();
+    state.orphans_picker = ListPicker::default();
+    state.orphans_delete_confirm = false;
+    state.orphans_message = None;
+    state.orphans_error = None;
+}
+
+fn orphan_labels(state: &TuiState) -> Vec<String> {
+    state.orphans.iter().map(|orphan| orphan_label(state, orphan)).collect()
+}
+
+/// Файл показывается по имени, запись - по расшифрованному названию, если оно известно
+fn orphan_label(state: &TuiState, orphan: &Orphan) -> String {
+    match orphan {
+        Orphan::File { filename } => filename.clone(),
+        Orphan::Entry { filename } => state
+            .name_to_filename
+            .iter()
+            .find(|(f, _)| f == filename)
+            .map(|(_, name)| name.clone())
+            .unwrap_or_else(|| filename.clone()),
+    }
+}
+
+fn trash_labels(state: &TuiState) -> Vec<String> {
+    state.trash_items.iter().map(|(_, name, _)| name.clone()).collect()
+}
+
+/// Подписи архивов в списке отката: дата, число записей, имя файла
+fn restore_labels(state: &TuiState) -> Vec<String> {
+    state
+        .restore_backups
+        .iter()
+        .map(|info| {
+            format!(
+                "{}  ({} {})  {}",
+                format_datetime(state, info.created_at),
+                info.entry_count,
+                state.i18n.ts("backup_entries"),
+                info.path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default()
+            )
+        })
+        .collect()
+}
+
+/// Откатить хранилище к выбранному архиву. Мастер-пароль текущего хранилища проверяется,
+/// а само хранилище сначала архивируется с тем же паролем архива.
+fn restore_vault(state: &TuiState, crypto: &CryptoManager) -> Result<String, String> {
+    let info = state
+        .restore_selected
+        .and_then(|index| state.restore_backups.get(index))
+        .ok_or_else(|| state.i18n.ts("restore_no_backups").to_string())?;
+
+    let passwords_dir = state.config.passwords_directory_path();
+    let dir_config = DirectoryConfig::load(&passwords_dir).map_err(|e| e.to_string())?;
+    let verified = dir_config
+        .master_password_hash
+        .as_ref()
+        .map(|hash| crypto.verify_password(&state.restore_master, hash).unwrap_or(false))
+        .unwrap_or(false);
+    if !verified {
+        return Err(state.i18n.ts("change_master_wrong_old").to_string());
+    }
+    if info.is_encrypted() && state.restore_passphrase.is_empty() {
+        return Err(state.i18n.ts("backup_empty_passphrase").to_string());
+    }
+
+    let current = info.path.with_file_name(format!(
+        "rpm-before-restore-{}.{}",
+        Local::now().format("%Y%m%d-%H%M%S"),
+        backup::BACKUP_EXTENSION
+    ));
+    // Без пароля архива (автоматические копии) текущее хранилище сохраняется так же без него
+    if state.restore_passphrase.is_empty() {
+        backup::create_snapshot(&passwords_dir, &current)
+    } else {
+        backup::create_backup(&passwords_dir, &current, &state.restore_passphrase, crypto)
+    }
+    .map_err(|e| e.to_string())?;
+
+    let restored = backup::restore_backup(&info.path, &passwords_dir, &state.restore_passphrase, crypto).map_err(|e| match e {
+        RpmError::AuthenticationFailed => {
+            // Пароль не подошел к архиву, хранилище не изменено: снимок текущего больше не нужен
+            let _ = std::fs::remove_file(&current);
+            state.i18n.ts("restore_wrong_passphrase").to_string()
+        }
+        e => e.to_string(),
+    })?;
+    Ok(format!(
+        "{} {} {} | {} {}",
+        state.i18n.ts("restore_done"),
+        restored,
+        state.i18n.ts("backup_entries"),
+        state.i18n.ts("restore_previous_saved"),
+        current.display()
+    ))
+}
+
+/// Очистить поля экрана смены мастер-пароля
+fn clear_change_master(state: &mut TuiState) {
+    state.change_master_old.zeroize();
+    state.change_master_new.zeroize();
+    state.change_master_confirm.zeroize();
+    state.change_master_hint.clear();
+    state.change_master_field = 0;
+    state.change_master_argon2 = Default::default();
+    state.change_master_error = None;
+}
+
+/// Стереть расшифрованные имена записей и результаты поиска. Ключ хранилища
+/// остается только зашифрованным (`scrubbed_key`); если зашифровать его не
+/// удалось, хранилище блокируется
+pub fn scrub_metadata(state: &mut TuiState, crypto: &CryptoManager) {
+    if let Some(key) = state.encryption_key.take() {
+        let mut wrapping_key = vec![0u8; 32];
+        OsRng.fill_bytes(&mut wrapping_key);
+        let wrapping_key = SecureKey::new(wrapping_key);
+        match keychain::WrappedKey::wrap(crypto, key.as_slice(), "", wrapping_key.as_slice()) {
+            Ok(wrapped) => state.scrubbed_key = Some((wrapped, wrapping_key)),
+            Err(e) => {
+                tracing::warn!("Could not wrap the vault key, locking: {}", e);
+                lock_vault(state);
+                return;
+            }
+        }
+    }
+    wipe_names(state);
+    state.metadata_scrubbed = true;
+}
+
+/// Затереть расшифрованные имена записей, метаданные и результаты поиска и аудита
+fn wipe_names(state: &mut TuiState) {
+    for (_, name) in state.name_to_filename.iter_mut() {
+        name.zeroize();
+    }
+    state.name_loader = None;
+    state.names_loading = None;
+    state.name_to_filename.clear();
+    state.entry_tags.clear();
+    state.entry_folders.clear();
+    state.archived_entries.clear();
+    state.entry_metadata.clear();
+    state.visible_folders.clear();
+    state.all_items.zeroize();
+    state.filtered_items.zeroize();
+    state.audit_reuse_groups.zeroize();
+    for (name, _) in state.audit_breached.iter_mut() {
+        name.zeroize();
+    }
+    state.audit_breached.clear();
+}
+
+/// Вернуть ключ и заново расшифровать имена записей после `scrub_metadata`
+pub fn restore_metadata(state: &mut TuiState, storage: &PasswordStorage, crypto: &CryptoManager) {
+    state.metadata_scrubbed = false;
+    if let Some((wrapped, wrapping_key)) = state.scrubbed_key.take() {
+        match wrapped.unwrap(crypto, "", wrapping_key.as_slice()) {
+            Ok(key) => state.encryption_key = key,
+            Err(e) => tracing::warn!("Could not unwrap the vault key: {}", e),
+        }
+        if state.encryption_key.is_none() {
+            lock_vault(state);
+            return;
+        }
+    }
+    if let Some(ref key) = state.encryption_key {
+        if let Ok(names) = storage.list_decrypted_names(key.as_slice()) {
+            state.name_to_filename = names.clone();
+            state.all_items = names.iter().map(|(_, name)| name.clone()).collect();
+            load_entry_metadata(state, storage);
+            filter_items(state);
+        }
+    }
+}
+
+/// Вернуться к работе после `scrub_metadata` и выбрать запись на прежнем месте
+fn resume_after_scrub(state: &mut TuiState, storage: &PasswordStorage, crypto: &CryptoManager, list_state: &mut ListState) {
+    restore_metadata(state, storage, crypto);
+    list_state.select(if state.list_len() == 0 {
+        None
+    } else {
+        Some(state.selected_index.min(state.list_len() - 1))
+    });
+}
+
+/// Разобрать накопившиеся события хранилища: добавленные и удаленные записи попадают
+/// в журнал аудита, а список имен перечитывается, если записи изменились не в нем
+fn follow_vault_events(
+    state: &mut TuiState,
+    storage: &PasswordStorage,
+    list_state: &mut ListState,
+    events: &mut broadcast::Receiver<VaultEvent>,
+    names_stale: &mut bool,
+) {
+    let mut audited = Vec::new();
+    loop {
+        match events.try_recv() {
+            Ok(event) => {
+                *names_stale |= event.entry_id().is_some() || event == VaultEvent::SyncCompleted { changed: true };
+                match event {
+                    VaultEvent::EntryAdded { id } => audited.push((AuditAction::EntryAdded, id)),
+                    VaultEvent::EntryDeleted { id } => audited.push((AuditAction::EntryDeleted, id)),
+                    _ => {}
+                }
+            }
+            // Часть событий потеряна: надежнее перечитать все
+            Err(broadcast::error::TryRecvError::Lagged(_)) => *names_stale = true,
+            Err(_) => break,
+        }
+    }
+    let Some(key) = state.encryption_key.as_ref().map(|key| key.as_slice().to_vec()) else {
+        *names_stale = false;
+        return;
+    };
+    let key = Zeroizing::new(key);
+
+    // Имя удаленной записи известно только до перечитывания, добавленной - только после
+    let name_of = |state: &TuiState, id: &str| {
+        state
+            .name_to_filename
+            .iter()
+            .find(|(filename, _)| filename == id)
+            .map(|(_, name)| name.clone())
+    };
+    let mut details: Vec<Option<String>> = audited
+        .iter()
+        .map(|(action, id)| if *action == AuditAction::EntryDeleted { name_of(state, id) } else { None })
+        .collect();
+
+    // Во время фоновой загрузки и пока имена стерты список перечитается и так
+    if *names_stale && state.name_loader.is_none() && !state.metadata_scrubbed {
+        *names_stale = false;
+        match storage.list_decrypted_names(&key) {
+            Ok(names) => {
+                let mut current = state.name_to_filename.clone();
+                let mut fresh = names.clone();
+                current.sort_unstable();
+                fresh.sort_unstable();
+                if current != fresh {
+                    state.all_items = names.iter().map(|(_, name)| name.clone()).collect();
+                    state.name_to_filename = names;
+                    load_entry_metadata(state, storage);
+                    filter_items(state);
+                    state.selected_index = state.selected_index.min(state.list_len().saturating_sub(1));
+                    list_state.select(if state.list_len() == 0 { None } else { Some(state.selected_index) });
+                }
+            }
+            // Имена не расшифровываются: после синхронизации сменился мастер-пароль
+            Err(_) => {
+                lock_vault(state);
+                list_state.select(None);
+                state.status_message = Some(state.i18n.ts("sync_relock").to_string());
+                return;
+            }
+        }
+    }
+
+    if audited.is_empty() {
+        return;
+    }
+    for ((_, id), name) in audited.iter().zip(details.iter_mut()) {
+        if name.is_none() {
+            *name = name_of(state, id);
+        }
+    }
+    let user = export::current_user();
+    let at = Utc::now();
+    let records = audited
+        .into_iter()
+        .zip(details)
+        .map(|((action, id), name)| AuditEvent {
+            action,
+            at,
+            user: user.clone(),
+            details: name.unwrap_or(id),
+        })
+        .collect();
+    if let Err(e) = storage.record_audit_events(records, &key) {
+        state.status_message = Some(format!("{} {}", state.i18n.ts("audit_log_failed"), e));
+    }
+}
+
+/// Хранилище текущей директории; его изменения публикуются в шину событий хранилища
+fn open_storage(state: &TuiState, crypto: &CryptoManager) -> PasswordStorage {
+    PasswordStorage::new(&state.config, crypto.clone()).with_events(state.entry_metadata.events().clone())
+}
+
+/// Заблокировать хранилище: затереть ключ и все расшифрованные данные, показать экран мастер-пароля
+fn lock_vault(state: &mut TuiState) {
+    if let Some(mut key) = state.encryption_key.take() {
+        key.zeroize();
+        state.entry_metadata.events().publish(VaultEvent::Locked);
+    }
+    // Хранилище, простаивавшее со стертыми именами, тоже было открыто
+    if state.scrubbed_key.take().is_some() {
+        state.entry_metadata.events().publish(VaultEvent::Locked);
+    }
+
+    // Расшифрованные имена затираются, а не просто освобождаются
+    wipe_names(state);
+    state.metadata_scrubbed = false;
+    state.pending_list_position = None;
+    state.macros.clear();
+    state.current_folder.clear();
+    state.show_archived = false;
+    state.move_to_folder = None;
+    state.move_folder_input.clear();
+    state.search_query.zeroize();
+    state.selected_index = 0;
+    state.delete_confirmation = None;
+    state.status_message = None;
+    state.audit_breach_status = None;
+    state.maintenance_report = None;
+    state.maintenance_error = None;
+    state.maintenance_integrity = None;
+    state.rotate_key_password.zeroize();
+    state.rotate_key_password = None;
+    state.rotate_key_error = None;
+    clear_orphans(state);
+    reset_password_entry(state);
+    clear_rotation(state);
+    clear_change_master(state);
+    clear_import(state);
+    clear_backup(state);
+    clear_restore(state);
+    clear_trash(state);
+    clear_sync(state);
+
+    state.master_password_input.zeroize();
+    state.master_password_confirm.zeroize();
+    state.master_password_field = 0;
+    state.master_password_show_password = false;
+    state.is_creating_master_password = false;
+    state.current_screen = Screen::MasterPassword;
+}
+
+/// Очистить поля экрана записи пароля
+fn reset_password_entry(state: &mut TuiState) {
+    state.password_entry_name.clear();
+    state.password_entry_username.clear();
+    state.password_entry_password.zeroize();
+    state.password_entry_url.clear();
+    state.password_entry_tags.clear();
+    state.password_entry_notes.zeroize();
+    state.password_entry_show_password = false;
+    state.password_entry_field = 0;
+    state.password_entry_reuse_warning = None;
+    for question in state.password_entry_security_questions.iter_mut() {
+        question.answer.zeroize();
+    }
+    state.password_entry_security_questions.clear();
+    for field in state.password_entry_custom_fields.iter_mut() {
+        field.value.zeroize();
+    }
+    state.password_entry_custom_fields.clear();
+    state.password_entry_ssh_host.clear();
+    state.password_entry_ssh_port.clear();
+    state.password_entry_ssh_username.clear();
+    state.password_entry_autotype.clear();
+    state.password_entry_access_window.clear();
+    state.password_entry_policy = None;
+    state.password_entry_timestamps = EntryTimestamps::default();
+    state.password_entry_tray_shortcut = false;
+    state.password_entry_editor_error = None;
+}
+
+/// Строка состояния после неудачных входов через API: сколько было неверных
+/// мастер-паролей и какие расширения заблокированы и до какого времени
+fn api_auth_failures_message(state: &TuiState) -> String {
+    let mut message = format!("{} {}", state.i18n.ts("api_auth_failures"), state.auth_throttle.failed_attempts());
+    let clients = state.pairing.clients();
+    for (id, until) in state.auth_throttle.locked_out(Utc::now()) {
+        let name = clients.iter().find(|client| client.id == id).map_or(id.as_str(), |client| client.name.as_str());
+        message.push_str(&format!(" | {}: {} {}", name, state.i18n.ts("api_auth_locked_until"), format_datetime(state, until)));
+    }
+    message
+}
+
+/// Дата и время в формате из настроек (или формате языка) и выбранном часовом поясе
+fn format_datetime(state: &TuiState, datetime: DateTime<Utc>) -> String {
+    state.i18n.format_datetime(
+        datetime,
+        state.config.datetime_format.as_deref(),
+        state.config.display_timezone.eq_ignore_ascii_case("utc"),
+    )
+}
+
+/// Случайный ответ на контрольный вопрос: четыре группы по пять символов через дефис,
+/// чтобы его можно было продиктовать по телефону
+fn generate_security_answer() -> String {
+    const CHARS: &[u8] = b"abcdefghijkmnpqrstuvwxyz23456789";
+    let mut rng = OsRng;
+    (0..4)
+        .map(|_| {
+            (0..5)
+                .map(|_| CHARS[rng.gen_range(0..CHARS.len())] as char)
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Пустая строка (после trim) -> None
+fn non_empty(value: &str) -> Option<String> {
+    let trimmed = value.trim();
+    if trimmed.is_empty() {
+        None
+    } else {
+        Some(value.to_string())
+    }
+}
+
+/// Пустой порт (22 по умолчанию) или число 1-65535
+fn ssh_port_valid(port: &str) -> bool {
+    port.is_empty() || port.parse::<u16>().is_ok_and(|port| port > 0)
+}
+
+/// SSH-подключение из полей записи; без хоста записи не нужен блок `ssh`
+fn entry_ssh_host(state: &TuiState) -> Option<SshHost> {
+    let host = non_empty(&state.password_entry_ssh_host)?;
+    Some(SshHost {
+        host,
+        port: state.password_entry_ssh_port.parse().ok().filter(|&port| port > 0),
+        username: non_empty(&state.password_entry_ssh_username),
+    })
+}
+
+fn filter_items(state: &mut TuiState) {
+    // Архивные записи видны только в режиме архива, остальные - только вне его
+    let in_view = |filename: &String| state.archived_entries.contains(filename) == state.show_archived;
+
+    // Без запроса показываем содержимое текущей папки; поиск идет по всем папкам
+    if state.search_query.trim().is_empty() {
+        let folders: HashMap<String, String> = state
+            .entry_folders
+            .iter()
+            .filter(|(filename, _)| in_view(filename))
+            .map(|(filename, folder)| (filename.clone(), folder.clone()))
+            .collect();
+        state.visible_folders = child_folders(&folders, &state.current_folder);
+        state.filtered_items = state
+            .name_to_filename
+            .iter()
+            .filter(|(filename, _)| in_view(filename))
+            .filter(|(filename, _)| state.entry_folders.get(filename).map(String::as_str).unwrap_or_default() == state.current_folder)
+            .map(|(_, name)| name.clone())
+            .collect();
+        return;
+    }
+    state.visible_folders.clear();
+
+    let (tags, text) = split_tag_query(&state.search_query);
+    if tags.is_empty() && !state.show_archived && state.archived_entries.is_empty() {
+        state.filtered_items = fuzzy_filter(&state.all_items, &text);
+        return;
+    }
+    let candidates: Vec<String> = state
+        .name_to_filename
+        .iter()
+        .filter(|(filename, _)| in_view(filename))
+        .filter(|(filename, _)| {
+            let entry_tags = state.entry_tags.get(filename).map(Vec::as_slice).unwrap_or_default();
+            tags.iter().all(|tag| entry_tags.iter().any(|t| t.to_lowercase().starts_with(tag.as_str())))
+        })
+        .map(|(_, name)| name.clone())
+        .collect();
+    state.filtered_items = fuzzy_filter(&candidates, &text);
+}
+
+/// Разделить поисковый запрос на теги (слова с `#`, в нижнем регистре, без `#`) и текст.
+/// Тег совпадает по началу, чтобы список сужался прямо во время ввода.
+pub fn split_tag_query(query: &str) -> (Vec<String>, String) {
+    let mut tags = Vec::new();
+    let mut words = Vec::new();
+    for word in query.split_whitespace() {
+        match word.strip_prefix('#') {
+            Some(tag) => tags.push(tag.to_lowercase()),
+            None => words.push(word),
+        }
+    }
+    (tags, words.join(" "))
+}
+
+/// Теги из поля ввода: через запятую или пробел
+fn parse_tags(input: &str) -> Vec<String> {
+    input
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|tag| !tag.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Загрузить теги (для фильтра `#тег` в поиске), папки и архивные записи
+/// и обновить метаданные, которые видит API расширений
+fn load_entry_metadata(state: &mut TuiState, storage: &PasswordStorage) {
+    // Список перечитан целиком: недогруженные в фоне имена больше не нужны
+    state.name_loader = None;
+    state.names_loading = None;
+    let Some(ref key) = state.encryption_key else {
+        state.entry_tags.clear();
+        state.entry_folders.clear();
+        state.archived_entries.clear();
+        state.entry_metadata.clear();
+        return;
+    };
+    state.entry_tags = storage.list_entry_tags(key.as_slice()).unwrap_or_default();
+    state.entry_folders = storage.list_entry_folders(key.as_slice()).unwrap_or_default();
+    state.archived_entries = storage.list_archived_entries(key.as_slice()).unwrap_or_default();
+    match storage.list_entry_meta(key.as_slice()) {
+        Ok(entries) => publish_to_api(state, storage, entries),
+        Err(_) => state.entry_metadata.clear(),
+    }
+}
+
+/// Открыть хранилище для API: метаданные для списка расширения и копию ключа
+/// для эндпоинтов паролей (стирается при блокировке)
+fn publish_to_api(state: &mut TuiState, storage: &PasswordStorage, entries: Vec<EntryMeta>) {
+    let Some(ref key) = state.encryption_key else {
+        return;
+    };
+    state.vault_stats.entries = entries.len();
+    state.entry_metadata.publish(entries);
+    state.entry_metadata.publish_vault(storage.handle(), SecureKey::new(key.as_slice().to_vec()));
+}
+
+/// Клавиши макросов на любом экране: F11 (запись), F12 (повтор), буква регистра после них.
+/// Остальные клавиши записываются в макрос, если идет запись; `true` - клавиша обработана
+fn handle_macro_key(state: &mut TuiState, key: KeyEvent) -> bool {
+    if let Some(prompt) = state.macros.take_prompt() {
+        match key.code {
+            KeyCode::Char(register) if register.is_ascii_lowercase() && key.modifiers.difference(KeyModifiers::SHIFT).is_empty() => {
+                match prompt {
+                    MacroPrompt::Record => {
+                        state.macros.start_recording(register);
+                        state.status_message = Some(format!("{} @{}", state.i18n.ts("macro_recording"), register));
+                    }
+                    MacroPrompt::Replay => replay_macro(state, register),
+                }
+            }
+            _ => state.status_message = None,
+        }
+        return true;
+    }
+    if state.macros.is_replaying() {
+        return false;
+    }
+    match key.code {
+        KeyCode::F(11) => {
+            state.status_message = Some(match state.macros.stop_recording() {
+                Some((register, keys)) => format!("{} @{} ({})", state.i18n.ts("macro_saved"), register, keys),
+                None => {
+                    state.macros.set_prompt(MacroPrompt::Record);
+                    state.i18n.ts("macro_prompt_record").to_string()
+                }
+            });
+            true
+        }
+        KeyCode::F(12) if state.current_screen == Screen::Main => {
+            if state.macros.recording().is_some() {
+                state.status_message = Some(state.i18n.ts("macro_stop_recording_first").to_string());
+            } else {
+                state.macros.set_prompt(MacroPrompt::Replay);
+                state.status_message = Some(state.i18n.ts("macro_prompt_replay").to_string());
+            }
+            true
+        }
+        _ => {
+            state.macros.record(key);
+            false
+        }
+    }
+}
+
+/// Повторить макрос для каждой записи главного списка (текущая папка или результаты поиска)
+fn replay_macro(state: &mut TuiState, register: char) {
+    // Одинаковые имена у разных записей: каждое имя берет следующий еще не взятый файл
+    let mut filenames: Vec<String> = Vec::with_capacity(state.filtered_items.len());
+    for item in &state.filtered_items {
+        if let Some((filename, _)) = state
+            .name_to_filename
+            .iter()
+            .find(|(filename, name)| name == item && !filenames.contains(filename))
+        {
+            filenames.push(filename.clone());
+        }
+    }
+    state.status_message = Some(if state.macros.queue_replay(register, &filenames) {
+        format!("{} @{}", state.i18n.ts("macro_replaying"), register)
+    } else {
+        format!("{} @{}", state.i18n.ts("macro_empty"), register)
+    });
+}
+
+/// Следующая клавиша повторяемого макроса. Перед клавишами каждой записи курсор
+/// ставится на нее; Esc, нажатый во время повтора, прерывает его
+fn next_replayed_key(state: &mut TuiState, list_state: &mut ListState) -> RpmResult<Option<KeyEvent>> {
+    if !state.macros.is_replaying() {
+        return Ok(None);
+    }
+    while event::poll(Duration::ZERO)? {
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press && key.code == KeyCode::Esc {
+                state.macros.abort();
+                state.status_message = Some(state.i18n.ts("macro_aborted").to_string());
+                return Ok(None);
+            }
+        }
+    }
+    while let Some(step) = state.macros.next_step() {
+        match step {
+            ReplayStep::Key(key) => return Ok(Some(key)),
+            ReplayStep::Select(filename) => {
+                // Макрос должен начинаться и заканчиваться на главном экране
+                if state.current_screen != Screen::Main {
+                    state.macros.abort();
+                    state.status_message = Some(state.i18n.ts("macro_not_on_main").to_string());
+                    return Ok(None);
+                }
+                let index = state
+                    .name_to_filename
+                    .iter()
+                    .find(|(f, _)| *f == filename)
+                    .and_then(|(_, name)| state.filtered_items.iter().position(|item| item == name));
+                match index {
+                    Some(index) => {
+                        state.selected_index = state.visible_folders.len() + index;
+                        list_state.select(Some(state.selected_index));
+                    }
+                    // Запись удалена или скрыта предыдущими шагами: пропускаем ее
+                    None => state.macros.skip_entry(),
+                }
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Добавить имена, расшифрованные в фоне; по окончании опубликовать метаданные и обновить трей
+fn apply_loaded_names(state: &mut TuiState, storage: &PasswordStorage, tray: &TrayHandle, update: LoadUpdate) {
+    if !update.names.is_empty() {
+        if let Some((ref mut loaded, _)) = state.names_loading {
+            *loaded += update.names.len();
+        }
+        state.all_items.extend(update.names.iter().map(|(_, name)| name.clone()));
+        state.name_to_filename.extend(update.names);
+        filter_items(state);
+    }
+    let Some(result) = update.finished else {
+        return;
+    };
+    state.name_loader = None;
+    state.names_loading = None;
+    match result {
+        Ok(entries) => publish_to_api(state, storage, entries),
+        Err(e) => {
+            state.entry_metadata.clear();
+            state.status_message = Some(format!("{} {}", state.i18n.ts("main_loading_failed"), e));
+        }
+    }
+    refresh_tray_shortcuts(state, storage, tray);
+}
+
+/// Сохранить, где остался главный список (папка, фильтр по тегам, выбранная запись),
+/// чтобы вернуться туда после следующей разблокировки
+fn save_ui_state(state: &TuiState, storage: &PasswordStorage, list_state: &ListState) {
+    // Пока имена не загружены или стерты, выбранную запись не узнать: оставляем прошлое состояние
+    if state.name_loader.is_some() || state.metadata_scrubbed {
+        return;
+    }
+    let Some(ref key) = state.encryption_key else {
+        return;
+    };
+    let selected_entry = selected_entry_name(state).and_then(|selected| {
+        state
+            .name_to_filename
+            .iter()
+            .find(|(_, name)| name == selected)
+            .map(|(filename, _)| filename.clone())
+    });
+    let ui_state = UiState {
+        current_folder: state.current_folder.clone(),
+        tag_filter: split_tag_query(&state.search_query).0,
+        selected_entry,
+        list_offset: list_state.offset(),
+    };
+    if let Err(e) = storage.save_ui_state(&ui_state, key.as_slice()) {
+        eprintln!("Failed to save UI state: {}", e);
+    }
+}
+
+/// Открыть сохраненные папку и фильтр по тегам; запись выбирается, когда догрузятся имена
+fn restore_ui_state(state: &mut TuiState, ui_state: UiState) {
+    // Папку могли удалить (или переместить записи) на другом устройстве
+    let folder_exists = state
+        .entry_folders
+        .values()
+        .any(|path| *path == ui_state.current_folder || path.starts_with(&format!("{}/", ui_state.current_folder)));
+    state.current_folder = if folder_exists { ui_state.current_folder.clone() } else { String::new() };
+    state.search_query = ui_state.tag_filter.iter().map(|tag| format!("#{} ", tag)).collect();
+    state.pending_list_position = Some(ui_state);
+}
+
+/// Выбрать запись и прокрутку из сохраненного состояния (если запись еще видна)
+fn apply_pending_list_position(state: &mut TuiState, list_state: &mut ListState) {
+    let Some(ui_state) = state.pending_list_position.take() else {
+        return;
+    };
+    let index = ui_state.selected_entry.and_then(|filename| {
+        state
+            .name_to_filename
+            .iter()
+            .find(|(f, _)| *f == filename)
+            .and_then(|(_, name)| state.filtered_items.iter().position(|item| item == name))
+    });
+    if let Some(index) = index {
+        state.selected_index = state.visible_folders.len() + index;
+        *list_state.offset_mut() = ui_state.list_offset.min(state.selected_index);
+        list_state.select(Some(state.selected_index));
+    }
+}
+
+/// Запись под курсором главного списка (строки папок идут перед записями)
+fn selected_entry_name(state: &TuiState) -> Option<&String> {
+    state
+        .selected_index
+        .checked_sub(state.visible_folders.len())
+        .and_then(|index| state.filtered_items.get(index))
+}
+
+/// Подпапки `folder` первого уровня (полные пути), по алфавиту
+fn child_folders(entry_folders: &HashMap<String, String>, folder: &str) -> Vec<String> {
+    let children: BTreeSet<String> = entry_folders
+        .values()
+        .filter_map(|path| {
+            let rest = if folder.is_empty() {
+                path.as_str()
+            } else {
+                path.strip_prefix(folder)?.strip_prefix('/')?
+            };
+            let child = rest.split('/').next()?;
+            Some(if folder.is_empty() { child.to_string() } else { format!("{}/{}", folder, child) })
+        })
+        .collect();
+    children.into_iter().collect()
+}
+
+/// Нечеткий поиск по списку записей: совпадения, отсортированные по релевантности
+pub fn fuzzy_filter(items: &[String], query: &str) -> Vec<String> {
+    if query.is_empty() {
+        return items.to_vec();
+    }
+    let matcher = SkimMatcherV2::default();
+    let mut scored_items: Vec<(i64, &String)> = items
+        .iter()
+        .filter_map(|item| matcher.fuzzy_match(item, query).map(|score| (score, item)))
+        .collect();
+
+    // Сортируем по релевантности (больший score = лучшее совпадение)
+    scored_items.sort_by_key(|item| std::cmp::Reverse(item.0));
+
+    scored_items.into_iter().map(|(_, item)| item.clone()).collect()
+}
+
+fn generate_password(state: &TuiState) -> RpmResult<String> {
+    generate_from_policy(&generator_policy(state)?)
+}
+
+/// Текущие настройки экрана генератора в виде политики
+fn generator_policy(state: &TuiState) -> RpmResult<PasswordPolicy> {
+    use crate::errors::RpmError;
+
+    // Парсим длину пароля
+    let length: usize = state.password_generator_length.trim().parse()
+        .map_err(|_| RpmError::Crypto("Неверная длина пароля".to_string()))?;
+
+    Ok(PasswordPolicy {
+        length,
+        exclude_chars: state.password_generator_exclude_chars.clone(),
+        custom_chars: state.password_generator_custom_chars.clone(),
+        use_uppercase: state.password_generator_use_uppercase,
+        use_lowercase: state.password_generator_use_lowercase,
+        use_digits: state.password_generator_use_digits,
+        use_special: state.password_generator_use_special,
+        use_cyrillic: state.password_generator_use_cyrillic,
+        exclude_ambiguous: state.password_generator_exclude_ambiguous,
+        min_digits: state.password_generator_min_digits,
+        min_special: state.password_generator_min_special,
+        min_uppercase: state.password_generator_min_uppercase,
+        passphrase_language: state
+            .password_generator_passphrase
+            .then(|| Language::from_code(&state.config.language).to_code().to_string()),
+    })
+}
+
+/// Заполнить экран генератора настройками пресета
+fn apply_generator_policy(state: &mut TuiState, policy: &PasswordPolicy) {
+    state.password_generator_length = policy.length.to_string();
+    state.password_generator_exclude_chars = policy.exclude_chars.clone();
+    state.password_generator_custom_chars = policy.custom_chars.clone();
+    state.password_generator_use_uppercase = policy.use_uppercase;
+    state.password_generator_use_lowercase = policy.use_lowercase;
+    state.password_generator_use_digits = policy.use_digits;
+    state.password_generator_use_special = policy.use_special;
+    state.password_generator_use_cyrillic = policy.use_cyrillic;
+    state.password_generator_exclude_ambiguous = policy.exclude_ambiguous;
+    state.password_generator_min_digits = policy.min_digits;
+    state.password_generator_min_special = policy.min_special;
+    state.password_generator_min_uppercase = policy.min_uppercase;
+    state.password_generator_passphrase = policy.passphrase_language.is_some();
+}
+
+/// Поле минимума под курсором генератора (поля 10-12)
+fn generator_minimum(state: &mut TuiState) -> Option<&mut usize> {
+    match state.password_generator_selected_field {
+        10 => Some(&mut state.password_generator_min_digits),
+        11 => Some(&mut state.password_generator_min_special),
+        12 => Some(&mut state.password_generator_min_uppercase),
+        _ => None,
+    }
+}
+
+/// Пресет, с которым совпадают текущие настройки генератора
+fn current_generator_preset(state: &TuiState) -> Option<&str> {
+    let policy = generator_policy(state).ok()?;
+    state
+        .config
+        .generator_presets
+        .iter()
+        .find(|(_, preset)| **preset == policy)
+        .map(|(name, _)| name.as_str())
+}
+
+/// Пароль (или парольная фраза) по политике генератора; им же пользуется `rpm generate`
+pub fn generate_from_policy(policy: &PasswordPolicy) -> RpmResult<String> {
+    use crate::errors::RpmError;
+
+    if let Some(ref language) = policy.passphrase_language {
+        return generate_passphrase(policy.length, Language::from_code(language));
+    }
+    
+    // Проверяем, что выбран хотя бы один набор символов (или задан свой)
+    if !policy.use_uppercase
+        && !policy.use_lowercase
+        && !policy.use_digits
+        && !policy.use_special
+        && !policy.use_cyrillic
+        && policy.custom_chars.is_empty()
+    {
+        return Err(RpmError::Crypto("Необходимо выбрать хотя бы один набор символов".to_string()));
+    }
+    
+    let length = policy.length;
+    
+    if length < 1 {
+        return Err(RpmError::Crypto("Длина пароля должна быть не менее 1".to_string()));
+    }
+    
+    if length > MAX_PASSWORD_LENGTH {
+        return Err(RpmError::Crypto(format!("Длина пароля не должна превышать {}", MAX_PASSWORD_LENGTH)));
+    }
+    
+    let GeneratorCharsets { uppercase, digits, special, available: available_chars } = generator_charsets(policy);
+    
+    // Проверяем, что после исключения остались символы
+    if available_chars.is_empty() {
+        return Err(RpmError::Crypto("После исключения символов не осталось доступных символов".to_string()));
+    }
+
+    // Минимумы выполнимы, только если набор включен и все они помещаются в длину
+    let required = [
+        (policy.min_digits, &digits, "цифр"),
+        (policy.min_special, &special, "спецсимволов"),
+        (policy.min_uppercase, &uppercase, "заглавных букв"),
+    ];
+    for (min, chars, what) in required {
+        if min > 0 && chars.is_empty() {
+            return Err(RpmError::Crypto(format!("Нужно не менее {} {}, но этот набор выключен или исключен", min, what)));
+        }
+    }
+    let required_total: usize = required.iter().map(|(min, _, _)| min).sum();
+    if required_total > length {
+        return Err(RpmError::Crypto(format!("Минимумы ({}) больше длины пароля ({})", required_total, length)));
+    }
+    
+    // Сначала обязательные символы, остальное - из всех наборов; затем перемешиваем,
+    // чтобы обязательные символы не стояли в начале. Генератор криптографически стойкий
+    let mut rng = OsRng;
+    let mut password: Vec<char> = Vec::with_capacity(length);
+    for (min, chars, _) in required {
+        password.extend((0..min).map(|_| chars[rng.gen_range(0..chars.len())]));
+    }
+    while password.len() < length {
+        password.push(available_chars[rng.gen_range(0..available_chars.len())]);
+    }
+    password.shuffle(&mut rng);
+    let result = password.iter().collect();
+    password.zeroize();
+
+    Ok(result)
+}
+
+/// Наборы символов генератора: те, из которых берутся минимумы, и все доступные
+struct GeneratorCharsets {
+    uppercase: Vec<char>,
+    digits: Vec<char>,
+    /// Спецсимволы вместе с небуквенными символами своего набора
+    special: Vec<char>,
+    available: Vec<char>,
+}
+
+fn generator_charsets(policy: &PasswordPolicy) -> GeneratorCharsets {
+    // Наборы символов по классам, без исключенных (и, если нужно, похожих) символов
+    let mut exclude_set: HashSet<char> = policy.exclude_chars.chars().collect();
+    if policy.exclude_ambiguous {
+        exclude_set.extend(AMBIGUOUS_CHARS.chars());
+    }
+    let class = |enabled: bool, chars: Vec<char>| -> Vec<char> {
+        if enabled {
+            chars.into_iter().filter(|c| !exclude_set.contains(c)).collect()
+        } else {
+            Vec::new()
+        }
+    };
+    let uppercase = class(policy.use_uppercase, ('A'..='Z').collect());
+    let lowercase = class(policy.use_lowercase, ('a'..='z').collect());
+    let digits = class(policy.use_digits, ('0'..='9').collect());
+    let mut special = class(policy.use_special, "!@#$%^&*()_+-=[]{}|;:,.<>?".chars().collect());
+    let cyrillic = class(policy.use_cyrillic, ('а'..='я').chain('А'..='Я').chain(['ё', 'Ё']).collect());
+    // Свой набор без повторов и уже включенных символов, чтобы они не выпадали чаще других
+    let mut custom: Vec<char> = Vec::new();
+    for c in class(true, policy.custom_chars.chars().collect()) {
+        let known = [&uppercase, &lowercase, &digits, &special, &cyrillic].iter().any(|set| set.contains(&c));
+        if !c.is_control() && !known && !custom.contains(&c) {
+            custom.push(c);
+        }
+    }
+    // Символы своего набора (не буквы и не цифры) засчитываются в минимум спецсимволов
+    special.extend(custom.iter().filter(|c| !c.is_alphanumeric()));
+
+    let available: Vec<char> = [&uppercase, &lowercase, &digits, &special, &cyrillic]
+        .into_iter()
+        .flatten()
+        .chain(custom.iter().filter(|c| c.is_alphanumeric()))
+        .copied()
+        .collect();
+
+    GeneratorCharsets { uppercase, digits, special, available }
+}
+
+/// Оценка энтропии пароля с текущими настройками в битах; `None`, если настройки неверны.
+/// Минимумы немного сужают выбор, поэтому для них это оценка сверху
+fn generator_entropy_bits(policy: &PasswordPolicy) -> Option<f64> {
+    if let Some(ref language) = policy.passphrase_language {
+        let words = Language::from_code(language).wordlist().len();
+        return (1..=MAX_PASSPHRASE_WORDS)
+            .contains(&policy.length)
+            .then(|| policy.length as f64 * (words as f64).log2());
+    }
+    let pool = generator_charsets(policy).available.len();
+    (pool > 0 && (1..=MAX_PASSWORD_LENGTH).contains(&policy.length)).then(|| policy.length as f64 * (pool as f64).log2())
+}
+
+/// Парольная фраза из `words` случайных слов словаря языка, через дефис
+fn generate_passphrase(words: usize, language: Language) -> RpmResult<String> {
+    use crate::errors::RpmError;
+
+    if !(1..=MAX_PASSPHRASE_WORDS).contains(&words) {
+        return Err(RpmError::Crypto(format!("Число слов должно быть от 1 до {}", MAX_PASSPHRASE_WORDS)));
+    }
+
+    let wordlist = language.wordlist();
+    let mut rng = OsRng;
+    let passphrase: Vec<&str> = (0..words)
+        .map(|_| wordlist[rng.gen_range(0..wordlist.len())])
+        .collect();
+    Ok(passphrase.join("-"))
+}
+
+fn render_main_screen(f: &mut Frame, state: &TuiState, list_state: &mut ListState, theme: &Theme) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Поле поиска
+            Constraint::Length(if state.names_loading.is_some() { 1 } else { 0 }), // Прогресс загрузки имен
+            Constraint::Min(0),    // Основной контент
+            Constraint::Length(footer_height(state.i18n.ts("main_footer"), f.size().width)), // Футер
+        ])
+        .split(f.size());
+
+    // Поле ввода для поиска
+    let search_input = Paragraph::new(state.search_query.as_str())
+        .style(theme.accent_style())
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(theme.active_border_style())
+                .style(theme.surface_style())
+                .title(state.i18n.ts("main_search"))
+        );
+    f.render_widget(search_input, chunks[0]);
+
+    if let Some((loaded, total)) = state.names_loading {
+        let gauge = Gauge::default()
+            .gauge_style(theme.accent_style())
+            .ratio(if total == 0 { 1.0 } else { (loaded as f64 / total as f64).min(1.0) })
+            .label(format!("{} {} / {}", state.i18n.ts("main_loading"), loaded, total));
+        f.render_widget(gauge, chunks[1]);
+    }
+
+    // Main content area
+    let folders = state.visible_folders.iter().map(|folder| {
+        let name = folder.rsplit('/').next().unwrap_or(folder);
+        ListItem::new(format!("▸ {}/", name)).style(theme.accent_style())
+    });
+    let searching = !state.search_query.trim().is_empty();
+    let entries = state
+        .filtered_items
+        .iter()
+        .map(|item| {
+            let filename = state
+                .name_to_filename
+                .iter()
+                .find(|(_, name)| name == item)
+                .map(|(filename, _)| filename);
+            let mut spans = vec![Span::styled(item.as_str(), theme.text_style())];
+            // В результатах поиска по всем папкам показываем, где лежит запись
+            if let Some(folder) = filename.and_then(|f| state.entry_folders.get(f)).filter(|_| searching) {
+                spans.push(Span::styled(format!("  {}/", folder), theme.dimmed_style()));
+            }
+            if let Some(tags) = filename.and_then(|f| state.entry_tags.get(f)) {
+                let tags: Vec<String> = tags.iter().map(|tag| format!("#{}", tag)).collect();
+                spans.push(Span::styled(format!("  {}", tags.join(" ")), theme.dimmed_style()));
+            }
+            ListItem::new(Line::from(spans)).style(theme.text_style())
+        });
+    let items: Vec<ListItem> = folders.chain(entries).collect();
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(theme.inactive_border_style())
+                .style(theme.surface_style())
+                .title(if state.metadata_scrubbed {
+                    state.i18n.ts("main_names_scrubbed").to_string()
+                } else {
+                    let list_title = if state.show_archived { "main_archive" } else { "main_passwords" };
+                    let mut title = format!("{} ({})", state.i18n.ts(list_title), state.filtered_items.len());
+                    // Путь к открытой папке
+                    if !state.current_folder.is_empty() && !searching {
+                        title = format!("{} | / {} | {}", title, state.current_folder.replace('/', " / "), state.i18n.ts("main_folder_up"));
+                    }
+                    title
+                })
+        )
+        .highlight_style(theme.selection_style())
+        .highlight_symbol(">> ");
+
+    f.render_stateful_widget(list, chunks[2], list_state);
+
+    // Footer (сообщение о последнем действии, если есть)
+    let footer = match state.status_message {
+        Some(ref message) => Paragraph::new(message.as_str()).style(theme.success_style()),
+        None => Paragraph::new(state.i18n.ts("main_footer")).style(theme.dimmed_style()),
+    };
+    let footer = footer
+        .wrap(Wrap { trim: true })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(theme.inactive_border_style())
+                .style(theme.status_bar_style())
+        );
+    f.render_widget(footer, chunks[3]);
+
+    // Диалог перемещения записи в папку
+    if let Some((_, ref name)) = state.move_to_folder {
+        let area = centered_rect(60, 8, f.size());
+        f.render_widget(Clear, area);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(theme.active_border_style())
+            .style(theme.surface_style())
+            .title(state.i18n.ts("main_move_title"));
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Length(3), Constraint::Min(0)])
+            .split(inner);
+        f.render_widget(Paragraph::new(name.as_str()).style(theme.text_style()).alignment(Alignment::Center), rows[0]);
+        render_text_field(f, rows[1], &state.move_folder_input, state.i18n.ts("main_move_folder"), true, theme);
+        let hint = Paragraph::new(state.i18n.ts("main_move_hint"))
+            .style(theme.dimmed_style())
+            .wrap(Wrap { trim: true });
+        f.render_widget(hint, rows[2]);
+    }
+
+    // Диалог подтверждения удаления поверх списка
+    if let Some((_, ref name)) = state.delete_confirmation {
+        let area = centered_rect(50, 7, f.size());
+        let text = format!(
+            "{}\n\n{}\n\n{}",
+            state.i18n.ts("delete_confirm_question"),
+            name,
+            state.i18n.ts("delete_confirm_hint")
+        );
+        let dialog = Paragraph::new(text)
+            .style(theme.text_style())
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: false })
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(theme.active_border_style())
+                    .style(theme.surface_style())
+                    .title(state.i18n.ts("delete_confirm_title"))
+            );
+        f.render_widget(Clear, area);
+        f.render_widget(dialog, area);
+    }
+}
+
+/// Запрос сопряжения, который ждет ответа; при заблокированном хранилище не показывается,
+/// чтобы разрешить доступ мог только знающий мастер-пароль
+fn pending_pairing(state: &TuiState) -> Option<PairingRequest> {
+    state.encryption_key.as_ref()?;
+    state.pairing.pending()
+}
+
+/// Диалог сопряжения расширения с кодом, который пользователь вводит в расширении
+fn render_pairing_dialog(f: &mut Frame, state: &TuiState, request: &PairingRequest, theme: &Theme) {
+    let area = centered_rect(50, 9, f.size());
+    let text = format!(
+        "{}\n{}\n\n{}\n{}\n\n{}",
+        state.i18n.ts("pairing_question"),
+        request.client_name,
+        state.i18n.ts("pairing_code"),
+        request.code,
+        state.i18n.ts("pairing_hint")
+    );
+    let dialog = Paragraph::new(text)
+        .style(theme.text_style())
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(theme.active_border_style())
+                .style(theme.surface_style())
+                .title(state.i18n.ts("pairing_title"))
+        );
+    f.render_widget(Clear, area);
+    f.render_widget(dialog, area);
+}
+
+/// Высота футера с подсказками (с рамкой): длинные подсказки и переводы
+/// переносятся на следующие строки (до трёх), а не обрезаются
+fn footer_height(text: &str, width: u16) -> u16 {
+    let inner = usize::from(width.saturating_sub(2)).max(1);
+    let mut lines = 1;
+    let mut line_width = 0;
+    for word in text.split_whitespace() {
+        let word_width = Span::raw(word).width();
+        if line_width > 0 && line_width + 1 + word_width > inner {
+            lines += 1;
+            line_width = word_width;
+        } else if line_width > 0 {
+            line_width += 1 + word_width;
+        } else {
+            line_width = word_width;
+        }
+    }
+    lines.min(3) as u16 + 2
+}
+
+/// Прямоугольник по центру `area`: ширина в процентах, высота в строках
+fn centered_rect(percent_x: u16, height: u16, area: Rect) -> Rect {
+    let width = (u32::from(area.width) * u32::from(percent_x) / 100) as u16;
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+fn render_settings_screen(f: &mut Frame, state: &TuiState, theme: &Theme) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(0),    // Основной контент
+            Constraint::Length(3), // Футер
+        ])
+        .split(f.size());
+
+    // На низком терминале подписи полей скрываются: заголовки рамок говорят то же самое
+    let label_height = if chunks[0].height >= 33 { 1 } else { 0 };
+
+    // Окно настроек
+    let settings_content = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Заголовок
+            Constraint::Length(label_height), // Метка для пути сохранения
+            Constraint::Length(3), // Путь сохранения
+            Constraint::Length(label_height), // Метка для конфига
+            Constraint::Length(3), // Путь конфига
+            Constraint::Length(label_height), // Метка для директории
+            Constraint::Length(3), // Поле ввода директории
+            Constraint::Length(label_height), // Метка для времени хранения
+            Constraint::Length(3), // Поле ввода времени хранения
+            Constraint::Length(label_height), // Метка для темы
+            Constraint::Length(3), // Поле выбора темы
+            Constraint::Length(label_height), // Метка для языка
+            Constraint::Length(3), // Поле выбора языка
+            Constraint::Length(3), // Смена мастер-пароля
+            Constraint::Length(3), // Режим для программ чтения с экрана
+            Constraint::Length(3), // Разблокировка через связку ключей ОС
+            Constraint::Min(0),    // Остальное пространство
+        ])
+        .split(chunks[0]);
+
+    let settings_title = Paragraph::new(state.i18n.ts("settings_title"))
+        .style(theme.title_style())
+        .alignment(Alignment::Center)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(theme.active_border_style())
+                .style(theme.surface_style())
+        );
+    f.render_widget(settings_title, settings_content[0]);
+
+    // Информация о пути сохранения файлов
+    let save_path_label = Paragraph::new(state.i18n.ts("settings_save_path_label"))
+        .style(theme.text_style())
+        .block(Block::default().borders(Borders::NONE));
+    f.render_widget(save_path_label, settings_content[1]);
+
+    let save_path = state.config.passwords_directory_path();
+    let save_path_text = save_path.to_string_lossy().to_string();
+    let save_path_display = Paragraph::new(save_path_text.as_str())
+        .style(theme.accent_style())
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(theme.inactive_border_style())
+                .style(theme.surface_style())
+                .title(state.i18n.ts("settings_save_path_title")),
+        );
+    f.render_widget(save_path_display, settings_content[2]);
+
+    // Информация о пути к конфигурационному файлу
+    // Порт API показываем в рамке файла конфигурации: отдельное поле не помещается на экран
+    let mut config_path_title = match (state.server_port, state.server_tls.is_some()) {
+        (Some(port), _) => format!("{} | {} 127.0.0.1:{}", state.i18n.ts("settings_config_path_title"), state.i18n.ts("settings_api_port"), port),
+        (None, true) => state.i18n.ts("settings_config_path_title").to_string(),
+        (None, false) => format!("{} | {}", state.i18n.ts("settings_config_path_title"), state.i18n.ts("settings_api_not_running")),
+    };
+    // HTTPS: порт сверху, отпечаток сертификата (для закрепления в расширении) в нижней рамке
+    if let Some((port, _)) = state.server_tls {
+        config_path_title.push_str(&format!(" | HTTPS 127.0.0.1:{}", port));
+    }
+    let config_path_label = Paragraph::new(state.i18n.ts("settings_config_path_label"))
+        .style(theme.text_style())
+        .block(Block::default().borders(Borders::NONE));
+    f.render_widget(config_path_label, settings_content[3]);
+
+    let config_path_text = state.config.config_file_path()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|_| state.i18n.ts("settings_config_path_error").to_string());
+    let mut config_path_block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(theme.inactive_border_style())
+        .style(theme.surface_style())
+        .title(config_path_title);
+    if let Some((_, ref fingerprint)) = state.server_tls {
+        config_path_block = config_path_block.title(
+            Title::from(format!("{} {}", state.i18n.ts("settings_tls_fingerprint"), fingerprint)).position(Position::Bottom),
+        );
+    }
+    let config_path_display = Paragraph::new(config_path_text.as_str())
+        .style(theme.accent_style())
+        .block(config_path_block);
+    f.render_widget(config_path_display, settings_content[4]);
+
+    let dir_label = Paragraph::new(state.i18n.ts("settings_directory_label"))
+        .style(theme.text_style())
+        .block(Block::default().borders(Borders::NONE));
+    f.render_widget(dir_label, settings_content[5]);
+
+    let dir_style = if state.settings_field == 0 {
+        theme.active_input_style()
+    } else {
+        theme.inactive_input_style()
+    };
+
+    let dir_title = if state.settings_field == 0 {
+        state.i18n.ts("settings_directory_active")
+    } else {
+        state.i18n.ts("settings_directory")
+    };
+
+    let dir_border_style = if state.settings_field == 0 {
+        theme.active_border_style()
+    } else {
+        theme.inactive_border_style()
+    };
+
+    let dir_input = Paragraph::new(state.passwords_dir_input.as_str())
+        .style(dir_style)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(dir_border_style)
+                .style(theme.surface_style())
+                .title(dir_title),
+        );
+    f.render_widget(dir_input, settings_content[6]);
+
+    // Метка для времени хранения в буфере обмена
+    let timeout_label = Paragraph::new(state.i18n.ts("settings_clipboard_timeout_label"))
+        .style(theme.text_style())
+        .block(Block::default().borders(Borders::NONE));
+    f.render_widget(timeout_label, settings_content[7]);
+
+    let timeout_style = if state.settings_field == 1 {
+        theme.active_input_style()
+    } else {
+        theme.inactive_input_style()
+    };
+
+    let timeout_title = if state.settings_field == 1 {
+        state.i18n.ts("settings_clipboard_timeout_active")
+    } else {
+        state.i18n.ts("settings_clipboard_timeout")
+    };
+
+    let timeout_border_style = if state.settings_field == 1 {
+        theme.active_border_style()
+    } else {
+        theme.inactive_border_style()
+    };
+
+    let timeout_input = Paragraph::new(state.clipboard_timeout_input.as_str())
+        .style(timeout_style)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(timeout_border_style)
+                .style(theme.surface_style())
+                .title(timeout_title),
+        );
+    f.render_widget(timeout_input, settings_content[8]);
+
+    // Метка для темы
+    let theme_label = Paragraph::new(state.i18n.ts("settings_theme_label"))
+        .style(theme.text_style())
+        .block(Block::default().borders(Borders::NONE));
+    f.render_widget(theme_label, settings_content[9]);
+
+    let theme_style = if state.settings_field == 2 {
+        theme.active_input_style()
+    } else {
+        theme.inactive_input_style()
+    };
+
+    let theme_title = if state.settings_field == 2 {
+        state.i18n.ts("settings_theme_active")
+    } else {
+        state.i18n.ts("settings_theme")
+    };
+
+    let theme_border_style = if state.settings_field == 2 {
+        theme.active_border_style()
+    } else {
+        theme.inactive_border_style()
+    };
+
+    let current_theme_name = match state.config.theme.as_str() {
+        "vscode_style" => "VS Code Dark+",
+        "opencode_style" => "OpenCode / Dark Modern",
+        _ => "Textual / Modern Web",
+    };
+
+    let theme_display = Paragraph::new(current_theme_name)
+        .style(theme_style)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(theme_border_style)
+                .style(theme.surface_style())
+                .title(theme_title),
+        );
+    f.render_widget(theme_display, settings_content[10]);
+
+    // Метка для языка
+    let language_label = Paragraph::new(state.i18n.ts("settings_language_label"))
+        .style(theme.text_style())
+        .block(Block::default().borders(Borders::NONE));
+    f.render_widget(language_label, settings_content[11]);
+
+    let language_style = if state.settings_field == 3 {
+        theme.active_input_style()
+    } else {
+        theme.inactive_input_style()
+    };
+
+    let language_title = if state.settings_field == 3 {
+        state.i18n.ts("settings_language_active")
+    } else {
+        state.i18n.ts("settings_language")
+    };
+
+    let language_border_style = if state.settings_field == 3 {
+        theme.active_border_style()
+    } else {
+        theme.inactive_border_style()
+    };
+
+    let current_language = Language::from_code(&state.config.language);
+    let language_display = Paragraph::new(current_language.display_name())
+        .style(language_style)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(language_border_style)
+                .style(theme.surface_style())
+                .title(language_title),
+        );
+    f.render_widget(language_display, settings_content[12]);
+
+    let change_master_title = if state.settings_field == 4 {
+        state.i18n.ts("settings_change_master_active")
+    } else {
+        state.i18n.ts("settings_change_master")
+    };
+    render_text_field(f, settings_content[13], "********", change_master_title, state.settings_field == 4, theme);
+
+    let screen_reader_title = if state.settings_field == 5 {
+        state.i18n.ts("settings_screen_reader_active")
+    } else {
+        state.i18n.ts("settings_screen_reader")
+    };
+    let screen_reader_value = if state.config.screen_reader {
+        state.i18n.ts("settings_on")
+    } else {
+        state.i18n.ts("settings_off")
+    };
+    render_text_field(f, settings_content[14], screen_reader_value, screen_reader_title, state.settings_field == 5, theme);
+
+    let keychain_title = if state.settings_field == 6 {
+        state.i18n.ts("settings_keychain_active")
+    } else {
+        state.i18n.ts("settings_keychain")
+    };
+    let keychain_value = if state.config.keychain_unlock {
+        state.i18n.ts("settings_on")
+    } else {
+        state.i18n.ts("settings_off")
+    };
+    render_text_field(f, settings_content[15], keychain_value, keychain_title, state.settings_field == 6, theme);
+
+    // Footer
+    let footer = Paragraph::new(state.i18n.ts("settings_footer"))
+        .style(theme.dimmed_style())
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(theme.inactive_border_style())
+                .style(theme.status_bar_style())
+        );
+    f.render_widget(footer, chunks[1]);
+}
+
+fn render_master_password_screen(f: &mut Frame, state: &TuiState, theme: &Theme) {
+    let footer_text = if state.is_creating_master_password {
+        state.i18n.ts("master_password_footer_create")
+    } else {
+        state.i18n.ts("master_password_footer_enter")
+    };
+    let footer_height = footer_height(footer_text, f.size().width);
+    let constraints = if state.is_creating_master_password {
+        vec![
+            Constraint::Min(0),
+            Constraint::Length(3),
+            Constraint::Length(1),
+            Constraint::Length(3),
+            Constraint::Length(1),
+            Constraint::Length(3),
+            Constraint::Length(1),
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(footer_height),
+        ]
+    } else {
+        vec![
+            Constraint::Min(0),
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(footer_height),
+        ]
+    };
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(constraints)
+        .split(f.size());
+
+    let title_text = if state.is_creating_master_password {
+        state.i18n.ts("master_password_create_title")
+    } else {
+        state.i18n.ts("master_password_title")
+    };
+
+    let title = Paragraph::new(title_text)
+        .style(theme.title_style())
+        .alignment(Alignment::Center)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(theme.active_border_style())
+                .style(theme.surface_style())
+        );
+    f.render_widget(title, chunks[1]);
+
+    if state.is_creating_master_password {
+        // Creating new master password - show directory, password, and confirm fields
+        let dir_label = Paragraph::new(state.i18n.ts("master_password_directory_label"))
+            .style(theme.text_style())
+            .block(Block::default().borders(Borders::NONE));
+        f.render_widget(dir_label, chunks[2]);
+
+        let dir_style = if state.master_password_field == 0 {
+            theme.active_input_style()
+        } else {
+            theme.inactive_input_style()
+        };
+
+        let dir_title = if state.master_password_field == 0 {
+            state.i18n.ts("master_password_directory_active")
+        } else {
+            state.i18n.ts("master_password_directory")
+        };
+
+        let dir_border_style = if state.master_password_field == 0 {
+            theme.active_border_style()
+        } else {
+            theme.inactive_border_style()
+        };
+
+        let dir_input = Paragraph::new(state.passwords_dir_input.as_str())
+            .style(dir_style)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(dir_border_style)
+                    .style(theme.surface_style())
+                    .title(dir_title),
+            );
+        f.render_widget(dir_input, chunks[3]);
+
+        let password_label = Paragraph::new(state.i18n.ts("master_password_label"))
+            .style(theme.text_style())
+            .block(Block::default().borders(Borders::NONE));
+        f.render_widget(password_label, chunks[4]);
+
+        let password_display = if state.master_password_input.is_empty() {
+            String::new()
+        } else if state.master_password_show_password {
+            state.master_password_input.clone()
+        } else {
+            "*".repeat(state.master_password_input.len())
+        };
+
+        let password_style = if state.master_password_field == 1 {
+            theme.active_input_style()
+        } else {
+            theme.inactive_input_style()
+        };
+
+        let password_title = if state.master_password_field == 1 {
+            format!("{} | Ctrl+H - {}", state.i18n.ts("master_password_active"), if state.master_password_show_password { state.i18n.ts("hide") } else { state.i18n.ts("show") })
+        } else {
+            state.i18n.ts("master_password").to_string()
+        };
+
+        let password_border_style = if state.master_password_field == 1 {
+            theme.active_border_style()
+        } else {
+            theme.inactive_border_style()
+        };
+
+        let password_input = Paragraph::new(password_display.as_str())
+            .style(password_style)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(password_border_style)
+                    .style(theme.surface_style())
+                    .title(password_title),
+            );
+        f.render_widget(password_input, chunks[5]);
+
+        let confirm_label = Paragraph::new(state.i18n.ts("master_password_confirm_label"))
+            .style(theme.text_style())
+            .block(Block::default().borders(Borders::NONE));
+        f.render_widget(confirm_label, chunks[6]);
+
+        let confirm_display = if state.master_password_confirm.is_empty() {
+            String::new()
+        } else if state.master_password_show_password {
+            state.master_password_confirm.clone()
+        } else {
+            "*".repeat(state.master_password_confirm.len())
+        };
+
+        let confirm_style = if state.master_password_field == 2 {
+            theme.active_input_style()
+        } else {
+            theme.inactive_input_style()
+        };
+
+        let confirm_title = if state.master_password_field == 2 {
+            format!("{} | Ctrl+H - {}", state.i18n.ts("master_password_confirm_active"), if state.master_password_show_password { state.i18n.ts("hide") } else { state.i18n.ts("show") })
+        } else {
+            state.i18n.ts("master_password_confirm").to_string()
+        };
+
+        let confirm_border_style = if state.master_password_field == 2 {
+            theme.active_border_style()
+        } else {
+            theme.inactive_border_style()
+        };
+
+        let confirm_input = Paragraph::new(confirm_display.as_str())
+            .style(confirm_style)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(confirm_border_style)
+                    .style(theme.surface_style())
+                    .title(confirm_title),
+            );
+        f.render_widget(confirm_input, chunks[7]);
+
+        // Необязательная подсказка, видна открытым текстом
+        render_text_field(
+            f,
+            chunks[8],
+            &state.master_password_hint_input,
+            state.i18n.ts("master_password_hint"),
+            state.master_password_field == 3,
+            theme,
+        );
+
+        if let Some(ref error) = state.master_password_error {
+            let error = Paragraph::new(error.as_str())
+                .style(theme.error_style())
+                .alignment(Alignment::Center)
+                .wrap(Wrap { trim: true });
+            f.render_widget(error, chunks[9]);
+        }
+
+        let footer = Paragraph::new(footer_text)
+            .style(theme.dimmed_style())
+            .wrap(Wrap { trim: true })
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(theme.inactive_border_style())
+                    .style(theme.status_bar_style())
+            );
+        f.render_widget(footer, chunks[10]);
+    } else {
+        // Entering existing master password - show one field
+        let password_display = if state.master_password_input.is_empty() {
+            String::new()
+        } else if state.master_password_show_password {
+            state.master_password_input.clone()
+        } else {
+            "*".repeat(state.master_password_input.len())
+        };
+
+        let password_title = format!("{} | Ctrl+H - {}", state.i18n.ts("master_password_enter"), if state.master_password_show_password { state.i18n.ts("hide") } else { state.i18n.ts("show") });
 
-fn ui(f: &mut Frame, state: &TuiState, list_state: &mut ListState) {
-    // Загружаем тему из конфига
-    let theme = get_theme_by_name(&state.config.theme);
-    
-    // Устанавливаем фон для всего экрана
-    f.render_widget(
-        Block::default()
-            .style(theme.bg_style()),
-        f.size()
-    );
-    
-    match state.current_screen {
-        Screen::MasterPassword => render_master_password_screen(f, state, &theme),
-        Screen::Main => render_main_screen(f, state, list_state, &theme),
-        Screen::Settings => render_settings_screen(f, state, &theme),
-        Screen::PasswordEntry { .. } => render_password_entry_screen(f, state, &theme),
-        Screen::PasswordGenerator { .. } => render_password_generator_screen(f, state, &theme),
-        Screen::Help => render_help_screen(f, state, &theme),
-        Screen::ThemeSelection => render_theme_selection_screen(f, state, &theme),
-        Screen::LanguageSelection => render_language_selection_screen(f, state, &theme),
+        let password_input = Paragraph::new(password_display.as_str())
+            .style(theme.accent_style())
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(theme.active_border_style())
+                    .style(theme.surface_style())
+                    .title(password_title),
+            );
+        f.render_widget(password_input, chunks[2]);
+
+        if let Some(ref error) = state.master_password_error {
+            let error = Paragraph::new(error.as_str())
+                .style(theme.error_style())
+                .alignment(Alignment::Center)
+                .wrap(Wrap { trim: true });
+            f.render_widget(error, chunks[3]);
+        } else if let Some(ref hint) = state.master_password_hint {
+            // Показывается после второй неудачной попытки
+            let hint = Paragraph::new(format!("{} {}", state.i18n.ts("master_password_hint_shown"), hint))
+                .style(theme.warning_style())
+                .alignment(Alignment::Center)
+                .wrap(Wrap { trim: true });
+            f.render_widget(hint, chunks[3]);
+        } else if let Some(ref shortcut) = state.tray_pending_copy {
+            let hint = Paragraph::new(format!("{} {}", state.i18n.ts("tray_unlock_to_copy"), shortcut.name))
+                .style(theme.accent_style())
+                .alignment(Alignment::Center)
+                .wrap(Wrap { trim: true });
+            f.render_widget(hint, chunks[3]);
+        } else if let Some(ref name) = state.instance_pending_copy {
+            let hint = Paragraph::new(format!("{} {}", state.i18n.ts("instance_unlock_to_copy"), name))
+                .style(theme.accent_style())
+                .alignment(Alignment::Center)
+                .wrap(Wrap { trim: true });
+            f.render_widget(hint, chunks[3]);
+        }
+
+        let footer = Paragraph::new(footer_text)
+            .style(theme.dimmed_style())
+            .wrap(Wrap { trim: true })
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(theme.inactive_border_style())
+                    .style(theme.status_bar_style())
+            );
+        f.render_widget(footer, chunks[4]);
     }
 }
 
-fn filter_items(state: &mut TuiState) {
-    if state.search_query.is_empty() {
-        state.filtered_items = state.all_items.clone();
+fn render_password_entry_screen(f: &mut Frame, state: &TuiState, theme: &Theme) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Заголовок
+            Constraint::Length(1), // Метка имени
+            Constraint::Length(3), // Имя
+            Constraint::Length(3), // Имя пользователя
+            Constraint::Length(1), // Метка пароля
+            Constraint::Length(3), // Пароль
+            Constraint::Length(3), // URL и теги
+            Constraint::Length(5), // Заметки
+            Constraint::Min(0),    // Предупреждения
+            Constraint::Length(footer_height(state.i18n.ts("password_entry_footer"), f.size().width)), // Футер
+        ])
+        .split(f.size());
+
+    let mut title_text = if matches!(state.current_screen, Screen::PasswordEntry { is_edit: true, .. }) {
+        state.i18n.ts("password_entry_edit_title").to_string()
     } else {
-        let matcher = SkimMatcherV2::default();
-        let mut scored_items: Vec<(i64, String)> = state
-            .all_items
-            .iter()
-            .filter_map(|item| {
-                matcher.fuzzy_match(item, &state.search_query).map(|score| (score, item.clone()))
-            })
-            .collect();
-        
-        // Сортируем по релевантности (больший score = лучшее совпадение)
-        scored_items.sort_by(|a, b| b.0.cmp(&a.0));
-        
-        state.filtered_items = scored_items.into_iter().map(|(_, item)| item).collect();
+        state.i18n.ts("password_entry_create_title").to_string()
+    };
+    if state.password_entry_tray_shortcut {
+        title_text = format!("{} [{}]", title_text, state.i18n.ts("password_entry_tray_pinned"));
     }
-}
 
-fn generate_password(state: &TuiState) -> RpmResult<String> {
-    use crate::errors::RpmError;
-    
-    // Проверяем, что выбран хотя бы один набор символов
-    if !state.password_generator_use_uppercase
-        && !state.password_generator_use_lowercase
-        && !state.password_generator_use_digits
-        && !state.password_generator_use_special
-    {
-        return Err(RpmError::Crypto("Необходимо выбрать хотя бы один набор символов".to_string()));
-    }
-    
-    // Парсим длину пароля
-    let length: usize = state.password_generator_length.trim().parse()
-        .map_err(|_| RpmError::Crypto("Неверная длина пароля".to_string()))?;
-    
-    if length < 1 {
-        return Err(RpmError::Crypto("Длина пароля должна быть не менее 1".to_string()));
-    }
-    
-    if length > 256 {
-        return Err(RpmError::Crypto("Длина пароля не должна превышать 256".to_string()));
-    }
-    
-    // Собираем доступные символы
-    let mut available_chars = Vec::new();
-    
-    if state.password_generator_use_uppercase {
-        available_chars.extend('A'..='Z');
-    }
-    if state.password_generator_use_lowercase {
-        available_chars.extend('a'..='z');
-    }
-    if state.password_generator_use_digits {
-        available_chars.extend('0'..='9');
-    }
-    if state.password_generator_use_special {
-        available_chars.extend("!@#$%^&*()_+-=[]{}|;:,.<>?".chars());
-    }
-    
-    // Исключаем символы из exclude_chars
-    let exclude_set: HashSet<char> = state.password_generator_exclude_chars.chars().collect();
-    available_chars.retain(|&c| !exclude_set.contains(&c));
-    
-    // Проверяем, что после исключения остались символы
-    if available_chars.is_empty() {
-        return Err(RpmError::Crypto("После исключения символов не осталось доступных символов".to_string()));
-    }
-    
-    // Генерируем пароль используя криптографически стойкий генератор
-    let mut rng = OsRng;
-    let password: String = (0..length)
-        .map(|_| {
-            let idx = rng.gen_range(0..available_chars.len());
-            available_chars[idx]
+    let title = Paragraph::new(title_text)
+        .style(theme.title_style())
+        .alignment(Alignment::Center)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(theme.active_border_style())
+                .style(theme.surface_style())
+        );
+    f.render_widget(title, chunks[0]);
+
+    let name_label = Paragraph::new(state.i18n.ts("password_entry_name_label"))
+        .style(theme.text_style())
+        .block(Block::default().borders(Borders::NONE));
+    f.render_widget(name_label, chunks[1]);
+
+    let name_style = if state.password_entry_field == 0 {
+        theme.active_input_style()
+    } else {
+        theme.inactive_input_style()
+    };
+
+    let name_title = if state.password_entry_field == 0 {
+        state.i18n.ts("password_entry_name_active")
+    } else {
+        state.i18n.ts("password_entry_name")
+    };
+
+    let name_border_style = if state.password_entry_field == 0 {
+        theme.active_border_style()
+    } else {
+        theme.inactive_border_style()
+    };
+
+    let name_input = Paragraph::new(state.password_entry_name.as_str())
+        .style(name_style)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(name_border_style)
+                .style(theme.surface_style())
+                .title(name_title),
+        );
+    f.render_widget(name_input, chunks[2]);
+
+    let username_title = if state.password_entry_field == 1 {
+        state.i18n.ts("password_entry_username_active")
+    } else {
+        state.i18n.ts("password_entry_username")
+    };
+    render_text_field(f, chunks[3], &state.password_entry_username, username_title, state.password_entry_field == 1, theme);
+
+    let password_label = Paragraph::new(state.i18n.ts("password_entry_password_label"))
+        .style(theme.text_style())
+        .block(Block::default().borders(Borders::NONE));
+    f.render_widget(password_label, chunks[4]);
+
+    let password_display = if state.password_entry_show_password {
+        state.password_entry_password.clone()
+    } else {
+        "*".repeat(state.password_entry_password.len())
+    };
+
+    let password_style = if state.password_entry_field == 2 {
+        theme.active_input_style()
+    } else {
+        theme.inactive_input_style()
+    };
+
+    let password_title = if state.password_entry_field == 2 {
+        format!("{} | Ctrl+H - {}", state.i18n.ts("password_entry_password_active"), if state.password_entry_show_password { state.i18n.ts("hide") } else { state.i18n.ts("show") })
+    } else {
+        format!("{} | Ctrl+H - {}", state.i18n.ts("password_entry_password"), if state.password_entry_show_password { state.i18n.ts("hide") } else { state.i18n.ts("show") })
+    };
+
+    let password_border_style = if state.password_entry_field == 2 {
+        theme.active_border_style()
+    } else {
+        theme.inactive_border_style()
+    };
+
+    let password_input = Paragraph::new(password_display.as_str())
+        .style(password_style)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(password_border_style)
+                .style(theme.surface_style())
+                .title(password_title),
+        );
+    f.render_widget(password_input, chunks[5]);
+
+    let url_title = if state.password_entry_field == 3 {
+        state.i18n.ts("password_entry_url_active")
+    } else {
+        state.i18n.ts("password_entry_url")
+    };
+    // URL и теги в одной строке
+    let url_row = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+        .split(chunks[6]);
+    render_text_field(f, url_row[0], &state.password_entry_url, url_title, state.password_entry_field == 3, theme);
+
+    let tags_title = if state.password_entry_field == 5 {
+        state.i18n.ts("password_entry_tags_active")
+    } else {
+        state.i18n.ts("password_entry_tags")
+    };
+    render_text_field(f, url_row[1], &state.password_entry_tags, tags_title, state.password_entry_field == 5, theme);
+
+    let notes_title = if state.password_entry_field == 4 {
+        state.i18n.ts("password_entry_notes_active")
+    } else {
+        state.i18n.ts("password_entry_notes")
+    };
+    render_text_field(f, chunks[7], &state.password_entry_notes, notes_title, state.password_entry_field == 4, theme);
+
+    // Предупреждение о переиспользовании пароля
+    if let Some(ref reused_by) = state.password_entry_reuse_warning {
+        let warning_text = format!(
+            "⚠ {} {}\n{}",
+            state.i18n.ts("password_entry_reuse_warning"),
+            reused_by.join(", "),
+            state.i18n.ts("password_entry_reuse_hint")
+        );
+        let warning = Paragraph::new(warning_text)
+            .style(theme.warning_style())
+            .block(Block::default().borders(Borders::NONE));
+        f.render_widget(warning, chunks[8]);
+    } else if let Some(ref error) = state.password_entry_editor_error {
+        let error = Paragraph::new(error.as_str())
+            .style(theme.error_style())
+            .wrap(Wrap { trim: true })
+            .block(Block::default().borders(Borders::NONE));
+        f.render_widget(error, chunks[8]);
+    } else if matches!(state.current_screen, Screen::PasswordEntry { is_edit: true, .. }) {
+        // Даты создания, изменения и последнего копирования
+        let timestamps = &state.password_entry_timestamps;
+        let lines: Vec<String> = [
+            ("password_entry_created_at", timestamps.created_at),
+            ("password_entry_updated_at", timestamps.updated_at),
+            ("password_entry_last_used_at", timestamps.last_used_at),
+        ]
+        .iter()
+        .map(|(label, datetime)| {
+            let value = match datetime {
+                Some(datetime) => format_datetime(state, *datetime),
+                None => state.i18n.ts("password_entry_timestamp_unknown").to_string(),
+            };
+            format!("{} {}", state.i18n.ts(label), value)
         })
         .collect();
-    
-    Ok(password)
+        let info = Paragraph::new(lines.join("\n"))
+            .style(theme.dimmed_style())
+            .block(Block::default().borders(Borders::NONE));
+        f.render_widget(info, chunks[8]);
+    }
+
+    let footer = Paragraph::new(state.i18n.ts("password_entry_footer"))
+        .style(theme.dimmed_style())
+        .wrap(Wrap { trim: true })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(theme.inactive_border_style())
+                .style(theme.status_bar_style())
+        );
+    f.render_widget(footer, chunks[9]);
+}
+
+/// Поле ввода в рамке; активное поле подсвечивается
+fn render_text_field(f: &mut Frame, area: Rect, value: &str, title: &str, active: bool, theme: &Theme) {
+    let (style, border_style) = if active {
+        (theme.active_input_style(), theme.active_border_style())
+    } else {
+        (theme.inactive_input_style(), theme.inactive_border_style())
+    };
+
+    let input = Paragraph::new(value)
+        .style(style)
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(border_style)
+                .style(theme.surface_style())
+                .title(title),
+        );
+    f.render_widget(input, area);
+}
+
+fn render_help_screen(f: &mut Frame, state: &TuiState, theme: &Theme) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Заголовок
+            Constraint::Min(0),    // Основной контент
+            Constraint::Length(3), // Футер
+        ])
+        .split(f.size());
+
+    // Заголовок
+    let title = Paragraph::new(state.i18n.ts("help_title"))
+        .style(theme.title_style())
+        .alignment(Alignment::Center)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(theme.active_border_style())
+                .style(theme.surface_style())
+        );
+    f.render_widget(title, chunks[0]);
+
+    // Основной контент с описанием горячих клавиш из таблицы клавиш
+    let help_text = keymap::help_lines(&state.i18n);
+
+    let help_content = Paragraph::new(help_text.join("\n"))
+        .style(theme.text_style())
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(theme.inactive_border_style())
+                .style(theme.surface_style())
+                .title(state.i18n.ts("help_navigation")),
+        );
+    f.render_widget(help_content, chunks[1]);
+
+    // Футер
+    let footer = Paragraph::new(state.i18n.ts("help_footer"))
+        .style(theme.dimmed_style())
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(theme.inactive_border_style())
+                .style(theme.status_bar_style())
+        );
+    f.render_widget(footer, chunks[2]);
 }
 
-fn render_main_screen(f: &mut Frame, state: &TuiState, list_state: &mut ListState, theme: &Theme) {
+fn render_about_screen(f: &mut Frame, state: &TuiState, theme: &Theme) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(3), // Поле поиска
-            Constraint::Min(0),    // Основной контент
+            Constraint::Length(3), // Заголовок
+            Constraint::Min(0),    // Диагностика
             Constraint::Length(3), // Футер
         ])
         .split(f.size());
 
-    // Поле ввода для поиска
-    let search_input = Paragraph::new(state.search_query.as_str())
-        .style(theme.accent_style())
+    let title = Paragraph::new(state.i18n.ts("about_title"))
+        .style(theme.title_style())
+        .alignment(Alignment::Center)
         .block(
             Block::default()
                 .borders(Borders::ALL)
                 .border_type(BorderType::Rounded)
                 .border_style(theme.active_border_style())
                 .style(theme.surface_style())
-                .title(state.i18n.ts("main_search"))
         );
-    f.render_widget(search_input, chunks[0]);
-
-    // Main content area
-    let items: Vec<ListItem> = state
-        .filtered_items
-        .iter()
-        .map(|item| ListItem::new(item.as_str()).style(theme.text_style()))
-        .collect();
+    f.render_widget(title, chunks[0]);
 
-    let list = List::new(items)
+    let mut lines = Vec::new();
+    if let Some(ref about) = state.about {
+        let i18n = &state.i18n;
+        lines.push(format!("RPM {}", about.version));
+        lines.push(format!("{} {} ({}, {})", i18n.ts("about_build"), about.build_hash, about.profile, about.target));
+        lines.push(String::new());
+        let rng = match about.rng_check {
+            Ok(()) => i18n.ts("about_rng_ok").to_string(),
+            Err(ref e) => format!("{} {}", i18n.ts("about_rng_failed"), e),
+        };
+        lines.push(format!("{} {} - {}", i18n.ts("about_rng"), about.rng_source, rng));
+        lines.push(match about.entropy_pool {
+            Some((available, size)) => format!("{} {} / {} {}", i18n.ts("about_entropy"), available, size, i18n.ts("about_bits")),
+            None => format!("{} {}", i18n.ts("about_entropy"), i18n.ts("about_unknown")),
+        });
+        lines.push(String::new());
+        lines.push(format!("{} {}", i18n.ts("about_crypto_crates"), about.crypto_crates.join(", ")));
+        lines.push(String::new());
+        let verdict = if about.hardened() { "about_hardened" } else { "about_not_hardened" };
+        lines.push(format!("{} {}", i18n.ts("about_hardening"), i18n.ts(verdict)));
+        for check in &about.hardening {
+            let mark = match check.passed {
+                Some(true) => "✓",
+                Some(false) => "✗",
+                None => "?",
+            };
+            lines.push(format!("  {} {}", mark, i18n.ts(&format!("about_hardening_{}", check.name))));
+        }
+    }
+    let content = Paragraph::new(lines.join("\n"))
+        .style(theme.text_style())
+        .wrap(Wrap { trim: false })
         .block(
             Block::default()
                 .borders(Borders::ALL)
                 .border_type(BorderType::Rounded)
                 .border_style(theme.inactive_border_style())
                 .style(theme.surface_style())
-                .title(format!("{} ({})", state.i18n.ts("main_passwords"), state.filtered_items.len()))
-        )
-        .highlight_style(theme.selection_style())
-        .highlight_symbol(">> ");
-
-    f.render_stateful_widget(list, chunks[1], list_state);
+        );
+    f.render_widget(content, chunks[1]);
 
-    // Footer
-    let footer = Paragraph::new(state.i18n.ts("main_footer"))
+    let footer = Paragraph::new(state.i18n.ts("about_footer"))
         .style(theme.dimmed_style())
         .block(
             Block::default()
@@ -1256,37 +6096,37 @@ fn render_main_screen(f: &mut Frame, state: &TuiState, list_state: &mut ListStat
     f.render_widget(footer, chunks[2]);
 }
 
-fn render_settings_screen(f: &mut Frame, state: &TuiState, theme: &Theme) {
+fn render_password_generator_screen(f: &mut Frame, state: &TuiState, theme: &Theme) {
     let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Min(0),    // Основной контент
-            Constraint::Length(3), // Футер
-        ])
-        .split(f.size());
-
-    // Окно настроек
-    let settings_content = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(3), // Заголовок
-            Constraint::Length(1), // Метка для пути сохранения
-            Constraint::Length(3), // Путь сохранения
-            Constraint::Length(1), // Метка для конфига
-            Constraint::Length(3), // Путь конфига
-            Constraint::Length(1), // Метка для директории
-            Constraint::Length(3), // Поле ввода директории
-            Constraint::Length(1), // Метка для времени хранения
-            Constraint::Length(3), // Поле ввода времени хранения
-            Constraint::Length(1), // Метка для темы
-            Constraint::Length(3), // Поле выбора темы
-            Constraint::Length(1), // Метка для языка
-            Constraint::Length(3), // Поле выбора языка
+            Constraint::Length(1), // Метка для длины
+            Constraint::Length(3), // Поле ввода длины
+            Constraint::Length(1), // Метка для исключений
+            Constraint::Length(3), // Поле ввода исключений
+            Constraint::Length(1), // Пустая строка (или ошибка генерации)
+            Constraint::Length(1), // Метка для галочек
+            Constraint::Length(1), // Заглавные буквы
+            Constraint::Length(1), // Строчные буквы
+            Constraint::Length(1), // Цифры
+            Constraint::Length(1), // Спецсимволы
+            Constraint::Length(1), // Кириллица
+            Constraint::Length(1), // Парольная фраза
+            Constraint::Length(1), // Без похожих символов
+            Constraint::Length(1), // Минимумы цифр, спецсимволов и заглавных
+            Constraint::Length(1), // Энтропия
             Constraint::Min(0),    // Остальное пространство
+            Constraint::Length(footer_height(state.i18n.ts("password_generator_footer"), f.size().width)), // Футер
         ])
-        .split(chunks[0]);
+        .split(f.size());
 
-    let settings_title = Paragraph::new(state.i18n.ts("settings_title"))
+    // Заголовок (с именем пресета, если настройки совпадают с ним)
+    let title = match current_generator_preset(state) {
+        Some(preset) => format!("{} · {}: {}", state.i18n.ts("password_generator_title"), state.i18n.ts("password_generator_preset"), preset),
+        None => state.i18n.ts("password_generator_title").to_string(),
+    };
+    let title = Paragraph::new(title)
         .style(theme.title_style())
         .alignment(Alignment::Center)
         .block(
@@ -1296,201 +6136,452 @@ fn render_settings_screen(f: &mut Frame, state: &TuiState, theme: &Theme) {
                 .border_style(theme.active_border_style())
                 .style(theme.surface_style())
         );
-    f.render_widget(settings_title, settings_content[0]);
+    f.render_widget(title, chunks[0]);
 
-    // Информация о пути сохранения файлов
-    let save_path_label = Paragraph::new(state.i18n.ts("settings_save_path_label"))
+    // Метка для длины (во фразе длина - это число слов)
+    let length_label = Paragraph::new(if state.password_generator_passphrase {
+        state.i18n.ts("password_generator_words_label")
+    } else {
+        state.i18n.ts("password_generator_length_label")
+    })
         .style(theme.text_style())
         .block(Block::default().borders(Borders::NONE));
-    f.render_widget(save_path_label, settings_content[1]);
+    f.render_widget(length_label, chunks[1]);
 
-    let save_path = state.config.passwords_directory_path();
-    let save_path_text = save_path.to_string_lossy().to_string();
-    let save_path_display = Paragraph::new(save_path_text.as_str())
-        .style(theme.accent_style())
+    // Поле ввода длины
+    let length_style = if state.password_generator_selected_field == 0 {
+        theme.active_input_style()
+    } else {
+        theme.inactive_input_style()
+    };
+    let length_title = if state.password_generator_selected_field == 0 {
+        state.i18n.ts("password_generator_length_active")
+    } else {
+        state.i18n.ts("password_generator_length")
+    };
+    let length_border_style = if state.password_generator_selected_field == 0 {
+        theme.active_border_style()
+    } else {
+        theme.inactive_border_style()
+    };
+    let length_input = Paragraph::new(state.password_generator_length.as_str())
+        .style(length_style)
         .block(
             Block::default()
                 .borders(Borders::ALL)
                 .border_type(BorderType::Rounded)
-                .border_style(theme.inactive_border_style())
+                .border_style(length_border_style)
                 .style(theme.surface_style())
-                .title(state.i18n.ts("settings_save_path_title")),
+                .title(length_title),
         );
-    f.render_widget(save_path_display, settings_content[2]);
-
-    // Информация о пути к конфигурационному файлу
-    let config_path_label = Paragraph::new(state.i18n.ts("settings_config_path_label"))
-        .style(theme.text_style())
-        .block(Block::default().borders(Borders::NONE));
-    f.render_widget(config_path_label, settings_content[3]);
+    f.render_widget(length_input, chunks[2]);
 
-    let config_path_text = state.config.config_file_path()
-        .map(|p| p.to_string_lossy().to_string())
-        .unwrap_or_else(|_| state.i18n.ts("settings_config_path_error").to_string());
-    let config_path_display = Paragraph::new(config_path_text.as_str())
-        .style(theme.accent_style())
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .border_type(BorderType::Rounded)
-                .border_style(theme.inactive_border_style())
-                .style(theme.surface_style())
-                .title(state.i18n.ts("settings_config_path_title")),
-        );
-    f.render_widget(config_path_display, settings_content[4]);
+    // Исключения и свой набор символов рядом
+    let labels = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(chunks[3]);
+    let inputs = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(chunks[4]);
 
-    let dir_label = Paragraph::new(state.i18n.ts("settings_directory_label"))
+    // Метка для исключений
+    let exclude_label = Paragraph::new(state.i18n.ts("password_generator_exclude_label"))
         .style(theme.text_style())
         .block(Block::default().borders(Borders::NONE));
-    f.render_widget(dir_label, settings_content[5]);
+    f.render_widget(exclude_label, labels[0]);
 
-    let dir_style = if state.settings_field == 0 {
+    // Поле ввода исключений
+    let exclude_style = if state.password_generator_selected_field == 1 {
         theme.active_input_style()
     } else {
         theme.inactive_input_style()
     };
-
-    let dir_title = if state.settings_field == 0 {
-        state.i18n.ts("settings_directory_active")
+    let exclude_title = if state.password_generator_selected_field == 1 {
+        state.i18n.ts("password_generator_exclude_active")
     } else {
-        state.i18n.ts("settings_directory")
+        state.i18n.ts("password_generator_exclude")
     };
-
-    let dir_border_style = if state.settings_field == 0 {
+    let exclude_border_style = if state.password_generator_selected_field == 1 {
         theme.active_border_style()
     } else {
         theme.inactive_border_style()
     };
-
-    let dir_input = Paragraph::new(state.passwords_dir_input.as_str())
-        .style(dir_style)
+    let exclude_input = Paragraph::new(state.password_generator_exclude_chars.as_str())
+        .style(exclude_style)
         .block(
             Block::default()
                 .borders(Borders::ALL)
                 .border_type(BorderType::Rounded)
-                .border_style(dir_border_style)
+                .border_style(exclude_border_style)
                 .style(theme.surface_style())
-                .title(dir_title),
+                .title(exclude_title),
         );
-    f.render_widget(dir_input, settings_content[6]);
+    f.render_widget(exclude_input, inputs[0]);
 
-    // Метка для времени хранения в буфере обмена
-    let timeout_label = Paragraph::new(state.i18n.ts("settings_clipboard_timeout_label"))
+    // Свой набор символов
+    let custom_label = Paragraph::new(state.i18n.ts("password_generator_custom_label"))
         .style(theme.text_style())
         .block(Block::default().borders(Borders::NONE));
-    f.render_widget(timeout_label, settings_content[7]);
-
-    let timeout_style = if state.settings_field == 1 {
-        theme.active_input_style()
+    f.render_widget(custom_label, labels[1]);
+    let custom_active = state.password_generator_selected_field == 2;
+    let custom_title = if custom_active {
+        state.i18n.ts("password_generator_custom_active")
     } else {
-        theme.inactive_input_style()
+        state.i18n.ts("password_generator_custom")
     };
+    render_text_field(f, inputs[1], &state.password_generator_custom_chars, custom_title, custom_active, theme);
 
-    let timeout_title = if state.settings_field == 1 {
-        state.i18n.ts("settings_clipboard_timeout_active")
-    } else {
-        state.i18n.ts("settings_clipboard_timeout")
+    if let Some(ref error) = state.password_generator_error {
+        f.render_widget(Paragraph::new(error.as_str()).style(theme.error_style()), chunks[5]);
+    }
+
+    // Метка для галочек
+    let checkboxes_label = Paragraph::new(state.i18n.ts("password_generator_charsets_label"))
+        .style(theme.text_style())
+        .block(Block::default().borders(Borders::NONE));
+    f.render_widget(checkboxes_label, chunks[6]);
+
+    // Галочки
+    let checkbox_style = |field_idx: usize| {
+        if state.password_generator_selected_field == field_idx {
+            theme.active_input_style()
+        } else {
+            theme.text_style()
+        }
     };
 
-    let timeout_border_style = if state.settings_field == 1 {
-        theme.active_border_style()
-    } else {
-        theme.inactive_border_style()
+    // Заглавные буквы
+    let uppercase_mark = if state.password_generator_use_uppercase { "[✓]" } else { "[ ]" };
+    let uppercase_text = format!("{} {}", uppercase_mark, state.i18n.ts("password_generator_uppercase"));
+    let uppercase_para = Paragraph::new(uppercase_text.as_str())
+        .style(checkbox_style(3))
+        .block(Block::default().borders(Borders::NONE));
+    f.render_widget(uppercase_para, chunks[7]);
+
+    // Строчные буквы
+    let lowercase_mark = if state.password_generator_use_lowercase { "[✓]" } else { "[ ]" };
+    let lowercase_text = format!("{} {}", lowercase_mark, state.i18n.ts("password_generator_lowercase"));
+    let lowercase_para = Paragraph::new(lowercase_text.as_str())
+        .style(checkbox_style(4))
+        .block(Block::default().borders(Borders::NONE));
+    f.render_widget(lowercase_para, chunks[8]);
+
+    // Цифры
+    let digits_mark = if state.password_generator_use_digits { "[✓]" } else { "[ ]" };
+    let digits_text = format!("{} {}", digits_mark, state.i18n.ts("password_generator_digits"));
+    let digits_para = Paragraph::new(digits_text.as_str())
+        .style(checkbox_style(5))
+        .block(Block::default().borders(Borders::NONE));
+    f.render_widget(digits_para, chunks[9]);
+
+    // Спецсимволы
+    let special_mark = if state.password_generator_use_special { "[✓]" } else { "[ ]" };
+    let special_text = format!("{} {}", special_mark, state.i18n.ts("password_generator_special"));
+    let special_para = Paragraph::new(special_text.as_str())
+        .style(checkbox_style(6))
+        .block(Block::default().borders(Borders::NONE));
+    f.render_widget(special_para, chunks[10]);
+
+    // Кириллица
+    let cyrillic_mark = if state.password_generator_use_cyrillic { "[✓]" } else { "[ ]" };
+    let cyrillic_text = format!("{} {}", cyrillic_mark, state.i18n.ts("password_generator_cyrillic"));
+    let cyrillic_para = Paragraph::new(cyrillic_text.as_str())
+        .style(checkbox_style(7))
+        .block(Block::default().borders(Borders::NONE));
+    f.render_widget(cyrillic_para, chunks[11]);
+
+    // Парольная фраза
+    let passphrase_mark = if state.password_generator_passphrase { "[✓]" } else { "[ ]" };
+    let passphrase_text = format!("{} {}", passphrase_mark, state.i18n.ts("password_generator_passphrase"));
+    let passphrase_para = Paragraph::new(passphrase_text.as_str())
+        .style(checkbox_style(8))
+        .block(Block::default().borders(Borders::NONE));
+    f.render_widget(passphrase_para, chunks[12]);
+
+    // Без похожих символов
+    let ambiguous_mark = if state.password_generator_exclude_ambiguous { "[✓]" } else { "[ ]" };
+    let ambiguous_text = format!("{} {}", ambiguous_mark, state.i18n.ts("password_generator_exclude_ambiguous"));
+    let ambiguous_para = Paragraph::new(ambiguous_text.as_str())
+        .style(checkbox_style(9))
+        .block(Block::default().borders(Borders::NONE));
+    f.render_widget(ambiguous_para, chunks[13]);
+
+    // Минимумы в одну строку: "Не менее: [2] цифр  [1] спецсимволов  [0] заглавных"
+    let minimums = [
+        (10, state.password_generator_min_digits, "password_generator_min_digits"),
+        (11, state.password_generator_min_special, "password_generator_min_special"),
+        (12, state.password_generator_min_uppercase, "password_generator_min_uppercase"),
+    ];
+    let mut spans = vec![Span::styled(state.i18n.ts("password_generator_minimum"), theme.text_style())];
+    for (field_idx, min, label) in minimums {
+        spans.push(Span::styled(format!(" [{}]", min), checkbox_style(field_idx)));
+        spans.push(Span::styled(format!(" {} ", state.i18n.ts(label)), theme.text_style()));
+    }
+    f.render_widget(Paragraph::new(Line::from(spans)), chunks[14]);
+
+    // Энтропия текущих настроек, цветом - насколько она достаточна
+    let entropy = generator_policy(state).ok().as_ref().and_then(generator_entropy_bits);
+    let entropy_value = match entropy {
+        Some(bits) => {
+            let (rating, style) = if bits < WEAK_ENTROPY_BITS {
+                ("password_generator_entropy_weak", theme.error_style())
+            } else if bits < STRONG_ENTROPY_BITS {
+                ("password_generator_entropy_fair", theme.warning_style())
+            } else {
+                ("password_generator_entropy_strong", theme.success_style())
+            };
+            Span::styled(
+                format!(" ~{:.0} {} ({})", bits, state.i18n.ts("password_generator_entropy_bits"), state.i18n.ts(rating)),
+                style,
+            )
+        }
+        None => Span::styled(" -", theme.dimmed_style()),
     };
+    let entropy_line = Line::from(vec![
+        Span::styled(state.i18n.ts("password_generator_entropy"), theme.text_style()),
+        entropy_value,
+    ]);
+    f.render_widget(Paragraph::new(entropy_line), chunks[15]);
+
+    // Футер
+    let footer = Paragraph::new(state.i18n.ts("password_generator_footer"))
+        .style(theme.dimmed_style())
+        .wrap(Wrap { trim: true })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(theme.inactive_border_style())
+                .style(theme.status_bar_style())
+        );
+    f.render_widget(footer, chunks[17]);
+
+    // Выпадающий список пресетов
+    if state.password_generator_presets_open {
+        let presets = &state.config.generator_presets;
+        let area = centered_rect(50, (presets.len() as u16).clamp(1, 10) + 3, f.size());
+        f.render_widget(Clear, area);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(theme.active_border_style())
+            .style(theme.surface_style())
+            .title(state.i18n.ts("password_generator_presets_title"));
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(1)])
+            .split(inner);
+        if presets.is_empty() {
+            let empty = Paragraph::new(state.i18n.ts("password_generator_presets_empty"))
+                .style(theme.dimmed_style())
+                .alignment(Alignment::Center);
+            f.render_widget(empty, rows[0]);
+        } else {
+            let items: Vec<ListItem> = presets
+                .iter()
+                .map(|(name, policy)| {
+                    // Кратко: длина и тип (символы или слова)
+                    let unit = if policy.passphrase_language.is_some() {
+                        state.i18n.ts("password_generator_preset_words")
+                    } else {
+                        state.i18n.ts("password_generator_preset_chars")
+                    };
+                    ListItem::new(Line::from(vec![
+                        Span::styled(name.as_str(), theme.text_style()),
+                        Span::styled(format!("  {} {}", policy.length, unit), theme.dimmed_style()),
+                    ]))
+                })
+                .collect();
+            let list = List::new(items)
+                .highlight_style(theme.selection_style())
+                .highlight_symbol(">> ");
+            let mut list_state = ListState::default();
+            list_state.select(Some(state.password_generator_preset_picker.index));
+            f.render_stateful_widget(list, rows[0], &mut list_state);
+        }
+        let hint = Paragraph::new(state.i18n.ts("password_generator_presets_hint"))
+            .style(theme.dimmed_style())
+            .alignment(Alignment::Center);
+        f.render_widget(hint, rows[1]);
+    }
+
+    // Диалог сохранения пресета
+    if let Some(ref name) = state.password_generator_preset_name {
+        let area = centered_rect(60, 7, f.size());
+        f.render_widget(Clear, area);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(theme.active_border_style())
+            .style(theme.surface_style())
+            .title(state.i18n.ts("password_generator_save_preset_title"));
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(0)])
+            .split(inner);
+        render_text_field(f, rows[0], name, state.i18n.ts("password_generator_preset_name"), true, theme);
+        let hint = match state.password_generator_preset_error {
+            Some(ref error) => Paragraph::new(error.as_str()).style(theme.error_style()),
+            None => Paragraph::new(state.i18n.ts("password_generator_save_preset_hint")).style(theme.dimmed_style()),
+        };
+        f.render_widget(hint.wrap(Wrap { trim: true }), rows[1]);
+    }
+}
+
+fn render_theme_selection_screen(f: &mut Frame, state: &TuiState, theme: &Theme) {
+    let items: Vec<PickerItem> = THEMES
+        .iter()
+        .map(|(name, theme_id, desc)| PickerItem {
+            label: name.to_string(),
+            description: Some(desc.to_string()),
+            checked: state.config.theme == *theme_id,
+        })
+        .collect();
+
+    state.theme_picker.render(
+        f,
+        state.i18n.ts("theme_selection_title"),
+        state.i18n.ts("theme_selection_list_title"),
+        state.i18n.ts("theme_selection_footer"),
+        &items,
+        theme,
+    );
+}
+
+fn render_language_selection_screen(f: &mut Frame, state: &TuiState, theme: &Theme) {
+    let items: Vec<PickerItem> = Language::all()
+        .into_iter()
+        .map(|lang| PickerItem {
+            label: lang.display_name().to_string(),
+            description: None,
+            checked: state.config.language == lang.to_code(),
+        })
+        .collect();
+
+    state.language_picker.render(
+        f,
+        state.i18n.ts("language_selection_title"),
+        state.i18n.ts("language_selection_list_title"),
+        state.i18n.ts("language_selection_footer"),
+        &items,
+        theme,
+    );
+}
+
+fn render_audit_screen(f: &mut Frame, state: &TuiState, theme: &Theme) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Заголовок
+            Constraint::Min(0),    // Основной контент
+            Constraint::Length(3), // Футер
+        ])
+        .split(f.size());
 
-    let timeout_input = Paragraph::new(state.clipboard_timeout_input.as_str())
-        .style(timeout_style)
+    // Заголовок
+    let title = Paragraph::new(state.i18n.ts("audit_title"))
+        .style(theme.title_style())
+        .alignment(Alignment::Center)
         .block(
             Block::default()
                 .borders(Borders::ALL)
                 .border_type(BorderType::Rounded)
-                .border_style(timeout_border_style)
+                .border_style(theme.active_border_style())
                 .style(theme.surface_style())
-                .title(timeout_title),
         );
-    f.render_widget(timeout_input, settings_content[8]);
-
-    // Метка для темы
-    let theme_label = Paragraph::new(state.i18n.ts("settings_theme_label"))
-        .style(theme.text_style())
-        .block(Block::default().borders(Borders::NONE));
-    f.render_widget(theme_label, settings_content[9]);
-
-    let theme_style = if state.settings_field == 2 {
-        theme.active_input_style()
-    } else {
-        theme.inactive_input_style()
-    };
+    f.render_widget(title, chunks[0]);
 
-    let theme_title = if state.settings_field == 2 {
-        state.i18n.ts("settings_theme_active")
-    } else {
-        state.i18n.ts("settings_theme")
-    };
+    let content = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage(50), // Переиспользованные пароли
+            Constraint::Percentage(50), // Утечки
+        ])
+        .split(chunks[1]);
 
-    let theme_border_style = if state.settings_field == 2 {
-        theme.active_border_style()
+    // Группы записей с одинаковым паролем
+    let items: Vec<ListItem> = if state.audit_reuse_groups.is_empty() {
+        vec![ListItem::new(state.i18n.ts("audit_no_reuse")).style(theme.success_style())]
     } else {
-        theme.inactive_border_style()
-    };
-
-    let current_theme_name = match state.config.theme.as_str() {
-        "vscode_style" => "VS Code Dark+",
-        "opencode_style" => "OpenCode / Dark Modern",
-        _ => "Textual / Modern Web",
+        state
+            .audit_reuse_groups
+            .iter()
+            .map(|cluster| {
+                let text = format!(
+                    "⚠ {} ({}) | {} {}/100 | ~{:.0} {}\n     {} {}",
+                    state.i18n.ts("audit_reused_password"),
+                    cluster.entries.len(),
+                    state.i18n.ts("audit_reuse_risk"),
+                    cluster.risk_score,
+                    cluster.entropy_bits,
+                    state.i18n.ts("audit_bits"),
+                    state.i18n.ts("audit_reuse_sites"),
+                    cluster.sites.join(", ")
+                );
+                // Самые опасные кластеры выделяем, чтобы с них начать смену паролей
+                let style = if cluster.risk_score >= HIGH_REUSE_RISK {
+                    theme.error_style()
+                } else {
+                    theme.warning_style()
+                };
+                ListItem::new(text).style(style)
+            })
+            .collect()
     };
 
-    let theme_display = Paragraph::new(current_theme_name)
-        .style(theme_style)
+    let list = List::new(items)
         .block(
             Block::default()
                 .borders(Borders::ALL)
                 .border_type(BorderType::Rounded)
-                .border_style(theme_border_style)
+                .border_style(theme.inactive_border_style())
                 .style(theme.surface_style())
-                .title(theme_title),
+                .title(format!("{} ({})", state.i18n.ts("audit_reuse_list_title"), state.audit_reuse_groups.len()))
         );
-    f.render_widget(theme_display, settings_content[10]);
-
-    // Метка для языка
-    let language_label = Paragraph::new(state.i18n.ts("settings_language_label"))
-        .style(theme.text_style())
-        .block(Block::default().borders(Borders::NONE));
-    f.render_widget(language_label, settings_content[11]);
-
-    let language_style = if state.settings_field == 3 {
-        theme.active_input_style()
-    } else {
-        theme.inactive_input_style()
-    };
+    f.render_widget(list, content[0]);
 
-    let language_title = if state.settings_field == 3 {
-        state.i18n.ts("settings_language_active")
+    // Записи, найденные в базе утечек
+    let breach_list_title = if state.config.hibp_dataset_path.is_none() && state.config.hibp_online {
+        "audit_breach_list_title_online"
     } else {
-        state.i18n.ts("settings_language")
+        "audit_breach_list_title"
     };
-
-    let language_border_style = if state.settings_field == 3 {
-        theme.active_border_style()
+    let breach_items: Vec<ListItem> = if let Some(ref status) = state.audit_breach_status {
+        vec![ListItem::new(status.as_str()).style(theme.dimmed_style())]
+    } else if state.audit_breached.is_empty() {
+        vec![ListItem::new(state.i18n.ts("audit_no_breaches")).style(theme.success_style())]
     } else {
-        theme.inactive_border_style()
+        state
+            .audit_breached
+            .iter()
+            .map(|(name, count)| {
+                let text = format!("✗ {} ({} {})", name, state.i18n.ts("audit_breach_seen"), count);
+                ListItem::new(text).style(theme.error_style())
+            })
+            .collect()
     };
 
-    let current_language = Language::from_code(&state.config.language);
-    let language_display = Paragraph::new(current_language.display_name())
-        .style(language_style)
+    let breach_list = List::new(breach_items)
         .block(
             Block::default()
                 .borders(Borders::ALL)
                 .border_type(BorderType::Rounded)
-                .border_style(language_border_style)
+                .border_style(theme.inactive_border_style())
                 .style(theme.surface_style())
-                .title(language_title),
+                .title(format!("{} ({})", state.i18n.ts(breach_list_title), state.audit_breached.len()))
         );
-    f.render_widget(language_display, settings_content[12]);
+    f.render_widget(breach_list, content[1]);
 
-    // Footer
-    let footer = Paragraph::new(state.i18n.ts("settings_footer"))
+    // Футер
+    let footer = Paragraph::new(state.i18n.ts("audit_footer"))
         .style(theme.dimmed_style())
         .block(
             Block::default()
@@ -1499,45 +6590,23 @@ fn render_settings_screen(f: &mut Frame, state: &TuiState, theme: &Theme) {
                 .border_style(theme.inactive_border_style())
                 .style(theme.status_bar_style())
         );
-    f.render_widget(footer, chunks[1]);
+    f.render_widget(footer, chunks[2]);
 }
 
-fn render_master_password_screen(f: &mut Frame, state: &TuiState, theme: &Theme) {
-    let constraints = if state.is_creating_master_password {
-        vec![
-            Constraint::Min(0),
-            Constraint::Length(3),
-            Constraint::Length(1),
-            Constraint::Length(3),
-            Constraint::Length(1),
-            Constraint::Length(3),
-            Constraint::Length(1),
-            Constraint::Length(3),
-            Constraint::Min(0),
-            Constraint::Length(3),
-        ]
-    } else {
-        vec![
-            Constraint::Min(0),
-            Constraint::Length(3),
-            Constraint::Length(3),
-            Constraint::Min(0),
-            Constraint::Length(3),
-        ]
-    };
-
+fn render_security_questions_screen(f: &mut Frame, state: &TuiState, theme: &Theme) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
-        .constraints(constraints)
+        .constraints([
+            Constraint::Length(3), // Заголовок
+            Constraint::Min(0),    // Список вопросов
+            Constraint::Length(3), // Вопрос
+            Constraint::Length(3), // Ответ
+            Constraint::Length(footer_height(state.i18n.ts("security_questions_footer"), f.size().width)), // Футер
+        ])
         .split(f.size());
 
-    let title_text = if state.is_creating_master_password {
-        state.i18n.ts("master_password_create_title")
-    } else {
-        state.i18n.ts("master_password_title")
-    };
-
-    let title = Paragraph::new(title_text)
+    // Заголовок
+    let title = Paragraph::new(format!("{}: {}", state.i18n.ts("security_questions_title"), state.password_entry_name))
         .style(theme.title_style())
         .alignment(Alignment::Center)
         .block(
@@ -1547,199 +6616,263 @@ fn render_master_password_screen(f: &mut Frame, state: &TuiState, theme: &Theme)
                 .border_style(theme.active_border_style())
                 .style(theme.surface_style())
         );
-    f.render_widget(title, chunks[1]);
-
-    if state.is_creating_master_password {
-        // Creating new master password - show directory, password, and confirm fields
-        let dir_label = Paragraph::new(state.i18n.ts("master_password_directory_label"))
-            .style(theme.text_style())
-            .block(Block::default().borders(Borders::NONE));
-        f.render_widget(dir_label, chunks[2]);
-
-        let dir_style = if state.master_password_field == 0 {
-            theme.active_input_style()
-        } else {
-            theme.inactive_input_style()
-        };
-
-        let dir_title = if state.master_password_field == 0 {
-            state.i18n.ts("master_password_directory_active")
-        } else {
-            state.i18n.ts("master_password_directory")
-        };
-
-        let dir_border_style = if state.master_password_field == 0 {
-            theme.active_border_style()
-        } else {
-            theme.inactive_border_style()
-        };
-
-        let dir_input = Paragraph::new(state.passwords_dir_input.as_str())
-            .style(dir_style)
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .border_type(BorderType::Rounded)
-                    .border_style(dir_border_style)
-                    .style(theme.surface_style())
-                    .title(dir_title),
-            );
-        f.render_widget(dir_input, chunks[3]);
-
-        let password_label = Paragraph::new(state.i18n.ts("master_password_label"))
-            .style(theme.text_style())
-            .block(Block::default().borders(Borders::NONE));
-        f.render_widget(password_label, chunks[4]);
-
-        let password_display = if state.master_password_input.is_empty() {
-            String::new()
-        } else if state.master_password_show_password {
-            state.master_password_input.clone()
-        } else {
-            "*".repeat(state.master_password_input.len())
-        };
-
-        let password_style = if state.master_password_field == 1 {
-            theme.active_input_style()
-        } else {
-            theme.inactive_input_style()
-        };
+    f.render_widget(title, chunks[0]);
 
-        let password_title = if state.master_password_field == 1 {
-            format!("{} | Ctrl+H - {}", state.i18n.ts("master_password_active"), if state.master_password_show_password { state.i18n.ts("hide") } else { state.i18n.ts("show") })
+    let questions = &state.password_entry_security_questions;
+    let mask = |answer: &str| {
+        if state.security_questions_show_answers {
+            answer.to_string()
         } else {
-            state.i18n.ts("master_password").to_string()
-        };
+            "*".repeat(answer.chars().count())
+        }
+    };
 
-        let password_border_style = if state.master_password_field == 1 {
-            theme.active_border_style()
-        } else {
-            theme.inactive_border_style()
-        };
+    // Список пар вопрос/ответ; ответы скрыты, пока не нажат Ctrl+H
+    let items: Vec<ListItem> = if questions.is_empty() {
+        vec![ListItem::new(state.i18n.ts("security_questions_empty")).style(theme.dimmed_style())]
+    } else {
+        questions
+            .iter()
+            .enumerate()
+            .map(|(i, q)| {
+                let text = format!("{}. {}\n     {}", i + 1, q.question, mask(&q.answer));
+                if i == state.security_questions_index {
+                    ListItem::new(text).style(theme.selection_style())
+                } else {
+                    ListItem::new(text).style(theme.text_style())
+                }
+            })
+            .collect()
+    };
 
-        let password_input = Paragraph::new(password_display.as_str())
-            .style(password_style)
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .border_type(BorderType::Rounded)
-                    .border_style(password_border_style)
-                    .style(theme.surface_style())
-                    .title(password_title),
-            );
-        f.render_widget(password_input, chunks[5]);
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(theme.inactive_border_style())
+                .style(theme.surface_style())
+                .title(format!("{} ({})", state.i18n.ts("security_questions_list_title"), questions.len()))
+        );
+    f.render_widget(list, chunks[1]);
 
-        let confirm_label = Paragraph::new(state.i18n.ts("master_password_confirm_label"))
-            .style(theme.text_style())
-            .block(Block::default().borders(Borders::NONE));
-        f.render_widget(confirm_label, chunks[6]);
+    // Поля редактирования выбранной пары
+    let selected = questions.get(state.security_questions_index);
+    let question_text = selected.map(|q| q.question.clone()).unwrap_or_default();
+    let answer_text = selected.map(|q| mask(&q.answer)).unwrap_or_default();
+    let has_selection = selected.is_some();
+
+    render_text_field(
+        f,
+        chunks[2],
+        &question_text,
+        state.i18n.ts("security_questions_question"),
+        has_selection && state.security_questions_field == 0,
+        theme,
+    );
+    render_text_field(
+        f,
+        chunks[3],
+        &answer_text,
+        state.i18n.ts("security_questions_answer"),
+        has_selection && state.security_questions_field == 1,
+        theme,
+    );
 
-        let confirm_display = if state.master_password_confirm.is_empty() {
-            String::new()
-        } else if state.master_password_show_password {
-            state.master_password_confirm.clone()
-        } else {
-            "*".repeat(state.master_password_confirm.len())
-        };
+    // Футер
+    let footer = Paragraph::new(state.i18n.ts("security_questions_footer"))
+        .style(theme.dimmed_style())
+        .wrap(Wrap { trim: true })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(theme.inactive_border_style())
+                .style(theme.status_bar_style())
+        );
+    f.render_widget(footer, chunks[4]);
+}
 
-        let confirm_style = if state.master_password_field == 2 {
-            theme.active_input_style()
-        } else {
-            theme.inactive_input_style()
-        };
+fn render_custom_fields_screen(f: &mut Frame, state: &TuiState, theme: &Theme) {
+    let footer_text = state.status_message.as_deref().unwrap_or(state.i18n.ts("custom_fields_footer"));
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Заголовок
+            Constraint::Min(0),    // Список полей
+            Constraint::Length(3), // Название
+            Constraint::Length(3), // Значение
+            Constraint::Length(footer_height(footer_text, f.size().width)), // Футер
+        ])
+        .split(f.size());
 
-        let confirm_title = if state.master_password_field == 2 {
-            format!("{} | Ctrl+H - {}", state.i18n.ts("master_password_confirm_active"), if state.master_password_show_password { state.i18n.ts("hide") } else { state.i18n.ts("show") })
-        } else {
-            state.i18n.ts("master_password_confirm").to_string()
-        };
+    // Заголовок
+    let title = Paragraph::new(format!("{}: {}", state.i18n.ts("custom_fields_title"), state.password_entry_name))
+        .style(theme.title_style())
+        .alignment(Alignment::Center)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(theme.active_border_style())
+                .style(theme.surface_style())
+        );
+    f.render_widget(title, chunks[0]);
 
-        let confirm_border_style = if state.master_password_field == 2 {
-            theme.active_border_style()
+    let fields = &state.password_entry_custom_fields;
+    let mask = |value: &str| {
+        if state.custom_fields_show_values {
+            value.to_string()
         } else {
-            theme.inactive_border_style()
-        };
-
-        let confirm_input = Paragraph::new(confirm_display.as_str())
-            .style(confirm_style)
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .border_type(BorderType::Rounded)
-                    .border_style(confirm_border_style)
-                    .style(theme.surface_style())
-                    .title(confirm_title),
-            );
-        f.render_widget(confirm_input, chunks[7]);
+            "*".repeat(value.chars().count())
+        }
+    };
 
-        let footer = Paragraph::new(state.i18n.ts("master_password_footer_create"))
-            .style(theme.dimmed_style())
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .border_type(BorderType::Rounded)
-                    .border_style(theme.inactive_border_style())
-                    .style(theme.status_bar_style())
-            );
-        f.render_widget(footer, chunks[9]);
+    // Список полей; значения скрыты, пока не нажат Ctrl+H
+    let items: Vec<ListItem> = if fields.is_empty() {
+        vec![ListItem::new(state.i18n.ts("custom_fields_empty")).style(theme.dimmed_style())]
     } else {
-        // Entering existing master password - show one field
-        let password_display = if state.master_password_input.is_empty() {
-            String::new()
-        } else if state.master_password_show_password {
-            state.master_password_input.clone()
-        } else {
-            "*".repeat(state.master_password_input.len())
-        };
+        fields
+            .iter()
+            .enumerate()
+            .map(|(i, field)| {
+                let text = format!("{}: {}", field.label, mask(&field.value));
+                if i == state.custom_fields_index {
+                    ListItem::new(text).style(theme.selection_style())
+                } else {
+                    ListItem::new(text).style(theme.text_style())
+                }
+            })
+            .collect()
+    };
 
-        let password_title = format!("{} | Ctrl+H - {}", state.i18n.ts("master_password_enter"), if state.master_password_show_password { state.i18n.ts("hide") } else { state.i18n.ts("show") });
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(theme.inactive_border_style())
+                .style(theme.surface_style())
+                .title(format!("{} ({})", state.i18n.ts("custom_fields_list_title"), fields.len()))
+        );
+    f.render_widget(list, chunks[1]);
 
-        let password_input = Paragraph::new(password_display.as_str())
-            .style(theme.accent_style())
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .border_type(BorderType::Rounded)
-                    .border_style(theme.active_border_style())
-                    .style(theme.surface_style())
-                    .title(password_title),
-            );
-        f.render_widget(password_input, chunks[2]);
+    // Поля редактирования выбранного поля записи
+    let selected = fields.get(state.custom_fields_index);
+    let label_text = selected.map(|field| field.label.clone()).unwrap_or_default();
+    let value_text = selected.map(|field| mask(&field.value)).unwrap_or_default();
+    let has_selection = selected.is_some();
+
+    render_text_field(
+        f,
+        chunks[2],
+        &label_text,
+        state.i18n.ts("custom_fields_label"),
+        has_selection && state.custom_fields_field == 0,
+        theme,
+    );
+    render_text_field(
+        f,
+        chunks[3],
+        &value_text,
+        state.i18n.ts("custom_fields_value"),
+        has_selection && state.custom_fields_field == 1,
+        theme,
+    );
 
-        let footer = Paragraph::new(state.i18n.ts("master_password_footer_enter"))
-            .style(theme.dimmed_style())
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .border_type(BorderType::Rounded)
-                    .border_style(theme.inactive_border_style())
-                    .style(theme.status_bar_style())
-            );
-        f.render_widget(footer, chunks[4]);
-    }
+    // Футер
+    let footer = Paragraph::new(footer_text)
+        .style(theme.dimmed_style())
+        .wrap(Wrap { trim: true })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(theme.inactive_border_style())
+                .style(theme.status_bar_style())
+        );
+    f.render_widget(footer, chunks[4]);
 }
 
-fn render_password_entry_screen(f: &mut Frame, state: &TuiState, theme: &Theme) {
+fn render_ssh_host_screen(f: &mut Frame, state: &TuiState, theme: &Theme) {
+    let footer_text = state.status_message.as_deref().unwrap_or(state.i18n.ts("ssh_host_footer"));
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(3),
-            Constraint::Length(1),
-            Constraint::Length(3),
-            Constraint::Length(1),
-            Constraint::Length(3),
+            Constraint::Length(3), // Заголовок
+            Constraint::Length(3), // Хост
+            Constraint::Length(3), // Порт
+            Constraint::Length(3), // Пользователь
+            Constraint::Length(3), // Команда
             Constraint::Min(0),
-            Constraint::Length(3),
+            Constraint::Length(footer_height(footer_text, f.size().width)), // Футер
         ])
         .split(f.size());
 
-    let title_text = if matches!(state.current_screen, Screen::PasswordEntry { is_edit: true, .. }) {
-        state.i18n.ts("password_entry_edit_title")
-    } else {
-        state.i18n.ts("password_entry_create_title")
+    // Заголовок
+    let title = Paragraph::new(format!("{}: {}", state.i18n.ts("ssh_host_title"), state.password_entry_name))
+        .style(theme.title_style())
+        .alignment(Alignment::Center)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(theme.active_border_style())
+                .style(theme.surface_style())
+        );
+    f.render_widget(title, chunks[0]);
+
+    render_text_field(f, chunks[1], &state.password_entry_ssh_host, state.i18n.ts("ssh_host_host"), state.ssh_host_field == 0, theme);
+    render_text_field(f, chunks[2], &state.password_entry_ssh_port, state.i18n.ts("ssh_host_port"), state.ssh_host_field == 1, theme);
+    // Без своего логина подставляется имя пользователя записи
+    let username_title = format!("{} ({})", state.i18n.ts("ssh_host_username"), state.i18n.ts("ssh_host_username_hint"));
+    render_text_field(f, chunks[3], &state.password_entry_ssh_username, &username_title, state.ssh_host_field == 2, theme);
+
+    // Команда, которую скопирует Ctrl+K на главном экране
+    let entry_username = non_empty(&state.password_entry_username);
+    let command = entry_ssh_host(state).and_then(|ssh| ssh.ssh_command(entry_username.as_deref()));
+    let preview = match command {
+        Some(command) => Paragraph::new(command).style(theme.text_style()),
+        None => Paragraph::new(state.i18n.ts("ssh_host_no_command")).style(theme.dimmed_style()),
     };
+    let preview = preview.block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(theme.inactive_border_style())
+            .style(theme.surface_style())
+            .title(state.i18n.ts("ssh_host_command")),
+    );
+    f.render_widget(preview, chunks[4]);
 
-    let title = Paragraph::new(title_text)
+    // Футер
+    let footer = Paragraph::new(footer_text)
+        .style(theme.dimmed_style())
+        .wrap(Wrap { trim: true })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(theme.inactive_border_style())
+                .style(theme.status_bar_style())
+        );
+    f.render_widget(footer, chunks[6]);
+}
+
+fn render_autotype_screen(f: &mut Frame, state: &TuiState, theme: &Theme) {
+    let footer_text = state.status_message.as_deref().unwrap_or(state.i18n.ts("autotype_footer"));
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Заголовок
+            Constraint::Length(3), // Последовательность
+            Constraint::Min(0),    // Подсказка
+            Constraint::Length(footer_height(footer_text, f.size().width)), // Футер
+        ])
+        .split(f.size());
+
+    // Заголовок
+    let title = Paragraph::new(format!("{}: {}", state.i18n.ts("autotype_title"), state.password_entry_name))
         .style(theme.title_style())
         .alignment(Alignment::Center)
         .block(
@@ -1751,83 +6884,163 @@ fn render_password_entry_screen(f: &mut Frame, state: &TuiState, theme: &Theme)
         );
     f.render_widget(title, chunks[0]);
 
-    let name_label = Paragraph::new(state.i18n.ts("password_entry_name_label"))
-        .style(theme.text_style())
-        .block(Block::default().borders(Borders::NONE));
-    f.render_widget(name_label, chunks[1]);
+    render_text_field(f, chunks[1], &state.password_entry_autotype, state.i18n.ts("autotype_sequence"), true, theme);
 
-    let name_style = if state.password_entry_field == 0 {
-        theme.active_input_style()
-    } else {
-        theme.inactive_input_style()
-    };
+    // Пустое поле - последовательность из настроек
+    let hint = format!(
+        "{} {}\n\n{}",
+        state.i18n.ts("autotype_default"),
+        state.config.autotype_sequence,
+        state.i18n.ts("autotype_placeholders"),
+    );
+    let hint = Paragraph::new(hint)
+        .style(theme.dimmed_style())
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(theme.inactive_border_style())
+                .style(theme.surface_style())
+        );
+    f.render_widget(hint, chunks[2]);
 
-    let name_title = if state.password_entry_field == 0 {
-        state.i18n.ts("password_entry_name_active")
-    } else {
-        state.i18n.ts("password_entry_name")
-    };
+    // Футер
+    let footer = Paragraph::new(footer_text)
+        .style(theme.dimmed_style())
+        .wrap(Wrap { trim: true })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(theme.inactive_border_style())
+                .style(theme.status_bar_style())
+        );
+    f.render_widget(footer, chunks[3]);
+}
 
-    let name_border_style = if state.password_entry_field == 0 {
-        theme.active_border_style()
-    } else {
-        theme.inactive_border_style()
-    };
+fn render_access_window_screen(f: &mut Frame, state: &TuiState, theme: &Theme) {
+    let footer_text = state.status_message.as_deref().unwrap_or(state.i18n.ts("access_window_footer"));
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Заголовок
+            Constraint::Length(3), // Часы доступа
+            Constraint::Min(0),    // Подсказка
+            Constraint::Length(footer_height(footer_text, f.size().width)), // Футер
+        ])
+        .split(f.size());
 
-    let name_input = Paragraph::new(state.password_entry_name.as_str())
-        .style(name_style)
+    // Заголовок
+    let title = Paragraph::new(format!("{}: {}", state.i18n.ts("access_window_title"), state.password_entry_name))
+        .style(theme.title_style())
+        .alignment(Alignment::Center)
         .block(
             Block::default()
                 .borders(Borders::ALL)
                 .border_type(BorderType::Rounded)
-                .border_style(name_border_style)
+                .border_style(theme.active_border_style())
                 .style(theme.surface_style())
-                .title(name_title),
         );
-    f.render_widget(name_input, chunks[2]);
+    f.render_widget(title, chunks[0]);
 
-    let password_label = Paragraph::new(state.i18n.ts("password_entry_password_label"))
-        .style(theme.text_style())
-        .block(Block::default().borders(Borders::NONE));
-    f.render_widget(password_label, chunks[3]);
+    render_text_field(f, chunks[1], &state.password_entry_access_window, state.i18n.ts("access_window_hours"), true, theme);
 
-    let password_display = if state.password_entry_show_password {
-        state.password_entry_password.clone()
-    } else {
-        "*".repeat(state.password_entry_password.len())
-    };
+    let hint = Paragraph::new(state.i18n.ts("access_window_hint"))
+        .style(theme.dimmed_style())
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(theme.inactive_border_style())
+                .style(theme.surface_style())
+        );
+    f.render_widget(hint, chunks[2]);
 
-    let password_style = if state.password_entry_field == 1 {
-        theme.active_input_style()
-    } else {
-        theme.inactive_input_style()
-    };
+    // Футер
+    let footer = Paragraph::new(footer_text)
+        .style(theme.dimmed_style())
+        .wrap(Wrap { trim: true })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(theme.inactive_border_style())
+                .style(theme.status_bar_style())
+        );
+    f.render_widget(footer, chunks[3]);
+}
 
-    let password_title = if state.password_entry_field == 1 {
-        format!("{} | Ctrl+H - {}", state.i18n.ts("password_entry_password_active"), if state.password_entry_show_password { state.i18n.ts("hide") } else { state.i18n.ts("show") })
-    } else {
-        format!("{} | Ctrl+H - {}", state.i18n.ts("password_entry_password"), if state.password_entry_show_password { state.i18n.ts("hide") } else { state.i18n.ts("show") })
-    };
+fn render_rotation_screen(f: &mut Frame, state: &TuiState, theme: &Theme) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Заголовок
+            Constraint::Min(0),    // Очередь
+            Constraint::Length(3), // URL
+            Constraint::Length(3), // Старый пароль
+            Constraint::Length(3), // Новый пароль
+            Constraint::Length(3), // Футер
+        ])
+        .split(f.size());
 
-    let password_border_style = if state.password_entry_field == 1 {
-        theme.active_border_style()
+    // Заголовок
+    let title = Paragraph::new(format!("{} ({})", state.i18n.ts("rotation_title"), state.rotation_queue.len()))
+        .style(theme.title_style())
+        .alignment(Alignment::Center)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(theme.active_border_style())
+                .style(theme.surface_style())
+        );
+    f.render_widget(title, chunks[0]);
+
+    // Очередь помеченных записей
+    let items: Vec<ListItem> = if state.rotation_queue.is_empty() {
+        vec![ListItem::new(state.i18n.ts("rotation_empty")).style(theme.success_style())]
     } else {
-        theme.inactive_border_style()
+        state
+            .rotation_queue
+            .iter()
+            .enumerate()
+            .map(|(i, (_, name))| {
+                if i == state.rotation_index {
+                    ListItem::new(format!("» {}", name)).style(theme.selection_style())
+                } else {
+                    ListItem::new(format!("  {}", name)).style(theme.text_style())
+                }
+            })
+            .collect()
     };
-
-    let password_input = Paragraph::new(password_display.as_str())
-        .style(password_style)
+    let list = List::new(items)
         .block(
             Block::default()
                 .borders(Borders::ALL)
                 .border_type(BorderType::Rounded)
-                .border_style(password_border_style)
+                .border_style(theme.inactive_border_style())
                 .style(theme.surface_style())
-                .title(password_title),
+                .title(state.i18n.ts("rotation_queue_title"))
         );
-    f.render_widget(password_input, chunks[4]);
+    f.render_widget(list, chunks[1]);
+
+    let mask = |password: &str| {
+        if state.rotation_show_passwords {
+            password.to_string()
+        } else {
+            "*".repeat(password.chars().count())
+        }
+    };
 
-    let footer = Paragraph::new(state.i18n.ts("password_entry_footer"))
+    let url = state.rotation_url.clone().unwrap_or_default();
+    render_text_field(f, chunks[2], &url, state.i18n.ts("rotation_url"), false, theme);
+    render_text_field(f, chunks[3], &mask(&state.rotation_old_password), state.i18n.ts("rotation_old_password"), false, theme);
+    render_text_field(f, chunks[4], &mask(&state.rotation_new_password), state.i18n.ts("rotation_new_password"), true, theme);
+
+    // Футер
+    let footer = Paragraph::new(state.i18n.ts("rotation_footer"))
         .style(theme.dimmed_style())
         .block(
             Block::default()
@@ -1836,21 +7049,21 @@ fn render_password_entry_screen(f: &mut Frame, state: &TuiState, theme: &Theme)
                 .border_style(theme.inactive_border_style())
                 .style(theme.status_bar_style())
         );
-    f.render_widget(footer, chunks[6]);
+    f.render_widget(footer, chunks[5]);
 }
 
-fn render_help_screen(f: &mut Frame, state: &TuiState, theme: &Theme) {
+fn render_maintenance_screen(f: &mut Frame, state: &TuiState, theme: &Theme) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(3), // Заголовок
-            Constraint::Min(0),    // Основной контент
+            Constraint::Min(0),    // Отчет
             Constraint::Length(3), // Футер
         ])
         .split(f.size());
 
     // Заголовок
-    let title = Paragraph::new(state.i18n.ts("help_title"))
+    let title = Paragraph::new(state.i18n.ts("maintenance_title"))
         .style(theme.title_style())
         .alignment(Alignment::Center)
         .block(
@@ -1862,91 +7075,87 @@ fn render_help_screen(f: &mut Frame, state: &TuiState, theme: &Theme) {
         );
     f.render_widget(title, chunks[0]);
 
-    // Основной контент с описанием горячих клавиш
-    let help_text = vec![
-        state.i18n.ts("help_separator"),
-        state.i18n.ts("help_main_screen_title"),
-        state.i18n.ts("help_separator"),
-        "",
-        state.i18n.ts("help_main_ctrl_q"),
-        state.i18n.ts("help_main_ctrl_n"),
-        state.i18n.ts("help_main_ctrl_e"),
-        state.i18n.ts("help_main_ctrl_c"),
-        state.i18n.ts("help_main_ctrl_s"),
-        state.i18n.ts("help_main_f1"),
-        state.i18n.ts("help_main_f2"),
-        state.i18n.ts("help_main_arrows"),
-        state.i18n.ts("help_main_esc"),
-        state.i18n.ts("help_main_backspace"),
-        state.i18n.ts("help_main_type"),
-        "",
-        state.i18n.ts("help_separator"),
-        state.i18n.ts("help_master_password_title"),
-        state.i18n.ts("help_separator"),
-        "",
-        state.i18n.ts("help_master_password_enter"),
-        state.i18n.ts("help_master_password_arrows"),
-        state.i18n.ts("help_master_password_ctrl_h"),
-        state.i18n.ts("help_master_password_f1"),
-        state.i18n.ts("help_master_password_esc"),
-        state.i18n.ts("help_master_password_backspace"),
-        "",
-        state.i18n.ts("help_separator"),
-        state.i18n.ts("help_password_entry_title"),
-        state.i18n.ts("help_separator"),
-        "",
-        state.i18n.ts("help_password_entry_enter"),
-        state.i18n.ts("help_password_entry_esc"),
-        state.i18n.ts("help_password_entry_arrows"),
-        state.i18n.ts("help_password_entry_ctrl_h"),
-        state.i18n.ts("help_password_entry_ctrl_g"),
-        state.i18n.ts("help_password_entry_f1"),
-        state.i18n.ts("help_password_entry_backspace"),
-        "",
-        state.i18n.ts("help_separator"),
-        state.i18n.ts("help_password_generator_title"),
-        state.i18n.ts("help_separator"),
-        "",
-        state.i18n.ts("help_password_generator_enter"),
-        state.i18n.ts("help_password_generator_esc"),
-        state.i18n.ts("help_password_generator_arrows"),
-        state.i18n.ts("help_password_generator_space"),
-        state.i18n.ts("help_password_generator_backspace"),
-        state.i18n.ts("help_password_generator_type"),
-        state.i18n.ts("help_password_generator_f1"),
-        "",
-        state.i18n.ts("help_separator"),
-        state.i18n.ts("help_settings_title"),
-        state.i18n.ts("help_separator"),
-        "",
-        state.i18n.ts("help_settings_enter"),
-        state.i18n.ts("help_settings_esc"),
-        state.i18n.ts("help_settings_arrows"),
-        state.i18n.ts("help_settings_f1"),
-        state.i18n.ts("help_settings_backspace"),
-        "",
-        state.i18n.ts("help_separator"),
-        state.i18n.ts("help_help_title"),
-        state.i18n.ts("help_separator"),
-        "",
-        state.i18n.ts("help_help_close"),
-        "",
-    ];
+    // Отчет: что будет удалено (или удалено после подтверждения)
+    let mut items: Vec<ListItem> = Vec::new();
+    if let Some(ref error) = state.maintenance_error {
+        items.push(ListItem::new(format!("✗ {}", error)).style(theme.error_style()));
+    } else if let Some(ref report) = state.maintenance_report {
+        if report.is_empty() {
+            items.push(ListItem::new(state.i18n.ts("maintenance_nothing")).style(theme.success_style()));
+        } else {
+            items.push(
+                ListItem::new(format!("{} {}", state.i18n.ts("maintenance_orphaned"), report.orphaned_files.len()))
+                    .style(theme.text_style()),
+            );
+            for filename in &report.orphaned_files {
+                items.push(ListItem::new(format!("    {}", filename)).style(theme.dimmed_style()));
+            }
+            items.push(
+                ListItem::new(format!(
+                    "{} {} ({} {})",
+                    state.i18n.ts("maintenance_history"),
+                    report.expired_history,
+                    state.config.history_retention_days,
+                    state.i18n.ts("maintenance_days")
+                ))
+                .style(theme.text_style()),
+            );
+            let reclaimed_key = if state.maintenance_done { "maintenance_reclaimed" } else { "maintenance_reclaimable" };
+            let style = if state.maintenance_done { theme.success_style() } else { theme.warning_style() };
+            items.push(ListItem::new(""));
+            items.push(ListItem::new(format!("{} {} B", state.i18n.ts(reclaimed_key), report.reclaimed_bytes)).style(style));
+        }
+    }
+    if let Some(ref report) = state.maintenance_integrity {
+        items.push(ListItem::new(""));
+        if report.is_ok() {
+            items.push(
+                ListItem::new(format!("{} {}", state.i18n.ts("verify_ok"), report.entries_checked)).style(theme.success_style()),
+            );
+        } else {
+            items.push(
+                ListItem::new(format!("{} {}", state.i18n.ts("verify_issues"), report.issues.len())).style(theme.error_style()),
+            );
+            for issue in &report.issues {
+                items.push(
+                    ListItem::new(format!("    {} {}", state.i18n.ts(integrity_issue_key(issue)), issue.file()))
+                        .style(theme.text_style()),
+                );
+            }
+        }
+    }
+    if let Some(ref error) = state.rotate_key_error {
+        items.push(ListItem::new(""));
+        items.push(ListItem::new(format!("✗ {}", error)).style(theme.error_style()));
+    }
+    if let Some(ref password) = state.rotate_key_password {
+        items.push(ListItem::new(""));
+        items.push(ListItem::new(state.i18n.ts("rotate_key_prompt")).style(theme.warning_style()));
+        items.push(
+            ListItem::new(format!("{} {}", state.i18n.ts("rotate_key_password"), "*".repeat(password.chars().count())))
+                .style(theme.text_style()),
+        );
+    }
 
-    let help_content = Paragraph::new(help_text.join("\n"))
-        .style(theme.text_style())
+    let list = List::new(items)
         .block(
             Block::default()
                 .borders(Borders::ALL)
                 .border_type(BorderType::Rounded)
                 .border_style(theme.inactive_border_style())
                 .style(theme.surface_style())
-                .title(state.i18n.ts("help_navigation")),
         );
-    f.render_widget(help_content, chunks[1]);
+    f.render_widget(list, chunks[1]);
 
     // Футер
-    let footer = Paragraph::new(state.i18n.ts("help_footer"))
+    let footer_key = if state.rotate_key_password.is_some() {
+        "rotate_key_footer"
+    } else if state.maintenance_done {
+        "maintenance_footer_done"
+    } else {
+        "maintenance_footer"
+    };
+    let footer = Paragraph::new(state.i18n.ts(footer_key))
         .style(theme.dimmed_style())
         .block(
             Block::default()
@@ -1958,28 +7167,35 @@ fn render_help_screen(f: &mut Frame, state: &TuiState, theme: &Theme) {
     f.render_widget(footer, chunks[2]);
 }
 
-fn render_password_generator_screen(f: &mut Frame, state: &TuiState, theme: &Theme) {
+/// Ключ перевода для вида проблемы целостности
+fn integrity_issue_key(issue: &IntegrityIssue) -> &'static str {
+    match issue {
+        IntegrityIssue::UnreadableIndex { .. } => "verify_unreadable_index",
+        IntegrityIssue::UndecryptableName { .. } => "verify_undecryptable_name",
+        IntegrityIssue::MissingFile { .. } => "verify_missing_file",
+        IntegrityIssue::CorruptedFile { .. } => "verify_corrupted_file",
+        IntegrityIssue::UndecryptableFile { .. } => "verify_undecryptable_file",
+        IntegrityIssue::UnreferencedFile { .. } => "verify_unreferenced_file",
+    }
+}
+
+fn render_change_master_password_screen(f: &mut Frame, state: &TuiState, theme: &Theme) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(3), // Заголовок
-            Constraint::Length(1), // Метка для длины
-            Constraint::Length(3), // Поле ввода длины
-            Constraint::Length(1), // Метка для исключений
-            Constraint::Length(3), // Поле ввода исключений
-            Constraint::Length(1), // Пустая строка
-            Constraint::Length(1), // Метка для галочек
-            Constraint::Length(1), // Заглавные буквы
-            Constraint::Length(1), // Строчные буквы
-            Constraint::Length(1), // Цифры
-            Constraint::Length(1), // Спецсимволы
-            Constraint::Min(0),    // Остальное пространство
+            Constraint::Length(3), // Старый пароль
+            Constraint::Length(3), // Новый пароль
+            Constraint::Length(3), // Подтверждение
+            Constraint::Length(3), // Подсказка
+            Constraint::Length(3), // Параметры Argon2
+            Constraint::Min(0),    // Ошибка
             Constraint::Length(3), // Футер
         ])
         .split(f.size());
 
     // Заголовок
-    let title = Paragraph::new(state.i18n.ts("password_generator_title"))
+    let title = Paragraph::new(state.i18n.ts("change_master_title"))
         .style(theme.title_style())
         .alignment(Alignment::Center)
         .block(
@@ -1991,123 +7207,87 @@ fn render_password_generator_screen(f: &mut Frame, state: &TuiState, theme: &The
         );
     f.render_widget(title, chunks[0]);
 
-    // Метка для длины
-    let length_label = Paragraph::new(state.i18n.ts("password_generator_length_label"))
-        .style(theme.text_style())
-        .block(Block::default().borders(Borders::NONE));
-    f.render_widget(length_label, chunks[1]);
+    let fields = [
+        (&state.change_master_old, "change_master_old"),
+        (&state.change_master_new, "change_master_new"),
+        (&state.change_master_confirm, "change_master_confirm"),
+    ];
+    for (i, (value, title_key)) in fields.iter().enumerate() {
+        let masked = "*".repeat(value.chars().count());
+        render_text_field(f, chunks[1 + i], &masked, state.i18n.ts(title_key), state.change_master_field == i, theme);
+    }
+    // Подсказка не секрет и показывается как есть
+    render_text_field(f, chunks[4], &state.change_master_hint, state.i18n.ts("master_password_hint"), state.change_master_field == 3, theme);
+
+    let argon2_columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Ratio(1, 3); 3])
+        .split(chunks[5]);
+    let argon2_titles = ["change_master_argon2_memory", "change_master_argon2_iterations", "change_master_argon2_parallelism"];
+    for (i, title_key) in argon2_titles.iter().enumerate() {
+        render_text_field(f, argon2_columns[i], &state.change_master_argon2[i], state.i18n.ts(title_key), state.change_master_field == 4 + i, theme);
+    }
 
-    // Поле ввода длины
-    let length_style = if state.password_generator_selected_field == 0 {
-        theme.active_input_style()
-    } else {
-        theme.inactive_input_style()
-    };
-    let length_title = if state.password_generator_selected_field == 0 {
-        state.i18n.ts("password_generator_length_active")
-    } else {
-        state.i18n.ts("password_generator_length")
-    };
-    let length_border_style = if state.password_generator_selected_field == 0 {
-        theme.active_border_style()
-    } else {
-        theme.inactive_border_style()
-    };
-    let length_input = Paragraph::new(state.password_generator_length.as_str())
-        .style(length_style)
+    if let Some(ref error) = state.change_master_error {
+        let error = Paragraph::new(format!("✗ {}", error))
+            .style(theme.error_style())
+            .wrap(Wrap { trim: false });
+        f.render_widget(error, chunks[6]);
+    }
+
+    // Футер
+    let footer = Paragraph::new(state.i18n.ts("change_master_footer"))
+        .style(theme.dimmed_style())
         .block(
             Block::default()
                 .borders(Borders::ALL)
                 .border_type(BorderType::Rounded)
-                .border_style(length_border_style)
-                .style(theme.surface_style())
-                .title(length_title),
+                .border_style(theme.inactive_border_style())
+                .style(theme.status_bar_style())
         );
-    f.render_widget(length_input, chunks[2]);
+    f.render_widget(footer, chunks[7]);
+}
 
-    // Метка для исключений
-    let exclude_label = Paragraph::new(state.i18n.ts("password_generator_exclude_label"))
-        .style(theme.text_style())
-        .block(Block::default().borders(Borders::NONE));
-    f.render_widget(exclude_label, chunks[3]);
+fn render_backup_screen(f: &mut Frame, state: &TuiState, theme: &Theme) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Заголовок
+            Constraint::Length(3), // Файл архива
+            Constraint::Length(3), // Пароль архива
+            Constraint::Length(3), // Подтверждение
+            Constraint::Min(0),    // Подсказка / ошибка
+            Constraint::Length(3), // Футер
+        ])
+        .split(f.size());
 
-    // Поле ввода исключений
-    let exclude_style = if state.password_generator_selected_field == 1 {
-        theme.active_input_style()
-    } else {
-        theme.inactive_input_style()
-    };
-    let exclude_title = if state.password_generator_selected_field == 1 {
-        state.i18n.ts("password_generator_exclude_active")
-    } else {
-        state.i18n.ts("password_generator_exclude")
-    };
-    let exclude_border_style = if state.password_generator_selected_field == 1 {
-        theme.active_border_style()
-    } else {
-        theme.inactive_border_style()
-    };
-    let exclude_input = Paragraph::new(state.password_generator_exclude_chars.as_str())
-        .style(exclude_style)
+    // Заголовок
+    let title = Paragraph::new(state.i18n.ts("backup_title"))
+        .style(theme.title_style())
+        .alignment(Alignment::Center)
         .block(
             Block::default()
                 .borders(Borders::ALL)
                 .border_type(BorderType::Rounded)
-                .border_style(exclude_border_style)
+                .border_style(theme.active_border_style())
                 .style(theme.surface_style())
-                .title(exclude_title),
         );
-    f.render_widget(exclude_input, chunks[4]);
+    f.render_widget(title, chunks[0]);
 
-    // Метка для галочек
-    let checkboxes_label = Paragraph::new(state.i18n.ts("password_generator_charsets_label"))
-        .style(theme.text_style())
-        .block(Block::default().borders(Borders::NONE));
-    f.render_widget(checkboxes_label, chunks[6]);
+    render_text_field(f, chunks[1], &state.backup_path_input, state.i18n.ts("backup_path"), state.backup_field == 0, theme);
+    let masked = "*".repeat(state.backup_passphrase.chars().count());
+    render_text_field(f, chunks[2], &masked, state.i18n.ts("backup_passphrase"), state.backup_field == 1, theme);
+    let masked = "*".repeat(state.backup_confirm.chars().count());
+    render_text_field(f, chunks[3], &masked, state.i18n.ts("change_master_confirm"), state.backup_field == 2, theme);
 
-    // Галочки
-    let checkbox_style = |field_idx: usize| {
-        if state.password_generator_selected_field == field_idx {
-            theme.active_input_style()
-        } else {
-            theme.text_style()
-        }
+    let message = match state.backup_error {
+        Some(ref error) => Paragraph::new(format!("✗ {}", error)).style(theme.error_style()),
+        None => Paragraph::new(state.i18n.ts("backup_hint")).style(theme.dimmed_style()),
     };
-
-    // Заглавные буквы
-    let uppercase_mark = if state.password_generator_use_uppercase { "[✓]" } else { "[ ]" };
-    let uppercase_text = format!("{} {}", uppercase_mark, state.i18n.ts("password_generator_uppercase"));
-    let uppercase_para = Paragraph::new(uppercase_text.as_str())
-        .style(checkbox_style(2))
-        .block(Block::default().borders(Borders::NONE));
-    f.render_widget(uppercase_para, chunks[7]);
-
-    // Строчные буквы
-    let lowercase_mark = if state.password_generator_use_lowercase { "[✓]" } else { "[ ]" };
-    let lowercase_text = format!("{} {}", lowercase_mark, state.i18n.ts("password_generator_lowercase"));
-    let lowercase_para = Paragraph::new(lowercase_text.as_str())
-        .style(checkbox_style(3))
-        .block(Block::default().borders(Borders::NONE));
-    f.render_widget(lowercase_para, chunks[8]);
-
-    // Цифры
-    let digits_mark = if state.password_generator_use_digits { "[✓]" } else { "[ ]" };
-    let digits_text = format!("{} {}", digits_mark, state.i18n.ts("password_generator_digits"));
-    let digits_para = Paragraph::new(digits_text.as_str())
-        .style(checkbox_style(4))
-        .block(Block::default().borders(Borders::NONE));
-    f.render_widget(digits_para, chunks[9]);
-
-    // Спецсимволы
-    let special_mark = if state.password_generator_use_special { "[✓]" } else { "[ ]" };
-    let special_text = format!("{} {}", special_mark, state.i18n.ts("password_generator_special"));
-    let special_para = Paragraph::new(special_text.as_str())
-        .style(checkbox_style(5))
-        .block(Block::default().borders(Borders::NONE));
-    f.render_widget(special_para, chunks[10]);
+    f.render_widget(message.wrap(Wrap { trim: false }), chunks[4]);
 
     // Футер
-    let footer = Paragraph::new(state.i18n.ts("password_generator_footer"))
+    let footer = Paragraph::new(state.i18n.ts("backup_footer"))
         .style(theme.dimmed_style())
         .block(
             Block::default()
@@ -2116,21 +7296,217 @@ fn render_password_generator_screen(f: &mut Frame, state: &TuiState, theme: &The
                 .border_style(theme.inactive_border_style())
                 .style(theme.status_bar_style())
         );
-    f.render_widget(footer, chunks[12]);
+    f.render_widget(footer, chunks[5]);
 }
 
-fn render_theme_selection_screen(f: &mut Frame, state: &TuiState, theme: &Theme) {
+fn render_trash_screen(f: &mut Frame, state: &TuiState, theme: &Theme) {
+    let items: Vec<PickerItem> = state
+        .trash_items
+        .iter()
+        .map(|(_, name, deleted_at)| PickerItem {
+            label: name.clone(),
+            description: Some(format!("{} {}", state.i18n.ts("trash_deleted_at"), format_datetime(state, *deleted_at))),
+            checked: false,
+        })
+        .collect();
+    // Результат последнего действия показывается в заголовке списка
+    let list_title = match (&state.trash_error, &state.trash_message) {
+        (Some(error), _) => format!("✗ {}", error),
+        (None, Some(message)) => format!("✓ {}", message),
+        (None, None) if items.is_empty() => state.i18n.ts("trash_empty").to_string(),
+        (None, None) => state.i18n.ts("trash_list_title").to_string(),
+    };
+    state.trash_picker.render(
+        f,
+        state.i18n.ts("trash_title"),
+        &list_title,
+        state.i18n.ts("trash_footer"),
+        &items,
+        theme,
+    );
+
+    // Диалог подтверждения окончательного удаления поверх списка
+    if state.trash_purge_confirm {
+        let name = state
+            .trash_items
+            .get(state.trash_picker.index)
+            .map(|(_, name, _)| name.as_str())
+            .unwrap_or_default();
+        let area = centered_rect(50, 8, f.size());
+        let text = format!(
+            "{}\n\n{}\n\n{}",
+            state.i18n.ts("trash_purge_question"),
+            name,
+            state.i18n.ts("delete_confirm_hint")
+        );
+        let dialog = Paragraph::new(text)
+            .style(theme.text_style())
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: false })
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(theme.active_border_style())
+                    .style(theme.surface_style())
+                    .title(state.i18n.ts("delete_confirm_title"))
+            );
+        f.render_widget(Clear, area);
+        f.render_widget(dialog, area);
+    }
+}
+
+fn render_orphans_screen(f: &mut Frame, state: &TuiState, theme: &Theme) {
+    let items: Vec<PickerItem> = state
+        .orphans
+        .iter()
+        .map(|orphan| {
+            let description = match orphan {
+                Orphan::File { .. } => state.i18n.ts("orphans_file").to_string(),
+                Orphan::Entry { filename } => format!("{} {}", state.i18n.ts("orphans_entry"), filename),
+            };
+            PickerItem {
+                label: orphan_label(state, orphan),
+                description: Some(description),
+                checked: false,
+            }
+        })
+        .collect();
+    let list_title = match (&state.orphans_error, &state.orphans_message) {
+        (Some(error), _) => format!("✗ {}", error),
+        (None, Some(message)) => format!("✓ {}", message),
+        (None, None) if items.is_empty() => state.i18n.ts("orphans_none").to_string(),
+        (None, None) => state.i18n.ts("orphans_list_title").to_string(),
+    };
+    state.orphans_picker.render(
+        f,
+        state.i18n.ts("orphans_title"),
+        &list_title,
+        state.i18n.ts("orphans_footer"),
+        &items,
+        theme,
+    );
+
+    // Подтверждение удаления поверх списка
+    if state.orphans_delete_confirm {
+        let label = state
+            .orphans
+            .get(state.orphans_picker.index)
+            .map(|orphan| orphan_label(state, orphan))
+            .unwrap_or_default();
+        let area = centered_rect(50, 8, f.size());
+        let text = format!(
+            "{}\n\n{}\n\n{}",
+            state.i18n.ts("orphans_delete_question"),
+            label,
+            state.i18n.ts("delete_confirm_hint")
+        );
+        let dialog = Paragraph::new(text)
+            .style(theme.text_style())
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: false })
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(theme.active_border_style())
+                    .style(theme.surface_style())
+                    .title(state.i18n.ts("delete_confirm_title"))
+            );
+        f.render_widget(Clear, area);
+        f.render_widget(dialog, area);
+    }
+}
+
+fn render_restore_screen(f: &mut Frame, state: &TuiState, theme: &Theme) {
+    let labels = restore_labels(state);
+    let items: Vec<PickerItem> = labels
+        .into_iter()
+        .map(|label| PickerItem {
+            label,
+            description: None,
+            checked: false,
+        })
+        .collect();
+    let list_title = if items.is_empty() {
+        state.i18n.ts("restore_no_backups")
+    } else {
+        state.i18n.ts("restore_list_title")
+    };
+    state.restore_picker.render(
+        f,
+        state.i18n.ts("restore_title"),
+        list_title,
+        state.i18n.ts("restore_footer"),
+        &items,
+        theme,
+    );
+
+    // Диалог повторной аутентификации поверх списка
+    if state.restore_selected.is_none() {
+        if let Some(ref error) = state.restore_error {
+            let area = centered_rect(60, 3, f.size());
+            let error = Paragraph::new(format!("✗ {}", error))
+                .style(theme.error_style())
+                .block(Block::default().borders(Borders::ALL).border_type(BorderType::Rounded).style(theme.surface_style()));
+            f.render_widget(Clear, area);
+            f.render_widget(error, area);
+        }
+        return;
+    }
+
+    let area = centered_rect(60, 13, f.size());
+    f.render_widget(Clear, area);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(theme.active_border_style())
+        .style(theme.surface_style())
+        .title(state.i18n.ts("restore_confirm_title"));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2), // Предупреждение
+            Constraint::Length(3), // Мастер-пароль
+            Constraint::Length(3), // Пароль архива
+            Constraint::Min(0),    // Ошибка / подсказка
+        ])
+        .split(inner);
+
+    let warning = Paragraph::new(state.i18n.ts("restore_warning"))
+        .style(theme.warning_style())
+        .wrap(Wrap { trim: false });
+    f.render_widget(warning, rows[0]);
+
+    let masked = "*".repeat(state.restore_master.chars().count());
+    render_text_field(f, rows[1], &masked, state.i18n.ts("change_master_old"), state.restore_field == 0, theme);
+    let masked = "*".repeat(state.restore_passphrase.chars().count());
+    render_text_field(f, rows[2], &masked, state.i18n.ts("backup_passphrase"), state.restore_field == 1, theme);
+
+    let message = match state.restore_error {
+        Some(ref error) => Paragraph::new(format!("✗ {}", error)).style(theme.error_style()),
+        None => Paragraph::new(state.i18n.ts("restore_confirm_hint")).style(theme.dimmed_style()),
+    };
+    f.render_widget(message.wrap(Wrap { trim: false }), rows[3]);
+}
+
+fn render_sync_screen(f: &mut Frame, state: &TuiState, theme: &Theme) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(3), // Заголовок
-            Constraint::Min(0),    // Основной контент
+            Constraint::Length(3), // URL удаленного репозитория
+            Constraint::Length(3), // Состояние
+            Constraint::Min(0),    // Конфликты
             Constraint::Length(3), // Футер
         ])
         .split(f.size());
 
     // Заголовок
-    let title = Paragraph::new(state.i18n.ts("theme_selection_title"))
+    let title = Paragraph::new(state.i18n.ts("sync_title"))
         .style(theme.title_style())
         .alignment(Alignment::Center)
         .block(
@@ -2142,44 +7518,36 @@ fn render_theme_selection_screen(f: &mut Frame, state: &TuiState, theme: &Theme)
         );
     f.render_widget(title, chunks[0]);
 
-    // Список тем
-    let themes = vec![
-        ("Textual / Modern Web", "textual_dark", "Глубокий темный фон с яркими зелеными акцентами"),
-        ("VS Code Dark+", "vscode_style", "Классический стиль IDE с мягкими цветами"),
-        ("OpenCode / Dark Modern", "opencode_style", "Нейтральный современный вид"),
-    ];
+    render_text_field(f, chunks[1], &state.sync_remote_input, state.i18n.ts("sync_remote"), true, theme);
 
-    let items: Vec<ListItem> = themes
-        .iter()
-        .enumerate()
-        .map(|(idx, (name, theme_id, desc))| {
-            let prefix = if state.theme_selection_index == idx { ">> " } else { "   " };
-            let is_selected = state.config.theme == *theme_id;
-            let marker = if is_selected { " [✓]" } else { " [ ]" };
-            let text = format!("{}{}{}\n     {}", prefix, marker, name, desc);
-            ListItem::new(text)
-                .style(if state.theme_selection_index == idx {
-                    theme.selection_style()
-                } else {
-                    theme.text_style()
-                })
-        })
-        .collect();
+    let status = match (&state.sync_error, &state.sync_message) {
+        (Some(error), _) => Paragraph::new(format!("✗ {}", error)).style(theme.error_style()),
+        (None, Some(message)) => Paragraph::new(format!("✓ {}", message)).style(theme.success_style()),
+        (None, None) if state.config.git_sync => Paragraph::new(state.i18n.ts("sync_enabled")).style(theme.dimmed_style()),
+        (None, None) => Paragraph::new(state.i18n.ts("sync_disabled")).style(theme.dimmed_style()),
+    };
+    f.render_widget(status.wrap(Wrap { trim: false }), chunks[2]);
 
-    let list = List::new(items)
-        .block(
+    if !state.sync_conflicts.is_empty() {
+        let items: Vec<ListItem> = state
+            .sync_conflicts
+            .iter()
+            .map(|name| ListItem::new(format!("  {}", name)).style(theme.warning_style()))
+            .collect();
+        let list = List::new(items).block(
             Block::default()
                 .borders(Borders::ALL)
                 .border_type(BorderType::Rounded)
                 .border_style(theme.inactive_border_style())
                 .style(theme.surface_style())
-                .title(state.i18n.ts("theme_selection_list_title"))
+                .title(state.i18n.ts("sync_conflicts")),
         );
-
-    f.render_widget(list, chunks[1]);
+        f.render_widget(list, chunks[3]);
+    }
 
     // Футер
-    let footer = Paragraph::new(state.i18n.ts("theme_selection_footer"))
+    let footer_key = if state.sync_conflicts.is_empty() { "sync_footer" } else { "sync_conflict_footer" };
+    let footer = Paragraph::new(state.i18n.ts(footer_key))
         .style(theme.dimmed_style())
         .block(
             Block::default()
@@ -2188,21 +7556,23 @@ fn render_theme_selection_screen(f: &mut Frame, state: &TuiState, theme: &Theme)
                 .border_style(theme.inactive_border_style())
                 .style(theme.status_bar_style())
         );
-    f.render_widget(footer, chunks[2]);
+    f.render_widget(footer, chunks[4]);
 }
 
-fn render_language_selection_screen(f: &mut Frame, state: &TuiState, theme: &Theme) {
+fn render_import_screen(f: &mut Frame, state: &TuiState, theme: &Theme) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(3), // Заголовок
-            Constraint::Min(0),    // Основной контент
+            Constraint::Length(3), // Путь к файлу
+            Constraint::Min(0),    // Сопоставление столбцов
+            Constraint::Length(3), // Результат / ошибка
             Constraint::Length(3), // Футер
         ])
         .split(f.size());
 
     // Заголовок
-    let title = Paragraph::new(state.i18n.ts("language_selection_title"))
+    let title = Paragraph::new(state.i18n.ts("import_title"))
         .style(theme.title_style())
         .alignment(Alignment::Center)
         .block(
@@ -2214,26 +7584,37 @@ fn render_language_selection_screen(f: &mut Frame, state: &TuiState, theme: &The
         );
     f.render_widget(title, chunks[0]);
 
-    // Список языков
-    let languages = Language::all();
+    render_text_field(
+        f,
+        chunks[1],
+        &state.import_path_input,
+        state.i18n.ts("import_path"),
+        state.import_table.is_none(),
+        theme,
+    );
 
-    let items: Vec<ListItem> = languages
-        .iter()
-        .enumerate()
-        .map(|(idx, lang)| {
-            let prefix = if state.language_selection_index == idx { ">> " } else { "   " };
-            let is_selected = state.config.language == lang.to_code();
-            let marker = if is_selected { " [✓]" } else { " [ ]" };
-            let text = format!("{}{}{}", prefix, marker, lang.display_name());
-            ListItem::new(text)
-                .style(if state.language_selection_index == idx {
-                    theme.selection_style()
+    // Столбцы: заголовок, пример значения из первой строки, поле записи
+    let items: Vec<ListItem> = match state.import_table {
+        Some(ref table) => table
+            .headers
+            .iter()
+            .zip(state.import_mapping.iter())
+            .enumerate()
+            .map(|(i, (header, field))| {
+                let sample = table.rows.first().and_then(|row| row.get(i)).map(String::as_str).unwrap_or("");
+                // Не показываем пароль из файла
+                let sample = if *field == ImportField::Password { "********" } else { sample };
+                let text = format!("{:<20} → {:<12} ({})", header, state.i18n.ts(field.i18n_key()), sample);
+                if i == state.import_column && state.import_report.is_none() {
+                    ListItem::new(text).style(theme.selection_style())
                 } else {
-                    theme.text_style()
-                })
-        })
-        .collect();
-
+                    ListItem::new(text).style(theme.text_style())
+                }
+            })
+            .collect(),
+        None => Vec::new(),
+    };
+    let rows = state.import_table.as_ref().map(|t| t.rows.len()).unwrap_or(0);
     let list = List::new(items)
         .block(
             Block::default()
@@ -2241,13 +7622,37 @@ fn render_language_selection_screen(f: &mut Frame, state: &TuiState, theme: &The
                 .border_type(BorderType::Rounded)
                 .border_style(theme.inactive_border_style())
                 .style(theme.surface_style())
-                .title(state.i18n.ts("language_selection_list_title"))
+                .title(format!("{} ({} {})", state.i18n.ts("import_mapping_title"), rows, state.i18n.ts("import_rows")))
         );
-
-    f.render_widget(list, chunks[1]);
+    f.render_widget(list, chunks[2]);
+
+    let status = if let Some(ref error) = state.import_error {
+        Paragraph::new(format!("✗ {}", error)).style(theme.error_style())
+    } else if let Some(ref report) = state.import_report {
+        Paragraph::new(format!(
+            "{} {} | {} {}",
+            state.i18n.ts("import_created"),
+            report.created,
+            state.i18n.ts("import_skipped"),
+            report.skipped
+        ))
+        .style(theme.success_style())
+    } else if state.import_mapping_restored {
+        Paragraph::new(state.i18n.ts("import_mapping_restored")).style(theme.dimmed_style())
+    } else {
+        Paragraph::new("")
+    };
+    f.render_widget(status.block(Block::default().borders(Borders::NONE)), chunks[3]);
 
     // Футер
-    let footer = Paragraph::new(state.i18n.ts("language_selection_footer"))
+    let footer_key = if state.import_report.is_some() {
+        "import_footer_done"
+    } else if state.import_table.is_some() {
+        "import_footer_mapping"
+    } else {
+        "import_footer_path"
+    };
+    let footer = Paragraph::new(state.i18n.ts(footer_key))
         .style(theme.dimmed_style())
         .block(
             Block::default()
@@ -2256,6 +7661,5 @@ fn render_language_selection_screen(f: &mut Frame, state: &TuiState, theme: &The
                 .border_style(theme.inactive_border_style())
                 .style(theme.status_bar_style())
         );
-    f.render_widget(footer, chunks[2]);
+    f.render_widget(footer, chunks[4]);
 }
-
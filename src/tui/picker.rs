@@ -0,0 +1,188 @@
+use super::theme::Theme;
+use crossterm::event::KeyCode;
+use ratatui::layout::{Alignment, Constraint, Direction, Layout};
+use ratatui::widgets::{Block, BorderType, Borders, List, ListItem, Paragraph};
+use ratatui::Frame;
+use std::time::{Duration, Instant};
+
+/// Пауза, после которой набранные буквы начинают новый поиск
+const TYPEAHEAD_RESET: Duration = Duration::from_millis(1000);
+
+/// Элемент списка выбора
+pub struct PickerItem {
+    pub label: String,
+    pub description: Option<String>,
+    /// Текущее сохраненное значение (отмечается [✓])
+    pub checked: bool,
+}
+
+/// Результат обработки клавиши списком выбора
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PickerAction {
+    None,
+    Selected,
+    Cancelled,
+}
+
+/// Переиспользуемый список выбора (темы, языки): стрелки, Enter/Esc
+/// и переход к первому элементу, начинающемуся с набранных букв
+#[derive(Debug, Clone, Default)]
+pub struct ListPicker {
+    pub index: usize,
+    typeahead: TypeAhead,
+}
+
+impl ListPicker {
+    pub fn new(index: usize) -> Self {
+        Self {
+            index,
+            typeahead: TypeAhead::default(),
+        }
+    }
+
+    /// Обработать клавишу; `labels` - подписи элементов в порядке отображения
+    pub fn handle_key(&mut self, code: KeyCode, labels: &[&str]) -> PickerAction {
+        let len = labels.len();
+        if len == 0 {
+            return match code {
+                KeyCode::Esc => PickerAction::Cancelled,
+                _ => PickerAction::None,
+            };
+        }
+
+        match code {
+            KeyCode::Esc => PickerAction::Cancelled,
+            KeyCode::Enter => PickerAction::Selected,
+            KeyCode::Up => {
+                self.index = if self.index > 0 { self.index - 1 } else { len - 1 };
+                PickerAction::None
+            }
+            KeyCode::Down => {
+                self.index = (self.index + 1) % len;
+                PickerAction::None
+            }
+            KeyCode::Home => {
+                self.index = 0;
+                PickerAction::None
+            }
+            KeyCode::End => {
+                self.index = len - 1;
+                PickerAction::None
+            }
+            KeyCode::Char(c) => {
+                if let Some(index) = self.typeahead.push(c, labels.iter().copied()) {
+                    self.index = index;
+                }
+                PickerAction::None
+            }
+            _ => PickerAction::None,
+        }
+    }
+
+    /// Отрисовать экран выбора: заголовок, список и футер
+    pub fn render(
+        &self,
+        f: &mut Frame,
+        title: &str,
+        list_title: &str,
+        footer: &str,
+        items: &[PickerItem],
+        theme: &Theme,
+    ) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Заголовок
+                Constraint::Min(0),    // Основной контент
+                Constraint::Length(3), // Футер
+            ])
+            .split(f.size());
+
+        // Заголовок
+        let title = Paragraph::new(title)
+            .style(theme.title_style())
+            .alignment(Alignment::Center)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(theme.active_border_style())
+                    .style(theme.surface_style())
+            );
+        f.render_widget(title, chunks[0]);
+
+        let list_items: Vec<ListItem> = items
+            .iter()
+            .enumerate()
+            .map(|(idx, item)| {
+                let prefix = if self.index == idx { ">> " } else { "   " };
+                let marker = if item.checked { " [✓]" } else { " [ ]" };
+                let text = match item.description {
+                    Some(ref description) => format!("{}{}{}\n     {}", prefix, marker, item.label, description),
+                    None => format!("{}{}{}", prefix, marker, item.label),
+                };
+                ListItem::new(text)
+                    .style(if self.index == idx {
+                        theme.selection_style()
+                    } else {
+                        theme.text_style()
+                    })
+            })
+            .collect();
+
+        let list = List::new(list_items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(theme.inactive_border_style())
+                    .style(theme.surface_style())
+                    .title(list_title)
+            );
+        f.render_widget(list, chunks[1]);
+
+        // Футер
+        let footer = Paragraph::new(footer)
+            .style(theme.dimmed_style())
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(theme.inactive_border_style())
+                    .style(theme.status_bar_style())
+            );
+        f.render_widget(footer, chunks[2]);
+    }
+}
+
+/// Буфер набранных букв для перехода по списку
+#[derive(Debug, Clone, Default)]
+pub struct TypeAhead {
+    buffer: String,
+    last_key: Option<Instant>,
+}
+
+impl TypeAhead {
+    /// Добавить букву и найти первый подходящий элемент. Если с накопленным
+    /// префиксом ничего не найдено, ищем заново только по новой букве.
+    pub fn push<'a>(&mut self, c: char, labels: impl Iterator<Item = &'a str> + Clone) -> Option<usize> {
+        if self.last_key.is_none_or(|t| t.elapsed() > TYPEAHEAD_RESET) {
+            self.buffer.clear();
+        }
+        self.last_key = Some(Instant::now());
+        self.buffer.extend(c.to_lowercase());
+
+        if let Some(index) = find_prefix(labels.clone(), &self.buffer) {
+            return Some(index);
+        }
+        self.buffer = c.to_lowercase().collect();
+        find_prefix(labels, &self.buffer)
+    }
+}
+
+/// Индекс первого элемента, начинающегося с `prefix` (без учета регистра)
+pub fn find_prefix<'a>(labels: impl Iterator<Item = &'a str>, prefix: &str) -> Option<usize> {
+    labels
+        .into_iter()
+        .position(|label| label.to_lowercase().starts_with(prefix))
+}
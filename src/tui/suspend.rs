@@ -0,0 +1,72 @@
+use crate::errors::{RpmError, RpmResult};
+use crossterm::event::{DisableMouseCapture, EnableMouseCapture};
+use crossterm::execute;
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
+use ratatui::backend::CrosstermBackend;
+use ratatui::Terminal;
+use std::io::Stdout;
+use std::process::Command;
+
+/// Команда в строке поиска главного экрана, открывающая оболочку
+pub const SHELL_COMMAND: &str = ":sh";
+
+/// Вернуть терминал в обычный режим перед передачей его другой программе
+pub fn leave(terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> RpmResult<()> {
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen, DisableMouseCapture)?;
+    terminal.show_cursor()?;
+    Ok(())
+}
+
+/// Снова занять терминал под TUI; экран перерисовывается целиком
+pub fn enter(terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> RpmResult<()> {
+    enable_raw_mode()?;
+    execute!(terminal.backend_mut(), EnterAlternateScreen, EnableMouseCapture)?;
+    terminal.clear()?;
+    Ok(())
+}
+
+/// Ctrl+Z: в raw-режиме терминал не присылает SIGTSTP, поэтому останавливаемся сами,
+/// предварительно вернув терминал оболочке; после `fg` выполнение продолжается здесь.
+/// Там, где нет управления заданиями, вместо остановки открывается оболочка
+pub fn suspend(terminal: &mut Terminal<CrosstermBackend<Stdout>>, shell_banner: &str) -> RpmResult<()> {
+    #[cfg(unix)]
+    {
+        let _ = shell_banner;
+        leave(terminal)?;
+        // SAFETY: raise только отправляет сигнал текущему процессу
+        unsafe {
+            libc::raise(libc::SIGTSTP);
+        }
+        enter(terminal)
+    }
+    #[cfg(not(unix))]
+    {
+        run_shell(terminal, shell_banner)
+    }
+}
+
+/// Открыть оболочку пользователя ($SHELL, в Windows %COMSPEC%) и вернуться в TUI после ее завершения
+pub fn run_shell(terminal: &mut Terminal<CrosstermBackend<Stdout>>, banner: &str) -> RpmResult<()> {
+    let shell = std::env::var("SHELL")
+        .or_else(|_| std::env::var("COMSPEC"))
+        .unwrap_or_else(|_| "/bin/sh".to_string());
+    leave(terminal)?;
+    println!("{}", banner);
+    let status = Command::new(&shell).status();
+    enter(terminal)?;
+    status
+        .map(|_| ())
+        .map_err(|e| RpmError::Tui(format!("failed to start {}: {}", shell, e)))
+}
+
+/// Подключиться по SSH в этом же терминале и вернуться в TUI после завершения сеанса
+pub fn run_ssh(terminal: &mut Terminal<CrosstermBackend<Stdout>>, args: &[String], banner: &str) -> RpmResult<()> {
+    leave(terminal)?;
+    println!("{} ssh {}", banner, args.join(" "));
+    let status = Command::new("ssh").args(args).status();
+    enter(terminal)?;
+    status
+        .map(|_| ())
+        .map_err(|e| RpmError::Tui(format!("failed to start ssh: {}", e)))
+}
@@ -1,6 +1,7 @@
 use ratatui::style::{Color, Modifier, Style};
 
 /// Централизованная система тем для TUI
+#[allow(dead_code)]
 pub struct Theme {
     // Основные цвета
     pub bg: Color,
@@ -179,6 +180,30 @@ impl Theme {
     }
 }
 
+impl Theme {
+    /// Палитра из 16 цветов ANSI для консолей без 24-битного цвета (cmd.exe, консоль Linux).
+    /// Используется вместо выбранной темы в режиме совместимости.
+    pub fn ansi16() -> Self {
+        Self {
+            bg: Color::Black,
+            surface: Color::Black,
+            fg: Color::Gray,
+            dimmed: Color::DarkGray,
+            title: Color::White,
+            accent: Color::LightGreen,
+            accent_secondary: Color::LightMagenta,
+            border_inactive: Color::DarkGray,
+            border_active: Color::LightGreen,
+            selection_bg: Color::Blue,
+            selection_fg: Color::White,
+            status_bar: Color::Black,
+            success: Color::LightGreen,
+            warning: Color::Yellow,
+            error: Color::LightRed,
+        }
+    }
+}
+
 /// Получить тему по имени
 pub fn get_theme_by_name(name: &str) -> Theme {
     match name {
@@ -189,6 +214,7 @@ pub fn get_theme_by_name(name: &str) -> Theme {
 }
 
 /// Глобальная тема по умолчанию (можно изменить на другую)
+#[allow(dead_code)]
 pub fn default_theme() -> Theme {
     Theme::textual_dark()
 }
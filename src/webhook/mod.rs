@@ -0,0 +1,132 @@
+use crate::config::Config;
+use crate::errors::{RpmError, RpmResult};
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::path::Path;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use tracing::warn;
+
+/// Header with the HMAC-SHA256 of the body, `sha256=<hex>`, when `webhook_secret` is set
+pub const SIGNATURE_HEADER: &str = "X-RPM-Signature";
+
+/// Header with the event name, so a receiver can route without parsing the body
+pub const EVENT_HEADER: &str = "X-RPM-Event";
+
+/// Names of every event, as used in `webhook_events`
+pub const EVENTS: [&str; 3] = ["client_unlock", "export", "breach"];
+
+/// Something a user may want to be alerted about. Payloads never carry secrets
+/// or entry names: they go to third-party services such as Slack or ntfy
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WebhookEvent {
+    /// A paired extension unlocked the vault through the API for the first time
+    ClientUnlock { client: String },
+    /// An export snapshot was written
+    Export { file: String },
+    /// The audit found passwords in the breach dataset
+    Breach { entries: usize },
+}
+
+impl WebhookEvent {
+    /// Export of the snapshot at `path`; only its file name is sent
+    pub fn export(path: &Path) -> Self {
+        Self::Export {
+            file: path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default(),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::ClientUnlock { .. } => "client_unlock",
+            Self::Export { .. } => "export",
+            Self::Breach { .. } => "breach",
+        }
+    }
+
+    fn details(&self) -> serde_json::Value {
+        match self {
+            Self::ClientUnlock { client } => serde_json::json!({ "client": client }),
+            Self::Export { file } => serde_json::json!({ "file": file }),
+            Self::Breach { entries } => serde_json::json!({ "entries": entries }),
+        }
+    }
+}
+
+/// `sha256=<hex>` HMAC of `body`, as sent in `X-RPM-Signature`
+pub fn sign(secret: &str, body: &str) -> String {
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(body.as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Webhook configured in `webhook_url`; does nothing while it is not set
+#[derive(Debug, Clone, Default)]
+pub struct Webhooks {
+    url: Option<String>,
+    secret: Option<String>,
+    events: Vec<String>,
+}
+
+impl Webhooks {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            url: config.webhook_url.as_deref().map(str::trim).filter(|url| !url.is_empty()).map(String::from),
+            secret: config.webhook_secret.clone().filter(|secret| !secret.is_empty()),
+            events: config.webhook_events.clone(),
+        }
+    }
+
+    /// Whether `event` is sent at all
+    pub fn wants(&self, event: &WebhookEvent) -> bool {
+        self.url.is_some() && self.events.iter().any(|name| name == event.name())
+    }
+
+    /// JSON body for `event` and its signature, if a secret is configured
+    pub fn payload(&self, event: &WebhookEvent, at: DateTime<Utc>) -> (String, Option<String>) {
+        let body = serde_json::json!({
+            "event": event.name(),
+            "timestamp": at,
+            "details": event.details(),
+        })
+        .to_string();
+        let signature = self.secret.as_deref().map(|secret| sign(secret, &body));
+        (body, signature)
+    }
+
+    /// POST `event` to the webhook and wait for the answer
+    pub fn deliver(&self, event: &WebhookEvent) -> RpmResult<()> {
+        let Some(ref url) = self.url else {
+            return Ok(());
+        };
+        let (body, signature) = self.payload(event, Utc::now());
+        let mut request = ureq::AgentBuilder::new()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .post(url)
+            .set("Content-Type", "application/json")
+            .set(EVENT_HEADER, event.name());
+        if let Some(ref signature) = signature {
+            request = request.set(SIGNATURE_HEADER, signature);
+        }
+        request
+            .send_string(&body)
+            .map_err(|e| RpmError::Io(std::io::Error::other(format!("Webhook request failed: {}", e))))?;
+        Ok(())
+    }
+
+    /// Send `event` in the background if it is configured; failures are only logged.
+    /// Short-lived callers (the command line mode) join the handle before exiting
+    pub fn fire(&self, event: WebhookEvent) -> Option<JoinHandle<()>> {
+        if !self.wants(&event) {
+            return None;
+        }
+        let webhooks = self.clone();
+        Some(std::thread::spawn(move || {
+            if let Err(e) = webhooks.deliver(&event) {
+                warn!("Webhook for {} not delivered: {}", event.name(), e);
+            }
+        }))
+    }
+}
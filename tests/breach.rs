@@ -0,0 +1,93 @@
+use rpm::breach::{find_suffix, sha1_hex, OfflineHibpDataset};
+use std::path::PathBuf;
+
+fn temp_file(name: &str, contents: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("rpm-breach-{}-{}.txt", name, std::process::id()));
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+/// Dataset in the ordered-by-hash format: (hash, count) sorted by hash
+fn dataset(size: u64) -> Vec<(String, u64)> {
+    let mut lines: Vec<(String, u64)> = (0..size).map(|i| (sha1_hex(&format!("password{}", i)), i + 1)).collect();
+    lines.sort();
+    lines
+}
+
+fn write_dataset(name: &str, lines: &[(String, u64)], newline: &str) -> PathBuf {
+    let contents: String = lines.iter().map(|(hash, count)| format!("{}:{}{}", hash, count, newline)).collect();
+    temp_file(name, &contents)
+}
+
+#[test]
+fn every_line_of_the_dataset_is_found() {
+    let lines = dataset(300);
+    for (name, newline) in [("lf", "\n"), ("crlf", "\r\n")] {
+        let path = write_dataset(name, &lines, newline);
+        let dataset = OfflineHibpDataset::open(&path).unwrap();
+        // First and last lines included
+        for (hash, count) in &lines {
+            assert_eq!(dataset.lookup_hash(hash).unwrap(), Some(*count), "{} {}", name, hash);
+        }
+        assert_eq!(dataset.lookup_hash(&lines[7].0.to_ascii_lowercase()).unwrap(), Some(lines[7].1));
+        assert_eq!(dataset.check_password("password42").unwrap(), Some(43));
+        let _ = std::fs::remove_file(&path);
+    }
+}
+
+#[test]
+fn missing_hashes_are_not_found() {
+    let lines: Vec<(String, u64)> = dataset(50).into_iter().filter(|(hash, _)| !hash.starts_with('0')).collect();
+    let path = write_dataset("missing", &lines, "\r\n");
+    let dataset = OfflineHibpDataset::open(&path).unwrap();
+
+    // Before the first line, after the last and next to an existing one
+    assert_eq!(dataset.lookup_hash(&"0".repeat(40)).unwrap(), None);
+    assert_eq!(dataset.lookup_hash(&"F".repeat(40)).unwrap(), None);
+    let mut between = lines[20].0.clone();
+    between.replace_range(39.., if between.ends_with('0') { "1" } else { "0" });
+    assert!(lines.iter().all(|(hash, _)| *hash != between));
+    assert_eq!(dataset.lookup_hash(&between).unwrap(), None);
+    assert_eq!(dataset.check_password("not in the dataset").unwrap(), None);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn a_single_line_dataset_works_with_and_without_a_final_newline() {
+    let hash = sha1_hex("hunter2");
+    for (name, contents) in [("single", format!("{}:17", hash)), ("single-nl", format!("{}:17\n", hash))] {
+        let path = temp_file(name, &contents);
+        let dataset = OfflineHibpDataset::open(&path).unwrap();
+        assert_eq!(dataset.check_password("hunter2").unwrap(), Some(17), "{}", name);
+        assert_eq!(dataset.lookup_hash(&"0".repeat(40)).unwrap(), None, "{}", name);
+        assert_eq!(dataset.lookup_hash(&"F".repeat(40)).unwrap(), None, "{}", name);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    let path = temp_file("empty", "");
+    assert_eq!(OfflineHibpDataset::open(&path).unwrap().check_password("hunter2").unwrap(), None);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn a_file_in_another_format_is_reported() {
+    let path = temp_file("ntlm", "8846F7EAEE8FB117AD06BDD830B7586C:3\n");
+    let error = OfflineHibpDataset::open(&path).unwrap().check_password("hunter2").unwrap_err();
+    assert!(error.to_string().contains("ordered-by-hash"), "{}", error);
+    let _ = std::fs::remove_file(&path);
+    assert!(OfflineHibpDataset::open(&std::env::temp_dir()).is_err());
+}
+
+#[test]
+fn range_responses_ignore_padding_lines() {
+    let body = "0018A45C4D1DEF81644B54AB7F969B88D65:10\r\n\
+                00D4F6E8FA6EECAD2A3AA415EEC418D38EC:0\r\n\
+                011053FD0102E94D6AE2F8B83D76FAF94F6:1";
+    assert_eq!(find_suffix(body, "0018A45C4D1DEF81644B54AB7F969B88D65"), Some(10));
+    assert_eq!(find_suffix(body, "0018a45c4d1def81644b54ab7f969b88d65"), Some(10));
+    assert_eq!(find_suffix(body, "011053FD0102E94D6AE2F8B83D76FAF94F6"), Some(1));
+    // Padding suffixes come with a count of 0
+    assert_eq!(find_suffix(body, "00D4F6E8FA6EECAD2A3AA415EEC418D38EC"), None);
+    assert_eq!(find_suffix(body, "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF"), None);
+    assert_eq!(find_suffix("", "0018A45C4D1DEF81644B54AB7F969B88D65"), None);
+}
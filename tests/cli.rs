@@ -0,0 +1,190 @@
+//! Output and prompts of the command line mode. Tests run without a terminal,
+//! so output is never colored here.
+
+use clap::error::ErrorKind;
+use clap::Parser;
+use rpm::cli::{find_entry, generator_options, Cli, Command, EntryJson, GenerateArgs, PasswordSource, Term, Tone, ASKPASS_ENV};
+use rpm::config::Config;
+use rpm::crypto::CryptoManager;
+use rpm::storage::PasswordStorage;
+use rpm::models::PasswordPolicy;
+use rpm::tui::generate_from_policy;
+
+fn parse(args: &[&str]) -> Result<Cli, clap::Error> {
+    Cli::try_parse_from(std::iter::once("rpm").chain(args.iter().copied()))
+}
+
+fn generate_args(options: &[&str]) -> GenerateArgs {
+    match parse(&[&["generate"], options].concat()).unwrap().command {
+        Some(Command::Generate(options)) => options,
+        command => panic!("{:?}", command),
+    }
+}
+
+#[test]
+fn output_is_plain_without_a_terminal() {
+    let term = Term::new(&Config::default(), false);
+    assert_eq!(term.paint(Tone::Success, "Pushed"), "Pushed");
+}
+
+#[test]
+fn entries_are_found_by_exact_then_unique_partial_name() {
+    let names: Vec<(String, String)> = ["GitHub", "github-work", "Gmail", "bank"]
+        .iter()
+        .enumerate()
+        .map(|(index, name)| (format!("{}.pwd", index), name.to_string()))
+        .collect();
+    assert_eq!(find_entry(&names, "GitHub").unwrap().0, "0.pwd");
+    assert_eq!(find_entry(&names, "github").unwrap().0, "0.pwd");
+    assert_eq!(find_entry(&names, "WORK").unwrap().0, "1.pwd");
+    assert_eq!(find_entry(&names, "ban").unwrap().0, "3.pwd");
+
+    // Several candidates or none: nothing is picked for the user
+    let error = find_entry(&names, "g").unwrap_err().to_string();
+    assert!(error.contains("GitHub, Gmail, github-work"), "{}", error);
+    assert!(find_entry(&names, "twitter").is_err());
+}
+
+#[test]
+fn generate_options_build_the_generator_policy() {
+    let mut config = Config::default();
+    let (policy, count) = generator_options(&generate_args(&["--length", "24", "--no-symbols", "--exclude", "0O"]), &config).unwrap();
+    assert_eq!((policy.length, count), (24, 1));
+    assert!(!policy.use_special && policy.use_digits);
+    let password = generate_from_policy(&policy).unwrap();
+    assert_eq!(password.chars().count(), 24);
+    assert!(password.chars().all(|c| c.is_ascii_alphanumeric() && c != '0' && c != 'O'), "{}", password);
+
+    // Symbols are on unless turned off; a passphrase counts words
+    assert!(generator_options(&generate_args(&[]), &config).unwrap().0.use_special);
+    let (policy, _) = generator_options(&generate_args(&["--passphrase"]), &config).unwrap();
+    assert_eq!(policy.length, 6);
+    assert_eq!(generate_from_policy(&policy).unwrap().split('-').count(), 6);
+
+    // The other options adjust a preset
+    config.generator_presets.insert("pin".to_string(), PasswordPolicy {
+        length: 4,
+        use_uppercase: false,
+        use_lowercase: false,
+        ..PasswordPolicy::default()
+    });
+    let (policy, count) = generator_options(&generate_args(&["--preset", "pin", "--length", "6", "-n", "3"]), &config).unwrap();
+    assert_eq!((policy.length, count, policy.use_lowercase), (6, 3, false));
+
+    assert!(parse(&["generate", "--length"]).is_err());
+    assert!(parse(&["generate", "--length", "many"]).is_err());
+    assert!(parse(&["generate", "--symbols", "--no-symbols"]).is_err());
+    assert!(parse(&["generate", "--shiny"]).is_err());
+    assert!(generator_options(&generate_args(&["--preset", "missing"]), &config).is_err());
+}
+
+#[test]
+fn json_output_leaves_secrets_out_unless_asked() {
+    let dir = std::env::temp_dir().join(format!("rpm-cli-json-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let config = Config {
+        passwords_directory: Some(dir.clone()),
+        ..Config::default()
+    };
+    let storage = PasswordStorage::new(&config, CryptoManager::new().unwrap());
+    let key = vec![7; 32];
+    let id = storage.add_entry("github", &key).unwrap();
+    storage.update_password_file(&id, "hunter2", &key).unwrap();
+    let meta = storage.entry_meta(&id, &key).unwrap().unwrap();
+    assert_eq!(meta.title, "github");
+    assert!(storage.entry_meta("missing.pwd", &key).unwrap().is_none());
+
+    let hidden = serde_json::to_value(EntryJson { meta: meta.clone(), password: None, notes: None }).unwrap();
+    assert_eq!(hidden["id"], id.as_str());
+    assert_eq!(hidden["title"], "github");
+    assert!(hidden.get("password").is_none() && hidden.get("notes").is_none());
+
+    let shown = EntryJson { meta, password: Some("hunter2".to_string()), notes: None };
+    assert_eq!(serde_json::to_value(&shown).unwrap()["password"], "hunter2");
+    assert!(Term::new(&config, false).with_json(true).json());
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn password_source_options_go_before_or_after_the_command() {
+    let source = |args: &[&str]| parse(args).unwrap().global.password_source();
+
+    let cli = parse(&["get", "--password-stdin", "github"]).unwrap();
+    assert_eq!(cli.global.password_source(), Some(PasswordSource::Stdin));
+    assert!(matches!(cli.command, Some(Command::Get { ref entry, clip: false, .. }) if entry == "github"));
+    assert_eq!(source(&["--password-fd", "3", "list"]), Some(PasswordSource::Fd(3)));
+    assert_eq!(source(&["list", "--password-fd=4"]), Some(PasswordSource::Fd(4)));
+    assert_eq!(source(&["list"]), None);
+
+    assert!(parse(&["list", "--password-fd"]).is_err());
+    assert!(parse(&["list", "--password-fd", "three"]).is_err());
+    assert!(parse(&["--password-stdin", "--password-fd", "3", "list"]).is_err());
+}
+
+#[test]
+fn every_subcommand_has_its_own_help_and_options() {
+    for args in [&["get", "--help"][..], &["import", "--help"], &["import", "kdbx", "--help"], &["sync", "webdav", "--help"]] {
+        let error = parse(args).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::DisplayHelp, "{:?}", args);
+    }
+    let help = parse(&["rotate-key", "--help"]).unwrap_err().to_string();
+    assert!(help.contains("Re-encrypt the vault") && help.contains("--password-stdin"), "{}", help);
+
+    // Without a command the TUI starts
+    assert!(parse(&[]).unwrap().command.is_none());
+    assert!(parse(&["--copy", "gmail"]).unwrap().copy.is_some());
+    assert!(parse(&["get", "-c", "--json", "--show-password", "github"]).is_ok());
+    assert!(parse(&["list", "--show-password"]).is_err(), "--show-password needs --json");
+    assert!(parse(&["export", "--json"]).is_err());
+    assert!(parse(&["get"]).is_err());
+    assert!(parse(&["unlock"]).is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn master_password_is_read_from_a_file_descriptor() {
+    use std::os::unix::io::IntoRawFd;
+
+    let path = std::env::temp_dir().join(format!("rpm-cli-password-fd-{}", std::process::id()));
+    std::fs::write(&path, "hunter2\r\nsecond line\n").unwrap();
+    let fd = std::fs::File::open(&path).unwrap().into_raw_fd();
+    let term = Term::new(&Config::default(), false).with_password_source(Some(PasswordSource::Fd(fd)));
+    assert_eq!(term.read_master_password().unwrap(), "hunter2");
+
+    std::fs::write(&path, "").unwrap();
+    let fd = std::fs::File::open(&path).unwrap().into_raw_fd();
+    assert!(PasswordSource::Fd(fd).read_line().is_err());
+    assert!(PasswordSource::Fd(-1).read_line().is_err());
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn yes_answers_confirmations_without_reading_stdin() {
+    let term = Term::new(&Config::default(), true);
+    assert!(term.confirm("Restore it?").unwrap());
+}
+
+/// Both cases share one test: they set the same environment variable
+#[cfg(unix)]
+#[test]
+fn askpass_helper_supplies_the_password() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = std::env::temp_dir().join(format!("rpm-cli-askpass-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let helper = dir.join("askpass");
+    let term = Term::new(&Config::default(), false);
+
+    // The prompt without its colon is the helper's argument
+    std::fs::write(&helper, "#!/bin/sh\n[ \"$1\" = \"Master password\" ] && printf 'hunter2\\nignored\\n'\n").unwrap();
+    std::fs::set_permissions(&helper, std::fs::Permissions::from_mode(0o755)).unwrap();
+    std::env::set_var(ASKPASS_ENV, &helper);
+    assert_eq!(term.read_password("Master password:").unwrap(), "hunter2");
+
+    // Closing the helper's dialog cancels
+    std::fs::write(&helper, "#!/bin/sh\nexit 1\n").unwrap();
+    assert!(term.read_password("Master password:").is_err());
+
+    std::env::remove_var(ASKPASS_ENV);
+    let _ = std::fs::remove_dir_all(&dir);
+}
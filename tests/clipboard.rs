@@ -0,0 +1,13 @@
+use rpm::tui::clipboard::CopiedText;
+
+#[test]
+fn the_clipboard_is_cleared_only_while_it_holds_what_was_copied() {
+    let copied = CopiedText::new("hunter2");
+    assert!(copied.matches(Ok("hunter2".to_string())));
+    // The user copied something else since
+    assert!(!copied.matches(Ok("hunter3".to_string())));
+    assert!(!copied.matches(Ok(String::new())));
+    assert!(!copied.matches(Err(arboard::Error::ContentNotAvailable)));
+    // Checking does not use the copy up: a later timeout compares again
+    assert!(copied.matches(Ok("hunter2".to_string())));
+}
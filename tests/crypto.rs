@@ -0,0 +1,381 @@
+use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+use base64::Engine;
+use rpm::config::{Config, DirectoryConfig};
+use rpm::crypto::{memory, Cipher, CryptoManager, SecureKey};
+use rpm::crypto::guard::{check_rng, check_samples, draw_nonce, NonceGuard, NONCE_ATTEMPTS};
+use rpm::crypto::key_derivation::{
+    calibrate, derive_key, Argon2id, Kdf, KdfParams, Pbkdf2Sha256, Scrypt, MAX_ARGON2_ITERATIONS,
+    MAX_ARGON2_MEMORY_KIB, MAX_ARGON2_PARALLELISM, MAX_PBKDF2_ITERATIONS, MAX_SCRYPT_PARALLELISM,
+};
+use rpm::errors::RpmError;
+use rpm::keychain::WrappedKey;
+use rpm::models::{AuditAction, EntryDetails, UiState};
+use rpm::storage::PasswordStorage;
+use std::time::{Duration, Instant};
+
+fn temp_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("rpm-crypto-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn argon2_parameters_are_stored_per_vault() {
+    let dir = temp_dir("argon2");
+    let salt = [9u8; 32];
+
+    // Vaults without a [kdf] section keep deriving the key they always had
+    let mut dir_config = DirectoryConfig::default();
+    dir_config.save(&dir).unwrap();
+    assert!(!std::fs::read_to_string(dir.join(".rpm_config")).unwrap().contains("[kdf]"));
+    let loaded = DirectoryConfig::load(&dir).unwrap();
+    assert_eq!(loaded.kdf, KdfParams::default());
+    assert_eq!(loaded.kdf.kdf().derive_key("master", &salt).unwrap(), derive_key("master", Some(&salt)).unwrap());
+
+    let tuned = Argon2id { memory_kib: 8 * 1024, iterations: 3, parallelism: 2 };
+    dir_config.kdf = KdfParams::Argon2id(tuned);
+    dir_config.save(&dir).unwrap();
+    let loaded = DirectoryConfig::load(&dir).unwrap();
+    assert_eq!(loaded.kdf.argon2(), tuned);
+    let key = loaded.kdf.kdf().derive_key("master", &salt).unwrap();
+    assert_eq!(key.len(), 32);
+    assert_ne!(key, derive_key("master", Some(&salt)).unwrap());
+
+    // Parameters left out of the section fall back to the defaults
+    std::fs::write(dir.join(".rpm_config"), "[kdf]\nalgorithm = \"argon2id\"\niterations = 4\n").unwrap();
+    let loaded = DirectoryConfig::load(&dir).unwrap();
+    assert_eq!(loaded.kdf.argon2(), Argon2id { iterations: 4, ..Argon2id::default() });
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn unusable_argon2_parameters_are_rejected() {
+    assert!(Argon2id::default().params().is_ok());
+    assert!(Argon2id { iterations: 0, ..Argon2id::default() }.params().is_err());
+    assert!(Argon2id { parallelism: 0, ..Argon2id::default() }.params().is_err());
+    assert!(Argon2id { memory_kib: MAX_ARGON2_MEMORY_KIB + 1, ..Argon2id::default() }.params().is_err());
+    assert!(Argon2id { iterations: MAX_ARGON2_ITERATIONS, ..Argon2id::default() }.params().is_ok());
+    assert!(Argon2id { iterations: MAX_ARGON2_ITERATIONS + 1, ..Argon2id::default() }.params().is_err());
+    assert!(Argon2id { parallelism: MAX_ARGON2_PARALLELISM, ..Argon2id::default() }.params().is_ok());
+    assert!(Argon2id { parallelism: MAX_ARGON2_PARALLELISM + 1, ..Argon2id::default() }.params().is_err());
+    assert!(Argon2id { iterations: u32::MAX, ..Argon2id::default() }.derive_key("master", &[1; 32]).is_err());
+    assert!(Argon2id { iterations: 0, ..Argon2id::default() }.derive_key("master", &[1; 32]).is_err());
+}
+
+#[test]
+fn unusable_scrypt_and_pbkdf2_parameters_are_rejected() {
+    assert!(Scrypt { log_n: 15, r: 8, p: 1 }.validate().is_ok());
+    // 128 * r * N bytes: 2^22 * 8 * 128 is 4 GiB, one step more is too much
+    assert!(Scrypt { log_n: 22, r: 8, p: 1 }.validate().is_ok());
+    assert!(Scrypt { log_n: 23, r: 8, p: 1 }.validate().is_err());
+    assert!(Scrypt { log_n: 63, r: 8, p: 1 }.validate().is_err());
+    assert!(Scrypt { log_n: 255, r: u32::MAX, p: 1 }.validate().is_err());
+    assert!(Scrypt { log_n: 15, r: 8, p: MAX_SCRYPT_PARALLELISM + 1 }.validate().is_err());
+    assert!(Scrypt { log_n: 40, r: 8, p: 1 }.derive_key("master", &[1; 32]).is_err());
+
+    assert!(Pbkdf2Sha256 { iterations: 600_000 }.validate().is_ok());
+    assert!(Pbkdf2Sha256 { iterations: 0 }.validate().is_err());
+    assert!(Pbkdf2Sha256 { iterations: MAX_PBKDF2_ITERATIONS + 1 }.validate().is_err());
+    // Rejected before any work is done
+    assert!(Pbkdf2Sha256 { iterations: u32::MAX }.derive_key("master", &[1; 32]).is_err());
+
+    // A tampered directory config fails to unlock instead of hanging
+    let dir_config: DirectoryConfig = toml::from_str("[kdf]\nalgorithm = \"pbkdf2_sha256\"\niterations = 4294967295\n").unwrap();
+    assert!(dir_config.kdf.kdf().derive_key("master", &[1; 32]).is_err());
+}
+
+#[test]
+fn calibration_never_goes_below_the_defaults() {
+    // A target this machine can't beat keeps the defaults
+    assert_eq!(calibrate(Duration::from_millis(1)).unwrap(), Argon2id::default());
+
+    let started = Instant::now();
+    derive_key("master", Some(&[1; 32])).unwrap();
+    let default_time = started.elapsed();
+    let argon2 = calibrate(default_time * 3).unwrap();
+    assert!(argon2.params().is_ok());
+    assert!(argon2.memory_kib >= Argon2id::default().memory_kib);
+    assert!(argon2.iterations >= Argon2id::default().iterations);
+    assert_ne!(argon2, Argon2id::default());
+    assert_eq!(argon2.memory_kib % 1024, 0);
+}
+
+#[test]
+fn the_stored_hash_carries_the_vault_parameters() {
+    let crypto = CryptoManager::new().unwrap();
+    let argon2 = Argon2id { memory_kib: 8 * 1024, iterations: 3, parallelism: 2 };
+    let hash = crypto.hash_password("master", &argon2).unwrap();
+    assert!(hash.starts_with("$argon2id$v=19$m=8192,t=3,p=2$"));
+    assert!(crypto.verify_password("master", &hash).unwrap());
+    assert!(!crypto.verify_password("guess", &hash).unwrap());
+
+    // A tampered hash can't make checking it exhaust memory
+    let huge = hash.replace("m=8192", &format!("m={}", MAX_ARGON2_MEMORY_KIB + 1));
+    assert!(crypto.verify_password("master", &huge).is_err());
+    let slow = hash.replace("t=3", &format!("t={}", MAX_ARGON2_ITERATIONS + 1));
+    assert!(crypto.verify_password("master", &slow).is_err());
+    let wide = hash.replace("p=2", &format!("p={}", MAX_ARGON2_PARALLELISM + 1));
+    assert!(crypto.verify_password("master", &wide).is_err());
+}
+
+#[test]
+fn the_stored_hash_carries_the_calibrated_parameters() {
+    let crypto = CryptoManager::new().unwrap();
+    let started = Instant::now();
+    derive_key("master", Some(&[1; 32])).unwrap();
+    let argon2 = calibrate(started.elapsed() * 2).unwrap();
+
+    let dir_config = DirectoryConfig {
+        kdf: KdfParams::Argon2id(argon2),
+        ..DirectoryConfig::default()
+    };
+    let hash = crypto.hash_password("master", &dir_config.kdf.argon2()).unwrap();
+    let params = format!("m={},t={},p={}", argon2.memory_kib, argon2.iterations, argon2.parallelism);
+    assert!(hash.contains(&params), "{} lacks {}", hash, params);
+    assert!(crypto.verify_password("master", &hash).unwrap());
+}
+
+#[test]
+fn each_entry_file_keeps_its_cipher() {
+    let dir = temp_dir("cipher");
+    let key = vec![7; 32];
+    let config = |algorithm: &str| Config {
+        passwords_directory: Some(dir.clone()),
+        encryption_algorithm: algorithm.to_string(),
+        ..Config::default()
+    };
+    assert_eq!(config("XChaCha20_Poly1305").cipher().unwrap(), Cipher::XChaCha20Poly1305);
+    assert!(config("rot13").cipher().is_err());
+
+    let aes = PasswordStorage::new(&config("aes256-gcm"), CryptoManager::new().unwrap());
+    let old = aes.add_entry("old", &key).unwrap();
+    aes.update_password_file(&old, "hunter2", &key).unwrap();
+
+    let xchacha = PasswordStorage::new(&config("xchacha20-poly1305"), CryptoManager::new().unwrap());
+    let new = xchacha.add_entry("new", &key).unwrap();
+    xchacha.update_password_file(&new, "correct horse", &key).unwrap();
+    let details = EntryDetails { username: Some("me".to_string()), ..EntryDetails::default() };
+    xchacha.update_entry_details(&new, &details, &key).unwrap();
+    let file: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(dir.join(&new)).unwrap()).unwrap();
+    assert_eq!(file["algorithm"], "xchacha20-poly1305");
+
+    // Changing the setting doesn't touch entries already saved
+    xchacha.update_password_file(&old, "hunter3", &key).unwrap();
+    for storage in [&aes, &xchacha] {
+        assert_eq!(storage.load_password_file(&old, &key).unwrap(), "hunter3");
+        assert_eq!(storage.load_password_file(&new, &key).unwrap(), "correct horse");
+        assert_eq!(storage.load_entry_details(&new, &key).unwrap().username.as_deref(), Some("me"));
+    }
+
+    // Files from before the field existed are AES-256-GCM
+    let mut file: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(dir.join(&old)).unwrap()).unwrap();
+    assert_eq!(file["algorithm"], "aes256-gcm");
+    file.as_object_mut().unwrap().remove("algorithm");
+    std::fs::write(dir.join(&old), file.to_string()).unwrap();
+    assert_eq!(xchacha.load_password_file(&old, &key).unwrap(), "hunter3");
+
+    // A file naming the wrong cipher fails to decrypt instead of panicking
+    let mut file: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(dir.join(&new)).unwrap()).unwrap();
+    file["algorithm"] = "aes256-gcm".into();
+    std::fs::write(dir.join(&new), file.to_string()).unwrap();
+    assert!(xchacha.load_password_file(&new, &key).is_err());
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn rotating_the_key_reencrypts_everything_under_a_new_salt() {
+    let dir = temp_dir("rotate");
+    let config = Config {
+        passwords_directory: Some(dir.clone()),
+        ..Config::default()
+    };
+    let crypto = CryptoManager::new().unwrap();
+    let mut dir_config = DirectoryConfig {
+        master_password_hash: Some(crypto.hash_password("master", &Argon2id::default()).unwrap()),
+        encryption_key_salt: Some("c2FsdHNhbHRzYWx0c2FsdA".to_string()),
+        ..DirectoryConfig::default()
+    };
+    dir_config.save(&dir).unwrap();
+    let old_key = dir_config.kdf.kdf().derive_key("master", b"c2FsdHNhbHRzYWx0c2FsdA").unwrap();
+    let storage = PasswordStorage::new(&config, crypto);
+    let id = storage.add_entry("github", &old_key).unwrap();
+    storage.update_password_file(&id, "hunter2", &old_key).unwrap();
+    let ui_state = UiState { current_folder: "work".to_string(), selected_entry: Some(id.clone()), ..UiState::default() };
+    storage.save_ui_state(&ui_state, &old_key).unwrap();
+
+    let unchanged = dir_config.clone();
+    assert!(matches!(
+        storage.rotate_key(&old_key, "wrong", &mut dir_config),
+        Err(RpmError::AuthenticationFailed)
+    ));
+    assert_eq!(dir_config.encryption_key_salt, unchanged.encryption_key_salt);
+
+    let new_key = storage.rotate_key(&old_key, "master", &mut dir_config).unwrap();
+    assert_ne!(dir_config.encryption_key_salt, unchanged.encryption_key_salt);
+    // The salt on disk is the one the files are encrypted for
+    let saved = DirectoryConfig::load(&dir).unwrap();
+    assert_eq!(saved.encryption_key_salt, dir_config.encryption_key_salt);
+    assert!(std::fs::read_dir(&dir).unwrap().all(|f| !f.unwrap().file_name().to_string_lossy().ends_with(".rekey")));
+    assert!(!dir.join("rekey.pending").exists());
+
+    assert!(storage.load_def_file(&old_key).is_err());
+    assert_eq!(storage.load_password_file(&id, new_key.as_slice()).unwrap(), "hunter2");
+    assert_eq!(storage.list_decrypted_names(new_key.as_slice()).unwrap(), vec![(id, "github".to_string())]);
+    let audit_log = storage.load_audit_log(new_key.as_slice()).unwrap();
+    assert_eq!(audit_log.events.last().unwrap().action, AuditAction::KeyRotated);
+    // The UI state is re-encrypted too, so the next unlock restores it
+    assert_eq!(storage.load_ui_state(new_key.as_slice()).unwrap(), ui_state);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn an_interrupted_reencryption_is_finished_on_open() {
+    let dir = temp_dir("rekey-pending");
+    let config = Config {
+        passwords_directory: Some(dir.clone()),
+        ..Config::default()
+    };
+    std::fs::write(dir.join("def"), "old def").unwrap();
+    std::fs::write(dir.join("a.pwd"), "old a").unwrap();
+    std::fs::write(dir.join(".rpm_config"), "").unwrap();
+
+    // Staged, but never committed: left as it is
+    std::fs::write(dir.join("def.rekey"), "new def").unwrap();
+    PasswordStorage::new(&config, CryptoManager::new().unwrap());
+    assert_eq!(std::fs::read_to_string(dir.join("def")).unwrap(), "old def");
+    assert!(dir.join("def.rekey").exists());
+
+    // Committed, with a.pwd already renamed when it stopped
+    std::fs::write(dir.join("a.pwd"), "new a").unwrap();
+    std::fs::write(dir.join(".rpm_config.rekey"), "password_hint = \"new\"\n").unwrap();
+    std::fs::write(dir.join("rekey.pending"), "").unwrap();
+    let dir_config = DirectoryConfig::load(&dir).unwrap();
+    assert_eq!(dir_config.password_hint.as_deref(), Some("new"));
+    assert_eq!(std::fs::read_to_string(dir.join("def")).unwrap(), "new def");
+    assert_eq!(std::fs::read_to_string(dir.join("a.pwd")).unwrap(), "new a");
+    assert!(std::fs::read_dir(&dir).unwrap().all(|f| {
+        let name = f.unwrap().file_name().to_string_lossy().into_owned();
+        !name.ends_with(".rekey") && name != "rekey.pending"
+    }));
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn changing_the_master_password_unlocks_with_the_new_one() {
+    let dir = temp_dir("change-master");
+    let config = Config {
+        passwords_directory: Some(dir.clone()),
+        ..Config::default()
+    };
+    let crypto = CryptoManager::new().unwrap();
+    let mut dir_config = DirectoryConfig {
+        master_password_hash: Some(crypto.hash_password("old master", &Argon2id::default()).unwrap()),
+        encryption_key_salt: Some("c2FsdHNhbHRzYWx0c2FsdA".to_string()),
+        ..DirectoryConfig::default()
+    };
+    dir_config.save(&dir).unwrap();
+    let old_key = dir_config.kdf.kdf().derive_key("old master", b"c2FsdHNhbHRzYWx0c2FsdA").unwrap();
+    let storage = PasswordStorage::new(&config, crypto.clone());
+    let id = storage.add_entry("github", &old_key).unwrap();
+    storage.update_password_file(&id, "hunter2", &old_key).unwrap();
+
+    let argon2 = Argon2id { memory_kib: 8 * 1024, iterations: 3, parallelism: 1 };
+    storage
+        .change_master_password(&old_key, "new master", argon2, Some("hint".to_string()), &mut dir_config)
+        .unwrap();
+    assert!(std::fs::read_dir(&dir).unwrap().all(|f| !f.unwrap().file_name().to_string_lossy().ends_with(".rekey")));
+
+    // Unlock from what is on disk, as the TUI and the CLI do
+    let saved = DirectoryConfig::load(&dir).unwrap();
+    let hash = saved.master_password_hash.as_deref().unwrap();
+    assert!(!crypto.verify_password("old master", hash).unwrap());
+    assert!(crypto.verify_password("new master", hash).unwrap());
+    assert_eq!(saved.kdf, KdfParams::Argon2id(argon2));
+    assert!(hash.contains("m=8192,t=3,p=1"));
+    assert_eq!(saved.password_hint.as_deref(), Some("hint"));
+    let salt = base64::engine::general_purpose::STANDARD_NO_PAD
+        .decode(saved.encryption_key_salt.as_deref().unwrap())
+        .unwrap();
+    let new_key = saved.kdf.kdf().derive_key("new master", &salt).unwrap();
+
+    assert!(storage.load_def_file(&old_key).is_err());
+    assert_eq!(storage.load_password_file(&id, &new_key).unwrap(), "hunter2");
+    assert_eq!(storage.list_decrypted_names(&new_key).unwrap(), vec![(id, "github".to_string())]);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn secret_keys_stay_locked_in_memory() {
+    assert!(!SecureKey::new(Vec::new()).is_locked());
+    let mut keys: Vec<SecureKey> = (0..16).map(|i| SecureKey::new(vec![i; 32])).collect();
+    if cfg!(any(unix, windows)) {
+        assert!(keys.iter().all(SecureKey::is_locked));
+    }
+    // Small keys share pages: dropping some leaves the others intact
+    keys.truncate(8);
+    for (i, key) in keys.iter().enumerate() {
+        assert_eq!(key.as_slice(), [i as u8; 32]);
+    }
+
+    // Overlapping regions lock the shared page once and unlock it once
+    let buf = vec![1u8; 10_000];
+    let first = memory::lock(&buf[..5_000]);
+    let second = memory::lock(&buf[4_000..]);
+    assert_eq!(first.is_some(), second.is_some());
+    drop(first);
+    drop(second);
+    assert!(memory::lock(&[]).is_none());
+}
+
+#[test]
+fn keychain_keys_unwrap_only_for_the_same_salt() {
+    let crypto = CryptoManager::new().unwrap();
+    let vault_key = [5u8; 32];
+    let wrapping_key = [6u8; 32];
+    let wrapped = WrappedKey::wrap(&crypto, &vault_key, "salt", &wrapping_key).unwrap();
+    assert!(!serde_json::to_string(&wrapped).unwrap().contains(&BASE64_STANDARD.encode(vault_key)));
+
+    let key = wrapped.unwrap(&crypto, "salt", &wrapping_key).unwrap().unwrap();
+    assert_eq!(key.as_slice(), vault_key);
+    // A new salt means a new key: the stored one is stale
+    assert!(wrapped.unwrap(&crypto, "new salt", &wrapping_key).unwrap().is_none());
+    assert!(wrapped.unwrap(&crypto, "salt", &[7u8; 32]).is_err());
+}
+
+#[test]
+fn the_nonce_guard_flags_a_repeat_under_the_same_key() {
+    let mut guard = NonceGuard::default();
+    let nonce = [5u8; 12];
+    assert!(guard.register(&[1; 32], &nonce));
+    assert!(!guard.register(&[1; 32], &nonce));
+    // Another key may use the same nonce
+    assert!(guard.register(&[2; 32], &nonce));
+    assert!(guard.register(&[1; 32], &[6u8; 12]));
+    // An all-zero nonce is what a dead generator returns
+    assert!(!guard.register(&[3; 32], &[0u8; 12]));
+}
+
+#[test]
+fn a_repeating_generator_is_an_error_not_an_encryption() {
+    let mut guard = NonceGuard::default();
+    let key = [1u8; 32];
+    assert_eq!(draw_nonce(&mut guard, &key, || [5u8; 12]).unwrap(), [5u8; 12]);
+    let mut draws = 0;
+    let result = draw_nonce(&mut guard, &key, || {
+        draws += 1;
+        [5u8; 12]
+    });
+    assert!(matches!(result, Err(RpmError::Crypto(_))));
+    assert_eq!(draws, NONCE_ATTEMPTS);
+    assert!(draw_nonce(&mut guard, &key, || [0u8; 12]).is_err());
+    // A single repeat is drawn again
+    let mut next = [[5u8; 12], [7u8; 12]].into_iter();
+    assert_eq!(draw_nonce(&mut guard, &key, || next.next().unwrap()).unwrap(), [7u8; 12]);
+
+    // The startup check refuses constant or zero output
+    assert!(check_rng().is_ok());
+    assert!(check_samples(&[9; 32], &[8; 32]).is_ok());
+    assert!(check_samples(&[9; 32], &[9; 32]).is_err());
+    assert!(check_samples(&[0; 32], &[8; 32]).is_err());
+}
@@ -0,0 +1,19 @@
+//! Diagnostics of the running test binary.
+
+use rpm::diagnostics::{Diagnostics, BUILD_HASH};
+
+#[test]
+fn diagnostics_describe_this_build() {
+    let about = Diagnostics::collect();
+    assert_eq!(about.version, env!("CARGO_PKG_VERSION"));
+    assert_eq!(about.build_hash, BUILD_HASH);
+    assert!(!about.build_hash.is_empty());
+    assert_eq!(about.rng_check, Ok(()));
+    assert!(about.crypto_crates.iter().any(|item| item.starts_with("aes-gcm ")));
+    assert!(about.crypto_crates.iter().any(|item| item.starts_with("argon2 ")));
+
+    // Tests run unoptimized with debug assertions
+    assert!(!about.hardened());
+    let names: Vec<&str> = about.hardening.iter().map(|check| check.name).collect();
+    assert_eq!(names, ["release_profile", "no_debug_assertions", "panic_abort", "pie"]);
+}
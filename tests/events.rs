@@ -0,0 +1,60 @@
+use rpm::config::Config;
+use rpm::crypto::CryptoManager;
+use rpm::events::{VaultEvent, VaultEvents};
+use rpm::storage::PasswordStorage;
+use rpm::tray::{TrayHandle, TrayShortcut};
+use tokio::sync::broadcast::error::TryRecvError;
+
+#[test]
+fn storage_publishes_entry_changes_once_per_change() {
+    let dir = std::env::temp_dir().join(format!("rpm-events-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let config = Config {
+        passwords_directory: Some(dir.clone()),
+        ..Config::default()
+    };
+    let events = VaultEvents::default();
+    let mut received = events.subscribe();
+    let storage = PasswordStorage::new(&config, CryptoManager::new().unwrap()).with_events(events.clone());
+    let key = vec![7; 32];
+
+    let id = storage.add_entry("github", &key).unwrap();
+    assert_eq!(received.try_recv().unwrap(), VaultEvent::EntryAdded { id: id.clone() });
+    assert_eq!(received.try_recv(), Err(TryRecvError::Empty));
+
+    // Bookkeeping on use is not a change anyone needs to hear about
+    storage.mark_used(&id, &key).unwrap();
+    assert_eq!(received.try_recv(), Err(TryRecvError::Empty));
+
+    storage.set_entry_tags(&id, &["work".to_string()], &key).unwrap();
+    assert_eq!(received.try_recv().unwrap(), VaultEvent::EntryChanged { id: id.clone() });
+
+    storage.delete_entry(&id, &key).unwrap();
+    assert_eq!(received.try_recv().unwrap(), VaultEvent::EntryDeleted { id: id.clone() });
+    assert_eq!(received.try_recv(), Err(TryRecvError::Empty));
+
+    // Handles for the API publish on the same bus
+    storage.handle().restore_from_trash(&id, &key).unwrap();
+    assert_eq!(received.try_recv().unwrap(), VaultEvent::EntryAdded { id });
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn tray_follows_deletions_and_locking() {
+    let tray = TrayHandle::default();
+    assert!(tray.locked());
+    tray.set_shortcuts(vec![
+        TrayShortcut { filename: "a.pwd".to_string(), name: "a".to_string() },
+        TrayShortcut { filename: "b.pwd".to_string(), name: "b".to_string() },
+    ]);
+
+    tray.apply(&VaultEvent::Unlocked);
+    assert!(!tray.locked());
+    tray.apply(&VaultEvent::EntryDeleted { id: "a.pwd".to_string() });
+    tray.apply(&VaultEvent::EntryChanged { id: "b.pwd".to_string() });
+    let names: Vec<String> = tray.shortcuts().into_iter().map(|shortcut| shortcut.name).collect();
+    assert_eq!(names, vec!["b".to_string()]);
+
+    tray.apply(&VaultEvent::Locked);
+    assert!(tray.locked());
+}
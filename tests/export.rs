@@ -0,0 +1,106 @@
+use rpm::config::{Config, DirectoryConfig};
+use rpm::crypto::CryptoManager;
+use rpm::export::{run_export, upload, upload_command};
+use rpm::models::AuditAction;
+use rpm::storage::PasswordStorage;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rpm-export-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Config exporting the vault in `dir` to `dir/exports` for `identity`
+fn export_config(dir: &Path, identity: &age::x25519::Identity) -> Config {
+    Config {
+        passwords_directory: Some(dir.join("vault")),
+        export_recipient: Some(identity.to_public().to_string()),
+        export_directory: Some(dir.join("exports")),
+        ..Config::default()
+    }
+}
+
+fn decrypt(path: &Path, identity: &age::x25519::Identity) -> serde_json::Value {
+    let json = age::decrypt(identity, &std::fs::read(path).unwrap()).unwrap();
+    serde_json::from_slice(&json).unwrap()
+}
+
+#[test]
+fn exports_carry_a_watermark_and_are_audited() {
+    let dir = temp_dir("watermark");
+    let identity = age::x25519::Identity::generate();
+    let config = export_config(&dir, &identity);
+    std::fs::create_dir_all(dir.join("vault")).unwrap();
+    let storage = PasswordStorage::new(&config, CryptoManager::new().unwrap());
+    let key = vec![7; 32];
+    let id = storage.add_entry("github", &key).unwrap();
+    storage.update_password_file(&id, "hunter2", &key).unwrap();
+
+    let path = run_export(&storage, &config, &key).unwrap();
+    assert_eq!(path.parent(), Some(dir.join("exports").as_path()));
+    let file_name = path.file_name().unwrap().to_string_lossy().into_owned();
+    assert!(file_name.starts_with("rpm-export-") && file_name.ends_with(".json.age"), "{}", file_name);
+    // Nothing is left next to the export
+    assert_eq!(std::fs::read_dir(dir.join("exports")).unwrap().count(), 1);
+
+    let snapshot = decrypt(&path, &identity);
+    let vault_id = DirectoryConfig::load(&dir.join("vault")).unwrap().vault_id.unwrap();
+    let user = std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_else(|_| "unknown".to_string());
+    let watermark = &snapshot["watermark"];
+    assert_eq!(watermark["vault_id"], vault_id.as_str());
+    assert_eq!(watermark["exported_by"], user.as_str());
+    assert_eq!(watermark["exported_at"], snapshot["exported_at"]);
+    let exported_at = chrono::DateTime::<chrono::Utc>::from_str(watermark["exported_at"].as_str().unwrap()).unwrap();
+    assert_eq!(snapshot["entries"][0]["name"], "github");
+    assert_eq!(snapshot["entries"][0]["password"], "hunter2");
+
+    let events = storage.load_audit_log(&key).unwrap().events;
+    let event = events.iter().find(|event| event.action == AuditAction::Export).unwrap();
+    assert_eq!(event.details, file_name);
+    assert_eq!(event.user, user);
+    assert_eq!(event.at, exported_at);
+
+    // The vault keeps its ID across exports
+    std::thread::sleep(std::time::Duration::from_millis(1100));
+    let second = run_export(&storage, &config, &key).unwrap();
+    assert_eq!(decrypt(&second, &identity)["watermark"]["vault_id"], vault_id.as_str());
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn the_upload_command_is_split_with_shell_quoting() {
+    let path = Path::new("/exports/rpm-export.json.age");
+    let command = upload_command(r#"rclone copyto --config "/home/me/my rclone.conf" 'remote:vault backups/'"#, path).unwrap();
+    assert_eq!(command.get_program(), "rclone");
+    let args: Vec<_> = command.get_args().collect();
+    assert_eq!(
+        args,
+        ["copyto", "--config", "/home/me/my rclone.conf", "remote:vault backups/", "/exports/rpm-export.json.age"]
+    );
+
+    assert!(upload_command("", path).is_err());
+    assert!(upload_command("   ", path).is_err());
+    assert!(upload_command("scp 'unterminated", path).is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn the_upload_command_gets_the_export_and_its_failure_is_reported() {
+    let dir = temp_dir("upload");
+    let export = dir.join("rpm-export.json.age");
+    std::fs::write(&export, "snapshot").unwrap();
+    let target = dir.join("remote copy");
+    // The snapshot path is the last argument, `$0` of the script
+    let command = format!(r#"sh -c 'cp "$0" "{}"'"#, target.display());
+    upload(&command, &export).unwrap();
+    assert_eq!(std::fs::read_to_string(&target).unwrap(), "snapshot");
+
+    let error = upload("sh -c 'echo quota exceeded >&2; exit 3'", &export).unwrap_err();
+    assert!(error.to_string().contains("quota exceeded"), "{}", error);
+    let _ = std::fs::remove_dir_all(&dir);
+}
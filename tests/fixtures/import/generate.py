@@ -0,0 +1,220 @@
+"""Rebuild the importer fixtures: python3 generate.py (needs `cryptography`)"""
+import base64, gzip, hashlib, hmac, json, os, sqlite3, struct
+from cryptography.hazmat.primitives.ciphers import Cipher, algorithms, modes
+from cryptography.hazmat.primitives import padding
+
+OUT = os.path.dirname(os.path.abspath(__file__))
+os.makedirs(OUT, exist_ok=True)
+PASSWORD = "correct horse"
+
+def rnd(n, tag):
+    # deterministic "random" bytes so the fixtures are reproducible
+    return hashlib.sha512(tag.encode()).digest()[:n]
+
+def pkcs7(data, bs=128):
+    p = padding.PKCS7(bs).padder()
+    return p.update(data) + p.finalize()
+
+def aes_cbc(key, iv, data):
+    e = Cipher(algorithms.AES(key), modes.CBC(iv)).encryptor()
+    return e.update(pkcs7(data)) + e.finalize()
+
+def aes_kdf(composite, seed, rounds):
+    e = Cipher(algorithms.AES(seed), modes.ECB()).encryptor()
+    blk = composite
+    for _ in range(rounds):
+        blk = e.update(blk)
+    return hashlib.sha256(blk).digest()
+
+def chacha20(key, nonce12, data):
+    e = Cipher(algorithms.ChaCha20(key, b"\0\0\0\0" + nonce12), None).encryptor()
+    return e.update(data)
+
+# --- Salsa20 (KeePass inner stream) ---
+def rotl(v, c): return ((v << c) & 0xffffffff) | (v >> (32 - c))
+def salsa20_block(key, iv, counter):
+    c = [0x61707865, 0x3320646e, 0x79622d32, 0x6b206574]
+    k = struct.unpack("<8I", key); n = struct.unpack("<2I", iv)
+    st = [c[0], k[0], k[1], k[2], k[3], c[1], n[0], n[1],
+          counter & 0xffffffff, counter >> 32, c[2], k[4], k[5], k[6], k[7], c[3]]
+    x = st[:]
+    def qr(a, b, cc, d):
+        x[b] ^= rotl((x[a] + x[d]) & 0xffffffff, 7)
+        x[cc] ^= rotl((x[b] + x[a]) & 0xffffffff, 9)
+        x[d] ^= rotl((x[cc] + x[b]) & 0xffffffff, 13)
+        x[a] ^= rotl((x[d] + x[cc]) & 0xffffffff, 18)
+    for _ in range(10):
+        qr(0, 4, 8, 12); qr(5, 9, 13, 1); qr(10, 14, 2, 6); qr(15, 3, 7, 11)
+        qr(0, 1, 2, 3); qr(5, 6, 7, 4); qr(10, 11, 8, 9); qr(15, 12, 13, 14)
+    return struct.pack("<16I", *[(x[i] + st[i]) & 0xffffffff for i in range(16)])
+
+class Salsa:
+    def __init__(self, key, iv): self.key, self.iv, self.buf, self.ctr = key, iv, b"", 0
+    def apply(self, data):
+        while len(self.buf) < len(data):
+            self.buf += salsa20_block(self.key, self.iv, self.ctr); self.ctr += 1
+        out = bytes(a ^ b for a, b in zip(data, self.buf)); self.buf = self.buf[len(data):]
+        return out
+
+class ChaChaStream:
+    def __init__(self, key):
+        h = hashlib.sha512(key).digest()
+        self.enc = Cipher(algorithms.ChaCha20(h[:32], b"\0\0\0\0" + h[32:44]), None).encryptor()
+    def apply(self, data): return self.enc.update(data)
+
+def xml_doc(stream, times):
+    def prot(v): return base64.b64encode(stream.apply(v.encode())).decode()
+    bin_uuid = base64.b64encode(rnd(16, "bin")).decode()
+    def entry(title, user, pw, url="", notes="", history=lambda: ""):
+        # f-string parts are evaluated left to right, so history is encrypted after the entry's own values
+        return (f"<Entry><UUID>{base64.b64encode(rnd(16, title)).decode()}</UUID>"
+                f"<String><Key>Title</Key><Value>{title}</Value></String>"
+                f"<String><Key>UserName</Key><Value>{user}</Value></String>"
+                f"<String><Key>Password</Key><Value Protected=\"True\">{prot(pw)}</Value></String>"
+                f"<String><Key>URL</Key><Value>{url}</Value></String>"
+                f"<String><Key>Notes</Key><Value>{notes}</Value></String>"
+                f"<Times><CreationTime>{times[0]}</CreationTime><LastModificationTime>{times[1]}</LastModificationTime></Times>"
+                f"{history()}</Entry>")
+    # Document order matters for the keystream: the history value sits between two imported ones
+    doc = ("<?xml version=\"1.0\" encoding=\"utf-8\" standalone=\"yes\"?>"
+           f"<KeePassFile><Meta><Generator>fixture</Generator><RecycleBinUUID>{bin_uuid}</RecycleBinUUID></Meta>"
+           "<Root><Group><UUID>" + base64.b64encode(rnd(16, "root")).decode() + "</UUID><Name>Database</Name>"
+           + entry("Mail", "me@example.com", "mail-secret", "https://mail.example.com", "first line\nsecond line")
+           + "<Group><UUID>" + base64.b64encode(rnd(16, "work")).decode() + "</UUID><Name>Work</Name>")
+    doc = doc + entry("GitHub", "octocat", "gh-password", "https://github.com", "",
+                      lambda: "<History>" + entry("GitHub", "octocat", "gh-old-password") + "</History>")
+    doc += entry("VPN", "", "vpn-ключ")
+    doc += "</Group>"
+    doc += f"<Group><UUID>{bin_uuid}</UUID><Name>Recycle Bin</Name>" + entry("Deleted", "x", "deleted-secret") + "</Group>"
+    doc += "</Group></Root></KeePassFile>"
+    return doc.encode()
+
+def kdbx3():
+    master_seed, transform_seed = rnd(32, "ms3"), rnd(32, "ts3")
+    iv, psk, ssb = rnd(16, "iv3"), rnd(32, "psk3"), rnd(32, "ssb3")
+    rounds = 1000
+    fields = [(2, bytes.fromhex("31c1f2e6bf714350be5805216afc5aff")), (3, struct.pack("<I", 1)),
+              (4, master_seed), (5, transform_seed), (6, struct.pack("<Q", rounds)), (7, iv),
+              (8, psk), (9, ssb), (10, struct.pack("<I", 2)), (0, b"\r\n\r\n")]
+    header = struct.pack("<III", 0x9AA2D903, 0xB54BFB67, 0x00030001)
+    for fid, val in fields:
+        header += struct.pack("<BH", fid, len(val)) + val
+    composite = hashlib.sha256(hashlib.sha256(PASSWORD.encode()).digest()).digest()
+    key = hashlib.sha256(master_seed + aes_kdf(composite, transform_seed, rounds)).digest()
+    stream = Salsa(hashlib.sha256(psk).digest(), bytes([0xE8, 0x30, 0x09, 0x4B, 0x97, 0x20, 0x5D, 0x2A]))
+    content = gzip.compress(xml_doc(stream, ("2023-04-05T06:07:08Z", "2024-01-02T03:04:05Z")), mtime=0)
+    blocks = struct.pack("<I", 0) + hashlib.sha256(content).digest() + struct.pack("<I", len(content)) + content
+    blocks += struct.pack("<I", 1) + b"\0" * 32 + struct.pack("<I", 0)
+    return header + aes_cbc(key, iv, ssb + blocks)
+
+def kdbx4_time(y_m_d):
+    import datetime
+    dt = datetime.datetime(*y_m_d, tzinfo=datetime.timezone.utc)
+    seconds = int(dt.timestamp()) + 62_135_596_800
+    return base64.b64encode(struct.pack("<q", seconds)).decode()
+
+def kdbx4():
+    master_seed, kdf_seed, iv, inner_key = rnd(32, "ms4"), rnd(32, "ks4"), rnd(12, "iv4"), rnd(64, "ik4")
+    rounds = 1000
+    def var(kind, name, val):
+        return struct.pack("<BI", kind, len(name)) + name + struct.pack("<I", len(val)) + val
+    kdf = struct.pack("<H", 0x0100) + var(0x42, b"$UUID", bytes.fromhex("7c02bb8279a74ac0927d114a00648238")) \
+        + var(0x05, b"R", struct.pack("<Q", rounds)) + var(0x42, b"S", kdf_seed) + b"\0"
+    fields = [(2, bytes.fromhex("d6038a2b8b6f4cb5a524339a31dbb59a")), (3, struct.pack("<I", 0)),
+              (4, master_seed), (7, iv), (11, kdf), (0, b"\r\n\r\n")]
+    header = struct.pack("<III", 0x9AA2D903, 0xB54BFB67, 0x00040001)
+    for fid, val in fields:
+        header += struct.pack("<BI", fid, len(val)) + val
+    composite = hashlib.sha256(hashlib.sha256(PASSWORD.encode()).digest()).digest()
+    transformed = aes_kdf(composite, kdf_seed, rounds)
+    key = hashlib.sha256(master_seed + transformed).digest()
+    hmac_key = hashlib.sha512(master_seed + transformed + b"\x01").digest()
+    def mac(index, data):
+        bk = hashlib.sha512(struct.pack("<Q", index) + hmac_key).digest()
+        return hmac.new(bk, data, hashlib.sha256).digest()
+    inner = struct.pack("<BI", 1, 4) + struct.pack("<I", 3) + struct.pack("<BI", 2, 64) + inner_key + struct.pack("<BI", 0, 0)
+    content = inner + xml_doc(ChaChaStream(inner_key), (kdbx4_time((2023, 4, 5, 6, 7, 8)), kdbx4_time((2024, 1, 2, 3, 4, 5))))
+    enc = chacha20(key, iv, content)
+    out = header + hashlib.sha256(header).digest() + mac(0xFFFFFFFFFFFFFFFF, header)
+    out += mac(0, struct.pack("<Q", 0) + struct.pack("<I", len(enc)) + enc) + struct.pack("<I", len(enc)) + enc
+    out += mac(1, struct.pack("<Q", 1) + struct.pack("<I", 0)) + struct.pack("<I", 0)
+    return out
+
+open(f"{OUT}/kdbx3.kdbx", "wb").write(kdbx3())
+open(f"{OUT}/kdbx4.kdbx", "wb").write(kdbx4())
+
+# --- DER helpers ---
+def der(tag, content):
+    n = len(content)
+    if n < 0x80: ln = bytes([n])
+    elif n < 0x100: ln = bytes([0x81, n])
+    else: ln = bytes([0x82, n >> 8, n & 0xff])
+    return bytes([tag]) + ln + content
+SEQ = lambda *items: der(0x30, b"".join(items))
+OCT = lambda b: der(0x04, b)
+OID = lambda b: der(0x06, b)
+def INT(v):
+    b = v.to_bytes((v.bit_length() + 8) // 8, "big")
+    return der(0x02, b)
+OID_PBES2 = bytes.fromhex("2a864886f70d01050d")
+OID_PBKDF2 = bytes.fromhex("2a864886f70d01050c")
+OID_HMAC_SHA256 = bytes.fromhex("2a864886f70d0209")
+OID_AES256 = bytes.fromhex("60864801650304012a")
+OID_3DES = bytes.fromhex("2a864886f70d0307")
+
+def pbes2(global_salt, password, plaintext, tag):
+    salt, iv14 = rnd(32, tag + "salt"), rnd(14, tag + "iv")
+    iterations = 1
+    k = hashlib.pbkdf2_hmac("sha256", hashlib.sha1(global_salt + password.encode()).digest(), salt, iterations, 32)
+    ct = aes_cbc(k, b"\x04\x0e" + iv14, plaintext)
+    return SEQ(SEQ(OID(OID_PBES2), SEQ(SEQ(OID(OID_PBKDF2), SEQ(OCT(salt), INT(iterations), INT(32), SEQ(OID(OID_HMAC_SHA256)))),
+                                        SEQ(OID(OID_AES256), OCT(iv14)))), OCT(ct))
+
+FF = f"{OUT}/firefox"
+os.makedirs(FF, exist_ok=True)
+primary = "primary secret"
+global_salt = rnd(20, "gsalt")
+login_key = rnd(32, "loginkey")
+key_id = bytes.fromhex("F8000000000000000000000000000001")
+db_path = f"{FF}/key4.db"
+if os.path.exists(db_path): os.remove(db_path)
+db = sqlite3.connect(db_path)
+db.execute("CREATE TABLE metaData (id PRIMARY KEY UNIQUE ON CONFLICT REPLACE, item1, item2)")
+db.execute("CREATE TABLE nssPrivate (id PRIMARY KEY UNIQUE ON CONFLICT ABORT, a11, a102)")
+db.execute("INSERT INTO metaData VALUES ('password', ?, ?)", (global_salt, pbes2(global_salt, primary, b"password-check", "check")))
+db.execute("INSERT INTO nssPrivate VALUES (1, ?, ?)", (pbes2(global_salt, primary, login_key, "key"), key_id))
+db.commit(); db.close()
+
+def field(value, tag, oid=OID_AES256):
+    iv = rnd(16, tag)
+    return base64.b64encode(SEQ(OCT(key_id), SEQ(OID(oid), OCT(iv)), OCT(aes_cbc(login_key, iv, value.encode())))).decode()
+logins = {"nextId": 4, "logins": [
+    {"id": 1, "hostname": "https://accounts.example.com", "encryptedUsername": field("alice", "u1"),
+     "encryptedPassword": field("ff-password", "p1"), "encType": 1},
+    {"id": 2, "hostname": "https://shop.example.org", "encryptedUsername": field("", "u2"),
+     "encryptedPassword": field("shop-пароль", "p2"), "encType": 1},
+    # Written by an old Firefox with 3DES: not supported
+    {"id": 3, "hostname": "https://legacy.example.net", "encryptedUsername": field("bob", "u3", OID_3DES),
+     "encryptedPassword": field("old", "p3", OID_3DES), "encType": 1},
+], "version": 3}
+json.dump(logins, open(f"{FF}/logins.json", "w"), indent=2)
+
+CR = f"{OUT}/chromium"
+os.makedirs(CR, exist_ok=True)
+db_path = f"{CR}/Login Data"
+if os.path.exists(db_path): os.remove(db_path)
+ckey = hashlib.pbkdf2_hmac("sha1", b"peanuts", b"saltysalt", 1, 16)
+def v10(pw): return b"v10" + aes_cbc(ckey, b" " * 16, pw.encode())
+db = sqlite3.connect(db_path)
+db.execute("CREATE TABLE logins (origin_url VARCHAR NOT NULL, username_value VARCHAR, password_value BLOB, blacklisted_by_user INTEGER NOT NULL)")
+rows = [
+    ("https://accounts.example.com/login", "alice", v10("chrome-password"), 0),
+    ("https://news.example.com/", "", v10("news-secret"), 0),
+    # Never-save entry
+    ("https://never.example.com/", "", b"", 1),
+    # Unknown format and a value cut in the middle of a block
+    ("https://future.example.com/", "carol", b"v99" + b"\0" * 16, 0),
+    ("https://broken.example.com/", "dave", v10("broken-password")[:-5], 0),
+]
+db.executemany("INSERT INTO logins VALUES (?, ?, ?, ?)", rows)
+db.commit(); db.close()
@@ -0,0 +1,254 @@
+//! Importers against fixture files in `tests/fixtures/import`: two KeePass
+//! databases (KDBX 3.1 with AES and Salsa20, KDBX 4 with ChaCha20, both with
+//! master password `correct horse`), a Firefox profile with primary password
+//! `primary secret`, a Linux Chromium profile and a Bitwarden CSV export.
+//! `generate.py` there rebuilds the binary fixtures.
+
+use rpm::config::Config;
+use rpm::crypto::CryptoManager;
+use rpm::errors::RpmError;
+use rpm::import::{firefox, guess_mapping, import_rows, kdbx, parse_csv, pass, saved_or_guessed_mapping, CsvTable, ImportField};
+use rpm::storage::PasswordStorage;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+const KEEPASS_PASSWORD: &str = "correct horse";
+const FIREFOX_PASSWORD: &str = "primary secret";
+
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/import").join(name)
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rpm-import-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn storage_in(dir: &Path) -> PasswordStorage {
+    let config = Config {
+        passwords_directory: Some(dir.to_path_buf()),
+        ..Config::default()
+    };
+    PasswordStorage::new(&config, CryptoManager::new().unwrap())
+}
+
+/// Copy of a fixture profile with one file cut to `keep` bytes
+fn truncated_profile(name: &str, file: &str, keep: usize) -> PathBuf {
+    let dir = temp_dir(name);
+    for entry in std::fs::read_dir(fixture(name)).unwrap() {
+        let entry = entry.unwrap();
+        std::fs::copy(entry.path(), dir.join(entry.file_name())).unwrap();
+    }
+    let data = std::fs::read(dir.join(file)).unwrap();
+    std::fs::write(dir.join(file), &data[..keep]).unwrap();
+    dir
+}
+
+/// Browser databases are read with the `sqlite3` tool
+fn has_sqlite3() -> bool {
+    let found = std::process::Command::new("sqlite3").arg("-version").output().is_ok();
+    if !found {
+        eprintln!("sqlite3 is not installed, skipping");
+    }
+    found
+}
+
+#[test]
+fn keepass_databases_are_read_except_history_and_recycle_bin() {
+    for file in ["kdbx3.kdbx", "kdbx4.kdbx"] {
+        let entries = kdbx::load(&fixture(file), KEEPASS_PASSWORD).unwrap();
+        let names: Vec<String> = entries.iter().map(|entry| entry.vault_name()).collect();
+        assert_eq!(names, vec!["Mail", "Work/GitHub", "Work/VPN"], "{}", file);
+
+        let mail = &entries[0];
+        assert_eq!(mail.username, "me@example.com");
+        assert_eq!(mail.password, "mail-secret");
+        assert_eq!(mail.url, "https://mail.example.com");
+        assert_eq!(mail.notes, "first line\nsecond line");
+        assert_eq!(mail.timestamps.created_at.unwrap().to_rfc3339(), "2023-04-05T06:07:08+00:00", "{}", file);
+        assert_eq!(mail.timestamps.updated_at.unwrap().to_rfc3339(), "2024-01-02T03:04:05+00:00", "{}", file);
+        // Protected values after an entry's history are still decrypted
+        assert_eq!(entries[1].password, "gh-password", "{}", file);
+        assert_eq!(entries[2].password, "vpn-ключ", "{}", file);
+    }
+}
+
+#[test]
+fn keepass_entries_are_imported_into_the_vault() {
+    let dir = temp_dir("kdbx");
+    let storage = storage_in(&dir);
+    let key = vec![7; 32];
+    let entries = kdbx::load(&fixture("kdbx4.kdbx"), KEEPASS_PASSWORD).unwrap();
+
+    let report = kdbx::import_entries(&storage, &entries, &key).unwrap();
+    assert_eq!((report.created, report.skipped), (3, 0));
+    let (filename, _) = storage
+        .list_decrypted_names(&key)
+        .unwrap()
+        .into_iter()
+        .find(|(_, name)| name == "Work/GitHub")
+        .unwrap();
+    assert_eq!(storage.load_password_file(&filename, &key).unwrap(), "gh-password");
+    let details = storage.load_entry_details(&filename, &key).unwrap();
+    assert_eq!(details.username.as_deref(), Some("octocat"));
+    assert_eq!(details.notes, None);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn keepass_databases_reject_a_wrong_password() {
+    for file in ["kdbx3.kdbx", "kdbx4.kdbx"] {
+        let result = kdbx::load(&fixture(file), "wrong horse");
+        assert!(matches!(result, Err(RpmError::AuthenticationFailed)), "{}: {:?}", file, result.err());
+    }
+}
+
+#[test]
+fn truncated_keepass_databases_fail_cleanly() {
+    for file in ["kdbx3.kdbx", "kdbx4.kdbx"] {
+        let data = std::fs::read(fixture(file)).unwrap();
+        for keep in 0..data.len() {
+            assert!(kdbx::parse(&data[..keep], KEEPASS_PASSWORD).is_err(), "{} cut at {}", file, keep);
+        }
+    }
+    let error = kdbx::parse(b"PK\x03\x04 not a database", KEEPASS_PASSWORD).unwrap_err();
+    assert!(error.to_string().contains("not a KeePass"), "{}", error);
+}
+
+#[test]
+fn firefox_logins_are_decrypted_with_the_primary_password() {
+    if !has_sqlite3() {
+        return;
+    }
+    let logins = firefox::load(&fixture("firefox"), FIREFOX_PASSWORD).unwrap();
+    // The 3DES entry of an old profile is counted, not imported
+    assert_eq!(logins.unsupported, 1);
+    let found: Vec<(String, &str)> = logins
+        .logins
+        .iter()
+        .map(|login| (login.entry_name(), login.password.as_str()))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            ("accounts.example.com/alice".to_string(), "ff-password"),
+            ("shop.example.org".to_string(), "shop-пароль"),
+        ]
+    );
+
+    let result = firefox::load(&fixture("firefox"), "wrong secret");
+    assert!(matches!(result, Err(RpmError::AuthenticationFailed)), "{:?}", result.err());
+}
+
+#[test]
+fn truncated_firefox_profiles_fail_cleanly() {
+    if !has_sqlite3() {
+        return;
+    }
+    let size = std::fs::metadata(fixture("firefox").join("logins.json")).unwrap().len() as usize;
+    let profile = truncated_profile("firefox", "logins.json", size / 2);
+    assert!(firefox::load(&profile, FIREFOX_PASSWORD).is_err());
+    let _ = std::fs::remove_dir_all(&profile);
+
+    for keep in [0, 100, 4096] {
+        let profile = truncated_profile("firefox", "key4.db", keep);
+        assert!(firefox::load(&profile, FIREFOX_PASSWORD).is_err(), "key4.db cut at {}", keep);
+        let _ = std::fs::remove_dir_all(&profile);
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+#[test]
+fn chromium_logins_are_decrypted_with_the_linux_fallback_key() {
+    use rpm::import::chromium::{self, Browser};
+    if !has_sqlite3() {
+        return;
+    }
+    let logins = chromium::load(Browser::Chromium, &fixture("chromium")).unwrap();
+    let found: Vec<(String, &str)> = logins
+        .logins
+        .iter()
+        .map(|login| (login.entry_name(), login.password.as_str()))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            ("accounts.example.com/alice".to_string(), "chrome-password"),
+            ("news.example.com".to_string(), "news-secret"),
+        ]
+    );
+    // An unknown version prefix and a value cut mid-block; never-save entries are left out
+    assert_eq!(logins.unsupported, 2);
+
+    let profile = truncated_profile("chromium", "Login Data", 100);
+    assert!(chromium::load(Browser::Chromium, &profile).is_err());
+    let _ = std::fs::remove_dir_all(&profile);
+}
+
+#[test]
+fn pass_entries_gpg_cannot_decrypt_are_reported_not_printed() {
+    let dir = temp_dir("pass");
+    let store = dir.join("store");
+    std::fs::create_dir_all(store.join("web")).unwrap();
+    std::fs::write(store.join("web").join("broken.gpg"), "not an OpenPGP message").unwrap();
+    std::fs::create_dir_all(dir.join("vault")).unwrap();
+    let storage = storage_in(&dir.join("vault"));
+
+    let report = pass::import_store(&storage, &store, &[7; 32]).unwrap();
+    assert_eq!((report.created, report.skipped), (0, 1));
+    assert_eq!(report.warnings.len(), 1);
+    assert!(report.warnings[0].contains("broken.gpg"), "{:?}", report.warnings);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn csv_exports_are_parsed_and_mapped_by_header() {
+    let table = CsvTable::load(&fixture("bitwarden.csv")).unwrap();
+    assert_eq!(table.headers[0], "folder", "the byte order mark is dropped");
+    assert_eq!(table.rows.len(), 3, "the empty line is skipped");
+    assert_eq!(table.rows[0][4], "Recovery codes:\r\n1111, 2222");
+    assert_eq!(table.rows[0][9], "gh \"quoted\" pass");
+
+    use ImportField::*;
+    let mapping = guess_mapping(&table.headers);
+    assert_eq!(mapping, vec![Skip, Skip, Skip, Name, Notes, Skip, Skip, Url, Username, Password, Skip]);
+
+    let dir = temp_dir("csv");
+    let storage = storage_in(&dir);
+    let key = vec![7; 32];
+    let report = import_rows(&storage, &table, &mapping, &key).unwrap();
+    assert_eq!((report.created, report.skipped), (2, 1));
+    let names = storage.list_decrypted_names(&key).unwrap();
+    let (filename, _) = names.iter().find(|(_, name)| name == "GitHub").unwrap();
+    assert_eq!(storage.load_password_file(filename, &key).unwrap(), "gh \"quoted\" pass");
+    let details = storage.load_entry_details(filename, &key).unwrap();
+    assert_eq!(details.url.as_deref(), Some("https://github.com"));
+    assert_eq!(details.notes.as_deref(), Some("Recovery codes:\r\n1111, 2222"));
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn csv_mapping_prefers_a_saved_one_for_the_same_headers() {
+    let headers: Vec<String> = ["Title", "Email", "Login", "Password"].iter().map(|h| h.to_string()).collect();
+    use ImportField::*;
+    // Each field goes to the first column that looks like it
+    assert_eq!(guess_mapping(&headers), vec![Name, Username, Skip, Password]);
+
+    let mut saved = BTreeMap::new();
+    saved.insert("title,email,login,password".to_string(), vec![Name, Notes, Username, Password]);
+    assert_eq!(saved_or_guessed_mapping(&headers, &saved), (vec![Name, Notes, Username, Password], true));
+    // A saved mapping for a different number of columns is not used
+    saved.insert("title,email,login,password".to_string(), vec![Name]);
+    assert_eq!(saved_or_guessed_mapping(&headers, &saved), (guess_mapping(&headers), false));
+}
+
+#[test]
+fn malformed_csv_is_rejected() {
+    assert!(parse_csv("name,password\r\ngithub,\"unterminated").is_err());
+    assert!(parse_csv("").is_err());
+    assert!(parse_csv("\r\n\r\n").is_err());
+    // A header row alone is a valid, empty export
+    assert!(parse_csv("name,password\n").unwrap().rows.is_empty());
+}
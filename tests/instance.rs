@@ -0,0 +1,44 @@
+#![cfg(unix)]
+
+use rpm::instance::{acquire, send, InstanceHandle, InstanceRequest};
+use tokio::sync::watch;
+
+#[test]
+fn requests_are_one_line_each() {
+    for request in [InstanceRequest::Focus, InstanceRequest::Copy("Work/GitHub".to_string())] {
+        assert_eq!(InstanceRequest::parse(&format!("{}\n", request.to_line())), Some(request));
+    }
+    assert_eq!(InstanceRequest::parse("copy  "), None);
+    assert_eq!(InstanceRequest::parse("unlock"), None);
+}
+
+#[tokio::test]
+async fn a_second_launch_hands_its_request_to_the_first() {
+    // The lock file and the socket live next to the config
+    let dir = std::env::temp_dir().join(format!("rpm-instance-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::env::set_var("HOME", &dir);
+    std::env::set_var("XDG_CONFIG_HOME", &dir);
+
+    let mut guard = acquire().await.unwrap().expect("the first launch is the primary instance");
+    let handle = InstanceHandle::default();
+    let (shutdown_tx, shutdown_rx) = watch::channel(());
+    let server = guard.listen(handle.clone(), shutdown_rx).unwrap();
+
+    assert!(acquire().await.unwrap().is_none());
+    send(&InstanceRequest::Copy("github".to_string())).await.unwrap();
+    send(&InstanceRequest::Focus).await.unwrap();
+    assert_eq!(
+        handle.take_requests(),
+        vec![InstanceRequest::Copy("github".to_string()), InstanceRequest::Focus]
+    );
+    assert!(handle.take_requests().is_empty());
+
+    shutdown_tx.send(()).unwrap();
+    server.await.unwrap();
+    drop(guard);
+    // The lock is released with the primary instance
+    assert!(acquire().await.unwrap().is_some());
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,56 @@
+use rpm::errors::RpmError;
+use rpm::native_messaging::{read_message, write_message};
+use std::io::Cursor;
+
+/// Browsers' limit for host messages
+const MAX_MESSAGE_LEN: usize = 1024 * 1024;
+
+fn framed(len: usize, body: &[u8]) -> Vec<u8> {
+    let mut data = (len as u32).to_ne_bytes().to_vec();
+    data.extend_from_slice(body);
+    data
+}
+
+#[test]
+fn messages_round_trip_back_to_back() {
+    let mut buffer = Vec::new();
+    let messages: [&[u8]; 3] = [br#"{"path":"/api/status"}"#, b"", "{\"name\":\"пароль\"}".as_bytes()];
+    for message in messages {
+        write_message(&mut buffer, message).unwrap();
+    }
+    assert_eq!(&buffer[..4], &22u32.to_ne_bytes());
+
+    let mut input = Cursor::new(buffer);
+    for message in messages {
+        assert_eq!(read_message(&mut input).unwrap().as_deref(), Some(message));
+    }
+    assert_eq!(read_message(&mut input).unwrap(), None);
+}
+
+#[test]
+fn a_closed_pipe_ends_the_session() {
+    assert_eq!(read_message(&mut Cursor::new(Vec::new())).unwrap(), None);
+    // The browser went away in the middle of a length prefix
+    assert_eq!(read_message(&mut Cursor::new(vec![5, 0])).unwrap(), None);
+}
+
+#[test]
+fn a_message_cut_short_is_an_error() {
+    let mut input = Cursor::new(framed(10, b"{\"id\":"));
+    assert!(matches!(read_message(&mut input), Err(RpmError::Io(_))));
+}
+
+#[test]
+fn oversized_messages_are_rejected_before_reading_them() {
+    let largest = vec![b' '; MAX_MESSAGE_LEN];
+    let mut input = Cursor::new(framed(largest.len(), &largest));
+    assert_eq!(read_message(&mut input).unwrap().map(|message| message.len()), Some(MAX_MESSAGE_LEN));
+
+    // Only the length prefix is consumed
+    let mut input = Cursor::new(framed(MAX_MESSAGE_LEN + 1, b"{}"));
+    let error = read_message(&mut input).unwrap_err();
+    assert!(error.to_string().contains("too large"), "{}", error);
+    assert_eq!(input.position(), 4);
+    let mut input = Cursor::new(framed(u32::MAX as usize, b""));
+    assert!(read_message(&mut input).is_err());
+}
@@ -0,0 +1,985 @@
+//! HTTP API contract for browser extensions: requests go straight through the
+//! router with `tower::ServiceExt::oneshot`, no socket is bound.
+
+use axum::body::Body;
+use axum::http::{header, Method, Request, StatusCode};
+use axum::Router;
+use chrono::{Duration, Local, NaiveDate, NaiveTime, Timelike, Utc};
+use http_body_util::BodyExt;
+use rpm::config::{Config, DirectoryConfig, VaultPolicy};
+use rpm::crypto::key_derivation::Argon2id;
+use rpm::crypto::{CryptoManager, SecureKey};
+use rpm::events::VaultEvent;
+use rpm::models::{
+    AccessWindow, AuthResponse, EntryDetails, EntryMeta, PairResponse, PairedResponse, SyncStatus, VaultStats,
+};
+use rpm::server::{
+    bind, router, start_server, start_tls_server, AllowedOrigins, AppState, AuthThrottle, MetadataHandle, PairingHandle, PairingOutcome, TlsIdentity, TokenSigner,
+    extension_origin, CLIENT_HEADER, EVENTS_PROTOCOL,
+};
+use rpm::storage::PasswordStorage;
+use rpm::webhook::Webhooks;
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use std::sync::{Arc, OnceLock};
+use tower::ServiceExt;
+
+const EXTENSION_ORIGIN: &str = "chrome-extension://abcdefghijklmnop";
+
+/// Master password of the vault `/api/auth` checks
+const MASTER_PASSWORD: &str = "secret";
+
+/// Vault with `MASTER_PASSWORD`, created once per run because hashing it is slow
+fn vault_dir() -> PathBuf {
+    static DIR: OnceLock<PathBuf> = OnceLock::new();
+    DIR.get_or_init(|| {
+        let dir = std::env::temp_dir().join(format!("rpm-server-vault-{}", std::process::id()));
+        let dir_config = DirectoryConfig {
+            master_password_hash: Some(CryptoManager::new().unwrap().hash_password(MASTER_PASSWORD, &Argon2id::default()).unwrap()),
+            ..DirectoryConfig::default()
+        };
+        dir_config.save(&dir).unwrap();
+        dir
+    })
+    .clone()
+}
+
+/// Pairing state with one paired extension, whose credential the test requests carry
+fn paired_client() -> &'static (PairingHandle, String) {
+    static CLIENT: OnceLock<(PairingHandle, String)> = OnceLock::new();
+    CLIENT.get_or_init(|| {
+        let pairing = PairingHandle::default();
+        let request = pairing.request("test extension").unwrap();
+        pairing.approve(&request.id);
+        let PairingOutcome::Paired { credential, .. } = pairing.complete(&request.id, &request.code).unwrap() else {
+            panic!("pairing was not completed");
+        };
+        (pairing, credential)
+    })
+}
+
+fn app() -> Router {
+    app_with_policy(VaultPolicy::default())
+}
+
+fn app_with_policy(policy: VaultPolicy) -> Router {
+    app_with(policy, MetadataHandle::default())
+}
+
+fn app_with(policy: VaultPolicy, metadata: MetadataHandle) -> Router {
+    app_with_pairing(policy, metadata, paired_client().0.clone())
+}
+
+fn app_with_pairing(policy: VaultPolicy, metadata: MetadataHandle, pairing: PairingHandle) -> Router {
+    router(state(policy, metadata, pairing))
+}
+
+fn state(policy: VaultPolicy, metadata: MetadataHandle, pairing: PairingHandle) -> Arc<AppState> {
+    state_with_throttle(policy, metadata, pairing, AuthThrottle::default())
+}
+
+fn state_with_throttle(policy: VaultPolicy, metadata: MetadataHandle, pairing: PairingHandle, throttle: AuthThrottle) -> Arc<AppState> {
+    Arc::new(AppState {
+        crypto: CryptoManager::new().expect("crypto"),
+        metadata,
+        policy,
+        passwords_dir: vault_dir(),
+        tokens: TokenSigner::new(),
+        pairing,
+        webhooks: Webhooks::default(),
+        throttle,
+        allowed_origins: AllowedOrigins::new(&[EXTENSION_ORIGIN]),
+    })
+}
+
+/// A token from `/api/auth`
+async fn token(app: &Router) -> String {
+    let response = app
+        .clone()
+        .oneshot(json_request(Method::POST, "/api/auth", json!({ "master_password": MASTER_PASSWORD })))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let auth: AuthResponse = serde_json::from_value(body_json(response).await).unwrap();
+    auth.token
+}
+
+/// The request as sent by the paired extension
+fn paired(mut request: Request<Body>) -> Request<Body> {
+    request
+        .headers_mut()
+        .insert(CLIENT_HEADER, paired_client().1.parse().unwrap());
+    request
+}
+
+fn authorized(request: Request<Body>, token: &str) -> Request<Body> {
+    let mut request = paired(request);
+    request
+        .headers_mut()
+        .insert(header::AUTHORIZATION, format!("Bearer {}", token).parse().unwrap());
+    request
+}
+
+/// A JSON request from the paired extension
+fn json_request(method: Method, uri: &str, body: Value) -> Request<Body> {
+    paired(
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap(),
+    )
+}
+
+async fn body_json(response: axum::response::Response) -> Value {
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+#[tokio::test]
+async fn health_reports_service() {
+    let response = app()
+        .oneshot(Request::get("/health").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(body_json(response).await, json!({ "status": "ok", "service": "rpm-api" }));
+}
+
+#[tokio::test]
+async fn health_reports_published_vault_stats() {
+    let metadata = MetadataHandle::default();
+    metadata.publish_stats(VaultStats {
+        entries: 42,
+        last_backup_at: Some(Utc::now() - Duration::days(35)),
+        audit_findings: Some(0),
+        sync: SyncStatus::Ok,
+    });
+    let response = app_with(VaultPolicy::default(), metadata.clone())
+        .oneshot(Request::get("/health").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let health = body_json(response).await;
+    assert_eq!(health["status"], "ok");
+    assert_eq!(health["vault"]["entries"], 42);
+    assert_eq!(health["vault"]["backup_age_days"], 35);
+    assert_eq!(health["vault"]["audit_findings"], 0);
+    assert_eq!(health["vault"]["sync"], "ok");
+    assert_eq!(health["vault"]["warnings"], json!(["backup_overdue"]));
+
+    // Locking the vault keeps the counts
+    metadata.clear();
+    metadata.publish_stats(VaultStats { audit_findings: Some(3), ..VaultStats::default() });
+    let response = app_with(VaultPolicy::default(), metadata)
+        .oneshot(Request::get("/health").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let health = body_json(response).await;
+    assert_eq!(health["vault"]["backup_age_days"], Value::Null);
+    assert_eq!(health["vault"]["warnings"], json!(["no_backup", "audit_findings"]));
+}
+
+#[tokio::test]
+async fn auth_issues_token_valid_for_a_day() {
+    let response = app()
+        .oneshot(json_request(Method::POST, "/api/auth", json!({ "master_password": MASTER_PASSWORD })))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let auth: AuthResponse = serde_json::from_value(body_json(response).await).unwrap();
+    assert!(!auth.token.is_empty());
+    let lifetime = auth.expires_at - Utc::now();
+    assert!(lifetime > Duration::hours(23) && lifetime <= Duration::hours(24));
+}
+
+#[tokio::test]
+async fn auth_token_lifetime_is_capped_by_auto_lock_policy() {
+    let policy = VaultPolicy {
+        max_auto_lock_seconds: Some(600),
+        ..VaultPolicy::default()
+    };
+    let response = app_with_policy(policy)
+        .oneshot(json_request(Method::POST, "/api/auth", json!({ "master_password": MASTER_PASSWORD })))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let auth: AuthResponse = serde_json::from_value(body_json(response).await).unwrap();
+    let lifetime = auth.expires_at - Utc::now();
+    assert!(lifetime > Duration::minutes(9) && lifetime <= Duration::minutes(10));
+}
+
+#[tokio::test]
+async fn auth_is_refused_when_policy_requires_a_keyfile() {
+    let policy = VaultPolicy {
+        require_keyfile: true,
+        ..VaultPolicy::default()
+    };
+    let response = app_with_policy(policy)
+        .oneshot(json_request(Method::POST, "/api/auth", json!({ "master_password": MASTER_PASSWORD })))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn auth_rejects_a_wrong_master_password() {
+    let response = app()
+        .oneshot(json_request(Method::POST, "/api/auth", json!({ "master_password": "wrong" })))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn failed_auth_attempts_back_off_and_lock_the_client_out() {
+    let throttle = AuthThrottle::new(2, 60);
+    let app = router(state_with_throttle(
+        VaultPolicy::default(),
+        MetadataHandle::default(),
+        paired_client().0.clone(),
+        throttle.clone(),
+    ));
+    let attempt = |password: &str| app.clone().oneshot(json_request(Method::POST, "/api/auth", json!({ "master_password": password })));
+
+    assert_eq!(attempt("wrong").await.unwrap().status(), StatusCode::UNAUTHORIZED);
+    // Even the right password waits out the backoff
+    let response = attempt(MASTER_PASSWORD).await.unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    let retry_after: i64 = response.headers()[header::RETRY_AFTER].to_str().unwrap().parse().unwrap();
+    assert!((1..=2).contains(&retry_after));
+    assert_eq!(throttle.failed_attempts(), 1);
+    assert!(throttle.locked_out(Utc::now()).is_empty());
+
+    // The second failure in a row locks the client out for the whole window
+    let client_id = paired_client().1.split_once('.').unwrap().0.to_string();
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+    assert_eq!(attempt("wrong").await.unwrap().status(), StatusCode::UNAUTHORIZED);
+    let response = attempt(MASTER_PASSWORD).await.unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    let retry_after: i64 = response.headers()[header::RETRY_AFTER].to_str().unwrap().parse().unwrap();
+    assert!((59..=60).contains(&retry_after));
+    assert_eq!(throttle.failed_attempts(), 2);
+    let locked = throttle.locked_out(Utc::now());
+    assert_eq!(locked.len(), 1);
+    assert_eq!(locked[0].0, client_id);
+}
+
+#[test]
+fn auth_throttle_forgets_old_failures_and_clears_on_success() {
+    let throttle = AuthThrottle::new(3, 60);
+    let start = Utc::now();
+    throttle.begin("a", start).unwrap().finish(false, start);
+    throttle.begin("a", start + Duration::seconds(1)).unwrap().finish(false, start + Duration::seconds(1));
+    // 1, then 2 seconds after the second failure
+    assert!(throttle.begin("a", start + Duration::seconds(2)).is_err());
+    // Other clients are not affected
+    assert!(throttle.begin("b", start + Duration::seconds(2)).is_ok());
+
+    // Only one attempt of a client at a time
+    let pending = throttle.begin("a", start + Duration::seconds(3)).unwrap();
+    assert!(throttle.begin("a", start + Duration::seconds(3)).is_err());
+    pending.finish(true, start + Duration::seconds(3));
+    assert!(throttle.begin("a", start + Duration::seconds(3)).is_ok());
+
+    for second in 0..3 {
+        let now = start + Duration::seconds(10 + second * 10);
+        throttle.begin("c", now).unwrap().finish(false, now);
+    }
+    assert_eq!(throttle.locked_out(start + Duration::seconds(40)).len(), 1);
+    assert!(throttle.begin("c", start + Duration::seconds(80)).is_err());
+    assert!(throttle.begin("c", start + Duration::seconds(91)).is_ok());
+    assert!(throttle.locked_out(start + Duration::seconds(91)).is_empty());
+    assert_eq!(throttle.failed_attempts(), 5);
+}
+
+#[tokio::test]
+async fn password_endpoints_require_a_valid_token() {
+    let app = app();
+    // Tokens of another server (e.g. before a restart) are not accepted
+    let foreign = token(&app_with_policy(VaultPolicy::default())).await;
+    let token = token(&app).await;
+    let meta = || Request::get("/api/passwords/meta").body(Body::empty()).unwrap();
+
+    let missing = app.clone().oneshot(paired(meta())).await.unwrap();
+    assert_eq!(missing.status(), StatusCode::UNAUTHORIZED);
+
+    // Change the first character of the signature
+    let signature = token.rfind('.').unwrap() + 1;
+    let replacement = if token[signature..].starts_with('A') { "B" } else { "A" };
+    let mut tampered = token.clone();
+    tampered.replace_range(signature..signature + 1, replacement);
+    let forged = app.clone().oneshot(authorized(meta(), &tampered)).await.unwrap();
+    assert_eq!(forged.status(), StatusCode::UNAUTHORIZED);
+
+    let foreign = app.clone().oneshot(authorized(meta(), &foreign)).await.unwrap();
+    assert_eq!(foreign.status(), StatusCode::UNAUTHORIZED);
+
+    let valid = app.oneshot(authorized(meta(), &token)).await.unwrap();
+    assert_eq!(valid.status(), StatusCode::LOCKED);
+}
+
+#[tokio::test]
+async fn pairing_issues_a_credential_once_the_user_approves() {
+    let pairing = PairingHandle::default();
+    let app = app_with_pairing(VaultPolicy::default(), MetadataHandle::default(), pairing.clone());
+    let unpaired = |request: Request<Body>| {
+        let (mut parts, body) = request.into_parts();
+        parts.headers.remove(CLIENT_HEADER);
+        Request::from_parts(parts, body)
+    };
+
+    let response = app
+        .clone()
+        .oneshot(unpaired(json_request(Method::POST, "/api/pair", json!({ "client_name": "Firefox" }))))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+    let request: PairResponse = serde_json::from_value(body_json(response).await).unwrap();
+
+    // The TUI shows the code of the waiting request
+    let shown = pairing.pending().unwrap();
+    assert_eq!(shown.id, request.request_id);
+    assert_eq!(shown.client_name, "Firefox");
+    assert_eq!(shown.code.len(), 6);
+
+    let send_code = |code: &str| {
+        unpaired(json_request(
+            Method::POST,
+            &format!("/api/pair/{}", request.request_id),
+            json!({ "code": code }),
+        ))
+    };
+    let waiting = app.clone().oneshot(send_code(&shown.code)).await.unwrap();
+    assert_eq!(waiting.status(), StatusCode::ACCEPTED);
+
+    pairing.approve(&shown.id);
+    assert!(pairing.pending().is_none());
+    let wrong_code = if shown.code == "000000" { "111111" } else { "000000" };
+    let wrong = app.clone().oneshot(send_code(wrong_code)).await.unwrap();
+    assert_eq!(wrong.status(), StatusCode::FORBIDDEN);
+
+    let done = app.clone().oneshot(send_code(&shown.code)).await.unwrap();
+    assert_eq!(done.status(), StatusCode::OK);
+    let paired: PairedResponse = serde_json::from_value(body_json(done).await).unwrap();
+    assert_eq!(pairing.clients().len(), 1);
+    assert_eq!(pairing.clients()[0].name, "Firefox");
+
+    // The request is used up
+    let again = app.clone().oneshot(send_code(&shown.code)).await.unwrap();
+    assert_eq!(again.status(), StatusCode::NOT_FOUND);
+
+    let mut auth = unpaired(json_request(Method::POST, "/api/auth", json!({ "master_password": MASTER_PASSWORD })));
+    auth.headers_mut().insert(CLIENT_HEADER, paired.credential.parse().unwrap());
+    let auth = app.oneshot(auth).await.unwrap();
+    assert_eq!(auth.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn unpaired_clients_are_forbidden() {
+    let pairing = PairingHandle::default();
+    let app = app_with_pairing(VaultPolicy::default(), MetadataHandle::default(), pairing.clone());
+
+    // The credential of another server's client means nothing here
+    let stranger = app
+        .clone()
+        .oneshot(json_request(Method::POST, "/api/auth", json!({ "master_password": MASTER_PASSWORD })))
+        .await
+        .unwrap();
+    assert_eq!(stranger.status(), StatusCode::FORBIDDEN);
+
+    let anonymous = app
+        .clone()
+        .oneshot(Request::get("/api/passwords").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(anonymous.status(), StatusCode::FORBIDDEN);
+
+    let first = app
+        .clone()
+        .oneshot(json_request(Method::POST, "/api/pair", json!({ "client_name": "Chrome" })))
+        .await
+        .unwrap();
+    assert_eq!(first.status(), StatusCode::ACCEPTED);
+    let first: PairResponse = serde_json::from_value(body_json(first).await).unwrap();
+
+    // One request waits at a time
+    let second = app
+        .clone()
+        .oneshot(json_request(Method::POST, "/api/pair", json!({ "client_name": "Evil" })))
+        .await
+        .unwrap();
+    assert_eq!(second.status(), StatusCode::CONFLICT);
+
+    let code = pairing.pending().unwrap().code;
+    pairing.deny(&first.request_id);
+    let denied = app
+        .oneshot(json_request(
+            Method::POST,
+            &format!("/api/pair/{}", first.request_id),
+            json!({ "code": code }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(denied.status(), StatusCode::FORBIDDEN);
+    assert!(pairing.clients().is_empty());
+}
+
+#[tokio::test]
+async fn auth_tokens_are_unique() {
+    let app = app();
+    let mut tokens = Vec::new();
+    for _ in 0..2 {
+        let response = app
+            .clone()
+            .oneshot(json_request(Method::POST, "/api/auth", json!({ "master_password": MASTER_PASSWORD })))
+            .await
+            .unwrap();
+        let auth: AuthResponse = serde_json::from_value(body_json(response).await).unwrap();
+        tokens.push(auth.token);
+    }
+    assert_ne!(tokens[0], tokens[1]);
+}
+
+#[tokio::test]
+async fn malformed_bodies_are_rejected() {
+    let app = app();
+
+    let missing_field = app
+        .clone()
+        .oneshot(json_request(Method::POST, "/api/auth", json!({})))
+        .await
+        .unwrap();
+    assert_eq!(missing_field.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let invalid_json = app
+        .clone()
+        .oneshot(paired(
+            Request::post("/api/auth")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from("{"))
+                .unwrap(),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(invalid_json.status(), StatusCode::BAD_REQUEST);
+
+    let no_content_type = app
+        .oneshot(paired(Request::post("/api/auth").body(Body::from("{}")).unwrap()))
+        .await
+        .unwrap();
+    assert_eq!(no_content_type.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+}
+
+/// The password endpoints need the vault the TUI lends while unlocked
+#[tokio::test]
+async fn password_endpoints_are_locked_until_the_tui_publishes_the_vault() {
+    let app = app();
+    let token = token(&app).await;
+
+    let list = app
+        .clone()
+        .oneshot(authorized(Request::get("/api/passwords").body(Body::empty()).unwrap(), &token))
+        .await
+        .unwrap();
+    assert_eq!(list.status(), StatusCode::LOCKED);
+
+    let create = app
+        .oneshot(authorized(
+            json_request(
+                Method::POST,
+                "/api/passwords",
+                json!({ "title": "github", "username": "alice", "password": "pw", "url": null, "notes": null, "tags": [] }),
+            ),
+            &token,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(create.status(), StatusCode::LOCKED);
+}
+
+#[tokio::test]
+async fn password_crud_round_trip() {
+    let dir = std::env::temp_dir().join(format!("rpm-server-crud-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let config = Config {
+        passwords_directory: Some(dir.clone()),
+        ..Config::default()
+    };
+    let metadata = MetadataHandle::default();
+    metadata.publish_vault(
+        PasswordStorage::new(&config, CryptoManager::new().expect("crypto")),
+        SecureKey::new(vec![7; 32]),
+    );
+    let app = app_with(VaultPolicy::default(), metadata.clone());
+    let token = token(&app).await;
+
+    let create = app
+        .clone()
+        .oneshot(authorized(
+            json_request(
+                Method::POST,
+                "/api/passwords",
+                json!({ "title": "github", "username": "alice", "password": "pw", "url": "https://github.com", "notes": null }),
+            ),
+            &token,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(create.status(), StatusCode::CREATED);
+    let created = body_json(create).await;
+    let id = created["id"].as_str().unwrap().to_string();
+    assert_eq!(created["password"], "pw");
+    assert_eq!(created["username"], "alice");
+
+    let duplicate = app
+        .clone()
+        .oneshot(authorized(
+            json_request(
+                Method::POST,
+                "/api/passwords",
+                json!({ "title": "github", "username": null, "password": "other", "url": null, "notes": null }),
+            ),
+            &token,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(duplicate.status(), StatusCode::CONFLICT);
+
+    let update = app
+        .clone()
+        .oneshot(authorized(
+            json_request(
+                Method::PUT,
+                &format!("/api/passwords/{}", id),
+                json!({ "password": "new-pw", "username": "", "tags": ["work"] }),
+            ),
+            &token,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(update.status(), StatusCode::OK);
+
+    let fetched = app
+        .clone()
+        .oneshot(authorized(Request::get(format!("/api/passwords/{}", id)).body(Body::empty()).unwrap(), &token))
+        .await
+        .unwrap();
+    assert_eq!(fetched.status(), StatusCode::OK);
+    let fetched = body_json(fetched).await;
+    assert_eq!(fetched["title"], "github");
+    assert_eq!(fetched["password"], "new-pw");
+    assert_eq!(fetched["username"], Value::Null);
+    assert_eq!(fetched["url"], "https://github.com");
+    assert_eq!(fetched["tags"], json!(["work"]));
+
+    let list = app
+        .clone()
+        .oneshot(authorized(Request::get("/api/passwords").body(Body::empty()).unwrap(), &token))
+        .await
+        .unwrap();
+    assert_eq!(body_json(list).await.as_array().unwrap().len(), 1);
+
+    let delete = app
+        .clone()
+        .oneshot(authorized(Request::delete(format!("/api/passwords/{}", id)).body(Body::empty()).unwrap(), &token))
+        .await
+        .unwrap();
+    assert_eq!(delete.status(), StatusCode::NO_CONTENT);
+
+    let gone = app
+        .clone()
+        .oneshot(authorized(Request::get(format!("/api/passwords/{}", id)).body(Body::empty()).unwrap(), &token))
+        .await
+        .unwrap();
+    assert_eq!(gone.status(), StatusCode::NOT_FOUND);
+
+    metadata.clear();
+    let locked = app
+        .oneshot(authorized(Request::get("/api/passwords").body(Body::empty()).unwrap(), &token))
+        .await
+        .unwrap();
+    assert_eq!(locked.status(), StatusCode::LOCKED);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn access_windows_parse_and_wrap_past_midnight() {
+    let weekdays = AccessWindow::parse("Mon-Fri 08:00-20:00").unwrap();
+    assert_eq!(weekdays.to_string(), "mon-fri 08:00-20:00");
+    // 2026-01-05 is a Monday
+    let at = |day: u32, hour: u32| NaiveDate::from_ymd_opt(2026, 1, day).unwrap().and_hms_opt(hour, 0, 0).unwrap();
+    assert!(weekdays.allows(at(5, 8)));
+    assert!(!weekdays.allows(at(5, 20)));
+    assert!(!weekdays.allows(at(10, 12)));
+
+    let night = AccessWindow::parse("fri 22:00-02:00").unwrap();
+    assert!(night.allows(at(9, 23)));
+    assert!(night.allows(at(10, 1)));
+    assert!(!night.allows(at(10, 23)));
+
+    assert_eq!(AccessWindow::parse("sun,sat 10:00-14:00").unwrap().to_string(), "sat,sun 10:00-14:00");
+    assert_eq!(AccessWindow::parse("sat-fri 09:00-10:00").unwrap().to_string(), "09:00-10:00");
+    for invalid in ["", "mon-fri", "someday 08:00-20:00", "08:00-08:00", "8-20"] {
+        assert!(AccessWindow::parse(invalid).is_err(), "{}", invalid);
+    }
+}
+
+#[tokio::test]
+async fn entries_outside_their_access_window_are_not_served() {
+    let dir = std::env::temp_dir().join(format!("rpm-server-window-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let config = Config {
+        passwords_directory: Some(dir.clone()),
+        ..Config::default()
+    };
+    let storage = PasswordStorage::new(&config, CryptoManager::new().expect("crypto"));
+    let key = vec![7; 32];
+    // An hour-long window starting two hours from now is closed whatever the time
+    let hour = (Local::now().hour() + 2) % 24;
+    let closed = AccessWindow {
+        days: Vec::new(),
+        start: NaiveTime::from_hms_opt(hour, 0, 0).unwrap(),
+        end: NaiveTime::from_hms_opt((hour + 1) % 24, 0, 0).unwrap(),
+    };
+    let mut ids = Vec::new();
+    for (title, access_window) in [("personal", None), ("work", Some(closed))] {
+        let id = storage.add_entry(title, &key).unwrap();
+        storage.update_password_file(&id, "pw", &key).unwrap();
+        let details = EntryDetails { access_window, ..EntryDetails::default() };
+        storage.update_entry_details(&id, &details, &key).unwrap();
+        ids.push(id);
+    }
+    let metadata = MetadataHandle::default();
+    metadata.publish_vault(storage, SecureKey::new(key));
+    let app = app_with(VaultPolicy::default(), metadata);
+    let token = token(&app).await;
+
+    let list = app
+        .clone()
+        .oneshot(authorized(Request::get("/api/passwords").body(Body::empty()).unwrap(), &token))
+        .await
+        .unwrap();
+    let list = body_json(list).await;
+    let titles: Vec<&str> = list.as_array().unwrap().iter().map(|record| record["title"].as_str().unwrap()).collect();
+    assert_eq!(titles, ["personal"]);
+
+    let open = app
+        .clone()
+        .oneshot(authorized(Request::get(format!("/api/passwords/{}", ids[0])).body(Body::empty()).unwrap(), &token))
+        .await
+        .unwrap();
+    assert_eq!(open.status(), StatusCode::OK);
+
+    let closed = app
+        .clone()
+        .oneshot(authorized(Request::get(format!("/api/passwords/{}", ids[1])).body(Body::empty()).unwrap(), &token))
+        .await
+        .unwrap();
+    assert_eq!(closed.status(), StatusCode::FORBIDDEN);
+
+    let update = app
+        .oneshot(authorized(
+            json_request(Method::PUT, &format!("/api/passwords/{}", ids[1]), json!({ "password": "new-pw" })),
+            &token,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(update.status(), StatusCode::FORBIDDEN);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn entry_meta_is_locked_until_the_tui_publishes_it() {
+    let app = app();
+    let token = token(&app).await;
+    let response = app
+        .oneshot(authorized(Request::get("/api/passwords/meta").body(Body::empty()).unwrap(), &token))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::LOCKED);
+}
+
+#[tokio::test]
+async fn entry_meta_lists_published_entries_without_secrets() {
+    let metadata = MetadataHandle::default();
+    metadata.publish(vec![EntryMeta {
+        id: "0.pwd".to_string(),
+        title: "github/alice".to_string(),
+        username: Some("alice".to_string()),
+        url: Some("https://github.com/login".to_string()),
+        icon_url: Some("https://github.com/favicon.ico".to_string()),
+        tags: vec!["work".to_string()],
+        folder: None,
+        updated_at: None,
+    }]);
+    let app = app_with(VaultPolicy::default(), metadata.clone());
+    let token = token(&app).await;
+
+    let response = app
+        .clone()
+        .oneshot(authorized(Request::get("/api/passwords/meta").body(Body::empty()).unwrap(), &token))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_json(response).await;
+    assert_eq!(body[0]["title"], "github/alice");
+    assert_eq!(body[0]["username"], "alice");
+    assert_eq!(body[0]["icon_url"], "https://github.com/favicon.ico");
+    assert!(body[0].get("password").is_none());
+
+    metadata.clear();
+    let response = app
+        .oneshot(authorized(Request::get("/api/passwords/meta").body(Body::empty()).unwrap(), &token))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::LOCKED);
+}
+
+#[tokio::test]
+async fn bind_falls_back_to_the_next_free_port() {
+    let taken = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = taken.local_addr().unwrap().port();
+
+    let listener = bind(port, 5).await.unwrap();
+    let fallback = listener.local_addr().unwrap().port();
+    assert!(fallback > port && fallback <= port + 5);
+
+    assert!(bind(port, 0).await.is_err());
+}
+
+#[tokio::test]
+async fn unknown_routes_and_methods() {
+    let app = app();
+
+    let missing = app
+        .clone()
+        .oneshot(Request::get("/api/unknown").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+
+    let token = token(&app).await;
+    let wrong_method = app
+        .oneshot(authorized(Request::delete("/api/passwords").body(Body::empty()).unwrap(), &token))
+        .await
+        .unwrap();
+    assert_eq!(wrong_method.status(), StatusCode::METHOD_NOT_ALLOWED);
+}
+
+#[tokio::test]
+async fn tls_server_serves_its_pinned_certificate() {
+    let dir = std::env::temp_dir().join(format!("rpm-server-tls-{}", std::process::id()));
+    let (certificate, key) = (dir.join("cert.pem"), dir.join("key.pem"));
+    let identity = TlsIdentity::generate().unwrap();
+    identity.save(&certificate, &key).unwrap();
+    let identity = TlsIdentity::load(&certificate, &key).unwrap();
+    let fingerprint = identity.fingerprint().unwrap();
+    assert_eq!(fingerprint.len(), 64);
+    assert_eq!(TlsIdentity::load(&certificate, &key).unwrap().fingerprint().unwrap(), fingerprint);
+
+    let listener = bind(0, 0).await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let (shutdown_tx, shutdown_rx) = tokio::sync::watch::channel(());
+    let pem = identity.certificate_pem().to_string();
+    let server = tokio::spawn(async move {
+        start_tls_server(listener, &identity, state(VaultPolicy::default(), MetadataHandle::default(), PairingHandle::default()), shutdown_rx)
+            .await
+            .unwrap();
+    });
+
+    // A client that trusts only this certificate
+    let body = tokio::task::spawn_blocking(move || {
+        use rustls::pki_types::{pem::PemObject, CertificateDer};
+        let mut roots = rustls::RootCertStore::empty();
+        roots.add(CertificateDer::from_pem_slice(pem.as_bytes()).unwrap()).unwrap();
+        let tls = rustls::ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .unwrap()
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+        ureq::AgentBuilder::new()
+            .tls_config(Arc::new(tls))
+            .build()
+            .get(&format!("https://localhost:{}/health", port))
+            .call()
+            .unwrap()
+            .into_string()
+            .unwrap()
+    })
+    .await
+    .unwrap();
+    assert_eq!(serde_json::from_str::<Value>(&body).unwrap()["service"], "rpm-api");
+
+    shutdown_tx.send(()).unwrap();
+    server.await.unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn events_push_entry_changes_to_paired_clients() {
+    use futures_util::StreamExt;
+    use tokio_tungstenite::tungstenite::{client::IntoClientRequest, Message};
+
+    let dir = std::env::temp_dir().join(format!("rpm-server-events-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let config = Config {
+        passwords_directory: Some(dir.clone()),
+        ..Config::default()
+    };
+    let storage = PasswordStorage::new(&config, CryptoManager::new().expect("crypto"));
+    let key = vec![7; 32];
+    let metadata = MetadataHandle::default();
+    metadata.publish(storage.list_entry_meta(&key).unwrap());
+    metadata.publish_vault(storage, SecureKey::new(key));
+    let state = state(VaultPolicy::default(), metadata.clone(), paired_client().0.clone());
+    let app = router(state.clone());
+    let token = token(&app).await;
+
+    let listener = bind(0, 0).await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let (shutdown_tx, shutdown_rx) = tokio::sync::watch::channel(());
+    let server = tokio::spawn(start_server(listener, state, shutdown_rx));
+
+    // Without the credentials the upgrade is refused
+    let url = format!("ws://127.0.0.1:{}/api/events", port);
+    assert!(tokio_tungstenite::connect_async(url.as_str()).await.is_err());
+
+    // What a browser sends: the token and credential as subprotocols
+    let mut request = url.as_str().into_client_request().unwrap();
+    let protocols = format!("{}, token.{}, client.{}", EVENTS_PROTOCOL, token, paired_client().1);
+    request.headers_mut().insert("sec-websocket-protocol", protocols.parse().unwrap());
+    let (mut socket, response) = tokio_tungstenite::connect_async(request).await.unwrap();
+    assert_eq!(response.headers()["sec-websocket-protocol"], EVENTS_PROTOCOL);
+    async fn next_event<S>(socket: &mut S) -> Value
+    where
+        S: futures_util::Stream<Item = Result<Message, tokio_tungstenite::tungstenite::Error>> + Unpin,
+    {
+        let message = tokio::time::timeout(std::time::Duration::from_secs(5), socket.next()).await;
+        match message.unwrap().unwrap().unwrap() {
+            Message::Text(text) => serde_json::from_str(&text).unwrap(),
+            other => panic!("unexpected message {:?}", other),
+        }
+    }
+
+    let create = app
+        .clone()
+        .oneshot(authorized(
+            json_request(
+                Method::POST,
+                "/api/passwords",
+                json!({ "title": "github", "username": "alice", "password": "pw", "url": null, "notes": null }),
+            ),
+            &token,
+        ))
+        .await
+        .unwrap();
+    let id = body_json(create).await["id"].as_str().unwrap().to_string();
+    assert_eq!(next_event(&mut socket).await, json!({ "type": "added", "id": id }));
+
+    // A password change alone is announced too
+    app.clone()
+        .oneshot(authorized(
+            json_request(Method::PUT, &format!("/api/passwords/{}", id), json!({ "password": "new-pw" })),
+            &token,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(next_event(&mut socket).await, json!({ "type": "changed", "id": id }));
+
+    app.clone()
+        .oneshot(authorized(
+            Request::builder()
+                .method(Method::DELETE)
+                .uri(format!("/api/passwords/{}", id))
+                .body(Body::empty())
+                .unwrap(),
+            &token,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(next_event(&mut socket).await, json!({ "type": "deleted", "id": id }));
+
+    // Locking ends the stream: the client has to authenticate again
+    metadata.events().publish(VaultEvent::Locked);
+    let closed = tokio::time::timeout(std::time::Duration::from_secs(5), socket.next()).await.unwrap();
+    assert!(matches!(closed, None | Some(Ok(Message::Close(_)))));
+
+    shutdown_tx.send(()).unwrap();
+    tokio::time::timeout(std::time::Duration::from_secs(5), server).await.unwrap().unwrap().unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn cors_preflight_allows_extensions() {
+    let response = app()
+        .oneshot(
+            Request::builder()
+                .method(Method::OPTIONS)
+                .uri("/api/auth")
+                .header(header::ORIGIN, EXTENSION_ORIGIN)
+                .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+                .header(header::ACCESS_CONTROL_REQUEST_HEADERS, "content-type")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let headers = response.headers();
+    assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_ORIGIN], EXTENSION_ORIGIN);
+    assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_METHODS], "GET,POST,PUT,DELETE");
+    assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_HEADERS], "authorization,content-type,x-rpm-client");
+}
+
+#[tokio::test]
+async fn cors_headers_on_responses() {
+    let response = app()
+        .oneshot(
+            Request::get("/health")
+                .header(header::ORIGIN, EXTENSION_ORIGIN)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN], EXTENSION_ORIGIN);
+}
+
+#[tokio::test]
+async fn requests_from_other_origins_are_rejected() {
+    for origin in ["https://evil.example", "chrome-extension://otherextension", "moz-extension://abcdefghijklmnop", "null"] {
+        let mut request = json_request(Method::POST, "/api/pair", json!({ "client_name": "evil" }));
+        request.headers_mut().insert(header::ORIGIN, origin.parse().unwrap());
+        let response = app().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN, "{}", origin);
+        assert!(response.headers().get(header::ACCESS_CONTROL_ALLOW_ORIGIN).is_none());
+    }
+
+    // Clients outside a browser send no Origin and are left to the other checks
+    let response = app().oneshot(Request::get("/health").body(Body::empty()).unwrap()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[test]
+fn allowed_origins_accept_extension_origins_only() {
+    assert_eq!(extension_origin("chrome-extension://ABCDEFGHIJKLMNOP/").unwrap(), EXTENSION_ORIGIN);
+    assert_eq!(
+        extension_origin(" moz-extension://0b5a1c2e-1111-4a4a-9c9c-123456789abc ").unwrap(),
+        "moz-extension://0b5a1c2e-1111-4a4a-9c9c-123456789abc"
+    );
+    for entry in ["https://example.com", "chrome-extension://", "moz-extension://x/y", "*"] {
+        assert!(extension_origin(entry).is_err(), "{}", entry);
+    }
+
+    // Malformed entries are dropped, the rest still apply
+    let origins = AllowedOrigins::new(&["*", EXTENSION_ORIGIN]);
+    assert!(origins.allows(&EXTENSION_ORIGIN.parse().unwrap()));
+    assert!(!origins.allows(&"https://example.com".parse().unwrap()));
+    assert!(!AllowedOrigins::default().allows(&EXTENSION_ORIGIN.parse().unwrap()));
+}
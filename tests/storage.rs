@@ -0,0 +1,254 @@
+use rpm::backup::{create_backup, create_snapshot, prune_snapshots, restore_backup, scheduled_backup};
+use rpm::config::{Config, DirectoryConfig};
+use rpm::crypto::CryptoManager;
+use rpm::storage::atomic::{write_atomic, write_atomic_private};
+use rpm::storage::{IntegrityIssue, Orphan, OrphanRepair, PasswordStorage, QUARANTINE_DIR};
+
+fn temp_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("rpm-storage-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn file_names(dir: &std::path::Path) -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn vault_files_are_replaced_without_leaving_temporary_files() {
+    let dir = temp_dir("atomic");
+    let target = dir.join("def");
+    write_atomic(&target, "old").unwrap();
+    write_atomic(&target, "new").unwrap();
+    assert_eq!(std::fs::read_to_string(&target).unwrap(), "new");
+    // A write that can't happen leaves the directory as it was
+    assert!(write_atomic(&dir.join("missing").join("def"), "new").is_err());
+    assert_eq!(file_names(&dir), vec!["def".to_string()]);
+
+    let config = Config {
+        passwords_directory: Some(dir.clone()),
+        ..Config::default()
+    };
+    let storage = PasswordStorage::new(&config, CryptoManager::new().unwrap());
+    let key = vec![7; 32];
+    std::fs::remove_file(&target).unwrap();
+    let id = storage.add_entry("github", &key).unwrap();
+    storage.update_password_file(&id, "hunter2", &key).unwrap();
+    storage.delete_entry(&id, &key).unwrap();
+    DirectoryConfig::default().save(&dir).unwrap();
+    assert!(file_names(&dir).iter().all(|name| !name.ends_with(".tmp")), "{:?}", file_names(&dir));
+    assert_eq!(storage.load_trash_file(&key).unwrap().entries.len(), 1);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[cfg(unix)]
+#[test]
+fn private_files_are_readable_by_the_owner_only() {
+    use std::os::unix::fs::PermissionsExt;
+    let dir = temp_dir("private");
+    let target = dir.join("config.toml");
+    std::fs::write(&target, "webdav_password = \"old\"").unwrap();
+    std::fs::set_permissions(&target, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+    write_atomic_private(&target, "webdav_password = \"new\"").unwrap();
+    assert_eq!(std::fs::metadata(&target).unwrap().permissions().mode() & 0o777, 0o600);
+    assert_eq!(std::fs::read_to_string(&target).unwrap(), "webdav_password = \"new\"");
+    assert_eq!(file_names(&dir), vec!["config.toml".to_string()]);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn verification_reports_every_kind_of_damage() {
+    let dir = temp_dir("verify");
+    let config = Config {
+        passwords_directory: Some(dir.clone()),
+        ..Config::default()
+    };
+    let storage = PasswordStorage::new(&config, CryptoManager::new().unwrap());
+    let key = vec![7; 32];
+    let ids: Vec<String> = ["intact", "corrupted", "tampered", "missing"]
+        .iter()
+        .map(|name| {
+            let id = storage.add_entry(name, &key).unwrap();
+            storage.update_password_file(&id, "hunter2", &key).unwrap();
+            id
+        })
+        .collect();
+    let report = storage.verify(&key).unwrap();
+    assert!(report.is_ok(), "{:?}", report.issues);
+    assert_eq!(report.entries_checked, 4);
+    assert!(!storage.verify(&[8; 32]).unwrap().is_ok());
+
+    std::fs::write(dir.join(&ids[1]), "{ not json").unwrap();
+    let mut file: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(dir.join(&ids[2])).unwrap()).unwrap();
+    let other: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(dir.join(&ids[0])).unwrap()).unwrap();
+    file["encrypted_password"] = other["encrypted_password"].clone();
+    std::fs::write(dir.join(&ids[2]), file.to_string()).unwrap();
+    std::fs::rename(dir.join(&ids[3]), dir.join("stray.pwd")).unwrap();
+
+    let report = storage.verify(&key).unwrap();
+    assert_eq!(report.entries_checked, 4);
+    assert!(matches!(&report.issues[0], IntegrityIssue::CorruptedFile { filename, .. } if *filename == ids[1]));
+    assert_eq!(
+        report.issues[1..],
+        [
+            IntegrityIssue::UndecryptableFile { filename: ids[2].clone() },
+            IntegrityIssue::MissingFile { filename: ids[3].clone() },
+            IntegrityIssue::UnreferencedFile { filename: "stray.pwd".to_string() },
+        ]
+    );
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn orphans_are_relinked_quarantined_or_deleted() {
+    let dir = temp_dir("orphans");
+    let config = Config {
+        passwords_directory: Some(dir.clone()),
+        ..Config::default()
+    };
+    let storage = PasswordStorage::new(&config, CryptoManager::new().unwrap());
+    let key = vec![7; 32];
+    let ids: Vec<String> = ["lost", "stray", "junk", "gone"]
+        .iter()
+        .map(|name| {
+            let id = storage.add_entry(name, &key).unwrap();
+            storage.update_password_file(&id, "hunter2", &key).unwrap();
+            id
+        })
+        .collect();
+    assert!(storage.find_orphans(&key).unwrap().is_empty());
+
+    // Three files lose their entries, one entry loses its file
+    let mut def_file = storage.load_def_file(&key).unwrap();
+    def_file.entries.retain(|e| e.encrypted_filename == ids[3]);
+    storage.save_def_file(&def_file, &key).unwrap();
+    std::fs::remove_file(dir.join(&ids[3])).unwrap();
+    let mut files: Vec<Orphan> = ids[..3].iter().map(|id| Orphan::File { filename: id.clone() }).collect();
+    files.sort_by(|a, b| a.filename().cmp(b.filename()));
+    let entry = Orphan::Entry { filename: ids[3].clone() };
+    let orphans = storage.find_orphans(&key).unwrap();
+    assert_eq!(orphans[..3], files[..]);
+    assert_eq!(orphans[3], entry);
+
+    let lost = Orphan::File { filename: ids[0].clone() };
+    let relink = OrphanRepair::Relink { name: "Recovered".to_string() };
+    assert!(storage.repair_orphan(&entry, &relink, &key).is_err());
+    assert!(storage.repair_orphan(&lost, &relink, &[8; 32]).is_err());
+    storage.repair_orphan(&lost, &relink, &key).unwrap();
+    assert_eq!(
+        storage.list_decrypted_names(&key).unwrap(),
+        vec![(ids[3].clone(), "gone".to_string()), (ids[0].clone(), "Recovered".to_string())]
+    );
+    assert_eq!(storage.load_password_file(&ids[0], &key).unwrap(), "hunter2");
+
+    storage.repair_orphan(&Orphan::File { filename: ids[1].clone() }, &OrphanRepair::Quarantine, &key).unwrap();
+    storage.repair_orphan(&Orphan::File { filename: ids[2].clone() }, &OrphanRepair::Delete, &key).unwrap();
+    storage.repair_orphan(&entry, &OrphanRepair::Quarantine, &key).unwrap();
+    assert!(storage.find_orphans(&key).unwrap().is_empty());
+    assert!(!dir.join(&ids[1]).exists() && !dir.join(&ids[2]).exists());
+    let mut quarantined = vec![ids[1].clone(), format!("{}.entry", ids[3])];
+    quarantined.sort();
+    assert_eq!(file_names(&dir.join(QUARANTINE_DIR)), quarantined);
+    assert!(storage.verify(&key).unwrap().is_ok());
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn restoring_a_snapshot_removes_entries_added_since() {
+    let dir = temp_dir("restore");
+    let backups = temp_dir("restore-backups");
+    let config = Config {
+        passwords_directory: Some(dir.clone()),
+        ..Config::default()
+    };
+    let crypto = CryptoManager::new().unwrap();
+    let storage = PasswordStorage::new(&config, crypto.clone());
+    let key = vec![7; 32];
+    let kept = storage.add_entry("kept", &key).unwrap();
+    storage.update_password_file(&kept, "hunter2", &key).unwrap();
+    let snapshot = backups.join("snapshot.rpmbak");
+    create_snapshot(&dir, &snapshot).unwrap();
+    assert_eq!(file_names(&backups), vec!["snapshot.rpmbak".to_string()]);
+
+    let added = storage.add_entry("added", &key).unwrap();
+    storage.update_password_file(&added, "hunter3", &key).unwrap();
+    assert_eq!(restore_backup(&snapshot, &dir, "", &crypto).unwrap(), 1);
+
+    assert_eq!(storage.list_decrypted_names(&key).unwrap(), vec![(kept.clone(), "kept".to_string())]);
+    assert_eq!(storage.load_password_file(&kept, &key).unwrap(), "hunter2");
+    assert!(!dir.join(&added).exists());
+    assert!(file_names(&dir).iter().all(|name| !name.ends_with(".restore")), "{:?}", file_names(&dir));
+    let _ = std::fs::remove_dir_all(&dir);
+    let _ = std::fs::remove_dir_all(&backups);
+}
+
+#[test]
+fn pruning_keeps_the_newest_snapshots_and_every_manual_backup() {
+    let dir = temp_dir("prune");
+    let backups = temp_dir("prune-backups");
+    let crypto = CryptoManager::new().unwrap();
+    let storage = PasswordStorage::new(&Config { passwords_directory: Some(dir.clone()), ..Config::default() }, crypto.clone());
+    storage.add_entry("github", &[7; 32]).unwrap();
+
+    // The manual backups are older than every snapshot
+    create_backup(&dir, &backups.join("manual.rpmbak"), "passphrase", &crypto).unwrap();
+    create_snapshot(&dir, &backups.join("before-update.rpmbak")).unwrap();
+    for i in 1..=5 {
+        create_snapshot(&dir, &backups.join(format!("rpm-auto-{}.rpmbak", i))).unwrap();
+    }
+    assert_eq!(prune_snapshots(&backups, 2).unwrap(), 3);
+    assert_eq!(
+        file_names(&backups),
+        vec!["before-update.rpmbak", "manual.rpmbak", "rpm-auto-4.rpmbak", "rpm-auto-5.rpmbak"]
+    );
+    assert_eq!(prune_snapshots(&backups, 2).unwrap(), 0);
+    assert_eq!(prune_snapshots(&backups, 0).unwrap(), 2);
+    assert_eq!(file_names(&backups), vec!["before-update.rpmbak", "manual.rpmbak"]);
+    let _ = std::fs::remove_dir_all(&dir);
+    let _ = std::fs::remove_dir_all(&backups);
+}
+
+#[test]
+fn scheduled_snapshots_wait_for_the_interval() {
+    let dir = temp_dir("scheduled");
+    let backups = temp_dir("scheduled-backups");
+    let mut config = Config {
+        passwords_directory: Some(dir.clone()),
+        backup_directory: Some(backups.clone()),
+        backup_interval_hours: 24,
+        backup_keep_count: 2,
+        ..Config::default()
+    };
+    // No vault yet, nothing to back up
+    assert_eq!(scheduled_backup(&config).unwrap(), None);
+    let storage = PasswordStorage::new(&config, CryptoManager::new().unwrap());
+    storage.add_entry("github", &[7; 32]).unwrap();
+
+    let first = scheduled_backup(&config).unwrap().unwrap();
+    assert!(first.file_name().unwrap().to_string_lossy().starts_with("rpm-auto-"));
+    // A manual backup does not count as the newest snapshot, nor does it delay one
+    create_snapshot(&dir, &backups.join("manual.rpmbak")).unwrap();
+    assert_eq!(scheduled_backup(&config).unwrap(), None);
+    assert_eq!(file_names(&backups).len(), 2);
+
+    // Once the newest snapshot is old enough, another is taken and the oldest pruned
+    config.backup_interval_hours = 0;
+    for _ in 0..3 {
+        // Snapshot names only go down to the second
+        std::thread::sleep(std::time::Duration::from_millis(1100));
+        assert!(scheduled_backup(&config).unwrap().is_some());
+    }
+    let names = file_names(&backups);
+    assert_eq!(names.iter().filter(|name| name.starts_with("rpm-auto-")).count(), 2, "{:?}", names);
+    assert!(names.contains(&"manual.rpmbak".to_string()));
+    assert!(!backups.join(first.file_name().unwrap()).exists());
+    let _ = std::fs::remove_dir_all(&dir);
+    let _ = std::fs::remove_dir_all(&backups);
+}
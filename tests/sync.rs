@@ -0,0 +1,105 @@
+use chrono::{Duration, TimeZone, Utc};
+use rpm::config::Config;
+use rpm::crypto::CryptoManager;
+use rpm::storage::PasswordStorage;
+use rpm::sync::git::GitSync;
+use rpm::sync::tombstones::{record_deletions, Tombstones, TOMBSTONES_FILE, TOMBSTONE_RETENTION_DAYS};
+use rpm::sync::PullOutcome;
+use std::path::PathBuf;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rpm-sync-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn tombstones_merge_to_the_latest_deletion() {
+    let at = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+    let mut local = Tombstones::default();
+    local.record("a.pwd", at);
+    let mut remote = Tombstones::default();
+    remote.record("a.pwd", at + Duration::hours(1));
+    remote.record("b.pwd", at);
+
+    assert!(local.merge(&remote));
+    assert!(!local.merge(&remote));
+    assert_eq!(local.deleted_at("a.pwd"), Some(at + Duration::hours(1)));
+    assert_eq!(local.deleted_at("b.pwd"), Some(at));
+
+    // Duplicated lines, as git's union merge leaves them, parse to the same set
+    let text = format!("{}{}", local, remote);
+    assert_eq!(Tombstones::parse(&text).unwrap(), local);
+    assert!(Tombstones::parse("yesterday a.pwd").is_err());
+
+    // Copies modified after the deletion survive, older or undated ones don't
+    assert!(local.outdated("b.pwd", Some(at - Duration::seconds(1))));
+    assert!(local.outdated("b.pwd", None));
+    assert!(!local.outdated("b.pwd", Some(at + Duration::seconds(1))));
+    assert!(!local.outdated("c.pwd", None));
+
+    local.prune(at + Duration::days(TOMBSTONE_RETENTION_DAYS) + Duration::minutes(30));
+    assert_eq!(local.deleted_at("b.pwd"), None);
+    assert!(local.deleted_at("a.pwd").is_some());
+}
+
+#[test]
+fn purged_entries_stay_gone_when_a_stale_def_file_comes_back() {
+    let dir = temp_dir("purge");
+    let config = Config {
+        passwords_directory: Some(dir.clone()),
+        ..Config::default()
+    };
+    let storage = PasswordStorage::new(&config, CryptoManager::new().unwrap());
+    let key = vec![7; 32];
+    let kept = storage.add_entry("kept", &key).unwrap();
+    let purged = storage.add_entry("purged", &key).unwrap();
+    let stale_def = std::fs::read(dir.join("def")).unwrap();
+
+    storage.delete_entry(&purged, &key).unwrap();
+    assert_eq!(storage.purge_trash(|_, _| true, &key).unwrap(), 1);
+    let tombstones = Tombstones::load(&dir).unwrap();
+    assert!(tombstones.deleted_at(&purged).is_some());
+    assert!(tombstones.deleted_at(&kept).is_none());
+
+    // A replica that never saw the purge wins the def file
+    std::fs::write(dir.join("def"), stale_def).unwrap();
+    let names: Vec<String> = storage.list_decrypted_names(&key).unwrap().into_iter().map(|(_, name)| name).collect();
+    assert_eq!(names, vec!["kept".to_string()]);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn git_merges_tombstones_from_both_sides() {
+    let root = temp_dir("git");
+    let remote = root.join("remote.git");
+    std::fs::create_dir_all(&remote).unwrap();
+    let status = std::process::Command::new("git").args(["init", "-q", "--bare"]).arg(&remote).status().unwrap();
+    assert!(status.success());
+    let remote_url = remote.to_string_lossy().into_owned();
+
+    let first = GitSync::new(&root.join("first"));
+    first.init(Some(&remote_url)).unwrap();
+    std::fs::write(root.join("first").join("a.pwd"), "a").unwrap();
+    std::fs::write(root.join("first").join("b.pwd"), "b").unwrap();
+    first.push().unwrap();
+
+    let second = GitSync::new(&root.join("second"));
+    second.init(Some(&remote_url)).unwrap();
+    assert_eq!(second.pull().unwrap(), PullOutcome::Updated);
+
+    // Each side purges a different file
+    std::fs::remove_file(root.join("first").join("a.pwd")).unwrap();
+    record_deletions(&root.join("first"), &["a.pwd"]).unwrap();
+    first.push().unwrap();
+    std::fs::remove_file(root.join("second").join("b.pwd")).unwrap();
+    record_deletions(&root.join("second"), &["b.pwd"]).unwrap();
+
+    assert_eq!(second.pull().unwrap(), PullOutcome::Updated);
+    let merged = Tombstones::load(&root.join("second")).unwrap();
+    assert!(merged.deleted_at("a.pwd").is_some());
+    assert!(merged.deleted_at("b.pwd").is_some());
+    assert!(std::fs::read_to_string(root.join("second").join(TOMBSTONES_FILE)).unwrap().lines().count() >= 2);
+    assert!(!root.join("second").join("a.pwd").exists());
+    let _ = std::fs::remove_dir_all(&root);
+}
@@ -0,0 +1,355 @@
+//! Every TUI screen rendered with ratatui's `TestBackend`, in every language and theme.
+//!
+//! The text of each screen is snapshotted per language (themes only change colors),
+//! so layout regressions and cut-off translations show up as snapshot diffs.
+//! After an intentional UI change, review and accept them with `cargo insta review`
+//! (or `INSTA_UPDATE=always cargo test --test tui_snapshots`).
+
+use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+use base64::Engine;
+use chrono::{Duration, TimeZone, Utc};
+use ratatui::backend::TestBackend;
+use ratatui::buffer::{Buffer, Cell};
+use ratatui::style::Color;
+use ratatui::widgets::ListState;
+use ratatui::Terminal;
+use rpm::config::Config;
+use rpm::crypto::{CryptoManager, SecureKey};
+use rpm::diagnostics::{Diagnostics, HardeningCheck};
+use rpm::i18n::Language;
+use rpm::models::{CustomField, PasswordPolicy};
+use rpm::storage::{PasswordStorage, ReuseCluster};
+use rpm::tui::{restore_metadata, scrub_metadata, ui, ConsoleCaps, Screen, TuiState, THEMES};
+use std::path::PathBuf;
+
+/// Usual terminal size the layouts are designed for
+const WIDTH: u16 = 100;
+const HEIGHT: u16 = 30;
+
+fn screens() -> Vec<(&'static str, Screen)> {
+    vec![
+        ("master_password_create", Screen::MasterPassword),
+        ("master_password_unlock", Screen::MasterPassword),
+        ("master_password_hint", Screen::MasterPassword),
+        ("main", Screen::Main),
+        ("main_names_scrubbed", Screen::Main),
+        ("main_loading", Screen::Main),
+        ("main_macro_recording", Screen::Main),
+        ("main_tag_filter", Screen::Main),
+        ("main_folder", Screen::Main),
+        ("main_move_to_folder", Screen::Main),
+        ("main_archive", Screen::Main),
+        ("main_pairing", Screen::Main),
+        ("settings", Screen::Settings),
+        ("settings_tls", Screen::Settings),
+        ("password_entry_new", Screen::PasswordEntry { is_edit: false, filename: None }),
+        ("password_entry_edit", Screen::PasswordEntry { is_edit: true, filename: Some("entry.pwd".to_string()) }),
+        ("password_generator", Screen::PasswordGenerator { return_to_edit: false, return_filename: None }),
+        ("password_generator_presets", Screen::PasswordGenerator { return_to_edit: false, return_filename: None }),
+        ("password_generator_save_preset", Screen::PasswordGenerator { return_to_edit: false, return_filename: None }),
+        ("help", Screen::Help),
+        ("about", Screen::About),
+        ("theme_selection", Screen::ThemeSelection),
+        ("language_selection", Screen::LanguageSelection),
+        ("audit", Screen::Audit { return_to: Box::new(Screen::Main) }),
+        ("audit_reuse_clusters", Screen::Audit { return_to: Box::new(Screen::Main) }),
+        ("rotation", Screen::Rotation),
+        ("maintenance", Screen::Maintenance),
+        ("security_questions", Screen::SecurityQuestions { is_edit: false, filename: None }),
+        ("custom_fields", Screen::CustomFields { is_edit: true, filename: Some("entry.pwd".to_string()) }),
+        ("ssh_host", Screen::SshHost { is_edit: true, filename: Some("entry.pwd".to_string()) }),
+        ("autotype", Screen::AutoType { is_edit: true, filename: Some("entry.pwd".to_string()) }),
+        ("access_window", Screen::AccessWindow { is_edit: true, filename: Some("entry.pwd".to_string()) }),
+        ("change_master_password", Screen::ChangeMasterPassword),
+        ("import", Screen::Import),
+        ("backup", Screen::Backup),
+        ("restore", Screen::Restore),
+        ("sync", Screen::Sync),
+        ("trash", Screen::Trash),
+        ("trash_purge_confirm", Screen::Trash),
+    ]
+}
+
+fn state(name: &str, screen: &Screen, language: Language, theme: &str) -> TuiState {
+    let config = Config {
+        passwords_directory: Some(PathBuf::from("/vault")),
+        backup_directory: Some(PathBuf::from("/vault-backups")),
+        language: language.to_code().to_string(),
+        theme: theme.to_string(),
+        console_mode: "full".to_string(),
+        display_timezone: "utc".to_string(),
+        ..Config::default()
+    };
+    let mut state = TuiState::new(config, name == "master_password_create");
+    state.current_screen = screen.clone();
+    state.server_port = Some(8766);
+    state.all_items = ["github/alice", "mail/bob", "bank/carol"].map(String::from).to_vec();
+    state.filtered_items = state.all_items.clone();
+    state.name_to_filename = state.all_items.iter().enumerate().map(|(i, name)| (format!("{}.pwd", i), name.clone())).collect();
+    state.entry_tags.insert("0.pwd".to_string(), vec!["work".to_string(), "code".to_string()]);
+    state.entry_tags.insert("1.pwd".to_string(), vec!["personal".to_string()]);
+    if name == "main_folder" {
+        state.entry_folders.insert("0.pwd".to_string(), "work".to_string());
+        state.entry_folders.insert("2.pwd".to_string(), "work/finance".to_string());
+        state.current_folder = "work".to_string();
+        state.visible_folders = vec!["work/finance".to_string()];
+        state.filtered_items = vec!["github/alice".to_string()];
+    }
+    if name == "main_move_to_folder" {
+        state.move_to_folder = Some(("1.pwd".to_string(), "mail/bob".to_string()));
+        state.move_folder_input = "personal/mail".to_string();
+    }
+    if name == "main_archive" {
+        state.archived_entries.insert("2.pwd".to_string());
+        state.show_archived = true;
+        state.filtered_items = vec!["bank/carol".to_string()];
+    }
+    if name == "settings_tls" {
+        state.server_tls = Some((8764, "5f".repeat(32)));
+    }
+    if name == "main_pairing" {
+        // The code is only shown while the vault is unlocked
+        state.encryption_key = Some(SecureKey::new(vec![0; 32]));
+        state.pairing.request("Firefox").unwrap();
+    }
+    if name == "main_tag_filter" {
+        state.search_query = "#work".to_string();
+        state.filtered_items = vec!["github/alice".to_string()];
+    }
+    if name == "password_entry_edit" {
+        state.password_entry_name = "github/alice".to_string();
+        state.password_entry_username = "alice@example.com".to_string();
+        state.password_entry_password = "correct horse".to_string();
+        state.password_entry_url = "https://github.com".to_string();
+        state.password_entry_tags = "work, code".to_string();
+    }
+    if name.starts_with("password_generator") {
+        state.password_generator_length = "16".to_string();
+        state.config.generator_presets.insert("Default".to_string(), PasswordPolicy::default());
+        state.config.generator_presets.insert(
+            "Bank PIN".to_string(),
+            PasswordPolicy { length: 6, use_uppercase: false, use_lowercase: false, ..PasswordPolicy::default() },
+        );
+        state.password_generator_min_digits = 2;
+        state.password_generator_exclude_ambiguous = true;
+        state.password_generator_custom_chars = "~_".to_string();
+        state.password_generator_presets_open = name == "password_generator_presets";
+        if name == "password_generator_save_preset" {
+            state.password_generator_preset_name = Some("Work".to_string());
+        }
+    }
+    if name == "custom_fields" {
+        state.password_entry_name = "github/alice".to_string();
+        state.password_entry_custom_fields = vec![
+            CustomField { label: "Recovery email".to_string(), value: "alice.backup@example.com".to_string() },
+            CustomField { label: "PIN".to_string(), value: "4821".to_string() },
+        ];
+        state.custom_fields_index = 1;
+    }
+    if name == "master_password_hint" {
+        state.master_password_failed_attempts = 2;
+        state.master_password_hint = Some("first cat + street".to_string());
+    }
+    if name == "ssh_host" {
+        state.password_entry_name = "servers/build".to_string();
+        state.password_entry_username = "deploy".to_string();
+        state.password_entry_ssh_host = "build.example.com".to_string();
+        state.password_entry_ssh_port = "2222".to_string();
+        state.ssh_host_field = 1;
+    }
+    if name == "autotype" {
+        state.password_entry_name = "mail/bob".to_string();
+        state.password_entry_autotype = "{USERNAME}{ENTER}{DELAY 500}{PASSWORD}{ENTER}".to_string();
+    }
+    if name == "about" {
+        state.about = Some(Diagnostics {
+            version: "0.1.0".to_string(),
+            build_hash: "0123456789ab".to_string(),
+            profile: "release, opt-level 3".to_string(),
+            target: "x86_64-unknown-linux-gnu".to_string(),
+            rng_source: "getrandom(2)".to_string(),
+            rng_check: Ok(()),
+            entropy_pool: Some((256, 256)),
+            crypto_crates: vec!["aes-gcm 0.10.3".to_string(), "argon2 0.5.3".to_string(), "ring 0.17.8".to_string()],
+            hardening: vec![
+                HardeningCheck { name: "release_profile", passed: Some(true) },
+                HardeningCheck { name: "no_debug_assertions", passed: Some(true) },
+                HardeningCheck { name: "panic_abort", passed: Some(false) },
+                HardeningCheck { name: "pie", passed: None },
+            ],
+        });
+    }
+    if name == "access_window" {
+        state.password_entry_name = "work/jira".to_string();
+        state.password_entry_access_window = "mon-fri 08:00-20:00".to_string();
+    }
+    if name == "audit_reuse_clusters" {
+        state.audit_reuse_groups = vec![
+            ReuseCluster {
+                entries: vec!["bank/carol".to_string(), "mail/bob".to_string(), "shop/dave".to_string()],
+                sites: vec!["bank.example".to_string(), "mail.example".to_string(), "shop/dave".to_string()],
+                entropy_bits: 28.2,
+                risk_score: 66,
+            },
+            ReuseCluster {
+                entries: vec!["github/alice".to_string(), "gitlab/alice".to_string()],
+                sites: vec!["github.com".to_string(), "gitlab.com".to_string()],
+                entropy_bits: 95.3,
+                risk_score: 25,
+            },
+        ];
+    }
+    if name == "main_loading" {
+        state.name_to_filename.truncate(2);
+        state.all_items.truncate(2);
+        state.filtered_items.truncate(2);
+        state.names_loading = Some((2, 1500));
+    }
+    if name == "main_macro_recording" {
+        state.macros.start_recording('q');
+    }
+    if name == "main_names_scrubbed" {
+        state.all_items.clear();
+        state.filtered_items.clear();
+        state.metadata_scrubbed = true;
+    }
+    if name.starts_with("trash") {
+        let deleted_at = Utc.with_ymd_and_hms(2026, 3, 14, 9, 30, 0).unwrap();
+        state.trash_items = vec![
+            ("1.pwd".to_string(), "shop/dave".to_string(), deleted_at),
+            ("2.pwd".to_string(), "forum/erin".to_string(), deleted_at - Duration::days(12)),
+        ];
+        state.trash_purge_confirm = name == "trash_purge_confirm";
+    }
+    state
+}
+
+fn render(state: &TuiState, width: u16, height: u16) -> Terminal<TestBackend> {
+    let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+    let mut list_state = ListState::default();
+    if !state.filtered_items.is_empty() {
+        list_state.select(Some(0));
+    }
+    terminal.draw(|f| ui(f, state, &mut list_state)).unwrap();
+    terminal
+}
+
+/// The settings screen shows the config file path, which depends on the user's home directory,
+/// and the pairing dialog a random code
+fn redact(state: &TuiState, text: String) -> String {
+    let config_path = Config::default().config_file_path().unwrap().to_string_lossy().to_string();
+    let placeholder = format!("{:<width$}", "<config file>", width = config_path.chars().count());
+    let text = text.replace(&config_path, &placeholder);
+    match state.pairing.pending() {
+        Some(request) => text.replace(&request.code, "123456"),
+        None => text,
+    }
+}
+
+#[test]
+fn screens_in_every_language() {
+    for language in Language::all() {
+        for (name, screen) in screens() {
+            let state = state(name, &screen, language, THEMES[0].1);
+            let terminal = render(&state, WIDTH, HEIGHT);
+            insta::assert_snapshot!(format!("{}_{}", name, language.to_code()), redact(&state, terminal.backend().to_string()));
+        }
+    }
+}
+
+/// Every cell must get its background from the theme, not from the terminal
+#[test]
+fn themes_paint_every_cell() {
+    for language in Language::all() {
+        for (name, screen) in screens() {
+            for (_, theme, _) in THEMES {
+                let terminal = render(&state(name, &screen, language, theme), WIDTH, HEIGHT);
+                let buffer = terminal.backend().buffer();
+                // Diffing against a buffer no render can produce yields every visible cell,
+                // skipping the ones hidden behind wide (CJK) symbols
+                let blank = Buffer::filled(buffer.area, Cell::new("\0"));
+                let unpainted = blank.diff(buffer).iter().filter(|(_, _, cell)| cell.bg == Color::Reset).count();
+                assert_eq!(unpainted, 0, "{} in {} leaves {} cells without a background", name, theme, unpainted);
+            }
+        }
+    }
+}
+
+/// Screen reader mode: no borders, the first line names the screen and the focused field
+#[test]
+fn screens_in_screen_reader_mode() {
+    for (name, screen) in screens() {
+        let mut state = state(name, &screen, Language::English, THEMES[0].1);
+        state.config.screen_reader = true;
+        let terminal = render(&state, WIDTH, HEIGHT);
+        insta::assert_snapshot!(format!("screen_reader_{}", name), redact(&state, terminal.backend().to_string()));
+    }
+}
+
+/// Compatibility mode for legacy consoles: only ANSI-16 colors and no box-drawing or symbol glyphs
+#[test]
+fn compat_mode_uses_ansi_colors_and_ascii_symbols() {
+    for language in Language::all() {
+        for (name, screen) in screens() {
+            let mut state = state(name, &screen, language, THEMES[0].1);
+            state.console = ConsoleCaps::COMPAT;
+            let terminal = render(&state, WIDTH, HEIGHT);
+            for cell in &terminal.backend().buffer().content {
+                assert!(
+                    !matches!(cell.fg, Color::Rgb(..) | Color::Indexed(_)) && !matches!(cell.bg, Color::Rgb(..) | Color::Indexed(_)),
+                    "{} uses a non-ANSI color",
+                    name
+                );
+                assert!(
+                    !cell.symbol().chars().any(|c| ('\u{2000}'..'\u{2c00}').contains(&c)),
+                    "{} draws {:?} in compat mode",
+                    name,
+                    cell.symbol()
+                );
+            }
+        }
+    }
+}
+
+/// Small terminals must not make any screen panic
+#[test]
+fn screens_render_in_small_terminals() {
+    for language in Language::all() {
+        for (name, screen) in screens() {
+            for (width, height) in [(80, 24), (40, 12), (1, 1)] {
+                render(&state(name, &screen, language, THEMES[0].1), width, height);
+            }
+        }
+    }
+}
+
+/// After the idle timeout the vault key is only kept wrapped, and comes back on resume
+#[test]
+fn scrubbing_names_leaves_no_raw_key() {
+    let dir = std::env::temp_dir().join(format!("rpm-tui-scrub-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let config = Config {
+        passwords_directory: Some(dir.clone()),
+        ..Config::default()
+    };
+    let crypto = CryptoManager::new().unwrap();
+    let storage = PasswordStorage::new(&config, crypto.clone());
+    let key = vec![7; 32];
+    storage.add_entry("github", &key).unwrap();
+
+    let mut state = TuiState::new(config, false);
+    state.encryption_key = Some(SecureKey::new(key.clone()));
+    scrub_metadata(&mut state, &crypto);
+    assert!(state.metadata_scrubbed);
+    assert!(state.encryption_key.is_none());
+    let (wrapped, wrapping_key) = state.scrubbed_key.as_ref().unwrap();
+    assert_ne!(wrapping_key.as_slice(), key.as_slice());
+    assert!(!wrapped.key.contains(&BASE64_STANDARD.encode(&key)));
+
+    restore_metadata(&mut state, &storage, &crypto);
+    assert!(!state.metadata_scrubbed && state.scrubbed_key.is_none());
+    assert_eq!(state.encryption_key.as_ref().unwrap().as_slice(), key.as_slice());
+    assert_eq!(state.all_items, vec!["github".to_string()]);
+    let _ = std::fs::remove_dir_all(&dir);
+}
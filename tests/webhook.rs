@@ -0,0 +1,82 @@
+//! Webhook delivery against a local HTTP receiver.
+
+use rpm::config::Config;
+use rpm::webhook::{sign, WebhookEvent, Webhooks, EVENT_HEADER, SIGNATURE_HEADER};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::thread;
+
+/// Accept one request and return its headers (lowercased names) and body
+fn receive_one(listener: TcpListener) -> thread::JoinHandle<(Vec<(String, String)>, String)> {
+    thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut headers = Vec::new();
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        loop {
+            line.clear();
+            reader.read_line(&mut line).unwrap();
+            let Some((name, value)) = line.trim_end().split_once(": ") else {
+                break;
+            };
+            headers.push((name.to_lowercase(), value.to_string()));
+        }
+        let length: usize = headers.iter().find(|(name, _)| name == "content-length").unwrap().1.parse().unwrap();
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).unwrap();
+        reader
+            .get_mut()
+            .write_all(b"HTTP/1.1 204 No Content\r\nContent-Length: 0\r\n\r\n")
+            .unwrap();
+        (headers, String::from_utf8(body).unwrap())
+    })
+}
+
+fn header<'a>(headers: &'a [(String, String)], name: &str) -> &'a str {
+    &headers.iter().find(|(header, _)| *header == name.to_lowercase()).unwrap().1
+}
+
+#[test]
+fn delivers_signed_payloads() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let config = Config {
+        webhook_url: Some(format!("http://{}/hook", listener.local_addr().unwrap())),
+        webhook_secret: Some("shared".to_string()),
+        ..Config::default()
+    };
+    let receiver = receive_one(listener);
+
+    let event = WebhookEvent::Breach { entries: 2 };
+    Webhooks::from_config(&config).fire(event).unwrap().join().unwrap();
+
+    let (headers, body) = receiver.join().unwrap();
+    assert_eq!(header(&headers, EVENT_HEADER), "breach");
+    assert_eq!(header(&headers, SIGNATURE_HEADER), sign("shared", &body));
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(body["event"], "breach");
+    assert_eq!(body["details"], serde_json::json!({ "entries": 2 }));
+}
+
+#[test]
+fn only_configured_events_are_sent() {
+    let event = WebhookEvent::export(std::path::Path::new("/exports/rpm-export-20260101-030000.json.age"));
+    assert_eq!(event, WebhookEvent::Export { file: "rpm-export-20260101-030000.json.age".to_string() });
+
+    // No URL, no webhook
+    assert!(!Webhooks::from_config(&Config::default()).wants(&event));
+
+    let config = Config {
+        webhook_url: Some("https://ntfy.sh/rpm-alerts".to_string()),
+        webhook_events: vec!["breach".to_string()],
+        ..Config::default()
+    };
+    let webhooks = Webhooks::from_config(&config);
+    assert!(!webhooks.wants(&event));
+    assert!(webhooks.fire(event).is_none());
+    assert!(webhooks.wants(&WebhookEvent::Breach { entries: 1 }));
+
+    // Without a secret the payload is sent unsigned
+    let (_, signature) = webhooks.payload(&WebhookEvent::Breach { entries: 1 }, chrono::Utc::now());
+    assert!(signature.is_none());
+}